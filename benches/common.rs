@@ -0,0 +1,14 @@
+//! Helpers shared by the benchmarks of the workspace, included in every
+//! benchmark that needs them with `#[path = "../../benches/common.rs"]`.
+
+/// Maps the indices `0..n` to distinct keys in a scrambled order, for any
+/// `n` up to a million.
+///
+/// Inserting keys in ascending order is the best or the worst case of many
+/// structures: an unbalanced search tree degenerates into a list, a
+/// max-heap sifts every pushed key up to the root, and consecutive keys
+/// fill consecutive buckets of a hash table. Scrambled keys show the
+/// typical case instead.
+pub fn key(i: usize) -> usize {
+  i.wrapping_mul(0x9E37_79B9) % 1_000_003
+}
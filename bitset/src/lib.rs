@@ -0,0 +1,446 @@
+//! Sets of small integers stored as bits packed into words.
+
+pub mod rank;
+
+pub use rank::RankSelect;
+
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+
+/// Number of bits in a word of a set.
+const WORD: usize = u64::BITS as usize;
+
+/// A set of the integers below a fixed length, stored as a vector of bits.
+///
+/// Bit `i` of the vector tells if `i` is in the set, and the bits are
+/// packed 64 to a `u64` word. Setting and testing a bit is O(1), and a set
+/// of `n` bits takes `n / 8` bytes.
+///
+/// The bitwise operators combine two sets of the same length word by word,
+/// in loops over plain slices that the compiler turns into SIMD
+/// instructions, so they handle many words per instruction. The bits past
+/// the length in the last word are always zero, which keeps counting and
+/// comparing sets to whole words as well.
+///
+/// # Example
+///
+/// ```
+/// use bitset::BitSet;
+///
+/// let mut primes = BitSet::new(10);
+/// for prime in [2, 3, 5, 7] {
+///   primes.set(prime);
+/// }
+/// let odd: BitSet = (0..10).map(|i| i % 2 == 1).collect();
+///
+/// assert!(primes.test(7));
+/// assert_eq!(primes.count_ones(), 4);
+/// assert_eq!((&primes & &odd).iter().collect::<Vec<_>>(), vec![3, 5, 7]);
+/// assert_eq!((&primes | &odd).iter().collect::<Vec<_>>(), vec![1, 2, 3, 5, 7, 9]);
+/// assert_eq!((!&primes).iter().collect::<Vec<_>>(), vec![0, 1, 4, 6, 8, 9]);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+  words: Vec<u64>,
+  /// Number of bits of the set, the last word may hold up to 63 more.
+  len: usize
+}
+
+impl BitSet {
+  /// Creates a new set of `len` bits, all cleared.
+  pub fn new(len: usize) -> Self {
+    Self { words: vec![0; len.div_ceil(WORD)], len }
+  }
+
+  /// Returns the number of bits of the set.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the set has no bits.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the words of the set, the lowest bits first. The bits past
+  /// the length in the last word are zero.
+  pub fn as_words(&self) -> &[u64] {
+    &self.words
+  }
+
+  /// Sets the bit at the index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn set(&mut self, index: usize) {
+    assert!(index < self.len, "index out of bounds");
+    self.words[index / WORD] |= 1 << (index % WORD);
+  }
+
+  /// Clears the bit at the index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn clear(&mut self, index: usize) {
+    assert!(index < self.len, "index out of bounds");
+    self.words[index / WORD] &= !(1 << (index % WORD));
+  }
+
+  /// Flips the bit at the index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn toggle(&mut self, index: usize) {
+    assert!(index < self.len, "index out of bounds");
+    self.words[index / WORD] ^= 1 << (index % WORD);
+  }
+
+  /// Checks if the bit at the index is set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn test(&self, index: usize) -> bool {
+    assert!(index < self.len, "index out of bounds");
+    self.words[index / WORD] & (1 << (index % WORD)) != 0
+  }
+
+  /// Appends a bit to the end of the set, making it one bit longer.
+  pub fn push(&mut self, bit: bool) {
+    if self.len.is_multiple_of(WORD) {
+      self.words.push(0);
+    }
+    self.len += 1;
+
+    if bit {
+      self.set(self.len - 1);
+    }
+  }
+
+  /// Returns the number of set bits.
+  pub fn count_ones(&self) -> usize {
+    self.words.iter().map(|word| word.count_ones() as usize).sum()
+  }
+
+  /// Clears all the bits.
+  pub fn clear_all(&mut self) {
+    self.words.fill(0);
+  }
+
+  /// Returns an iterator over the indices of the set bits, in ascending
+  /// order.
+  pub fn iter(&self) -> Ones<'_> {
+    Ones { words: &self.words, index: 0, word: self.words.first().copied().unwrap_or(0) }
+  }
+
+  /// Combines every word of the set with the word of the other set at the
+  /// same position.
+  fn combine(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) {
+    assert!(self.len == other.len, "sets must have the same length");
+
+    for (word, &other) in self.words.iter_mut().zip(&other.words) {
+      *word = op(*word, other);
+    }
+  }
+
+  /// Clears the bits past the length in the last word.
+  fn trim(&mut self) {
+    if let (Some(last), extra @ 1..) = (self.words.last_mut(), self.len % WORD) {
+      *last &= (1 << extra) - 1;
+    }
+  }
+}
+
+/// An iterator over the indices of the set bits of a `BitSet`, in
+/// ascending order.
+pub struct Ones<'a> {
+  words: &'a [u64],
+  /// Index of the current word.
+  index: usize,
+  /// The bits of the current word that are yet to be yielded.
+  word: u64
+}
+
+impl Iterator for Ones<'_> {
+  type Item = usize;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.word == 0 {
+      self.index += 1;
+      self.word = *self.words.get(self.index)?;
+    }
+
+    let bit = self.word.trailing_zeros() as usize;
+    // Clears the lowest set bit.
+    self.word &= self.word - 1;
+    Some(self.index * WORD + bit)
+  }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+  type Item = usize;
+  type IntoIter = Ones<'a>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+  /// Keeps the bits set in both sets.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitand_assign(&mut self, other: &BitSet) {
+    self.combine(other, |a, b| a & b);
+  }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+  /// Sets the bits set in either set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitor_assign(&mut self, other: &BitSet) {
+    self.combine(other, |a, b| a | b);
+  }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+  /// Keeps the bits set in exactly one of the sets.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitxor_assign(&mut self, other: &BitSet) {
+    self.combine(other, |a, b| a ^ b);
+  }
+}
+
+impl BitAnd for &BitSet {
+  type Output = BitSet;
+
+  /// Returns the set of the bits set in both sets.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitand(self, other: &BitSet) -> BitSet {
+    let mut set = self.clone();
+    set &= other;
+    set
+  }
+}
+
+impl BitOr for &BitSet {
+  type Output = BitSet;
+
+  /// Returns the set of the bits set in either set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitor(self, other: &BitSet) -> BitSet {
+    let mut set = self.clone();
+    set |= other;
+    set
+  }
+}
+
+impl BitXor for &BitSet {
+  type Output = BitSet;
+
+  /// Returns the set of the bits set in exactly one of the sets.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sets have different lengths.
+  fn bitxor(self, other: &BitSet) -> BitSet {
+    let mut set = self.clone();
+    set ^= other;
+    set
+  }
+}
+
+impl Not for BitSet {
+  type Output = BitSet;
+
+  /// Flips all the bits of the set.
+  fn not(mut self) -> BitSet {
+    for word in &mut self.words {
+      *word = !*word;
+    }
+    self.trim();
+    self
+  }
+}
+
+impl Not for &BitSet {
+  type Output = BitSet;
+
+  /// Returns the set of the bits cleared in the set.
+  fn not(self) -> BitSet {
+    !self.clone()
+  }
+}
+
+impl FromIterator<bool> for BitSet {
+  /// Creates a set with a bit for every item of the iterator, set if the
+  /// item is `true`.
+  fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+    let mut set = Self::new(0);
+    set.extend(iter);
+    set
+  }
+}
+
+impl Extend<bool> for BitSet {
+  /// Appends a bit for every item of the iterator, set if the item is
+  /// `true`.
+  fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+    for bit in iter {
+      self.push(bit);
+    }
+  }
+}
+
+impl fmt::Debug for BitSet {
+  /// Formats the set as its bits, the lowest first.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let bits: String = (0..self.len).map(|index| if self.test(index) { '1' } else { '0' }).collect();
+    f.debug_tuple("BitSet").field(&format_args!("{}", bits)).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BitSet {
+  /// Serializes the length and the words of the set.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    (self.len, &self.words).serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BitSet {
+  /// Deserializes the length and the words of a set.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (len, words): (usize, Vec<u64>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if words.len() != len.div_ceil(WORD) {
+      return Err(D::Error::invalid_length(words.len(), &"one word for every 64 bits"));
+    }
+
+    if let (Some(last), extra @ 1..) = (words.last(), len % WORD) {
+      if last >> extra != 0 {
+        return Err(D::Error::custom("bits past the length are set"));
+      }
+    }
+
+    Ok(Self { words, len })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::BitSet;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_set() {
+    let set = BitSet::new(0);
+
+    assert!(set.is_empty());
+    assert_eq!(set.count_ones(), 0);
+    assert_eq!(set.iter().next(), None);
+    assert_eq!(!&set, set);
+  }
+
+  #[test]
+  fn set_and_clear() {
+    let mut set = BitSet::new(130);
+    set.set(0);
+    set.set(64);
+    set.set(129);
+    set.toggle(1);
+    set.toggle(0);
+    set.clear(64);
+
+    assert_eq!(set.len(), 130);
+    assert!(set.test(1) && set.test(129));
+    assert!(!set.test(0) && !set.test(64));
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 129]);
+
+    set.clear_all();
+    assert_eq!(set.count_ones(), 0);
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn set_out_of_bounds() {
+    BitSet::new(64).set(64);
+  }
+
+  #[test]
+  #[should_panic(expected = "sets must have the same length")]
+  fn different_lengths() {
+    let _ = &BitSet::new(3) | &BitSet::new(4);
+  }
+
+  #[test]
+  fn not_keeps_length() {
+    let set = !BitSet::new(70);
+
+    assert_eq!(set.count_ones(), 70);
+    assert_eq!(set.as_words(), [u64::MAX, 0x3f]);
+    assert_eq!(!&set, BitSet::new(70));
+  }
+
+  #[test]
+  fn push_and_debug() {
+    let mut set: BitSet = [true, false, true].into_iter().collect();
+    set.push(true);
+
+    assert_eq!(set.len(), 4);
+    assert_eq!(format!("{:?}", set), "BitSet(1011)");
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: BitSet = [false, true, true].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[3,[6]]");
+
+    let set: BitSet = serde_json::from_str(&json).unwrap();
+    assert_eq!(set, value);
+    assert!(serde_json::from_str::<BitSet>("[65,[1]]").is_err());
+    assert!(serde_json::from_str::<BitSet>("[3,[8]]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_bools(pairs in prop::collection::vec(any::<(bool, bool)>(), 0..200)) {
+      let (a, b): (Vec<bool>, Vec<bool>) = pairs.into_iter().unzip();
+      let (x, y): (BitSet, BitSet) = (a.iter().copied().collect(), b.iter().copied().collect());
+      let ones = |bits: Vec<bool>| bits.iter().enumerate().filter(|(_, &bit)| bit).map(|(i, _)| i).collect::<Vec<_>>();
+
+      prop_assert_eq!(x.count_ones(), a.iter().filter(|&&bit| bit).count());
+      prop_assert_eq!(x.iter().collect::<Vec<_>>(), ones(a.clone()));
+      prop_assert_eq!((&x & &y).iter().collect::<Vec<_>>(), ones(a.iter().zip(&b).map(|(p, q)| p & q).collect()));
+      prop_assert_eq!((&x | &y).iter().collect::<Vec<_>>(), ones(a.iter().zip(&b).map(|(p, q)| p | q).collect()));
+      prop_assert_eq!((&x ^ &y).iter().collect::<Vec<_>>(), ones(a.iter().zip(&b).map(|(p, q)| p ^ q).collect()));
+      prop_assert_eq!((!&x).iter().collect::<Vec<_>>(), ones(a.iter().map(|p| !p).collect()));
+    }
+  }
+}
@@ -0,0 +1,283 @@
+use crate::{BitSet, WORD};
+
+
+/// Number of words counted together in a block.
+const BLOCK_WORDS: usize = 8;
+/// Number of bits in a block.
+const BLOCK: usize = BLOCK_WORDS * WORD;
+
+/// A bit vector that counts and finds its bits fast, the building block of
+/// succinct structures such as wavelet trees.
+///
+/// `rank1(i)` counts the set bits before position `i`, and `select1(k)`
+/// finds the position of the set bit numbered `k`. A `BitSet` answers both
+/// by scanning its words, in O(n). This vector keeps the bits of a
+/// `BitSet` with the number of set bits before every block of 512 bits, so
+/// a rank adds the count of a block to the set bits of at most eight words
+/// of it, in O(1). A select finds the block of the bit by a binary search
+/// on the counts, then scans its words, in O(log n).
+///
+/// The counts take one `usize` per block, 1/8 of the space of the bits on
+/// 64-bit targets. The bits can not be changed once counted.
+///
+/// # Example
+///
+/// ```
+/// use bitset::{BitSet, RankSelect};
+///
+/// let bits: BitSet = [true, false, true, true, false, false, true].into_iter().collect();
+/// let vector = RankSelect::new(bits);
+///
+/// assert_eq!(vector.rank1(4), 3);
+/// assert_eq!(vector.rank0(4), 1);
+/// assert_eq!(vector.select1(2), Some(3));
+/// assert_eq!(vector.select0(2), Some(5));
+/// assert_eq!(vector.select1(4), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RankSelect {
+  bits: BitSet,
+  /// Number of set bits before every block, and before the end as the last
+  /// count.
+  ranks: Vec<usize>
+}
+
+impl RankSelect {
+  /// Counts the bits of the set in O(n).
+  pub fn new(bits: BitSet) -> Self {
+    let mut ranks = Vec::with_capacity(bits.len().div_ceil(BLOCK) + 1);
+    let mut rank = 0;
+
+    ranks.push(rank);
+    for block in bits.as_words().chunks(BLOCK_WORDS) {
+      rank += block.iter().map(|word| word.count_ones() as usize).sum::<usize>();
+      ranks.push(rank);
+    }
+
+    Self { bits, ranks }
+  }
+
+  /// Returns the bits of the vector.
+  pub fn bits(&self) -> &BitSet {
+    &self.bits
+  }
+
+  /// Returns the bits of the vector, dropping the counts.
+  pub fn into_inner(self) -> BitSet {
+    self.bits
+  }
+
+  /// Returns the number of bits of the vector.
+  pub fn len(&self) -> usize {
+    self.bits.len()
+  }
+
+  /// Checks if the vector has no bits.
+  pub fn is_empty(&self) -> bool {
+    self.bits.is_empty()
+  }
+
+  /// Returns the number of set bits, in O(1).
+  pub fn count_ones(&self) -> usize {
+    self.ranks[self.ranks.len() - 1]
+  }
+
+  /// Returns the number of cleared bits, in O(1).
+  pub fn count_zeros(&self) -> usize {
+    self.len() - self.count_ones()
+  }
+
+  /// Checks if the bit at the index is set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn test(&self, index: usize) -> bool {
+    self.bits.test(index)
+  }
+
+  /// Returns the number of set bits before the index, in O(1).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is greater than the length.
+  pub fn rank1(&self, index: usize) -> usize {
+    assert!(index <= self.len(), "index out of bounds");
+
+    let words = self.bits.as_words();
+    let (word, bit) = (index / WORD, index % WORD);
+    let before: usize = words[word / BLOCK_WORDS * BLOCK_WORDS..word].iter().map(|word| word.count_ones() as usize).sum();
+    let within = words.get(word).map_or(0, |&word| (word & ((1 << bit) - 1)).count_ones() as usize);
+
+    self.ranks[word / BLOCK_WORDS] + before + within
+  }
+
+  /// Returns the number of cleared bits before the index, in O(1).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is greater than the length.
+  pub fn rank0(&self, index: usize) -> usize {
+    index - self.rank1(index)
+  }
+
+  /// Returns the position of the set bit with `rank` set bits before it,
+  /// or `None` if there are not that many set bits, in O(log n).
+  pub fn select1(&self, rank: usize) -> Option<usize> {
+    if rank >= self.count_ones() {
+      return None;
+    }
+
+    self.select(rank, |block| self.ranks[block], |word| word)
+  }
+
+  /// Returns the position of the cleared bit with `rank` cleared bits
+  /// before it, or `None` if there are not that many cleared bits, in
+  /// O(log n).
+  pub fn select0(&self, rank: usize) -> Option<usize> {
+    if rank >= self.count_zeros() {
+      return None;
+    }
+
+    // The bits past the length are cleared too, but come after all the
+    // others, so the rank never reaches them.
+    self.select(rank, |block| block * BLOCK - self.ranks[block], |word| !word)
+  }
+
+  /// Finds the bit with `rank` bits of the same kind before it, given the
+  /// number of those bits before every block and a function that sets them
+  /// in a word.
+  fn select(&self, rank: usize, before: impl Fn(usize) -> usize, ones: impl Fn(u64) -> u64) -> Option<usize> {
+    // Finds the last block with at most `rank` bits of the kind before it.
+    let (mut low, mut high) = (0, self.ranks.len());
+    while high - low > 1 {
+      let middle = (low + high) / 2;
+      if before(middle) <= rank {
+        low = middle;
+      } else {
+        high = middle;
+      }
+    }
+
+    let block = low;
+    let mut rank = rank - before(block);
+
+    for (index, &word) in self.bits.as_words().iter().enumerate().skip(block * BLOCK_WORDS) {
+      let mut word = ones(word);
+      let count = word.count_ones() as usize;
+
+      if rank < count {
+        for _ in 0..rank {
+          word &= word - 1;
+        }
+        return Some(index * WORD + word.trailing_zeros() as usize);
+      }
+      rank -= count;
+    }
+
+    None
+  }
+}
+
+impl From<BitSet> for RankSelect {
+  fn from(bits: BitSet) -> Self {
+    Self::new(bits)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RankSelect {
+  /// Serializes the bits only, as the counts are rebuilt from them.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.bits.serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RankSelect {
+  /// Deserializes the bits of a vector and counts them.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    BitSet::deserialize(deserializer).map(RankSelect::new)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::RankSelect;
+  use crate::BitSet;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_vector() {
+    let vector = RankSelect::new(BitSet::new(0));
+
+    assert!(vector.is_empty());
+    assert_eq!(vector.rank1(0), 0);
+    assert_eq!(vector.select1(0), None);
+    assert_eq!(vector.select0(0), None);
+  }
+
+  #[test]
+  fn across_blocks() {
+    let mut bits = BitSet::new(2000);
+    for index in (0..2000).step_by(3) {
+      bits.set(index);
+    }
+    let vector = RankSelect::from(bits);
+
+    assert_eq!(vector.count_ones(), 667);
+    assert_eq!(vector.rank1(1024), 342);
+    assert_eq!(vector.rank0(2000), 1333);
+    assert_eq!(vector.select1(500), Some(1500));
+    assert_eq!(vector.select0(1000), Some(1501));
+    assert_eq!(vector.select1(667), None);
+    assert!(vector.test(1500) && !vector.test(1501));
+  }
+
+  #[test]
+  fn full_words() {
+    let vector = RankSelect::new(!BitSet::new(1024));
+
+    assert_eq!(vector.rank1(1024), 1024);
+    assert_eq!(vector.select1(1023), Some(1023));
+    assert_eq!(vector.select0(0), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn rank_out_of_bounds() {
+    RankSelect::new(BitSet::new(10)).rank1(11);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = RankSelect::new([true, false, true].into_iter().collect());
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[3,[5]]");
+
+    let vector: RankSelect = serde_json::from_str(&json).unwrap();
+    assert_eq!(vector, value);
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_counting(bits in prop::collection::vec(any::<bool>(), 0..1500)) {
+      let vector = RankSelect::new(bits.iter().copied().collect());
+
+      for index in 0..=bits.len() {
+        prop_assert_eq!(vector.rank1(index), bits[..index].iter().filter(|&&bit| bit).count());
+      }
+
+      let ones: Vec<usize> = (0..bits.len()).filter(|&index| bits[index]).collect();
+      let zeros: Vec<usize> = (0..bits.len()).filter(|&index| !bits[index]).collect();
+      for rank in 0..=bits.len() {
+        prop_assert_eq!(vector.select1(rank), ones.get(rank).copied());
+        prop_assert_eq!(vector.select0(rank), zeros.get(rank).copied());
+      }
+    }
+  }
+}
@@ -0,0 +1,104 @@
+//! Compares `BinarySearchTree` against `std::collections::BTreeSet` on
+//! inserting, removing and iterating over several sizes of sets.
+//!
+//! The keys are inserted in a scrambled order: inserted in ascending order,
+//! they would turn the unbalanced tree into a list.
+//!
+//! Run with `cargo bench -p bst --bench std`.
+
+use std::hint::black_box;
+use std::collections::BTreeSet;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use bst::BinarySearchTree;
+use common::key;
+
+#[path = "../../benches/common.rs"]
+mod common;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn insert(c: &mut Criterion) {
+  let mut group = c.benchmark_group("bst/insert");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinarySearchTree", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = BinarySearchTree::new();
+        for i in 0..size {
+          ours.insert(black_box(key(i)));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeSet", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = BTreeSet::new();
+        for i in 0..size {
+          std.insert(black_box(key(i)));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+  let mut group = c.benchmark_group("bst/remove");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinarySearchTree", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<BinarySearchTree<_>>(),
+        |mut ours| {
+          for i in 0..size {
+            black_box(ours.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeSet", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<BTreeSet<_>>(),
+        |mut std| {
+          for i in 0..size {
+            black_box(std.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("bst/iterate");
+
+  for size in SIZES {
+    let ours: BinarySearchTree<_> = (0..size).map(key).collect();
+    let std: BTreeSet<_> = (0..size).map(key).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinarySearchTree", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeSet", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, insert, remove, iterate);
+criterion_main!(benches);
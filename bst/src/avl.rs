@@ -0,0 +1,117 @@
+use std::cmp::Ordering;
+
+
+pub(crate) type Link<N> = Option<Box<N>>;
+
+/// Gives access to the height and the children of the nodes of an AVL tree,
+/// so that the trees whose nodes know something about their subtree can
+/// share the rebalancing.
+pub(crate) trait AvlNode: Sized {
+  /// Returns the number of nodes on the longest path from the node down to
+  /// a leaf.
+  fn height(&self) -> usize;
+  fn left_mut(&mut self) -> &mut Link<Self>;
+  fn right_mut(&mut self) -> &mut Link<Self>;
+
+  /// Recomputes the height and whatever the node knows about its subtree
+  /// from its children. Called every time the children of the node change.
+  fn update(&mut self);
+}
+
+pub(crate) fn height<N: AvlNode>(link: &Link<N>) -> usize {
+  link.as_ref().map_or(0, |node| node.height())
+}
+
+fn rotate_left<N: AvlNode>(mut node: Box<N>) -> Box<N> {
+  let mut right = node.right_mut().take().expect("rotated node has a right child");
+  *node.right_mut() = right.left_mut().take();
+  node.update();
+  *right.left_mut() = Some(node);
+  right.update();
+  right
+}
+
+fn rotate_right<N: AvlNode>(mut node: Box<N>) -> Box<N> {
+  let mut left = node.left_mut().take().expect("rotated node has a left child");
+  *node.left_mut() = left.right_mut().take();
+  node.update();
+  *left.right_mut() = Some(node);
+  left.update();
+  left
+}
+
+/// Updates the node after one of its subtrees changed, and rotates it if
+/// the heights of its subtrees differ by two.
+pub(crate) fn balance<N: AvlNode>(mut node: Box<N>) -> Box<N> {
+  node.update();
+  let (left, right) = (height(node.left_mut()), height(node.right_mut()));
+
+  if left > right + 1 {
+    let left = node.left_mut();
+    let child = left.as_mut().unwrap();
+    if height(child.left_mut()) < height(child.right_mut()) {
+      *left = left.take().map(rotate_left);
+    }
+    rotate_right(node)
+  } else if right > left + 1 {
+    let right = node.right_mut();
+    let child = right.as_mut().unwrap();
+    if height(child.right_mut()) < height(child.left_mut()) {
+      *right = right.take().map(rotate_right);
+    }
+    rotate_left(node)
+  } else {
+    node
+  }
+}
+
+/// Detaches from the subtree a node for which `compare` returns `Equal`,
+/// and returns it. `compare` tells whether the node to remove is before or
+/// after the node it is given.
+pub(crate) fn remove<N, F>(link: &mut Link<N>, compare: &F) -> Option<Box<N>>
+where
+  N: AvlNode,
+  F: Fn(&N) -> Ordering,
+{
+  let node = link.as_mut()?;
+
+  let removed = match compare(node) {
+    Ordering::Less => remove(node.left_mut(), compare)?,
+    Ordering::Greater => remove(node.right_mut(), compare)?,
+    Ordering::Equal => {
+      let mut node = link.take().unwrap();
+
+      *link = match node.right_mut().take() {
+        None => node.left_mut().take(),
+        Some(right) => {
+          // Replace the removed node with its in-order successor.
+          let (mut successor, rest) = take_min(right);
+          *successor.left_mut() = node.left_mut().take();
+          *successor.right_mut() = rest;
+          Some(successor)
+        }
+      };
+
+      node
+    }
+  };
+
+  *link = link.take().map(balance);
+  Some(removed)
+}
+
+/// Detaches the smallest node of the subtree, returning it and the new
+/// root of the rest of the subtree.
+fn take_min<N: AvlNode>(mut node: Box<N>) -> (Box<N>, Link<N>) {
+  match node.left_mut().take() {
+    None => {
+      let rest = node.right_mut().take();
+      (node, rest)
+    }
+    Some(left) => {
+      let (min, rest) = take_min(left);
+      *node.left_mut() = rest;
+      (min, Some(balance(node)))
+    }
+  }
+}
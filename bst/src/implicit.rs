@@ -0,0 +1,543 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use random::SplitMix64;
+
+
+/// A sequence based on an implicit treap, a treap ordered by position.
+///
+/// The nodes hold no keys: a node is ordered by its position in the
+/// sequence, which is the number of nodes before it, and every node knows
+/// the size of its subtree to find positions. The random priorities keep
+/// the expected height of the tree in O(log n), so unlike a `Vec`, the
+/// sequence inserts, removes, splits and joins at any position in expected
+/// O(log n), and reads an element in expected O(log n) too.
+///
+/// # Example
+///
+/// ```
+/// use bst::ImplicitTreap;
+///
+/// let mut sequence: ImplicitTreap<char> = "heo".chars().collect();
+/// sequence.insert(2, 'l');
+/// sequence.insert(2, 'l');
+/// assert_eq!(sequence.iter().collect::<String>(), "hello");
+///
+/// // Moves the two first characters to the end.
+/// let mut tail = sequence.split_off(2);
+/// tail.append(sequence);
+/// assert_eq!(tail.iter().collect::<String>(), "llohe");
+///
+/// assert_eq!(tail.remove(0), 'l');
+/// assert_eq!(tail[1], 'o');
+/// ```
+#[derive(Clone)]
+pub struct ImplicitTreap<T> {
+  root: Link<T>,
+  rng: SplitMix64
+}
+
+type Link<T> = Option<Box<Node<T>>>;
+
+/// Represents a node in an implicit treap.
+#[derive(Clone)]
+struct Node<T> {
+  value: T,
+  /// Random priority, higher than the priorities of the children.
+  priority: u64,
+  /// Number of nodes in the subtree of the node.
+  size: usize,
+  left: Link<T>,
+  right: Link<T>
+}
+
+impl<T> Default for ImplicitTreap<T> {
+  /// Creates a new instance of `ImplicitTreap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> ImplicitTreap<T> {
+  /// Creates a new empty sequence with random priorities.
+  pub fn new() -> Self {
+    Self { root: None, rng: SplitMix64::new(None) }
+  }
+
+  /// Creates a new empty sequence whose priorities are drawn from a
+  /// generator seeded with `seed`, which makes its shape reproducible.
+  pub fn with_seed(seed: u64) -> Self {
+    Self { root: None, rng: SplitMix64::new(Some(seed)) }
+  }
+
+  /// Returns the number of elements in the sequence.
+  pub fn len(&self) -> usize {
+    size(&self.root)
+  }
+
+  /// Checks if the sequence is empty.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns a reference to the element at `index`, or `None` if the index
+  /// is out of bounds.
+  pub fn get(&self, mut index: usize) -> Option<&T> {
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      let left = size(&node.left);
+      link = match index {
+        index if index < left => &node.left,
+        index if index == left => return Some(&node.value),
+        _ => {
+          index -= left + 1;
+          &node.right
+        }
+      };
+    }
+
+    None
+  }
+
+  /// Returns a mutable reference to the element at `index`, or `None` if
+  /// the index is out of bounds.
+  pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+    let mut link = &mut self.root;
+
+    while let Some(node) = link {
+      let left = size(&node.left);
+      link = match index {
+        index if index < left => &mut node.left,
+        index if index == left => return Some(&mut node.value),
+        _ => {
+          index -= left + 1;
+          &mut node.right
+        }
+      };
+    }
+
+    None
+  }
+
+  /// Inserts a value at `index`, shifting the elements after it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is greater than the length.
+  pub fn insert(&mut self, index: usize, value: T) {
+    assert!(index <= self.len(), "index out of bounds");
+
+    let priority = self.rng.next_u64();
+    insert(&mut self.root, index, Box::new(Node { value, priority, size: 1, left: None, right: None }));
+  }
+
+  /// Appends a value to the back of the sequence.
+  pub fn push(&mut self, value: T) {
+    self.insert(self.len(), value);
+  }
+
+  /// Removes the element at `index` and returns it, shifting the elements
+  /// after it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn remove(&mut self, index: usize) -> T {
+    assert!(index < self.len(), "index out of bounds");
+    remove(&mut self.root, index)
+  }
+
+  /// Splits the sequence in two at `at`: this sequence keeps the elements
+  /// before `at`, and the others are returned in a new sequence.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at` is greater than the length.
+  pub fn split_off(&mut self, at: usize) -> Self {
+    assert!(at <= self.len(), "index out of bounds");
+
+    let (left, right) = split(self.root.take(), at);
+    self.root = left;
+    Self { root: right, rng: SplitMix64::new(Some(self.rng.next_u64())) }
+  }
+
+  /// Moves all the elements of the other sequence to the back of this one.
+  pub fn append(&mut self, mut other: Self) {
+    self.root = merge(self.root.take(), other.root.take());
+  }
+
+  /// Removes all elements from the sequence.
+  pub fn clear(&mut self) {
+    self.root = None;
+  }
+
+  /// Returns an iterator over the elements of the sequence in order.
+  pub fn iter(&self) -> Iter<'_, T> {
+    let mut iter = Iter { stack: Vec::new() };
+    iter.push_left_spine(self.root.as_deref());
+    iter
+  }
+}
+
+impl<T> Node<T> {
+  /// Recomputes the size of the subtree from the children.
+  fn update(&mut self) {
+    self.size = size(&self.left) + size(&self.right) + 1;
+  }
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+  link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Splits the subtree into its `at` first nodes and the others.
+fn split<T>(link: Link<T>, at: usize) -> (Link<T>, Link<T>) {
+  let Some(mut node) = link else {
+    return (None, None);
+  };
+
+  let left = size(&node.left);
+  if left < at {
+    let (middle, right) = split(node.right.take(), at - left - 1);
+    node.right = middle;
+    node.update();
+    (Some(node), right)
+  } else {
+    let (left, middle) = split(node.left.take(), at);
+    node.left = middle;
+    node.update();
+    (left, Some(node))
+  }
+}
+
+/// Joins two subtrees, the nodes of `left` coming first.
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+  match (left, right) {
+    (Some(mut left), Some(mut right)) => {
+      if left.priority > right.priority {
+        left.right = merge(left.right.take(), Some(right));
+        left.update();
+        Some(left)
+      } else {
+        right.left = merge(Some(left), right.left.take());
+        right.update();
+        Some(right)
+      }
+    }
+    (left, right) => left.or(right)
+  }
+}
+
+/// Inserts a node at `index` of the subtree, where its priority puts it.
+fn insert<T>(link: &mut Link<T>, index: usize, mut new: Box<Node<T>>) {
+  match link {
+    Some(node) if node.priority >= new.priority => {
+      let left = size(&node.left);
+      if index <= left {
+        insert(&mut node.left, index, new);
+      } else {
+        insert(&mut node.right, index - left - 1, new);
+      }
+      node.update();
+    }
+    _ => {
+      // The node becomes the root of the subtree, above all the others.
+      let (left, right) = split(link.take(), index);
+      new.left = left;
+      new.right = right;
+      new.update();
+      *link = Some(new);
+    }
+  }
+}
+
+/// Removes the node at `index` of the subtree, which must exist, and
+/// returns its value.
+fn remove<T>(link: &mut Link<T>, index: usize) -> T {
+  let node = link.as_mut().expect("index is within the subtree");
+  let left = size(&node.left);
+
+  let value = if index < left {
+    remove(&mut node.left, index)
+  } else if index > left {
+    remove(&mut node.right, index - left - 1)
+  } else {
+    let mut node = link.take().unwrap();
+    *link = merge(node.left.take(), node.right.take());
+    return node.value;
+  };
+
+  node.update();
+  value
+}
+
+impl<T> Index<usize> for ImplicitTreap<T> {
+  type Output = T;
+
+  /// Returns the element at the index.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  fn index(&self, index: usize) -> &T {
+    self.get(index).expect("index out of bounds")
+  }
+}
+
+impl<T> IndexMut<usize> for ImplicitTreap<T> {
+  /// Returns the element at the index mutably.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  fn index_mut(&mut self, index: usize) -> &mut T {
+    self.get_mut(index).expect("index out of bounds")
+  }
+}
+
+/// An iterator over the elements of an `ImplicitTreap` in order.
+pub struct Iter<'a, T> {
+  stack: Vec<&'a Node<T>>
+}
+
+impl<'a, T> Iter<'a, T> {
+  fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+    while let Some(current) = node {
+      self.stack.push(current);
+      node = current.left.as_deref();
+    }
+  }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.push_left_spine(node.right.as_deref());
+    Some(&node.value)
+  }
+}
+
+impl<'a, T> IntoIterator for &'a ImplicitTreap<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+  /// Creates a sequence of the values in order.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut sequence = Self::new();
+    sequence.extend(iter);
+    sequence
+  }
+}
+
+impl<T> Extend<T> for ImplicitTreap<T> {
+  /// Appends the values in order.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T: PartialEq> PartialEq for ImplicitTreap<T> {
+  /// Compares the elements in order, whatever the shapes of the trees.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for ImplicitTreap<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for ImplicitTreap<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for ImplicitTreap<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence in order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for ImplicitTreap<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a sequence with random priorities, preserving the order
+  /// of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{size, ImplicitTreap, Link};
+  use proptest::prelude::*;
+
+  /// Checks the priorities and the sizes of the subtree, and returns its
+  /// height.
+  fn check<T>(link: &Link<T>) -> usize {
+    let Some(node) = link else {
+      return 0;
+    };
+
+    assert!([&node.left, &node.right].into_iter().flatten().all(|child| child.priority <= node.priority));
+    assert_eq!(node.size, size(&node.left) + size(&node.right) + 1);
+    check(&node.left).max(check(&node.right)) + 1
+  }
+
+  #[test]
+  fn empty_sequence() {
+    let mut sequence = ImplicitTreap::<i32>::new();
+
+    assert!(sequence.is_empty());
+    assert_eq!(sequence.get(0), None);
+    assert!(sequence.split_off(0).is_empty());
+  }
+
+  #[test]
+  fn insert_get_and_remove() {
+    let mut sequence = ImplicitTreap::with_seed(3);
+    sequence.push(1);
+    sequence.push(3);
+    sequence.insert(1, 2);
+    sequence.insert(0, 0);
+
+    assert_eq!(sequence.len(), 4);
+    assert_eq!(sequence.get(2), Some(&2));
+    assert_eq!(sequence.get(4), None);
+
+    sequence[3] = 30;
+    *sequence.get_mut(0).unwrap() = -1;
+    assert_eq!(format!("{:?}", sequence), "[-1, 1, 2, 30]");
+
+    assert_eq!(sequence.remove(1), 1);
+    assert_eq!(sequence.iter().copied().collect::<Vec<_>>(), [-1, 2, 30]);
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn insert_out_of_bounds() {
+    let mut sequence: ImplicitTreap<i32> = (0..3).collect();
+    sequence.insert(4, 0);
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn remove_out_of_bounds() {
+    let mut sequence: ImplicitTreap<i32> = (0..3).collect();
+    sequence.remove(3);
+  }
+
+  #[test]
+  fn pushes_stay_shallow() {
+    let sequence: ImplicitTreap<i32> = (0..1000).collect();
+
+    assert!(check(&sequence.root) < 50);
+    assert!(sequence.iter().copied().eq(0..1000));
+  }
+
+  #[test]
+  fn split_off_and_append() {
+    let mut sequence: ImplicitTreap<i32> = (0..10).collect();
+
+    let tail = sequence.split_off(4);
+    assert_eq!(sequence, (0..4).collect());
+    assert_eq!(tail, (4..10).collect());
+
+    // Moves the tail to the front.
+    let mut rotated = tail;
+    rotated.append(sequence);
+    assert!(rotated.iter().copied().eq((4..10).chain(0..4)));
+    check(&rotated.root);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: ImplicitTreap<i32> = [3, 1, 2].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[3,1,2]");
+
+    let sequence: ImplicitTreap<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sequence, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(usize, i32),
+    Remove(usize),
+    Set(usize, i32),
+    Rotate(usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (any::<usize>(), any::<i32>()).prop_map(|(index, value)| Operation::Insert(index, value)),
+      2 => any::<usize>().prop_map(Operation::Remove),
+      1 => (any::<usize>(), any::<i32>()).prop_map(|(index, value)| Operation::Set(index, value)),
+      1 => any::<usize>().prop_map(Operation::Rotate)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec(seed in any::<u64>(), operations in prop::collection::vec(operation(), 0..200)) {
+      let mut sequence = ImplicitTreap::with_seed(seed);
+      let mut model = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(index, value) => {
+            let index = index % (model.len() + 1);
+            sequence.insert(index, value);
+            model.insert(index, value);
+          }
+          Operation::Remove(index) => {
+            if !model.is_empty() {
+              let index = index % model.len();
+              prop_assert_eq!(sequence.remove(index), model.remove(index));
+            }
+          }
+          Operation::Set(index, value) => {
+            if !model.is_empty() {
+              let index = index % model.len();
+              sequence[index] = value;
+              model[index] = value;
+            }
+          }
+          Operation::Rotate(at) => {
+            let at = at % (model.len() + 1);
+            let mut tail = sequence.split_off(at);
+            tail.append(sequence);
+            sequence = tail;
+            model.rotate_left(at);
+          }
+        }
+
+        check(&sequence.root);
+        prop_assert_eq!(sequence.len(), model.len());
+        prop_assert!(sequence.iter().eq(model.iter()));
+      }
+    }
+  }
+}
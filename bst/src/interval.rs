@@ -0,0 +1,481 @@
+use std::cmp::{self, Ordering};
+use std::fmt;
+use std::ops::{Bound, Range};
+
+use crate::avl::{self, height, AvlNode};
+
+
+/// A balanced tree of intervals, which finds all the intervals that
+/// contain a point or overlap a range.
+///
+/// The intervals are half-open ranges `start..end` with a value each, kept
+/// in an AVL tree ordered by their start, so the tree stays balanced and
+/// every operation takes O(log n). Every node also knows the greatest end
+/// of the intervals below it, which lets a query skip the subtrees that end
+/// before it and the nodes that start after it: a query returning `m`
+/// intervals runs in O(log n + m) on typical data.
+///
+/// The same interval can be inserted several times with different values.
+/// The ends of the intervals are cloned to maintain the greatest ends.
+///
+/// # Example
+///
+/// ```
+/// use bst::IntervalTree;
+///
+/// let mut meetings = IntervalTree::new();
+/// meetings.insert(9..11, "standup");
+/// meetings.insert(10..12, "review");
+/// meetings.insert(14..15, "demo");
+///
+/// let at_ten: Vec<_> = meetings.query_point(&10).map(|(_, name)| *name).collect();
+/// assert_eq!(at_ten, vec!["standup", "review"]);
+///
+/// let afternoon: Vec<_> = meetings.query_overlapping(&(12..16)).collect();
+/// assert_eq!(afternoon, vec![(&(14..15), &"demo")]);
+///
+/// assert_eq!(meetings.remove(&(9..11)), Some("standup"));
+/// assert_eq!(meetings.query_point(&9).count(), 0);
+/// ```
+#[derive(Clone)]
+pub struct IntervalTree<K: Ord, V> {
+  root: Link<K, V>,
+  len: usize
+}
+
+type Link<K, V> = avl::Link<Node<K, V>>;
+
+/// Represents a node in an interval tree.
+#[derive(Clone)]
+struct Node<K, V> {
+  range: Range<K>,
+  value: V,
+  /// The greatest end of the intervals in the subtree of the node.
+  max: K,
+  /// Number of nodes on the longest path from the node down to a leaf.
+  height: usize,
+  /// Subtree with the intervals ordered before `range`, or equal to it
+  /// after a rotation.
+  left: Link<K, V>,
+  /// Subtree with the intervals ordered after or equal to `range`.
+  right: Link<K, V>
+}
+
+impl<K: Ord + Clone, V> Default for IntervalTree<K, V> {
+  /// Creates a new instance of `IntervalTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord + Clone, V> IntervalTree<K, V> {
+  /// Creates a new empty interval tree.
+  pub fn new() -> Self {
+    Self { root: None, len: 0 }
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns the number of intervals in the tree.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Inserts an interval with its value, even if the same interval is
+  /// already in the tree.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is empty.
+  pub fn insert(&mut self, range: Range<K>, value: V) {
+    assert!(range.start < range.end, "range must not be empty");
+    self.root = Some(insert(self.root.take(), range, value));
+    self.len += 1;
+  }
+
+  /// Removes an interval equal to the range and returns its value, or
+  /// `None` if the interval is not in the tree.
+  pub fn remove(&mut self, range: &Range<K>) -> Option<V> {
+    let node = avl::remove(&mut self.root, &|node: &Node<K, V>| compare(range, &node.range))?;
+    self.len -= 1;
+    Some(node.value)
+  }
+
+  /// Returns an iterator over the intervals that contain the point, in
+  /// ascending order.
+  pub fn query_point<'a>(&'a self, point: &'a K) -> Overlapping<'a, K, V> {
+    Overlapping::new(self.root.as_deref(), point, Bound::Included(point))
+  }
+
+  /// Returns an iterator over the intervals that overlap the range, in
+  /// ascending order.
+  pub fn query_overlapping<'a>(&'a self, range: &'a Range<K>) -> Overlapping<'a, K, V> {
+    Overlapping::new(self.root.as_deref(), &range.start, Bound::Excluded(&range.end))
+  }
+
+  /// Returns an iterator over the intervals of the tree in ascending order.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    let mut iter = Iter { stack: Vec::new() };
+    iter.push_left_spine(self.root.as_deref());
+    iter
+  }
+}
+
+impl<K: Ord + Clone, V> AvlNode for Node<K, V> {
+  fn height(&self) -> usize {
+    self.height
+  }
+
+  fn left_mut(&mut self) -> &mut Link<K, V> {
+    &mut self.left
+  }
+
+  fn right_mut(&mut self) -> &mut Link<K, V> {
+    &mut self.right
+  }
+
+  /// Recomputes the height and the greatest end from the children.
+  fn update(&mut self) {
+    self.height = height(&self.left).max(height(&self.right)) + 1;
+    self.max = [&self.left, &self.right].into_iter().flatten().map(|child| &child.max).fold(&self.range.end, cmp::max).clone();
+  }
+}
+
+/// Compares intervals by their start, then by their end.
+fn compare<K: Ord>(a: &Range<K>, b: &Range<K>) -> Ordering {
+  (&a.start, &a.end).cmp(&(&b.start, &b.end))
+}
+
+/// Inserts the interval into the subtree and returns its new root.
+fn insert<K: Ord + Clone, V>(link: Link<K, V>, range: Range<K>, value: V) -> Box<Node<K, V>> {
+  let Some(mut node) = link else {
+    let max = range.end.clone();
+    return Box::new(Node { range, value, max, height: 1, left: None, right: None });
+  };
+
+  if compare(&range, &node.range) == Ordering::Less {
+    node.left = Some(insert(node.left.take(), range, value));
+  } else {
+    node.right = Some(insert(node.right.take(), range, value));
+  }
+
+  avl::balance(node)
+}
+
+/// An iterator over the intervals of an `IntervalTree` that overlap a
+/// query, in ascending order.
+pub struct Overlapping<'a, K, V> {
+  /// Nodes whose subtree reaches past `low` and whose right subtree is
+  /// still to visit, the next one on top.
+  stack: Vec<&'a Node<K, V>>,
+  /// The intervals must end after this point.
+  low: &'a K,
+  /// The intervals must start before or at this point.
+  high: Bound<&'a K>
+}
+
+impl<'a, K: Ord, V> Overlapping<'a, K, V> {
+  fn new(root: Option<&'a Node<K, V>>, low: &'a K, high: Bound<&'a K>) -> Self {
+    let mut iter = Self { stack: Vec::new(), low, high };
+    iter.push_left_spine(root);
+    iter
+  }
+
+  /// Pushes the node and its left descendants, stopping at the first
+  /// subtree whose intervals all end before the query.
+  fn push_left_spine(&mut self, mut node: Option<&'a Node<K, V>>) {
+    while let Some(current) = node.filter(|current| current.max > *self.low) {
+      self.stack.push(current);
+      node = current.left.as_deref();
+    }
+  }
+
+  fn starts_in_query(&self, node: &Node<K, V>) -> bool {
+    match self.high {
+      Bound::Included(high) => node.range.start <= *high,
+      Bound::Excluded(high) => node.range.start < *high,
+      Bound::Unbounded => true
+    }
+  }
+}
+
+impl<'a, K: Ord, V> Iterator for Overlapping<'a, K, V> {
+  type Item = (&'a Range<K>, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some(node) = self.stack.pop() {
+      if !self.starts_in_query(node) {
+        // The nodes come by ascending start, so all the next ones start
+        // after the query too.
+        self.stack.clear();
+        return None;
+      }
+
+      self.push_left_spine(node.right.as_deref());
+      if node.range.end > *self.low {
+        return Some((&node.range, &node.value));
+      }
+    }
+
+    None
+  }
+}
+
+/// An in-order iterator over the intervals of an `IntervalTree`.
+pub struct Iter<'a, K, V> {
+  stack: Vec<&'a Node<K, V>>
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+  fn push_left_spine(&mut self, mut node: Option<&'a Node<K, V>>) {
+    while let Some(current) = node {
+      self.stack.push(current);
+      node = current.left.as_deref();
+    }
+  }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a Range<K>, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.push_left_spine(node.right.as_deref());
+    Some((&node.range, &node.value))
+  }
+}
+
+impl<'a, K: Ord + Clone, V> IntoIterator for &'a IntervalTree<K, V> {
+  type Item = (&'a Range<K>, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(Range<K>, V)> for IntervalTree<K, V> {
+  /// Creates an interval tree by inserting every interval of the iterator.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a range is empty.
+  fn from_iter<I: IntoIterator<Item = (Range<K>, V)>>(iter: I) -> Self {
+    let mut tree = Self::new();
+
+    for (range, value) in iter {
+      tree.insert(range, value);
+    }
+
+    tree
+  }
+}
+
+impl<K: Ord + Clone + fmt::Debug, V: fmt::Debug> fmt::Debug for IntervalTree<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K: Ord + Clone, V> serde::Serialize for IntervalTree<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the intervals as a sequence of pairs in ascending order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: Ord + Clone, V> serde::Deserialize<'de> for IntervalTree<K, V>
+where
+  K: serde::Deserialize<'de>,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a tree by inserting every interval of a sequence.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let intervals: Vec<(Range<K>, V)> = serde::Deserialize::deserialize(deserializer)?;
+
+    if intervals.iter().any(|(range, _)| range.start >= range.end) {
+      return Err(D::Error::custom("range must not be empty"));
+    }
+
+    Ok(intervals.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{height, IntervalTree, Link};
+  use proptest::prelude::*;
+  use std::ops::Range;
+
+  /// Checks that the tree is ordered and balanced, and that every node
+  /// knows the greatest end below it.
+  fn check(link: &Link<i32, i32>) -> Option<i32> {
+    let node = link.as_ref()?;
+    let (left, right) = (node.left.as_deref(), node.right.as_deref());
+
+    assert!(left.is_none_or(|left| (&left.range.start, &left.range.end) <= (&node.range.start, &node.range.end)));
+    assert!(right.is_none_or(|right| (&right.range.start, &right.range.end) >= (&node.range.start, &node.range.end)));
+    assert!(height(&node.left).abs_diff(height(&node.right)) <= 1);
+    assert_eq!(node.height, height(&node.left).max(height(&node.right)) + 1);
+
+    let max = [check(&node.left), check(&node.right)].into_iter().flatten().fold(node.range.end, i32::max);
+    assert_eq!(node.max, max);
+    Some(max)
+  }
+
+  fn ranges<'a>(iter: impl Iterator<Item = (&'a Range<i32>, &'a i32)>) -> Vec<Range<i32>> {
+    iter.map(|(range, _)| range.clone()).collect()
+  }
+
+  #[test]
+  fn empty_tree() {
+    let mut tree = IntervalTree::<i32, ()>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.query_point(&0).count(), 0);
+    assert_eq!(tree.query_overlapping(&(0..10)).count(), 0);
+    assert_eq!(tree.remove(&(0..1)), None);
+  }
+
+  #[test]
+  fn query_point() {
+    let tree: IntervalTree<i32, i32> = [(1..5, 0), (3..8, 1), (5..6, 2), (10..12, 3)].into_iter().collect();
+
+    assert_eq!(ranges(tree.query_point(&0)), vec![]);
+    assert_eq!(ranges(tree.query_point(&4)), vec![1..5, 3..8]);
+    // The ranges are half-open, so 5 is not in `1..5`.
+    assert_eq!(ranges(tree.query_point(&5)), vec![3..8, 5..6]);
+    assert_eq!(ranges(tree.query_point(&11)), vec![10..12]);
+    assert_eq!(ranges(tree.query_point(&12)), vec![]);
+  }
+
+  #[test]
+  fn query_overlapping() {
+    let tree: IntervalTree<i32, i32> = [(1..5, 0), (3..8, 1), (5..6, 2), (10..12, 3)].into_iter().collect();
+
+    assert_eq!(ranges(tree.query_overlapping(&(5..10))), vec![3..8, 5..6]);
+    assert_eq!(ranges(tree.query_overlapping(&(0..1))), vec![]);
+    assert_eq!(ranges(tree.query_overlapping(&(0..100))), vec![1..5, 3..8, 5..6, 10..12]);
+    assert_eq!(ranges(tree.query_overlapping(&(8..10))), vec![]);
+  }
+
+  #[test]
+  fn duplicates_and_remove() {
+    let mut tree = IntervalTree::new();
+    tree.insert(0..2, 'a');
+    tree.insert(0..2, 'b');
+    tree.insert(1..3, 'c');
+
+    assert_eq!(tree.len(), 3);
+    assert!(tree.remove(&(0..2)).is_some());
+    assert!(tree.remove(&(0..2)).is_some());
+    assert_eq!(tree.remove(&(0..2)), None);
+    assert_eq!(tree.iter().collect::<Vec<_>>(), vec![(&(1..3), &'c')]);
+  }
+
+  #[test]
+  #[should_panic(expected = "range must not be empty")]
+  fn insert_empty_range() {
+    IntervalTree::new().insert(3..3, ());
+  }
+
+  #[test]
+  fn stays_balanced() {
+    let mut tree: IntervalTree<i32, i32> = (0..1000).map(|i| (i..i + 10, i)).collect();
+    assert!(tree.root.as_ref().unwrap().height <= 11);
+    check(&tree.root);
+
+    for i in (0..1000).step_by(2) {
+      assert_eq!(tree.remove(&(i..i + 10)), Some(i));
+    }
+    check(&tree.root);
+    assert_eq!(tree.query_point(&500).map(|(_, &value)| value).collect::<Vec<_>>(), vec![491, 493, 495, 497, 499]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: IntervalTree<i32, char> = [(3..5, 'b'), (1..2, 'a')].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"[[{"start":1,"end":2},"a"],[{"start":3,"end":5},"b"]]"#);
+
+    let tree: IntervalTree<i32, char> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tree.iter().collect::<Vec<_>>(), value.iter().collect::<Vec<_>>());
+
+    assert!(serde_json::from_str::<IntervalTree<i32, char>>(r#"[[{"start":2,"end":2},"a"]]"#).is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(i32, i32),
+    Remove(usize),
+    QueryPoint(i32),
+    QueryOverlapping(i32, i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (0..50, 1..10).prop_map(|(start, len)| Operation::Insert(start, len)),
+      1 => any::<usize>().prop_map(Operation::Remove),
+      1 => (0..60).prop_map(Operation::QueryPoint),
+      1 => (0..60, 0..20).prop_map(|(start, len)| Operation::QueryOverlapping(start, len))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_linear_scan(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut tree = IntervalTree::new();
+      let mut model: Vec<(Range<i32>, i32)> = Vec::new();
+
+      for (value, operation) in operations.into_iter().enumerate() {
+        match operation {
+          Operation::Insert(start, len) => {
+            tree.insert(start..start + len, value as i32);
+            model.push((start..start + len, value as i32));
+          }
+          Operation::Remove(index) if !model.is_empty() => {
+            let (range, _) = model[index % model.len()].clone();
+            let removed = tree.remove(&range).unwrap();
+            let position = model.iter().position(|entry| *entry == (range.clone(), removed)).unwrap();
+            model.remove(position);
+          }
+          Operation::Remove(_) => {}
+          Operation::QueryPoint(point) => {
+            let mut expected: Vec<_> = model.iter().filter(|(range, _)| range.contains(&point)).cloned().collect();
+            let mut found: Vec<_> = tree.query_point(&point).map(|(range, &value)| (range.clone(), value)).collect();
+            prop_assert!(found.windows(2).all(|pair| pair[0].0.start <= pair[1].0.start));
+            expected.sort_by_key(|(range, value)| (range.start, range.end, *value));
+            found.sort_by_key(|(range, value)| (range.start, range.end, *value));
+            prop_assert_eq!(found, expected);
+          }
+          Operation::QueryOverlapping(start, len) => {
+            let query = start..start + len;
+            let mut expected: Vec<_> = model.iter().filter(|(range, _)| range.start < query.end && query.start < range.end).cloned().collect();
+            let mut found: Vec<_> = tree.query_overlapping(&query).map(|(range, &value)| (range.clone(), value)).collect();
+            expected.sort_by_key(|(range, value)| (range.start, range.end, *value));
+            found.sort_by_key(|(range, value)| (range.start, range.end, *value));
+            prop_assert_eq!(found, expected);
+          }
+        }
+
+        check(&tree.root);
+        prop_assert_eq!(tree.len(), model.len());
+      }
+    }
+  }
+}
@@ -0,0 +1,446 @@
+use std::cmp::Ordering;
+
+pub mod implicit;
+pub mod interval;
+pub mod order_statistics;
+pub mod splay;
+pub mod treap;
+mod avl;
+mod range;
+
+pub use implicit::ImplicitTreap;
+pub use interval::IntervalTree;
+pub use order_statistics::OrderStatisticsTree;
+pub use splay::SplayTree;
+pub use treap::Treap;
+
+
+/// A binary search tree implementation.
+///
+/// This data structure keeps its elements ordered: for every node, all
+/// values in its left subtree are smaller and all values in its right
+/// subtree are greater than the value of the node itself. Duplicate
+/// values are not stored. The tree is not self-balancing, so operations
+/// run in O(h) where `h` is the height of the tree (O(log n) on average,
+/// O(n) in the worst case for sorted input).
+///
+/// # Example
+///
+/// ```
+/// use bst::BinarySearchTree;
+///
+/// let mut tree = BinarySearchTree::<i32>::new();
+/// tree.insert(5);
+/// tree.insert(3);
+/// tree.insert(8);
+///
+/// assert!(tree.contains(&3));
+/// assert_eq!(tree.min(), Some(&3));
+/// assert_eq!(tree.max(), Some(&8));
+///
+/// // In-order traversal yields the values sorted.
+/// assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![3, 5, 8]);
+///
+/// assert!(tree.remove(&5));
+/// assert_eq!(tree.len(), 2);
+/// ```
+pub struct BinarySearchTree<T: Ord> {
+  root: Link<T>,
+  len: usize
+}
+
+type Link<T> = Option<Box<Node<T>>>;
+
+/// Represents a node in a binary search tree.
+struct Node<T> {
+  /// The value stored in the node.
+  value: T,
+  /// Subtree with values smaller than `value`.
+  left: Link<T>,
+  /// Subtree with values greater than `value`.
+  right: Link<T>
+}
+
+impl<T: Ord> Default for BinarySearchTree<T> {
+  /// Creates a new instance of `BinarySearchTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord> BinarySearchTree<T> {
+  /// Creates a new empty binary search tree.
+  pub fn new() -> Self {
+    Self { root: None, len: 0 }
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns the number of elements in the tree.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Inserts a value into the tree.
+  ///
+  /// Returns `true` if the value was inserted, or `false` if an equal
+  /// value was already present.
+  pub fn insert(&mut self, value: T) -> bool {
+    let mut current = &mut self.root;
+
+    while let Some(node) = current {
+      current = match value.cmp(&node.value) {
+        Ordering::Less => &mut node.left,
+        Ordering::Greater => &mut node.right,
+        Ordering::Equal => return false,
+      };
+    }
+
+    *current = Some(Box::new(Node { value, left: None, right: None }));
+    self.len += 1;
+
+    true
+  }
+
+  /// Checks if the tree contains the given value.
+  pub fn contains(&self, value: &T) -> bool {
+    let mut current = &self.root;
+
+    while let Some(node) = current {
+      current = match value.cmp(&node.value) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => &node.right,
+        Ordering::Equal => return true,
+      };
+    }
+
+    false
+  }
+
+  /// Removes a value from the tree.
+  ///
+  /// Returns `true` if the value was present in the tree.
+  pub fn remove(&mut self, value: &T) -> bool {
+    let mut current = &mut self.root;
+
+    loop {
+      match current {
+        None => return false,
+        Some(node) => match value.cmp(&node.value) {
+          Ordering::Less => current = &mut current.as_mut().unwrap().left,
+          Ordering::Greater => current = &mut current.as_mut().unwrap().right,
+          Ordering::Equal => break,
+        },
+      }
+    }
+
+    let mut node = current.take().unwrap();
+
+    *current = match (node.left.take(), node.right.take()) {
+      (None, None) => None,
+      (Some(left), None) => Some(left),
+      (None, Some(right)) => Some(right),
+      (Some(left), Some(right)) => {
+        // Replace the removed value with its in-order successor.
+        let mut right = Some(right);
+        node.value = Self::take_min(&mut right);
+        node.left = Some(left);
+        node.right = right;
+        Some(node)
+      }
+    };
+
+    self.len -= 1;
+    true
+  }
+
+  /// Returns a reference to the smallest value in the tree,
+  /// or `None` if the tree is empty.
+  pub fn min(&self) -> Option<&T> {
+    let mut node = self.root.as_ref()?;
+
+    while let Some(left) = &node.left {
+      node = left;
+    }
+
+    Some(&node.value)
+  }
+
+  /// Returns a reference to the greatest value in the tree,
+  /// or `None` if the tree is empty.
+  pub fn max(&self) -> Option<&T> {
+    let mut node = self.root.as_ref()?;
+
+    while let Some(right) = &node.right {
+      node = right;
+    }
+
+    Some(&node.value)
+  }
+
+  /// Returns an iterator over the values of the tree in ascending order.
+  pub fn iter(&self) -> InOrderIter<'_, T> {
+    self.in_order()
+  }
+
+  /// Returns an iterator visiting the left subtree, the node itself,
+  /// and then the right subtree (ascending order).
+  pub fn in_order(&self) -> InOrderIter<'_, T> {
+    let mut iter = InOrderIter { stack: Vec::new() };
+    iter.push_left_spine(self.root.as_deref());
+    iter
+  }
+
+  /// Returns an iterator visiting the node itself, then its left
+  /// subtree, and then its right subtree.
+  pub fn pre_order(&self) -> PreOrderIter<'_, T> {
+    PreOrderIter { stack: self.root.as_deref().into_iter().collect() }
+  }
+
+  /// Returns an iterator visiting the left subtree, then the right
+  /// subtree, and then the node itself.
+  pub fn post_order(&self) -> PostOrderIter<'_, T> {
+    PostOrderIter { stack: self.root.as_deref().map(|node| (node, false)).into_iter().collect() }
+  }
+
+  /// Detaches the smallest node of a non-empty subtree and returns its value.
+  fn take_min(link: &mut Link<T>) -> T {
+    let mut current = link;
+
+    while current.as_ref().unwrap().left.is_some() {
+      current = &mut current.as_mut().unwrap().left;
+    }
+
+    let node = current.take().unwrap();
+    *current = node.right;
+    node.value
+  }
+}
+
+/// An in-order iterator over the values of a `BinarySearchTree`.
+pub struct InOrderIter<'a, T> {
+  stack: Vec<&'a Node<T>>
+}
+
+impl<'a, T> InOrderIter<'a, T> {
+  /// Pushes the node and all of its left descendants onto the stack.
+  fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+    while let Some(current) = node {
+      self.stack.push(current);
+      node = current.left.as_deref();
+    }
+  }
+}
+
+impl<'a, T> Iterator for InOrderIter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.push_left_spine(node.right.as_deref());
+    Some(&node.value)
+  }
+}
+
+/// A pre-order iterator over the values of a `BinarySearchTree`.
+pub struct PreOrderIter<'a, T> {
+  stack: Vec<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for PreOrderIter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.stack.extend(node.right.as_deref());
+    self.stack.extend(node.left.as_deref());
+    Some(&node.value)
+  }
+}
+
+/// A post-order iterator over the values of a `BinarySearchTree`.
+pub struct PostOrderIter<'a, T> {
+  /// Pending nodes, flagged once their children have been scheduled.
+  stack: Vec<(&'a Node<T>, bool)>
+}
+
+impl<'a, T> Iterator for PostOrderIter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some((node, expanded)) = self.stack.pop() {
+      if expanded {
+        return Some(&node.value);
+      }
+
+      self.stack.push((node, true));
+      self.stack.extend(node.right.as_deref().map(|right| (right, false)));
+      self.stack.extend(node.left.as_deref().map(|left| (left, false)));
+    }
+
+    None
+  }
+}
+
+impl<T: Ord> FromIterator<T> for BinarySearchTree<T> {
+  /// Creates a binary search tree by inserting every value of the iterator.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut tree = Self::new();
+
+    for value in iter {
+      tree.insert(value);
+    }
+
+    tree
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Ord> serde::Serialize for BinarySearchTree<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the values as a sequence in pre-order, every node before
+  /// its subtrees, so that inserting them again rebuilds the same shape.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.pre_order())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord> serde::Deserialize<'de> for BinarySearchTree<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a tree by inserting every value of a sequence in order,
+  /// so a sequence in pre-order rebuilds the tree it was taken from.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::BinarySearchTree;
+  use std::collections::BTreeSet;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_tree() {
+    let tree = BinarySearchTree::<i32>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.min(), None);
+    assert_eq!(tree.max(), None);
+  }
+
+  #[test]
+  fn insert() {
+    let mut tree = BinarySearchTree::<i32>::new();
+
+    assert!(tree.insert(42));
+    assert!(tree.insert(7));
+    assert!(!tree.insert(42));
+    assert_eq!(tree.len(), 2);
+  }
+
+  #[test]
+  fn contains() {
+    let tree: BinarySearchTree<i32> = [5, 3, 8, 1].into_iter().collect();
+
+    assert!(tree.contains(&1));
+    assert!(tree.contains(&8));
+    assert!(!tree.contains(&4));
+  }
+
+  #[test]
+  fn remove() {
+    let mut tree: BinarySearchTree<i32> = [5, 3, 8, 1, 4, 7, 9].into_iter().collect();
+
+    // Leaf, node with two children, and the root.
+    assert!(tree.remove(&1));
+    assert!(tree.remove(&8));
+    assert!(tree.remove(&5));
+    assert!(!tree.remove(&5));
+
+    assert_eq!(tree.len(), 4);
+    assert_eq!(tree.iter().copied().collect::<Vec<_>>(), vec![3, 4, 7, 9]);
+  }
+
+  #[test]
+  fn min_max() {
+    let tree: BinarySearchTree<i32> = [5, 3, 8, 1, 9].into_iter().collect();
+
+    assert_eq!(tree.min(), Some(&1));
+    assert_eq!(tree.max(), Some(&9));
+  }
+
+  #[test]
+  fn traversals() {
+    //     5
+    //    / \
+    //   3   8
+    //  / \   \
+    // 1   4   9
+    let tree: BinarySearchTree<i32> = [5, 3, 8, 1, 4, 9].into_iter().collect();
+
+    assert_eq!(tree.in_order().copied().collect::<Vec<_>>(), vec![1, 3, 4, 5, 8, 9]);
+    assert_eq!(tree.pre_order().copied().collect::<Vec<_>>(), vec![5, 3, 1, 4, 8, 9]);
+    assert_eq!(tree.post_order().copied().collect::<Vec<_>>(), vec![1, 4, 3, 9, 8, 5]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: BinarySearchTree<i32> = [4, 2, 6, 1, 3, 5, 7].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[4,2,1,3,6,5,7]");
+
+    // The tree keeps its shape instead of becoming a chain of ascending
+    // values.
+    let tree: BinarySearchTree<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tree.pre_order().collect::<Vec<_>>(), value.pre_order().collect::<Vec<_>>());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(i32),
+    Remove(i32),
+    Contains(i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    // A small key range makes removals and duplicate inserts likely.
+    prop_oneof![
+      (0..50).prop_map(Operation::Insert),
+      (0..50).prop_map(Operation::Remove),
+      (0..50).prop_map(Operation::Contains)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_btree_set(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut tree = BinarySearchTree::new();
+      let mut model = BTreeSet::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(value) => prop_assert_eq!(tree.insert(value), model.insert(value)),
+          Operation::Remove(value) => prop_assert_eq!(tree.remove(&value), model.remove(&value)),
+          Operation::Contains(value) => prop_assert_eq!(tree.contains(&value), model.contains(&value))
+        }
+
+        prop_assert_eq!(tree.len(), model.len());
+        prop_assert_eq!(tree.min(), model.first());
+        prop_assert_eq!(tree.max(), model.last());
+        prop_assert!(tree.iter().eq(model.iter()));
+      }
+    }
+  }
+}
@@ -0,0 +1,847 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+use std::ops::RangeBounds;
+
+use crate::avl::{self, height, AvlNode};
+use crate::range::{Branches, Walk};
+
+
+/// An ordered map that finds the entry of a given rank, based on an AVL
+/// tree whose nodes know the size of their subtree.
+///
+/// The tree is kept balanced like an `IntervalTree`, so searching,
+/// inserting and removing take O(log n). The sizes of the subtrees tell how
+/// many keys come before a node without visiting them: `select` finds the
+/// k-th smallest key and `rank` counts the keys less than a key, both by
+/// walking down a single path in O(log n).
+///
+/// # Example
+///
+/// ```
+/// use bst::OrderStatisticsTree;
+///
+/// let mut scores = OrderStatisticsTree::new();
+/// scores.insert(72, "carol");
+/// scores.insert(95, "alice");
+/// scores.insert(88, "bob");
+///
+/// // The median score.
+/// assert_eq!(scores.select(scores.len() / 2), Some((&88, &"bob")));
+///
+/// // Two scores are below 90, whether or not 90 is in the tree.
+/// assert_eq!(scores.rank(&90), 2);
+/// assert_eq!(scores.rank(&95), 2);
+/// ```
+#[derive(Clone)]
+pub struct OrderStatisticsTree<K, V> {
+  root: Link<K, V>
+}
+
+type Link<K, V> = avl::Link<Node<K, V>>;
+
+/// Represents a node in an order statistics tree.
+#[derive(Clone)]
+struct Node<K, V> {
+  key: K,
+  value: V,
+  /// Number of nodes on the longest path from the node down to a leaf.
+  height: usize,
+  /// Number of nodes in the subtree of the node.
+  size: usize,
+  left: Link<K, V>,
+  right: Link<K, V>
+}
+
+impl<K: Ord, V> Default for OrderStatisticsTree<K, V> {
+  /// Creates a new instance of `OrderStatisticsTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord, V> OrderStatisticsTree<K, V> {
+  /// Creates a new empty tree.
+  pub fn new() -> Self {
+    Self { root: None }
+  }
+
+  /// Inserts a key-value pair into the tree.
+  ///
+  /// If the tree already contained the key, its value is replaced and
+  /// the old value is returned.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    let (root, old) = insert(self.root.take(), key, value);
+    self.root = Some(root);
+    old
+  }
+
+  /// Returns the entry of the key for in-place manipulation.
+  ///
+  /// The key is searched once, which also gives its rank. An occupied
+  /// entry is then reached by its rank without comparing keys again.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::OrderStatisticsTree;
+  ///
+  /// let mut counts = OrderStatisticsTree::new();
+  ///
+  /// for word in "the cat saw the dog".split(' ') {
+  ///   counts.entry(word).and_modify(|count| *count += 1).or_insert(1);
+  /// }
+  ///
+  /// assert_eq!(counts.get("the"), Some(&2));
+  /// assert_eq!(counts.select(0), Some((&"cat", &1)));
+  /// ```
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    match self.locate(&key) {
+      Ok(index) => {
+        let (key, value) = self.entry_mut(index);
+        Entry::Occupied(OccupiedEntry { key, value })
+      }
+      Err(index) => Entry::Vacant(VacantEntry { tree: self, key, index })
+    }
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the tree
+  /// does not contain the key.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => &node.right,
+        Ordering::Equal => return Some(&node.value)
+      };
+    }
+
+    None
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if
+  /// the tree does not contain the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut link = &mut self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &mut node.left,
+        Ordering::Greater => &mut node.right,
+        Ordering::Equal => return Some(&mut node.value)
+      };
+    }
+
+    None
+  }
+
+  /// Checks if the tree contains the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.get(key).is_some()
+  }
+
+  /// Returns a double-ended iterator over the entries whose keys are
+  /// within the range, in ascending order of keys.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::OrderStatisticsTree;
+  /// use std::ops::Bound::{Excluded, Unbounded};
+  ///
+  /// let tree: OrderStatisticsTree<i32, ()> = (1..=10).map(|key| (key, ())).collect();
+  ///
+  /// assert_eq!(tree.range(3..6).map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 4, 5]);
+  /// assert_eq!(tree.range((Excluded(8), Unbounded)).map(|(key, _)| *key).collect::<Vec<_>>(), vec![9, 10]);
+  /// assert_eq!(tree.range(..=3).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 2, 1]);
+  /// ```
+  pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    Range { walk: Walk::new(self.root.as_deref(), &range) }
+  }
+
+  /// Removes the key from the tree and returns its value,
+  /// or `None` if the tree did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let node = avl::remove(&mut self.root, &|node: &Node<K, V>| key.cmp(node.key.borrow()))?;
+    Some(node.value)
+  }
+
+  /// Returns the number of keys in the tree less than the key, which is
+  /// the index of the key in ascending order if the tree contains it.
+  pub fn rank<Q>(&self, key: &Q) -> usize
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.locate(key).unwrap_or_else(|rank| rank)
+  }
+
+  /// Returns the rank of the key, as `Ok` if the tree contains the key and
+  /// as `Err` otherwise.
+  fn locate<Q>(&self, key: &Q) -> Result<usize, usize>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut rank = 0;
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => {
+          rank += size(&node.left) + 1;
+          &node.right
+        }
+        Ordering::Equal => return Ok(rank + size(&node.left))
+      };
+    }
+
+    Err(rank)
+  }
+}
+
+impl<K, V> OrderStatisticsTree<K, V> {
+  /// Returns the number of entries in the tree.
+  pub fn len(&self) -> usize {
+    size(&self.root)
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns the entry with the `index`-th smallest key, counting from
+  /// zero, or `None` if the tree has fewer entries.
+  pub fn select(&self, mut index: usize) -> Option<(&K, &V)> {
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      let left = size(&node.left);
+      link = match index.cmp(&left) {
+        Ordering::Less => &node.left,
+        Ordering::Equal => return Some((&node.key, &node.value)),
+        Ordering::Greater => {
+          index -= left + 1;
+          &node.right
+        }
+      };
+    }
+
+    None
+  }
+
+  /// Returns the key and the value of the entry at the index, which must
+  /// be less than the length of the tree.
+  fn entry_mut(&mut self, mut index: usize) -> (&K, &mut V) {
+    let mut node = self.root.as_deref_mut().expect("index out of bounds");
+
+    loop {
+      let left = size(&node.left);
+      node = match index.cmp(&left) {
+        Ordering::Less => node.left.as_deref_mut().expect("index out of bounds"),
+        Ordering::Equal => return (&node.key, &mut node.value),
+        Ordering::Greater => {
+          index -= left + 1;
+          node.right.as_deref_mut().expect("index out of bounds")
+        }
+      };
+    }
+  }
+
+  /// Returns the entry with the smallest key, or `None` if the tree is
+  /// empty.
+  pub fn first(&self) -> Option<(&K, &V)> {
+    self.select(0)
+  }
+
+  /// Returns the entry with the greatest key, or `None` if the tree is
+  /// empty.
+  pub fn last(&self) -> Option<(&K, &V)> {
+    self.select(self.len().checked_sub(1)?)
+  }
+
+  /// Returns the entry with the smallest key, like `first`, under the name
+  /// `std::collections::BTreeMap` gives it.
+  pub fn first_key_value(&self) -> Option<(&K, &V)> {
+    self.first()
+  }
+
+  /// Returns the entry with the greatest key, like `last`, under the name
+  /// `std::collections::BTreeMap` gives it.
+  pub fn last_key_value(&self) -> Option<(&K, &V)> {
+    self.last()
+  }
+
+  /// Returns an iterator over the entries of the tree in ascending order
+  /// of keys.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { walk: Walk::all(self.root.as_deref()) }
+  }
+
+  /// Returns an iterator over the keys of the tree in ascending order.
+  pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  /// Returns an iterator over the values of the tree in ascending order of
+  /// keys.
+  pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  /// Removes all entries from the tree.
+  pub fn clear(&mut self) {
+    self.root = None;
+  }
+}
+
+impl<K, V> AvlNode for Node<K, V> {
+  fn height(&self) -> usize {
+    self.height
+  }
+
+  fn left_mut(&mut self) -> &mut Link<K, V> {
+    &mut self.left
+  }
+
+  fn right_mut(&mut self) -> &mut Link<K, V> {
+    &mut self.right
+  }
+
+  /// Recomputes the height and the size from the children.
+  fn update(&mut self) {
+    self.height = height(&self.left).max(height(&self.right)) + 1;
+    self.size = size(&self.left) + size(&self.right) + 1;
+  }
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+  link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Inserts the pair into the subtree and returns its new root, with the
+/// old value of the key if it was already in the subtree.
+fn insert<K: Ord, V>(link: Link<K, V>, key: K, value: V) -> (Box<Node<K, V>>, Option<V>) {
+  let Some(mut node) = link else {
+    return (Box::new(Node { key, value, height: 1, size: 1, left: None, right: None }), None);
+  };
+
+  match key.cmp(&node.key) {
+    Ordering::Less => {
+      let (left, old) = insert(node.left.take(), key, value);
+      node.left = Some(left);
+      (avl::balance(node), old)
+    }
+    Ordering::Greater => {
+      let (right, old) = insert(node.right.take(), key, value);
+      node.right = Some(right);
+      (avl::balance(node), old)
+    }
+    Ordering::Equal => {
+      let old = mem::replace(&mut node.value, value);
+      (node, Some(old))
+    }
+  }
+}
+
+/// A view into a single entry of an `OrderStatisticsTree`, which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V> {
+  /// The key is present in the tree.
+  Occupied(OccupiedEntry<'a, K, V>),
+  /// The key is absent from the tree.
+  Vacant(VacantEntry<'a, K, V>)
+}
+
+/// A view into an occupied entry of an `OrderStatisticsTree`.
+pub struct OccupiedEntry<'a, K, V> {
+  key: &'a K,
+  value: &'a mut V
+}
+
+/// A view into a vacant entry of an `OrderStatisticsTree`.
+pub struct VacantEntry<'a, K, V> {
+  tree: &'a mut OrderStatisticsTree<K, V>,
+  key: K,
+  /// Number of keys in the tree less than the key.
+  index: usize
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key()
+    }
+  }
+
+  /// Inserts `default` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    self.or_insert_with(|| default)
+  }
+
+  /// Inserts the result of `default` if the entry is vacant and returns
+  /// a mutable reference to the value.
+  pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default())
+    }
+  }
+
+  /// Inserts the default value if the entry is vacant and returns a
+  /// mutable reference to the value.
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// Calls `f` with the value if the entry is occupied, and returns the
+  /// entry so that it can be chained with `or_insert`.
+  pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      entry => entry
+    }
+  }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    self.key
+  }
+
+  /// Returns a reference to the value of the entry.
+  pub fn get(&self) -> &V {
+    self.value
+  }
+
+  /// Returns a mutable reference to the value of the entry.
+  pub fn get_mut(&mut self) -> &mut V {
+    self.value
+  }
+
+  /// Converts the entry into a mutable reference bound to the tree.
+  pub fn into_mut(self) -> &'a mut V {
+    self.value
+  }
+
+  /// Replaces the value of the entry and returns the old one.
+  pub fn insert(&mut self, value: V) -> V {
+    mem::replace(self.value, value)
+  }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+  /// Returns a reference to the key that would be inserted.
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  /// Inserts the value under the entry's key and returns a mutable
+  /// reference to it.
+  ///
+  /// The rotations that rebalance the tree move the nodes around, so the
+  /// new entry is reached again by its rank.
+  pub fn insert(self, value: V) -> &'a mut V {
+    let tree = self.tree;
+    let (root, _) = insert(tree.root.take(), self.key, value);
+    tree.root = Some(root);
+
+    tree.entry_mut(self.index).1
+  }
+}
+
+impl<K, V> Branches for Node<K, V> {
+  type Key = K;
+
+  fn key(&self) -> &K {
+    &self.key
+  }
+
+  fn left(&self) -> Option<&Self> {
+    self.left.as_deref()
+  }
+
+  fn right(&self) -> Option<&Self> {
+    self.right.as_deref()
+  }
+}
+
+/// An in-order iterator over the entries of an `OrderStatisticsTree`.
+pub struct Iter<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+/// An in-order iterator over the entries of an `OrderStatisticsTree`
+/// whose keys are within a range.
+pub struct Range<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a OrderStatisticsTree<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for OrderStatisticsTree<K, V> {
+  /// Creates a tree from key-value pairs. Later pairs overwrite earlier
+  /// pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut tree = Self::new();
+    tree.extend(iter);
+    tree
+  }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for OrderStatisticsTree<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for OrderStatisticsTree<K, V> {
+  /// Compares the entries in order, whatever the shapes of the trees.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for OrderStatisticsTree<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OrderStatisticsTree<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for OrderStatisticsTree<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map in ascending order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for OrderStatisticsTree<K, V>
+where
+  K: serde::Deserialize<'de> + Ord,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map into a tree. Later pairs overwrite earlier pairs
+  /// with the same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Ord,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = OrderStatisticsTree<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut tree = OrderStatisticsTree::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          tree.insert(key, value);
+        }
+
+        Ok(tree)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{height, size, Entry, Link, OrderStatisticsTree};
+  use proptest::prelude::*;
+  use std::collections::BTreeMap;
+  use std::ops::RangeBounds;
+  use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+  /// Checks the order of the keys, the balance, the heights and the sizes
+  /// of the subtree.
+  fn check<K: Ord, V>(link: &Link<K, V>, low: Option<&K>, high: Option<&K>) {
+    let Some(node) = link else {
+      return;
+    };
+
+    assert!(low.is_none_or(|low| *low < node.key) && high.is_none_or(|high| node.key < *high));
+    assert!(height(&node.left).abs_diff(height(&node.right)) <= 1);
+    assert_eq!(node.height, height(&node.left).max(height(&node.right)) + 1);
+    assert_eq!(node.size, size(&node.left) + size(&node.right) + 1);
+
+    check(&node.left, low, Some(&node.key));
+    check(&node.right, Some(&node.key), high);
+  }
+
+  #[test]
+  fn empty_tree() {
+    let mut tree = OrderStatisticsTree::<i32, i32>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.get(&1), None);
+    assert_eq!(tree.remove(&1), None);
+    assert_eq!(tree.select(0), None);
+    assert_eq!(tree.rank(&1), 0);
+    assert_eq!(tree.last(), None);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut tree = OrderStatisticsTree::new();
+
+    assert_eq!(tree.insert(2, "two"), None);
+    assert_eq!(tree.insert(1, "one"), None);
+    assert_eq!(tree.insert(2, "deux"), Some("two"));
+
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.get(&2), Some(&"deux"));
+    assert!(tree.contains_key(&1));
+
+    *tree.get_mut(&1).unwrap() = "un";
+    assert_eq!(tree.first(), Some((&1, &"un")));
+    assert_eq!(tree.last(), Some((&2, &"deux")));
+    assert_eq!(format!("{:?}", tree), r#"{1: "un", 2: "deux"}"#);
+  }
+
+  #[test]
+  fn select_and_rank() {
+    let tree: OrderStatisticsTree<i32, ()> = (0..100).map(|key| (key * 2, ())).collect();
+    check(&tree.root, None, None);
+
+    for index in 0..100 {
+      assert_eq!(tree.select(index), Some((&(index as i32 * 2), &())));
+      assert_eq!(tree.rank(&(index as i32 * 2)), index);
+      assert_eq!(tree.rank(&(index as i32 * 2 + 1)), index + 1);
+    }
+
+    assert_eq!(tree.select(100), None);
+    assert_eq!(tree.rank(&-1), 0);
+  }
+
+  #[test]
+  fn remove_keeps_balance() {
+    let mut tree: OrderStatisticsTree<i32, i32> = (0..1000).map(|key| (key, key * 10)).collect();
+    assert!(height(&tree.root) <= 15);
+
+    for key in (0..1000).filter(|key| key % 3 != 0) {
+      assert_eq!(tree.remove(&key), Some(key * 10));
+    }
+
+    check(&tree.root, None, None);
+    assert_eq!(tree.len(), 334);
+    assert_eq!(tree.select(100), Some((&300, &3000)));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: OrderStatisticsTree<i32, String> = [(2, "b".to_string()), (1, "a".to_string())].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"1":"a","2":"b"}"#);
+
+    let tree: OrderStatisticsTree<i32, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tree, value);
+  }
+
+  #[test]
+  fn entry() {
+    let mut tree = OrderStatisticsTree::new();
+
+    for key in (0..100).chain(0..50) {
+      *tree.entry(key).or_insert(0) += 1;
+    }
+
+    assert_eq!(tree.len(), 100);
+    assert_eq!(tree.entry(10).key(), &10);
+    assert_eq!(*tree.entry(10).or_default(), 2);
+    assert_eq!(*tree.entry(60).or_default(), 1);
+
+    match tree.entry(60) {
+      Entry::Occupied(mut entry) => assert_eq!(entry.insert(10), 1),
+      Entry::Vacant(_) => unreachable!()
+    }
+    assert_eq!(*tree.entry(60).and_modify(|value| *value += 1).or_default(), 11);
+    assert_eq!(*tree.entry(100).and_modify(|value| *value += 1).or_default(), 0);
+    assert_eq!(tree.len(), 101);
+  }
+
+  #[test]
+  fn range() {
+    let tree: OrderStatisticsTree<i32, ()> = (0..20).step_by(2).map(|key| (key, ())).collect();
+    let keys = |range: (Bound<i32>, Bound<i32>)| tree.range(range).map(|(key, _)| *key).collect::<Vec<_>>();
+
+    assert_eq!(keys((Included(4), Excluded(10))), vec![4, 6, 8]);
+    assert_eq!(keys((Excluded(4), Included(10))), vec![6, 8, 10]);
+    assert_eq!(keys((Included(5), Included(9))), vec![6, 8]);
+    assert_eq!(keys((Unbounded, Excluded(4))), vec![0, 2]);
+    assert_eq!(keys((Included(17), Unbounded)), vec![18]);
+    assert!(keys((Excluded(6), Excluded(8))).is_empty());
+    assert!(keys((Included(9), Included(3))).is_empty());
+  }
+
+  #[test]
+  fn reverse_iteration() {
+    let tree: OrderStatisticsTree<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+
+    assert_eq!(tree.keys().rev().copied().collect::<Vec<_>>(), (0..10).rev().collect::<Vec<_>>());
+    assert_eq!(tree.range(3..7).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![6, 5, 4, 3]);
+    assert_eq!(tree.first_key_value(), Some((&0, &0)));
+    assert_eq!(tree.last_key_value(), Some((&9, &90)));
+
+    // Both ends stop where they meet.
+    let mut range = tree.range(3..=6);
+    assert_eq!(range.next(), Some((&3, &30)));
+    assert_eq!(range.next_back(), Some((&6, &60)));
+    assert_eq!(range.next_back(), Some((&5, &50)));
+    assert_eq!(range.next(), Some((&4, &40)));
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next_back(), None);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Select(usize),
+    Rank(u8),
+    Range(Bound<u8>, Bound<u8>),
+    Entry(u8, i32)
+  }
+
+  fn bound() -> impl Strategy<Value = Bound<u8>> {
+    prop_oneof![
+      any::<u8>().prop_map(Included),
+      any::<u8>().prop_map(Excluded),
+      Just(Unbounded)
+    ]
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      1 => (0..300usize).prop_map(Operation::Select),
+      1 => any::<u8>().prop_map(Operation::Rank),
+      1 => (bound(), bound()).prop_map(|(start, end)| Operation::Range(start, end)),
+      2 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Entry(key, value))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_btree_map(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut tree = OrderStatisticsTree::new();
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(tree.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(tree.remove(&key), model.remove(&key)),
+          Operation::Select(index) => prop_assert_eq!(tree.select(index), model.iter().nth(index)),
+          Operation::Rank(key) => prop_assert_eq!(tree.rank(&key), model.range(..key).count()),
+          Operation::Range(start, end) => {
+            // `BTreeMap::range` panics on ranges that start after their end.
+            let expected: Vec<_> = model.iter().filter(|(key, _)| (start, end).contains(*key)).collect();
+
+            prop_assert_eq!(tree.range((start, end)).collect::<Vec<_>>(), expected.clone());
+            prop_assert!(tree.range((start, end)).rev().eq(expected.into_iter().rev()));
+          }
+          Operation::Entry(key, value) => {
+            let expected = *model.entry(key).and_modify(|old| *old ^= value).or_insert(value);
+            prop_assert_eq!(*tree.entry(key).and_modify(|old| *old ^= value).or_insert(value), expected);
+          }
+        }
+
+        check(&tree.root, None, None);
+        prop_assert_eq!(tree.len(), model.len());
+        prop_assert!(tree.iter().eq(model.iter()));
+      }
+    }
+  }
+}
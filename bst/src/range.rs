@@ -0,0 +1,128 @@
+use std::borrow::Borrow;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+
+
+/// Gives access to the key and the children of the nodes of a binary search
+/// tree, so that the ordered maps can share `Walk`.
+pub(crate) trait Branches {
+  type Key;
+
+  fn key(&self) -> &Self::Key;
+  fn left(&self) -> Option<&Self>;
+  fn right(&self) -> Option<&Self>;
+}
+
+/// An in-order walk over a range of the nodes of a binary search tree, from
+/// both ends.
+///
+/// Each end keeps the path to its next node, so both directions take O(1)
+/// amortized per node. The walk is over when the two ends meet at the same
+/// node.
+pub(crate) struct Walk<'a, N> {
+  /// Nodes left to visit from the front, the next one on top.
+  front: Vec<&'a N>,
+  /// Nodes left to visit from the back, the next one on top.
+  back: Vec<&'a N>
+}
+
+impl<'a, N: Branches> Walk<'a, N> {
+  /// Creates a walk over every node of the tree.
+  pub(crate) fn all(root: Option<&'a N>) -> Self {
+    let mut walk = Self { front: Vec::new(), back: Vec::new() };
+    walk.push_left_spine(root);
+    walk.push_right_spine(root);
+    walk
+  }
+
+  /// Creates a walk over the nodes whose keys are within the range.
+  pub(crate) fn new<Q, R>(root: Option<&'a N>, range: &R) -> Self
+  where
+    N::Key: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    let mut walk = Self { front: Vec::new(), back: Vec::new() };
+
+    let mut node = root;
+    while let Some(current) = node {
+      let key = current.key().borrow();
+      node = match range.start_bound() {
+        Bound::Included(start) if key < start => current.right(),
+        Bound::Excluded(start) if key <= start => current.right(),
+        _ => {
+          walk.front.push(current);
+          current.left()
+        }
+      };
+    }
+
+    let mut node = root;
+    while let Some(current) = node {
+      let key = current.key().borrow();
+      node = match range.end_bound() {
+        Bound::Included(end) if key > end => current.left(),
+        Bound::Excluded(end) if key >= end => current.left(),
+        _ => {
+          walk.back.push(current);
+          current.right()
+        }
+      };
+    }
+
+    // An empty range may start after its end, in which case the two ends
+    // would never meet.
+    if walk.front.last().is_none_or(|node| !range.contains(node.key().borrow())) {
+      walk.front.clear();
+      walk.back.clear();
+    }
+
+    walk
+  }
+
+  fn push_left_spine(&mut self, mut node: Option<&'a N>) {
+    while let Some(current) = node {
+      self.front.push(current);
+      node = current.left();
+    }
+  }
+
+  fn push_right_spine(&mut self, mut node: Option<&'a N>) {
+    while let Some(current) = node {
+      self.back.push(current);
+      node = current.right();
+    }
+  }
+}
+
+impl<'a, N: Branches> Iterator for Walk<'a, N> {
+  type Item = &'a N;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.front.pop()?;
+
+    if self.back.last().is_some_and(|last| ptr::eq(*last, node)) {
+      self.front.clear();
+      self.back.clear();
+    } else {
+      self.push_left_spine(node.right());
+    }
+
+    Some(node)
+  }
+}
+
+impl<'a, N: Branches> DoubleEndedIterator for Walk<'a, N> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let node = self.back.pop()?;
+
+    if self.front.last().is_some_and(|first| ptr::eq(*first, node)) {
+      self.front.clear();
+      self.back.clear();
+    } else {
+      self.push_right_spine(node.left());
+    }
+
+    Some(node)
+  }
+}
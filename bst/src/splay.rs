@@ -0,0 +1,887 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+use std::ops::RangeBounds;
+
+use crate::range::{Branches, Walk};
+
+
+/// An ordered map based on a splay tree, a binary search tree that moves
+/// every key it accesses to the root.
+///
+/// Every access splays the tree: the accessed node is rotated up to the
+/// root in pairs of rotations, which also roughly halves the depth of the
+/// nodes on its path. The tree is not balanced and a single access can take
+/// O(n), but with the sum of the logarithms of the sizes of the subtrees as
+/// a potential, a splay costs at most O(log n) plus the drop of potential,
+/// so any sequence of m accesses takes O((m + n) log n): every operation
+/// runs in amortized O(log n).
+///
+/// Splaying also adapts the tree to the accesses. Recently accessed keys
+/// are near the root, so accessing keys out of a working set of `w` keys
+/// takes amortized O(log w) instead of O(log n), and repeated accesses to
+/// the same key take O(1).
+///
+/// Since reading a key changes the shape of the tree, `get` takes the tree
+/// mutably. `peek` reads a key without splaying, so it takes the tree
+/// immutably but does not pay for the next accesses.
+///
+/// # Example
+///
+/// ```
+/// use bst::SplayTree;
+///
+/// let mut tree: SplayTree<i32, &str> = [(1, "one"), (2, "two"), (3, "three")].into_iter().collect();
+///
+/// assert_eq!(tree.peek(&1), Some(&"one"));
+/// assert_eq!(tree.get(&2), Some(&"two"));
+/// assert_eq!(tree.root(), Some((&2, &"two")));
+///
+/// assert_eq!(tree.remove(&2), Some("two"));
+/// assert_eq!(tree.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+/// ```
+pub struct SplayTree<K, V> {
+  root: Link<K, V>,
+  len: usize
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// Represents a node in a splay tree.
+struct Node<K, V> {
+  key: K,
+  value: V,
+  left: Link<K, V>,
+  right: Link<K, V>
+}
+
+impl<K: Ord, V> Default for SplayTree<K, V> {
+  /// Creates a new instance of `SplayTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord, V> SplayTree<K, V> {
+  /// Creates a new empty splay tree.
+  pub fn new() -> Self {
+    Self { root: None, len: 0 }
+  }
+
+  /// Returns the number of entries in the tree.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Inserts a key-value pair into the tree, and splays the key to the
+  /// root.
+  ///
+  /// If the tree already contained the key, its value is replaced and
+  /// the old value is returned.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.entry(key) {
+      Entry::Occupied(mut entry) => Some(entry.insert(value)),
+      Entry::Vacant(entry) => {
+        entry.insert(value);
+        None
+      }
+    }
+  }
+
+  /// Returns the entry of the key for in-place manipulation, and splays
+  /// the key to the root.
+  ///
+  /// After the splay an occupied entry is at the root, and a vacant one is
+  /// inserted there in O(1), between the root and one of its subtrees.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::SplayTree;
+  ///
+  /// let mut counts = SplayTree::new();
+  ///
+  /// for word in "the cat saw the dog".split(' ') {
+  ///   counts.entry(word).and_modify(|count| *count += 1).or_insert(1);
+  /// }
+  ///
+  /// assert_eq!(counts.peek("the"), Some(&2));
+  /// assert_eq!(counts.root(), Some((&"dog", &1)));
+  /// ```
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    self.root = self.root.take().map(|root| splay(root, &key));
+
+    if self.root.as_ref().is_some_and(|root| root.key == key) {
+      let root = self.root.as_deref_mut().unwrap();
+      return Entry::Occupied(OccupiedEntry { key: &root.key, value: &mut root.value });
+    }
+
+    Entry::Vacant(VacantEntry { tree: self, key })
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the tree
+  /// does not contain the key.
+  ///
+  /// The key, or the last key on its search path if it is missing, is
+  /// splayed to the root.
+  pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.get_mut(key).map(|value| &*value)
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if the
+  /// tree does not contain the key.
+  ///
+  /// The key, or the last key on its search path if it is missing, is
+  /// splayed to the root.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.root = self.root.take().map(|root| splay(root, key));
+    self.root.as_mut().filter(|root| root.key.borrow() == key).map(|root| &mut root.value)
+  }
+
+  /// Returns a reference to the value of the key without splaying, or
+  /// `None` if the tree does not contain the key.
+  ///
+  /// This takes O(h) where `h` is the depth of the key, which is not
+  /// bounded by the amortized analysis of the splay tree.
+  pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => &node.right,
+        Ordering::Equal => return Some(&node.value)
+      };
+    }
+
+    None
+  }
+
+  /// Checks if the tree contains the key, without splaying.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.peek(key).is_some()
+  }
+
+  /// Returns a double-ended iterator over the entries whose keys are
+  /// within the range, in ascending order of keys, without splaying.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::SplayTree;
+  /// use std::ops::Bound::{Excluded, Unbounded};
+  ///
+  /// let tree: SplayTree<i32, ()> = (1..=10).map(|key| (key, ())).collect();
+  ///
+  /// assert_eq!(tree.range(3..6).map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 4, 5]);
+  /// assert_eq!(tree.range((Excluded(8), Unbounded)).map(|(key, _)| *key).collect::<Vec<_>>(), vec![9, 10]);
+  /// assert_eq!(tree.range(..=3).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 2, 1]);
+  /// ```
+  pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    Range { walk: Walk::new(self.root.as_deref(), &range) }
+  }
+
+  /// Removes the key from the tree and returns its value,
+  /// or `None` if the tree did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let root = splay(self.root.take()?, key);
+
+    if root.key.borrow() != key {
+      self.root = Some(root);
+      return None;
+    }
+
+    let Node { value, left, right, .. } = *root;
+    self.root = match left {
+      None => right,
+      Some(left) => {
+        // Every key of the left subtree is less than the key, so its
+        // greatest key is splayed to its root, which has no right child.
+        let mut left = splay(left, key);
+        left.right = right;
+        Some(left)
+      }
+    };
+    self.len -= 1;
+
+    Some(value)
+  }
+}
+
+impl<K, V> SplayTree<K, V> {
+  /// Returns the entry at the root of the tree, the last one accessed, or
+  /// `None` if the tree is empty.
+  pub fn root(&self) -> Option<(&K, &V)> {
+    self.root.as_deref().map(|root| (&root.key, &root.value))
+  }
+
+  /// Returns the entry with the smallest key without splaying, or `None`
+  /// if the tree is empty.
+  pub fn first_key_value(&self) -> Option<(&K, &V)> {
+    let mut node = self.root.as_deref()?;
+    while let Some(left) = node.left.as_deref() {
+      node = left;
+    }
+    Some((&node.key, &node.value))
+  }
+
+  /// Returns the entry with the greatest key without splaying, or `None`
+  /// if the tree is empty.
+  pub fn last_key_value(&self) -> Option<(&K, &V)> {
+    let mut node = self.root.as_deref()?;
+    while let Some(right) = node.right.as_deref() {
+      node = right;
+    }
+    Some((&node.key, &node.value))
+  }
+
+  /// Returns an iterator over the entries of the tree in ascending order
+  /// of keys, without splaying.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { walk: Walk::all(self.root.as_deref()) }
+  }
+
+  /// Returns an iterator over the keys of the tree in ascending order.
+  pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  /// Returns an iterator over the values of the tree in ascending order of
+  /// keys.
+  pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  /// Removes all entries from the tree.
+  pub fn clear(&mut self) {
+    // Frees the nodes one by one, as the tree may be too deep to drop
+    // recursively.
+    let mut stack: Vec<_> = self.root.take().into_iter().collect();
+
+    while let Some(mut node) = stack.pop() {
+      stack.extend(node.left.take());
+      stack.extend(node.right.take());
+    }
+
+    self.len = 0;
+  }
+}
+
+/// Splays the key in the subtree top-down and returns the new root: the
+/// node of the key, or the last node on its search path if it is missing.
+fn splay<K, V, Q>(mut root: Box<Node<K, V>>, key: &Q) -> Box<Node<K, V>>
+where
+  K: Borrow<Q>,
+  Q: Ord + ?Sized,
+{
+  // Nodes less than the key in ascending order, each waiting for its right
+  // subtree, and nodes greater than it in descending order, each waiting
+  // for its left subtree.
+  let mut lesser = Vec::new();
+  let mut greater = Vec::new();
+
+  loop {
+    match key.cmp(root.key.borrow()) {
+      Ordering::Less => {
+        let Some(mut left) = root.left.take() else {
+          break;
+        };
+
+        if key < left.key.borrow() {
+          // Zig-zig: rotates the left child up first.
+          root.left = left.right.take();
+          left.right = Some(root);
+          root = left;
+
+          let Some(next) = root.left.take() else {
+            break;
+          };
+          greater.push(mem::replace(&mut root, next));
+        } else {
+          greater.push(mem::replace(&mut root, left));
+        }
+      }
+      Ordering::Greater => {
+        let Some(mut right) = root.right.take() else {
+          break;
+        };
+
+        if key > right.key.borrow() {
+          // Zig-zig: rotates the right child up first.
+          root.right = right.left.take();
+          right.left = Some(root);
+          root = right;
+
+          let Some(next) = root.right.take() else {
+            break;
+          };
+          lesser.push(mem::replace(&mut root, next));
+        } else {
+          lesser.push(mem::replace(&mut root, right));
+        }
+      }
+      Ordering::Equal => break
+    }
+  }
+
+  let mut left = root.left.take();
+  for mut node in lesser.into_iter().rev() {
+    node.right = left;
+    left = Some(node);
+  }
+
+  let mut right = root.right.take();
+  for mut node in greater.into_iter().rev() {
+    node.left = right;
+    right = Some(node);
+  }
+
+  root.left = left;
+  root.right = right;
+  root
+}
+
+impl<K, V> Drop for SplayTree<K, V> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+/// A view into a single entry of a `SplayTree`, which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V> {
+  /// The key is present in the tree.
+  Occupied(OccupiedEntry<'a, K, V>),
+  /// The key is absent from the tree.
+  Vacant(VacantEntry<'a, K, V>)
+}
+
+/// A view into an occupied entry of a `SplayTree`.
+pub struct OccupiedEntry<'a, K, V> {
+  key: &'a K,
+  value: &'a mut V
+}
+
+/// A view into a vacant entry of a `SplayTree`.
+pub struct VacantEntry<'a, K, V> {
+  tree: &'a mut SplayTree<K, V>,
+  key: K
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key()
+    }
+  }
+
+  /// Inserts `default` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    self.or_insert_with(|| default)
+  }
+
+  /// Inserts the result of `default` if the entry is vacant and returns
+  /// a mutable reference to the value.
+  pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default())
+    }
+  }
+
+  /// Inserts the default value if the entry is vacant and returns a
+  /// mutable reference to the value.
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// Calls `f` with the value if the entry is occupied, and returns the
+  /// entry so that it can be chained with `or_insert`.
+  pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      entry => entry
+    }
+  }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    self.key
+  }
+
+  /// Returns a reference to the value of the entry.
+  pub fn get(&self) -> &V {
+    self.value
+  }
+
+  /// Returns a mutable reference to the value of the entry.
+  pub fn get_mut(&mut self) -> &mut V {
+    self.value
+  }
+
+  /// Converts the entry into a mutable reference bound to the tree.
+  pub fn into_mut(self) -> &'a mut V {
+    self.value
+  }
+
+  /// Replaces the value of the entry and returns the old one.
+  pub fn insert(&mut self, value: V) -> V {
+    mem::replace(self.value, value)
+  }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+  /// Returns a reference to the key that would be inserted.
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  /// Inserts the value under the entry's key and returns a mutable
+  /// reference to it.
+  ///
+  /// The key was splayed to the root when the entry was created, so the
+  /// root is next to the key and the new node takes its place.
+  pub fn insert(self, value: V) -> &'a mut V {
+    let tree = self.tree;
+    let (left, right) = match tree.root.take() {
+      None => (None, None),
+      Some(mut root) if self.key < root.key => (root.left.take(), Some(root)),
+      Some(mut root) => {
+        let right = root.right.take();
+        (Some(root), right)
+      }
+    };
+
+    tree.len += 1;
+    &mut tree.root.insert(Box::new(Node { key: self.key, value, left, right })).value
+  }
+}
+
+impl<K, V> Branches for Node<K, V> {
+  type Key = K;
+
+  fn key(&self) -> &K {
+    &self.key
+  }
+
+  fn left(&self) -> Option<&Self> {
+    self.left.as_deref()
+  }
+
+  fn right(&self) -> Option<&Self> {
+    self.right.as_deref()
+  }
+}
+
+/// An in-order iterator over the entries of a `SplayTree`.
+pub struct Iter<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+/// An in-order iterator over the entries of a `SplayTree` whose keys are
+/// within a range.
+pub struct Range<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a SplayTree<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SplayTree<K, V> {
+  /// Creates a splay tree from key-value pairs. Later pairs overwrite
+  /// earlier pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut tree = Self::new();
+    tree.extend(iter);
+    tree
+  }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SplayTree<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for SplayTree<K, V> {
+  /// Compares the entries in order, whatever the shapes of the trees.
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for SplayTree<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for SplayTree<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for SplayTree<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map in ascending order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for SplayTree<K, V>
+where
+  K: serde::Deserialize<'de> + Ord,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map into a splay tree. Later pairs overwrite earlier
+  /// pairs with the same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Ord,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = SplayTree<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut tree = SplayTree::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          tree.insert(key, value);
+        }
+
+        Ok(tree)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Entry, SplayTree};
+  use proptest::prelude::*;
+  use std::collections::BTreeMap;
+  use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+  /// Returns the number of nodes on the search path of the key.
+  fn depth<V>(tree: &SplayTree<u32, V>, key: u32) -> usize {
+    let mut depth = 0;
+    let mut link = &tree.root;
+
+    while let Some(node) = link {
+      depth += 1;
+      link = match key.cmp(&node.key) {
+        std::cmp::Ordering::Less => &node.left,
+        std::cmp::Ordering::Greater => &node.right,
+        std::cmp::Ordering::Equal => break
+      };
+    }
+
+    depth
+  }
+
+  /// Accesses the keys in order with `get` and returns the average number
+  /// of nodes visited per access.
+  fn average_cost(tree: &mut SplayTree<u32, ()>, keys: impl Iterator<Item = u32>) -> f64 {
+    let (mut total, mut count) = (0, 0);
+
+    for key in keys {
+      total += depth(tree, key);
+      count += 1;
+      assert!(tree.get(&key).is_some());
+    }
+
+    total as f64 / count as f64
+  }
+
+  /// Maps `0..n` onto itself in a scrambled order, for any `n` coprime
+  /// with 7919.
+  fn scramble(i: u32, n: u32) -> u32 {
+    (u64::from(i) * 7_919 % u64::from(n)) as u32
+  }
+
+  #[test]
+  fn empty_tree() {
+    let mut tree = SplayTree::<i32, i32>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.get(&1), None);
+    assert_eq!(tree.remove(&1), None);
+    assert_eq!(tree.root(), None);
+  }
+
+  #[test]
+  fn insert_get_and_peek() {
+    let mut tree = SplayTree::new();
+
+    assert_eq!(tree.insert(2, "two"), None);
+    assert_eq!(tree.insert(1, "one"), None);
+    assert_eq!(tree.insert(3, "three"), None);
+    assert_eq!(tree.insert(2, "deux"), Some("two"));
+    assert_eq!(tree.len(), 3);
+    assert_eq!(tree.root(), Some((&2, &"deux")));
+
+    // Peeking leaves the tree as it is, getting splays.
+    assert_eq!(tree.peek(&1), Some(&"one"));
+    assert_eq!(tree.root(), Some((&2, &"deux")));
+    assert_eq!(tree.get(&1), Some(&"one"));
+    assert_eq!(tree.root(), Some((&1, &"one")));
+
+    // A missing key splays the last key on its path.
+    assert_eq!(tree.get(&4), None);
+    assert_eq!(tree.root(), Some((&3, &"three")));
+
+    *tree.get_mut(&1).unwrap() = "un";
+    assert!(tree.contains_key(&1));
+    assert_eq!(format!("{:?}", tree), r#"{1: "un", 2: "deux", 3: "three"}"#);
+  }
+
+  #[test]
+  fn remove() {
+    let mut tree: SplayTree<i32, i32> = (0..100).map(|key| (key, key * 10)).collect();
+
+    for key in (0..100).step_by(2) {
+      assert_eq!(tree.remove(&key), Some(key * 10));
+    }
+
+    assert_eq!(tree.remove(&0), None);
+    assert_eq!(tree.len(), 50);
+    assert!(tree.keys().copied().eq((1..100).step_by(2)));
+  }
+
+  #[test]
+  fn deep_tree() {
+    // Sorted insertions build a path, which must not overflow the stack.
+    let mut tree: SplayTree<u32, ()> = (0..100_000).map(|key| (key, ())).collect();
+    assert_eq!(depth(&tree, 0), 100_000);
+
+    // Splaying the deepest key roughly halves the depth of its path.
+    assert!(tree.get(&0).is_some());
+    assert!(depth(&tree, 1) < 60_000);
+  }
+
+  #[test]
+  fn working_set_is_cheap() {
+    let n = 10_000;
+    let mut tree: SplayTree<u32, ()> = (0..n).map(|i| (scramble(i, n), ())).collect();
+
+    // Accesses spread over all the keys cost about log n on average.
+    let spread = average_cost(&mut tree, (0..20_000).map(|i| scramble(i * 7 + 3, n)));
+
+    // Accesses to eight keys keep them near the root.
+    let working_set = [17, 4_242, 999, 7_001, 3_333, 8_765, 123, 5_555];
+    let hot = average_cost(&mut tree, (0..20_000).map(|i| working_set[scramble(i, 8) as usize]));
+
+    assert!(hot * 3.0 < spread, "working set costs {} per access against {}", hot, spread);
+    assert!(working_set.iter().all(|&key| depth(&tree, key) <= 8));
+  }
+
+  #[test]
+  fn repeated_access_is_free() {
+    let mut tree: SplayTree<u32, ()> = (0..1_000).map(|key| (key * 7 % 1_000, ())).collect();
+    tree.get(&500);
+
+    assert_eq!(average_cost(&mut tree, std::iter::repeat_n(500, 100)), 1.0);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: SplayTree<i32, String> = [(2, "b".to_string()), (1, "a".to_string())].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"1":"a","2":"b"}"#);
+
+    let tree: SplayTree<i32, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tree, value);
+  }
+
+  #[test]
+  fn entry() {
+    let mut tree = SplayTree::new();
+
+    for key in (0..100).chain(0..50) {
+      *tree.entry(key).or_insert(0) += 1;
+    }
+
+    assert_eq!(tree.len(), 100);
+    assert_eq!(tree.entry(10).key(), &10);
+    assert_eq!(*tree.entry(10).or_default(), 2);
+    assert_eq!(*tree.entry(60).or_default(), 1);
+
+    match tree.entry(60) {
+      Entry::Occupied(mut entry) => assert_eq!(entry.insert(10), 1),
+      Entry::Vacant(_) => unreachable!()
+    }
+    assert_eq!(*tree.entry(60).and_modify(|value| *value += 1).or_default(), 11);
+    assert_eq!(*tree.entry(100).and_modify(|value| *value += 1).or_default(), 0);
+    assert_eq!(tree.len(), 101);
+  }
+
+  #[test]
+  fn range() {
+    let tree: SplayTree<i32, ()> = (0..20).step_by(2).map(|key| (key, ())).collect();
+    let keys = |range: (Bound<i32>, Bound<i32>)| tree.range(range).map(|(key, _)| *key).collect::<Vec<_>>();
+
+    assert_eq!(keys((Included(4), Excluded(10))), vec![4, 6, 8]);
+    assert_eq!(keys((Excluded(4), Included(10))), vec![6, 8, 10]);
+    assert_eq!(keys((Included(5), Included(9))), vec![6, 8]);
+    assert_eq!(keys((Unbounded, Excluded(4))), vec![0, 2]);
+    assert_eq!(keys((Included(17), Unbounded)), vec![18]);
+    assert!(keys((Excluded(6), Excluded(8))).is_empty());
+    assert!(keys((Included(9), Included(3))).is_empty());
+  }
+
+  #[test]
+  fn reverse_iteration() {
+    let tree: SplayTree<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+
+    assert_eq!(tree.keys().rev().copied().collect::<Vec<_>>(), (0..10).rev().collect::<Vec<_>>());
+    assert_eq!(tree.range(3..7).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![6, 5, 4, 3]);
+    assert_eq!(tree.first_key_value(), Some((&0, &0)));
+    assert_eq!(tree.last_key_value(), Some((&9, &90)));
+
+    // Both ends stop where they meet.
+    let mut range = tree.range(3..=6);
+    assert_eq!(range.next(), Some((&3, &30)));
+    assert_eq!(range.next_back(), Some((&6, &60)));
+    assert_eq!(range.next_back(), Some((&5, &50)));
+    assert_eq!(range.next(), Some((&4, &40)));
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next_back(), None);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Get(u8),
+    Peek(u8),
+    Entry(u8, i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      any::<u8>().prop_map(Operation::Remove),
+      any::<u8>().prop_map(Operation::Get),
+      any::<u8>().prop_map(Operation::Peek),
+      (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Entry(key, value))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_btree_map(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut tree = SplayTree::new();
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(tree.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(tree.remove(&key), model.remove(&key)),
+          Operation::Get(key) => prop_assert_eq!(tree.get(&key), model.get(&key)),
+          Operation::Peek(key) => prop_assert_eq!(tree.peek(&key), model.get(&key)),
+          Operation::Entry(key, value) => {
+            let expected = *model.entry(key).and_modify(|old| *old ^= value).or_insert(value);
+            prop_assert_eq!(*tree.entry(key).and_modify(|old| *old ^= value).or_insert(value), expected);
+          }
+        }
+
+        prop_assert_eq!(tree.len(), model.len());
+        prop_assert!(tree.iter().eq(model.iter()));
+      }
+    }
+  }
+}
@@ -0,0 +1,917 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+use std::ops::RangeBounds;
+
+use random::SplitMix64;
+
+use crate::range::{Branches, Walk};
+
+
+/// An ordered map based on a treap, a binary search tree with random
+/// priorities.
+///
+/// The nodes are ordered by key like in a binary search tree, and every
+/// node also gets a random priority higher than the priorities of its
+/// children, like in a heap. The tree has the shape it would have if the
+/// keys had been inserted in order of priority, so whatever order they are
+/// really inserted in, its expected height is O(log n), and so is the cost
+/// of every operation.
+///
+/// The treap is built on two primitives that run in expected O(log n)
+/// and are exposed: `split_off` cuts the map in two at a key, and
+/// `append` joins two maps whose keys do not overlap.
+///
+/// # Example
+///
+/// ```
+/// use bst::Treap;
+///
+/// let mut treap = Treap::new();
+/// treap.insert(3, "three");
+/// treap.insert(1, "one");
+/// treap.insert(2, "two");
+///
+/// assert_eq!(treap.get(&2), Some(&"two"));
+/// assert_eq!(treap.remove(&1), Some("one"));
+///
+/// let high = treap.split_off(&3);
+/// assert_eq!(treap.keys().copied().collect::<Vec<_>>(), vec![2]);
+/// assert_eq!(high.keys().copied().collect::<Vec<_>>(), vec![3]);
+///
+/// treap.append(high);
+/// assert_eq!(treap.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct Treap<K, V> {
+  root: Link<K, V>,
+  rng: SplitMix64
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+/// Represents a node in a treap.
+#[derive(Clone)]
+struct Node<K, V> {
+  key: K,
+  value: V,
+  /// Random priority, higher than the priorities of the children.
+  priority: u64,
+  /// Number of nodes in the subtree of the node.
+  size: usize,
+  left: Link<K, V>,
+  right: Link<K, V>
+}
+
+impl<K: Ord, V> Default for Treap<K, V> {
+  /// Creates a new instance of `Treap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord, V> Treap<K, V> {
+  /// Creates a new empty treap with random priorities.
+  pub fn new() -> Self {
+    Self { root: None, rng: SplitMix64::new(None) }
+  }
+
+  /// Creates a new empty treap whose priorities are drawn from a
+  /// generator seeded with `seed`, which makes its shape reproducible.
+  pub fn with_seed(seed: u64) -> Self {
+    Self { root: None, rng: SplitMix64::new(Some(seed)) }
+  }
+
+  /// Returns the number of entries in the treap.
+  pub fn len(&self) -> usize {
+    size(&self.root)
+  }
+
+  /// Checks if the treap is empty.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Inserts a key-value pair into the treap.
+  ///
+  /// If the treap already contained the key, its value is replaced and
+  /// the old value is returned.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.entry(key) {
+      Entry::Occupied(mut entry) => Some(entry.insert(value)),
+      Entry::Vacant(entry) => {
+        entry.insert(value);
+        None
+      }
+    }
+  }
+
+  /// Returns the entry of the key for in-place manipulation.
+  ///
+  /// The key is searched once, which also gives its rank. An occupied
+  /// entry is then reached by its rank without comparing keys again.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::Treap;
+  ///
+  /// let mut counts = Treap::new();
+  ///
+  /// for word in "the cat saw the dog".split(' ') {
+  ///   counts.entry(word).and_modify(|count| *count += 1).or_insert(1);
+  /// }
+  ///
+  /// assert_eq!(counts.get("the"), Some(&2));
+  /// assert_eq!(counts.get("cat"), Some(&1));
+  /// ```
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    match self.locate(&key) {
+      Ok(index) => {
+        let (key, value) = self.entry_mut(index);
+        Entry::Occupied(OccupiedEntry { key, value })
+      }
+      Err(_) => Entry::Vacant(VacantEntry { treap: self, key })
+    }
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the treap
+  /// does not contain the key.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => &node.right,
+        Ordering::Equal => return Some(&node.value)
+      };
+    }
+
+    None
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if
+  /// the treap does not contain the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut link = &mut self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &mut node.left,
+        Ordering::Greater => &mut node.right,
+        Ordering::Equal => return Some(&mut node.value)
+      };
+    }
+
+    None
+  }
+
+  /// Checks if the treap contains the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.get(key).is_some()
+  }
+
+  /// Returns a double-ended iterator over the entries whose keys are
+  /// within the range, in ascending order of keys.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bst::Treap;
+  /// use std::ops::Bound::{Excluded, Unbounded};
+  ///
+  /// let treap: Treap<i32, ()> = (1..=10).map(|key| (key, ())).collect();
+  ///
+  /// assert_eq!(treap.range(3..6).map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 4, 5]);
+  /// assert_eq!(treap.range((Excluded(8), Unbounded)).map(|(key, _)| *key).collect::<Vec<_>>(), vec![9, 10]);
+  /// assert_eq!(treap.range(..=3).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 2, 1]);
+  /// ```
+  pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    Range { walk: Walk::new(self.root.as_deref(), &range) }
+  }
+
+  /// Returns the rank of the key, as `Ok` if the treap contains the key
+  /// and as `Err` otherwise.
+  fn locate<Q>(&self, key: &Q) -> Result<usize, usize>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut rank = 0;
+    let mut link = &self.root;
+
+    while let Some(node) = link {
+      link = match key.cmp(node.key.borrow()) {
+        Ordering::Less => &node.left,
+        Ordering::Greater => {
+          rank += size(&node.left) + 1;
+          &node.right
+        }
+        Ordering::Equal => return Ok(rank + size(&node.left))
+      };
+    }
+
+    Err(rank)
+  }
+
+  /// Removes the key from the treap and returns its value,
+  /// or `None` if the treap did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    remove(&mut self.root, key)
+  }
+
+  /// Splits the treap in two at the key: this treap keeps the keys less
+  /// than `key`, and the keys greater than or equal to it are returned in
+  /// a new treap, in expected O(log n).
+  pub fn split_off<Q>(&mut self, key: &Q) -> Self
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let (left, right) = split(self.root.take(), key);
+    self.root = left;
+    Self { root: right, rng: SplitMix64::new(Some(self.rng.next_u64())) }
+  }
+
+  /// Moves all the entries of the other treap into this one, in expected
+  /// O(log n).
+  ///
+  /// # Panics
+  ///
+  /// Panics if a key of the other treap is not greater than all the keys
+  /// of this one.
+  pub fn append(&mut self, mut other: Self) {
+    if let (Some((last, _)), Some((first, _))) = (self.last(), other.first()) {
+      assert!(last < first, "keys must be greater than the keys of the treap");
+    }
+
+    self.root = merge(self.root.take(), other.root.take());
+  }
+}
+
+impl<K, V> Treap<K, V> {
+  /// Returns the entry with the smallest key, or `None` if the treap is
+  /// empty.
+  pub fn first(&self) -> Option<(&K, &V)> {
+    let mut node = self.root.as_deref()?;
+    while let Some(left) = node.left.as_deref() {
+      node = left;
+    }
+    Some((&node.key, &node.value))
+  }
+
+  /// Returns the entry with the greatest key, or `None` if the treap is
+  /// empty.
+  pub fn last(&self) -> Option<(&K, &V)> {
+    let mut node = self.root.as_deref()?;
+    while let Some(right) = node.right.as_deref() {
+      node = right;
+    }
+    Some((&node.key, &node.value))
+  }
+
+  /// Returns the entry with the smallest key, like `first`, under the name
+  /// `std::collections::BTreeMap` gives it.
+  pub fn first_key_value(&self) -> Option<(&K, &V)> {
+    self.first()
+  }
+
+  /// Returns the entry with the greatest key, like `last`, under the name
+  /// `std::collections::BTreeMap` gives it.
+  pub fn last_key_value(&self) -> Option<(&K, &V)> {
+    self.last()
+  }
+
+  /// Returns the key and the value of the entry at the index, which must
+  /// be less than the length of the treap.
+  fn entry_mut(&mut self, mut index: usize) -> (&K, &mut V) {
+    let mut node = self.root.as_deref_mut().expect("index out of bounds");
+
+    loop {
+      let left = size(&node.left);
+      node = match index.cmp(&left) {
+        Ordering::Less => node.left.as_deref_mut().expect("index out of bounds"),
+        Ordering::Equal => return (&node.key, &mut node.value),
+        Ordering::Greater => {
+          index -= left + 1;
+          node.right.as_deref_mut().expect("index out of bounds")
+        }
+      };
+    }
+  }
+
+  /// Returns an iterator over the entries of the treap in ascending order
+  /// of keys.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { walk: Walk::all(self.root.as_deref()) }
+  }
+
+  /// Returns an iterator over the keys of the treap in ascending order.
+  pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  /// Returns an iterator over the values of the treap in ascending order
+  /// of keys.
+  pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  /// Removes all entries from the treap.
+  pub fn clear(&mut self) {
+    self.root = None;
+  }
+}
+
+impl<K, V> Node<K, V> {
+  /// Recomputes the size of the subtree from the children.
+  fn update(&mut self) {
+    self.size = size(&self.left) + size(&self.right) + 1;
+  }
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+  link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Splits the subtree into the nodes with keys less than `key` and the
+/// nodes with keys greater than or equal to it.
+fn split<K, V, Q>(link: Link<K, V>, key: &Q) -> (Link<K, V>, Link<K, V>)
+where
+  K: Borrow<Q>,
+  Q: Ord + ?Sized,
+{
+  let Some(mut node) = link else {
+    return (None, None);
+  };
+
+  if node.key.borrow() < key {
+    let (left, right) = split(node.right.take(), key);
+    node.right = left;
+    node.update();
+    (Some(node), right)
+  } else {
+    let (left, right) = split(node.left.take(), key);
+    node.left = right;
+    node.update();
+    (left, Some(node))
+  }
+}
+
+/// Joins two subtrees, all the keys of `left` being less than the keys of
+/// `right`.
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+  match (left, right) {
+    (Some(mut left), Some(mut right)) => {
+      if left.priority > right.priority {
+        left.right = merge(left.right.take(), Some(right));
+        left.update();
+        Some(left)
+      } else {
+        right.left = merge(Some(left), right.left.take());
+        right.update();
+        Some(right)
+      }
+    }
+    (left, right) => left.or(right)
+  }
+}
+
+/// Inserts a node whose key is not in the subtree, where its priority
+/// puts it.
+fn insert<K: Ord, V>(mut link: &mut Link<K, V>, mut new: Box<Node<K, V>>) -> &mut V {
+  while link.as_ref().is_some_and(|node| node.priority >= new.priority) {
+    let node = link.as_mut().unwrap();
+    // The key is not in the treap, so every subtree on the way grows.
+    node.size += 1;
+    link = if new.key < node.key { &mut node.left } else { &mut node.right };
+  }
+
+  // The node becomes the root of the subtree, above all the others.
+  let (left, right) = split(link.take(), &new.key);
+  new.left = left;
+  new.right = right;
+  new.update();
+
+  &mut link.insert(new).value
+}
+
+/// Removes the key from the subtree and returns its value.
+fn remove<K, V, Q>(link: &mut Link<K, V>, key: &Q) -> Option<V>
+where
+  K: Borrow<Q>,
+  Q: Ord + ?Sized,
+{
+  let node = link.as_mut()?;
+
+  let value = match key.cmp(node.key.borrow()) {
+    Ordering::Less => remove(&mut node.left, key)?,
+    Ordering::Greater => remove(&mut node.right, key)?,
+    Ordering::Equal => {
+      let mut node = link.take().unwrap();
+      *link = merge(node.left.take(), node.right.take());
+      return Some(node.value);
+    }
+  };
+
+  node.update();
+  Some(value)
+}
+
+/// A view into a single entry of a `Treap`, which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V> {
+  /// The key is present in the treap.
+  Occupied(OccupiedEntry<'a, K, V>),
+  /// The key is absent from the treap.
+  Vacant(VacantEntry<'a, K, V>)
+}
+
+/// A view into an occupied entry of a `Treap`.
+pub struct OccupiedEntry<'a, K, V> {
+  key: &'a K,
+  value: &'a mut V
+}
+
+/// A view into a vacant entry of a `Treap`.
+pub struct VacantEntry<'a, K, V> {
+  treap: &'a mut Treap<K, V>,
+  key: K
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key()
+    }
+  }
+
+  /// Inserts `default` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    self.or_insert_with(|| default)
+  }
+
+  /// Inserts the result of `default` if the entry is vacant and returns
+  /// a mutable reference to the value.
+  pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default())
+    }
+  }
+
+  /// Inserts the default value if the entry is vacant and returns a
+  /// mutable reference to the value.
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// Calls `f` with the value if the entry is occupied, and returns the
+  /// entry so that it can be chained with `or_insert`.
+  pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      entry => entry
+    }
+  }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    self.key
+  }
+
+  /// Returns a reference to the value of the entry.
+  pub fn get(&self) -> &V {
+    self.value
+  }
+
+  /// Returns a mutable reference to the value of the entry.
+  pub fn get_mut(&mut self) -> &mut V {
+    self.value
+  }
+
+  /// Converts the entry into a mutable reference bound to the treap.
+  pub fn into_mut(self) -> &'a mut V {
+    self.value
+  }
+
+  /// Replaces the value of the entry and returns the old one.
+  pub fn insert(&mut self, value: V) -> V {
+    mem::replace(self.value, value)
+  }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+  /// Returns a reference to the key that would be inserted.
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  /// Inserts the value under the entry's key and returns a mutable
+  /// reference to it.
+  ///
+  /// The node is linked in a single pass down the treap, below the nodes
+  /// of higher priority.
+  pub fn insert(self, value: V) -> &'a mut V {
+    let treap = self.treap;
+    let priority = treap.rng.next_u64();
+
+    insert(&mut treap.root, Box::new(Node { key: self.key, value, priority, size: 1, left: None, right: None }))
+  }
+}
+
+impl<K, V> Branches for Node<K, V> {
+  type Key = K;
+
+  fn key(&self) -> &K {
+    &self.key
+  }
+
+  fn left(&self) -> Option<&Self> {
+    self.left.as_deref()
+  }
+
+  fn right(&self) -> Option<&Self> {
+    self.right.as_deref()
+  }
+}
+
+/// An in-order iterator over the entries of a `Treap`.
+pub struct Iter<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+/// An in-order iterator over the entries of a `Treap` whose keys are
+/// within a range.
+pub struct Range<'a, K, V> {
+  walk: Walk<'a, Node<K, V>>
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.walk.next().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.walk.next_back().map(|node| (&node.key, &node.value))
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a Treap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for Treap<K, V> {
+  /// Creates a treap from key-value pairs. Later pairs overwrite earlier
+  /// pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut treap = Self::new();
+    treap.extend(iter);
+    treap
+  }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for Treap<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for Treap<K, V> {
+  /// Compares the entries in order, whatever the shapes of the trees.
+  fn eq(&self, other: &Self) -> bool {
+    size(&self.root) == size(&other.root) && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for Treap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Treap<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for Treap<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map in ascending order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for Treap<K, V>
+where
+  K: serde::Deserialize<'de> + Ord,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map into a treap with random priorities. Later pairs
+  /// overwrite earlier pairs with the same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Ord,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = Treap<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut treap = Treap::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          treap.insert(key, value);
+        }
+
+        Ok(treap)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{size, Entry, Link, Treap};
+  use proptest::prelude::*;
+  use std::collections::BTreeMap;
+  use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+  /// Checks the order of the keys, the priorities and the sizes of the
+  /// subtree, and returns its height.
+  fn check<K: Ord, V>(link: &Link<K, V>, low: Option<&K>, high: Option<&K>) -> usize {
+    let Some(node) = link else {
+      return 0;
+    };
+
+    assert!(low.is_none_or(|low| *low < node.key) && high.is_none_or(|high| node.key < *high));
+    assert!([&node.left, &node.right].into_iter().flatten().all(|child| child.priority <= node.priority));
+    assert_eq!(node.size, size(&node.left) + size(&node.right) + 1);
+
+    let left = check(&node.left, low, Some(&node.key));
+    let right = check(&node.right, Some(&node.key), high);
+    left.max(right) + 1
+  }
+
+  #[test]
+  fn empty_treap() {
+    let mut treap = Treap::<i32, i32>::new();
+
+    assert!(treap.is_empty());
+    assert_eq!(treap.get(&1), None);
+    assert_eq!(treap.remove(&1), None);
+    assert_eq!(treap.first(), None);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut treap = Treap::new();
+
+    assert_eq!(treap.insert(2, "two"), None);
+    assert_eq!(treap.insert(1, "one"), None);
+    assert_eq!(treap.insert(2, "deux"), Some("two"));
+
+    assert_eq!(treap.len(), 2);
+    assert_eq!(treap.get(&2), Some(&"deux"));
+    assert!(treap.contains_key(&1));
+    assert!(!treap.contains_key(&3));
+
+    *treap.get_mut(&1).unwrap() = "un";
+    assert_eq!(treap.first(), Some((&1, &"un")));
+    assert_eq!(treap.last(), Some((&2, &"deux")));
+    assert_eq!(format!("{:?}", treap), r#"{1: "un", 2: "deux"}"#);
+  }
+
+  #[test]
+  fn sorted_insertions_stay_shallow() {
+    let treap: Treap<i32, ()> = (0..1000).map(|key| (key, ())).collect();
+
+    // A plain binary search tree would have a height of 1000.
+    assert!(check(&treap.root, None, None) < 50);
+    assert!(treap.keys().copied().eq(0..1000));
+  }
+
+  #[test]
+  fn seeded_treaps_have_the_same_shape() {
+    let shape = |seed| {
+      let mut treap = Treap::with_seed(seed);
+      (0..100).for_each(|key| {
+        treap.insert(key, ());
+      });
+      treap.root.as_ref().map(|root| root.key)
+    };
+
+    assert_eq!(shape(7), shape(7));
+  }
+
+  #[test]
+  fn split_off_and_append() {
+    let mut treap: Treap<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+
+    let high = treap.split_off(&4);
+    assert_eq!((treap.len(), high.len()), (4, 6));
+    assert!(treap.keys().copied().eq(0..4));
+    assert_eq!(high.first(), Some((&4, &40)));
+
+    let empty = treap.split_off(&10);
+    assert!(empty.is_empty());
+
+    treap.append(high);
+    treap.append(empty);
+    assert!(treap.keys().copied().eq(0..10));
+    check(&treap.root, None, None);
+  }
+
+  #[test]
+  #[should_panic(expected = "keys must be greater than the keys of the treap")]
+  fn append_overlapping() {
+    let mut treap: Treap<i32, ()> = [(1, ()), (5, ())].into_iter().collect();
+    treap.append([(3, ())].into_iter().collect());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: Treap<i32, String> = [(2, "b".to_string()), (1, "a".to_string())].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"1":"a","2":"b"}"#);
+
+    let treap: Treap<i32, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(treap, value);
+  }
+
+  #[test]
+  fn entry() {
+    let mut treap = Treap::new();
+
+    for key in (0..100).chain(0..50) {
+      *treap.entry(key).or_insert(0) += 1;
+    }
+
+    assert_eq!(treap.len(), 100);
+    assert_eq!(treap.entry(10).key(), &10);
+    assert_eq!(*treap.entry(10).or_default(), 2);
+    assert_eq!(*treap.entry(60).or_default(), 1);
+
+    match treap.entry(60) {
+      Entry::Occupied(mut entry) => assert_eq!(entry.insert(10), 1),
+      Entry::Vacant(_) => unreachable!()
+    }
+    assert_eq!(*treap.entry(60).and_modify(|value| *value += 1).or_default(), 11);
+    assert_eq!(*treap.entry(100).and_modify(|value| *value += 1).or_default(), 0);
+    assert_eq!(treap.len(), 101);
+  }
+
+  #[test]
+  fn range() {
+    let treap: Treap<i32, ()> = (0..20).step_by(2).map(|key| (key, ())).collect();
+    let keys = |range: (Bound<i32>, Bound<i32>)| treap.range(range).map(|(key, _)| *key).collect::<Vec<_>>();
+
+    assert_eq!(keys((Included(4), Excluded(10))), vec![4, 6, 8]);
+    assert_eq!(keys((Excluded(4), Included(10))), vec![6, 8, 10]);
+    assert_eq!(keys((Included(5), Included(9))), vec![6, 8]);
+    assert_eq!(keys((Unbounded, Excluded(4))), vec![0, 2]);
+    assert_eq!(keys((Included(17), Unbounded)), vec![18]);
+    assert!(keys((Excluded(6), Excluded(8))).is_empty());
+    assert!(keys((Included(9), Included(3))).is_empty());
+  }
+
+  #[test]
+  fn reverse_iteration() {
+    let treap: Treap<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+
+    assert_eq!(treap.keys().rev().copied().collect::<Vec<_>>(), (0..10).rev().collect::<Vec<_>>());
+    assert_eq!(treap.range(3..7).rev().map(|(key, _)| *key).collect::<Vec<_>>(), vec![6, 5, 4, 3]);
+    assert_eq!(treap.first_key_value(), Some((&0, &0)));
+    assert_eq!(treap.last_key_value(), Some((&9, &90)));
+
+    // Both ends stop where they meet.
+    let mut range = treap.range(3..=6);
+    assert_eq!(range.next(), Some((&3, &30)));
+    assert_eq!(range.next_back(), Some((&6, &60)));
+    assert_eq!(range.next_back(), Some((&5, &50)));
+    assert_eq!(range.next(), Some((&4, &40)));
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next_back(), None);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    SplitAppend(u8),
+    Entry(u8, i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      1 => any::<u8>().prop_map(Operation::SplitAppend),
+      2 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Entry(key, value))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_btree_map(seed in any::<u64>(), operations in prop::collection::vec(operation(), 0..200)) {
+      let mut treap = Treap::with_seed(seed);
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(treap.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(treap.remove(&key), model.remove(&key)),
+          Operation::SplitAppend(key) => {
+            let high = treap.split_off(&key);
+            prop_assert!(treap.keys().all(|&other| other < key));
+            prop_assert!(high.keys().all(|&other| other >= key));
+            prop_assert_eq!(treap.len() + high.len(), model.len());
+            treap.append(high);
+          }
+          Operation::Entry(key, value) => {
+            let expected = *model.entry(key).and_modify(|old| *old ^= value).or_insert(value);
+            prop_assert_eq!(*treap.entry(key).and_modify(|old| *old ^= value).or_insert(value), expected);
+          }
+        }
+
+        check(&treap.root, None, None);
+        prop_assert_eq!(treap.len(), model.len());
+        prop_assert!(treap.iter().eq(model.iter()));
+      }
+    }
+  }
+}
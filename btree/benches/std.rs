@@ -0,0 +1,101 @@
+//! Compares `BTreeMap` against `std::collections::BTreeMap` on inserting,
+//! removing and iterating over several sizes of maps.
+//!
+//! Run with `cargo bench -p btree --bench std`.
+
+use std::hint::black_box;
+use std::collections::BTreeMap as StdBTreeMap;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use btree::BTreeMap;
+use common::key;
+
+#[path = "../../benches/common.rs"]
+mod common;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn insert(c: &mut Criterion) {
+  let mut group = c.benchmark_group("btree/insert");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BTreeMap", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = BTreeMap::new();
+        for i in 0..size {
+          ours.insert(black_box(key(i)), black_box(key(i)));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeMap", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = StdBTreeMap::new();
+        for i in 0..size {
+          std.insert(black_box(key(i)), black_box(key(i)));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+  let mut group = c.benchmark_group("btree/remove");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BTreeMap", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(|i| (key(i), key(i))).collect::<BTreeMap<_, _>>(),
+        |mut ours| {
+          for i in 0..size {
+            black_box(ours.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeMap", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(|i| (key(i), key(i))).collect::<StdBTreeMap<_, _>>(),
+        |mut std| {
+          for i in 0..size {
+            black_box(std.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("btree/iterate");
+
+  for size in SIZES {
+    let ours: BTreeMap<_, _> = (0..size).map(|i| (key(i), key(i))).collect();
+    let std: StdBTreeMap<_, _> = (0..size).map(|i| (key(i), key(i))).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BTreeMap", size), &ours, |b, ours| {
+      b.iter(|| ours.values().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BTreeMap", size), &std, |b, std| {
+      b.iter(|| std.values().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, insert, remove, iterate);
+criterion_main!(benches);
@@ -0,0 +1,1099 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+
+/// Branching factor used by `BTreeMap::new`.
+const DEFAULT_BRANCHING_FACTOR: usize = 6;
+
+/// An ordered map based on a B-tree.
+///
+/// Every node of the tree holds a sorted array of up to `b - 1` entries
+/// and, unless it is a leaf, one more child than entries, where `b` is the
+/// branching factor of the tree. The children lie between the keys of
+/// their parent. A node that overflows on insertion is split in two around
+/// its median entry, which moves up to the parent, and a node that
+/// underflows on removal borrows an entry from a sibling or is merged with
+/// it. All leaves therefore stay at the same depth and every operation
+/// costs O(log n).
+///
+/// # Example
+///
+/// ```
+/// use btree::BTreeMap;
+///
+/// let mut map = BTreeMap::new();
+/// map.insert(3, "three");
+/// map.insert(1, "one");
+/// map.insert(2, "two");
+///
+/// assert_eq!(map.get(&2), Some(&"two"));
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// assert_eq!(map.range(2..).map(|(key, _)| *key).collect::<Vec<_>>(), vec![2, 3]);
+///
+/// assert_eq!(map.remove(&1), Some("one"));
+/// assert_eq!(map.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct BTreeMap<K, V> {
+  root: Node<K, V>,
+  len: usize,
+  /// Maximum number of children of a node.
+  branching_factor: usize
+}
+
+/// Represents a node of a B-tree.
+#[derive(Clone)]
+struct Node<K, V> {
+  keys: Vec<K>,
+  values: Vec<V>,
+  /// Children of the node, empty for a leaf. The keys of `children[i]`
+  /// lie between `keys[i - 1]` and `keys[i]`.
+  children: Vec<Node<K, V>>
+}
+
+/// Position of an entry in a subtree: the children to descend into from
+/// the root of the subtree, and the index of the entry in the node reached.
+struct Position {
+  children: Vec<usize>,
+  index: usize
+}
+
+/// Outcome of an insertion into a subtree.
+enum Insertion<K, V> {
+  /// The key was already present and its old value is returned.
+  Replaced(V),
+  /// The entry was inserted without splitting the subtree root.
+  Inserted,
+  /// The subtree root was split: the median entry and the new right
+  /// sibling have to be inserted into the parent.
+  Split(K, V, Node<K, V>)
+}
+
+/// Outcome of an insertion at a known position into a subtree.
+enum PlacedInsertion<K, V> {
+  /// The entry was inserted without splitting the subtree root.
+  Inserted(Position),
+  /// The subtree root was split: the median entry and the new right
+  /// sibling have to be inserted into the parent.
+  Split(K, V, Node<K, V>, Placement)
+}
+
+/// Where the inserted entry ended up after its subtree root was split.
+enum Placement {
+  /// In the left part of the split node, which kept its place.
+  Left(Position),
+  /// In the new right sibling.
+  Right(Position),
+  /// The inserted entry is the median, which moves up to the parent.
+  Median
+}
+
+impl Position {
+  /// Returns the position relative to the parent of the subtree, which is
+  /// the child at `child`.
+  fn under(mut self, child: usize) -> Self {
+    self.children.insert(0, child);
+    self
+  }
+}
+
+impl Placement {
+  /// Returns the position relative to the parent of the split node, which
+  /// is the child at `child` with the right sibling just after it.
+  fn under(self, child: usize) -> Position {
+    match self {
+      Placement::Left(position) => position.under(child),
+      Placement::Right(position) => position.under(child + 1),
+      Placement::Median => Position { children: Vec::new(), index: child }
+    }
+  }
+}
+
+impl<K, V> Node<K, V> {
+  fn new() -> Self {
+    Self { keys: Vec::new(), values: Vec::new(), children: Vec::new() }
+  }
+
+  fn is_leaf(&self) -> bool {
+    self.children.is_empty()
+  }
+
+  /// Returns the position of the key in the node, or the child to
+  /// descend into if the node does not contain it.
+  fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.keys.binary_search_by(|other| other.borrow().cmp(key))
+  }
+
+  /// Splits an overflowing node around its median entry, returning the
+  /// median and the new right sibling.
+  fn split(&mut self) -> (K, V, Node<K, V>) {
+    let middle = self.keys.len() / 2;
+
+    let right = Node {
+      keys: self.keys.split_off(middle + 1),
+      values: self.values.split_off(middle + 1),
+      children: if self.is_leaf() { Vec::new() } else { self.children.split_off(middle + 1) }
+    };
+
+    let key = self.keys.pop().expect("split node is empty");
+    let value = self.values.pop().expect("split node is empty");
+    (key, value, right)
+  }
+
+  /// Removes and returns the largest entry of the subtree.
+  fn remove_last(&mut self, min_keys: usize) -> (K, V) {
+    if self.is_leaf() {
+      let key = self.keys.pop().expect("subtree is empty");
+      let value = self.values.pop().expect("subtree is empty");
+      return (key, value);
+    }
+
+    let last = self.children.len() - 1;
+    let entry = self.children[last].remove_last(min_keys);
+    self.rebalance(last, min_keys);
+    entry
+  }
+
+  /// Restores the minimum number of entries of the child after a
+  /// removal, by borrowing an entry from a sibling or merging with it.
+  fn rebalance(&mut self, child: usize, min_keys: usize) {
+    if self.children[child].keys.len() >= min_keys {
+      return;
+    }
+
+    if child > 0 && self.children[child - 1].keys.len() > min_keys {
+      self.rotate_right(child - 1);
+    } else if child + 1 < self.children.len() && self.children[child + 1].keys.len() > min_keys {
+      self.rotate_left(child);
+    } else if child > 0 {
+      self.merge(child - 1);
+    } else {
+      self.merge(child);
+    }
+  }
+
+  /// Moves the last entry of `children[left]` up to the parent and the
+  /// separating entry down to the front of `children[left + 1]`.
+  fn rotate_right(&mut self, left: usize) {
+    let (head, tail) = self.children.split_at_mut(left + 1);
+    let (left_node, right_node) = (&mut head[left], &mut tail[0]);
+
+    let key = mem::replace(&mut self.keys[left], left_node.keys.pop().expect("sibling is empty"));
+    let value = mem::replace(&mut self.values[left], left_node.values.pop().expect("sibling is empty"));
+    right_node.keys.insert(0, key);
+    right_node.values.insert(0, value);
+
+    if let Some(child) = left_node.children.pop() {
+      right_node.children.insert(0, child);
+    }
+  }
+
+  /// Moves the first entry of `children[left + 1]` up to the parent and
+  /// the separating entry down to the back of `children[left]`.
+  fn rotate_left(&mut self, left: usize) {
+    let (head, tail) = self.children.split_at_mut(left + 1);
+    let (left_node, right_node) = (&mut head[left], &mut tail[0]);
+
+    let key = mem::replace(&mut self.keys[left], right_node.keys.remove(0));
+    let value = mem::replace(&mut self.values[left], right_node.values.remove(0));
+    left_node.keys.push(key);
+    left_node.values.push(value);
+
+    if !right_node.is_leaf() {
+      left_node.children.push(right_node.children.remove(0));
+    }
+  }
+
+  /// Merges `children[left + 1]` and the separating entry into `children[left]`.
+  fn merge(&mut self, left: usize) {
+    let right = self.children.remove(left + 1);
+    let key = self.keys.remove(left);
+    let value = self.values.remove(left);
+
+    let left_node = &mut self.children[left];
+    left_node.keys.push(key);
+    left_node.values.push(value);
+    left_node.keys.extend(right.keys);
+    left_node.values.extend(right.values);
+    left_node.children.extend(right.children);
+  }
+}
+
+impl<K, V> Node<K, V> {
+  /// Inserts the entry at the position, which must be in a leaf, and
+  /// splits the nodes that overflow on the way back up.
+  fn insert_at(&mut self, children: &[usize], index: usize, key: K, value: V, max_keys: usize) -> PlacedInsertion<K, V> {
+    let position = match children.split_first() {
+      None => {
+        self.keys.insert(index, key);
+        self.values.insert(index, value);
+        Position { children: Vec::new(), index }
+      }
+      Some((&child, children)) => match self.children[child].insert_at(children, index, key, value, max_keys) {
+        PlacedInsertion::Inserted(position) => return PlacedInsertion::Inserted(position.under(child)),
+        PlacedInsertion::Split(key, value, right, placement) => {
+          self.keys.insert(child, key);
+          self.values.insert(child, value);
+          self.children.insert(child + 1, right);
+          placement.under(child)
+        }
+      }
+    };
+
+    if self.keys.len() <= max_keys {
+      return PlacedInsertion::Inserted(position);
+    }
+
+    let middle = self.keys.len() / 2;
+    let (key, value, right) = self.split();
+
+    let placement = match position.children.first() {
+      None if position.index < middle => Placement::Left(position),
+      None if position.index == middle => Placement::Median,
+      None => Placement::Right(Position { children: Vec::new(), index: position.index - middle - 1 }),
+      Some(&child) if child <= middle => Placement::Left(position),
+      Some(&child) => {
+        let mut position = position;
+        position.children[0] = child - middle - 1;
+        Placement::Right(position)
+      }
+    };
+
+    PlacedInsertion::Split(key, value, right, placement)
+  }
+
+  /// Returns the key and the value of the entry at the position.
+  fn entry_mut(&mut self, position: &Position) -> (&K, &mut V) {
+    let mut node = self;
+    for &child in &position.children {
+      node = &mut node.children[child];
+    }
+
+    (&node.keys[position.index], &mut node.values[position.index])
+  }
+}
+
+impl<K: Ord, V> Node<K, V> {
+  fn insert(&mut self, key: K, value: V, max_keys: usize) -> Insertion<K, V> {
+    let index = match self.search(&key) {
+      Ok(index) => return Insertion::Replaced(mem::replace(&mut self.values[index], value)),
+      Err(index) => index
+    };
+
+    if self.is_leaf() {
+      self.keys.insert(index, key);
+      self.values.insert(index, value);
+    } else {
+      match self.children[index].insert(key, value, max_keys) {
+        Insertion::Split(key, value, right) => {
+          self.keys.insert(index, key);
+          self.values.insert(index, value);
+          self.children.insert(index + 1, right);
+        }
+        insertion => return insertion
+      }
+    }
+
+    if self.keys.len() > max_keys {
+      let (key, value, right) = self.split();
+      Insertion::Split(key, value, right)
+    } else {
+      Insertion::Inserted
+    }
+  }
+
+  fn remove<Q>(&mut self, key: &Q, min_keys: usize) -> Option<(K, V)>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    match self.search(key) {
+      Ok(index) if self.is_leaf() => Some((self.keys.remove(index), self.values.remove(index))),
+      Ok(index) => {
+        // Replace the entry with its predecessor, the largest entry of
+        // the left subtree, which always comes from a leaf.
+        let (key, value) = self.children[index].remove_last(min_keys);
+        let entry = (
+          mem::replace(&mut self.keys[index], key),
+          mem::replace(&mut self.values[index], value)
+        );
+        self.rebalance(index, min_keys);
+        Some(entry)
+      }
+      Err(_) if self.is_leaf() => None,
+      Err(index) => {
+        let entry = self.children[index].remove(key, min_keys)?;
+        self.rebalance(index, min_keys);
+        Some(entry)
+      }
+    }
+  }
+}
+
+impl<K: Ord, V> Default for BTreeMap<K, V> {
+  /// Creates a new instance of `BTreeMap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord, V> BTreeMap<K, V> {
+  /// Creates a new empty B-tree map with a branching factor of 6.
+  pub fn new() -> Self {
+    Self::with_branching_factor(DEFAULT_BRANCHING_FACTOR)
+  }
+
+  /// Creates a new empty B-tree map whose nodes have at most
+  /// `branching_factor` children.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `branching_factor` is less than 3.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use btree::BTreeMap;
+  ///
+  /// // A 2-3 tree.
+  /// let mut map = BTreeMap::with_branching_factor(3);
+  /// map.extend((0..100).map(|key| (key, key * key)));
+  ///
+  /// assert_eq!(map.get(&9), Some(&81));
+  /// ```
+  pub fn with_branching_factor(branching_factor: usize) -> Self {
+    assert!(branching_factor >= 3, "branching factor must be at least 3");
+    Self { root: Node::new(), len: 0, branching_factor }
+  }
+
+  /// Inserts a key-value pair into the map.
+  ///
+  /// If the map already contained the key, its value is replaced and
+  /// the old value is returned.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.root.insert(key, value, self.branching_factor - 1) {
+      Insertion::Replaced(value) => return Some(value),
+      Insertion::Inserted => {}
+      Insertion::Split(key, value, right) => {
+        let left = mem::replace(&mut self.root, Node::new());
+        self.root.keys.push(key);
+        self.root.values.push(value);
+        self.root.children = vec![left, right];
+      }
+    }
+
+    self.len += 1;
+    None
+  }
+
+  /// Returns the entry of the key for in-place manipulation.
+  ///
+  /// The key is searched once: an occupied entry holds the value, and a
+  /// vacant entry remembers the path to the leaf where the key belongs.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use btree::BTreeMap;
+  ///
+  /// let mut counts = BTreeMap::new();
+  ///
+  /// for word in "the cat saw the dog".split(' ') {
+  ///   counts.entry(word).and_modify(|count| *count += 1).or_insert(1);
+  /// }
+  ///
+  /// assert_eq!(counts.get("the"), Some(&2));
+  /// assert_eq!(counts.get("cat"), Some(&1));
+  /// ```
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    match self.locate(&key) {
+      Ok(position) => {
+        let (key, value) = self.root.entry_mut(&position);
+        Entry::Occupied(OccupiedEntry { key, value })
+      }
+      Err(position) => Entry::Vacant(VacantEntry { map: self, key, position })
+    }
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the map
+  /// does not contain the key.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut node = &self.root;
+
+    loop {
+      match node.search(key) {
+        Ok(index) => return Some(&node.values[index]),
+        Err(_) if node.is_leaf() => return None,
+        Err(index) => node = &node.children[index]
+      }
+    }
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if
+  /// the map does not contain the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut node = &mut self.root;
+
+    loop {
+      match node.search(key) {
+        Ok(index) => return Some(&mut node.values[index]),
+        Err(_) if node.is_leaf() => return None,
+        Err(index) => node = &mut node.children[index]
+      }
+    }
+  }
+
+  /// Checks if the map contains the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.get(key).is_some()
+  }
+
+  /// Removes the key from the map and returns its value,
+  /// or `None` if the map did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let min_keys = self.branching_factor.div_ceil(2) - 1;
+    let (_, value) = self.root.remove(key, min_keys)?;
+
+    // The root may have lost its last entry in a merge of its children.
+    if self.root.keys.is_empty() && !self.root.is_leaf() {
+      self.root = self.root.children.pop().expect("root has no child");
+    }
+
+    self.len -= 1;
+    Some(value)
+  }
+
+  /// Returns an iterator over the entries whose keys fall in the range,
+  /// in ascending order of keys.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use btree::BTreeMap;
+  ///
+  /// let map: BTreeMap<i32, ()> = (1..=10).map(|key| (key, ())).collect();
+  ///
+  /// assert_eq!(map.range(3..6).map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 4, 5]);
+  /// assert_eq!(map.range(..=2).map(|(key, _)| *key).collect::<Vec<_>>(), vec![1, 2]);
+  /// ```
+  pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    let mut iter = match range.start_bound() {
+      Bound::Included(start) => self.seek(|key| key.borrow() < start),
+      Bound::Excluded(start) => self.seek(|key| key.borrow() <= start),
+      Bound::Unbounded => self.seek(|_| false)
+    };
+    iter.end = match range.end_bound() {
+      Bound::Included(end) => self.seek(|key| key.borrow() <= end).position(),
+      Bound::Excluded(end) => self.seek(|key| key.borrow() < end).position(),
+      Bound::Unbounded => None
+    };
+
+    // An empty range may start after its end, in which case the
+    // iterator would never reach `end`.
+    if iter.peek().is_some_and(|(key, _)| !range.contains(key.borrow())) {
+      iter.stack.clear();
+    }
+
+    iter
+  }
+}
+
+impl<K, V> BTreeMap<K, V> {
+  /// Returns the number of entries in the map.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the map is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the maximum number of children of a node.
+  pub fn branching_factor(&self) -> usize {
+    self.branching_factor
+  }
+
+  /// Returns the entry with the smallest key, or `None` if the map is empty.
+  pub fn first(&self) -> Option<(&K, &V)> {
+    let mut node = &self.root;
+
+    while let Some(child) = node.children.first() {
+      node = child;
+    }
+
+    node.keys.first().zip(node.values.first())
+  }
+
+  /// Returns the entry with the largest key, or `None` if the map is empty.
+  pub fn last(&self) -> Option<(&K, &V)> {
+    let mut node = &self.root;
+
+    while let Some(child) = node.children.last() {
+      node = child;
+    }
+
+    node.keys.last().zip(node.values.last())
+  }
+
+  /// Returns an iterator over the entries of the map in ascending order of keys.
+  pub fn iter(&self) -> Range<'_, K, V> {
+    self.seek(|_| false)
+  }
+
+  /// Returns an iterator over the keys of the map in ascending order.
+  pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  /// Returns an iterator over the values of the map in ascending order of keys.
+  pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  /// Removes all entries from the map.
+  pub fn clear(&mut self) {
+    self.root = Node::new();
+    self.len = 0;
+  }
+
+  /// Returns the position of the key, or the position in a leaf where it
+  /// would be inserted if the map does not contain it.
+  fn locate<Q>(&self, key: &Q) -> Result<Position, Position>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let mut children = Vec::new();
+    let mut node = &self.root;
+
+    loop {
+      match node.search(key) {
+        Ok(index) => return Ok(Position { children, index }),
+        Err(index) if node.is_leaf() => return Err(Position { children, index }),
+        Err(index) => {
+          children.push(index);
+          node = &node.children[index];
+        }
+      }
+    }
+  }
+
+  /// Returns an unbounded iterator starting at the first entry whose key
+  /// does not satisfy `before`, which must hold for a prefix of the keys.
+  fn seek(&self, before: impl Fn(&K) -> bool) -> Range<'_, K, V> {
+    let mut stack = Vec::new();
+    let mut node = &self.root;
+
+    loop {
+      let index = node.keys.partition_point(&before);
+      stack.push((node, index));
+
+      match node.children.get(index) {
+        Some(child) => node = child,
+        None => break
+      }
+    }
+
+    Range { stack, end: None }
+  }
+}
+
+/// A view into a single entry of a `BTreeMap`, which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V> {
+  /// The key is present in the map.
+  Occupied(OccupiedEntry<'a, K, V>),
+  /// The key is absent from the map.
+  Vacant(VacantEntry<'a, K, V>)
+}
+
+/// A view into an occupied entry of a `BTreeMap`.
+pub struct OccupiedEntry<'a, K, V> {
+  key: &'a K,
+  value: &'a mut V
+}
+
+/// A view into a vacant entry of a `BTreeMap`.
+pub struct VacantEntry<'a, K, V> {
+  map: &'a mut BTreeMap<K, V>,
+  key: K,
+  /// Position in a leaf where the key belongs.
+  position: Position
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key()
+    }
+  }
+
+  /// Inserts `default` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    self.or_insert_with(|| default)
+  }
+
+  /// Inserts the result of `default` if the entry is vacant and returns
+  /// a mutable reference to the value.
+  pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default())
+    }
+  }
+
+  /// Inserts the default value if the entry is vacant and returns a
+  /// mutable reference to the value.
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// Calls `f` with the value if the entry is occupied, and returns the
+  /// entry so that it can be chained with `or_insert`.
+  pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      entry => entry
+    }
+  }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    self.key
+  }
+
+  /// Returns a reference to the value of the entry.
+  pub fn get(&self) -> &V {
+    self.value
+  }
+
+  /// Returns a mutable reference to the value of the entry.
+  pub fn get_mut(&mut self) -> &mut V {
+    self.value
+  }
+
+  /// Converts the entry into a mutable reference bound to the map.
+  pub fn into_mut(self) -> &'a mut V {
+    self.value
+  }
+
+  /// Replaces the value of the entry and returns the old one.
+  pub fn insert(&mut self, value: V) -> V {
+    mem::replace(self.value, value)
+  }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+  /// Returns a reference to the key that would be inserted.
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  /// Inserts the value under the entry's key and returns a mutable
+  /// reference to it.
+  ///
+  /// The entry is inserted at the remembered position without comparing
+  /// keys, and the nodes that overflow are split on the way back up.
+  pub fn insert(self, value: V) -> &'a mut V {
+    let map = self.map;
+    let max_keys = map.branching_factor - 1;
+    let Position { children, index } = self.position;
+
+    let position = match map.root.insert_at(&children, index, self.key, value, max_keys) {
+      PlacedInsertion::Inserted(position) => position,
+      PlacedInsertion::Split(key, value, right, placement) => {
+        let left = mem::replace(&mut map.root, Node::new());
+        map.root.keys.push(key);
+        map.root.values.push(value);
+        map.root.children = vec![left, right];
+        placement.under(0)
+      }
+    };
+    map.len += 1;
+
+    map.root.entry_mut(&position).1
+  }
+}
+
+/// An iterator over a range of entries of a `BTreeMap`, in ascending
+/// order of keys.
+pub struct Range<'a, K, V> {
+  /// Path from the root to the next entry. Every node is paired with the
+  /// position of its next entry; the subtrees left of that position have
+  /// been visited.
+  stack: Vec<(&'a Node<K, V>, usize)>,
+  /// Position of the first entry after the range, or `None` if the range
+  /// is not bounded.
+  end: Option<(*const Node<K, V>, usize)>
+}
+
+impl<'a, K, V> Range<'a, K, V> {
+  /// Drops the exhausted nodes from the top of the stack and returns the
+  /// position of the next entry.
+  fn position(&mut self) -> Option<(*const Node<K, V>, usize)> {
+    while let Some(&(node, index)) = self.stack.last() {
+      if index < node.keys.len() {
+        return Some((node as *const Node<K, V>, index));
+      }
+      self.stack.pop();
+    }
+
+    None
+  }
+
+  /// Returns the next entry without advancing the iterator.
+  fn peek(&mut self) -> Option<(&'a K, &'a V)> {
+    self.position()?;
+    let &(node, index) = self.stack.last()?;
+    Some((&node.keys[index], &node.values[index]))
+  }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let position = self.position()?;
+    if self.end == Some(position) {
+      return None;
+    }
+
+    let (node, index) = self.stack.last_mut()?;
+    let node: &'a Node<K, V> = node;
+    let entry = (&node.keys[*index], &node.values[*index]);
+    *index += 1;
+
+    // Continue with the leftmost entry of the subtree right of the entry.
+    let mut child = node.children.get(*index);
+    while let Some(next) = child {
+      self.stack.push((next, 0));
+      child = next.children.first();
+    }
+
+    Some(entry)
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a BTreeMap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Range<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BTreeMap<K, V> {
+  /// Creates a B-tree map from key-value pairs. Later pairs overwrite
+  /// earlier pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut map = Self::new();
+    map.extend(iter);
+    map
+  }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for BTreeMap<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for BTreeMap<K, V> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for BTreeMap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for BTreeMap<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for BTreeMap<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map in ascending order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for BTreeMap<K, V>
+where
+  K: serde::Deserialize<'de> + Ord,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map into a B-tree map with the default branching
+  /// factor. Later pairs overwrite earlier pairs with the same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Ord,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = BTreeMap<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = BTreeMap::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          map.insert(key, value);
+        }
+
+        Ok(map)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{BTreeMap, Entry, Node};
+  use std::ops::Bound::{self, Excluded, Included, Unbounded};
+  use proptest::prelude::*;
+
+  /// Checks the B-tree invariants of the subtree and returns its height.
+  fn check_node<K: Ord, V>(node: &Node<K, V>, map: &BTreeMap<K, V>, is_root: bool) -> usize {
+    let min_keys = map.branching_factor.div_ceil(2) - 1;
+
+    assert!(node.keys.len() < map.branching_factor, "node overflows");
+    assert!(is_root || node.keys.len() >= min_keys, "node underflows");
+    assert_eq!(node.keys.len(), node.values.len());
+    assert!(node.keys.windows(2).all(|pair| pair[0] < pair[1]), "keys are not sorted");
+
+    if node.is_leaf() {
+      return 1;
+    }
+
+    assert_eq!(node.children.len(), node.keys.len() + 1);
+    let heights: Vec<_> = node.children.iter().map(|child| check_node(child, map, false)).collect();
+    assert!(heights.windows(2).all(|pair| pair[0] == pair[1]), "leaves are not at the same depth");
+
+    heights[0] + 1
+  }
+
+  fn check<K: Ord, V>(map: &BTreeMap<K, V>) {
+    check_node(&map.root, map, true);
+    assert_eq!(map.iter().count(), map.len());
+  }
+
+  #[test]
+  fn empty_map() {
+    let map = BTreeMap::<i32, i32>::new();
+
+    assert!(map.is_empty());
+    assert_eq!(map.get(&1), None);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last(), None);
+    assert_eq!(map.iter().next(), None);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut map = BTreeMap::new();
+
+    assert_eq!(map.insert(2, "two"), None);
+    assert_eq!(map.insert(1, "one"), None);
+    assert_eq!(map.insert(2, "deux"), Some("two"));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&2), Some(&"deux"));
+    assert!(map.contains_key(&1));
+    assert!(!map.contains_key(&3));
+
+    *map.get_mut(&1).unwrap() = "un";
+    assert_eq!(map.first(), Some((&1, &"un")));
+    assert_eq!(map.last(), Some((&2, &"deux")));
+  }
+
+  #[test]
+  fn entry() {
+    let mut map = BTreeMap::with_branching_factor(3);
+
+    for key in (0..100).chain(0..50) {
+      *map.entry(key).or_insert(0) += 1;
+      check(&map);
+    }
+
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.get(&10), Some(&2));
+    assert_eq!(map.get(&60), Some(&1));
+
+    match map.entry(60) {
+      Entry::Occupied(mut entry) => assert_eq!(entry.insert(10), 1),
+      Entry::Vacant(_) => unreachable!()
+    }
+    assert_eq!(map.entry(100).key(), &100);
+    assert_eq!(*map.entry(60).and_modify(|value| *value += 1).or_default(), 11);
+    assert_eq!(*map.entry(100).and_modify(|value| *value += 1).or_default(), 0);
+  }
+
+  #[test]
+  fn splits_keep_the_tree_balanced() {
+    for branching_factor in 3..8 {
+      let mut map = BTreeMap::with_branching_factor(branching_factor);
+
+      for key in 0..500 {
+        map.insert(key, ());
+        check(&map);
+      }
+
+      assert!(map.keys().copied().eq(0..500));
+    }
+  }
+
+  #[test]
+  fn removals_keep_the_tree_balanced() {
+    for branching_factor in 3..8 {
+      let mut map: BTreeMap<i32, i32> = BTreeMap::with_branching_factor(branching_factor);
+      map.extend((0..300).map(|key| (key, -key)));
+
+      for key in (0..300).step_by(3).chain((1..300).step_by(3)) {
+        assert_eq!(map.remove(&key), Some(-key));
+        check(&map);
+      }
+
+      assert_eq!(map.remove(&0), None);
+      assert!(map.keys().copied().eq((2..300).step_by(3)));
+    }
+  }
+
+  #[test]
+  fn range() {
+    let map: BTreeMap<i32, ()> = (0..100).step_by(2).map(|key| (key, ())).collect();
+    let keys = |range: (Bound<i32>, Bound<i32>)| map.range(range).map(|(key, _)| *key).collect::<Vec<_>>();
+
+    assert_eq!(keys((Included(4), Excluded(10))), vec![4, 6, 8]);
+    assert_eq!(keys((Excluded(4), Included(10))), vec![6, 8, 10]);
+    assert_eq!(keys((Included(91), Unbounded)), vec![92, 94, 96, 98]);
+    assert_eq!(keys((Unbounded, Excluded(3))), vec![0, 2]);
+    assert_eq!(keys((Included(10), Excluded(5))), Vec::<i32>::new());
+    assert_eq!(keys((Included(200), Unbounded)), Vec::<i32>::new());
+  }
+
+  #[test]
+  #[should_panic(expected = "branching factor must be at least 3")]
+  fn invalid_branching_factor() {
+    BTreeMap::<i32, i32>::with_branching_factor(2);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: BTreeMap<String, i32> = [("b".to_string(), 2), ("a".to_string(), 1)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"a":1,"b":2}"#);
+
+    let map: BTreeMap<String, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(map, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Get(u8),
+    Range(u8, u8),
+    Entry(u8, i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      1 => any::<u8>().prop_map(Operation::Get),
+      1 => (any::<u8>(), any::<u8>()).prop_map(|(start, end)| Operation::Range(start, end)),
+      2 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Entry(key, value))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_std(branching_factor in 3..9usize, operations in prop::collection::vec(operation(), 0..400)) {
+      let mut map = BTreeMap::with_branching_factor(branching_factor);
+      let mut model = std::collections::BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(map.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(map.remove(&key), model.remove(&key)),
+          Operation::Get(key) => prop_assert_eq!(map.get(&key), model.get(&key)),
+          Operation::Range(start, end) => {
+            let start = start.min(end);
+            prop_assert!(map.range(start..end).eq(model.range(start..end)));
+          }
+          Operation::Entry(key, value) => {
+            let expected = *model.entry(key).and_modify(|old| *old ^= value).or_insert(value);
+            prop_assert_eq!(*map.entry(key).and_modify(|old| *old ^= value).or_insert(value), expected);
+          }
+        }
+
+        prop_assert_eq!(map.len(), model.len());
+      }
+
+      check(&map);
+      prop_assert!(map.iter().eq(model.iter()));
+      prop_assert_eq!(map.first(), model.first_key_value());
+      prop_assert_eq!(map.last(), model.last_key_value());
+    }
+  }
+}
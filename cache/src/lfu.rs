@@ -0,0 +1,442 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::Rev;
+
+use hashmap::HashMap;
+
+use crate::list::{self, List};
+
+
+/// A cache that evicts the least frequently used entry when it is full.
+///
+/// Every entry counts the number of times it was used. The entries with
+/// the same count are kept in a bucket ordered by recency, and the buckets
+/// are kept in a doubly linked list ordered by count, so using an entry
+/// only moves it to the next bucket. A full cache evicts the least
+/// recently used entry of the first bucket, which makes `get` and `put`
+/// O(1).
+///
+/// # Example
+///
+/// ```
+/// use cache::LfuCache;
+///
+/// let mut cache = LfuCache::new(2);
+/// cache.put("a", 1);
+/// cache.put("b", 2);
+///
+/// cache.get("a");
+/// cache.get("a");
+/// cache.get("b");
+///
+/// // "b" was used less often than "a".
+/// cache.put("c", 3);
+/// assert_eq!(cache.get("b"), None);
+/// assert_eq!(cache.frequency("a"), Some(3));
+/// ```
+pub struct LfuCache<K, V> {
+  /// Maps every key to the index of its bucket in `buckets` and the index
+  /// of its entry in the bucket.
+  map: HashMap<K, (usize, usize)>,
+  /// Buckets from the lowest to the highest frequency, none of them empty.
+  buckets: List<Bucket<K, V>>,
+  capacity: usize,
+  on_evict: Option<Box<dyn FnMut(K, V)>>
+}
+
+/// The entries used the same number of times.
+struct Bucket<K, V> {
+  frequency: usize,
+  /// Entries from the most to the least recently used.
+  entries: List<(K, V)>
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+  /// Creates a new empty cache holding at most `capacity` entries.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the capacity is zero.
+  pub fn new(capacity: usize) -> Self {
+    assert!(capacity > 0, "capacity must be positive");
+
+    Self {
+      map: HashMap::new(),
+      buckets: List::new(),
+      capacity,
+      on_evict: None
+    }
+  }
+
+  /// Creates a new empty cache holding at most `capacity` entries, which
+  /// passes every evicted entry to `callback`.
+  ///
+  /// Entries removed with `remove`, `pop_lfu` or `clear` are returned to
+  /// the caller instead and are not passed to the callback.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the capacity is zero.
+  pub fn with_eviction_callback<F: FnMut(K, V) + 'static>(capacity: usize, callback: F) -> Self {
+    let mut cache = Self::new(capacity);
+    cache.on_evict = Some(Box::new(callback));
+    cache
+  }
+
+  /// Returns the number of entries in the cache.
+  pub fn len(&self) -> usize {
+    self.map.len()
+  }
+
+  /// Checks if the cache is empty.
+  pub fn is_empty(&self) -> bool {
+    self.map.is_empty()
+  }
+
+  /// Returns the maximum number of entries in the cache.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Inserts a key-value pair and counts it as a use of the key.
+  ///
+  /// If the key was present, its value is replaced and the old value is
+  /// returned. Otherwise, if the cache is full, the least frequently used
+  /// entry is evicted to make room, the least recently used one among
+  /// the entries used the same number of times.
+  pub fn put(&mut self, key: K, value: V) -> Option<V> {
+    if let Some(&position) = self.map.get(&key) {
+      let (bucket, index) = self.touch(position);
+      return Some(std::mem::replace(&mut self.buckets.get_mut(bucket).entries.get_mut(index).1, value));
+    }
+
+    if self.len() == self.capacity {
+      if let Some((key, value)) = self.pop_lfu() {
+        if let Some(on_evict) = self.on_evict.as_mut() {
+          on_evict(key, value);
+        }
+      }
+    }
+
+    let bucket = match self.buckets.front() {
+      Some(bucket) if self.buckets.get(bucket).frequency == 1 => bucket,
+      _ => self.buckets.push_front(Bucket { frequency: 1, entries: List::new() })
+    };
+    let index = self.buckets.get_mut(bucket).entries.push_front((key.clone(), value));
+    self.map.insert(key, (bucket, index));
+    None
+  }
+
+  /// Returns a reference to the value corresponding to the key and counts
+  /// it as a use of the key.
+  pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let position = *self.map.get(key)?;
+    let (bucket, index) = self.touch(position);
+    Some(&self.buckets.get(bucket).entries.get(index).1)
+  }
+
+  /// Returns a mutable reference to the value corresponding to the key and
+  /// counts it as a use of the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let position = *self.map.get(key)?;
+    let (bucket, index) = self.touch(position);
+    Some(&mut self.buckets.get_mut(bucket).entries.get_mut(index).1)
+  }
+
+  /// Returns a reference to the value corresponding to the key without
+  /// counting it as a use.
+  pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let &(bucket, index) = self.map.get(key)?;
+    Some(&self.buckets.get(bucket).entries.get(index).1)
+  }
+
+  /// Returns the number of times the key was used since it was inserted,
+  /// or `None` if the key is not present.
+  pub fn frequency<Q>(&self, key: &Q) -> Option<usize>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let &(bucket, _) = self.map.get(key)?;
+    Some(self.buckets.get(bucket).frequency)
+  }
+
+  /// Checks if the cache contains the key, without counting it as a use.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(key)
+  }
+
+  /// Removes a key from the cache and returns its value,
+  /// or `None` if the key was not present.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let (bucket, index) = self.map.remove(key)?;
+    Some(self.take(bucket, index).1)
+  }
+
+  /// Removes the entry that would be evicted next and returns it,
+  /// or `None` if the cache is empty.
+  pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+    let bucket = self.buckets.front()?;
+    let index = self.buckets.get(bucket).entries.back().expect("bucket is empty");
+    let (key, value) = self.take(bucket, index);
+    self.map.remove(&key);
+    Some((key, value))
+  }
+
+  /// Removes all entries from the cache.
+  pub fn clear(&mut self) {
+    self.map.clear();
+    self.buckets.clear();
+  }
+
+  /// Moves an entry to the bucket of the next frequency and returns its
+  /// new position.
+  fn touch(&mut self, (bucket, index): (usize, usize)) -> (usize, usize) {
+    let frequency = self.buckets.get(bucket).frequency + 1;
+    let next = match self.buckets.next(bucket) {
+      Some(next) if self.buckets.get(next).frequency == frequency => next,
+      _ => self.buckets.insert_after(bucket, Bucket { frequency, entries: List::new() })
+    };
+
+    let entry = self.take(bucket, index);
+    let index = self.buckets.get_mut(next).entries.push_front(entry);
+    let key = &self.buckets.get(next).entries.get(index).0;
+    *self.map.get_mut(key).expect("cached key is missing") = (next, index);
+    (next, index)
+  }
+
+  /// Unlinks an entry from its bucket, removing the bucket if it becomes
+  /// empty.
+  fn take(&mut self, bucket: usize, index: usize) -> (K, V) {
+    let entries = &mut self.buckets.get_mut(bucket).entries;
+    let entry = entries.remove(index);
+
+    if entries.is_empty() {
+      self.buckets.remove(bucket);
+    }
+
+    entry
+  }
+}
+
+impl<K, V> LfuCache<K, V> {
+  /// Returns an iterator over the entries, from the most to the least
+  /// frequently used, the most recently used first among the entries used
+  /// the same number of times. Iterating does not count as a use.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter {
+      buckets: self.buckets.iter().rev(),
+      entries: None
+    }
+  }
+}
+
+/// An iterator over the entries of an `LfuCache`, from the most to the
+/// least frequently used.
+pub struct Iter<'a, K, V> {
+  buckets: Rev<list::Iter<'a, Bucket<K, V>>>,
+  entries: Option<list::Iter<'a, (K, V)>>
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((key, value)) = self.entries.as_mut().and_then(Iterator::next) {
+        return Some((key, value));
+      }
+
+      self.entries = Some(self.buckets.next()?.entries.iter());
+    }
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a LfuCache<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LfuCache<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::LfuCache;
+  use std::cell::RefCell;
+  use std::rc::Rc;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_cache() {
+    let mut cache = LfuCache::<i32, i32>::new(2);
+
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.frequency(&1), None);
+    assert_eq!(cache.pop_lfu(), None);
+  }
+
+  #[test]
+  fn evicts_least_frequently_used() {
+    let mut cache = LfuCache::new(3);
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    cache.get(&1);
+    cache.get(&3);
+    cache.get(&1);
+    cache.put(4, "four");
+
+    assert!(!cache.contains_key(&2));
+    assert_eq!(cache.frequency(&1), Some(3));
+    assert_eq!(cache.iter().map(|(key, _)| *key).collect::<Vec<_>>(), [1, 3, 4]);
+  }
+
+  #[test]
+  fn ties_evict_least_recently_used() {
+    let mut cache = LfuCache::new(2);
+    cache.put(1, 10);
+    cache.put(2, 20);
+    cache.get(&2);
+    cache.get(&1);
+    cache.put(3, 30);
+
+    assert_eq!(cache.peek(&2), None);
+    assert_eq!(cache.peek(&1), Some(&10));
+  }
+
+  #[test]
+  fn put_counts_as_use() {
+    let mut cache = LfuCache::new(2);
+    cache.put(1, 10);
+    cache.put(2, 20);
+
+    assert_eq!(cache.put(1, 11), Some(10));
+    cache.put(3, 30);
+    assert_eq!(cache.peek(&1), Some(&11));
+    assert_eq!(cache.peek(&2), None);
+  }
+
+  #[test]
+  fn remove() {
+    let mut cache = LfuCache::new(2);
+    cache.put("a".to_string(), 1);
+    cache.put("b".to_string(), 2);
+    *cache.get_mut("b").unwrap() += 1;
+
+    assert_eq!(cache.remove("b"), Some(3));
+    assert_eq!(cache.remove("b"), None);
+    assert_eq!(cache.pop_lfu(), Some(("a".to_string(), 1)));
+    assert!(cache.is_empty());
+  }
+
+  #[test]
+  fn eviction_callback() {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let mut cache = LfuCache::with_eviction_callback(2, {
+      let evicted = Rc::clone(&evicted);
+      move |key, value| evicted.borrow_mut().push((key, value))
+    });
+
+    cache.put(0, 0);
+    cache.get(&0);
+    for i in 1..4 {
+      cache.put(i, i * 10);
+    }
+
+    assert_eq!(*evicted.borrow(), [(1, 10), (2, 20)]);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Put(u8, i32),
+    Get(u8),
+    Remove(u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      (0..16u8, any::<i32>()).prop_map(|(key, value)| Operation::Put(key, value)),
+      (0..16u8).prop_map(Operation::Get),
+      (0..16u8).prop_map(Operation::Remove),
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_counted_entries(capacity in 1..8usize, operations in prop::collection::vec(operation(), 0..200)) {
+      let mut cache = LfuCache::new(capacity);
+      // Entries as (key, value, frequency, last use).
+      let mut model: Vec<(u8, i32, usize, usize)> = Vec::new();
+
+      for (time, operation) in operations.into_iter().enumerate() {
+        match operation {
+          Operation::Put(key, value) => {
+            let old = match model.iter_mut().find(|entry| entry.0 == key) {
+              Some(entry) => {
+                entry.2 += 1;
+                entry.3 = time;
+                Some(std::mem::replace(&mut entry.1, value))
+              }
+              None => {
+                if model.len() == capacity {
+                  let index = (0..model.len()).min_by_key(|&index| (model[index].2, model[index].3)).unwrap();
+                  model.remove(index);
+                }
+                model.push((key, value, 1, time));
+                None
+              }
+            };
+            prop_assert_eq!(cache.put(key, value), old);
+          }
+          Operation::Get(key) => {
+            let found = model.iter_mut().find(|entry| entry.0 == key).map(|entry| {
+              entry.2 += 1;
+              entry.3 = time;
+              entry.1
+            });
+            prop_assert_eq!(cache.get(&key).copied(), found);
+          }
+          Operation::Remove(key) => {
+            let removed = model.iter().position(|entry| entry.0 == key).map(|index| model.remove(index).1);
+            prop_assert_eq!(cache.remove(&key), removed);
+          }
+        }
+
+        model.sort_by_key(|entry| std::cmp::Reverse((entry.2, entry.3)));
+        let expected: Vec<_> = model.iter().map(|entry| (entry.0, entry.1)).collect();
+        prop_assert_eq!(cache.iter().map(|(key, value)| (*key, *value)).collect::<Vec<_>>(), expected);
+      }
+    }
+  }
+}
@@ -0,0 +1,13 @@
+//! Fixed-capacity caches with O(1) operations.
+//!
+//! A cache keeps at most `capacity` entries and evicts one whenever a new
+//! entry does not fit, the caches differ in which entry they choose to
+//! evict. Every cache can be given a callback that receives the evicted
+//! entries, for example to write them back to a slower storage.
+
+pub mod lfu;
+mod list;
+pub mod lru;
+
+pub use lfu::LfuCache;
+pub use lru::LruCache;
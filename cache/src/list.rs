@@ -0,0 +1,201 @@
+use slab::Slab;
+
+
+/// A doubly linked list whose nodes are addressed by stable indices.
+///
+/// Unlike `DoublyLinkedList`, a node can be unlinked, moved to the front or
+/// followed by a new node in O(1) given its index, which is what the caches
+/// keep in their maps.
+/// The nodes are stored in a `Slab`, which reuses the slot of a removed
+/// node for the next insertion.
+pub(crate) struct List<T> {
+  nodes: Slab<Node<T>>,
+  head: Option<usize>,
+  tail: Option<usize>
+}
+
+struct Node<T> {
+  value: T,
+  prev: Option<usize>,
+  next: Option<usize>
+}
+
+impl<T> List<T> {
+  pub(crate) fn new() -> Self {
+    Self {
+      nodes: Slab::new(),
+      head: None,
+      tail: None
+    }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub(crate) fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// Returns the index of the first node.
+  pub(crate) fn front(&self) -> Option<usize> {
+    self.head
+  }
+
+  /// Returns the index of the last node.
+  pub(crate) fn back(&self) -> Option<usize> {
+    self.tail
+  }
+
+  /// Returns the index of the node following the node at the index.
+  pub(crate) fn next(&self, index: usize) -> Option<usize> {
+    self.node(index).next
+  }
+
+  pub(crate) fn get(&self, index: usize) -> &T {
+    &self.node(index).value
+  }
+
+  pub(crate) fn get_mut(&mut self, index: usize) -> &mut T {
+    &mut self.node_mut(index).value
+  }
+
+  /// Adds a value to the front of the list and returns the index of its
+  /// node.
+  pub(crate) fn push_front(&mut self, value: T) -> usize {
+    let index = self.allocate(value);
+    self.link_front(index);
+    index
+  }
+
+  /// Inserts a value right after the node at `after` and returns the index
+  /// of its node.
+  pub(crate) fn insert_after(&mut self, after: usize, value: T) -> usize {
+    let index = self.allocate(value);
+    let next = self.node(after).next;
+
+    let node = self.node_mut(index);
+    node.prev = Some(after);
+    node.next = next;
+
+    self.node_mut(after).next = Some(index);
+    match next {
+      Some(next) => self.node_mut(next).prev = Some(index),
+      None => self.tail = Some(index)
+    }
+
+    index
+  }
+
+  /// Unlinks the node at the index and returns its value.
+  pub(crate) fn remove(&mut self, index: usize) -> T {
+    self.unlink(index);
+    self.nodes.remove(index).expect("linked node is missing").value
+  }
+
+  /// Moves the node at the index to the front of the list.
+  pub(crate) fn move_to_front(&mut self, index: usize) {
+    if self.head != Some(index) {
+      self.unlink(index);
+      self.link_front(index);
+    }
+  }
+
+  pub(crate) fn clear(&mut self) {
+    self.nodes.clear();
+    self.head = None;
+    self.tail = None;
+  }
+
+  /// Returns an iterator from the front to the back of the list.
+  pub(crate) fn iter(&self) -> Iter<'_, T> {
+    Iter {
+      list: self,
+      front: self.head,
+      back: self.tail,
+      len: self.len()
+    }
+  }
+
+  /// Stores an unlinked node and returns its index.
+  fn allocate(&mut self, value: T) -> usize {
+    self.nodes.insert(Node { value, prev: None, next: None })
+  }
+
+  fn link_front(&mut self, index: usize) {
+    let head = self.head;
+    let node = self.node_mut(index);
+    node.prev = None;
+    node.next = head;
+
+    match head {
+      Some(head) => self.node_mut(head).prev = Some(index),
+      None => self.tail = Some(index)
+    }
+    self.head = Some(index);
+  }
+
+  fn unlink(&mut self, index: usize) {
+    let node = self.node(index);
+    let (prev, next) = (node.prev, node.next);
+
+    match prev {
+      Some(prev) => self.node_mut(prev).next = next,
+      None => self.head = next
+    }
+    match next {
+      Some(next) => self.node_mut(next).prev = prev,
+      None => self.tail = prev
+    }
+  }
+
+  fn node(&self, index: usize) -> &Node<T> {
+    self.nodes.get(index).expect("linked node is missing")
+  }
+
+  fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+    self.nodes.get_mut(index).expect("linked node is missing")
+  }
+}
+
+/// An iterator over the values of a `List`.
+pub(crate) struct Iter<'a, T> {
+  list: &'a List<T>,
+  front: Option<usize>,
+  back: Option<usize>,
+  len: usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    let node = self.list.node(self.front?);
+    self.front = node.next;
+    self.len -= 1;
+    Some(&node.value)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.len, Some(self.len))
+  }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    let node = self.list.node(self.back?);
+    self.back = node.prev;
+    self.len -= 1;
+    Some(&node.value)
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
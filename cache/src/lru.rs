@@ -0,0 +1,373 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+
+use hashmap::HashMap;
+
+use crate::list::{self, List};
+
+
+/// A cache that evicts the least recently used entry when it is full.
+///
+/// The entries are kept in a doubly linked list ordered by recency, and a
+/// `HashMap` maps every key to its node in the list. Looking up an entry
+/// moves its node to the front of the list and inserting a new entry into
+/// a full cache evicts the node at the back, so `get` and `put` are O(1).
+///
+/// # Example
+///
+/// ```
+/// use cache::LruCache;
+///
+/// let mut cache = LruCache::new(2);
+/// cache.put("a", 1);
+/// cache.put("b", 2);
+///
+/// assert_eq!(cache.get("a"), Some(&1));
+///
+/// // "b" is now the least recently used entry.
+/// cache.put("c", 3);
+/// assert_eq!(cache.get("b"), None);
+///
+/// let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+/// assert_eq!(keys, ["c", "a"]);
+/// ```
+pub struct LruCache<K, V> {
+  /// Maps every key to the index of its node in `entries`.
+  map: HashMap<K, usize>,
+  /// Entries from the most to the least recently used.
+  entries: List<(K, V)>,
+  capacity: usize,
+  on_evict: Option<Box<dyn FnMut(K, V)>>
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+  /// Creates a new empty cache holding at most `capacity` entries.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the capacity is zero.
+  pub fn new(capacity: usize) -> Self {
+    assert!(capacity > 0, "capacity must be positive");
+
+    Self {
+      map: HashMap::new(),
+      entries: List::new(),
+      capacity,
+      on_evict: None
+    }
+  }
+
+  /// Creates a new empty cache holding at most `capacity` entries, which
+  /// passes every evicted entry to `callback`.
+  ///
+  /// Entries removed with `remove`, `pop_lru` or `clear` are returned to
+  /// the caller instead and are not passed to the callback.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the capacity is zero.
+  pub fn with_eviction_callback<F: FnMut(K, V) + 'static>(capacity: usize, callback: F) -> Self {
+    let mut cache = Self::new(capacity);
+    cache.on_evict = Some(Box::new(callback));
+    cache
+  }
+
+  /// Returns the number of entries in the cache.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Checks if the cache is empty.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Returns the maximum number of entries in the cache.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Inserts a key-value pair and marks it as the most recently used.
+  ///
+  /// If the key was present, its value is replaced and the old value is
+  /// returned. Otherwise, if the cache is full, the least recently used
+  /// entry is evicted to make room.
+  pub fn put(&mut self, key: K, value: V) -> Option<V> {
+    if let Some(&index) = self.map.get(&key) {
+      self.entries.move_to_front(index);
+      return Some(std::mem::replace(&mut self.entries.get_mut(index).1, value));
+    }
+
+    if self.len() == self.capacity {
+      if let Some((key, value)) = self.pop_lru() {
+        if let Some(on_evict) = self.on_evict.as_mut() {
+          on_evict(key, value);
+        }
+      }
+    }
+
+    let index = self.entries.push_front((key.clone(), value));
+    self.map.insert(key, index);
+    None
+  }
+
+  /// Returns a reference to the value corresponding to the key and marks
+  /// it as the most recently used.
+  pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let index = *self.map.get(key)?;
+    self.entries.move_to_front(index);
+    Some(&self.entries.get(index).1)
+  }
+
+  /// Returns a mutable reference to the value corresponding to the key and
+  /// marks it as the most recently used.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let index = *self.map.get(key)?;
+    self.entries.move_to_front(index);
+    Some(&mut self.entries.get_mut(index).1)
+  }
+
+  /// Returns a reference to the value corresponding to the key without
+  /// changing its recency.
+  pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let index = *self.map.get(key)?;
+    Some(&self.entries.get(index).1)
+  }
+
+  /// Checks if the cache contains the key, without changing its recency.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(key)
+  }
+
+  /// Removes a key from the cache and returns its value,
+  /// or `None` if the key was not present.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let index = self.map.remove(key)?;
+    Some(self.entries.remove(index).1)
+  }
+
+  /// Removes the least recently used entry and returns it,
+  /// or `None` if the cache is empty.
+  pub fn pop_lru(&mut self) -> Option<(K, V)> {
+    let index = self.entries.back()?;
+    let (key, value) = self.entries.remove(index);
+    self.map.remove(&key);
+    Some((key, value))
+  }
+
+  /// Removes all entries from the cache.
+  pub fn clear(&mut self) {
+    self.map.clear();
+    self.entries.clear();
+  }
+}
+
+impl<K, V> LruCache<K, V> {
+  /// Returns an iterator over the entries, from the most to the least
+  /// recently used, without changing their recency.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { entries: self.entries.iter() }
+  }
+}
+
+/// An iterator over the entries of an `LruCache`, from the most to the
+/// least recently used.
+pub struct Iter<'a, K, V> {
+  entries: list::Iter<'a, (K, V)>
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.entries.next().map(|(key, value)| (key, value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.entries.size_hint()
+  }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.entries.next_back().map(|(key, value)| (key, value))
+  }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<'a, K, V> IntoIterator for &'a LruCache<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LruCache<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::LruCache;
+  use std::cell::RefCell;
+  use std::rc::Rc;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_cache() {
+    let mut cache = LruCache::<i32, i32>::new(2);
+
+    assert!(cache.is_empty());
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.pop_lru(), None);
+  }
+
+  #[test]
+  fn evicts_least_recently_used() {
+    let mut cache = LruCache::new(3);
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    cache.get(&1);
+    cache.put(4, "four");
+
+    assert_eq!(cache.len(), 3);
+    assert!(!cache.contains_key(&2));
+    assert_eq!(cache.iter().map(|(key, _)| *key).collect::<Vec<_>>(), [4, 1, 3]);
+  }
+
+  #[test]
+  fn put_replaces_value() {
+    let mut cache = LruCache::new(2);
+    cache.put(1, 10);
+    cache.put(2, 20);
+
+    assert_eq!(cache.put(1, 11), Some(10));
+    cache.put(3, 30);
+    assert_eq!(cache.peek(&1), Some(&11));
+    assert_eq!(cache.peek(&2), None);
+  }
+
+  #[test]
+  fn peek_keeps_recency() {
+    let mut cache = LruCache::new(2);
+    cache.put(1, 10);
+    cache.put(2, 20);
+    cache.peek(&1);
+    cache.put(3, 30);
+
+    assert!(!cache.contains_key(&1));
+  }
+
+  #[test]
+  fn remove() {
+    let mut cache = LruCache::new(2);
+    cache.put("a".to_string(), 1);
+    cache.put("b".to_string(), 2);
+    *cache.get_mut("b").unwrap() += 1;
+
+    assert_eq!(cache.remove("b"), Some(3));
+    assert_eq!(cache.remove("b"), None);
+    assert_eq!(cache.pop_lru(), Some(("a".to_string(), 1)));
+  }
+
+  #[test]
+  fn eviction_callback() {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let mut cache = LruCache::with_eviction_callback(2, {
+      let evicted = Rc::clone(&evicted);
+      move |key, value| evicted.borrow_mut().push((key, value))
+    });
+
+    for i in 0..5 {
+      cache.put(i, i * 10);
+    }
+    cache.remove(&3);
+
+    assert_eq!(*evicted.borrow(), [(0, 0), (1, 10), (2, 20)]);
+  }
+
+  #[test]
+  #[should_panic(expected = "capacity must be positive")]
+  fn zero_capacity() {
+    LruCache::<i32, i32>::new(0);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Put(u8, i32),
+    Get(u8),
+    Remove(u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      (0..16u8, any::<i32>()).prop_map(|(key, value)| Operation::Put(key, value)),
+      (0..16u8).prop_map(Operation::Get),
+      (0..16u8).prop_map(Operation::Remove),
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_recency_list(capacity in 1..8usize, operations in prop::collection::vec(operation(), 0..200)) {
+      let mut cache = LruCache::new(capacity);
+      // Entries from the most to the least recently used.
+      let mut model: Vec<(u8, i32)> = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Put(key, value) => {
+            let old = model.iter().position(|entry| entry.0 == key).map(|index| model.remove(index).1);
+            if old.is_none() && model.len() == capacity {
+              model.pop();
+            }
+            model.insert(0, (key, value));
+            prop_assert_eq!(cache.put(key, value), old);
+          }
+          Operation::Get(key) => {
+            let found = model.iter().position(|entry| entry.0 == key).map(|index| model.remove(index));
+            if let Some(entry) = found {
+              model.insert(0, entry);
+            }
+            prop_assert_eq!(cache.get(&key).copied(), found.map(|entry| entry.1));
+          }
+          Operation::Remove(key) => {
+            let removed = model.iter().position(|entry| entry.0 == key).map(|index| model.remove(index).1);
+            prop_assert_eq!(cache.remove(&key), removed);
+          }
+        }
+
+        prop_assert_eq!(cache.iter().map(|(key, value)| (*key, *value)).collect::<Vec<_>>(), model.clone());
+      }
+    }
+  }
+}
@@ -0,0 +1,244 @@
+use heap::{BinaryHeap, DAryHeap, Order, PairingHeap};
+
+
+/// A priority queue: values are pushed in any order and popped by
+/// priority, the highest first.
+///
+/// # Example
+///
+/// ```
+/// use collections_traits::Heap;
+/// use heap::{Min, MinHeap, PairingHeap};
+///
+/// /// Merges sorted runs into one sorted vector with any heap.
+/// fn merge_runs<H: Heap<i32> + Default>(runs: &[&[i32]]) -> Vec<i32> {
+///   let mut heap = H::default();
+///   for run in runs {
+///     let mut other = H::default();
+///     run.iter().for_each(|&value| other.push(value));
+///     heap.merge(other);
+///   }
+///   std::iter::from_fn(|| heap.pop()).collect()
+/// }
+///
+/// let runs: [&[i32]; 2] = [&[1, 4, 7], &[2, 3, 9]];
+/// assert_eq!(merge_runs::<MinHeap<i32>>(&runs), [1, 2, 3, 4, 7, 9]);
+/// assert_eq!(merge_runs::<PairingHeap<i32, Min>>(&runs), [1, 2, 3, 4, 7, 9]);
+/// ```
+pub trait Heap<T> {
+  /// Pushes a value onto the heap.
+  fn push(&mut self, value: T);
+
+  /// Removes the value with the highest priority and returns it, or `None`
+  /// if the heap is empty.
+  fn pop(&mut self) -> Option<T>;
+
+  /// Returns a reference to the value with the highest priority.
+  fn peek(&self) -> Option<&T>;
+
+  /// Returns the number of values in the heap.
+  fn len(&self) -> usize;
+
+  /// Checks if the heap is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Moves all the values of the other heap into this one.
+  fn merge(&mut self, other: Self)
+  where
+    Self: Sized;
+}
+
+impl<T: Ord, O: Order> Heap<T> for BinaryHeap<T, O> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  /// Rebuilds the heap from the values of both heaps in O(n + m).
+  fn merge(&mut self, other: Self) {
+    let mut items = std::mem::take(self).into_vec();
+    items.extend(other.into_vec());
+    *self = Self::from_vec(items);
+  }
+}
+
+impl<T: Ord, const D: usize, O: Order> Heap<T> for DAryHeap<T, D, O> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  /// Rebuilds the heap from the values of both heaps in O(n + m).
+  fn merge(&mut self, other: Self) {
+    let mut items = std::mem::take(self).into_vec();
+    items.extend(other.into_vec());
+    *self = Self::from_vec(items);
+  }
+}
+
+impl<T: Ord, O: Order> Heap<T> for PairingHeap<T, O> {
+  /// Pushes a value onto the heap, dropping its handle.
+  fn push(&mut self, value: T) {
+    let _ = self.push(value);
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  /// Links the roots of both heaps in O(1).
+  fn merge(&mut self, other: Self) {
+    self.merge(other)
+  }
+}
+
+impl<T: Ord> Heap<T> for std::collections::BinaryHeap<T> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  /// Moves the values of the smaller heap into the larger one in
+  /// O(min(n, m) log(n + m)).
+  fn merge(&mut self, mut other: Self) {
+    self.append(&mut other)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::cmp::Reverse;
+
+  use super::Heap;
+  use heap::{DAryHeap, Max, MaxHeap, Min, MinHeap, PairingHeap};
+  use proptest::prelude::*;
+
+  /// Pushes, merges and pops a few values through the trait, the greatest
+  /// first.
+  fn highest_priority_first<H: Heap<i32> + Default>() {
+    let mut heap = H::default();
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+
+    [3, 1, 4].into_iter().for_each(|value| heap.push(value));
+    let mut other = H::default();
+    [1, 5, 9].into_iter().for_each(|value| other.push(value));
+    heap.merge(other);
+
+    assert_eq!(heap.len(), 6);
+    assert_eq!(heap.peek(), Some(&9));
+    assert_eq!(heap.pop(), Some(9));
+    heap.push(2);
+    assert_eq!(std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>(), [5, 4, 3, 2, 1, 1]);
+    assert!(heap.is_empty());
+  }
+
+  #[test]
+  fn workspace_structures() {
+    highest_priority_first::<MaxHeap<_>>();
+    highest_priority_first::<DAryHeap<_, 4>>();
+    highest_priority_first::<PairingHeap<_>>();
+  }
+
+  #[test]
+  fn standard_library() {
+    highest_priority_first::<std::collections::BinaryHeap<_>>();
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop,
+    Merge(Vec<i32>)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Push),
+      Just(Operation::Pop),
+      prop::collection::vec(any::<i32>(), 0..5).prop_map(Operation::Merge)
+    ]
+  }
+
+  /// Applies the operations to a heap and returns the popped values
+  /// followed by the values left in the heap.
+  fn run<T: Ord, H: Heap<T> + Default>(operations: &[Operation], wrap: fn(i32) -> T) -> Vec<Option<T>> {
+    let mut heap = H::default();
+    let mut output = Vec::new();
+
+    for operation in operations {
+      match operation {
+        Operation::Push(value) => heap.push(wrap(*value)),
+        Operation::Pop => output.push(heap.pop()),
+        Operation::Merge(values) => {
+          let mut other = H::default();
+          values.iter().for_each(|&value| other.push(wrap(value)));
+          heap.merge(other);
+        }
+      }
+    }
+
+    output.extend(std::iter::from_fn(|| heap.pop()).map(Some));
+    output
+  }
+
+  proptest! {
+    #[test]
+    fn matches_std_binary_heap(operations in prop::collection::vec(operation(), 0..100)) {
+      let expected = run::<_, std::collections::BinaryHeap<_>>(&operations, |value| value);
+      prop_assert_eq!(&run::<_, MaxHeap<_>>(&operations, |value| value), &expected);
+      prop_assert_eq!(&run::<_, DAryHeap<_, 3, Max>>(&operations, |value| value), &expected);
+      prop_assert_eq!(&run::<_, PairingHeap<_, Max>>(&operations, |value| value), &expected);
+
+      let reversed = run::<_, std::collections::BinaryHeap<_>>(&operations, Reverse);
+      let expected: Vec<_> = reversed.into_iter().map(|value| value.map(|Reverse(value)| value)).collect();
+      prop_assert_eq!(&run::<_, MinHeap<_>>(&operations, |value| value), &expected);
+      prop_assert_eq!(&run::<_, DAryHeap<_, 3, Min>>(&operations, |value| value), &expected);
+      prop_assert_eq!(&run::<_, PairingHeap<_, Min>>(&operations, |value| value), &expected);
+    }
+  }
+}
@@ -0,0 +1,45 @@
+//! Traits shared by the structures of the workspace.
+//!
+//! Every structure keeps its own inherent API, and these traits describe
+//! the part that structures of the same kind have in common, so that an
+//! algorithm can be written once and run on any backing structure,
+//! including the collections of the standard library:
+//!
+//! - `StackLike` for last-in first-out structures,
+//! - `QueueLike` for first-in first-out structures,
+//! - `Heap` for priority queues,
+//! - `Map` for structures that associate values with keys,
+//! - `Set` for structures that hold distinct values.
+//!
+//! As the traits share method names with the inherent methods of the
+//! structures, they are usually imported as `use collections_traits::Map as _;`
+//! in code that uses the structures directly.
+//!
+//! # Example
+//!
+//! ```
+//! use collections_traits::StackLike;
+//! use stack::Stack;
+//!
+//! /// Reverses the values with any stack.
+//! fn reverse<S: StackLike<char> + Default>(text: &str) -> String {
+//!   let mut stack = S::default();
+//!   text.chars().for_each(|c| stack.push(c));
+//!   std::iter::from_fn(|| stack.pop()).collect()
+//! }
+//!
+//! assert_eq!(reverse::<Stack<char>>("abc"), "cba");
+//! assert_eq!(reverse::<Vec<char>>("abc"), "cba");
+//! ```
+
+mod heap;
+mod map;
+mod queue;
+mod set;
+mod stack;
+
+pub use heap::Heap;
+pub use map::Map;
+pub use queue::QueueLike;
+pub use set::Set;
+pub use stack::StackLike;
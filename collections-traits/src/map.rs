@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap as StdBTreeMap, HashMap as StdHashMap};
+use std::hash::{BuildHasher, Hash};
+
+use bst::{OrderStatisticsTree, SplayTree, Treap};
+use btree::BTreeMap;
+use hashmap::HashMap;
+use skiplist::SkipList;
+
+
+/// A structure that associates values with distinct keys.
+///
+/// # Example
+///
+/// ```
+/// use collections_traits::Map;
+/// use hashmap::HashMap;
+/// use skiplist::SkipList;
+///
+/// /// Counts the occurrences of every word.
+/// fn count<'a, M: Map<&'a str, usize> + Default>(text: &'a str) -> M {
+///   let mut counts = M::default();
+///   for word in text.split_whitespace() {
+///     match counts.get_mut(&word) {
+///       Some(count) => *count += 1,
+///       None => {
+///         counts.insert(word, 1);
+///       }
+///     }
+///   }
+///   counts
+/// }
+///
+/// let text = "to be or not to be";
+/// assert_eq!(Map::get(&count::<HashMap<_, _>>(text), &"to"), Some(&2));
+/// assert_eq!(Map::len(&count::<SkipList<_, _>>(text)), 4);
+/// ```
+pub trait Map<K, V> {
+  /// Inserts a key-value pair into the map and returns the previous value
+  /// if the key was already present.
+  fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+  /// Returns a reference to the value corresponding to the key.
+  fn get(&self, key: &K) -> Option<&V>;
+
+  /// Returns a mutable reference to the value corresponding to the key.
+  fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+
+  /// Removes a key from the map and returns its value, or `None` if the key
+  /// was not present.
+  fn remove(&mut self, key: &K) -> Option<V>;
+
+  /// Checks if the map contains a value for the key.
+  fn contains_key(&self, key: &K) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// Returns the number of pairs in the map.
+  fn len(&self) -> usize;
+
+  /// Checks if the map is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<K: Hash + Eq, V> Map<K, V> for HashMap<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for BTreeMap<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for SkipList<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for Treap<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for SplayTree<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  /// Returns a reference to the value corresponding to the key, without
+  /// splaying as the map is borrowed immutably.
+  fn get(&self, key: &K) -> Option<&V> {
+    self.peek(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for OrderStatisticsTree<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Map<K, V> for StdHashMap<K, V, S> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<K: Ord, V> Map<K, V> for StdBTreeMap<K, V> {
+  fn insert(&mut self, key: K, value: V) -> Option<V> {
+    self.insert(key, value)
+  }
+
+  fn get(&self, key: &K) -> Option<&V> {
+    self.get(key)
+  }
+
+  fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    self.get_mut(key)
+  }
+
+  fn remove(&mut self, key: &K) -> Option<V> {
+    self.remove(key)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeMap as StdBTreeMap, HashMap as StdHashMap};
+
+  use super::Map;
+  use bst::{OrderStatisticsTree, SplayTree, Treap};
+  use btree::BTreeMap;
+  use hashmap::HashMap;
+  use proptest::prelude::*;
+  use skiplist::SkipList;
+
+  /// Inserts, updates and removes a few pairs through the trait.
+  fn pairs<M: Map<&'static str, i32> + Default>() {
+    let mut map = M::default();
+    assert!(map.is_empty());
+
+    assert_eq!(map.insert("one", 1), None);
+    assert_eq!(map.insert("two", 2), None);
+    assert_eq!(map.insert("one", 10), Some(1));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&"one"), Some(&10));
+    assert!(map.contains_key(&"two"));
+
+    *map.get_mut(&"two").unwrap() += 1;
+    assert_eq!(map.remove(&"two"), Some(3));
+    assert_eq!(map.remove(&"two"), None);
+    assert!(!map.contains_key(&"two"));
+    assert_eq!(map.get_mut(&"three"), None);
+    assert_eq!(map.len(), 1);
+  }
+
+  #[test]
+  fn workspace_structures() {
+    pairs::<HashMap<_, _>>();
+    pairs::<BTreeMap<_, _>>();
+    pairs::<SkipList<_, _>>();
+    pairs::<Treap<_, _>>();
+    pairs::<SplayTree<_, _>>();
+    pairs::<OrderStatisticsTree<_, _>>();
+  }
+
+  #[test]
+  fn standard_library() {
+    pairs::<StdHashMap<_, _>>();
+    pairs::<StdBTreeMap<_, _>>();
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Get(u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      any::<u8>().prop_map(Operation::Remove),
+      any::<u8>().prop_map(Operation::Get)
+    ]
+  }
+
+  /// Applies the operations to a map and returns their results followed by
+  /// the final length of the map.
+  fn run<M: Map<u8, i32> + Default>(operations: &[Operation]) -> (Vec<Option<i32>>, usize) {
+    let mut map = M::default();
+    let output = operations.iter().map(|operation| match *operation {
+      Operation::Insert(key, value) => map.insert(key, value),
+      Operation::Remove(key) => map.remove(&key),
+      Operation::Get(key) => map.get(&key).copied()
+    }).collect();
+
+    (output, map.len())
+  }
+
+  proptest! {
+    #[test]
+    fn matches_std_hash_map(operations in prop::collection::vec(operation(), 0..100)) {
+      let expected = run::<StdHashMap<_, _>>(&operations);
+
+      prop_assert_eq!(&run::<HashMap<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<BTreeMap<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<SkipList<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<Treap<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<SplayTree<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<OrderStatisticsTree<_, _>>(&operations), &expected);
+      prop_assert_eq!(&run::<StdBTreeMap<_, _>>(&operations), &expected);
+    }
+  }
+}
@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+
+use deque::Deque;
+use doubly_linked_list::DoublyLinkedList;
+use linked_list::arena::ArenaLinkedList;
+use linked_list::LinkedList;
+use queue::Queue;
+use stack::TwoStackQueue;
+
+
+/// A first-in first-out structure: values are enqueued at the back and
+/// dequeued from the front.
+///
+/// # Example
+///
+/// ```
+/// use collections_traits::QueueLike;
+/// use queue::Queue;
+///
+/// /// Interleaves two queues into a third one.
+/// fn interleave<Q: QueueLike<i32> + Default>(mut a: Q, mut b: Q) -> Q {
+///   let mut merged = Q::default();
+///   while !a.is_empty() || !b.is_empty() {
+///     a.dequeue().into_iter().chain(b.dequeue()).for_each(|value| merged.enqueue(value));
+///   }
+///   merged
+/// }
+///
+/// let mut odd = Queue::new();
+/// let mut even = Queue::new();
+/// [1, 3, 5].into_iter().for_each(|value| odd.enqueue(value));
+/// [2, 4].into_iter().for_each(|value| even.enqueue(value));
+/// let merged = interleave(odd, even);
+///
+/// assert_eq!(merged.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+/// ```
+pub trait QueueLike<T> {
+  /// Adds a value to the back of the queue.
+  fn enqueue(&mut self, value: T);
+
+  /// Removes the value at the front of the queue and returns it, or `None`
+  /// if the queue is empty.
+  fn dequeue(&mut self) -> Option<T>;
+
+  /// Returns a reference to the value at the front of the queue.
+  fn peek(&self) -> Option<&T>;
+
+  /// Returns the number of values in the queue.
+  fn len(&self) -> usize;
+
+  /// Checks if the queue is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> QueueLike<T> for Queue<T> {
+  fn enqueue(&mut self, value: T) {
+    self.enqueue(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.dequeue()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for Deque<T> {
+  fn enqueue(&mut self, value: T) {
+    self.push_back(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for LinkedList<T> {
+  fn enqueue(&mut self, value: T) {
+    self.append(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for ArenaLinkedList<T> {
+  fn enqueue(&mut self, value: T) {
+    self.append(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for DoublyLinkedList<T> {
+  fn enqueue(&mut self, value: T) {
+    self.push_back(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for TwoStackQueue<T> {
+  fn enqueue(&mut self, value: T) {
+    self.enqueue(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.dequeue()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> QueueLike<T> for VecDeque<T> {
+  fn enqueue(&mut self, value: T) {
+    self.push_back(value)
+  }
+
+  fn dequeue(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::collections::VecDeque;
+
+  use super::QueueLike;
+  use deque::Deque;
+  use doubly_linked_list::DoublyLinkedList;
+  use linked_list::arena::ArenaLinkedList;
+  use linked_list::LinkedList;
+  use proptest::prelude::*;
+  use queue::Queue;
+  use stack::TwoStackQueue;
+
+  /// Enqueues and dequeues a few values through the trait.
+  fn first_in_first_out<Q: QueueLike<i32> + Default>() {
+    let mut queue = Q::default();
+    assert!(queue.is_empty());
+    assert_eq!(queue.peek(), None);
+
+    for value in 1..=3 {
+      queue.enqueue(value);
+    }
+
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue.peek(), Some(&1));
+    assert_eq!(queue.dequeue(), Some(1));
+    queue.enqueue(4);
+    assert_eq!(std::iter::from_fn(|| queue.dequeue()).collect::<Vec<_>>(), [2, 3, 4]);
+    assert!(queue.is_empty());
+  }
+
+  #[test]
+  fn workspace_structures() {
+    first_in_first_out::<Queue<_>>();
+    first_in_first_out::<Deque<_>>();
+    first_in_first_out::<LinkedList<_>>();
+    first_in_first_out::<ArenaLinkedList<_>>();
+    first_in_first_out::<DoublyLinkedList<_>>();
+    first_in_first_out::<TwoStackQueue<_>>();
+  }
+
+  #[test]
+  fn standard_library() {
+    first_in_first_out::<VecDeque<_>>();
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Enqueue(i32),
+    Dequeue
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![any::<i32>().prop_map(Operation::Enqueue), Just(Operation::Dequeue)]
+  }
+
+  /// Applies the operations to a queue and returns the dequeued values
+  /// followed by the values left in the queue.
+  fn run<Q: QueueLike<i32> + Default>(operations: &[Operation]) -> Vec<Option<i32>> {
+    let mut queue = Q::default();
+    let mut output = Vec::new();
+
+    for operation in operations {
+      match *operation {
+        Operation::Enqueue(value) => queue.enqueue(value),
+        Operation::Dequeue => output.push(queue.dequeue())
+      }
+    }
+
+    output.extend(std::iter::from_fn(|| queue.dequeue()).map(Some));
+    output
+  }
+
+  proptest! {
+    #[test]
+    fn matches_vec_deque(operations in prop::collection::vec(operation(), 0..100)) {
+      let expected = run::<VecDeque<_>>(&operations);
+
+      prop_assert_eq!(&run::<Queue<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<Deque<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<LinkedList<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<ArenaLinkedList<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<DoublyLinkedList<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<TwoStackQueue<_>>(&operations), &expected);
+    }
+  }
+}
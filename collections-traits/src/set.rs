@@ -0,0 +1,156 @@
+use std::collections::{BTreeSet, HashSet as StdHashSet};
+use std::hash::{BuildHasher, Hash};
+
+use bst::BinarySearchTree;
+use hashset::HashSet;
+
+
+/// A structure that holds distinct values.
+///
+/// # Example
+///
+/// ```
+/// use bst::BinarySearchTree;
+/// use collections_traits::Set;
+/// use hashset::HashSet;
+///
+/// /// Checks if any value appears twice.
+/// fn has_duplicates<S: Set<i32> + Default>(values: &[i32]) -> bool {
+///   let mut seen = S::default();
+///   values.iter().any(|&value| !seen.insert(value))
+/// }
+///
+/// assert!(has_duplicates::<HashSet<_>>(&[3, 1, 3]));
+/// assert!(!has_duplicates::<BinarySearchTree<_>>(&[3, 1, 2]));
+/// ```
+pub trait Set<T> {
+  /// Adds a value to the set and returns `true` if it was not already
+  /// present.
+  fn insert(&mut self, value: T) -> bool;
+
+  /// Checks if the set contains the value.
+  fn contains(&self, value: &T) -> bool;
+
+  /// Removes a value from the set and returns `true` if it was present.
+  fn remove(&mut self, value: &T) -> bool;
+
+  /// Returns the number of values in the set.
+  fn len(&self) -> usize;
+
+  /// Checks if the set is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T: Hash + Eq> Set<T> for HashSet<T> {
+  fn insert(&mut self, value: T) -> bool {
+    self.insert(value)
+  }
+
+  fn contains(&self, value: &T) -> bool {
+    self.contains(value)
+  }
+
+  fn remove(&mut self, value: &T) -> bool {
+    self.remove(value)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T: Ord> Set<T> for BinarySearchTree<T> {
+  fn insert(&mut self, value: T) -> bool {
+    self.insert(value)
+  }
+
+  fn contains(&self, value: &T) -> bool {
+    self.contains(value)
+  }
+
+  fn remove(&mut self, value: &T) -> bool {
+    self.remove(value)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> Set<T> for StdHashSet<T, S> {
+  fn insert(&mut self, value: T) -> bool {
+    self.insert(value)
+  }
+
+  fn contains(&self, value: &T) -> bool {
+    self.contains(value)
+  }
+
+  fn remove(&mut self, value: &T) -> bool {
+    self.remove(value)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T: Ord> Set<T> for BTreeSet<T> {
+  fn insert(&mut self, value: T) -> bool {
+    self.insert(value)
+  }
+
+  fn contains(&self, value: &T) -> bool {
+    self.contains(value)
+  }
+
+  fn remove(&mut self, value: &T) -> bool {
+    self.remove(value)
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeSet, HashSet as StdHashSet};
+
+  use super::Set;
+  use bst::BinarySearchTree;
+  use hashset::HashSet;
+
+  /// Inserts and removes a few values through the trait.
+  fn distinct_values<S: Set<i32> + Default>() {
+    let mut set = S::default();
+    assert!(set.is_empty());
+
+    assert!(set.insert(2));
+    assert!(set.insert(1));
+    assert!(!set.insert(2));
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&1));
+    assert!(!set.contains(&3));
+
+    assert!(set.remove(&1));
+    assert!(!set.remove(&1));
+    assert!(!set.contains(&1));
+    assert_eq!(set.len(), 1);
+  }
+
+  #[test]
+  fn workspace_structures() {
+    distinct_values::<HashSet<_>>();
+    distinct_values::<BinarySearchTree<_>>();
+  }
+
+  #[test]
+  fn standard_library() {
+    distinct_values::<StdHashSet<_>>();
+    distinct_values::<BTreeSet<_>>();
+  }
+}
@@ -0,0 +1,286 @@
+use doubly_linked_list::DoublyLinkedList;
+use linked_list::arena::ArenaLinkedList;
+use linked_list::LinkedList;
+use stack::storage::Storage;
+use stack::{ArrayStack, GenericStack, MinStack, SmallStack, Stack};
+
+
+/// A last-in first-out structure: values are pushed on and popped off the
+/// top.
+///
+/// # Example
+///
+/// ```
+/// use collections_traits::StackLike;
+/// use stack::Stack;
+///
+/// let mut stack = Stack::new();
+/// StackLike::push(&mut stack, 1);
+/// StackLike::push(&mut stack, 2);
+///
+/// assert_eq!(StackLike::peek(&stack), Some(&2));
+/// assert_eq!(StackLike::pop(&mut stack), Some(2));
+/// assert_eq!(StackLike::len(&stack), 1);
+/// ```
+pub trait StackLike<T> {
+  /// Pushes a value on top of the stack.
+  fn push(&mut self, value: T);
+
+  /// Removes the value on top of the stack and returns it, or `None` if the
+  /// stack is empty.
+  fn pop(&mut self) -> Option<T>;
+
+  /// Returns a reference to the value on top of the stack.
+  fn peek(&self) -> Option<&T>;
+
+  /// Returns the number of values in the stack.
+  fn len(&self) -> usize;
+
+  /// Checks if the stack is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> StackLike<T> for Stack<T> {
+  /// Pushes a value on top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack has a capacity limit and is full.
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T, S: Storage<T>> StackLike<T> for GenericStack<T, S> {
+  /// Pushes a value on top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the storage is full.
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T, const N: usize> StackLike<T> for ArrayStack<T, N> {
+  /// Pushes a value on top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack is full.
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T, const N: usize> StackLike<T> for SmallStack<T, N> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T: Ord> StackLike<T> for MinStack<T> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.peek()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> StackLike<T> for LinkedList<T> {
+  fn push(&mut self, value: T) {
+    self.prepend(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> StackLike<T> for ArenaLinkedList<T> {
+  fn push(&mut self, value: T) {
+    self.prepend(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> StackLike<T> for DoublyLinkedList<T> {
+  fn push(&mut self, value: T) {
+    self.push_back(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop_back()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.back()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> StackLike<T> for deque::Deque<T> {
+  fn push(&mut self, value: T) {
+    self.push_back(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop_back()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.back()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> StackLike<T> for Vec<T> {
+  fn push(&mut self, value: T) {
+    self.push(value)
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn peek(&self) -> Option<&T> {
+    self.last()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::StackLike;
+  use doubly_linked_list::DoublyLinkedList;
+  use linked_list::arena::ArenaLinkedList;
+  use linked_list::LinkedList;
+  use stack::storage::Hybrid;
+  use stack::{ArrayStack, GenericStack, MinStack, SmallStack, Stack};
+
+  /// Pushes and pops a few values through the trait.
+  fn last_in_first_out<S: StackLike<i32> + Default>() {
+    let mut stack = S::default();
+    assert!(stack.is_empty());
+    assert_eq!(stack.peek(), None);
+
+    for value in 1..=3 {
+      stack.push(value);
+    }
+
+    assert_eq!(stack.len(), 3);
+    assert_eq!(stack.peek(), Some(&3));
+    assert_eq!(stack.pop(), Some(3));
+    stack.push(4);
+    assert_eq!(std::iter::from_fn(|| stack.pop()).collect::<Vec<_>>(), [4, 2, 1]);
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  fn workspace_structures() {
+    last_in_first_out::<Stack<_>>();
+    last_in_first_out::<ArrayStack<_, 3>>();
+    last_in_first_out::<GenericStack<_>>();
+    last_in_first_out::<GenericStack<_, Hybrid<_, 2>>>();
+    last_in_first_out::<SmallStack<_, 2>>();
+    last_in_first_out::<MinStack<_>>();
+    last_in_first_out::<LinkedList<_>>();
+    last_in_first_out::<ArenaLinkedList<_>>();
+    last_in_first_out::<DoublyLinkedList<_>>();
+    last_in_first_out::<deque::Deque<_>>();
+  }
+
+  #[test]
+  fn standard_library() {
+    last_in_first_out::<Vec<_>>();
+  }
+}
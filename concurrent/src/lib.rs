@@ -0,0 +1,10 @@
+//! Data structures that can be shared between threads.
+//!
+//! The structures of this crate take `&self` in all of their operations,
+//! so they can be put in an `Arc` and used from several threads at once.
+
+mod queue;
+mod stack;
+
+pub use queue::{QueueClosedError, SyncQueue};
+pub use stack::ConcurrentStack;
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use queue::Queue;
+
+
+/// A multi-producer, multi-consumer FIFO queue that can be shared
+/// between threads.
+///
+/// The queue is a `Queue` behind a mutex. Consumers calling `dequeue`
+/// block on a condition variable until a value is available, which gives
+/// the queue channel-like semantics: once it is closed, producers can no
+/// longer enqueue values and consumers drain the remaining values before
+/// `dequeue` returns `None`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use concurrent::SyncQueue;
+///
+/// let queue = Arc::new(SyncQueue::new());
+///
+/// let consumer = {
+///   let queue = Arc::clone(&queue);
+///   thread::spawn(move || {
+///     let mut sum = 0;
+///     while let Some(value) = queue.dequeue() {
+///       sum += value;
+///     }
+///     sum
+///   })
+/// };
+///
+/// for value in 1..=4 {
+///   queue.enqueue(value).unwrap();
+/// }
+/// queue.close();
+///
+/// assert_eq!(consumer.join().unwrap(), 10);
+/// ```
+pub struct SyncQueue<T> {
+  state: Mutex<State<T>>,
+  /// Signaled when a value is enqueued or the queue is closed.
+  available: Condvar
+}
+
+/// The part of a `SyncQueue` protected by its mutex.
+struct State<T> {
+  items: Queue<T>,
+  closed: bool
+}
+
+/// The error returned by `SyncQueue::enqueue` when the queue is closed.
+///
+/// It carries the rejected value so it can be recovered.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueueClosedError<T>(pub T);
+
+impl<T> QueueClosedError<T> {
+  /// Returns the value that could not be enqueued.
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl<T> fmt::Display for QueueClosedError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "queue is closed")
+  }
+}
+
+impl<T: fmt::Debug> std::error::Error for QueueClosedError<T> {}
+
+impl<T> Default for SyncQueue<T> {
+  /// Creates a new instance of `SyncQueue` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> SyncQueue<T> {
+  /// Creates a new empty, open instance of `SyncQueue`.
+  pub fn new() -> Self {
+    Self {
+      state: Mutex::new(State { items: Queue::new(), closed: false }),
+      available: Condvar::new()
+    }
+  }
+
+  /// Adds a value to the back of the queue and wakes up one waiting
+  /// consumer.
+  ///
+  /// Returns the value back in an error if the queue is closed.
+  pub fn enqueue(&self, value: T) -> Result<(), QueueClosedError<T>> {
+    let mut state = self.lock();
+
+    if state.closed {
+      return Err(QueueClosedError(value));
+    }
+
+    state.items.enqueue(value);
+    drop(state);
+    self.available.notify_one();
+
+    Ok(())
+  }
+
+  /// Removes the value at the front of the queue and returns it, waiting
+  /// until a value is available.
+  ///
+  /// Returns `None` once the queue is closed and empty.
+  pub fn dequeue(&self) -> Option<T> {
+    let mut state = self.lock();
+
+    loop {
+      if let Some(value) = state.items.dequeue() {
+        return Some(value);
+      }
+      if state.closed {
+        return None;
+      }
+
+      state = self.available.wait(state).unwrap_or_else(PoisonError::into_inner);
+    }
+  }
+
+  /// Removes the value at the front of the queue and returns it,
+  /// or `None` if the queue is empty, without waiting.
+  pub fn try_dequeue(&self) -> Option<T> {
+    self.lock().items.dequeue()
+  }
+
+  /// Closes the queue and wakes up every waiting consumer.
+  ///
+  /// Values already in the queue can still be dequeued.
+  pub fn close(&self) {
+    self.lock().closed = true;
+    self.available.notify_all();
+  }
+
+  /// Checks if the queue is closed.
+  pub fn is_closed(&self) -> bool {
+    self.lock().closed
+  }
+
+  /// Returns the number of elements in the queue.
+  pub fn len(&self) -> usize {
+    self.lock().items.len()
+  }
+
+  /// Checks if the queue is empty.
+  pub fn is_empty(&self) -> bool {
+    self.lock().items.is_empty()
+  }
+
+  /// Locks the state of the queue.
+  ///
+  /// The state is consistent between any two operations, so a panic of
+  /// another thread holding the lock does not make it unusable.
+  fn lock(&self) -> MutexGuard<'_, State<T>> {
+    self.state.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl<T> fmt::Debug for SyncQueue<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("SyncQueue")
+      .field("len", &self.len())
+      .field("closed", &self.is_closed())
+      .finish_non_exhaustive()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{QueueClosedError, SyncQueue};
+  use std::sync::Arc;
+  use std::thread;
+
+  /// Number of values enqueued by every producer, kept small under Miri.
+  const COUNT: usize = if cfg!(miri) { 50 } else { 10_000 };
+  const THREADS: usize = 4;
+
+  #[test]
+  fn empty_queue() {
+    let queue = SyncQueue::<i32>::new();
+
+    assert!(queue.is_empty());
+    assert!(!queue.is_closed());
+    assert_eq!(queue.try_dequeue(), None);
+  }
+
+  #[test]
+  fn first_in_first_out() {
+    let queue = SyncQueue::new();
+    queue.enqueue(1).unwrap();
+    queue.enqueue(2).unwrap();
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.dequeue(), Some(1));
+    assert_eq!(queue.try_dequeue(), Some(2));
+  }
+
+  #[test]
+  fn close() {
+    let queue = SyncQueue::new();
+    queue.enqueue(1).unwrap();
+    queue.close();
+
+    assert!(queue.is_closed());
+    assert_eq!(queue.enqueue(2), Err(QueueClosedError(2)));
+    assert_eq!(queue.dequeue(), Some(1));
+    assert_eq!(queue.dequeue(), None);
+  }
+
+  #[test]
+  fn close_wakes_up_consumers() {
+    let queue = Arc::new(SyncQueue::<i32>::new());
+
+    let consumers: Vec<_> = (0..THREADS)
+      .map(|_| {
+        let queue = Arc::clone(&queue);
+        thread::spawn(move || queue.dequeue())
+      })
+      .collect();
+
+    queue.close();
+
+    for consumer in consumers {
+      assert_eq!(consumer.join().unwrap(), None);
+    }
+  }
+
+  #[test]
+  fn multiple_producers_and_consumers() {
+    let queue = Arc::new(SyncQueue::new());
+
+    let consumers: Vec<_> = (0..THREADS)
+      .map(|_| {
+        let queue = Arc::clone(&queue);
+        thread::spawn(move || {
+          let mut values = Vec::new();
+          while let Some(value) = queue.dequeue() {
+            values.push(value);
+          }
+          values
+        })
+      })
+      .collect();
+
+    let producers: Vec<_> = (0..THREADS)
+      .map(|thread| {
+        let queue = Arc::clone(&queue);
+        thread::spawn(move || {
+          for i in 0..COUNT {
+            queue.enqueue(thread * COUNT + i).unwrap();
+          }
+        })
+      })
+      .collect();
+
+    for producer in producers {
+      producer.join().unwrap();
+    }
+    queue.close();
+
+    let mut values: Vec<_> = consumers.into_iter().flat_map(|consumer| consumer.join().unwrap()).collect();
+    values.sort_unstable();
+    assert_eq!(values, (0..THREADS * COUNT).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn order_per_producer() {
+    let queue = Arc::new(SyncQueue::new());
+
+    let producer = {
+      let queue = Arc::clone(&queue);
+      thread::spawn(move || {
+        for i in 0..COUNT {
+          queue.enqueue(i).unwrap();
+        }
+        queue.close();
+      })
+    };
+
+    let mut values = Vec::new();
+    while let Some(value) = queue.dequeue() {
+      values.push(value);
+    }
+
+    producer.join().unwrap();
+    assert_eq!(values, (0..COUNT).collect::<Vec<_>>());
+  }
+}
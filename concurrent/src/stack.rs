@@ -0,0 +1,240 @@
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+
+
+/// A lock-free stack that can be shared between threads.
+///
+/// This is the Treiber stack: the top of the stack is an atomic pointer
+/// that `push` and `pop` update with a compare-and-swap loop, retrying
+/// when another thread changed the top in between. Popped nodes are
+/// freed through epoch-based reclamation, so a node is never freed while
+/// another thread may still be reading it.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use concurrent::ConcurrentStack;
+///
+/// let stack = Arc::new(ConcurrentStack::new());
+///
+/// let handles: Vec<_> = (0..4)
+///   .map(|i| {
+///     let stack = Arc::clone(&stack);
+///     thread::spawn(move || stack.push(i))
+///   })
+///   .collect();
+///
+/// for handle in handles {
+///   handle.join().unwrap();
+/// }
+///
+/// let mut values = Vec::new();
+/// while let Some(value) = stack.pop() {
+///   values.push(value);
+/// }
+///
+/// values.sort();
+/// assert_eq!(values, vec![0, 1, 2, 3]);
+/// ```
+pub struct ConcurrentStack<T> {
+  head: Atomic<Node<T>>
+}
+
+/// Represents a node of a `ConcurrentStack`.
+struct Node<T> {
+  /// The value stored in the node. It is moved out by `pop`, and the node
+  /// itself is freed later, so it must not be dropped with the node.
+  value: ManuallyDrop<T>,
+  /// The node below this one.
+  next: Atomic<Node<T>>
+}
+
+// Values are only moved in and out of the stack and never shared, so the
+// stack can be sent and shared between threads whenever `T` can be sent.
+unsafe impl<T: Send> Send for ConcurrentStack<T> {}
+unsafe impl<T: Send> Sync for ConcurrentStack<T> {}
+
+impl<T> Default for ConcurrentStack<T> {
+  /// Creates a new instance of `ConcurrentStack` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> ConcurrentStack<T> {
+  /// Creates a new empty instance of `ConcurrentStack`.
+  pub fn new() -> Self {
+    Self { head: Atomic::null() }
+  }
+
+  /// Pushes a value onto the top of the stack.
+  pub fn push(&self, value: T) {
+    let mut node = Owned::new(Node { value: ManuallyDrop::new(value), next: Atomic::null() });
+    let guard = epoch::pin();
+
+    loop {
+      let head = self.head.load(Relaxed, &guard);
+      node.next.store(head, Relaxed);
+
+      match self.head.compare_exchange(head, node, Release, Relaxed, &guard) {
+        Ok(_) => return,
+        Err(error) => node = error.new
+      }
+    }
+  }
+
+  /// Removes the top value of the stack and returns it,
+  /// or `None` if the stack is empty.
+  pub fn pop(&self) -> Option<T> {
+    let guard = epoch::pin();
+
+    loop {
+      let head = self.head.load(Acquire, &guard);
+      // SAFETY: the node cannot be freed while the guard is pinned.
+      let node = unsafe { head.as_ref() }?;
+      let next = node.next.load(Relaxed, &guard);
+
+      if self.head.compare_exchange(head, next, Relaxed, Relaxed, &guard).is_ok() {
+        // SAFETY: the node is unlinked, so no other thread can pop it
+        // and the value is moved out exactly once. The node is freed once
+        // every thread that could still read it has unpinned.
+        unsafe {
+          guard.defer_destroy(head);
+          return Some(ManuallyDrop::into_inner(ptr::read(&node.value)));
+        }
+      }
+    }
+  }
+
+  /// Checks if the stack is empty.
+  ///
+  /// Other threads may push or pop at any time, so the result can be
+  /// outdated as soon as it is returned.
+  pub fn is_empty(&self) -> bool {
+    self.head.load(Acquire, &epoch::pin()).is_null()
+  }
+}
+
+impl<T> Drop for ConcurrentStack<T> {
+  /// Drops the remaining values one by one.
+  fn drop(&mut self) {
+    while self.pop().is_some() {}
+  }
+}
+
+impl<T> fmt::Debug for ConcurrentStack<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ConcurrentStack").finish_non_exhaustive()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::ConcurrentStack;
+  use std::sync::Arc;
+  use std::thread;
+
+  /// Number of values pushed by every thread, kept small under Miri.
+  const COUNT: usize = if cfg!(miri) { 50 } else { 10_000 };
+  const THREADS: usize = 4;
+
+  #[test]
+  fn empty_stack() {
+    let stack = ConcurrentStack::<i32>::new();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.pop(), None);
+  }
+
+  #[test]
+  fn push_and_pop() {
+    let stack = ConcurrentStack::new();
+    stack.push(1);
+    stack.push(2);
+    stack.push(3);
+
+    assert!(!stack.is_empty());
+    assert_eq!(stack.pop(), Some(3));
+    assert_eq!(stack.pop(), Some(2));
+    assert_eq!(stack.pop(), Some(1));
+    assert_eq!(stack.pop(), None);
+  }
+
+  #[test]
+  fn concurrent_push() {
+    let stack = Arc::new(ConcurrentStack::new());
+
+    let handles: Vec<_> = (0..THREADS)
+      .map(|thread| {
+        let stack = Arc::clone(&stack);
+        thread::spawn(move || {
+          for i in 0..COUNT {
+            stack.push(thread * COUNT + i);
+          }
+        })
+      })
+      .collect();
+
+    for handle in handles {
+      handle.join().unwrap();
+    }
+
+    let mut values = Vec::new();
+    while let Some(value) = stack.pop() {
+      values.push(value);
+    }
+
+    values.sort_unstable();
+    assert_eq!(values, (0..THREADS * COUNT).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn concurrent_push_and_pop() {
+    let stack = Arc::new(ConcurrentStack::new());
+
+    let handles: Vec<_> = (0..THREADS)
+      .map(|thread| {
+        let stack = Arc::clone(&stack);
+        thread::spawn(move || {
+          let mut popped = Vec::new();
+          for i in 0..COUNT {
+            stack.push(thread * COUNT + i);
+            popped.extend(stack.pop());
+          }
+          popped
+        })
+      })
+      .collect();
+
+    let mut values: Vec<_> = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+    while let Some(value) = stack.pop() {
+      values.push(value);
+    }
+
+    values.sort_unstable();
+    assert_eq!(values, (0..THREADS * COUNT).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn drop_remaining_values() {
+    let value = Arc::new(());
+    let stack = ConcurrentStack::new();
+
+    for _ in 0..3 {
+      stack.push(Arc::clone(&value));
+    }
+    drop(stack.pop());
+    assert_eq!(Arc::strong_count(&value), 3);
+
+    drop(stack);
+    assert_eq!(Arc::strong_count(&value), 1);
+  }
+}
@@ -0,0 +1,99 @@
+//! Umbrella crate re-exporting every data structure of the workspace.
+//!
+//! Each structure lives in its own crate and is enabled by the cargo
+//! feature of the same name (all of them are enabled by default). The
+//! main types are re-exported at the crate root, and every crate is
+//! also available as a module for the rest of its API (iterators,
+//! errors, marker types).
+//!
+//! ```toml
+//! [dependencies]
+//! datastructures = { path = "datastructures", default-features = false, features = ["stack", "queue"] }
+//! ```
+// The example needs the structures it uses, which the features may leave
+// out.
+#![cfg_attr(
+  all(feature = "linked-list", feature = "queue", feature = "stack"),
+  doc = r#"
+# Example
+
+```
+use datastructures::{LinkedList, Queue, Stack};
+
+let mut stack: Stack<i32> = (1..=3).collect();
+let mut queue = Queue::new();
+
+while let Some(value) = stack.pop() {
+  queue.enqueue(value);
+}
+
+let list: LinkedList<i32> = queue.iter().copied().collect();
+assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+```
+"#
+)]
+
+#[cfg(feature = "bitset")]
+pub use bitset::{self, BitSet, RankSelect};
+#[cfg(feature = "bst")]
+pub use bst::{self, BinarySearchTree, ImplicitTreap, IntervalTree, OrderStatisticsTree, SplayTree, Treap};
+#[cfg(feature = "btree")]
+pub use btree::{self, BTreeMap};
+#[cfg(feature = "cache")]
+pub use cache::{self, LfuCache, LruCache};
+#[cfg(feature = "collections-traits")]
+pub use collections_traits::{self, Map, QueueLike, Set, StackLike};
+#[cfg(feature = "concurrent")]
+pub use concurrent::{self, ConcurrentStack, SyncQueue};
+#[cfg(feature = "deque")]
+pub use deque::{self, Deque, SlidingWindowMax};
+#[cfg(feature = "disjoint-set")]
+pub use disjoint_set::{self, DisjointSet, DisjointSetMap};
+#[cfg(feature = "doubly-linked-list")]
+pub use doubly_linked_list::{self, DoublyLinkedList};
+#[cfg(feature = "fenwick-tree")]
+pub use fenwick_tree::{self, FenwickTree};
+#[cfg(feature = "gap-buffer")]
+pub use gap_buffer::{self, GapBuffer};
+#[cfg(feature = "graph")]
+pub use graph::{self, AdjacencyMatrixGraph, DiGraph, Graph, GraphOptions, UnGraph};
+#[cfg(feature = "hashmap")]
+pub use hashmap::{self, HashMap};
+#[cfg(feature = "hashset")]
+pub use hashset::{self, HashSet};
+#[cfg(feature = "heap")]
+pub use heap::{self, BinaryHeap, DAryHeap, MaxHeap, MinHeap, PairingHeap};
+#[cfg(feature = "linked-list")]
+pub use linked_list::{self, ArenaLinkedList, LinkedList};
+#[cfg(feature = "matrix")]
+pub use matrix::{self, CooMatrix, Grid, Matrix, SparseMatrix};
+#[cfg(feature = "multi")]
+pub use multi::{self, MultiMap, MultiSet};
+#[cfg(feature = "persistent-list")]
+pub use persistent_list::{self, PersistentList, PersistentMap, PersistentVector};
+#[cfg(feature = "probabilistic")]
+pub use probabilistic::{
+  self, ApproximateMembership, BloomFilter, CountMinSketch, CountingBloomFilter, CuckooFilter, HyperLogLog,
+};
+#[cfg(feature = "queue")]
+pub use queue::{self, Queue};
+#[cfg(feature = "ring-buffer")]
+pub use ring_buffer::{self, OverflowPolicy, RingBuffer};
+#[cfg(feature = "rope")]
+pub use rope::{self, Rope};
+#[cfg(feature = "segment-tree")]
+pub use segment_tree::{self, LazySegmentTree, SegmentTree};
+#[cfg(feature = "skiplist")]
+pub use skiplist::{self, SkipList};
+#[cfg(feature = "slab")]
+pub use slab::{self, Slab};
+#[cfg(feature = "sorting")]
+pub use sorting::{self, HeapSort, InsertionSort, MergeSort, QuickSort, Sorter};
+#[cfg(feature = "spatial")]
+pub use spatial::{self, KdTree, Octree, QuadTree, Region, RegionTree};
+#[cfg(feature = "stack")]
+pub use stack::{self, ArrayStack, GenericStack, History, MinStack, MonotonicStack, SmallStack, Stack, TwoStackQueue};
+#[cfg(feature = "strings")]
+pub use strings::{self, AhoCorasick, RadixTrie, SuffixArray};
+#[cfg(feature = "tree")]
+pub use tree::{self, BinaryTree, NodeId, Tree};
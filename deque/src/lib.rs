@@ -0,0 +1,434 @@
+use std::ops::{Index, IndexMut};
+
+mod window;
+
+pub use window::SlidingWindowMax;
+
+
+/// A generic double-ended queue data structure.
+///
+/// This struct represents a deque, which allows elements to be added
+/// and removed at both the front and the back in (amortized) constant
+/// time. This implementation uses a growable ring buffer internally,
+/// so it can serve both as a stack (LIFO) and as a queue (FIFO).
+///
+/// # Example
+///
+/// ```
+/// use deque::Deque;
+///
+/// let mut deque = Deque::<i32>::new();
+/// deque.push_back(7);
+/// deque.push_back(32);
+/// deque.push_front(1);
+///
+/// // item: 1, item: 7, item: 32
+/// for item in deque.iter() {
+///   println!("item: {item}");
+/// }
+///
+/// assert_eq!(deque[1], 7);
+/// assert_eq!(deque.pop_front(), Some(1));
+/// assert_eq!(deque.pop_back(), Some(32));
+/// assert_eq!(deque.len(), 1);
+/// ```
+pub struct Deque<T> {
+  /// Ring buffer holding the elements, `None` marks a free slot.
+  buffer: Vec<Option<T>>,
+  /// Index of the front element in the buffer.
+  head: usize,
+  /// Number of elements in the deque.
+  len: usize
+}
+
+impl<T> Default for Deque<T> {
+  /// Creates a new instance of `Deque` with default values.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use deque::Deque;
+  ///
+  /// let deque = Deque::<i32>::default();
+  /// ```
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Deque<T> {
+  /// Creates a new empty instance of `Deque`.
+  pub fn new() -> Self {
+    Self { buffer: Vec::new(), head: 0, len: 0 }
+  }
+
+  /// Creates a new empty instance of `Deque` that can hold at least
+  /// `capacity` elements without reallocating.
+  pub fn with_capacity(capacity: usize) -> Self {
+    let mut buffer = Vec::with_capacity(capacity);
+    buffer.resize_with(capacity, || None);
+
+    Self { buffer, head: 0, len: 0 }
+  }
+
+  /// Adds a value to the front of the deque.
+  pub fn push_front(&mut self, value: T) {
+    if self.len == self.buffer.len() {
+      self.grow();
+    }
+
+    self.head = (self.head + self.buffer.len() - 1) % self.buffer.len();
+    self.buffer[self.head] = Some(value);
+    self.len += 1;
+  }
+
+  /// Adds a value to the back of the deque.
+  pub fn push_back(&mut self, value: T) {
+    if self.len == self.buffer.len() {
+      self.grow();
+    }
+
+    let tail = self.position(self.len);
+    self.buffer[tail] = Some(value);
+    self.len += 1;
+  }
+
+  /// Removes the front value from the deque and returns it,
+  /// or `None` if the deque is empty.
+  pub fn pop_front(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let value = self.buffer[self.head].take();
+    self.head = (self.head + 1) % self.buffer.len();
+    self.len -= 1;
+
+    value
+  }
+
+  /// Removes the back value from the deque and returns it,
+  /// or `None` if the deque is empty.
+  pub fn pop_back(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    self.len -= 1;
+    let tail = self.position(self.len);
+
+    self.buffer[tail].take()
+  }
+
+  /// Returns a reference to the front value of the deque,
+  /// or `None` if the deque is empty.
+  pub fn front(&self) -> Option<&T> {
+    self.get(0)
+  }
+
+  /// Returns a reference to the back value of the deque,
+  /// or `None` if the deque is empty.
+  pub fn back(&self) -> Option<&T> {
+    self.get(self.len.checked_sub(1)?)
+  }
+
+  /// Returns a reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index >= self.len {
+      return None;
+    }
+
+    self.buffer[self.position(index)].as_ref()
+  }
+
+  /// Returns a mutable reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    if index >= self.len {
+      return None;
+    }
+
+    let position = self.position(index);
+    self.buffer[position].as_mut()
+  }
+
+  /// Returns the number of elements in the deque.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the deque is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns a double-ended iterator over the elements of the deque,
+  /// from front to back. Use `.rev()` to iterate from back to front.
+  pub fn iter(&self) -> DequeIterator<'_, T> {
+    DequeIterator {
+      deque: self,
+      front: 0,
+      back: self.len,
+    }
+  }
+
+  /// Maps a logical index (relative to the front) to a buffer index.
+  fn position(&self, index: usize) -> usize {
+    (self.head + index) % self.buffer.len()
+  }
+
+  /// Doubles the size of the ring buffer, moving the elements
+  /// so that the front of the deque starts at index zero.
+  fn grow(&mut self) {
+    let capacity = (self.buffer.len() * 2).max(1);
+    let mut buffer = Vec::with_capacity(capacity);
+
+    for index in 0..self.len {
+      let position = self.position(index);
+      buffer.push(self.buffer[position].take());
+    }
+
+    buffer.resize_with(capacity, || None);
+
+    self.buffer = buffer;
+    self.head = 0;
+  }
+}
+
+impl<T> Index<usize> for Deque<T> {
+  type Output = T;
+
+  /// Returns a reference to the element at the given position.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  fn index(&self, index: usize) -> &Self::Output {
+    self.get(index).expect("index out of bounds")
+  }
+}
+
+impl<T> IndexMut<usize> for Deque<T> {
+  /// Returns a mutable reference to the element at the given position.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+    self.get_mut(index).expect("index out of bounds")
+  }
+}
+
+/// A double-ended iterator over the elements of a `Deque`.
+pub struct DequeIterator<'a, T> {
+  deque: &'a Deque<T>,
+  front: usize,
+  back: usize,
+}
+
+impl<'a, T> Iterator for DequeIterator<'a, T> {
+  type Item = &'a T;
+
+  /// Advances the iterator and returns the next element from the front,
+  /// or `None` if the iterator is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.front < self.back {
+      self.front += 1;
+      self.deque.get(self.front - 1)
+    } else {
+      None
+    }
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for DequeIterator<'a, T> {
+  /// Advances the iterator and returns the next element from the back,
+  /// or `None` if the iterator is exhausted.
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.front < self.back {
+      self.back -= 1;
+      self.deque.get(self.back)
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Deque<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the front to the back.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Deque<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a deque from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let values = Vec::deserialize(deserializer)?;
+    let mut deque = Self::with_capacity(values.len());
+
+    for value in values {
+      deque.push_back(value);
+    }
+
+    Ok(deque)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Deque;
+  use std::collections::VecDeque;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_deque() {
+    assert!(Deque::<i32>::new().is_empty());
+  }
+
+  #[test]
+  fn push_and_pop_front() {
+    let mut deque = Deque::<i32>::new();
+    deque.push_front(42);
+    deque.push_front(314);
+
+    assert_eq!(deque.len(), 2);
+    assert_eq!(deque.pop_front(), Some(314));
+    assert_eq!(deque.pop_front(), Some(42));
+    assert_eq!(deque.pop_front(), None);
+  }
+
+  #[test]
+  fn push_and_pop_back() {
+    let mut deque = Deque::<i32>::new();
+    deque.push_back(42);
+    deque.push_back(314);
+
+    assert_eq!(deque.len(), 2);
+    assert_eq!(deque.pop_back(), Some(314));
+    assert_eq!(deque.pop_back(), Some(42));
+    assert_eq!(deque.pop_back(), None);
+  }
+
+  #[test]
+  fn front_and_back() {
+    let mut deque = Deque::<i32>::new();
+    assert_eq!(deque.front(), None);
+    assert_eq!(deque.back(), None);
+
+    deque.push_back(42);
+    deque.push_front(7);
+    deque.push_back(314);
+
+    assert_eq!(deque.front(), Some(&7));
+    assert_eq!(deque.back(), Some(&314));
+  }
+
+  #[test]
+  fn index() {
+    let mut deque = Deque::<i32>::with_capacity(2);
+    deque.push_back(42);
+    deque.push_front(7);
+    deque.push_back(314);
+
+    assert_eq!(deque[0], 7);
+    assert_eq!(deque[2], 314);
+    assert_eq!(deque.get(3), None);
+
+    deque[1] = 43;
+    assert_eq!(deque.get(1), Some(&43));
+  }
+
+  #[test]
+  fn iter() {
+    let mut deque = Deque::<i32>::new();
+
+    for value in 0..5 {
+      deque.push_back(value);
+    }
+
+    deque.push_front(-1);
+
+    assert_eq!(deque.iter().copied().collect::<Vec<_>>(), vec![-1, 0, 1, 2, 3, 4]);
+    assert_eq!(deque.iter().rev().copied().collect::<Vec<_>>(), vec![4, 3, 2, 1, 0, -1]);
+
+    let mut iter = deque.iter();
+    assert_eq!(iter.next(), Some(&-1));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.count(), 4);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = Deque::<i32>::new();
+    value.push_back(2);
+    value.push_front(1);
+    value.push_back(3);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let deque: Deque<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deque.iter().collect::<Vec<_>>(), value.iter().collect::<Vec<_>>());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack,
+    Get(usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::PushFront),
+      any::<i32>().prop_map(Operation::PushBack),
+      Just(Operation::PopFront),
+      Just(Operation::PopBack),
+      (0..20usize).prop_map(Operation::Get)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut deque = Deque::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::PushFront(value) => {
+            deque.push_front(value);
+            model.push_front(value);
+          }
+          Operation::PushBack(value) => {
+            deque.push_back(value);
+            model.push_back(value);
+          }
+          Operation::PopFront => prop_assert_eq!(deque.pop_front(), model.pop_front()),
+          Operation::PopBack => prop_assert_eq!(deque.pop_back(), model.pop_back()),
+          Operation::Get(index) => prop_assert_eq!(deque.get(index), model.get(index))
+        }
+
+        prop_assert_eq!(deque.len(), model.len());
+        prop_assert_eq!(deque.front(), model.front());
+        prop_assert_eq!(deque.back(), model.back());
+        prop_assert!(deque.iter().eq(model.iter()));
+        prop_assert!(deque.iter().rev().eq(model.iter().rev()));
+      }
+    }
+  }
+}
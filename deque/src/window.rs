@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::{Deque, DequeIterator};
+
+
+/// A window over the last values of a stream that tracks their maximum.
+///
+/// Next to the values it keeps a monotonic deque of the positions of the
+/// values that can still become the maximum: every value smaller than a
+/// later one leaves the window before it, so it never will. The positions
+/// in the deque thus have non-increasing values from the front, the
+/// maximum being the value at the front. Every position is added and
+/// removed at most once, so `push` and `pop` take amortized O(1) time and
+/// `max` takes O(1), where searching the window would take O(w).
+///
+/// # Example
+///
+/// ```
+/// use deque::SlidingWindowMax;
+///
+/// // The maximum of every three consecutive values.
+/// let mut window = SlidingWindowMax::new(3);
+/// let maxes: Vec<i32> = [1, 3, -1, -3, 5, 3, 6, 7]
+///   .into_iter()
+///   .filter_map(|value| {
+///     window.push(value);
+///     (window.len() == 3).then(|| *window.max().unwrap())
+///   })
+///   .collect();
+///
+/// assert_eq!(maxes, vec![3, 3, 5, 5, 6, 7]);
+/// ```
+pub struct SlidingWindowMax<T> {
+  values: Deque<T>,
+  /// Positions of the values that can still become the maximum, counted
+  /// from the first value ever pushed, in order.
+  candidates: Deque<usize>,
+  /// Number of values that left the window, the position of the front one.
+  popped: usize,
+  size: usize
+}
+
+impl<T: Ord> SlidingWindowMax<T> {
+  /// Creates a new empty window of the last `size` values.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `size` is zero.
+  pub fn new(size: usize) -> Self {
+    assert!(size > 0, "window size must be positive");
+    Self { values: Deque::with_capacity(size), candidates: Deque::new(), popped: 0, size }
+  }
+
+  /// Adds a value to the back of the window.
+  ///
+  /// Returns the front value that left the window to make room for it,
+  /// or `None` if the window was not full.
+  pub fn push(&mut self, value: T) -> Option<T> {
+    let evicted = if self.values.len() == self.size { self.pop() } else { None };
+
+    while self.candidates.back().is_some_and(|&position| *self.value(position) < value) {
+      self.candidates.pop_back();
+    }
+
+    self.candidates.push_back(self.popped + self.values.len());
+    self.values.push_back(value);
+
+    evicted
+  }
+
+  /// Removes the front value, the oldest one, from the window and returns
+  /// it, or `None` if the window is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    let value = self.values.pop_front()?;
+
+    if self.candidates.front() == Some(&self.popped) {
+      self.candidates.pop_front();
+    }
+    self.popped += 1;
+
+    Some(value)
+  }
+
+  /// Returns a reference to the largest value of the window,
+  /// or `None` if the window is empty.
+  ///
+  /// If several values are equal, the oldest one is returned.
+  pub fn max(&self) -> Option<&T> {
+    self.candidates.front().map(|&position| self.value(position))
+  }
+
+  fn value(&self, position: usize) -> &T {
+    &self.values[position - self.popped]
+  }
+}
+
+impl<T> SlidingWindowMax<T> {
+  /// Returns the number of values the window holds when full.
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  /// Returns the number of values in the window.
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Checks if the window is empty.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Returns an iterator over the values of the window, from the oldest
+  /// to the newest.
+  pub fn iter(&self) -> DequeIterator<'_, T> {
+    self.values.iter()
+  }
+}
+
+impl<T: Ord> Extend<T> for SlidingWindowMax<T> {
+  /// Pushes the values in order, dropping the values that leave the
+  /// window.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlidingWindowMax<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::SlidingWindowMax;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_window() {
+    let mut window = SlidingWindowMax::<i32>::new(2);
+
+    assert!(window.is_empty());
+    assert_eq!(window.max(), None);
+    assert_eq!(window.pop(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "window size must be positive")]
+  fn zero_size() {
+    SlidingWindowMax::<i32>::new(0);
+  }
+
+  #[test]
+  fn push_evicts_oldest() {
+    let mut window = SlidingWindowMax::new(2);
+
+    assert_eq!(window.push(5), None);
+    assert_eq!(window.push(1), None);
+    assert_eq!(window.push(2), Some(5));
+    assert_eq!(window.max(), Some(&2));
+    assert_eq!(format!("{:?}", window), "[1, 2]");
+  }
+
+  #[test]
+  fn pop_and_max() {
+    let mut window = SlidingWindowMax::new(5);
+    window.extend([3, 1, 3, 2]);
+
+    assert_eq!(window.max(), Some(&3));
+    assert_eq!(window.pop(), Some(3));
+    assert_eq!(window.max(), Some(&3));
+    assert_eq!(window.pop(), Some(1));
+    assert_eq!(window.pop(), Some(3));
+    assert_eq!(window.max(), Some(&2));
+    assert_eq!(window.iter().collect::<Vec<_>>(), vec![&2]);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i8),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => any::<i8>().prop_map(Operation::Push),
+      1 => Just(Operation::Pop)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec(size in 1..6usize, operations in prop::collection::vec(operation(), 0..100)) {
+      let mut window = SlidingWindowMax::new(size);
+      let mut model = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            model.push(value);
+            let evicted = (model.len() > size).then(|| model.remove(0));
+            prop_assert_eq!(window.push(value), evicted);
+          }
+          Operation::Pop => prop_assert_eq!(window.pop(), (!model.is_empty()).then(|| model.remove(0)))
+        }
+
+        prop_assert_eq!(window.len(), model.len());
+        prop_assert_eq!(window.max(), model.iter().max());
+      }
+    }
+  }
+}
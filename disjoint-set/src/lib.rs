@@ -0,0 +1,466 @@
+use std::hash::Hash;
+
+use hashmap::HashMap;
+
+
+/// A disjoint set (union-find) implementation.
+///
+/// This data structure partitions the elements `0..len()` into disjoint
+/// sets. Every set is a tree whose root is the representative of the set.
+/// Union by rank keeps the trees shallow and path compression flattens
+/// them on every `find`, so any sequence of operations runs in nearly
+/// constant amortized time per operation (inverse Ackermann).
+///
+/// # Example
+///
+/// ```
+/// use disjoint_set::DisjointSet;
+///
+/// let mut sets = DisjointSet::new();
+/// let a = sets.make_set();
+/// let b = sets.make_set();
+/// let c = sets.make_set();
+///
+/// sets.union(a, b);
+///
+/// assert!(sets.same_set(a, b));
+/// assert!(!sets.same_set(a, c));
+/// assert_eq!(sets.set_count(), 2);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DisjointSet {
+  /// Parent of every element; roots are their own parent.
+  parent: Vec<usize>,
+  /// Upper bound of the height of the tree rooted at every element.
+  rank: Vec<u8>,
+  sets: usize
+}
+
+impl Default for DisjointSet {
+  /// Creates a new instance of `DisjointSet` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl DisjointSet {
+  /// Creates a new empty disjoint set.
+  pub fn new() -> Self {
+    Self { parent: Vec::new(), rank: Vec::new(), sets: 0 }
+  }
+
+  /// Creates a disjoint set of the elements `0..len`, each in its own set.
+  pub fn with_len(len: usize) -> Self {
+    Self { parent: (0..len).collect(), rank: vec![0; len], sets: len }
+  }
+
+  /// Returns the number of elements.
+  pub fn len(&self) -> usize {
+    self.parent.len()
+  }
+
+  /// Checks if there are no elements.
+  pub fn is_empty(&self) -> bool {
+    self.parent.is_empty()
+  }
+
+  /// Returns the number of disjoint sets.
+  pub fn set_count(&self) -> usize {
+    self.sets
+  }
+
+  /// Adds a new element in its own set and returns it.
+  pub fn make_set(&mut self) -> usize {
+    self.parent.push(self.parent.len());
+    self.rank.push(0);
+    self.sets += 1;
+
+    self.parent.len() - 1
+  }
+
+  /// Returns the representative of the set containing `element`,
+  /// pointing every element on the way directly at it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `element` is out of bounds.
+  pub fn find(&mut self, element: usize) -> usize {
+    let mut root = element;
+
+    while self.parent[root] != root {
+      root = self.parent[root];
+    }
+
+    let mut current = element;
+
+    while current != root {
+      let next = self.parent[current];
+      self.parent[current] = root;
+      current = next;
+    }
+
+    root
+  }
+
+  /// Merges the sets containing `a` and `b`, attaching the shallower tree
+  /// under the root of the deeper one.
+  ///
+  /// Returns `false` if both elements were already in the same set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either element is out of bounds.
+  pub fn union(&mut self, a: usize, b: usize) -> bool {
+    let a = self.find(a);
+    let b = self.find(b);
+
+    if a == b {
+      return false;
+    }
+
+    let (child, root) = if self.rank[a] < self.rank[b] { (a, b) } else { (b, a) };
+    self.parent[child] = root;
+
+    if self.rank[child] == self.rank[root] {
+      self.rank[root] += 1;
+    }
+
+    self.sets -= 1;
+    true
+  }
+
+  /// Checks if `a` and `b` belong to the same set.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either element is out of bounds.
+  pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+    self.find(a) == self.find(b)
+  }
+}
+
+/// A disjoint set over arbitrary hashable values.
+///
+/// This is a `DisjointSet` combined with a `HashMap` that assigns every
+/// value its element index.
+///
+/// # Example
+///
+/// ```
+/// use disjoint_set::DisjointSetMap;
+///
+/// let mut sets = DisjointSetMap::new();
+/// sets.make_set("a");
+/// sets.make_set("b");
+/// sets.make_set("c");
+///
+/// sets.union(&"a", &"c");
+///
+/// assert!(sets.same_set(&"a", &"c"));
+/// assert_eq!(sets.find(&"c"), Some(&"a"));
+/// assert_eq!(sets.find(&"d"), None);
+/// ```
+pub struct DisjointSetMap<T> {
+  indices: HashMap<T, usize>,
+  values: Vec<T>,
+  sets: DisjointSet
+}
+
+impl<T: Hash + Eq + Clone> Default for DisjointSetMap<T> {
+  /// Creates a new instance of `DisjointSetMap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Hash + Eq + Clone> DisjointSetMap<T> {
+  /// Creates a new empty disjoint set map.
+  pub fn new() -> Self {
+    Self { indices: HashMap::new(), values: Vec::new(), sets: DisjointSet::new() }
+  }
+
+  /// Returns the number of values.
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Checks if there are no values.
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Returns the number of disjoint sets.
+  pub fn set_count(&self) -> usize {
+    self.sets.set_count()
+  }
+
+  /// Adds a value in its own set.
+  ///
+  /// Returns `false` if the value was already present.
+  pub fn make_set(&mut self, value: T) -> bool {
+    if self.indices.contains_key(&value) {
+      return false;
+    }
+
+    self.indices.insert(value.clone(), self.sets.make_set());
+    self.values.push(value);
+
+    true
+  }
+
+  /// Returns the representative of the set containing `value`,
+  /// or `None` if the value is not present.
+  pub fn find(&mut self, value: &T) -> Option<&T> {
+    let index = *self.indices.get(value)?;
+    Some(&self.values[self.sets.find(index)])
+  }
+
+  /// Merges the sets containing `a` and `b`.
+  ///
+  /// Returns `false` if either value is missing or both were already
+  /// in the same set.
+  pub fn union(&mut self, a: &T, b: &T) -> bool {
+    match (self.indices.get(a), self.indices.get(b)) {
+      (Some(&a), Some(&b)) => self.sets.union(a, b),
+      _ => false,
+    }
+  }
+
+  /// Checks if `a` and `b` are present and belong to the same set.
+  pub fn same_set(&mut self, a: &T, b: &T) -> bool {
+    match (self.indices.get(a), self.indices.get(b)) {
+      (Some(&a), Some(&b)) => self.sets.same_set(a, b),
+      _ => false,
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DisjointSet {
+  /// Deserializes the parents and ranks of a disjoint set, checking that
+  /// they form trees whose ranks grow towards the roots.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "DisjointSet")]
+    struct Fields {
+      parent: Vec<usize>,
+      rank: Vec<u8>,
+      sets: usize
+    }
+
+    let Fields { parent, rank, sets } = Fields::deserialize(deserializer)?;
+
+    if rank.len() != parent.len() {
+      return Err(D::Error::invalid_length(rank.len(), &"one rank for every element"));
+    }
+
+    // A tree of rank `r` has at least `2^r` elements, so ranks never exceed
+    // `log2(len)`. Ranks growing along every parent also rule out cycles.
+    let max_rank = parent.len().checked_ilog2().unwrap_or(0);
+    for (element, &parent_element) in parent.iter().enumerate() {
+      if parent_element >= parent.len() {
+        return Err(D::Error::custom("parent is out of bounds"));
+      }
+      if u32::from(rank[element]) > max_rank {
+        return Err(D::Error::custom("rank exceeds the logarithm of the number of elements"));
+      }
+      if parent_element != element && rank[parent_element] <= rank[element] {
+        return Err(D::Error::custom("rank of a parent must be greater than the ranks of its children"));
+      }
+    }
+
+    if sets != parent.iter().enumerate().filter(|&(element, &parent)| element == parent).count() {
+      return Err(D::Error::custom("number of sets does not match the number of roots"));
+    }
+
+    Ok(Self { parent, rank, sets })
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for DisjointSetMap<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the values together with the underlying `DisjointSet`.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(&self.values, &self.sets), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for DisjointSetMap<T>
+where
+  T: serde::Deserialize<'de> + Hash + Eq + Clone,
+{
+  /// Deserializes the values and the underlying `DisjointSet`,
+  /// rebuilding the index of every value and checking that the values are
+  /// distinct.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (values, sets): (Vec<T>, DisjointSet) = serde::Deserialize::deserialize(deserializer)?;
+
+    if values.len() != sets.len() {
+      return Err(D::Error::custom("number of values does not match the number of elements"));
+    }
+
+    let indices: HashMap<T, usize> = values.iter().cloned().zip(0..).collect();
+    if indices.len() != values.len() {
+      return Err(D::Error::custom("values are not distinct"));
+    }
+
+    Ok(Self { indices, values, sets })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{DisjointSet, DisjointSetMap};
+  use proptest::prelude::*;
+
+  #[test]
+  fn make_set() {
+    let mut sets = DisjointSet::new();
+    assert!(sets.is_empty());
+
+    assert_eq!(sets.make_set(), 0);
+    assert_eq!(sets.make_set(), 1);
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets.set_count(), 2);
+    assert!(!sets.same_set(0, 1));
+  }
+
+  #[test]
+  fn union() {
+    let mut sets = DisjointSet::with_len(5);
+
+    assert!(sets.union(0, 1));
+    assert!(sets.union(2, 3));
+    assert!(sets.union(1, 3));
+    assert!(!sets.union(0, 2));
+
+    assert_eq!(sets.set_count(), 2);
+    assert!(sets.same_set(0, 3));
+    assert!(!sets.same_set(0, 4));
+  }
+
+  #[test]
+  fn path_compression() {
+    let mut sets = DisjointSet::with_len(4);
+    sets.parent = vec![0, 0, 1, 2];
+
+    let root = sets.find(3);
+
+    assert_eq!(root, 0);
+    assert_eq!(sets.parent, vec![0, 0, 0, 0]);
+  }
+
+  #[test]
+  fn union_by_rank() {
+    let mut sets = DisjointSet::with_len(3);
+    sets.union(0, 1);
+
+    // The single element joins the deeper tree rooted at 0.
+    sets.union(2, 0);
+    assert_eq!(sets.parent[2], 0);
+    assert_eq!(sets.rank[0], 1);
+  }
+
+  #[test]
+  fn disjoint_set_map() {
+    let mut sets = DisjointSetMap::new();
+
+    for name in ["a", "b", "c", "d"] {
+      assert!(sets.make_set(name.to_string()));
+    }
+
+    assert!(!sets.make_set("a".to_string()));
+    assert!(sets.union(&"a".to_string(), &"b".to_string()));
+    assert!(sets.union(&"c".to_string(), &"d".to_string()));
+    assert!(!sets.union(&"a".to_string(), &"x".to_string()));
+
+    assert_eq!(sets.set_count(), 2);
+    assert!(sets.same_set(&"b".to_string(), &"a".to_string()));
+    assert!(!sets.same_set(&"b".to_string(), &"c".to_string()));
+    assert_eq!(sets.find(&"x".to_string()), None);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = DisjointSetMap::new();
+    value.make_set(1);
+    value.make_set(2);
+    value.make_set(3);
+    value.union(&1, &3);
+
+    let json = serde_json::to_string(&value).unwrap();
+    let mut sets: DisjointSetMap<i32> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(sets.set_count(), 2);
+    assert!(sets.same_set(&3, &1));
+    assert!(!sets.same_set(&1, &2));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_rejects_malformed_sets() {
+    let parse = |json: &str| serde_json::from_str::<DisjointSet>(json);
+
+    assert!(parse(r#"{"parent":[0,0,2],"rank":[1,0,0],"sets":2}"#).is_ok());
+    // A parent out of bounds.
+    assert!(parse(r#"{"parent":[0,3,2],"rank":[1,0,0],"sets":2}"#).is_err());
+    // A cycle of parents.
+    assert!(parse(r#"{"parent":[1,0],"rank":[0,0],"sets":0}"#).is_err());
+    // Ranks that do not match the elements or the trees.
+    assert!(parse(r#"{"parent":[0,0,2],"rank":[1,0],"sets":2}"#).is_err());
+    assert!(parse(r#"{"parent":[0,0,2],"rank":[0,0,0],"sets":2}"#).is_err());
+    assert!(parse(r#"{"parent":[0,1],"rank":[255,255],"sets":2}"#).is_err());
+    // A number of sets that does not match the roots.
+    assert!(parse(r#"{"parent":[0,0,2],"rank":[1,0,0],"sets":3}"#).is_err());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_rejects_duplicate_values() {
+    let parse = |json: &str| serde_json::from_str::<DisjointSetMap<String>>(json);
+
+    assert!(parse(r#"[["a","b"],{"parent":[0,1],"rank":[0,0],"sets":2}]"#).is_ok());
+    assert!(parse(r#"[["a","a"],{"parent":[0,1],"rank":[0,0],"sets":2}]"#).is_err());
+  }
+
+  proptest! {
+    /// Compares `DisjointSet` with a naive model that stores a set label
+    /// for every element and relabels a whole set on each union.
+    #[test]
+    fn behaves_like_labels(len in 1..40usize, unions in prop::collection::vec((0..40usize, 0..40usize), 0..60)) {
+      let mut sets = DisjointSet::with_len(len);
+      let mut labels: Vec<usize> = (0..len).collect();
+
+      for (a, b) in unions {
+        let (a, b) = (a % len, b % len);
+        let (from, to) = (labels[a], labels[b]);
+
+        prop_assert_eq!(sets.union(a, b), from != to);
+        for label in labels.iter_mut().filter(|label| **label == from) {
+          *label = to;
+        }
+
+        let mut distinct = labels.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        prop_assert_eq!(sets.set_count(), distinct.len());
+      }
+
+      for a in 0..len {
+        for b in 0..len {
+          prop_assert_eq!(sets.same_set(a, b), labels[a] == labels[b]);
+        }
+      }
+    }
+  }
+}
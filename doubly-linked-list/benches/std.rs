@@ -0,0 +1,97 @@
+//! Compares `DoublyLinkedList` against `std::collections::LinkedList` from the standard library
+//! with throughput numbers for push, pop and iterate at several sizes.
+//!
+//! Run with `cargo bench -p doubly-linked-list --bench std`.
+
+use std::hint::black_box;
+use std::collections::LinkedList;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use doubly_linked_list::DoublyLinkedList;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("doubly_linked_list/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("DoublyLinkedList", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = DoublyLinkedList::new();
+        for i in 0..size {
+          ours.push_back(black_box(i));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::LinkedList", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = LinkedList::new();
+        for i in 0..size {
+          std.push_back(black_box(i));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("doubly_linked_list/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("DoublyLinkedList", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<DoublyLinkedList<_>>(),
+        |mut ours| {
+          for _ in 0..size {
+            black_box(ours.pop_front());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::LinkedList", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<LinkedList<_>>(),
+        |mut std| {
+          for _ in 0..size {
+            black_box(std.pop_front());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("doubly_linked_list/iterate");
+
+  for size in SIZES {
+    let ours: DoublyLinkedList<_> = (0..size).collect();
+    let std: LinkedList<_> = (0..size).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("DoublyLinkedList", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::LinkedList", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, iterate);
+criterion_main!(benches);
@@ -0,0 +1,519 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+
+/// A doubly linked list implementation.
+///
+/// This data structure represents a doubly linked list, where each element
+/// (node) contains a value, a pointer to the previous element and a pointer
+/// to the next element in the list. The list keeps track of both its head
+/// and its tail, so elements can be added and removed at either end in
+/// constant time, and the list can be traversed in both directions.
+///
+/// Nodes are linked with raw pointers internally, but the public API is
+/// entirely safe.
+///
+/// # Example
+///
+/// ```
+/// use doubly_linked_list::DoublyLinkedList;
+///
+/// let mut list = DoublyLinkedList::<i32>::new();
+/// list.push_back(2);
+/// list.push_back(3);
+/// list.push_front(1);
+///
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+///
+/// assert_eq!(list.pop_front(), Some(1));
+/// assert_eq!(list.pop_back(), Some(3));
+/// assert_eq!(list.len(), 1);
+/// ```
+pub struct DoublyLinkedList<T> {
+  head: Link<T>,
+  tail: Link<T>,
+  len: usize,
+  /// Tells the compiler that the list owns its nodes.
+  marker: PhantomData<Box<Node<T>>>
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// Represents a node in a doubly linked list.
+struct Node<T> {
+  /// The value stored in the node.
+  value: T,
+  /// Pointer to the previous node in the list.
+  prev: Link<T>,
+  /// Pointer to the next node in the list.
+  next: Link<T>
+}
+
+// The list owns its values just like `Box` does, so it is safe to send
+// or share it between threads whenever `T` is.
+unsafe impl<T: Send> Send for DoublyLinkedList<T> {}
+unsafe impl<T: Sync> Sync for DoublyLinkedList<T> {}
+
+impl<T> Default for DoublyLinkedList<T> {
+  /// Creates a new instance of `DoublyLinkedList` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> DoublyLinkedList<T> {
+  /// Creates a new empty doubly linked list.
+  pub fn new() -> Self {
+    Self { head: None, tail: None, len: 0, marker: PhantomData }
+  }
+
+  /// Checks if the list is empty.
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Returns the number of elements in the list.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Inserts a new element at the beginning of the list.
+  pub fn push_front(&mut self, value: T) {
+    let node = Box::new(Node { value, prev: None, next: self.head });
+    let node = NonNull::from(Box::leak(node));
+
+    match self.head {
+      // SAFETY: `head` points to a live node owned by this list.
+      Some(head) => unsafe { (*head.as_ptr()).prev = Some(node) },
+      None => self.tail = Some(node),
+    }
+
+    self.head = Some(node);
+    self.len += 1;
+  }
+
+  /// Appends a new element at the end of the list.
+  pub fn push_back(&mut self, value: T) {
+    let node = Box::new(Node { value, prev: self.tail, next: None });
+    let node = NonNull::from(Box::leak(node));
+
+    match self.tail {
+      // SAFETY: `tail` points to a live node owned by this list.
+      Some(tail) => unsafe { (*tail.as_ptr()).next = Some(node) },
+      None => self.head = Some(node),
+    }
+
+    self.tail = Some(node);
+    self.len += 1;
+  }
+
+  /// Removes the first element of the list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_front(&mut self) -> Option<T> {
+    self.head.map(|head| {
+      // SAFETY: `head` was created by `Box::leak` and is unlinked below,
+      // so ownership is taken back exactly once.
+      let node = unsafe { Box::from_raw(head.as_ptr()) };
+      self.head = node.next;
+
+      match self.head {
+        // SAFETY: the new head is a live node owned by this list.
+        Some(head) => unsafe { (*head.as_ptr()).prev = None },
+        None => self.tail = None,
+      }
+
+      self.len -= 1;
+      node.value
+    })
+  }
+
+  /// Removes the last element of the list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_back(&mut self) -> Option<T> {
+    self.tail.map(|tail| {
+      // SAFETY: `tail` was created by `Box::leak` and is unlinked below,
+      // so ownership is taken back exactly once.
+      let node = unsafe { Box::from_raw(tail.as_ptr()) };
+      self.tail = node.prev;
+
+      match self.tail {
+        // SAFETY: the new tail is a live node owned by this list.
+        Some(tail) => unsafe { (*tail.as_ptr()).next = None },
+        None => self.head = None,
+      }
+
+      self.len -= 1;
+      node.value
+    })
+  }
+
+  /// Returns a reference to the first element of the list,
+  /// or `None` if the list is empty.
+  pub fn front(&self) -> Option<&T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.head.map(|head| unsafe { &(*head.as_ptr()).value })
+  }
+
+  /// Returns a reference to the last element of the list,
+  /// or `None` if the list is empty.
+  pub fn back(&self) -> Option<&T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.tail.map(|tail| unsafe { &(*tail.as_ptr()).value })
+  }
+
+  /// Returns a double-ended iterator over references to the elements
+  /// of the list.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+  }
+
+  /// Returns a double-ended iterator over mutable references to the
+  /// elements of the list.
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    IterMut { head: self.head, tail: self.tail, len: self.len, marker: PhantomData }
+  }
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
+  /// Drops every node of the list one by one.
+  fn drop(&mut self) {
+    while self.pop_front().is_some() {}
+  }
+}
+
+/// A double-ended iterator over the elements of a `DoublyLinkedList`.
+pub struct Iter<'a, T> {
+  head: Link<T>,
+  tail: Link<T>,
+  /// Number of elements not yet yielded from either end.
+  len: usize,
+  marker: PhantomData<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  /// Advances the iterator and returns the next element from the front,
+  /// or `None` if the iterator is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.head.map(|node| {
+      // SAFETY: the node is live for as long as the list is borrowed.
+      let node = unsafe { &*node.as_ptr() };
+      self.len -= 1;
+      self.head = node.next;
+      &node.value
+    })
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+  /// Advances the iterator and returns the next element from the back,
+  /// or `None` if the iterator is exhausted.
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.tail.map(|node| {
+      // SAFETY: the node is live for as long as the list is borrowed.
+      let node = unsafe { &*node.as_ptr() };
+      self.len -= 1;
+      self.tail = node.prev;
+      &node.value
+    })
+  }
+}
+
+/// A double-ended mutable iterator over the elements of a `DoublyLinkedList`.
+pub struct IterMut<'a, T> {
+  head: Link<T>,
+  tail: Link<T>,
+  /// Number of elements not yet yielded from either end.
+  len: usize,
+  marker: PhantomData<&'a mut Node<T>>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = &'a mut T;
+
+  /// Advances the iterator and returns the next element from the front,
+  /// or `None` if the iterator is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.head.map(|node| {
+      // SAFETY: the list is mutably borrowed and `len` guarantees that
+      // every node is yielded at most once.
+      let node = unsafe { &mut *node.as_ptr() };
+      self.len -= 1;
+      self.head = node.next;
+      &mut node.value
+    })
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+  /// Advances the iterator and returns the next element from the back,
+  /// or `None` if the iterator is exhausted.
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.tail.map(|node| {
+      // SAFETY: the list is mutably borrowed and `len` guarantees that
+      // every node is yielded at most once.
+      let node = unsafe { &mut *node.as_ptr() };
+      self.len -= 1;
+      self.tail = node.prev;
+      &mut node.value
+    })
+  }
+}
+
+/// An owning double-ended iterator over the elements of a `DoublyLinkedList`.
+pub struct IntoIter<T> {
+  list: DoublyLinkedList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.list.pop_front()
+  }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.list.pop_back()
+  }
+}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  /// Consumes the list and returns an iterator over its elements.
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter { list: self }
+  }
+}
+
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+  type Item = &'a mut T;
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+  /// Creates a doubly linked list from an iterator, preserving the order
+  /// of the elements.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut list = Self::new();
+
+    for value in iter {
+      list.push_back(value);
+    }
+
+    list
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for DoublyLinkedList<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the front to the back.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for DoublyLinkedList<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a list from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::DoublyLinkedList;
+  use std::collections::VecDeque;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_list() {
+    let list = DoublyLinkedList::<i32>::new();
+
+    assert!(list.is_empty());
+    assert_eq!(list.front(), None);
+    assert_eq!(list.back(), None);
+  }
+
+  #[test]
+  fn push_front() {
+    let mut list = DoublyLinkedList::<i32>::new();
+    list.push_front(32);
+    list.push_front(64);
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.front(), Some(&64));
+    assert_eq!(list.back(), Some(&32));
+  }
+
+  #[test]
+  fn push_back() {
+    let mut list = DoublyLinkedList::<i32>::new();
+    list.push_back(32);
+    list.push_back(64);
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.front(), Some(&32));
+    assert_eq!(list.back(), Some(&64));
+  }
+
+  #[test]
+  fn pop_front() {
+    let mut list = DoublyLinkedList::<i32>::new();
+    assert_eq!(list.pop_front(), None);
+
+    list.push_back(32);
+    list.push_back(64);
+
+    assert_eq!(list.pop_front(), Some(32));
+    assert_eq!(list.pop_front(), Some(64));
+    assert_eq!(list.pop_front(), None);
+    assert!(list.is_empty());
+  }
+
+  #[test]
+  fn pop_back() {
+    let mut list = DoublyLinkedList::<i32>::new();
+    assert_eq!(list.pop_back(), None);
+
+    list.push_back(32);
+    list.push_back(64);
+
+    assert_eq!(list.pop_back(), Some(64));
+    assert_eq!(list.pop_back(), Some(32));
+    assert_eq!(list.pop_back(), None);
+    assert!(list.is_empty());
+  }
+
+  #[test]
+  fn iter() {
+    let list: DoublyLinkedList<i32> = (1..=4).collect();
+
+    let mut iter = list.iter();
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+  }
+
+  #[test]
+  fn iter_mut() {
+    let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+
+    for value in list.iter_mut().rev() {
+      *value *= 10;
+    }
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![10, 20, 30]);
+  }
+
+  #[test]
+  fn into_iter() {
+    let list: DoublyLinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+
+    assert_eq!(list.into_iter().rev().collect::<Vec<_>>(), vec!["c", "b", "a"]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: DoublyLinkedList<i32> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let list: DoublyLinkedList<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list.iter().collect::<Vec<_>>(), value.iter().collect::<Vec<_>>());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::PushFront),
+      any::<i32>().prop_map(Operation::PushBack),
+      Just(Operation::PopFront),
+      Just(Operation::PopBack)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut list = DoublyLinkedList::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::PushFront(value) => {
+            list.push_front(value);
+            model.push_front(value);
+          }
+          Operation::PushBack(value) => {
+            list.push_back(value);
+            model.push_back(value);
+          }
+          Operation::PopFront => prop_assert_eq!(list.pop_front(), model.pop_front()),
+          Operation::PopBack => prop_assert_eq!(list.pop_back(), model.pop_back())
+        }
+
+        prop_assert_eq!(list.len(), model.len());
+        prop_assert_eq!(list.front(), model.front());
+        prop_assert_eq!(list.back(), model.back());
+        prop_assert!(list.iter().eq(model.iter()));
+        prop_assert!(list.iter().rev().eq(model.iter().rev()));
+      }
+    }
+  }
+}
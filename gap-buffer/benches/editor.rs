@@ -0,0 +1,114 @@
+//! Compares `GapBuffer` against `Vec::insert` and `Vec::remove` on
+//! editor-like workloads over documents of several sizes.
+//!
+//! Someone editing a text moves the cursor a few characters at a time and
+//! types or deletes around it, so the gap rarely moves far while a `Vec`
+//! moves the whole rest of the document on every keystroke.
+//!
+//! Run with `cargo bench -p gap-buffer --bench editor`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gap_buffer::GapBuffer;
+
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+/// Number of keystrokes of every workload.
+const KEYSTROKES: usize = 10_000;
+
+/// A keystroke of an editing session.
+#[derive(Clone, Copy)]
+enum Key {
+  /// Moves the cursor to a position.
+  Move(usize),
+  Type(u8),
+  Backspace
+}
+
+/// Generates keystrokes starting in the middle of a document of `len`
+/// bytes: short cursor moves, words typed and a few deletions.
+fn session(len: usize) -> Vec<Key> {
+  // A xorshift generator with a fixed seed, so every run types the same.
+  let mut state = 0x2545_f491_4f6c_dd1d_u64;
+  let mut random = move || {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    state as usize
+  };
+
+  let (mut keys, mut cursor, mut len) = (Vec::with_capacity(KEYSTROKES), len / 2, len);
+  while keys.len() < KEYSTROKES {
+    match random() % 8 {
+      0 => {
+        cursor = (cursor + random() % 65).saturating_sub(32).min(len);
+        keys.push(Key::Move(cursor));
+      }
+      1 if cursor > 0 => {
+        keys.push(Key::Backspace);
+        (cursor, len) = (cursor - 1, len - 1);
+      }
+      _ => {
+        keys.push(Key::Type(b'a' + (random() % 26) as u8));
+        (cursor, len) = (cursor + 1, len + 1);
+      }
+    }
+  }
+
+  keys
+}
+
+fn typing(c: &mut Criterion) {
+  let mut group = c.benchmark_group("editor/typing");
+  group.sample_size(10);
+
+  for size in SIZES {
+    let document = vec![b'x'; size];
+    let keys = session(size);
+
+    group.throughput(Throughput::Elements(KEYSTROKES as u64));
+    group.bench_with_input(BenchmarkId::new("GapBuffer", size), &keys, |b, keys| {
+      b.iter(|| {
+        let mut ours: GapBuffer<u8> = document.iter().copied().collect();
+        ours.move_gap_to(size / 2);
+        for &key in keys {
+          match key {
+            Key::Move(index) => ours.move_gap_to(index),
+            Key::Type(byte) => ours.insert(black_box(byte)),
+            Key::Backspace => {
+              black_box(ours.remove_before());
+            }
+          }
+        }
+        ours.len()
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("Vec", size), &keys, |b, keys| {
+      b.iter(|| {
+        let mut std = document.clone();
+        let mut cursor = size / 2;
+        for &key in keys {
+          match key {
+            Key::Move(index) => cursor = index,
+            Key::Type(byte) => {
+              std.insert(cursor, black_box(byte));
+              cursor += 1;
+            }
+            Key::Backspace => {
+              cursor -= 1;
+              black_box(std.remove(cursor));
+            }
+          }
+        }
+        std.len()
+      })
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, typing);
+criterion_main!(benches);
@@ -0,0 +1,558 @@
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::{Index, IndexMut};
+use std::ptr;
+use std::slice;
+
+
+/// A buffer with a gap at a movable cursor, for editors.
+///
+/// The elements are stored in one allocation with a gap of free slots in
+/// the middle. The gap sits at the cursor, so inserting and removing
+/// elements around the cursor only fills or widens the gap in O(1),
+/// while a `Vec` moves every element after the position. Moving the
+/// cursor moves the elements between the old and the new position from
+/// one side of the gap to the other, which is cheap for the local edits
+/// of someone typing in a text.
+///
+/// # Example
+///
+/// ```
+/// use gap_buffer::GapBuffer;
+///
+/// let mut buffer: GapBuffer<char> = "helo world".chars().collect();
+/// buffer.move_gap_to(3);
+/// buffer.insert('l');
+/// buffer.move_gap_to(11);
+/// buffer.extend("!?".chars());
+/// buffer.remove_before();
+///
+/// assert_eq!(buffer.iter().collect::<String>(), "hello world!");
+/// assert_eq!(buffer.cursor(), 12);
+///
+/// buffer.move_gap_to(5);
+/// let (before, after) = buffer.as_slices();
+/// assert_eq!(before, ['h', 'e', 'l', 'l', 'o']);
+/// assert_eq!(after, [' ', 'w', 'o', 'r', 'l', 'd', '!']);
+/// ```
+pub struct GapBuffer<T> {
+  /// Slots of the buffer. The slots before `gap_start` and from `gap_end`
+  /// on are initialized.
+  buffer: Box<[MaybeUninit<T>]>,
+  /// Index of the first slot of the gap, which is the cursor.
+  gap_start: usize,
+  /// Index of the first slot after the gap.
+  gap_end: usize
+}
+
+impl<T> Default for GapBuffer<T> {
+  /// Creates a new instance of `GapBuffer` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> GapBuffer<T> {
+  /// Creates a new empty buffer, which allocates on the first insertion.
+  pub fn new() -> Self {
+    Self::with_capacity(0)
+  }
+
+  /// Creates a new empty buffer with room for `capacity` elements.
+  pub fn with_capacity(capacity: usize) -> Self {
+    let buffer = (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+    Self { buffer, gap_start: 0, gap_end: capacity }
+  }
+
+  /// Returns the number of elements the buffer holds without growing.
+  pub fn capacity(&self) -> usize {
+    self.buffer.len()
+  }
+
+  /// Returns the number of elements in the buffer.
+  pub fn len(&self) -> usize {
+    self.capacity() - (self.gap_end - self.gap_start)
+  }
+
+  /// Checks if the buffer is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns the position of the cursor, which is the number of elements
+  /// before it.
+  pub fn cursor(&self) -> usize {
+    self.gap_start
+  }
+
+  /// Moves the cursor, and the gap with it, before the element at `index`,
+  /// in O(d) where d is the distance moved.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is greater than the length of the buffer.
+  pub fn move_gap_to(&mut self, index: usize) {
+    assert!(index <= self.len(), "index out of bounds");
+    let slots = self.buffer.as_mut_ptr();
+
+    // SAFETY: both ranges are in bounds, and the moved elements leave
+    // initialized slots only on the other side of the gap. `ptr::copy`
+    // handles a distance shorter than the gap, where the ranges overlap.
+    unsafe {
+      if index < self.gap_start {
+        let count = self.gap_start - index;
+        ptr::copy(slots.add(index), slots.add(self.gap_end - count), count);
+        self.gap_end -= count;
+      } else {
+        let count = index - self.gap_start;
+        ptr::copy(slots.add(self.gap_end), slots.add(self.gap_start), count);
+        self.gap_end += count;
+      }
+    }
+
+    self.gap_start = index;
+  }
+
+  /// Inserts a value before the cursor, which moves past it.
+  pub fn insert(&mut self, value: T) {
+    if self.gap_start == self.gap_end {
+      self.grow();
+    }
+
+    self.buffer[self.gap_start].write(value);
+    self.gap_start += 1;
+  }
+
+  /// Removes the element before the cursor and returns it, like the
+  /// backspace key, or `None` if the cursor is at the start.
+  pub fn remove_before(&mut self) -> Option<T> {
+    if self.gap_start == 0 {
+      return None;
+    }
+
+    self.gap_start -= 1;
+    // SAFETY: the slot was before the gap, so it is initialized, and it
+    // is part of the gap afterwards.
+    Some(unsafe { self.buffer[self.gap_start].assume_init_read() })
+  }
+
+  /// Removes the element after the cursor and returns it, like the delete
+  /// key, or `None` if the cursor is at the end.
+  pub fn remove_after(&mut self) -> Option<T> {
+    if self.gap_end == self.capacity() {
+      return None;
+    }
+
+    self.gap_end += 1;
+    // SAFETY: the slot was after the gap, so it is initialized, and it is
+    // part of the gap afterwards.
+    Some(unsafe { self.buffer[self.gap_end - 1].assume_init_read() })
+  }
+
+  /// Returns a reference to the element at `index`, or `None` if the
+  /// index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    let (before, after) = self.as_slices();
+    before.get(index).or_else(|| after.get(index - before.len()))
+  }
+
+  /// Returns a mutable reference to the element at `index`, or `None` if
+  /// the index is out of bounds.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    let (before, after) = self.as_mut_slices();
+    let len = before.len();
+    before.get_mut(index).or_else(|| after.get_mut(index - len))
+  }
+
+  /// Returns the elements before and after the cursor as two slices.
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let (before, after) = (&self.buffer[..self.gap_start], &self.buffer[self.gap_end..]);
+
+    // SAFETY: both slices only cover initialized slots, and
+    // `MaybeUninit<T>` has the same layout as `T`.
+    unsafe {
+      (
+        slice::from_raw_parts(before.as_ptr().cast(), before.len()),
+        slice::from_raw_parts(after.as_ptr().cast(), after.len())
+      )
+    }
+  }
+
+  /// Returns the elements before and after the cursor as two mutable
+  /// slices.
+  pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+    let (before, rest) = self.buffer.split_at_mut(self.gap_start);
+    let after = &mut rest[self.gap_end - self.gap_start..];
+
+    // SAFETY: both slices only cover initialized slots, and
+    // `MaybeUninit<T>` has the same layout as `T`.
+    unsafe {
+      (
+        slice::from_raw_parts_mut(before.as_mut_ptr().cast(), before.len()),
+        slice::from_raw_parts_mut(after.as_mut_ptr().cast(), after.len())
+      )
+    }
+  }
+
+  /// Removes all elements from the buffer and moves the cursor to the
+  /// start.
+  pub fn clear(&mut self) {
+    let (before, after) = self.as_mut_slices();
+    let (before, after): (*mut [T], *mut [T]) = (before, after);
+    // Empties the buffer first, so a panicking `drop` leaks the elements
+    // instead of dropping them twice.
+    (self.gap_start, self.gap_end) = (0, self.capacity());
+
+    // SAFETY: the slices were initialized and are no longer part of the
+    // buffer.
+    unsafe {
+      ptr::drop_in_place(before);
+      ptr::drop_in_place(after);
+    }
+  }
+
+  /// Returns an iterator over the elements of the buffer, ignoring the
+  /// cursor.
+  pub fn iter(&self) -> Iter<'_, T> {
+    let (before, after) = self.as_slices();
+    Iter { before: before.iter(), after: after.iter() }
+  }
+
+  /// Doubles the capacity of the buffer, keeping the gap at the cursor.
+  fn grow(&mut self) {
+    let capacity = (self.capacity() * 2).max(8);
+    let mut buffer: Box<[MaybeUninit<T>]> = (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+    let after = self.capacity() - self.gap_end;
+
+    // SAFETY: the initialized slots are moved to the same side of the gap
+    // in the new buffer, and the old buffer does not drop its slots.
+    unsafe {
+      ptr::copy_nonoverlapping(self.buffer.as_ptr(), buffer.as_mut_ptr(), self.gap_start);
+      ptr::copy_nonoverlapping(self.buffer.as_ptr().add(self.gap_end), buffer.as_mut_ptr().add(capacity - after), after);
+    }
+
+    self.buffer = buffer;
+    self.gap_end = capacity - after;
+  }
+}
+
+impl<T> Drop for GapBuffer<T> {
+  /// Drops the elements of the buffer.
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<T> Index<usize> for GapBuffer<T> {
+  type Output = T;
+
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  fn index(&self, index: usize) -> &Self::Output {
+    self.get(index).expect("index out of bounds")
+  }
+}
+
+impl<T> IndexMut<usize> for GapBuffer<T> {
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+    self.get_mut(index).expect("index out of bounds")
+  }
+}
+
+impl<T: Clone> Clone for GapBuffer<T> {
+  /// Clones the elements and the position of the cursor.
+  fn clone(&self) -> Self {
+    let (before, after) = self.as_slices();
+    let mut buffer = Self::with_capacity(self.len());
+
+    buffer.extend(after.iter().cloned());
+    buffer.move_gap_to(0);
+    buffer.extend(before.iter().cloned());
+
+    buffer
+  }
+}
+
+impl<T: PartialEq> PartialEq for GapBuffer<T> {
+  /// Compares the elements, regardless of the positions of the cursors.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for GapBuffer<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for GapBuffer<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T> Extend<T> for GapBuffer<T> {
+  /// Inserts the values at the cursor, which moves past them.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.insert(value);
+    }
+  }
+}
+
+impl<T> FromIterator<T> for GapBuffer<T> {
+  /// Creates a buffer with the cursor at the end.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut buffer = Self::new();
+    buffer.extend(iter);
+    buffer
+  }
+}
+
+/// A double-ended iterator over the elements of a `GapBuffer`.
+pub struct Iter<'a, T> {
+  before: slice::Iter<'a, T>,
+  after: slice::Iter<'a, T>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.before.next().or_else(|| self.after.next())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.before.len() + self.after.len();
+    (len, Some(len))
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.after.next_back().or_else(|| self.before.next_back())
+  }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a GapBuffer<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for GapBuffer<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the position of the cursor together with the elements.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let values: Vec<&T> = self.iter().collect();
+    serde::Serialize::serialize(&(self.cursor(), values), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for GapBuffer<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes the position of the cursor and the elements of a buffer.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (cursor, values): (usize, Vec<T>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if cursor > values.len() {
+      return Err(D::Error::custom("cursor exceeds the number of values"));
+    }
+
+    let mut buffer = Self::with_capacity(values.len());
+    buffer.extend(values);
+    buffer.move_gap_to(cursor);
+
+    Ok(buffer)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::GapBuffer;
+  use proptest::prelude::*;
+  use std::rc::Rc;
+
+  #[test]
+  fn empty_buffer() {
+    let mut buffer = GapBuffer::<i32>::new();
+
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.capacity(), 0);
+    assert_eq!(buffer.cursor(), 0);
+    assert_eq!(buffer.remove_before(), None);
+    assert_eq!(buffer.remove_after(), None);
+    assert_eq!(buffer.get(0), None);
+  }
+
+  #[test]
+  fn insert_and_remove_at_cursor() {
+    let mut buffer = GapBuffer::new();
+    buffer.extend([1, 2, 3, 4]);
+    buffer.move_gap_to(2);
+    buffer.insert(10);
+
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![1, 2, 10, 3, 4]);
+    assert_eq!(buffer.cursor(), 3);
+    assert_eq!(buffer.remove_after(), Some(3));
+    assert_eq!(buffer.remove_before(), Some(10));
+    assert_eq!(buffer.remove_before(), Some(2));
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![1, 4]);
+    assert_eq!(buffer.cursor(), 1);
+  }
+
+  #[test]
+  fn move_gap_to() {
+    let mut buffer: GapBuffer<i32> = (0..10).collect();
+
+    for index in [0, 7, 3, 3, 10, 1] {
+      buffer.move_gap_to(index);
+      let (before, after) = buffer.as_slices();
+
+      assert_eq!(buffer.cursor(), index);
+      assert_eq!(before, (0..index as i32).collect::<Vec<_>>());
+      assert_eq!(after, (index as i32..10).collect::<Vec<_>>());
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn move_gap_out_of_bounds() {
+    let mut buffer: GapBuffer<i32> = (0..3).collect();
+    buffer.move_gap_to(4);
+  }
+
+  #[test]
+  fn grow_keeps_gap_at_cursor() {
+    let mut buffer = GapBuffer::with_capacity(2);
+    buffer.insert(1);
+    buffer.insert(2);
+    buffer.move_gap_to(1);
+    buffer.insert(3);
+
+    assert!(buffer.capacity() > 2);
+    assert_eq!(buffer.as_slices(), (&[1, 3][..], &[2][..]));
+  }
+
+  #[test]
+  fn index_and_get_mut() {
+    let mut buffer: GapBuffer<i32> = (0..5).collect();
+    buffer.move_gap_to(2);
+
+    buffer[1] *= 10;
+    *buffer.get_mut(3).unwrap() *= 10;
+    assert_eq!(buffer[1], 10);
+    assert_eq!(buffer[3], 30);
+    assert_eq!(buffer.get(5), None);
+    assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), vec![4, 30, 2, 10, 0]);
+  }
+
+  #[test]
+  fn clone_keeps_cursor() {
+    let mut buffer: GapBuffer<i32> = (0..5).collect();
+    buffer.move_gap_to(2);
+    let clone = buffer.clone();
+
+    assert_eq!(clone, buffer);
+    assert_eq!(clone.as_slices(), buffer.as_slices());
+  }
+
+  #[test]
+  fn drop_values() {
+    let value = Rc::new(());
+    let mut buffer: GapBuffer<Rc<()>> = (0..4).map(|_| Rc::clone(&value)).collect();
+    buffer.move_gap_to(1);
+    drop(buffer.remove_after());
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&value), 1);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value: GapBuffer<i32> = (1..=4).collect();
+    value.move_gap_to(1);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,[1,2,3,4]]");
+
+    let buffer: GapBuffer<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(buffer, value);
+    assert_eq!(buffer.cursor(), 1);
+
+    assert!(serde_json::from_str::<GapBuffer<i32>>("[3,[1,2]]").is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(i32),
+    MoveGap(usize),
+    RemoveBefore,
+    RemoveAfter
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => any::<i32>().prop_map(Operation::Insert),
+      1 => any::<usize>().prop_map(Operation::MoveGap),
+      1 => Just(Operation::RemoveBefore),
+      1 => Just(Operation::RemoveAfter)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut buffer = GapBuffer::new();
+      let mut model = Vec::new();
+      let mut cursor = 0;
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(value) => {
+            buffer.insert(value);
+            model.insert(cursor, value);
+            cursor += 1;
+          }
+          Operation::MoveGap(index) => {
+            cursor = index % (model.len() + 1);
+            buffer.move_gap_to(cursor);
+          }
+          Operation::RemoveBefore => {
+            let removed = (cursor > 0).then(|| {
+              cursor -= 1;
+              model.remove(cursor)
+            });
+            prop_assert_eq!(buffer.remove_before(), removed);
+          }
+          Operation::RemoveAfter => {
+            let removed = (cursor < model.len()).then(|| model.remove(cursor));
+            prop_assert_eq!(buffer.remove_after(), removed);
+          }
+        }
+
+        prop_assert_eq!(buffer.len(), model.len());
+        prop_assert_eq!(buffer.cursor(), cursor);
+        prop_assert!(buffer.iter().eq(model.iter()));
+      }
+    }
+  }
+}
@@ -0,0 +1,66 @@
+//! Compares the parallel breadth-first search `Graph::par_bfs` against the
+//! sequential `Graph::bfs` on large random graphs.
+//!
+//! Every node has a few random neighbors, so the levels quickly become
+//! wide and the parallel search has enough nodes to split between the
+//! threads. On small graphs the sequential search wins, as the cost of
+//! spreading every level over the threads dominates.
+//!
+//! Run with `cargo bench -p graph --bench parallel --features rayon`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use graph::{DiGraph, NodeId};
+
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+/// Number of edges leaving every node.
+const DEGREE: usize = 8;
+
+/// Adds `len` nodes and `DEGREE` edges from every node to random ones.
+fn random(len: usize) -> DiGraph<(), ()> {
+  let mut graph = DiGraph::new();
+  let ids: Vec<NodeId> = (0..len).map(|_| graph.add_node(())).collect();
+  // A xorshift generator with a fixed seed, so every run uses the same graph.
+  let mut state = 0x2545_f491_4f6c_dd1d_u64;
+
+  for &source in &ids {
+    for _ in 0..DEGREE {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+
+      let target = ids[(state % len as u64) as usize];
+      if target != source {
+        graph.add_edge(source, target, ());
+      }
+    }
+  }
+
+  graph
+}
+
+fn bfs(c: &mut Criterion) {
+  let mut group = c.benchmark_group("parallel/bfs");
+  group.sample_size(10);
+
+  for size in SIZES {
+    let graph = random(size);
+    let start = NodeId::new(0);
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("bfs", size), &graph, |b, graph| {
+      b.iter(|| graph.bfs(black_box(start)).count())
+    });
+    group.bench_with_input(BenchmarkId::new("par_bfs", size), &graph, |b, graph| {
+      b.iter(|| graph.par_bfs(black_box(start)).len())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, bfs);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+//! Compares the adjacency list `Graph` against `AdjacencyMatrixGraph`.
+//!
+//! The matrix wins when the graph is dense: adding an edge and checking
+//! whether two nodes are connected take O(1) time, while the list has to
+//! scan the edges of a node. The list wins when the graph is sparse:
+//! iterating the neighbors of a node takes time proportional to its
+//! degree, while the matrix scans a whole row.
+//!
+//! Run with `cargo bench -p graph --bench representation`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use graph::{traits, AdjacencyMatrixGraph, DiGraph, Directed, NodeId};
+
+
+const DENSE_SIZES: [usize; 3] = [16, 64, 256];
+const SPARSE_SIZES: [usize; 3] = [100, 1_000, 4_000];
+
+/// Number of edges leaving every node of a sparse graph.
+const DEGREE: usize = 4;
+
+/// Adds `len` nodes and an edge between every two of them.
+fn dense<G: traits::Graph<NodeWeight = (), EdgeWeight = ()>>(mut graph: G, len: usize) -> G {
+  let ids: Vec<NodeId> = (0..len).map(|_| graph.add_node(())).collect();
+
+  for &source in &ids {
+    for &target in &ids {
+      if source != target {
+        graph.add_edge(source, target, ());
+      }
+    }
+  }
+
+  graph
+}
+
+/// Adds `len` nodes and an edge from every node to the `DEGREE` next ones.
+fn sparse<G: traits::Graph<NodeWeight = (), EdgeWeight = ()>>(mut graph: G, len: usize) -> G {
+  let ids: Vec<NodeId> = (0..len).map(|_| graph.add_node(())).collect();
+
+  for (index, &source) in ids.iter().enumerate() {
+    for offset in 1..=DEGREE {
+      graph.add_edge(source, ids[(index + offset) % len], ());
+    }
+  }
+
+  graph
+}
+
+fn build_dense(c: &mut Criterion) {
+  let mut group = c.benchmark_group("representation/build_dense");
+
+  for size in DENSE_SIZES {
+    group.throughput(Throughput::Elements((size * (size - 1)) as u64));
+    group.bench_with_input(BenchmarkId::new("Graph", size), &size, |b, &size| {
+      b.iter(|| dense(DiGraph::new(), black_box(size)))
+    });
+    group.bench_with_input(BenchmarkId::new("AdjacencyMatrixGraph", size), &size, |b, &size| {
+      b.iter(|| dense(AdjacencyMatrixGraph::<(), (), Directed>::new(), black_box(size)))
+    });
+  }
+
+  group.finish();
+}
+
+/// Checks every pair of nodes of a dense graph for an edge.
+fn count_edges<G: traits::Graph>(graph: &G) -> usize {
+  let ids: Vec<_> = graph.node_ids().collect();
+  ids
+    .iter()
+    .flat_map(|&source| ids.iter().map(move |&target| (source, target)))
+    .filter(|&(source, target)| graph.contains_edge(source, target))
+    .count()
+}
+
+fn contains_edge(c: &mut Criterion) {
+  let mut group = c.benchmark_group("representation/contains_edge");
+
+  for size in DENSE_SIZES {
+    let list = dense(DiGraph::new(), size);
+    let matrix = dense(AdjacencyMatrixGraph::<(), (), Directed>::new(), size);
+
+    group.throughput(Throughput::Elements((size * size) as u64));
+    group.bench_with_input(BenchmarkId::new("Graph", size), &list, |b, graph| b.iter(|| count_edges(graph)));
+    group.bench_with_input(BenchmarkId::new("AdjacencyMatrixGraph", size), &matrix, |b, graph| {
+      b.iter(|| count_edges(graph))
+    });
+  }
+
+  group.finish();
+}
+
+/// Visits the neighbors of every node.
+fn count_neighbors<G: traits::Graph>(graph: &G) -> usize {
+  graph.node_ids().map(|node| graph.neighbors(node).count()).sum()
+}
+
+fn neighbors_sparse(c: &mut Criterion) {
+  let mut group = c.benchmark_group("representation/neighbors_sparse");
+
+  for size in SPARSE_SIZES {
+    let list = sparse(DiGraph::new(), size);
+    let matrix = sparse(AdjacencyMatrixGraph::<(), (), Directed>::new(), size);
+
+    group.throughput(Throughput::Elements((size * DEGREE) as u64));
+    group.bench_with_input(BenchmarkId::new("Graph", size), &list, |b, graph| b.iter(|| count_neighbors(graph)));
+    group.bench_with_input(BenchmarkId::new("AdjacencyMatrixGraph", size), &matrix, |b, graph| {
+      b.iter(|| count_neighbors(graph))
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, build_dense, contains_edge, neighbors_sparse);
+criterion_main!(benches);
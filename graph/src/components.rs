@@ -0,0 +1,149 @@
+use crate::{Directed, Graph, NodeId};
+
+
+impl<N, E> Graph<N, E, Directed> {
+  /// Returns the strongly connected components of the graph, the largest
+  /// groups of nodes that can all reach each other, computed with
+  /// Tarjan's algorithm in O(V + E).
+  ///
+  /// Every component is sorted, and the components are in reverse
+  /// topological order: no edge goes from a component to an earlier one.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<(), ()>::new();
+  /// let [a, b, c, d] = [(); 4].map(|_| graph.add_node(()));
+  /// graph.add_edge(a, b, ());
+  /// graph.add_edge(b, a, ());
+  /// graph.add_edge(b, c, ());
+  /// graph.add_edge(c, d, ());
+  /// graph.add_edge(d, c, ());
+  ///
+  /// assert_eq!(graph.strongly_connected_components(), vec![vec![c, d], vec![a, b]]);
+  /// ```
+  pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+    // The order in which the search found every node, and the earliest
+    // node on the stack it can reach.
+    let mut found: Vec<Option<usize>> = vec![None; self.nodes.len()];
+    let mut lowest = vec![0; self.nodes.len()];
+    // Found nodes whose component is not known yet.
+    let mut stack = Vec::new();
+    let mut on_stack = vec![false; self.nodes.len()];
+    let mut components = Vec::new();
+    let mut count = 0;
+
+    for start in self.node_ids() {
+      if found[start.0].is_some() {
+        continue;
+      }
+
+      // The search path, each node with its neighbors left to search.
+      let mut path = Vec::new();
+      let mut discovered = Some(start);
+
+      loop {
+        if let Some(node) = discovered.take() {
+          found[node.0] = Some(count);
+          lowest[node.0] = count;
+          count += 1;
+          stack.push(node);
+          on_stack[node.0] = true;
+          path.push((node, self.neighbors(node)));
+        }
+
+        let Some((node, neighbors)) = path.last_mut() else { break };
+        let node = *node;
+
+        if let Some(next) = neighbors.next() {
+          match found[next.0] {
+            None => discovered = Some(next),
+            Some(order) if on_stack[next.0] => lowest[node.0] = lowest[node.0].min(order),
+            Some(_) => {}
+          }
+          continue;
+        }
+
+        path.pop();
+        if let Some(&(parent, _)) = path.last() {
+          lowest[parent.0] = lowest[parent.0].min(lowest[node.0]);
+        }
+
+        // The node is the first one found of its component, which is on
+        // the stack above it.
+        if Some(lowest[node.0]) == found[node.0] {
+          let first = stack.iter().rposition(|&other| other == node).expect("node is on the stack");
+          let mut component = stack.split_off(first);
+          for member in &component {
+            on_stack[member.0] = false;
+          }
+          component.sort_unstable();
+          components.push(component);
+        }
+      }
+    }
+
+    components
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::DiGraph;
+  use proptest::prelude::*;
+
+  #[test]
+  fn single_nodes() {
+    let mut graph = DiGraph::<(), ()>::new();
+    let [a, b] = [(); 2].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+
+    assert_eq!(graph.strongly_connected_components(), vec![vec![b], vec![a]]);
+    assert!(DiGraph::<(), ()>::new().strongly_connected_components().is_empty());
+  }
+
+  #[test]
+  fn nested_cycles() {
+    // a → b → c → a and c → d → e → d, with f apart.
+    let mut graph = DiGraph::<(), ()>::new();
+    let [a, b, c, d, e, f] = [(); 6].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+    graph.add_edge(b, c, ());
+    graph.add_edge(c, a, ());
+    graph.add_edge(c, d, ());
+    graph.add_edge(d, e, ());
+    graph.add_edge(e, d, ());
+
+    assert_eq!(graph.strongly_connected_components(), vec![vec![d, e], vec![a, b, c], vec![f]]);
+  }
+
+  proptest! {
+    #[test]
+    fn components_are_mutually_reachable(len in 1..10usize, edges in prop::collection::vec((0..10usize, 0..10usize), 0..25)) {
+      let mut graph = DiGraph::<(), ()>::new();
+      let ids: Vec<_> = (0..len).map(|_| graph.add_node(())).collect();
+      for (a, b) in edges {
+        if a < len && b < len && a != b {
+          graph.add_edge(ids[a], ids[b], ());
+        }
+      }
+
+      let components = graph.strongly_connected_components();
+      let component = |node| components.iter().position(|component| component.contains(&node)).unwrap();
+      let reaches = |a, b| graph.dfs(a).any(|node| node == b);
+
+      prop_assert_eq!(components.iter().map(Vec::len).sum::<usize>(), len);
+      for &a in &ids {
+        for &b in &ids {
+          prop_assert_eq!(component(a) == component(b), reaches(a, b) && reaches(b, a));
+          if graph.find_edge(a, b).is_some() {
+            prop_assert!(component(a) >= component(b));
+          }
+        }
+      }
+    }
+  }
+}
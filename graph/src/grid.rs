@@ -0,0 +1,82 @@
+use crate::{Graph, Undirected};
+
+
+impl<E> Graph<(usize, usize), E, Undirected> {
+  /// Creates a graph of a `width` by `height` grid, for example for
+  /// pathfinding demos. Every cell is a node weighted with its `(x, y)`
+  /// coordinates, and every two horizontally or vertically adjacent cells
+  /// are connected by an edge weighted with `cost(cell, neighbor)`, unless
+  /// it returns `None`.
+  ///
+  /// The cells are added row by row, so the cell `(x, y)` has the id
+  /// `NodeId::new(y * width + x)`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::{NodeId, UnGraph};
+  ///
+  /// // Walls are the cells with a `#`.
+  /// let map = ["..#", "#..", "..."];
+  /// let open = |(x, y): (usize, usize)| map[y].as_bytes()[x] == b'.';
+  /// let graph = UnGraph::grid(3, 3, |cell, neighbor| (open(cell) && open(neighbor)).then_some(1));
+  ///
+  /// assert_eq!(graph.node_count(), 9);
+  /// assert_eq!(graph.node_weight(NodeId::new(5)), Some(&(2, 1)));
+  /// assert_eq!(graph.neighbors(NodeId::new(4)).count(), 3);
+  /// ```
+  pub fn grid<F>(width: usize, height: usize, mut cost: F) -> Self
+  where
+    F: FnMut((usize, usize), (usize, usize)) -> Option<E>,
+  {
+    let mut graph = Self::new();
+    let ids: Vec<_> = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|cell| graph.add_node(cell)).collect();
+
+    for y in 0..height {
+      for x in 0..width {
+        let neighbors = [(x + 1 < width).then_some((x + 1, y)), (y + 1 < height).then_some((x, y + 1))];
+
+        for (nx, ny) in neighbors.into_iter().flatten() {
+          if let Some(weight) = cost((x, y), (nx, ny)) {
+            graph.add_edge(ids[y * width + x], ids[ny * width + nx], weight);
+          }
+        }
+      }
+    }
+
+    graph
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{NodeId, UnGraph};
+
+  #[test]
+  fn grid() {
+    let graph = UnGraph::grid(3, 2, |_, _| Some(1));
+    let cell = |x: usize, y: usize| NodeId::new(y * 3 + x);
+
+    assert_eq!(graph.node_count(), 6);
+    // Three horizontal edges per row and three vertical ones.
+    assert_eq!(graph.edge_count(), 7);
+    assert_eq!(graph.node_weight(cell(1, 1)), Some(&(1, 1)));
+    assert_eq!(graph.neighbors(cell(1, 0)).collect::<Vec<_>>(), vec![cell(0, 0), cell(2, 0), cell(1, 1)]);
+  }
+
+  #[test]
+  fn walls_and_costs() {
+    // Leaving the left column costs 5, and the top-right cell is a wall.
+    let graph = UnGraph::grid(3, 2, |(x, y), (nx, ny)| match ((x, y), (nx, ny)) {
+      (_, (2, 0)) | ((2, 0), _) => None,
+      ((0, _), (1, _)) => Some(5),
+      _ => Some(1)
+    });
+    let cell = |x: usize, y: usize| NodeId::new(y * 3 + x);
+
+    assert_eq!(graph.neighbors(cell(2, 0)).count(), 0);
+    assert_eq!(graph.shortest_path(cell(0, 0), cell(2, 1)), Some((7, vec![cell(0, 0), cell(0, 1), cell(1, 1), cell(2, 1)])));
+    assert!(UnGraph::<(usize, usize), u32>::grid(0, 4, |_, _| Some(1)).node_count() == 0);
+  }
+}
@@ -0,0 +1,825 @@
+use std::marker::PhantomData;
+
+use queue::Queue;
+use stack::Stack;
+
+mod components;
+mod grid;
+mod matrix;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod render;
+mod shortest_path;
+mod spanning_tree;
+mod topological;
+pub mod traits;
+
+pub use matrix::{AdjacencyMatrixGraph, MatrixConversionError};
+pub use shortest_path::{NegativeCycleError, ShortestPaths};
+pub use topological::CycleError;
+
+
+/// Identifier of a node in a `Graph`.
+///
+/// Identifiers stay valid until the node is removed and are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId(usize);
+
+/// Identifier of an edge in a `Graph`.
+///
+/// Identifiers stay valid until the edge is removed and are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeId(usize);
+
+impl NodeId {
+  /// Creates the identifier of the node at the given position in a
+  /// graph's storage, which is the position in which it was added.
+  pub fn new(index: usize) -> Self {
+    Self(index)
+  }
+
+  /// Returns the position of the node in the graph's storage.
+  pub fn index(self) -> usize {
+    self.0
+  }
+}
+
+impl EdgeId {
+  /// Returns the position of the edge in the graph's storage.
+  pub fn index(self) -> usize {
+    self.0
+  }
+}
+
+/// Decides whether the edges of a `Graph` have a direction.
+pub trait EdgeType {
+  /// Returns `true` if an edge from `a` to `b` does not connect `b` to `a`.
+  fn is_directed() -> bool;
+}
+
+/// Marker type for a graph whose edges go from a source to a target.
+pub struct Directed;
+
+/// Marker type for a graph whose edges connect both endpoints.
+pub struct Undirected;
+
+impl EdgeType for Directed {
+  fn is_directed() -> bool {
+    true
+  }
+}
+
+impl EdgeType for Undirected {
+  fn is_directed() -> bool {
+    false
+  }
+}
+
+/// Options of a `Graph`, which all default to `false`.
+///
+/// # Example
+///
+/// ```
+/// use graph::{DiGraph, GraphOptions};
+///
+/// let mut graph = DiGraph::<(), &str>::with_options(GraphOptions { parallel_edges: true, self_loops: true });
+/// let a = graph.add_node(());
+/// let b = graph.add_node(());
+/// graph.add_edge(a, b, "road");
+/// graph.add_edge(a, b, "rail");
+/// graph.add_edge(b, b, "loop");
+///
+/// assert_eq!(graph.edges_between(a, b).map(|(_, weight)| *weight).collect::<Vec<_>>(), ["road", "rail"]);
+/// assert_eq!(graph.edge_count(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphOptions {
+  /// Whether adding an edge between nodes that are already connected adds
+  /// another edge, instead of replacing the weight of the existing one.
+  pub parallel_edges: bool,
+  /// Whether an edge may connect a node to itself.
+  pub self_loops: bool
+}
+
+/// A graph with directed edges.
+pub type DiGraph<N, E> = Graph<N, E, Directed>;
+
+/// A graph with undirected edges.
+pub type UnGraph<N, E> = Graph<N, E, Undirected>;
+
+/// A graph implementation based on adjacency lists.
+///
+/// Every node carries a weight of type `N` and every edge a weight of type
+/// `E` (use `()` when no data is needed). Nodes and edges are stored in
+/// vectors and addressed with `NodeId`/`EdgeId` handles, while each node
+/// keeps the list of its incident edges, so iterating the neighbors of a
+/// node takes time proportional to its degree.
+///
+/// Whether edges are directed is selected with the `D` marker type:
+/// `Directed` (the default) or `Undirected`. By default there is at most
+/// one edge between two nodes and self-loops are not allowed, which
+/// `GraphOptions` can change.
+///
+/// # Example
+///
+/// ```
+/// use graph::UnGraph;
+///
+/// let mut graph = UnGraph::<&str, u32>::new();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_edge(a, b, 1);
+/// graph.add_edge(b, c, 2);
+///
+/// assert_eq!(graph.neighbors(b).count(), 2);
+/// assert_eq!(graph.bfs(a).collect::<Vec<_>>(), vec![a, b, c]);
+///
+/// graph.remove_node(b);
+/// assert_eq!(graph.edge_count(), 0);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Graph<N, E, D: EdgeType = Directed> {
+  nodes: Vec<Option<Node<N>>>,
+  edges: Vec<Option<Edge<E>>>,
+  node_count: usize,
+  edge_count: usize,
+  #[cfg_attr(feature = "serde", serde(default))]
+  options: GraphOptions,
+  #[cfg_attr(feature = "serde", serde(skip))]
+  direction: PhantomData<D>
+}
+
+/// Represents a node in a graph.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node<N> {
+  /// The weight stored in the node.
+  weight: N,
+  /// Edges leaving the node (all incident edges for undirected graphs).
+  outgoing: Vec<EdgeId>,
+  /// Edges entering the node (unused for undirected graphs).
+  incoming: Vec<EdgeId>
+}
+
+/// Represents an edge in a graph.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Edge<E> {
+  /// The weight stored in the edge.
+  weight: E,
+  source: NodeId,
+  target: NodeId
+}
+
+impl<N, E, D: EdgeType> Default for Graph<N, E, D> {
+  /// Creates a new instance of `Graph` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<N, E, D: EdgeType> Graph<N, E, D> {
+  /// Creates a new empty graph.
+  pub fn new() -> Self {
+    Self::with_options(GraphOptions::default())
+  }
+
+  /// Creates a new empty graph with the given options.
+  pub fn with_options(options: GraphOptions) -> Self {
+    Self { nodes: Vec::new(), edges: Vec::new(), node_count: 0, edge_count: 0, options, direction: PhantomData }
+  }
+
+  /// Returns the options of the graph.
+  pub fn options(&self) -> GraphOptions {
+    self.options
+  }
+
+  /// Checks if the edges of the graph are directed.
+  pub fn is_directed(&self) -> bool {
+    D::is_directed()
+  }
+
+  /// Returns the number of nodes in the graph.
+  pub fn node_count(&self) -> usize {
+    self.node_count
+  }
+
+  /// Returns the number of edges in the graph.
+  pub fn edge_count(&self) -> usize {
+    self.edge_count
+  }
+
+  /// Adds a node with the given weight and returns its identifier.
+  pub fn add_node(&mut self, weight: N) -> NodeId {
+    self.nodes.push(Some(Node { weight, outgoing: Vec::new(), incoming: Vec::new() }));
+    self.node_count += 1;
+
+    NodeId(self.nodes.len() - 1)
+  }
+
+  /// Removes a node together with all of its edges and returns its weight,
+  /// or `None` if the node does not exist.
+  pub fn remove_node(&mut self, id: NodeId) -> Option<N> {
+    let node = self.nodes.get_mut(id.0)?.take()?;
+    self.node_count -= 1;
+
+    for edge in node.outgoing.into_iter().chain(node.incoming) {
+      self.remove_edge(edge);
+    }
+
+    Some(node.weight)
+  }
+
+  /// Checks if the graph contains the node.
+  pub fn contains_node(&self, id: NodeId) -> bool {
+    self.node(id).is_some()
+  }
+
+  /// Returns a reference to the weight of the node.
+  pub fn node_weight(&self, id: NodeId) -> Option<&N> {
+    self.node(id).map(|node| &node.weight)
+  }
+
+  /// Returns a mutable reference to the weight of the node.
+  pub fn node_weight_mut(&mut self, id: NodeId) -> Option<&mut N> {
+    self.nodes.get_mut(id.0)?.as_mut().map(|node| &mut node.weight)
+  }
+
+  /// Returns an iterator over the identifiers of all nodes.
+  pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+    self.nodes.iter().enumerate().filter(|(_, node)| node.is_some()).map(|(index, _)| NodeId(index))
+  }
+
+  /// Adds an edge from `source` to `target` and returns its identifier.
+  ///
+  /// If the nodes are already connected and the graph does not allow
+  /// parallel edges, the weight of the existing edge is replaced and its
+  /// identifier is returned instead.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either node does not exist, or if `source == target` and the
+  /// graph does not allow self-loops.
+  pub fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> EdgeId {
+    assert!(self.contains_node(source) && self.contains_node(target), "node does not exist");
+    assert!(source != target || self.options.self_loops, "self-loops are not supported");
+
+    if let Some(id) = self.find_edge(source, target).filter(|_| !self.options.parallel_edges) {
+      self.edges[id.0].as_mut().unwrap().weight = weight;
+      return id;
+    }
+
+    let id = EdgeId(self.edges.len());
+    self.edges.push(Some(Edge { weight, source, target }));
+    self.edge_count += 1;
+
+    self.nodes[source.0].as_mut().unwrap().outgoing.push(id);
+
+    // An undirected self-loop is listed once among the edges of its node.
+    let target_node = self.nodes[target.0].as_mut().unwrap();
+    if D::is_directed() {
+      target_node.incoming.push(id);
+    } else if source != target {
+      target_node.outgoing.push(id);
+    }
+
+    id
+  }
+
+  /// Removes an edge and returns its weight, or `None` if the edge
+  /// does not exist.
+  pub fn remove_edge(&mut self, id: EdgeId) -> Option<E> {
+    let edge = self.edges.get_mut(id.0)?.take()?;
+    self.edge_count -= 1;
+
+    if let Some(source) = self.nodes[edge.source.0].as_mut() {
+      source.outgoing.retain(|&edge| edge != id);
+    }
+
+    if let Some(target) = self.nodes[edge.target.0].as_mut() {
+      target.incoming.retain(|&edge| edge != id);
+      target.outgoing.retain(|&edge| edge != id);
+    }
+
+    Some(edge.weight)
+  }
+
+  /// Returns the identifier of the edge connecting `source` to `target`,
+  /// the first one added if there are parallel edges, or `None` if the
+  /// nodes are not connected.
+  pub fn find_edge(&self, source: NodeId, target: NodeId) -> Option<EdgeId> {
+    self.edges_between(source, target).next().map(|(id, _)| id)
+  }
+
+  /// Returns an iterator over all edges connecting `source` to `target` as
+  /// `(edge, weight)` pairs, in the order they were added.
+  pub fn edges_between(&self, source: NodeId, target: NodeId) -> impl Iterator<Item = (EdgeId, &E)> + '_ {
+    self.edges(source).filter(move |&(_, node, _)| node == target).map(|(id, _, weight)| (id, weight))
+  }
+
+  /// Returns a reference to the weight of the edge.
+  pub fn edge_weight(&self, id: EdgeId) -> Option<&E> {
+    self.edge(id).map(|edge| &edge.weight)
+  }
+
+  /// Returns a mutable reference to the weight of the edge.
+  pub fn edge_weight_mut(&mut self, id: EdgeId) -> Option<&mut E> {
+    self.edges.get_mut(id.0)?.as_mut().map(|edge| &mut edge.weight)
+  }
+
+  /// Returns the source and the target of the edge.
+  pub fn edge_endpoints(&self, id: EdgeId) -> Option<(NodeId, NodeId)> {
+    self.edge(id).map(|edge| (edge.source, edge.target))
+  }
+
+  /// Returns an iterator over the nodes reachable from `id` through a
+  /// single edge. For undirected graphs these are all adjacent nodes.
+  pub fn neighbors(&self, id: NodeId) -> Neighbors<'_, N, E, D> {
+    Neighbors { edges: self.edges(id) }
+  }
+
+  /// Returns an iterator over the edges leaving `id` as
+  /// `(edge, neighbor, weight)` triples.
+  pub fn edges(&self, id: NodeId) -> Edges<'_, N, E, D> {
+    let ids = self.node(id).map(|node| node.outgoing.as_slice()).unwrap_or_default();
+    Edges { graph: self, node: id, ids: ids.iter() }
+  }
+
+  /// Returns an iterator visiting the nodes reachable from `start`
+  /// in breadth-first order.
+  pub fn bfs(&self, start: NodeId) -> Bfs<'_, N, E, D> {
+    let mut visited = vec![false; self.nodes.len()];
+    let mut queue = Queue::new();
+
+    if self.contains_node(start) {
+      visited[start.0] = true;
+      queue.enqueue(start);
+    }
+
+    Bfs { graph: self, queue, visited }
+  }
+
+  /// Returns an iterator visiting the nodes reachable from `start`
+  /// in depth-first (pre-)order.
+  pub fn dfs(&self, start: NodeId) -> Dfs<'_, N, E, D> {
+    let mut stack = Stack::new();
+
+    if self.contains_node(start) {
+      stack.push(start);
+    }
+
+    Dfs { graph: self, stack, visited: vec![false; self.nodes.len()] }
+  }
+
+  fn node(&self, id: NodeId) -> Option<&Node<N>> {
+    self.nodes.get(id.0)?.as_ref()
+  }
+
+  fn edge(&self, id: EdgeId) -> Option<&Edge<E>> {
+    self.edges.get(id.0)?.as_ref()
+  }
+}
+
+/// An iterator over the edges leaving a node of a `Graph`.
+pub struct Edges<'a, N, E, D: EdgeType> {
+  graph: &'a Graph<N, E, D>,
+  node: NodeId,
+  ids: std::slice::Iter<'a, EdgeId>
+}
+
+impl<'a, N, E, D: EdgeType> Iterator for Edges<'a, N, E, D> {
+  type Item = (EdgeId, NodeId, &'a E);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let id = *self.ids.next()?;
+    let edge = self.graph.edge(id)?;
+    let other = if edge.source == self.node { edge.target } else { edge.source };
+
+    Some((id, other, &edge.weight))
+  }
+}
+
+/// An iterator over the neighbors of a node of a `Graph`.
+pub struct Neighbors<'a, N, E, D: EdgeType> {
+  edges: Edges<'a, N, E, D>
+}
+
+impl<'a, N, E, D: EdgeType> Iterator for Neighbors<'a, N, E, D> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.edges.next().map(|(_, node, _)| node)
+  }
+}
+
+/// A breadth-first iterator over the nodes of a `Graph`.
+pub struct Bfs<'a, N, E, D: EdgeType> {
+  graph: &'a Graph<N, E, D>,
+  queue: Queue<NodeId>,
+  visited: Vec<bool>
+}
+
+impl<'a, N, E, D: EdgeType> Iterator for Bfs<'a, N, E, D> {
+  type Item = NodeId;
+
+  /// Dequeues the next node and enqueues its unvisited neighbors.
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.queue.dequeue()?;
+
+    for neighbor in self.graph.neighbors(node) {
+      if !self.visited[neighbor.0] {
+        self.visited[neighbor.0] = true;
+        self.queue.enqueue(neighbor);
+      }
+    }
+
+    Some(node)
+  }
+}
+
+/// A depth-first iterator over the nodes of a `Graph`.
+pub struct Dfs<'a, N, E, D: EdgeType> {
+  graph: &'a Graph<N, E, D>,
+  stack: Stack<NodeId>,
+  visited: Vec<bool>
+}
+
+impl<'a, N, E, D: EdgeType> Iterator for Dfs<'a, N, E, D> {
+  type Item = NodeId;
+
+  /// Pops the next unvisited node and pushes its neighbors so that
+  /// the first neighbor is explored first.
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some(node) = self.stack.pop() {
+      if self.visited[node.0] {
+        continue;
+      }
+
+      self.visited[node.0] = true;
+
+      let neighbors: Vec<_> = self.graph.neighbors(node).collect();
+      for neighbor in neighbors.into_iter().rev() {
+        if !self.visited[neighbor.0] {
+          self.stack.push(neighbor);
+        }
+      }
+
+      return Some(node);
+    }
+
+    None
+  }
+}
+
+
+#[cfg(feature = "serde")]
+impl<'de, N, E, D: EdgeType> serde::Deserialize<'de> for Graph<N, E, D>
+where
+  N: serde::Deserialize<'de>,
+  E: serde::Deserialize<'de>,
+{
+  /// Deserializes the nodes and the edges of a graph, checking that the
+  /// edges connect existing nodes as the options allow and that the lists
+  /// of edges and the counts match them.
+  fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    use serde::de::Error;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "Graph")]
+    struct Fields<N, E> {
+      nodes: Vec<Option<Node<N>>>,
+      edges: Vec<Option<Edge<E>>>,
+      node_count: usize,
+      edge_count: usize,
+      #[serde(default)]
+      options: GraphOptions
+    }
+
+    let Fields { nodes, edges, node_count, edge_count, options } = Fields::deserialize(deserializer)?;
+    let graph = Self { nodes, edges, node_count, edge_count, options, direction: PhantomData };
+
+    if graph.node_count != graph.nodes.iter().flatten().count() || graph.edge_count != graph.edges.iter().flatten().count() {
+      return Err(De::Error::custom("counts do not match the nodes and edges"));
+    }
+
+    let mut outgoing = vec![Vec::new(); graph.nodes.len()];
+    let mut incoming = vec![Vec::new(); graph.nodes.len()];
+    let mut pairs = std::collections::HashSet::new();
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+      let Some(edge) = edge else {
+        continue;
+      };
+      let (source, target) = (edge.source, edge.target);
+
+      if !graph.contains_node(source) || !graph.contains_node(target) {
+        return Err(De::Error::custom("edge connects a node that does not exist"));
+      }
+      if source == target && !graph.options.self_loops {
+        return Err(De::Error::custom("self-loops are not supported"));
+      }
+      let pair = if D::is_directed() { (source, target) } else { (source.min(target), source.max(target)) };
+      if !pairs.insert(pair) && !graph.options.parallel_edges {
+        return Err(De::Error::custom("parallel edges are not supported"));
+      }
+
+      outgoing[source.0].push(EdgeId(index));
+      if D::is_directed() {
+        incoming[target.0].push(EdgeId(index));
+      } else if source != target {
+        outgoing[target.0].push(EdgeId(index));
+      }
+    }
+
+    for (node, (outgoing, incoming)) in graph.nodes.iter().zip(outgoing.iter().zip(&incoming)) {
+      if node.as_ref().is_some_and(|node| node.outgoing != *outgoing || node.incoming != *incoming) {
+        return Err(De::Error::custom("lists of edges do not match the edges"));
+      }
+    }
+
+    Ok(graph)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{DiGraph, GraphOptions, UnGraph};
+  use std::collections::{BTreeMap, BTreeSet};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_graph() {
+    let graph = DiGraph::<(), ()>::new();
+
+    assert_eq!(graph.node_count(), 0);
+    assert_eq!(graph.edge_count(), 0);
+    assert!(graph.is_directed());
+  }
+
+  #[test]
+  fn add_and_remove_nodes() {
+    let mut graph = DiGraph::<&str, ()>::new();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+
+    assert_eq!(graph.node_count(), 2);
+    assert_eq!(graph.node_weight(b), Some(&"b"));
+
+    assert_eq!(graph.remove_node(a), Some("a"));
+    assert_eq!(graph.remove_node(a), None);
+    assert!(!graph.contains_node(a));
+    assert_eq!(graph.node_ids().collect::<Vec<_>>(), vec![b]);
+  }
+
+  #[test]
+  fn directed_edges() {
+    let mut graph = DiGraph::<(), u32>::new();
+    let a = graph.add_node(());
+    let b = graph.add_node(());
+
+    let edge = graph.add_edge(a, b, 5);
+    assert_eq!(graph.add_edge(a, b, 7), edge);
+    assert_eq!(graph.edge_weight(edge), Some(&7));
+    assert_eq!(graph.edge_count(), 1);
+
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b]);
+    assert_eq!(graph.neighbors(b).count(), 0);
+    assert_eq!(graph.find_edge(b, a), None);
+
+    assert_eq!(graph.remove_edge(edge), Some(7));
+    assert_eq!(graph.neighbors(a).count(), 0);
+  }
+
+  #[test]
+  fn undirected_edges() {
+    let mut graph = UnGraph::<(), ()>::new();
+    let a = graph.add_node(());
+    let b = graph.add_node(());
+    let c = graph.add_node(());
+    graph.add_edge(a, b, ());
+    graph.add_edge(c, a, ());
+
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b, c]);
+    assert_eq!(graph.neighbors(b).collect::<Vec<_>>(), vec![a]);
+    assert!(graph.find_edge(b, a).is_some());
+
+    graph.remove_node(a);
+    assert_eq!(graph.edge_count(), 0);
+    assert_eq!(graph.neighbors(b).count(), 0);
+  }
+
+  #[test]
+  fn bfs() {
+    //   a
+    //  / \
+    // b   c
+    // |   |
+    // d   e
+    let mut graph = DiGraph::<char, ()>::new();
+    let [a, b, c, d, e] = ['a', 'b', 'c', 'd', 'e'].map(|name| graph.add_node(name));
+    graph.add_edge(a, b, ());
+    graph.add_edge(a, c, ());
+    graph.add_edge(b, d, ());
+    graph.add_edge(c, e, ());
+
+    assert_eq!(graph.bfs(a).collect::<Vec<_>>(), vec![a, b, c, d, e]);
+    assert_eq!(graph.bfs(c).collect::<Vec<_>>(), vec![c, e]);
+  }
+
+  #[test]
+  fn dfs() {
+    let mut graph = UnGraph::<char, ()>::new();
+    let [a, b, c, d, e] = ['a', 'b', 'c', 'd', 'e'].map(|name| graph.add_node(name));
+    graph.add_edge(a, b, ());
+    graph.add_edge(a, c, ());
+    graph.add_edge(b, d, ());
+    graph.add_edge(c, e, ());
+    graph.add_edge(d, e, ());
+
+    assert_eq!(graph.dfs(a).collect::<Vec<_>>(), vec![a, b, d, e, c]);
+  }
+
+  #[test]
+  fn parallel_edges() {
+    let mut graph = UnGraph::<(), u32>::with_options(GraphOptions { parallel_edges: true, self_loops: false });
+    let [a, b] = [(); 2].map(|_| graph.add_node(()));
+    let first = graph.add_edge(a, b, 1);
+    let second = graph.add_edge(b, a, 2);
+
+    assert_ne!(first, second);
+    assert_eq!(graph.edge_count(), 2);
+    assert_eq!(graph.find_edge(b, a), Some(first));
+    assert_eq!(graph.edges_between(b, a).collect::<Vec<_>>(), vec![(first, &1), (second, &2)]);
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b, b]);
+
+    graph.remove_edge(first);
+    assert_eq!(graph.edges_between(a, b).collect::<Vec<_>>(), vec![(second, &2)]);
+  }
+
+  #[test]
+  fn self_loops() {
+    let options = GraphOptions { parallel_edges: false, self_loops: true };
+    let mut directed = DiGraph::<(), ()>::with_options(options);
+    let mut undirected = UnGraph::<(), ()>::with_options(options);
+
+    let a = directed.add_node(());
+    directed.add_edge(a, a, ());
+    assert_eq!(directed.neighbors(a).collect::<Vec<_>>(), vec![a]);
+    assert_eq!(directed.topological_sort().unwrap_err().cycle(), [a]);
+    assert_eq!(directed.remove_node(a), Some(()));
+    assert_eq!(directed.edge_count(), 0);
+
+    let a = undirected.add_node(());
+    let edge = undirected.add_edge(a, a, ());
+    assert_eq!(undirected.add_edge(a, a, ()), edge);
+    assert_eq!(undirected.neighbors(a).collect::<Vec<_>>(), vec![a]);
+    assert!(undirected.has_cycle());
+  }
+
+  #[test]
+  #[should_panic(expected = "self-loops are not supported")]
+  fn self_loops_by_default() {
+    let mut graph = DiGraph::<(), ()>::new();
+    let a = graph.add_node(());
+    graph.add_edge(a, a, ());
+  }
+
+  #[test]
+  fn edge_weight_mut() {
+    let mut graph = DiGraph::<(), u32>::new();
+    let [a, b] = [(); 2].map(|_| graph.add_node(()));
+    let edge = graph.add_edge(a, b, 1);
+
+    *graph.edge_weight_mut(edge).unwrap() += 10;
+    assert_eq!(graph.edge_weight(edge), Some(&11));
+
+    graph.remove_edge(edge);
+    assert_eq!(graph.edge_weight_mut(edge), None);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = UnGraph::<&str, u32>::new();
+    let a = value.add_node("a");
+    let b = value.add_node("b");
+    let c = value.add_node("c");
+    value.add_edge(a, b, 1);
+    value.add_edge(b, c, 2);
+    value.remove_node(a);
+
+    let json = serde_json::to_string(&value).unwrap();
+    let graph: UnGraph<&str, u32> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(graph.node_count(), 2);
+    assert_eq!(graph.edge_count(), 1);
+    assert!(!graph.contains_node(a));
+    assert_eq!(graph.node_weight(c), Some(&"c"));
+    assert_eq!(graph.neighbors(c).collect::<Vec<_>>(), vec![b]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_rejects_malformed_graphs() {
+    let node = |outgoing: &str, incoming: &str| format!(r#"{{"weight":null,"outgoing":[{outgoing}],"incoming":[{incoming}]}}"#);
+    let graph = |nodes: &[String], edges: &str, node_count: usize, edge_count: usize, options: &str| {
+      let json = format!(r#"{{"nodes":[{}],"edges":[{edges}],"node_count":{node_count},"edge_count":{edge_count}{options}}}"#, nodes.join(","));
+      serde_json::from_str::<DiGraph<(), u32>>(&json)
+    };
+    let edge = |source: usize, target: usize| format!(r#"{{"weight":1,"source":{source},"target":{target}}}"#);
+
+    let valid = [node("0", ""), node("", "0")];
+    assert!(graph(&valid, &edge(0, 1), 2, 1, "").is_ok());
+
+    // Counts that do not match the data.
+    assert!(graph(&valid, &edge(0, 1), 3, 1, "").is_err());
+    assert!(graph(&valid, &edge(0, 1), 2, 0, "").is_err());
+    // An edge to a missing node, or a removed one.
+    assert!(graph(&[node("0", "")], &edge(0, 1), 1, 1, "").is_err());
+    assert!(graph(&[node("0", ""), "null".to_string()], &edge(0, 1), 1, 1, "").is_err());
+    // Lists of edges that do not match the edges.
+    assert!(graph(&[node("", ""), node("", "0")], &edge(0, 1), 2, 1, "").is_err());
+    assert!(graph(&[node("0,0", ""), node("", "0")], &edge(0, 1), 2, 1, "").is_err());
+    assert!(graph(&[node("1", ""), node("", "")], "", 2, 0, "").is_err());
+    // Edges the options do not allow.
+    assert!(graph(&[node("0", "0")], &edge(0, 0), 1, 1, "").is_err());
+    let options = r#","options":{"parallel_edges":false,"self_loops":true}"#;
+    assert!(graph(&[node("0", "0")], &edge(0, 0), 1, 1, options).is_ok());
+    let parallel = format!("{},{}", edge(0, 1), edge(0, 1));
+    assert!(graph(&[node("0,1", ""), node("", "0,1")], &parallel, 2, 2, "").is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    AddNode,
+    RemoveNode(usize),
+    AddEdge(usize, usize, u32),
+    RemoveEdge(usize, usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      Just(Operation::AddNode),
+      (0..10usize).prop_map(Operation::RemoveNode),
+      (0..10usize, 0..10usize, any::<u32>()).prop_map(|(a, b, weight)| Operation::AddEdge(a, b, weight)),
+      (0..10usize, 0..10usize).prop_map(|(a, b)| Operation::RemoveEdge(a, b))
+    ]
+  }
+
+  proptest! {
+    /// Compares a directed graph with a model that keeps the live nodes
+    /// and a map from `(source, target)` to the edge weight.
+    #[test]
+    fn behaves_like_edge_map(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut graph = DiGraph::new();
+      let mut ids = Vec::new();
+      let mut nodes = BTreeSet::new();
+      let mut edges = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::AddNode => {
+            nodes.insert(ids.len());
+            ids.push(graph.add_node(ids.len()));
+          }
+          Operation::RemoveNode(a) => {
+            let Some(&id) = ids.get(a) else { continue };
+            let removed = nodes.remove(&a);
+            prop_assert_eq!(graph.remove_node(id), removed.then_some(a));
+            edges.retain(|&(source, target), _| source != a && target != a);
+          }
+          Operation::AddEdge(a, b, weight) => {
+            if a == b || !nodes.contains(&a) || !nodes.contains(&b) {
+              continue;
+            }
+            graph.add_edge(ids[a], ids[b], weight);
+            edges.insert((a, b), weight);
+          }
+          Operation::RemoveEdge(a, b) => {
+            let (Some(&source), Some(&target)) = (ids.get(a), ids.get(b)) else { continue };
+            let removed = graph.find_edge(source, target).and_then(|edge| graph.remove_edge(edge));
+            prop_assert_eq!(removed, edges.remove(&(a, b)));
+          }
+        }
+
+        prop_assert_eq!(graph.node_count(), nodes.len());
+        prop_assert_eq!(graph.edge_count(), edges.len());
+      }
+
+      for &a in &nodes {
+        let mut neighbors: Vec<_> = graph.neighbors(ids[a]).map(|id| *graph.node_weight(id).unwrap()).collect();
+        neighbors.sort_unstable();
+        let expected: Vec<_> = edges.keys().filter(|(source, _)| *source == a).map(|&(_, target)| target).collect();
+        prop_assert_eq!(neighbors, expected);
+
+        for &b in &nodes {
+          let weight = graph.find_edge(ids[a], ids[b]).and_then(|edge| graph.edge_weight(edge));
+          prop_assert_eq!(weight, edges.get(&(a, b)));
+        }
+      }
+    }
+  }
+}
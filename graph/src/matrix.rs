@@ -0,0 +1,561 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{Directed, EdgeId, EdgeType, Graph, Node, NodeId};
+
+
+/// A graph implementation based on an adjacency matrix.
+///
+/// Like `Graph`, every node carries a weight of type `N` and every edge a
+/// weight of type `E`, and nodes are addressed with `NodeId` handles that
+/// are never reused. The edges are kept in a matrix with a cell for every
+/// pair of nodes, so checking whether two nodes are connected takes O(1)
+/// time, but iterating the neighbors of a node takes O(V) time and the
+/// matrix takes O(V²) space whatever the number of edges. It suits small
+/// or dense graphs, while `Graph` suits sparse ones.
+///
+/// There is at most one edge between two nodes and self-loops are not
+/// allowed. Both representations convert into each other, keeping the
+/// identifiers of the nodes: a `Graph` with `TryFrom`, since it may have
+/// edges the matrix can not hold, and a matrix back with `From`.
+///
+/// # Example
+///
+/// ```
+/// use graph::{AdjacencyMatrixGraph, Undirected};
+///
+/// let mut graph = AdjacencyMatrixGraph::<&str, u32, Undirected>::new();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_edge(a, b, 1);
+/// graph.add_edge(c, b, 2);
+///
+/// assert_eq!(graph.edge_weight(b, c), Some(&2));
+/// assert_eq!(graph.neighbors(b).collect::<Vec<_>>(), vec![a, c]);
+///
+/// graph.remove_node(b);
+/// assert_eq!(graph.edge_count(), 0);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AdjacencyMatrixGraph<N, E, D: EdgeType = Directed> {
+  nodes: Vec<Option<N>>,
+  /// Weight of the edge from every node to every node, row by row, with
+  /// `stride` cells per row. Undirected edges are only stored in the row
+  /// of their smaller endpoint.
+  matrix: Vec<Option<E>>,
+  stride: usize,
+  node_count: usize,
+  edge_count: usize,
+  #[cfg_attr(feature = "serde", serde(skip))]
+  direction: PhantomData<D>
+}
+
+impl<N, E, D: EdgeType> Default for AdjacencyMatrixGraph<N, E, D> {
+  /// Creates a new instance of `AdjacencyMatrixGraph` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<N, E, D: EdgeType> AdjacencyMatrixGraph<N, E, D> {
+  /// Creates a new empty graph.
+  pub fn new() -> Self {
+    Self { nodes: Vec::new(), matrix: Vec::new(), stride: 0, node_count: 0, edge_count: 0, direction: PhantomData }
+  }
+
+  /// Checks if the edges of the graph are directed.
+  pub fn is_directed(&self) -> bool {
+    D::is_directed()
+  }
+
+  /// Returns the number of nodes in the graph.
+  pub fn node_count(&self) -> usize {
+    self.node_count
+  }
+
+  /// Returns the number of edges in the graph.
+  pub fn edge_count(&self) -> usize {
+    self.edge_count
+  }
+
+  /// Adds a node with the given weight and returns its identifier.
+  ///
+  /// The matrix doubles its number of rows and columns whenever it is
+  /// full, which takes O(V²) time.
+  pub fn add_node(&mut self, weight: N) -> NodeId {
+    if self.nodes.len() == self.stride {
+      self.grow();
+    }
+
+    self.nodes.push(Some(weight));
+    self.node_count += 1;
+
+    NodeId(self.nodes.len() - 1)
+  }
+
+  /// Removes a node together with all of its edges in O(V) and returns its
+  /// weight, or `None` if the node does not exist.
+  pub fn remove_node(&mut self, id: NodeId) -> Option<N> {
+    let weight = self.nodes.get_mut(id.0)?.take()?;
+    self.node_count -= 1;
+
+    for other in 0..self.nodes.len() {
+      for cell in [self.cell(id.0, other), self.cell(other, id.0)] {
+        if self.matrix[cell].take().is_some() {
+          self.edge_count -= 1;
+        }
+      }
+    }
+
+    Some(weight)
+  }
+
+  /// Checks if the graph contains the node.
+  pub fn contains_node(&self, id: NodeId) -> bool {
+    self.node_weight(id).is_some()
+  }
+
+  /// Returns a reference to the weight of the node.
+  pub fn node_weight(&self, id: NodeId) -> Option<&N> {
+    self.nodes.get(id.0)?.as_ref()
+  }
+
+  /// Returns a mutable reference to the weight of the node.
+  pub fn node_weight_mut(&mut self, id: NodeId) -> Option<&mut N> {
+    self.nodes.get_mut(id.0)?.as_mut()
+  }
+
+  /// Returns an iterator over the identifiers of all nodes.
+  pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+    self.nodes.iter().enumerate().filter(|(_, node)| node.is_some()).map(|(index, _)| NodeId(index))
+  }
+
+  /// Adds an edge from `source` to `target` and returns the weight of the
+  /// edge it replaces, if the nodes were already connected.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either node does not exist or if `source == target`.
+  pub fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> Option<E> {
+    assert!(self.contains_node(source) && self.contains_node(target), "node does not exist");
+    assert!(source != target, "self-loops are not supported");
+
+    let cell = self.cell(source.0, target.0);
+    let previous = self.matrix[cell].replace(weight);
+
+    if previous.is_none() {
+      self.edge_count += 1;
+    }
+
+    previous
+  }
+
+  /// Removes the edge from `source` to `target` and returns its weight, or
+  /// `None` if the nodes are not connected.
+  pub fn remove_edge(&mut self, source: NodeId, target: NodeId) -> Option<E> {
+    self.edge_weight(source, target)?;
+
+    let cell = self.cell(source.0, target.0);
+    self.edge_count -= 1;
+    self.matrix[cell].take()
+  }
+
+  /// Checks if there is an edge from `source` to `target`.
+  pub fn contains_edge(&self, source: NodeId, target: NodeId) -> bool {
+    self.edge_weight(source, target).is_some()
+  }
+
+  /// Returns a reference to the weight of the edge from `source` to
+  /// `target`, or `None` if the nodes are not connected.
+  pub fn edge_weight(&self, source: NodeId, target: NodeId) -> Option<&E> {
+    if source.0 >= self.nodes.len() || target.0 >= self.nodes.len() {
+      return None;
+    }
+
+    self.matrix[self.cell(source.0, target.0)].as_ref()
+  }
+
+  /// Returns an iterator over the edges leaving `id` as `(neighbor,
+  /// weight)` pairs, which scans a whole row of the matrix.
+  pub fn edges(&self, id: NodeId) -> impl Iterator<Item = (NodeId, &E)> + '_ {
+    let len = if self.contains_node(id) { self.nodes.len() } else { 0 };
+    (0..len).filter_map(move |other| Some((NodeId(other), self.edge_weight(id, NodeId(other))?)))
+  }
+
+  /// Returns an iterator over the nodes reachable from `id` through a
+  /// single edge. For undirected graphs these are all adjacent nodes.
+  pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+    self.edges(id).map(|(neighbor, _)| neighbor)
+  }
+
+  /// Returns the position of the cell of the edge from `source` to
+  /// `target` in the matrix.
+  fn cell(&self, source: usize, target: usize) -> usize {
+    let (row, column) = if D::is_directed() { (source, target) } else { (source.min(target), source.max(target)) };
+    row * self.stride + column
+  }
+
+  /// Doubles the number of rows and columns of the matrix.
+  fn grow(&mut self) {
+    let stride = (self.stride * 2).max(4);
+    let mut matrix: Vec<Option<E>> = std::iter::repeat_with(|| None).take(stride * stride).collect();
+
+    for (cell, weight) in self.matrix.drain(..).enumerate() {
+      matrix[cell / self.stride * stride + cell % self.stride] = weight;
+    }
+
+    self.matrix = matrix;
+    self.stride = stride;
+  }
+}
+
+/// The error returned when converting a `Graph` into an
+/// `AdjacencyMatrixGraph`, which can not hold some of its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixConversionError {
+  /// The edge connects a node to itself.
+  SelfLoop(EdgeId),
+  /// The edge connects the same nodes as an edge added before it.
+  ParallelEdge(EdgeId)
+}
+
+impl fmt::Display for MatrixConversionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::SelfLoop(edge) => write!(f, "edge {} is a self-loop", edge.index()),
+      Self::ParallelEdge(edge) => write!(f, "edge {} is parallel to another edge", edge.index())
+    }
+  }
+}
+
+impl std::error::Error for MatrixConversionError {}
+
+impl<N, E, D: EdgeType> TryFrom<Graph<N, E, D>> for AdjacencyMatrixGraph<N, E, D> {
+  type Error = MatrixConversionError;
+
+  /// Converts an adjacency list graph, keeping the identifiers of the
+  /// nodes.
+  ///
+  /// Returns `MatrixConversionError` if the graph has a self-loop or
+  /// parallel edges, which the matrix can not hold.
+  fn try_from(graph: Graph<N, E, D>) -> Result<Self, Self::Error> {
+    let mut matrix = Self::new();
+
+    for node in graph.nodes {
+      if matrix.nodes.len() == matrix.stride {
+        matrix.grow();
+      }
+
+      matrix.nodes.push(node.map(|node| node.weight));
+    }
+
+    for (index, edge) in graph.edges.into_iter().enumerate() {
+      let Some(edge) = edge else {
+        continue;
+      };
+      if edge.source == edge.target {
+        return Err(MatrixConversionError::SelfLoop(EdgeId(index)));
+      }
+
+      let cell = matrix.cell(edge.source.0, edge.target.0);
+      if matrix.matrix[cell].replace(edge.weight).is_some() {
+        return Err(MatrixConversionError::ParallelEdge(EdgeId(index)));
+      }
+    }
+
+    matrix.node_count = graph.node_count;
+    matrix.edge_count = graph.edge_count;
+    Ok(matrix)
+  }
+}
+
+impl<N, E, D: EdgeType> From<AdjacencyMatrixGraph<N, E, D>> for Graph<N, E, D> {
+  /// Converts an adjacency matrix graph, keeping the identifiers of the
+  /// nodes. The edges get new identifiers, in the order of the rows of the
+  /// matrix.
+  fn from(matrix: AdjacencyMatrixGraph<N, E, D>) -> Self {
+    let mut graph = Self::new();
+    let len = matrix.nodes.len();
+
+    graph.nodes = matrix.nodes.into_iter().map(|weight| Some(Node { weight: weight?, outgoing: Vec::new(), incoming: Vec::new() })).collect();
+    graph.node_count = matrix.node_count;
+
+    for (cell, weight) in matrix.matrix.into_iter().enumerate() {
+      let (source, target) = (cell / matrix.stride, cell % matrix.stride);
+
+      if let Some(weight) = weight.filter(|_| source < len && target < len) {
+        graph.add_edge(NodeId(source), NodeId(target), weight);
+      }
+    }
+
+    graph
+  }
+}
+
+
+#[cfg(feature = "serde")]
+impl<'de, N, E, D: EdgeType> serde::Deserialize<'de> for AdjacencyMatrixGraph<N, E, D>
+where
+  N: serde::Deserialize<'de>,
+  E: serde::Deserialize<'de>,
+{
+  /// Deserializes the nodes and the matrix of a graph, checking that the
+  /// matrix has a cell for every pair of nodes, that the edges connect
+  /// existing nodes through the cells `add_edge` uses and that the counts
+  /// match them.
+  fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    use serde::de::Error;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename = "AdjacencyMatrixGraph")]
+    struct Fields<N, E> {
+      nodes: Vec<Option<N>>,
+      matrix: Vec<Option<E>>,
+      stride: usize,
+      node_count: usize,
+      edge_count: usize
+    }
+
+    let Fields { nodes, matrix, stride, node_count, edge_count } = Fields::deserialize(deserializer)?;
+    let graph = Self { nodes, matrix, stride, node_count, edge_count, direction: PhantomData };
+
+    if stride.checked_mul(stride) != Some(graph.matrix.len()) || graph.nodes.len() > stride {
+      return Err(De::Error::custom("matrix does not have a cell for every pair of nodes"));
+    }
+    if graph.node_count != graph.nodes.iter().flatten().count() || graph.edge_count != graph.matrix.iter().flatten().count() {
+      return Err(De::Error::custom("counts do not match the nodes and edges"));
+    }
+
+    for (cell, weight) in graph.matrix.iter().enumerate() {
+      if weight.is_none() {
+        continue;
+      }
+      let (source, target) = (cell / stride, cell % stride);
+
+      if !graph.contains_node(NodeId(source)) || !graph.contains_node(NodeId(target)) {
+        return Err(De::Error::custom("edge connects a node that does not exist"));
+      }
+      if source == target {
+        return Err(De::Error::custom("self-loops are not supported"));
+      }
+      if graph.cell(source, target) != cell {
+        return Err(De::Error::custom("undirected edge is not in the row of its smaller endpoint"));
+      }
+    }
+
+    Ok(graph)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{AdjacencyMatrixGraph, MatrixConversionError};
+  use crate::{DiGraph, Directed, Graph, GraphOptions, UnGraph, Undirected};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_graph() {
+    let graph = AdjacencyMatrixGraph::<(), (), Directed>::new();
+
+    assert_eq!(graph.node_count(), 0);
+    assert_eq!(graph.edge_count(), 0);
+    assert!(graph.is_directed());
+  }
+
+  #[test]
+  fn directed_edges() {
+    let mut graph = AdjacencyMatrixGraph::<(), u32, Directed>::new();
+    let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+
+    assert_eq!(graph.add_edge(a, b, 5), None);
+    assert_eq!(graph.add_edge(a, b, 7), Some(5));
+    graph.add_edge(c, a, 1);
+
+    assert_eq!(graph.edge_count(), 2);
+    assert_eq!(graph.edge_weight(a, b), Some(&7));
+    assert!(!graph.contains_edge(b, a));
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b]);
+
+    assert_eq!(graph.remove_edge(a, b), Some(7));
+    assert_eq!(graph.remove_edge(a, b), None);
+    assert_eq!(graph.edge_count(), 1);
+  }
+
+  #[test]
+  fn undirected_edges() {
+    let mut graph = AdjacencyMatrixGraph::<(), (), Undirected>::new();
+    let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+    graph.add_edge(b, a, ());
+    graph.add_edge(a, c, ());
+
+    assert!(graph.contains_edge(a, b));
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), vec![b, c]);
+
+    assert_eq!(graph.remove_node(a), Some(()));
+    assert_eq!(graph.edge_count(), 0);
+    assert_eq!(graph.neighbors(b).count(), 0);
+    assert!(!graph.contains_edge(a, b));
+  }
+
+  #[test]
+  fn growth_keeps_edges() {
+    let mut graph = AdjacencyMatrixGraph::<usize, usize, Directed>::new();
+    let ids: Vec<_> = (0..20).map(|index| graph.add_node(index)).collect();
+    for pair in ids.windows(2) {
+      graph.add_edge(pair[0], pair[1], pair[0].index());
+    }
+
+    for pair in ids.windows(2) {
+      assert_eq!(graph.edge_weight(pair[0], pair[1]), Some(&pair[0].index()));
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "self-loops are not supported")]
+  fn self_loop() {
+    let mut graph = AdjacencyMatrixGraph::<(), (), Directed>::new();
+    let a = graph.add_node(());
+    graph.add_edge(a, a, ());
+  }
+
+  #[test]
+  fn conversions() {
+    let mut list = UnGraph::<&str, u32>::new();
+    let [a, b, c] = ["a", "b", "c"].map(|name| list.add_node(name));
+    list.add_edge(a, b, 1);
+    list.add_edge(c, b, 2);
+    list.remove_node(a);
+
+    let matrix = AdjacencyMatrixGraph::try_from(list).unwrap();
+    assert_eq!((matrix.node_count(), matrix.edge_count()), (2, 1));
+    assert_eq!(matrix.node_weight(c), Some(&"c"));
+    assert_eq!(matrix.edge_weight(b, c), Some(&2));
+
+    let list = Graph::from(matrix);
+    assert_eq!((list.node_count(), list.edge_count()), (2, 1));
+    assert!(!list.contains_node(a));
+    assert_eq!(list.neighbors(b).collect::<Vec<_>>(), vec![c]);
+  }
+
+  #[test]
+  fn parallel_edges_and_self_loops() {
+    let build = |edges: &[(usize, usize, u32)]| {
+      let mut list = DiGraph::<(), u32>::with_options(GraphOptions { parallel_edges: true, self_loops: true });
+      let ids = [(); 2].map(|_| list.add_node(()));
+      let edges: Vec<_> = edges.iter().map(|&(source, target, weight)| list.add_edge(ids[source], ids[target], weight)).collect();
+      (list, ids, edges)
+    };
+
+    let (list, _, edges) = build(&[(0, 1, 1), (0, 1, 2)]);
+    assert_eq!(AdjacencyMatrixGraph::try_from(list).err(), Some(MatrixConversionError::ParallelEdge(edges[1])));
+    let (list, _, edges) = build(&[(0, 1, 1), (0, 0, 3)]);
+    assert_eq!(AdjacencyMatrixGraph::try_from(list).err(), Some(MatrixConversionError::SelfLoop(edges[1])));
+
+    let (mut list, [a, b], edges) = build(&[(0, 1, 1), (0, 1, 2), (0, 0, 3)]);
+    list.remove_edge(edges[1]);
+    list.remove_edge(edges[2]);
+    let matrix = AdjacencyMatrixGraph::try_from(list).unwrap();
+    assert_eq!(matrix.edge_count(), 1);
+    assert_eq!(matrix.edge_weight(a, b), Some(&1));
+  }
+
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = AdjacencyMatrixGraph::<&str, u32, Undirected>::new();
+    let a = value.add_node("a");
+    let b = value.add_node("b");
+    value.add_edge(a, b, 3);
+
+    let json = serde_json::to_string(&value).unwrap();
+    let graph: AdjacencyMatrixGraph<&str, u32, Undirected> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(graph.node_weight(b), Some(&"b"));
+    assert_eq!(graph.edge_weight(b, a), Some(&3));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_rejects_malformed_graphs() {
+    let json = |nodes: &str, matrix: &str, stride: usize, node_count: usize, edge_count: usize| {
+      format!(r#"{{"nodes":[{nodes}],"matrix":[{matrix}],"stride":{stride},"node_count":{node_count},"edge_count":{edge_count}}}"#)
+    };
+    let directed = |nodes: &str, matrix: &str, stride: usize, node_count: usize, edge_count: usize| {
+      serde_json::from_str::<AdjacencyMatrixGraph<u8, u32, Directed>>(&json(nodes, matrix, stride, node_count, edge_count))
+    };
+    let undirected = |nodes: &str, matrix: &str, stride: usize, node_count: usize, edge_count: usize| {
+      serde_json::from_str::<AdjacencyMatrixGraph<u8, u32, Undirected>>(&json(nodes, matrix, stride, node_count, edge_count))
+    };
+
+    assert!(directed("1,2", "null,5,7,null", 2, 2, 2).is_ok());
+    assert!(undirected("1,2", "null,5,null,null", 2, 2, 1).is_ok());
+
+    // A matrix without a cell for every pair of nodes.
+    assert!(directed("1,2", "", 0, 2, 0).is_err());
+    assert!(directed("1,2", "null,null,null", 2, 2, 0).is_err());
+    assert!(directed("1,2,3", "null,null,null,null", 2, 3, 0).is_err());
+    // Counts that do not match the data.
+    assert!(directed("1,2", "null,5,null,null", 2, 1, 1).is_err());
+    assert!(directed("1,2", "null,5,null,null", 2, 2, 0).is_err());
+    // An edge to a removed node, or past the last one.
+    assert!(directed("1,null", "null,5,null,null", 2, 1, 1).is_err());
+    assert!(directed("1", "null,5,null,null", 2, 1, 1).is_err());
+    // A self-loop.
+    assert!(directed("1,2", "5,null,null,null", 2, 2, 1).is_err());
+    // An undirected edge in the row of its greater endpoint.
+    assert!(undirected("1,2", "null,null,5,null", 2, 2, 1).is_err());
+  }
+
+  proptest! {
+    /// Builds the same graph in both representations and compares them,
+    /// also after a round trip through each other.
+    #[test]
+    fn agrees_with_adjacency_list(len in 1..12usize, edges in prop::collection::vec((0..12usize, 0..12usize, any::<u8>()), 0..40), removed in 0..12usize) {
+      let mut list = DiGraph::new();
+      let mut matrix = AdjacencyMatrixGraph::<usize, u8, Directed>::new();
+      let ids: Vec<_> = (0..len).map(|index| (list.add_node(index), matrix.add_node(index))).collect();
+      prop_assert!(ids.iter().all(|(a, b)| a == b));
+      let ids: Vec<_> = ids.into_iter().map(|(id, _)| id).collect();
+
+      for (a, b, weight) in edges {
+        if a < len && b < len && a != b {
+          list.add_edge(ids[a], ids[b], weight);
+          matrix.add_edge(ids[a], ids[b], weight);
+        }
+      }
+
+      if removed < len {
+        prop_assert_eq!(list.remove_node(ids[removed]), matrix.remove_node(ids[removed]));
+      }
+
+      for &a in &ids {
+        for &b in &ids {
+          let weight = list.find_edge(a, b).and_then(|edge| list.edge_weight(edge));
+          prop_assert_eq!(matrix.edge_weight(a, b), weight);
+        }
+      }
+
+      let round_trip = Graph::from(AdjacencyMatrixGraph::try_from(Graph::from(matrix)).unwrap());
+      for graph in [&list, &round_trip] {
+        prop_assert_eq!(graph.node_count(), list.node_count());
+        prop_assert_eq!(graph.edge_count(), list.edge_count());
+      }
+
+      for &a in &ids {
+        let mut expected: Vec<_> = list.neighbors(a).collect();
+        let mut actual: Vec<_> = round_trip.neighbors(a).collect();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        prop_assert_eq!(&actual, &expected);
+
+        for &b in &ids {
+          let weight = |graph: &DiGraph<usize, u8>| graph.find_edge(a, b).and_then(|edge| graph.edge_weight(edge)).copied();
+          prop_assert_eq!(weight(&round_trip), weight(&list));
+        }
+      }
+    }
+  }
+}
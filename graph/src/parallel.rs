@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
+
+use crate::{EdgeType, Graph, NodeId};
+
+
+impl<N: Sync, E: Sync, D: EdgeType + Sync> Graph<N, E, D> {
+  /// Returns the nodes reachable from `start` grouped by their distance
+  /// to it, with a breadth-first search that expands every level in
+  /// parallel.
+  ///
+  /// The first level only holds `start`, and every level is sorted by
+  /// node. All the nodes of a level look for unvisited neighbors at the
+  /// same time, and a neighbor belongs to the next level if it is claimed
+  /// first by one of them, so the search pays off on large graphs whose
+  /// levels are wide. It is empty if `start` is not in the graph.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<(), ()>::new();
+  /// let [a, b, c, d] = [(); 4].map(|_| graph.add_node(()));
+  /// graph.add_edge(a, c, ());
+  /// graph.add_edge(a, b, ());
+  /// graph.add_edge(b, d, ());
+  /// graph.add_edge(c, d, ());
+  ///
+  /// assert_eq!(graph.par_bfs(a), vec![vec![a], vec![b, c], vec![d]]);
+  /// ```
+  pub fn par_bfs(&self, start: NodeId) -> Vec<Vec<NodeId>> {
+    if !self.contains_node(start) {
+      return Vec::new();
+    }
+
+    let visited: Vec<AtomicBool> = (0..self.nodes.len()).map(|_| AtomicBool::new(false)).collect();
+    visited[start.0].store(true, Ordering::Relaxed);
+
+    let mut levels = Vec::new();
+    let mut frontier = vec![start];
+
+    while !frontier.is_empty() {
+      // Loading first avoids writing to the flags of visited nodes, which
+      // most neighbors are once the search has spread.
+      let mut next: Vec<NodeId> = frontier
+        .par_iter()
+        .flat_map_iter(|&node| {
+          self.neighbors(node).filter(|neighbor| {
+            let flag = &visited[neighbor.0];
+            !flag.load(Ordering::Relaxed) && !flag.swap(true, Ordering::Relaxed)
+          })
+        })
+        .collect();
+
+      next.par_sort_unstable();
+      levels.push(std::mem::replace(&mut frontier, next));
+    }
+
+    levels
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{DiGraph, Graph, NodeId, UnGraph};
+  use proptest::prelude::*;
+
+  /// Groups the nodes reachable from `start` by distance, one level at a
+  /// time on a single thread.
+  fn levels<D: crate::EdgeType>(graph: &Graph<(), (), D>, start: NodeId) -> Vec<Vec<NodeId>> {
+    let mut levels = Vec::new();
+    let mut seen = vec![start];
+    let mut frontier = vec![start];
+
+    while !frontier.is_empty() {
+      let mut next = Vec::new();
+      for &node in &frontier {
+        for neighbor in graph.neighbors(node) {
+          if !seen.contains(&neighbor) {
+            seen.push(neighbor);
+            next.push(neighbor);
+          }
+        }
+      }
+
+      next.sort();
+      levels.push(std::mem::replace(&mut frontier, next));
+    }
+
+    levels
+  }
+
+  #[test]
+  fn par_bfs() {
+    // a - b - c
+    //  \     /
+    //   d - e   f
+    let mut graph = UnGraph::<(), ()>::new();
+    let [a, b, c, d, e, f] = [(); 6].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+    graph.add_edge(b, c, ());
+    graph.add_edge(a, d, ());
+    graph.add_edge(d, e, ());
+    graph.add_edge(e, c, ());
+
+    assert_eq!(graph.par_bfs(a), vec![vec![a], vec![b, d], vec![c, e]]);
+    assert_eq!(graph.par_bfs(f), vec![vec![f]]);
+
+    graph.remove_node(f);
+    assert!(graph.par_bfs(f).is_empty());
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_sequential_levels(
+      len in 1..40usize,
+      edges in prop::collection::vec((0..40usize, 0..40usize), 0..120)
+    ) {
+      let mut graph = DiGraph::<(), ()>::new();
+      let ids: Vec<NodeId> = (0..len).map(|_| graph.add_node(())).collect();
+
+      for (source, target) in edges {
+        if source < len && target < len && source != target {
+          graph.add_edge(ids[source], ids[target], ());
+        }
+      }
+
+      let parallel = graph.par_bfs(ids[0]);
+      prop_assert_eq!(&parallel, &levels(&graph, ids[0]));
+      prop_assert_eq!(parallel.concat().len(), graph.bfs(ids[0]).count());
+    }
+  }
+}
@@ -0,0 +1,265 @@
+//! Rendering graphs as ASCII adjacency lists, as Graphviz DOT and as
+//! Mermaid flowcharts.
+
+use std::fmt::{Display, Write};
+
+use crate::{EdgeType, Graph};
+
+
+impl<N, E, D: EdgeType> Graph<N, E, D> {
+  /// Renders the graph as one line per node, listing the neighbors it has
+  /// an edge to, each followed by the weight of the edge in parentheses.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<&str, u32>::new();
+  /// let a = graph.add_node("a");
+  /// let b = graph.add_node("b");
+  /// let c = graph.add_node("c");
+  /// graph.add_edge(a, b, 1);
+  /// graph.add_edge(a, c, 2);
+  ///
+  /// assert_eq!(graph.to_ascii(), "a -> b (1), c (2)\nb\nc\n");
+  /// ```
+  pub fn to_ascii(&self) -> String
+  where
+    N: Display,
+    E: Display,
+  {
+    self.to_ascii_with(N::to_string, E::to_string)
+  }
+
+  /// Renders the graph like `to_ascii`, with the labels of the nodes and
+  /// the edges returned by the given functions. An empty edge label is
+  /// left out, along with its parentheses.
+  pub fn to_ascii_with<F, G>(&self, node_label: F, edge_label: G) -> String
+  where
+    F: Fn(&N) -> String,
+    G: Fn(&E) -> String,
+  {
+    let arrow = if D::is_directed() { "->" } else { "--" };
+    let mut output = String::new();
+
+    for id in self.node_ids() {
+      output.push_str(&node_label(&self.node(id).unwrap().weight));
+
+      for (index, (_, neighbor, weight)) in self.edges(id).enumerate() {
+        let separator = if index == 0 { format!(" {} ", arrow) } else { String::from(", ") };
+        write!(output, "{}{}", separator, node_label(&self.node(neighbor).unwrap().weight)).unwrap();
+
+        let label = edge_label(weight);
+        if !label.is_empty() {
+          write!(output, " ({})", label).unwrap();
+        }
+      }
+
+      output.push('\n');
+    }
+
+    output
+  }
+
+  /// Renders the graph as Graphviz DOT, a `digraph` if it is directed and
+  /// a `graph` otherwise, whose nodes are named after their index.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::UnGraph;
+  ///
+  /// let mut graph = UnGraph::<&str, u32>::new();
+  /// let a = graph.add_node("a");
+  /// let b = graph.add_node("b");
+  /// graph.add_edge(a, b, 3);
+  ///
+  /// assert_eq!(
+  ///   graph.to_dot(),
+  ///   "graph {\n  n0 [label=\"a\"];\n  n1 [label=\"b\"];\n  n0 -- n1 [label=\"3\"];\n}\n"
+  /// );
+  /// ```
+  pub fn to_dot(&self) -> String
+  where
+    N: Display,
+    E: Display,
+  {
+    self.to_dot_with(N::to_string, E::to_string)
+  }
+
+  /// Renders the graph like `to_dot`, with the labels of the nodes and the
+  /// edges returned by the given functions. An edge with an empty label is
+  /// drawn without one.
+  pub fn to_dot_with<F, G>(&self, node_label: F, edge_label: G) -> String
+  where
+    F: Fn(&N) -> String,
+    G: Fn(&E) -> String,
+  {
+    let (kind, arrow) = if D::is_directed() { ("digraph", "->") } else { ("graph", "--") };
+    let mut output = format!("{} {{\n", kind);
+
+    for id in self.node_ids() {
+      writeln!(output, "  n{} [label=\"{}\"];", id.0, escape(&node_label(&self.node(id).unwrap().weight))).unwrap();
+    }
+
+    for edge in self.edges.iter().flatten() {
+      write!(output, "  n{} {} n{}", edge.source.0, arrow, edge.target.0).unwrap();
+
+      let label = edge_label(&edge.weight);
+      if !label.is_empty() {
+        write!(output, " [label=\"{}\"]", escape(&label)).unwrap();
+      }
+
+      output.push_str(";\n");
+    }
+
+    output.push_str("}\n");
+    output
+  }
+
+  /// Renders the graph as a left-to-right Mermaid flowchart, with arrows
+  /// if it is directed and plain lines otherwise, whose nodes are named
+  /// after their index.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<&str, u32>::new();
+  /// let a = graph.add_node("a");
+  /// let b = graph.add_node("b");
+  /// graph.add_edge(a, b, 3);
+  ///
+  /// assert_eq!(graph.to_mermaid(), "flowchart LR\n  n0[\"a\"]\n  n1[\"b\"]\n  n0 -->|\"3\"| n1\n");
+  /// ```
+  pub fn to_mermaid(&self) -> String
+  where
+    N: Display,
+    E: Display,
+  {
+    self.to_mermaid_with(N::to_string, E::to_string)
+  }
+
+  /// Renders the graph like `to_mermaid`, with the labels of the nodes and
+  /// the edges returned by the given functions. An edge with an empty label
+  /// is drawn without one.
+  pub fn to_mermaid_with<F, G>(&self, node_label: F, edge_label: G) -> String
+  where
+    F: Fn(&N) -> String,
+    G: Fn(&E) -> String,
+  {
+    let arrow = if D::is_directed() { "-->" } else { "---" };
+    let mut output = String::from("flowchart LR\n");
+
+    for id in self.node_ids() {
+      writeln!(output, "  n{}[\"{}\"]", id.0, escape_mermaid(&node_label(&self.node(id).unwrap().weight))).unwrap();
+    }
+
+    for edge in self.edges.iter().flatten() {
+      let label = edge_label(&edge.weight);
+
+      if label.is_empty() {
+        writeln!(output, "  n{} {} n{}", edge.source.0, arrow, edge.target.0).unwrap();
+      } else {
+        writeln!(output, "  n{} {}|\"{}\"| n{}", edge.source.0, arrow, escape_mermaid(&label), edge.target.0).unwrap();
+      }
+    }
+
+    output
+  }
+}
+
+/// Escapes a label to be quoted in Mermaid, which reads HTML entities.
+fn escape_mermaid(label: &str) -> String {
+  label.replace('&', "#amp;").replace('"', "#quot;").replace('\n', "<br>")
+}
+
+/// Escapes a label to be quoted in DOT.
+fn escape(label: &str) -> String {
+  label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{DiGraph, UnGraph};
+
+  #[test]
+  fn to_ascii() {
+    let mut graph = UnGraph::<&str, u32>::new();
+    let a = graph.add_node("a");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    graph.add_edge(a, b, 1);
+    graph.add_edge(c, a, 2);
+
+    assert_eq!(UnGraph::<&str, u32>::new().to_ascii(), "");
+    assert_eq!(graph.to_ascii(), "a -- b (1), c (2)\nb -- a (1)\nc -- a (2)\n");
+  }
+
+  #[test]
+  fn to_ascii_with() {
+    let mut graph = DiGraph::<u32, ()>::new();
+    let a = graph.add_node(1);
+    let b = graph.add_node(2);
+    graph.add_edge(b, a, ());
+
+    assert_eq!(graph.to_ascii_with(|node| format!("#{}", node), |_| String::new()), "#1\n#2 -> #1\n");
+  }
+
+  #[test]
+  fn to_dot() {
+    let mut graph = DiGraph::<&str, &str>::new();
+    let a = graph.add_node("say \"hi\"");
+    let b = graph.add_node("b");
+    let c = graph.add_node("c");
+    graph.add_edge(a, b, "x");
+    graph.add_edge(c, a, "y");
+    graph.remove_node(b);
+
+    assert_eq!(graph.to_dot(), "digraph {\n  n0 [label=\"say \\\"hi\\\"\"];\n  n2 [label=\"c\"];\n  n2 -> n0 [label=\"y\"];\n}\n");
+  }
+
+  #[test]
+  fn to_dot_with() {
+    let mut graph = UnGraph::<(), ()>::new();
+    let a = graph.add_node(());
+    let b = graph.add_node(());
+    graph.add_edge(a, b, ());
+
+    assert_eq!(
+      graph.to_dot_with(|_| String::from("node"), |_| String::new()),
+      "graph {\n  n0 [label=\"node\"];\n  n1 [label=\"node\"];\n  n0 -- n1;\n}\n"
+    );
+  }
+
+  #[test]
+  fn to_mermaid() {
+    let mut graph = UnGraph::<&str, &str>::new();
+    let a = graph.add_node("a & b");
+    let b = graph.add_node("c");
+    let c = graph.add_node("d");
+    graph.add_edge(a, b, "say \"hi\"");
+    graph.add_edge(b, c, "");
+
+    assert_eq!(
+      graph.to_mermaid(),
+      "flowchart LR\n  n0[\"a #amp; b\"]\n  n1[\"c\"]\n  n2[\"d\"]\n  n0 ---|\"say #quot;hi#quot;\"| n1\n  n1 --- n2\n"
+    );
+  }
+
+  #[test]
+  fn to_mermaid_with() {
+    let mut graph = DiGraph::<(), ()>::new();
+    let a = graph.add_node(());
+    let b = graph.add_node(());
+    graph.add_edge(b, a, ());
+
+    assert_eq!(
+      graph.to_mermaid_with(|_| String::from("node"), |_| String::new()),
+      "flowchart LR\n  n0[\"node\"]\n  n1[\"node\"]\n  n1 --> n0\n"
+    );
+  }
+}
@@ -0,0 +1,356 @@
+use std::fmt;
+use std::ops::Add;
+
+use heap::MinHeap;
+
+use crate::{EdgeType, Graph, NodeId};
+
+
+/// Shortest paths from a single start node to every reachable node,
+/// as computed by `Graph::dijkstra` or `Graph::bellman_ford`.
+pub struct ShortestPaths<E> {
+  start: NodeId,
+  /// Distance from the start, indexed by node; `None` if unreachable.
+  distances: Vec<Option<E>>,
+  /// The node preceding each node on its shortest path.
+  predecessors: Vec<Option<NodeId>>
+}
+
+impl<E: Copy> ShortestPaths<E> {
+  /// Returns the node the paths start from.
+  pub fn start(&self) -> NodeId {
+    self.start
+  }
+
+  /// Returns the length of the shortest path to `node`,
+  /// or `None` if the node is unreachable.
+  pub fn distance(&self, node: NodeId) -> Option<E> {
+    *self.distances.get(node.index())?
+  }
+
+  /// Reconstructs the shortest path to `node` by following predecessors
+  /// back to the start. The path includes both endpoints, or is `None`
+  /// if the node is unreachable.
+  pub fn path_to(&self, node: NodeId) -> Option<Vec<NodeId>> {
+    self.distance(node)?;
+
+    let mut path = vec![node];
+    let mut current = node;
+
+    while let Some(previous) = self.predecessors[current.index()] {
+      path.push(previous);
+      current = previous;
+    }
+
+    path.reverse();
+    Some(path)
+  }
+}
+
+/// The error returned by `Graph::bellman_ford` when a cycle with a
+/// negative total weight is reachable from the start node, in which
+/// case shortest paths are not defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeCycleError;
+
+impl fmt::Display for NegativeCycleError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "graph contains a negative-weight cycle")
+  }
+}
+
+impl std::error::Error for NegativeCycleError {}
+
+impl<N, E, D> Graph<N, E, D>
+where
+  E: Copy + Ord + Add<Output = E> + Default,
+  D: EdgeType,
+{
+  /// Computes the shortest paths from `start` with Dijkstra's algorithm
+  /// in O((V + E) log V), using a `MinHeap` as the priority queue.
+  ///
+  /// Edge weights are added with `+` and `E::default()` is used as zero,
+  /// so all weights must be non-negative; use `bellman_ford` otherwise.
+  pub fn dijkstra(&self, start: NodeId) -> ShortestPaths<E> {
+    self.dijkstra_until(start, None)
+  }
+
+  /// Returns the length of the shortest path from `start` to `goal` and
+  /// the nodes along it, or `None` if `goal` is unreachable.
+  ///
+  /// The search stops as soon as `goal` is settled.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<(), u32>::new();
+  /// let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+  /// graph.add_edge(a, b, 1);
+  /// graph.add_edge(b, c, 2);
+  /// graph.add_edge(a, c, 5);
+  ///
+  /// assert_eq!(graph.shortest_path(a, c), Some((3, vec![a, b, c])));
+  /// ```
+  pub fn shortest_path(&self, start: NodeId, goal: NodeId) -> Option<(E, Vec<NodeId>)> {
+    let paths = self.dijkstra_until(start, Some(goal));
+    Some((paths.distance(goal)?, paths.path_to(goal)?))
+  }
+
+  /// Returns the length of the shortest path from `start` to `goal` and
+  /// the nodes along it with the A* algorithm, or `None` if `goal` is
+  /// unreachable.
+  ///
+  /// Like Dijkstra's algorithm, but nodes are explored in the order of
+  /// their distance from the start plus the `heuristic` estimate of their
+  /// distance to the goal, so the search heads towards the goal. The path
+  /// is the shortest one if the heuristic never overestimates, and a
+  /// heuristic that always returns `E::default()` makes this Dijkstra's
+  /// algorithm.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::{NodeId, UnGraph};
+  ///
+  /// // A 3x3 grid with a wall in the middle of the top two rows.
+  /// let open = |(x, y)| !(x == 1 && y < 2);
+  /// let graph = UnGraph::grid(3, 3, |cell, neighbor| (open(cell) && open(neighbor)).then_some(1));
+  /// let cell = |x: usize, y: usize| NodeId::new(y * 3 + x);
+  ///
+  /// let (x, y) = (2, 0);
+  /// let manhattan = |node| {
+  ///   let (a, b) = *graph.node_weight(node).unwrap();
+  ///   (a.abs_diff(x) + b.abs_diff(y)) as u32
+  /// };
+  ///
+  /// let (cost, path) = graph.astar(cell(0, 0), cell(x, y), manhattan).unwrap();
+  /// assert_eq!(cost, 6);
+  /// assert_eq!(path, [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)].map(|(x, y)| cell(x, y)));
+  /// ```
+  pub fn astar<H>(&self, start: NodeId, goal: NodeId, heuristic: H) -> Option<(E, Vec<NodeId>)>
+  where
+    H: Fn(NodeId) -> E,
+  {
+    let mut paths = self.empty_paths(start);
+    let mut heap = MinHeap::new();
+
+    if self.contains_node(start) {
+      heap.push((heuristic(start), E::default(), start));
+    }
+
+    while let Some((_, distance, node)) = heap.pop() {
+      if node == goal {
+        return Some((distance, paths.path_to(goal)?));
+      }
+
+      // A shorter path to the node was found after this one was queued.
+      if paths.distances[node.index()].is_some_and(|current| current < distance) {
+        continue;
+      }
+
+      for (_, neighbor, &weight) in self.edges(node) {
+        let candidate = distance + weight;
+
+        if paths.distances[neighbor.index()].is_none_or(|current| candidate < current) {
+          paths.distances[neighbor.index()] = Some(candidate);
+          paths.predecessors[neighbor.index()] = Some(node);
+          heap.push((candidate + heuristic(neighbor), candidate, neighbor));
+        }
+      }
+    }
+
+    None
+  }
+
+  /// Computes the shortest paths from `start` with the Bellman-Ford
+  /// algorithm in O(V * E). Unlike `dijkstra`, negative edge weights
+  /// are allowed.
+  ///
+  /// # Errors
+  ///
+  /// Returns `NegativeCycleError` if a negative-weight cycle is reachable
+  /// from `start`.
+  pub fn bellman_ford(&self, start: NodeId) -> Result<ShortestPaths<E>, NegativeCycleError> {
+    let mut paths = self.empty_paths(start);
+
+    for _ in 1..self.node_count().max(1) {
+      if !self.relax_all(&mut paths) {
+        return Ok(paths);
+      }
+    }
+
+    if self.relax_all(&mut paths) {
+      return Err(NegativeCycleError);
+    }
+
+    Ok(paths)
+  }
+
+  /// Runs Dijkstra's algorithm, stopping early once `goal` is settled.
+  fn dijkstra_until(&self, start: NodeId, goal: Option<NodeId>) -> ShortestPaths<E> {
+    let mut paths = self.empty_paths(start);
+    let mut settled = vec![false; self.nodes.len()];
+    let mut heap = MinHeap::new();
+
+    if self.contains_node(start) {
+      heap.push((E::default(), start));
+    }
+
+    while let Some((distance, node)) = heap.pop() {
+      if settled[node.index()] {
+        continue;
+      }
+
+      settled[node.index()] = true;
+
+      if Some(node) == goal {
+        break;
+      }
+
+      for (_, neighbor, &weight) in self.edges(node) {
+        let candidate = distance + weight;
+
+        if paths.distances[neighbor.index()].is_none_or(|current| candidate < current) {
+          paths.distances[neighbor.index()] = Some(candidate);
+          paths.predecessors[neighbor.index()] = Some(node);
+          heap.push((candidate, neighbor));
+        }
+      }
+    }
+
+    paths
+  }
+
+  /// Relaxes every edge of the graph once. Returns `true` if any
+  /// distance got shorter.
+  fn relax_all(&self, paths: &mut ShortestPaths<E>) -> bool {
+    let mut changed = false;
+
+    for node in self.node_ids() {
+      let Some(distance) = paths.distances[node.index()] else {
+        continue;
+      };
+
+      for (_, neighbor, &weight) in self.edges(node) {
+        let candidate = distance + weight;
+
+        if paths.distances[neighbor.index()].is_none_or(|current| candidate < current) {
+          paths.distances[neighbor.index()] = Some(candidate);
+          paths.predecessors[neighbor.index()] = Some(node);
+          changed = true;
+        }
+      }
+    }
+
+    changed
+  }
+
+  /// Creates paths where only `start` is reachable, at distance zero.
+  fn empty_paths(&self, start: NodeId) -> ShortestPaths<E> {
+    let mut distances = vec![None; self.nodes.len()];
+
+    if self.contains_node(start) {
+      distances[start.index()] = Some(E::default());
+    }
+
+    ShortestPaths { start, distances, predecessors: vec![None; self.nodes.len()] }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::NegativeCycleError;
+  use crate::{DiGraph, UnGraph};
+
+  #[test]
+  fn dijkstra() {
+    //   a --1-- b
+    //   |       |
+    //   4       1
+    //   |       |
+    //   c --1-- d    e
+    let mut graph = UnGraph::<(), u32>::new();
+    let [a, b, c, d, e] = [(); 5].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, 1);
+    graph.add_edge(a, c, 4);
+    graph.add_edge(b, d, 1);
+    graph.add_edge(c, d, 1);
+
+    let paths = graph.dijkstra(a);
+
+    assert_eq!(paths.distance(a), Some(0));
+    assert_eq!(paths.distance(c), Some(3));
+    assert_eq!(paths.distance(e), None);
+    assert_eq!(paths.path_to(c), Some(vec![a, b, d, c]));
+    assert_eq!(paths.path_to(e), None);
+  }
+
+  #[test]
+  fn shortest_path() {
+    let mut graph = DiGraph::<(), u32>::new();
+    let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, 2);
+    graph.add_edge(b, c, 2);
+    graph.add_edge(a, c, 5);
+
+    assert_eq!(graph.shortest_path(a, c), Some((4, vec![a, b, c])));
+    assert_eq!(graph.shortest_path(a, a), Some((0, vec![a])));
+    assert_eq!(graph.shortest_path(c, a), None);
+  }
+
+  #[test]
+  fn astar() {
+    // a --1-- b --1-- c --1-- d
+    //  \                     /
+    //   `--------4----------´
+    let mut graph = UnGraph::<i32, u32>::new();
+    let [a, b, c, d] = [0, 1, 2, 3].map(|position| graph.add_node(position));
+    graph.add_edge(a, b, 1);
+    graph.add_edge(b, c, 1);
+    graph.add_edge(c, d, 1);
+    graph.add_edge(a, d, 4);
+
+    let distance = |node| graph.node_weight(node).unwrap().abs_diff(3);
+    assert_eq!(graph.astar(a, d, distance), Some((3, vec![a, b, c, d])));
+    assert_eq!(graph.astar(d, d, distance), Some((0, vec![d])));
+  }
+
+  #[test]
+  fn astar_unreachable() {
+    let mut graph = DiGraph::<(), u32>::new();
+    let [a, b] = [(); 2].map(|_| graph.add_node(()));
+    graph.add_edge(b, a, 1);
+
+    assert_eq!(graph.astar(a, b, |_| 0), None);
+  }
+
+  #[test]
+  fn bellman_ford() {
+    let mut graph = DiGraph::<(), i32>::new();
+    let [a, b, c, d] = [(); 4].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, 4);
+    graph.add_edge(a, c, 5);
+    graph.add_edge(c, b, -3);
+    graph.add_edge(b, d, 2);
+
+    let paths = graph.bellman_ford(a).unwrap();
+
+    assert_eq!(paths.distance(b), Some(2));
+    assert_eq!(paths.distance(d), Some(4));
+    assert_eq!(paths.path_to(d), Some(vec![a, c, b, d]));
+  }
+
+  #[test]
+  fn negative_cycle() {
+    let mut graph = DiGraph::<(), i32>::new();
+    let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, 1);
+    graph.add_edge(b, c, -2);
+    graph.add_edge(c, b, 1);
+
+    assert_eq!(graph.bellman_ford(a).err(), Some(NegativeCycleError));
+  }
+}
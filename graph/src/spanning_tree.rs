@@ -0,0 +1,174 @@
+use std::ops::Add;
+
+use disjoint_set::DisjointSet;
+use heap::MinHeap;
+
+use crate::{EdgeId, Graph, Undirected};
+
+
+impl<N, E> Graph<N, E, Undirected>
+where
+  E: Copy + Ord + Add<Output = E> + Default,
+{
+  /// Computes a minimum spanning forest with Kruskal's algorithm in
+  /// O(E log E): the edges are taken from the lightest up, skipping those
+  /// whose endpoints a `DisjointSet` already knows to be connected.
+  ///
+  /// Returns the total weight and the edges of the forest, in ascending
+  /// order of weight. The forest spans every connected component, so it is
+  /// a spanning tree if the graph is connected.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::UnGraph;
+  ///
+  /// let mut graph = UnGraph::<(), u32>::new();
+  /// let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+  /// let ab = graph.add_edge(a, b, 1);
+  /// let bc = graph.add_edge(b, c, 2);
+  /// graph.add_edge(a, c, 3);
+  ///
+  /// assert_eq!(graph.kruskal(), (3, vec![ab, bc]));
+  /// ```
+  pub fn kruskal(&self) -> (E, Vec<EdgeId>) {
+    let mut edges: Vec<_> = (0..self.edges.len()).map(EdgeId).filter_map(|id| Some((*self.edge_weight(id)?, id))).collect();
+    edges.sort_unstable();
+
+    let mut sets = DisjointSet::with_len(self.nodes.len());
+    let mut total = E::default();
+    let mut tree = Vec::new();
+
+    for (weight, id) in edges {
+      let edge = self.edge(id).expect("edge exists");
+
+      if sets.union(edge.source.0, edge.target.0) {
+        total = total + weight;
+        tree.push(id);
+      }
+    }
+
+    (total, tree)
+  }
+
+  /// Computes a minimum spanning forest with Prim's algorithm in
+  /// O(E log V): every tree grows from a node by adding the lightest edge
+  /// to a node outside of it, found with a `MinHeap`.
+  ///
+  /// Returns the total weight and the edges of the forest, in the order
+  /// they were added. The forest spans every connected component, so it is
+  /// a spanning tree if the graph is connected.
+  pub fn prim(&self) -> (E, Vec<EdgeId>) {
+    let mut in_tree = vec![false; self.nodes.len()];
+    let mut heap = MinHeap::new();
+    let mut total = E::default();
+    let mut tree = Vec::new();
+
+    for start in self.node_ids() {
+      if in_tree[start.0] {
+        continue;
+      }
+
+      let mut next = Some(start);
+
+      loop {
+        if let Some(node) = next.take() {
+          in_tree[node.0] = true;
+
+          for (id, neighbor, &weight) in self.edges(node) {
+            if !in_tree[neighbor.0] {
+              heap.push((weight, id, neighbor));
+            }
+          }
+        }
+
+        let Some((weight, id, node)) = heap.pop() else { break };
+
+        if !in_tree[node.0] {
+          total = total + weight;
+          tree.push(id);
+          next = Some(node);
+        }
+      }
+    }
+
+    (total, tree)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{EdgeId, UnGraph};
+  use proptest::prelude::*;
+
+  /// Builds the graph:
+  ///
+  /// ```text
+  ///   a --4-- b --8-- c
+  ///   |     / |     / |
+  ///   8   11  2   9   7
+  ///   | /     | /     |
+  ///   h --1-- i --6-- d
+  /// ```
+  fn sample() -> (UnGraph<char, u32>, [EdgeId; 9]) {
+    let mut graph = UnGraph::new();
+    let [a, b, c, d, h, i] = ['a', 'b', 'c', 'd', 'h', 'i'].map(|name| graph.add_node(name));
+    let edges = [(a, b, 4), (b, c, 8), (a, h, 8), (b, h, 11), (b, i, 2), (c, d, 7), (h, i, 1), (i, d, 6), (c, i, 9)]
+      .map(|(source, target, weight)| graph.add_edge(source, target, weight));
+    (graph, edges)
+  }
+
+  #[test]
+  fn kruskal() {
+    let (graph, [ab, _, _, _, bi, cd, hi, id, _]) = sample();
+
+    assert_eq!(graph.kruskal(), (20, vec![hi, bi, ab, id, cd]));
+  }
+
+  #[test]
+  fn prim() {
+    let (graph, [ab, _, _, _, bi, cd, hi, id, _]) = sample();
+
+    assert_eq!(graph.prim(), (20, vec![ab, bi, hi, id, cd]));
+  }
+
+  #[test]
+  fn spanning_forest() {
+    let mut graph = UnGraph::<(), i32>::new();
+    let [a, b, c, d, _] = [(); 5].map(|_| graph.add_node(()));
+    let ab = graph.add_edge(a, b, -1);
+    let cd = graph.add_edge(c, d, 3);
+
+    assert_eq!(graph.kruskal(), (2, vec![ab, cd]));
+    assert_eq!(graph.prim(), (2, vec![ab, cd]));
+    assert_eq!(UnGraph::<(), i32>::new().kruskal(), (0, vec![]));
+  }
+
+  proptest! {
+    #[test]
+    fn kruskal_and_prim_agree(len in 1..10usize, edges in prop::collection::vec((0..10usize, 0..10usize, 0..20u32), 0..30)) {
+      let mut graph = UnGraph::<(), u32>::new();
+      let ids: Vec<_> = (0..len).map(|_| graph.add_node(())).collect();
+      for (a, b, weight) in edges {
+        if a < len && b < len && a != b {
+          graph.add_edge(ids[a], ids[b], weight);
+        }
+      }
+
+      let (kruskal, kruskal_edges) = graph.kruskal();
+      let (prim, prim_edges) = graph.prim();
+      prop_assert_eq!(kruskal, prim);
+      prop_assert_eq!(kruskal_edges.len(), prim_edges.len());
+
+      // A forest with an edge fewer than nodes per component connects
+      // every component without a cycle.
+      let components = ids.iter().filter(|&&node| graph.dfs(node).min() == Some(node)).count();
+      prop_assert_eq!(kruskal_edges.len(), len - components);
+
+      let weight = |edges: &[EdgeId]| edges.iter().map(|&edge| graph.edge_weight(edge).unwrap()).sum::<u32>();
+      prop_assert_eq!(weight(&kruskal_edges), kruskal);
+      prop_assert_eq!(weight(&prim_edges), prim);
+    }
+  }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+use disjoint_set::DisjointSet;
+
+use crate::{Directed, EdgeType, Graph, NodeId};
+
+
+/// The error returned by `Graph::topological_sort` when the graph has a
+/// cycle, in which case no topological order exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+  cycle: Vec<NodeId>
+}
+
+impl CycleError {
+  /// Returns the nodes of a cycle in the order of its edges: each node has
+  /// an edge to the next one, and the last node has an edge to the first.
+  pub fn cycle(&self) -> &[NodeId] {
+    &self.cycle
+  }
+}
+
+impl fmt::Display for CycleError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "graph contains a cycle through {} nodes", self.cycle.len())
+  }
+}
+
+impl std::error::Error for CycleError {}
+
+/// The progress of the depth-first search of a node.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+  Unvisited,
+  /// The node is on the current search path.
+  InProgress,
+  Done
+}
+
+impl<N, E> Graph<N, E, Directed> {
+  /// Returns the nodes ordered so that every edge goes from an earlier to
+  /// a later node, computed with a depth-first search in O(V + E).
+  ///
+  /// # Errors
+  ///
+  /// Returns a `CycleError` with one of the cycles of the graph if it is
+  /// not acyclic.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use graph::DiGraph;
+  ///
+  /// let mut graph = DiGraph::<&str, ()>::new();
+  /// let [shirt, tie, jacket] = ["shirt", "tie", "jacket"].map(|item| graph.add_node(item));
+  /// graph.add_edge(tie, jacket, ());
+  /// graph.add_edge(shirt, tie, ());
+  ///
+  /// assert_eq!(graph.topological_sort(), Ok(vec![shirt, tie, jacket]));
+  ///
+  /// graph.add_edge(jacket, shirt, ());
+  /// assert_eq!(graph.topological_sort().unwrap_err().cycle(), [shirt, tie, jacket]);
+  /// ```
+  pub fn topological_sort(&self) -> Result<Vec<NodeId>, CycleError> {
+    self.reverse_post_order()
+  }
+}
+
+impl<N, E, D: EdgeType> Graph<N, E, D> {
+  /// Checks if the graph has a cycle.
+  ///
+  /// A directed graph has a cycle if `topological_sort` fails. An
+  /// undirected graph has one if two nodes are connected by more than one
+  /// path, which is detected with a `DisjointSet` in O(V + E α(V)).
+  pub fn has_cycle(&self) -> bool {
+    if D::is_directed() {
+      return self.reverse_post_order().is_err();
+    }
+
+    let mut sets = DisjointSet::with_len(self.nodes.len());
+    self.edges.iter().flatten().any(|edge| !sets.union(edge.source.0, edge.target.0))
+  }
+
+  /// Returns the nodes in reverse post-order of a depth-first search
+  /// following the edges in their direction, or the first cycle found.
+  fn reverse_post_order(&self) -> Result<Vec<NodeId>, CycleError> {
+    let mut states = vec![State::Unvisited; self.nodes.len()];
+    let mut order = Vec::with_capacity(self.node_count());
+
+    for start in self.node_ids() {
+      if states[start.0] != State::Unvisited {
+        continue;
+      }
+
+      // The search path, each node with its neighbors left to search.
+      states[start.0] = State::InProgress;
+      let mut path = vec![(start, self.neighbors(start))];
+
+      while let Some((node, neighbors)) = path.last_mut() {
+        let node = *node;
+
+        match neighbors.next() {
+          Some(next) => match states[next.0] {
+            State::Unvisited => {
+              states[next.0] = State::InProgress;
+              path.push((next, self.neighbors(next)));
+            }
+            State::InProgress => {
+              let first = path.iter().position(|&(node, _)| node == next).expect("node is on the path");
+              return Err(CycleError { cycle: path[first..].iter().map(|&(node, _)| node).collect() });
+            }
+            State::Done => {}
+          },
+          None => {
+            states[node.0] = State::Done;
+            order.push(node);
+            path.pop();
+          }
+        }
+      }
+    }
+
+    order.reverse();
+    Ok(order)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{DiGraph, UnGraph};
+  use proptest::prelude::*;
+
+  #[test]
+  fn topological_sort() {
+    //   a → b → d
+    //   ↓       ↑
+    //   c ------┘   e
+    let mut graph = DiGraph::<(), ()>::new();
+    let [a, b, c, d, e] = [(); 5].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+    graph.add_edge(a, c, ());
+    graph.add_edge(b, d, ());
+    graph.add_edge(c, d, ());
+
+    assert_eq!(graph.topological_sort(), Ok(vec![e, a, c, b, d]));
+    assert!(!graph.has_cycle());
+    assert_eq!(DiGraph::<(), ()>::new().topological_sort(), Ok(vec![]));
+  }
+
+  #[test]
+  fn cycle() {
+    let mut graph = DiGraph::<(), ()>::new();
+    let [a, b, c, d] = [(); 4].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+    graph.add_edge(b, c, ());
+    graph.add_edge(c, d, ());
+    graph.add_edge(d, b, ());
+
+    let error = graph.topological_sort().unwrap_err();
+    assert_eq!(error.cycle(), [b, c, d]);
+    assert_eq!(error.to_string(), "graph contains a cycle through 3 nodes");
+    assert!(graph.has_cycle());
+
+    graph.remove_node(c);
+    assert!(!graph.has_cycle());
+  }
+
+  #[test]
+  fn undirected_cycle() {
+    let mut graph = UnGraph::<(), ()>::new();
+    let [a, b, c] = [(); 3].map(|_| graph.add_node(()));
+    graph.add_edge(a, b, ());
+    graph.add_edge(b, c, ());
+
+    assert!(!graph.has_cycle());
+
+    graph.add_edge(c, a, ());
+    assert!(graph.has_cycle());
+  }
+
+  proptest! {
+    #[test]
+    fn sorts_or_finds_a_cycle(len in 1..12usize, edges in prop::collection::vec((0..12usize, 0..12usize), 0..30)) {
+      let mut graph = DiGraph::<(), ()>::new();
+      let ids: Vec<_> = (0..len).map(|_| graph.add_node(())).collect();
+      for (a, b) in edges {
+        if a < len && b < len && a != b {
+          graph.add_edge(ids[a], ids[b], ());
+        }
+      }
+
+      match graph.topological_sort() {
+        Ok(order) => {
+          prop_assert_eq!(order.len(), len);
+          let position = |node| order.iter().position(|&other| other == node).unwrap();
+          for &node in &ids {
+            for neighbor in graph.neighbors(node) {
+              prop_assert!(position(node) < position(neighbor));
+            }
+          }
+          prop_assert!(!graph.has_cycle());
+        }
+        Err(error) => {
+          let cycle = error.cycle();
+          for (index, &node) in cycle.iter().enumerate() {
+            let next = cycle[(index + 1) % cycle.len()];
+            prop_assert!(graph.find_edge(node, next).is_some());
+          }
+          prop_assert!(graph.has_cycle());
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,234 @@
+//! The interface shared by the graph representations of this crate.
+//!
+//! The `Graph` trait is not re-exported at the root of the crate, where
+//! `Graph` names the adjacency list graph, so it is usually imported as
+//! `use graph::traits::Graph as _;` to call its methods, or named in full
+//! in bounds.
+//!
+//! # Example
+//!
+//! ```
+//! use graph::{AdjacencyMatrixGraph, DiGraph, Directed, NodeId};
+//!
+//! /// Returns the nodes without edges to other nodes.
+//! fn sinks<G: graph::traits::Graph>(graph: &G) -> Vec<NodeId> {
+//!   graph.node_ids().filter(|&node| graph.neighbors(node).next().is_none()).collect()
+//! }
+//!
+//! let mut list = DiGraph::<(), ()>::new();
+//! let [a, b] = [(); 2].map(|_| list.add_node(()));
+//! list.add_edge(a, b, ());
+//!
+//! assert_eq!(sinks(&list), [b]);
+//! assert_eq!(sinks(&AdjacencyMatrixGraph::<(), (), Directed>::try_from(list).unwrap()), [b]);
+//! ```
+
+use crate::{AdjacencyMatrixGraph, EdgeType, NodeId};
+
+
+/// A graph with weighted nodes addressed by `NodeId` and at most one
+/// weighted edge from a node to another, whose edges are addressed by
+/// their endpoints.
+pub trait Graph {
+  /// The type of the weights of the nodes.
+  type NodeWeight;
+  /// The type of the weights of the edges.
+  type EdgeWeight;
+
+  /// Checks if the edges of the graph are directed.
+  fn is_directed(&self) -> bool;
+
+  /// Returns the number of nodes in the graph.
+  fn node_count(&self) -> usize;
+
+  /// Returns the number of edges in the graph.
+  fn edge_count(&self) -> usize;
+
+  /// Adds a node with the given weight and returns its identifier.
+  fn add_node(&mut self, weight: Self::NodeWeight) -> NodeId;
+
+  /// Removes a node together with all of its edges and returns its weight,
+  /// or `None` if the node does not exist.
+  fn remove_node(&mut self, id: NodeId) -> Option<Self::NodeWeight>;
+
+  /// Checks if the graph contains the node.
+  fn contains_node(&self, id: NodeId) -> bool {
+    self.node_weight(id).is_some()
+  }
+
+  /// Returns a reference to the weight of the node.
+  fn node_weight(&self, id: NodeId) -> Option<&Self::NodeWeight>;
+
+  /// Returns an iterator over the identifiers of all nodes.
+  fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_;
+
+  /// Adds an edge from `source` to `target` and returns the weight of the
+  /// edge it replaces, if the nodes were already connected.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either node does not exist or if `source == target`.
+  fn add_edge(&mut self, source: NodeId, target: NodeId, weight: Self::EdgeWeight) -> Option<Self::EdgeWeight>;
+
+  /// Removes the edge from `source` to `target` and returns its weight, or
+  /// `None` if the nodes are not connected.
+  fn remove_edge(&mut self, source: NodeId, target: NodeId) -> Option<Self::EdgeWeight>;
+
+  /// Checks if there is an edge from `source` to `target`.
+  fn contains_edge(&self, source: NodeId, target: NodeId) -> bool {
+    self.edge_weight(source, target).is_some()
+  }
+
+  /// Returns a reference to the weight of the edge from `source` to
+  /// `target`, or `None` if the nodes are not connected.
+  fn edge_weight(&self, source: NodeId, target: NodeId) -> Option<&Self::EdgeWeight>;
+
+  /// Returns an iterator over the nodes reachable from `id` through a
+  /// single edge. For undirected graphs these are all adjacent nodes.
+  fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_;
+}
+
+impl<N, E, D: EdgeType> Graph for crate::Graph<N, E, D> {
+  type NodeWeight = N;
+  type EdgeWeight = E;
+
+  fn is_directed(&self) -> bool {
+    self.is_directed()
+  }
+
+  fn node_count(&self) -> usize {
+    self.node_count()
+  }
+
+  fn edge_count(&self) -> usize {
+    self.edge_count()
+  }
+
+  fn add_node(&mut self, weight: N) -> NodeId {
+    self.add_node(weight)
+  }
+
+  fn remove_node(&mut self, id: NodeId) -> Option<N> {
+    self.remove_node(id)
+  }
+
+  fn node_weight(&self, id: NodeId) -> Option<&N> {
+    self.node_weight(id)
+  }
+
+  fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+    self.node_ids()
+  }
+
+  fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> Option<E> {
+    // Even if the graph allows parallel edges, the weight of the first one
+    // is replaced, as the trait addresses edges by their endpoints.
+    match self.find_edge(source, target) {
+      Some(id) => Some(std::mem::replace(&mut self.edges[id.0].as_mut().unwrap().weight, weight)),
+      None => {
+        self.add_edge(source, target, weight);
+        None
+      }
+    }
+  }
+
+  fn remove_edge(&mut self, source: NodeId, target: NodeId) -> Option<E> {
+    self.remove_edge(self.find_edge(source, target)?)
+  }
+
+  fn edge_weight(&self, source: NodeId, target: NodeId) -> Option<&E> {
+    self.edge_weight(self.find_edge(source, target)?)
+  }
+
+  fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+    self.neighbors(id)
+  }
+}
+
+impl<N, E, D: EdgeType> Graph for AdjacencyMatrixGraph<N, E, D> {
+  type NodeWeight = N;
+  type EdgeWeight = E;
+
+  fn is_directed(&self) -> bool {
+    self.is_directed()
+  }
+
+  fn node_count(&self) -> usize {
+    self.node_count()
+  }
+
+  fn edge_count(&self) -> usize {
+    self.edge_count()
+  }
+
+  fn add_node(&mut self, weight: N) -> NodeId {
+    self.add_node(weight)
+  }
+
+  fn remove_node(&mut self, id: NodeId) -> Option<N> {
+    self.remove_node(id)
+  }
+
+  fn node_weight(&self, id: NodeId) -> Option<&N> {
+    self.node_weight(id)
+  }
+
+  fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+    self.node_ids()
+  }
+
+  fn add_edge(&mut self, source: NodeId, target: NodeId, weight: E) -> Option<E> {
+    self.add_edge(source, target, weight)
+  }
+
+  fn remove_edge(&mut self, source: NodeId, target: NodeId) -> Option<E> {
+    self.remove_edge(source, target)
+  }
+
+  fn edge_weight(&self, source: NodeId, target: NodeId) -> Option<&E> {
+    self.edge_weight(source, target)
+  }
+
+  fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+    self.neighbors(id)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Graph;
+  use crate::{AdjacencyMatrixGraph, DiGraph, Directed, NodeId, UnGraph, Undirected};
+
+  /// Builds a path `a - b - c` and checks the graph through the trait.
+  fn path<G: Graph<NodeWeight = char, EdgeWeight = u32>>(mut graph: G) {
+    let [a, b, c] = ['a', 'b', 'c'].map(|name| graph.add_node(name));
+
+    assert_eq!(graph.add_edge(a, b, 1), None);
+    assert_eq!(graph.add_edge(a, b, 2), Some(1));
+    assert_eq!(graph.add_edge(b, c, 3), None);
+    assert_eq!((graph.node_count(), graph.edge_count()), (3, 2));
+    assert_eq!(graph.edge_weight(a, b), Some(&2));
+    assert_eq!(graph.contains_edge(b, a), !graph.is_directed());
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [b]);
+
+    assert_eq!(graph.remove_edge(a, b), Some(2));
+    assert_eq!(graph.remove_edge(a, b), None);
+    assert_eq!(graph.remove_node(c), Some('c'));
+    assert!(!graph.contains_node(c));
+    assert_eq!(graph.node_ids().collect::<Vec<NodeId>>(), [a, b]);
+    assert_eq!(graph.edge_count(), 0);
+  }
+
+  #[test]
+  fn adjacency_list() {
+    path(DiGraph::new());
+    path(UnGraph::new());
+  }
+
+  #[test]
+  fn adjacency_matrix() {
+    path(AdjacencyMatrixGraph::<_, _, Directed>::new());
+    path(AdjacencyMatrixGraph::<_, _, Undirected>::new());
+  }
+}
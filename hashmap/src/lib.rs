@@ -0,0 +1,615 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+
+/// Number of buckets allocated on the first insertion.
+const INITIAL_BUCKETS: usize = 8;
+
+/// Load factor used by `HashMap::new`.
+const DEFAULT_LOAD_FACTOR: f64 = 0.75;
+
+/// A hash map implementation based on separate chaining.
+///
+/// This data structure stores key-value pairs in an array of buckets. The
+/// hash of a key selects the bucket, and every bucket holds a small list of
+/// the pairs whose keys landed in it. When the ratio of stored pairs to
+/// buckets (the load factor) would exceed the configured maximum, the number
+/// of buckets is doubled and every pair is redistributed, which keeps the
+/// chains short and the expected cost of every operation O(1).
+///
+/// # Example
+///
+/// ```
+/// use hashmap::HashMap;
+///
+/// let mut map = HashMap::<String, i32>::new();
+/// map.insert("one".to_string(), 1);
+/// map.insert("two".to_string(), 2);
+///
+/// assert_eq!(map.get("one"), Some(&1));
+/// assert_eq!(map.insert("one".to_string(), 10), Some(1));
+///
+/// *map.entry("three".to_string()).or_insert(0) += 3;
+/// assert_eq!(map.get("three"), Some(&3));
+///
+/// assert_eq!(map.remove("two"), Some(2));
+/// assert_eq!(map.len(), 2);
+/// ```
+pub struct HashMap<K, V> {
+  buckets: Vec<Vec<(K, V)>>,
+  len: usize,
+  load_factor: f64,
+  hasher: RandomState
+}
+
+impl<K: Hash + Eq, V> Default for HashMap<K, V> {
+  /// Creates a new instance of `HashMap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Hash + Eq, V> HashMap<K, V> {
+  /// Creates a new empty hash map with the default load factor of 0.75.
+  pub fn new() -> Self {
+    Self::with_load_factor(DEFAULT_LOAD_FACTOR)
+  }
+
+  /// Creates a new empty hash map that grows whenever the number of
+  /// pairs per bucket would exceed `load_factor`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `load_factor` is not a positive finite number.
+  pub fn with_load_factor(load_factor: f64) -> Self {
+    assert!(load_factor.is_finite() && load_factor > 0.0, "load factor must be positive");
+
+    Self { buckets: Vec::new(), len: 0, load_factor, hasher: RandomState::new() }
+  }
+
+  /// Returns the number of pairs in the map.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the map is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the maximum ratio of pairs to buckets before the map grows.
+  pub fn load_factor(&self) -> f64 {
+    self.load_factor
+  }
+
+  /// Inserts a key-value pair into the map.
+  ///
+  /// Returns the previous value if the key was already present.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.entry(key) {
+      Entry::Occupied(mut entry) => Some(entry.insert(value)),
+      Entry::Vacant(entry) => {
+        entry.insert(value);
+        None
+      }
+    }
+  }
+
+  /// Returns a reference to the value corresponding to the key.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let (bucket, index) = self.find(key)?;
+    Some(&self.buckets[bucket][index].1)
+  }
+
+  /// Returns a mutable reference to the value corresponding to the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let (bucket, index) = self.find(key)?;
+    Some(&mut self.buckets[bucket][index].1)
+  }
+
+  /// Checks if the map contains a value for the given key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.find(key).is_some()
+  }
+
+  /// Removes a key from the map and returns its value,
+  /// or `None` if the key was not present.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let (bucket, index) = self.find(key)?;
+    self.len -= 1;
+    Some(self.buckets[bucket].swap_remove(index).1)
+  }
+
+  /// Returns the entry for the given key for in-place manipulation.
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    match self.find(&key) {
+      Some((bucket, index)) => Entry::Occupied(OccupiedEntry { pair: &mut self.buckets[bucket][index] }),
+      None => Entry::Vacant(VacantEntry { map: self, key }),
+    }
+  }
+
+  /// Returns an iterator over the key-value pairs in arbitrary order.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { buckets: self.buckets.iter(), pairs: [].iter(), remaining: self.len }
+  }
+
+  /// Returns an iterator over the keys in arbitrary order.
+  pub fn keys(&self) -> Keys<'_, K, V> {
+    Keys { inner: self.iter() }
+  }
+
+  /// Returns an iterator over the values in arbitrary order.
+  pub fn values(&self) -> Values<'_, K, V> {
+    Values { inner: self.iter() }
+  }
+
+  /// Removes every pair from the map, keeping the allocated buckets.
+  pub fn clear(&mut self) {
+    for bucket in &mut self.buckets {
+      bucket.clear();
+    }
+
+    self.len = 0;
+  }
+
+  /// Returns the index of the bucket the key belongs to.
+  fn bucket<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+    (self.hasher.hash_one(key) % self.buckets.len() as u64) as usize
+  }
+
+  /// Returns the bucket and the position inside the bucket of the key.
+  fn find<Q>(&self, key: &Q) -> Option<(usize, usize)>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    if self.buckets.is_empty() {
+      return None;
+    }
+
+    let bucket = self.bucket(key);
+    let index = self.buckets[bucket].iter().position(|(k, _)| k.borrow() == key)?;
+
+    Some((bucket, index))
+  }
+
+  /// Doubles the number of buckets if one more pair would exceed
+  /// the load factor.
+  fn reserve_one(&mut self) {
+    if ((self.len + 1) as f64) <= self.buckets.len() as f64 * self.load_factor {
+      return;
+    }
+
+    let count = (self.buckets.len() * 2).max(INITIAL_BUCKETS);
+    let old = std::mem::replace(&mut self.buckets, (0..count).map(|_| Vec::new()).collect());
+
+    for (key, value) in old.into_iter().flatten() {
+      let bucket = self.bucket(&key);
+      self.buckets[bucket].push((key, value));
+    }
+  }
+}
+
+/// A view into a single entry of a `HashMap`, which may either be
+/// vacant or occupied.
+pub enum Entry<'a, K, V> {
+  /// The key is present in the map.
+  Occupied(OccupiedEntry<'a, K, V>),
+  /// The key is absent from the map.
+  Vacant(VacantEntry<'a, K, V>)
+}
+
+/// A view into an occupied entry of a `HashMap`.
+pub struct OccupiedEntry<'a, K, V> {
+  pair: &'a mut (K, V)
+}
+
+/// A view into a vacant entry of a `HashMap`.
+pub struct VacantEntry<'a, K, V> {
+  map: &'a mut HashMap<K, V>,
+  key: K
+}
+
+impl<'a, K: Hash + Eq, V> Entry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key(),
+    }
+  }
+
+  /// Inserts `default` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    self.or_insert_with(|| default)
+  }
+
+  /// Inserts the result of `default` if the entry is vacant and returns
+  /// a mutable reference to the value.
+  pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default()),
+    }
+  }
+
+  /// Inserts the default value if the entry is vacant and returns a
+  /// mutable reference to the value.
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// Calls `f` with the value if the entry is occupied, and returns the
+  /// entry so that it can be chained with `or_insert`.
+  pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      entry => entry,
+    }
+  }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+  /// Returns a reference to the key of the entry.
+  pub fn key(&self) -> &K {
+    &self.pair.0
+  }
+
+  /// Returns a reference to the value of the entry.
+  pub fn get(&self) -> &V {
+    &self.pair.1
+  }
+
+  /// Returns a mutable reference to the value of the entry.
+  pub fn get_mut(&mut self) -> &mut V {
+    &mut self.pair.1
+  }
+
+  /// Converts the entry into a mutable reference bound to the map.
+  pub fn into_mut(self) -> &'a mut V {
+    &mut self.pair.1
+  }
+
+  /// Replaces the value of the entry and returns the old one.
+  pub fn insert(&mut self, value: V) -> V {
+    std::mem::replace(&mut self.pair.1, value)
+  }
+}
+
+impl<'a, K: Hash + Eq, V> VacantEntry<'a, K, V> {
+  /// Returns a reference to the key that would be inserted.
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+
+  /// Inserts the value under the entry's key and returns a mutable
+  /// reference to it.
+  pub fn insert(self, value: V) -> &'a mut V {
+    let map = self.map;
+    map.reserve_one();
+
+    let bucket = map.bucket(&self.key);
+    map.buckets[bucket].push((self.key, value));
+    map.len += 1;
+
+    &mut map.buckets[bucket].last_mut().unwrap().1
+  }
+}
+
+/// An iterator over the key-value pairs of a `HashMap`.
+pub struct Iter<'a, K, V> {
+  buckets: std::slice::Iter<'a, Vec<(K, V)>>,
+  pairs: std::slice::Iter<'a, (K, V)>,
+  remaining: usize
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  /// Advances the iterator and returns the next pair, moving on to the
+  /// following non-empty bucket when the current one is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((key, value)) = self.pairs.next() {
+        self.remaining -= 1;
+        return Some((key, value));
+      }
+
+      self.pairs = self.buckets.next()?.iter();
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+/// An iterator over the keys of a `HashMap`.
+pub struct Keys<'a, K, V> {
+  inner: Iter<'a, K, V>
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+  type Item = &'a K;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(key, _)| key)
+  }
+}
+
+/// An iterator over the values of a `HashMap`.
+pub struct Values<'a, K, V> {
+  inner: Iter<'a, K, V>
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+  type Item = &'a V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(_, value)| value)
+  }
+}
+
+impl<'a, K: Hash + Eq, V> IntoIterator for &'a HashMap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for HashMap<K, V> {
+  /// Creates a hash map from an iterator of key-value pairs. Later
+  /// pairs overwrite earlier pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut map = Self::new();
+
+    for (key, value) in iter {
+      map.insert(key, value);
+    }
+
+    map
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for HashMap<K, V>
+where
+  K: serde::Serialize + Hash + Eq,
+  V: serde::Serialize,
+{
+  /// Serializes the pairs as a map in arbitrary order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for HashMap<K, V>
+where
+  K: serde::Deserialize<'de> + Hash + Eq,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map. Later pairs overwrite earlier pairs with the
+  /// same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Hash + Eq,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = HashMap<K, V>;
+
+      fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = HashMap::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          map.insert(key, value);
+        }
+
+        Ok(map)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Entry, HashMap};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_map() {
+    let map = HashMap::<i32, i32>::new();
+
+    assert!(map.is_empty());
+    assert_eq!(map.get(&42), None);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut map = HashMap::<i32, &str>::new();
+
+    assert_eq!(map.insert(1, "one"), None);
+    assert_eq!(map.insert(2, "two"), None);
+    assert_eq!(map.insert(1, "uno"), Some("one"));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&1), Some(&"uno"));
+    assert_eq!(map.get(&3), None);
+  }
+
+  #[test]
+  fn remove() {
+    let mut map: HashMap<i32, i32> = (0..10).map(|key| (key, key * key)).collect();
+
+    assert_eq!(map.remove(&3), Some(9));
+    assert_eq!(map.remove(&3), None);
+    assert!(!map.contains_key(&3));
+    assert_eq!(map.len(), 9);
+  }
+
+  #[test]
+  fn resize() {
+    let mut map = HashMap::<i32, i32>::with_load_factor(0.5);
+
+    for key in 0..1000 {
+      map.insert(key, key * 2);
+    }
+
+    assert_eq!(map.len(), 1000);
+    assert!(map.buckets.len() as f64 * map.load_factor() >= 1000.0);
+
+    for key in 0..1000 {
+      assert_eq!(map.get(&key), Some(&(key * 2)));
+    }
+  }
+
+  #[test]
+  fn entry() {
+    let mut map = HashMap::<&str, i32>::new();
+
+    for word in ["a", "b", "a", "c", "a"] {
+      *map.entry(word).or_insert(0) += 1;
+    }
+
+    assert_eq!(map.get("a"), Some(&3));
+    assert_eq!(map.get("b"), Some(&1));
+
+    match map.entry("b") {
+      Entry::Occupied(mut entry) => assert_eq!(entry.insert(10), 1),
+      Entry::Vacant(_) => unreachable!(),
+    }
+
+    assert_eq!(map.get("b"), Some(&10));
+  }
+
+  #[test]
+  fn entry_and_modify() {
+    let mut map = HashMap::<&str, Vec<i32>>::new();
+
+    map.entry("a").and_modify(|values| values.push(1)).or_default();
+    assert_eq!(map.get("a"), Some(&vec![]));
+
+    map.entry("a").and_modify(|values| values.push(2)).or_insert_with(|| vec![0]);
+    map.entry("b").and_modify(|values| values.push(3)).or_insert_with(|| vec![0]);
+    assert_eq!(map.get("a"), Some(&vec![2]));
+    assert_eq!(map.get("b"), Some(&vec![0]));
+    assert_eq!(map.len(), 2);
+  }
+
+  #[test]
+  fn iterators() {
+    let map: HashMap<i32, i32> = (0..5).map(|key| (key, key * 10)).collect();
+
+    let mut pairs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+    pairs.sort();
+    assert_eq!(pairs, vec![(0, 0), (1, 10), (2, 20), (3, 30), (4, 40)]);
+
+    let mut keys: Vec<_> = map.keys().copied().collect();
+    keys.sort();
+    assert_eq!(keys, vec![0, 1, 2, 3, 4]);
+
+    assert_eq!(map.values().sum::<i32>(), 100);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: HashMap<String, i32> = [("one".to_string(), 1), ("two".to_string(), 2)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    let map: HashMap<String, i32> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get("one"), Some(&1));
+    assert_eq!(map.get("two"), Some(&2));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Get(u8),
+    Increment(u8),
+    Clear
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      4 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      2 => any::<u8>().prop_map(Operation::Get),
+      2 => any::<u8>().prop_map(Operation::Increment),
+      1 => Just(Operation::Clear)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_std(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut map = HashMap::new();
+      let mut model = std::collections::HashMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(map.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(map.remove(&key), model.remove(&key)),
+          Operation::Get(key) => prop_assert_eq!(map.get(&key), model.get(&key)),
+          Operation::Increment(key) => {
+            let value = map.entry(key).or_insert(0);
+            *value = value.wrapping_add(1);
+            let value = model.entry(key).or_insert(0);
+            *value = value.wrapping_add(1);
+          }
+          Operation::Clear => {
+            map.clear();
+            model.clear();
+          }
+        }
+
+        prop_assert_eq!(map.len(), model.len());
+      }
+
+      let mut pairs: Vec<_> = map.iter().map(|(key, value)| (*key, *value)).collect();
+      pairs.sort();
+      let mut expected: Vec<_> = model.into_iter().collect();
+      expected.sort();
+      prop_assert_eq!(pairs, expected);
+    }
+  }
+}
@@ -0,0 +1,101 @@
+//! Compares `HashSet` against `std::collections::HashSet` on inserting,
+//! removing and iterating over several sizes of sets.
+//!
+//! Run with `cargo bench -p hashset --bench std`.
+
+use std::hint::black_box;
+use std::collections::HashSet as StdHashSet;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use hashset::HashSet;
+use common::key;
+
+#[path = "../../benches/common.rs"]
+mod common;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn insert(c: &mut Criterion) {
+  let mut group = c.benchmark_group("hashset/insert");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("HashSet", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = HashSet::new();
+        for i in 0..size {
+          ours.insert(black_box(key(i)));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::HashSet", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = StdHashSet::new();
+        for i in 0..size {
+          std.insert(black_box(key(i)));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+  let mut group = c.benchmark_group("hashset/remove");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("HashSet", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<HashSet<_>>(),
+        |mut ours| {
+          for i in 0..size {
+            black_box(ours.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::HashSet", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<StdHashSet<_>>(),
+        |mut std| {
+          for i in 0..size {
+            black_box(std.remove(&key(i)));
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("hashset/iterate");
+
+  for size in SIZES {
+    let ours: HashSet<_> = (0..size).map(key).collect();
+    let std: StdHashSet<_> = (0..size).map(key).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("HashSet", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::HashSet", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, insert, remove, iterate);
+criterion_main!(benches);
@@ -0,0 +1,353 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use hashmap::{HashMap, Keys};
+
+
+/// A hash set implementation.
+///
+/// This data structure stores unique values and is built on top of the
+/// workspace's `HashMap`, using the values as keys and `()` as the mapped
+/// value. Membership tests, insertions and removals run in expected O(1).
+/// Set operations (`union`, `intersection`, `difference`) return lazy
+/// iterators that only produce elements when advanced.
+///
+/// # Example
+///
+/// ```
+/// use hashset::HashSet;
+///
+/// let a: HashSet<i32> = [1, 2, 3].into_iter().collect();
+/// let b: HashSet<i32> = [2, 3, 4].into_iter().collect();
+///
+/// let mut common: Vec<_> = a.intersection(&b).copied().collect();
+/// common.sort();
+/// assert_eq!(common, vec![2, 3]);
+///
+/// assert_eq!(a.union(&b).count(), 4);
+/// assert!(!a.is_subset(&b));
+/// ```
+pub struct HashSet<T> {
+  map: HashMap<T, ()>
+}
+
+impl<T: Hash + Eq> Default for HashSet<T> {
+  /// Creates a new instance of `HashSet` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Hash + Eq> HashSet<T> {
+  /// Creates a new empty hash set.
+  pub fn new() -> Self {
+    Self { map: HashMap::new() }
+  }
+
+  /// Returns the number of values in the set.
+  pub fn len(&self) -> usize {
+    self.map.len()
+  }
+
+  /// Checks if the set is empty.
+  pub fn is_empty(&self) -> bool {
+    self.map.is_empty()
+  }
+
+  /// Adds a value to the set.
+  ///
+  /// Returns `true` if the value was not already present.
+  pub fn insert(&mut self, value: T) -> bool {
+    self.map.insert(value, ()).is_none()
+  }
+
+  /// Checks if the set contains the value.
+  pub fn contains<Q>(&self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(value)
+  }
+
+  /// Removes a value from the set.
+  ///
+  /// Returns `true` if the value was present.
+  pub fn remove<Q>(&mut self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.remove(value).is_some()
+  }
+
+  /// Returns an iterator over the values in arbitrary order.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { keys: self.map.keys() }
+  }
+
+  /// Returns a lazy iterator over the values in `self` or `other`,
+  /// without duplicates.
+  pub fn union<'a>(&'a self, other: &'a HashSet<T>) -> Union<'a, T> {
+    Union { first: self.iter(), second: other.difference(self) }
+  }
+
+  /// Returns a lazy iterator over the values in both `self` and `other`.
+  pub fn intersection<'a>(&'a self, other: &'a HashSet<T>) -> Intersection<'a, T> {
+    Intersection { iter: self.iter(), other }
+  }
+
+  /// Returns a lazy iterator over the values in `self` but not in `other`.
+  pub fn difference<'a>(&'a self, other: &'a HashSet<T>) -> Difference<'a, T> {
+    Difference { iter: self.iter(), other }
+  }
+
+  /// Checks if every value of `self` is also in `other`.
+  pub fn is_subset(&self, other: &HashSet<T>) -> bool {
+    self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+  }
+
+  /// Checks if every value of `other` is also in `self`.
+  pub fn is_superset(&self, other: &HashSet<T>) -> bool {
+    other.is_subset(self)
+  }
+}
+
+/// An iterator over the values of a `HashSet`.
+pub struct Iter<'a, T> {
+  keys: Keys<'a, T, ()>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.keys.next()
+  }
+}
+
+/// A lazy iterator over the union of two `HashSet`s.
+pub struct Union<'a, T> {
+  first: Iter<'a, T>,
+  second: Difference<'a, T>
+}
+
+impl<'a, T: Hash + Eq> Iterator for Union<'a, T> {
+  type Item = &'a T;
+
+  /// Yields every value of the first set, then the values of the
+  /// second set that are missing from the first one.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.first.next().or_else(|| self.second.next())
+  }
+}
+
+/// A lazy iterator over the intersection of two `HashSet`s.
+pub struct Intersection<'a, T> {
+  iter: Iter<'a, T>,
+  other: &'a HashSet<T>
+}
+
+impl<'a, T: Hash + Eq> Iterator for Intersection<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let other = self.other;
+    self.iter.by_ref().find(|value| other.contains(*value))
+  }
+}
+
+/// A lazy iterator over the difference of two `HashSet`s.
+pub struct Difference<'a, T> {
+  iter: Iter<'a, T>,
+  other: &'a HashSet<T>
+}
+
+impl<'a, T: Hash + Eq> Iterator for Difference<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let other = self.other;
+    self.iter.by_ref().find(|value| !other.contains(*value))
+  }
+}
+
+impl<'a, T: Hash + Eq> IntoIterator for &'a HashSet<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for HashSet<T> {
+  /// Creates a hash set from an iterator, dropping duplicate values.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut set = Self::new();
+
+    for value in iter {
+      set.insert(value);
+    }
+
+    set
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Hash + Eq> serde::Serialize for HashSet<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the values as a sequence in arbitrary order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Hash + Eq> serde::Deserialize<'de> for HashSet<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a set from a sequence, dropping duplicate values.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::HashSet;
+  use proptest::prelude::*;
+
+  fn sorted<'a>(iter: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+    let mut values: Vec<_> = iter.copied().collect();
+    values.sort();
+    values
+  }
+
+  #[test]
+  fn empty_set() {
+    let set = HashSet::<i32>::new();
+
+    assert!(set.is_empty());
+    assert!(!set.contains(&42));
+  }
+
+  #[test]
+  fn insert_and_remove() {
+    let mut set = HashSet::<i32>::new();
+
+    assert!(set.insert(42));
+    assert!(!set.insert(42));
+    assert!(set.contains(&42));
+    assert_eq!(set.len(), 1);
+
+    assert!(set.remove(&42));
+    assert!(!set.remove(&42));
+    assert!(set.is_empty());
+  }
+
+  #[test]
+  fn union() {
+    let a: HashSet<i32> = [1, 2, 3].into_iter().collect();
+    let b: HashSet<i32> = [3, 4].into_iter().collect();
+
+    assert_eq!(sorted(a.union(&b)), vec![1, 2, 3, 4]);
+  }
+
+  #[test]
+  fn intersection() {
+    let a: HashSet<i32> = [1, 2, 3].into_iter().collect();
+    let b: HashSet<i32> = [2, 3, 4].into_iter().collect();
+
+    assert_eq!(sorted(a.intersection(&b)), vec![2, 3]);
+  }
+
+  #[test]
+  fn difference() {
+    let a: HashSet<i32> = [1, 2, 3].into_iter().collect();
+    let b: HashSet<i32> = [2, 3, 4].into_iter().collect();
+
+    assert_eq!(sorted(a.difference(&b)), vec![1]);
+    assert_eq!(sorted(b.difference(&a)), vec![4]);
+  }
+
+  #[test]
+  fn is_subset() {
+    let a: HashSet<i32> = [1, 2].into_iter().collect();
+    let b: HashSet<i32> = [1, 2, 3].into_iter().collect();
+
+    assert!(a.is_subset(&b));
+    assert!(!b.is_subset(&a));
+    assert!(b.is_superset(&a));
+    assert!(HashSet::new().is_subset(&a));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: HashSet<i32> = [1, 2, 3].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+
+    let set: HashSet<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sorted(set.iter()), vec![1, 2, 3]);
+
+    let set: HashSet<i32> = serde_json::from_str("[1, 1, 2]").unwrap();
+    assert_eq!(set.len(), 2);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8),
+    Remove(u8),
+    Contains(u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<u8>().prop_map(Operation::Insert),
+      any::<u8>().prop_map(Operation::Remove),
+      any::<u8>().prop_map(Operation::Contains)
+    ]
+  }
+
+  fn small_values() -> impl Strategy<Value = Vec<i32>> {
+    prop::collection::vec(0..32, 0..32)
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_std(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut set = HashSet::new();
+      let mut model = std::collections::HashSet::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(value) => prop_assert_eq!(set.insert(value), model.insert(value)),
+          Operation::Remove(value) => prop_assert_eq!(set.remove(&value), model.remove(&value)),
+          Operation::Contains(value) => prop_assert_eq!(set.contains(&value), model.contains(&value))
+        }
+
+        prop_assert_eq!(set.len(), model.len());
+      }
+    }
+
+    #[test]
+    fn set_operations_behave_like_std(a in small_values(), b in small_values()) {
+      let set_a: HashSet<i32> = a.iter().copied().collect();
+      let set_b: HashSet<i32> = b.iter().copied().collect();
+      let model_a: std::collections::HashSet<i32> = a.into_iter().collect();
+      let model_b: std::collections::HashSet<i32> = b.into_iter().collect();
+
+      prop_assert_eq!(sorted(set_a.union(&set_b)), sorted(model_a.union(&model_b)));
+      prop_assert_eq!(sorted(set_a.intersection(&set_b)), sorted(model_a.intersection(&model_b)));
+      prop_assert_eq!(sorted(set_a.difference(&set_b)), sorted(model_a.difference(&model_b)));
+      prop_assert_eq!(set_a.is_subset(&set_b), model_a.is_subset(&model_b));
+      prop_assert_eq!(set_a.is_superset(&set_b), model_a.is_superset(&model_b));
+    }
+  }
+}
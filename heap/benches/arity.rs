@@ -0,0 +1,122 @@
+//! Compares `DAryHeap` at several arities against `BinaryHeap` on a
+//! push-heavy workload, which pushes eight values for every pop, and a
+//! pop-heavy one, which pops eight values for every push. A greater arity
+//! makes `push` cheaper and `pop` compare more children on fewer levels.
+//!
+//! The values are pushed in a scrambled order. Pushed in ascending order,
+//! every value would sift up to the root of the max-heap, and `push` would
+//! always pay for the full height of the tree instead of the few levels it
+//! climbs on typical input.
+//!
+//! Run with `cargo bench -p heap --bench arity`.
+
+use std::hint::black_box;
+
+use criterion::measurement::WallTime;
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput
+};
+use heap::{BinaryHeap, DAryHeap};
+use common::key;
+
+#[path = "../../benches/common.rs"]
+mod common;
+
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Number of operations of the main kind for every operation of the other.
+const RATIO: usize = 8;
+
+/// Benchmarks the push-heavy workload on a `DAryHeap` of arity `D`.
+fn push_heavy_arity<const D: usize>(group: &mut BenchmarkGroup<'_, WallTime>, size: usize) {
+  group.bench_with_input(BenchmarkId::new(format!("DAryHeap<{}>", D), size), &size, |b, &size| {
+    b.iter(|| {
+      let mut heap = DAryHeap::<_, D>::new();
+      for i in 0..size {
+        heap.push(black_box(key(i)));
+        if i % RATIO == 0 {
+          black_box(heap.pop());
+        }
+      }
+      heap
+    })
+  });
+}
+
+/// Benchmarks the pop-heavy workload on a `DAryHeap` of arity `D`.
+fn pop_heavy_arity<const D: usize>(group: &mut BenchmarkGroup<'_, WallTime>, size: usize) {
+  group.bench_with_input(BenchmarkId::new(format!("DAryHeap<{}>", D), size), &size, |b, &size| {
+    b.iter_batched(
+      || (0..size).map(key).collect::<DAryHeap<_, D>>(),
+      |mut heap| {
+        for i in 0..size {
+          black_box(heap.pop());
+          if i % RATIO == 0 {
+            heap.push(black_box(key(size + i)));
+          }
+        }
+        heap
+      },
+      BatchSize::LargeInput
+    )
+  });
+}
+
+fn push_heavy(c: &mut Criterion) {
+  let mut group = c.benchmark_group("arity/push_heavy");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinaryHeap", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut heap: BinaryHeap<_> = BinaryHeap::new();
+        for i in 0..size {
+          heap.push(black_box(key(i)));
+          if i % RATIO == 0 {
+            black_box(heap.pop());
+          }
+        }
+        heap
+      })
+    });
+    push_heavy_arity::<2>(&mut group, size);
+    push_heavy_arity::<4>(&mut group, size);
+    push_heavy_arity::<8>(&mut group, size);
+    push_heavy_arity::<16>(&mut group, size);
+  }
+
+  group.finish();
+}
+
+fn pop_heavy(c: &mut Criterion) {
+  let mut group = c.benchmark_group("arity/pop_heavy");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinaryHeap", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<BinaryHeap<_>>(),
+        |mut heap| {
+          for i in 0..size {
+            black_box(heap.pop());
+            if i % RATIO == 0 {
+              heap.push(black_box(key(size + i)));
+            }
+          }
+          heap
+        },
+        BatchSize::LargeInput
+      )
+    });
+    pop_heavy_arity::<2>(&mut group, size);
+    pop_heavy_arity::<4>(&mut group, size);
+    pop_heavy_arity::<8>(&mut group, size);
+    pop_heavy_arity::<16>(&mut group, size);
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push_heavy, pop_heavy);
+criterion_main!(benches);
@@ -0,0 +1,102 @@
+//! Compares `BinaryHeap` against `std::collections::BinaryHeap` on pushing
+//! values in a scrambled order, popping all of them and iterating over
+//! several sizes of heaps.
+//!
+//! Run with `cargo bench -p heap --bench std`.
+
+use std::hint::black_box;
+use std::collections::BinaryHeap as StdBinaryHeap;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use heap::BinaryHeap;
+use common::key;
+
+#[path = "../../benches/common.rs"]
+mod common;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("heap/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinaryHeap", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours: BinaryHeap<_> = BinaryHeap::new();
+        for i in 0..size {
+          ours.push(black_box(key(i)));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BinaryHeap", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = StdBinaryHeap::new();
+        for i in 0..size {
+          std.push(black_box(key(i)));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("heap/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinaryHeap", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<BinaryHeap<_>>(),
+        |mut ours| {
+          for _ in 0..size {
+            black_box(ours.pop());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BinaryHeap", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).map(key).collect::<StdBinaryHeap<_>>(),
+        |mut std| {
+          for _ in 0..size {
+            black_box(std.pop());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("heap/iterate");
+
+  for size in SIZES {
+    let ours: BinaryHeap<_> = (0..size).map(key).collect();
+    let std: StdBinaryHeap<_> = (0..size).map(key).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("BinaryHeap", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::BinaryHeap", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, iterate);
+criterion_main!(benches);
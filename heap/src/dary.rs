@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use crate::{Max, Order};
+
+
+/// A d-ary heap, a binary heap whose nodes have `D` children.
+///
+/// The elements are stored in a complete `D`-ary tree laid out in a
+/// `Vec<T>`, the children of the element at `i` being at `D * i + 1` to
+/// `D * i + D`. A greater arity makes the tree shallower, with
+/// log<sub>D</sub>(n) levels, so `push`, which compares an element with
+/// one parent per level, gets faster. `pop` compares all the `D` children
+/// on every level, in O(D log<sub>D</sub>(n)), but they are next to each
+/// other in memory, so a small arity such as 4 often beats a binary heap
+/// on both, and a large one suits push-heavy workloads. A `DAryHeap` with
+/// an arity of 2 is a `BinaryHeap`.
+///
+/// Whether the greatest or the smallest element has the highest priority
+/// is selected with the `O` marker type: `Max` (the default) or `Min`.
+///
+/// # Example
+///
+/// ```
+/// use heap::{DAryHeap, Min};
+///
+/// let mut heap = DAryHeap::<i32, 4>::new();
+/// heap.push(3);
+/// heap.push(7);
+/// heap.push(1);
+///
+/// assert_eq!(heap.peek(), Some(&7));
+/// assert_eq!(heap.pop(), Some(7));
+/// assert_eq!(heap.len(), 2);
+///
+/// let heap = DAryHeap::<_, 8, Min>::from_vec(vec![5, 2, 8]);
+/// assert_eq!(heap.peek(), Some(&2));
+/// ```
+pub struct DAryHeap<T: Ord, const D: usize, O: Order = Max> {
+  items: Vec<T>,
+  order: PhantomData<O>
+}
+
+impl<T: Ord, const D: usize, O: Order> Default for DAryHeap<T, D, O> {
+  /// Creates a new instance of `DAryHeap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord, const D: usize, O: Order> DAryHeap<T, D, O> {
+  /// Creates a new empty heap.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `D` is zero.
+  pub fn new() -> Self {
+    Self::with_capacity(0)
+  }
+
+  /// Creates a new empty heap that can hold at least `capacity`
+  /// elements without reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `D` is zero.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self::from_vec(Vec::with_capacity(capacity))
+  }
+
+  /// Builds a heap from a vector in O(n) by sifting down every
+  /// non-leaf element, starting from the last one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `D` is zero.
+  pub fn from_vec(items: Vec<T>) -> Self {
+    assert!(D > 0, "arity must be positive");
+    let mut heap = Self { items, order: PhantomData };
+
+    for index in (0..heap.items.len().div_ceil(D)).rev() {
+      heap.sift_down(index, heap.items.len());
+    }
+
+    heap
+  }
+
+  /// Returns the number of elements in the heap.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Checks if the heap is empty.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns a reference to the element with the highest priority,
+  /// or `None` if the heap is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.items.first()
+  }
+
+  /// Pushes a value onto the heap in O(log<sub>D</sub>(n)).
+  pub fn push(&mut self, value: T) {
+    self.items.push(value);
+    self.sift_up(self.items.len() - 1);
+  }
+
+  /// Removes the element with the highest priority and returns it,
+  /// or `None` if the heap is empty, in O(D log<sub>D</sub>(n)).
+  pub fn pop(&mut self) -> Option<T> {
+    let last = self.items.len().checked_sub(1)?;
+    self.items.swap(0, last);
+
+    let value = self.items.pop();
+    self.sift_down(0, self.items.len());
+
+    value
+  }
+
+  /// Consumes the heap and returns its elements in arbitrary order.
+  pub fn into_vec(self) -> Vec<T> {
+    self.items
+  }
+
+  /// Consumes the heap and returns its elements sorted from the lowest
+  /// to the highest priority, i.e. the reverse of the order `pop` yields
+  /// them in.
+  pub fn into_sorted_vec(mut self) -> Vec<T> {
+    for end in (1..self.items.len()).rev() {
+      self.items.swap(0, end);
+      self.sift_down(0, end);
+    }
+
+    self.items
+  }
+
+  /// Returns an iterator over the elements of the heap in arbitrary order.
+  pub fn iter(&self) -> std::slice::Iter<'_, T> {
+    self.items.iter()
+  }
+
+  /// Moves the element at `index` up until its parent has a higher priority.
+  fn sift_up(&mut self, mut index: usize) {
+    while index > 0 {
+      let parent = (index - 1) / D;
+
+      if O::compare(&self.items[index], &self.items[parent]) != Ordering::Greater {
+        break;
+      }
+
+      self.items.swap(index, parent);
+      index = parent;
+    }
+  }
+
+  /// Moves the element at `index` down until all of its children within
+  /// the first `len` elements have a lower priority.
+  fn sift_down(&mut self, mut index: usize, len: usize) {
+    loop {
+      let first = D * index + 1;
+      let mut largest = index;
+
+      for child in first..len.min(first + D) {
+        if O::compare(&self.items[child], &self.items[largest]) == Ordering::Greater {
+          largest = child;
+        }
+      }
+
+      if largest == index {
+        break;
+      }
+
+      self.items.swap(index, largest);
+      index = largest;
+    }
+  }
+}
+
+impl<T: Ord, const D: usize, O: Order> From<Vec<T>> for DAryHeap<T, D, O> {
+  /// Builds a heap from a vector in O(n).
+  fn from(items: Vec<T>) -> Self {
+    Self::from_vec(items)
+  }
+}
+
+impl<T: Ord, const D: usize, O: Order> FromIterator<T> for DAryHeap<T, D, O> {
+  /// Builds a heap from the elements of an iterator in O(n).
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    Self::from_vec(iter.into_iter().collect())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Ord, const D: usize, O: Order> serde::Serialize for DAryHeap<T, D, O>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence in heap (arbitrary) order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.items.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord, const D: usize, O: Order> serde::Deserialize<'de> for DAryHeap<T, D, O>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a heap from a sequence in any order, restoring the heap
+  /// property in O(n), whatever the arity of the serialized heap.
+  fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    Vec::deserialize(deserializer).map(Self::from_vec)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::DAryHeap;
+  use crate::{Max, Min};
+  use proptest::prelude::*;
+
+  /// Pops all the elements of a heap built from the values.
+  fn drain<const D: usize>(values: &[i32]) -> Vec<i32> {
+    let mut heap: DAryHeap<_, D> = values.iter().copied().collect();
+    std::iter::from_fn(|| heap.pop()).collect()
+  }
+
+  #[test]
+  fn empty_heap() {
+    let mut heap = DAryHeap::<i32, 3>::new();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+    assert_eq!(heap.pop(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "arity must be positive")]
+  fn zero_arity() {
+    DAryHeap::<i32, 0>::new();
+  }
+
+  #[test]
+  fn pops_in_order_for_any_arity() {
+    let values = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+    let expected = [9, 6, 5, 5, 5, 4, 3, 3, 2, 1, 1];
+
+    assert_eq!(drain::<1>(&values), expected);
+    assert_eq!(drain::<2>(&values), expected);
+    assert_eq!(drain::<3>(&values), expected);
+    assert_eq!(drain::<4>(&values), expected);
+    assert_eq!(drain::<16>(&values), expected);
+  }
+
+  #[test]
+  fn push_and_min_order() {
+    let mut heap = DAryHeap::<i32, 3, Min>::new();
+    for value in [5, 8, 2, 7, 1, 9] {
+      heap.push(value);
+    }
+
+    assert_eq!(heap.len(), 6);
+    assert_eq!(heap.peek(), Some(&1));
+    assert_eq!(heap.into_sorted_vec(), [9, 8, 7, 5, 2, 1]);
+  }
+
+  #[test]
+  fn into_sorted_vec() {
+    let heap: DAryHeap<_, 4, Max> = DAryHeap::from(vec![4, 10, 3, 5, 1, 7]);
+
+    assert_eq!(heap.iter().count(), 6);
+    assert_eq!(heap.into_sorted_vec(), [1, 3, 4, 5, 7, 10]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: DAryHeap<i32, 4> = [1, 3, 2, 5].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[5,3,2,1]");
+
+    let heap: DAryHeap<i32, 2, Min> = serde_json::from_str(&json).unwrap();
+    assert_eq!(heap.into_sorted_vec(), [5, 3, 2, 1]);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![any::<i32>().prop_map(Operation::Push), Just(Operation::Pop)]
+  }
+
+  /// Applies the operations to a heap and checks it against the standard
+  /// library.
+  fn matches_std<const D: usize>(operations: &[Operation]) -> Result<(), TestCaseError> {
+    let mut heap = DAryHeap::<i32, D>::new();
+    let mut model = std::collections::BinaryHeap::new();
+
+    for operation in operations {
+      match *operation {
+        Operation::Push(value) => {
+          heap.push(value);
+          model.push(value);
+        }
+        Operation::Pop => prop_assert_eq!(heap.pop(), model.pop())
+      }
+
+      prop_assert_eq!(heap.peek(), model.peek());
+      prop_assert_eq!(heap.len(), model.len());
+    }
+
+    Ok(())
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_std_binary_heap(operations in prop::collection::vec(operation(), 0..200)) {
+      matches_std::<2>(&operations)?;
+      matches_std::<3>(&operations)?;
+      matches_std::<5>(&operations)?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_vec_is_a_heap(values in prop::collection::vec(any::<i32>(), 0..100)) {
+      let mut sorted = values.clone();
+      sorted.sort();
+      prop_assert_eq!(DAryHeap::<_, 3>::from_vec(values.clone()).into_sorted_vec(), sorted.clone());
+      prop_assert_eq!(DAryHeap::<_, 7>::from_vec(values).into_sorted_vec(), sorted);
+    }
+  }
+}
@@ -0,0 +1,380 @@
+pub mod dary;
+pub mod pairing;
+
+pub use dary::DAryHeap;
+pub use pairing::{Handle, PairingHeap};
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+
+/// Decides which of two elements belongs closer to the top of a heap.
+pub trait Order {
+  /// Compares two elements. The element that compares as `Greater`
+  /// has the higher priority and is popped first.
+  fn compare<T: Ord>(a: &T, b: &T) -> Ordering;
+}
+
+/// Marker type for a heap that pops the greatest element first.
+pub struct Max;
+
+/// Marker type for a heap that pops the smallest element first.
+pub struct Min;
+
+impl Order for Max {
+  fn compare<T: Ord>(a: &T, b: &T) -> Ordering {
+    a.cmp(b)
+  }
+}
+
+impl Order for Min {
+  fn compare<T: Ord>(a: &T, b: &T) -> Ordering {
+    b.cmp(a)
+  }
+}
+
+/// A heap that pops the greatest element first.
+pub type MaxHeap<T> = BinaryHeap<T, Max>;
+
+/// A heap that pops the smallest element first.
+pub type MinHeap<T> = BinaryHeap<T, Min>;
+
+/// A binary heap (priority queue) implementation.
+///
+/// This data structure stores its elements in a complete binary tree laid
+/// out in a `Vec<T>`, where every parent has a priority at least as high as
+/// its children. The element with the highest priority is always at the
+/// root, so it can be inspected in O(1) and removed in O(log n).
+///
+/// Whether the greatest or the smallest element has the highest priority
+/// is selected with the `O` marker type: `Max` (the default) or `Min`.
+///
+/// # Example
+///
+/// ```
+/// use heap::{MaxHeap, MinHeap};
+///
+/// let mut heap = MaxHeap::<i32>::new();
+/// heap.push(3);
+/// heap.push(7);
+/// heap.push(1);
+///
+/// assert_eq!(heap.peek(), Some(&7));
+/// assert_eq!(heap.pop(), Some(7));
+/// assert_eq!(heap.len(), 2);
+///
+/// let heap = MinHeap::from_vec(vec![5, 2, 8]);
+/// assert_eq!(heap.peek(), Some(&2));
+/// ```
+pub struct BinaryHeap<T: Ord, O: Order = Max> {
+  items: Vec<T>,
+  order: PhantomData<O>
+}
+
+impl<T: Ord, O: Order> Default for BinaryHeap<T, O> {
+  /// Creates a new instance of `BinaryHeap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord, O: Order> BinaryHeap<T, O> {
+  /// Creates a new empty heap.
+  pub fn new() -> Self {
+    Self { items: Vec::new(), order: PhantomData }
+  }
+
+  /// Creates a new empty heap that can hold at least `capacity`
+  /// elements without reallocating.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self { items: Vec::with_capacity(capacity), order: PhantomData }
+  }
+
+  /// Builds a heap from a vector in O(n) by sifting down every
+  /// non-leaf element, starting from the last one.
+  pub fn from_vec(items: Vec<T>) -> Self {
+    let mut heap = Self { items, order: PhantomData };
+
+    for index in (0..heap.items.len() / 2).rev() {
+      heap.sift_down(index, heap.items.len());
+    }
+
+    heap
+  }
+
+  /// Returns the number of elements in the heap.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Checks if the heap is empty.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns a reference to the element with the highest priority,
+  /// or `None` if the heap is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.items.first()
+  }
+
+  /// Pushes a value onto the heap.
+  pub fn push(&mut self, value: T) {
+    self.items.push(value);
+    self.sift_up(self.items.len() - 1);
+  }
+
+  /// Removes the element with the highest priority and returns it,
+  /// or `None` if the heap is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    let last = self.items.len().checked_sub(1)?;
+    self.items.swap(0, last);
+
+    let value = self.items.pop();
+    self.sift_down(0, self.items.len());
+
+    value
+  }
+
+  /// Consumes the heap and returns its elements in arbitrary order.
+  pub fn into_vec(self) -> Vec<T> {
+    self.items
+  }
+
+  /// Consumes the heap and returns its elements sorted from the lowest
+  /// to the highest priority (ascending for a `MaxHeap`, descending for
+  /// a `MinHeap`), i.e. the reverse of the order `pop` yields them in.
+  ///
+  /// The elements are sorted in place with heapsort in O(n log n).
+  pub fn into_sorted_vec(mut self) -> Vec<T> {
+    for end in (1..self.items.len()).rev() {
+      self.items.swap(0, end);
+      self.sift_down(0, end);
+    }
+
+    self.items
+  }
+
+  /// Returns an iterator over the elements of the heap in arbitrary order.
+  pub fn iter(&self) -> std::slice::Iter<'_, T> {
+    self.items.iter()
+  }
+
+  /// Moves the element at `index` up until its parent has a higher priority.
+  fn sift_up(&mut self, mut index: usize) {
+    while index > 0 {
+      let parent = (index - 1) / 2;
+
+      if O::compare(&self.items[index], &self.items[parent]) != Ordering::Greater {
+        break;
+      }
+
+      self.items.swap(index, parent);
+      index = parent;
+    }
+  }
+
+  /// Moves the element at `index` down until both of its children within
+  /// the first `len` elements have a lower priority.
+  fn sift_down(&mut self, mut index: usize, len: usize) {
+    loop {
+      let left = 2 * index + 1;
+      let right = left + 1;
+      let mut largest = index;
+
+      if left < len && O::compare(&self.items[left], &self.items[largest]) == Ordering::Greater {
+        largest = left;
+      }
+
+      if right < len && O::compare(&self.items[right], &self.items[largest]) == Ordering::Greater {
+        largest = right;
+      }
+
+      if largest == index {
+        break;
+      }
+
+      self.items.swap(index, largest);
+      index = largest;
+    }
+  }
+}
+
+impl<T: Ord, O: Order> From<Vec<T>> for BinaryHeap<T, O> {
+  /// Builds a heap from a vector in O(n).
+  fn from(items: Vec<T>) -> Self {
+    Self::from_vec(items)
+  }
+}
+
+impl<T: Ord, O: Order> FromIterator<T> for BinaryHeap<T, O> {
+  /// Builds a heap from the elements of an iterator in O(n).
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    Self::from_vec(iter.into_iter().collect())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Ord, O: Order> serde::Serialize for BinaryHeap<T, O>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence in heap (arbitrary) order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.items.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord, O: Order> serde::Deserialize<'de> for BinaryHeap<T, O>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a heap from a sequence in any order, restoring the heap property in O(n).
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(Self::from_vec)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{MaxHeap, MinHeap};
+  use std::cmp::Reverse;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_heap() {
+    let mut heap = MaxHeap::<i32>::new();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+    assert_eq!(heap.pop(), None);
+  }
+
+  #[test]
+  fn push() {
+    let mut heap = MaxHeap::<i32>::new();
+    heap.push(42);
+    heap.push(314);
+    heap.push(7);
+
+    assert_eq!(heap.len(), 3);
+    assert_eq!(heap.peek(), Some(&314));
+  }
+
+  #[test]
+  fn pop() {
+    let mut heap = MaxHeap::<i32>::new();
+
+    for value in [5, 1, 8, 3, 9, 2] {
+      heap.push(value);
+    }
+
+    let popped: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, vec![9, 8, 5, 3, 2, 1]);
+  }
+
+  #[test]
+  fn min_heap() {
+    let mut heap = MinHeap::<i32>::new();
+
+    for value in [5, 1, 8, 3] {
+      heap.push(value);
+    }
+
+    assert_eq!(heap.pop(), Some(1));
+    assert_eq!(heap.pop(), Some(3));
+    assert_eq!(heap.pop(), Some(5));
+  }
+
+  #[test]
+  fn from_vec() {
+    let mut heap = MaxHeap::from_vec(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+
+    assert_eq!(heap.len(), 8);
+    assert_eq!(heap.pop(), Some(9));
+    assert_eq!(heap.pop(), Some(6));
+    assert_eq!(heap.pop(), Some(5));
+  }
+
+  #[test]
+  fn into_sorted_vec() {
+    let heap = MaxHeap::from_vec(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+    assert_eq!(heap.into_sorted_vec(), vec![1, 1, 2, 3, 4, 5, 6, 9]);
+
+    let heap = MinHeap::from_vec(vec![3, 1, 4, 1, 5]);
+    assert_eq!(heap.into_sorted_vec(), vec![5, 4, 3, 1, 1]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = MaxHeap::from_vec(vec![3, 1, 4, 1, 5]);
+
+    let json = serde_json::to_string(&value).unwrap();
+
+    let heap: MaxHeap<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(heap.into_sorted_vec(), value.into_sorted_vec());
+
+    let heap: MinHeap<i32> = serde_json::from_str("[3, 1, 2]").unwrap();
+    assert_eq!(heap.peek(), Some(&1));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop,
+    Peek
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Push),
+      Just(Operation::Pop),
+      Just(Operation::Peek)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn max_heap_behaves_like_std(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut heap = MaxHeap::new();
+      let mut model = std::collections::BinaryHeap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            heap.push(value);
+            model.push(value);
+          }
+          Operation::Pop => prop_assert_eq!(heap.pop(), model.pop()),
+          Operation::Peek => prop_assert_eq!(heap.peek(), model.peek())
+        }
+
+        prop_assert_eq!(heap.len(), model.len());
+      }
+
+      prop_assert_eq!(heap.into_sorted_vec(), model.into_sorted_vec());
+    }
+
+    #[test]
+    fn min_heap_behaves_like_std(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut heap = MinHeap::new();
+      let mut model = std::collections::BinaryHeap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            heap.push(value);
+            model.push(Reverse(value));
+          }
+          Operation::Pop => prop_assert_eq!(heap.pop(), model.pop().map(|Reverse(value)| value)),
+          Operation::Peek => prop_assert_eq!(heap.peek(), model.peek().map(|Reverse(value)| value))
+        }
+
+        prop_assert_eq!(heap.len(), model.len());
+      }
+    }
+  }
+}
@@ -0,0 +1,668 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use crate::{Max, Order};
+
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// Represents a node in a pairing heap, with its children in a list.
+struct Node<T> {
+  value: T,
+  /// The first child, the most recently linked.
+  child: Link<T>,
+  /// The next child of the same parent.
+  sibling: Link<T>,
+  /// The parent of the first child, the previous sibling of the others,
+  /// and `None` for the root.
+  prev: Link<T>,
+  entry: Rc<Entry<T>>
+}
+
+/// Identity of a heap. A merged heap forwards to the heap it was merged
+/// into, so the handles of its elements keep working in that heap.
+struct Owner {
+  forward: RefCell<Option<Rc<Owner>>>
+}
+
+/// What a handle knows about its element.
+struct Entry<T> {
+  /// The node of the element, or `None` once it left the heap.
+  node: Cell<Link<T>>,
+  /// The heap the element was pushed into.
+  owner: RefCell<Rc<Owner>>
+}
+
+/// A handle to an element of a `PairingHeap`, returned by `push`, to read
+/// its value or raise its priority later.
+///
+/// A handle stays valid until its element is popped or its heap is
+/// dropped, and follows the element when its heap is merged into another.
+pub struct Handle<T>(Rc<Entry<T>>);
+
+/// A pairing heap, a heap that merges with another one in O(1).
+///
+/// The heap is a tree whose every node has a priority at least as high as
+/// its children. Pushing a value and merging two heaps link the roots of
+/// two trees, the one with the lower priority becoming the first child of
+/// the other, in O(1). Popping the root merges its children in pairs from
+/// left to right, then the pairs from right to left, in amortized
+/// O(log n). This lazy shape makes the heap fast in practice and lets
+/// `decrease_key` cut an element from its parent and link it to the root
+/// in O(1), amortized O(log n), where a `BinaryHeap` can not find its
+/// elements at all. This speeds up algorithms such as Dijkstra's shortest
+/// paths, which update the priority of queued elements.
+///
+/// Whether the greatest or the smallest element has the highest priority
+/// is selected with the `O` marker type: `Max` (the default) or `Min`.
+///
+/// # Example
+///
+/// ```
+/// use heap::{Min, PairingHeap};
+///
+/// let mut heap = PairingHeap::<u32, Min>::new();
+/// heap.push(5);
+/// let seven = heap.push(7);
+///
+/// let mut other = PairingHeap::new();
+/// other.push(3);
+/// heap.merge(other);
+/// assert_eq!(heap.peek(), Some(&3));
+///
+/// // 7 becomes 1, a higher priority in a `Min` heap.
+/// heap.decrease_key(&seven, 1);
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.get(&seven), None);
+/// assert_eq!(heap.pop(), Some(3));
+/// ```
+pub struct PairingHeap<T: Ord, O: Order = Max> {
+  root: Link<T>,
+  len: usize,
+  owner: Rc<Owner>,
+  order: PhantomData<O>,
+  /// The heap owns the nodes it points to.
+  nodes: PhantomData<Box<Node<T>>>
+}
+
+impl<T: Ord, O: Order> Default for PairingHeap<T, O> {
+  /// Creates a new instance of `PairingHeap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord, O: Order> PairingHeap<T, O> {
+  /// Creates a new empty heap.
+  pub fn new() -> Self {
+    Self { root: None, len: 0, owner: Rc::new(Owner { forward: RefCell::new(None) }), order: PhantomData, nodes: PhantomData }
+  }
+
+  /// Returns the number of elements in the heap.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the heap is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns a reference to the element with the highest priority,
+  /// or `None` if the heap is empty.
+  pub fn peek(&self) -> Option<&T> {
+    // SAFETY: the root is a node owned by the heap, borrowed as long as
+    // the heap is.
+    self.root.map(|root| unsafe { &(*root.as_ptr()).value })
+  }
+
+  /// Pushes a value onto the heap in O(1) and returns a handle to it.
+  pub fn push(&mut self, value: T) -> Handle<T> {
+    // Compares before allocating, so a panicking `Ord` leaves the heap as
+    // it was.
+    // SAFETY: the root is a node owned by the heap.
+    let wins = self.root.map(|root| unsafe { O::compare(&value, &(*root.as_ptr()).value) } == Ordering::Greater);
+
+    let entry = Rc::new(Entry { node: Cell::new(None), owner: RefCell::new(Rc::clone(&self.owner)) });
+    let node = NonNull::from(Box::leak(Box::new(Node { value, child: None, sibling: None, prev: None, entry: Rc::clone(&entry) })));
+    entry.node.set(Some(node));
+
+    // SAFETY: the root and the new node are detached trees of the heap.
+    self.root = Some(match (self.root, wins) {
+      (Some(root), Some(true)) => unsafe { attach(node, root) },
+      (Some(root), _) => unsafe { attach(root, node) },
+      (None, _) => node
+    });
+    self.len += 1;
+
+    Handle(entry)
+  }
+
+  /// Removes the element with the highest priority and returns it,
+  /// or `None` if the heap is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    let root = self.root?;
+
+    // SAFETY: the children are merged while the root still holds them, so
+    // a panicking `Ord` leaves a valid heap. The root was leaked from a box
+    // by `push` and is only freed once its single child replaced it.
+    unsafe {
+      combine::<T, O>(root);
+      let node = Box::from_raw(root.as_ptr());
+      node.entry.node.set(None);
+      self.root = node.child.inspect(|child| (*child.as_ptr()).prev = None);
+      self.len -= 1;
+
+      Some(node.value)
+    }
+  }
+
+  /// Moves all the elements of the other heap into this one in O(1).
+  ///
+  /// The handles of the other heap stay valid and refer to the elements in
+  /// this heap.
+  pub fn merge(&mut self, mut other: Self) {
+    self.root = match (self.root, other.root) {
+      // SAFETY: both roots are detached trees. If `link` panics, neither
+      // heap was changed.
+      (Some(root), Some(other)) => Some(unsafe { link::<T, O>(root, other) }),
+      (root, other) => root.or(other)
+    };
+    other.root = None;
+    self.len += std::mem::take(&mut other.len);
+    *other.owner.forward.borrow_mut() = Some(Rc::clone(&self.owner));
+  }
+
+  /// Returns a reference to the element of the handle, or `None` if it is
+  /// not in the heap.
+  pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+    // SAFETY: the node is owned by the heap, borrowed as long as the heap
+    // is.
+    self.node(handle).map(|node| unsafe { &(*node.as_ptr()).value })
+  }
+
+  /// Replaces the element of the handle with a value of a priority at
+  /// least as high, smaller in a `Min` heap and greater in a `Max` heap, in
+  /// O(1), amortized O(log n).
+  ///
+  /// # Panics
+  ///
+  /// Panics if the element of the handle is not in the heap, or if the
+  /// value has a lower priority than the element.
+  pub fn decrease_key(&mut self, handle: &Handle<T>, value: T) {
+    let node = self.node(handle).expect("invalid handle");
+    let n = node.as_ptr();
+
+    // SAFETY: the node is owned by the heap. Cutting it from its parent
+    // makes it a detached tree, which is linked back to the root. Both
+    // comparisons run before the heap is changed.
+    unsafe {
+      assert!(O::compare(&value, &(*n).value) != Ordering::Less, "new value must not have lower priority");
+
+      let Some(prev) = (*n).prev else {
+        (*n).value = value;
+        return;
+      };
+      let root = self.root.expect("a node with a parent is not the root");
+      let wins = O::compare(&value, &(*root.as_ptr()).value) == Ordering::Greater;
+      (*n).value = value;
+
+      if (*prev.as_ptr()).child == Some(node) {
+        (*prev.as_ptr()).child = (*n).sibling;
+      } else {
+        (*prev.as_ptr()).sibling = (*n).sibling;
+      }
+      if let Some(sibling) = (*n).sibling {
+        (*sibling.as_ptr()).prev = Some(prev);
+      }
+      ((*n).prev, (*n).sibling) = (None, None);
+
+      self.root = Some(if wins { attach(node, root) } else { attach(root, node) });
+    }
+  }
+
+  /// Removes all elements from the heap.
+  pub fn clear(&mut self) {
+    let mut stack: Vec<_> = self.root.take().into_iter().collect();
+
+    while let Some(node) = stack.pop() {
+      // SAFETY: every node is reached once from its parent or its previous
+      // sibling and was leaked from a box by `push`.
+      let node = unsafe { Box::from_raw(node.as_ptr()) };
+      node.entry.node.set(None);
+      stack.extend(node.child.into_iter().chain(node.sibling));
+    }
+
+    self.len = 0;
+  }
+
+  /// Returns an iterator over the elements of the heap in arbitrary order.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { stack: self.root.into_iter().collect(), heap: PhantomData }
+  }
+
+  /// Returns the node of the handle if it is in the heap.
+  fn node(&self, handle: &Handle<T>) -> Link<T> {
+    let node = handle.0.node.get()?;
+
+    let mut owner = Rc::clone(&handle.0.owner.borrow());
+    loop {
+      let forward = owner.forward.borrow().clone();
+      match forward {
+        Some(forward) => owner = forward,
+        None => break
+      }
+    }
+
+    // Every live heap is the end of the forwards of its elements, so this
+    // is the heap of the element, and the node was not freed.
+    if !Rc::ptr_eq(&owner, &self.owner) {
+      return None;
+    }
+    *handle.0.owner.borrow_mut() = owner;
+
+    Some(node)
+  }
+}
+
+/// Links two detached trees, the root with the lower priority becoming the
+/// first child of the other one, and returns the root of the tree.
+///
+/// The trees are only changed after the comparison, so they are left as
+/// they were if it panics.
+///
+/// # Safety
+///
+/// Both nodes must be valid roots of distinct trees without siblings.
+unsafe fn link<T: Ord, O: Order>(a: NonNull<Node<T>>, b: NonNull<Node<T>>) -> NonNull<Node<T>> {
+  if O::compare(&(*b.as_ptr()).value, &(*a.as_ptr()).value) == Ordering::Greater {
+    attach(b, a)
+  } else {
+    attach(a, b)
+  }
+}
+
+/// Makes a detached tree the first child of another one, and returns the
+/// parent.
+///
+/// # Safety
+///
+/// Both nodes must be valid roots of distinct trees without siblings.
+unsafe fn attach<T>(parent: NonNull<Node<T>>, child: NonNull<Node<T>>) -> NonNull<Node<T>> {
+  let (p, c) = (parent.as_ptr(), child.as_ptr());
+
+  (*c).sibling = (*p).child;
+  if let Some(first) = (*p).child {
+    (*first.as_ptr()).prev = Some(child);
+  }
+  (*c).prev = Some(parent);
+  (*p).child = Some(child);
+
+  parent
+}
+
+/// Merges the children of a node into its single child with the two passes
+/// of the pairing heap.
+///
+/// # Safety
+///
+/// The node must be a valid node whose children are valid trees.
+unsafe fn combine<T: Ord, O: Order>(parent: NonNull<Node<T>>) {
+  let mut children = Children { parent, trees: Vec::new(), rest: (*parent.as_ptr()).child };
+
+  // Links the siblings in pairs from left to right.
+  while let Some(a) = children.rest {
+    match (*a.as_ptr()).sibling {
+      Some(b) => {
+        let next = (*b.as_ptr()).sibling;
+        let pair = link::<T, O>(a, b);
+        children.rest = next;
+        children.trees.push(pair);
+      }
+      None => {
+        children.rest = None;
+        children.trees.push(a);
+      }
+    }
+  }
+
+  // Then links the pairs from right to left.
+  while let [.., pair, tree] = children.trees[..] {
+    let tree = link::<T, O>(pair, tree);
+    children.trees.truncate(children.trees.len() - 2);
+    children.trees.push(tree);
+  }
+}
+
+/// The children of a node while they are merged: the merged trees, then
+/// the siblings not reached yet. Dropping it makes them the children of
+/// the node again, so a panicking `Ord` leaves a valid heap.
+struct Children<T> {
+  parent: NonNull<Node<T>>,
+  trees: Vec<NonNull<Node<T>>>,
+  rest: Link<T>
+}
+
+impl<T> Drop for Children<T> {
+  fn drop(&mut self) {
+    let mut next = self.rest;
+    // SAFETY: the trees and the rest are valid and only reachable from
+    // here, relinked as a list of siblings under the parent.
+    unsafe {
+      for &tree in self.trees.iter().rev() {
+        (*tree.as_ptr()).sibling = next;
+        if let Some(next) = next {
+          (*next.as_ptr()).prev = Some(tree);
+        }
+        next = Some(tree);
+      }
+      (*self.parent.as_ptr()).child = next;
+      if let Some(first) = next {
+        (*first.as_ptr()).prev = Some(self.parent);
+      }
+    }
+  }
+}
+
+impl<T: Ord, O: Order> Drop for PairingHeap<T, O> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+/// An iterator over the elements of a `PairingHeap` in arbitrary order.
+pub struct Iter<'a, T> {
+  stack: Vec<NonNull<Node<T>>>,
+  heap: PhantomData<&'a T>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+
+    // SAFETY: the nodes are owned by the heap, borrowed for `'a`.
+    let node = unsafe { &*node.as_ptr() };
+    self.stack.extend(node.child.into_iter().chain(node.sibling));
+    Some(&node.value)
+  }
+}
+
+impl<T> Clone for Handle<T> {
+  fn clone(&self) -> Self {
+    Self(Rc::clone(&self.0))
+  }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Handle").finish_non_exhaustive()
+  }
+}
+
+impl<T: Ord, O: Order> FromIterator<T> for PairingHeap<T, O> {
+  /// Builds a heap by pushing the elements of an iterator, in O(n).
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut heap = Self::new();
+    heap.extend(iter);
+    heap
+  }
+}
+
+impl<T: Ord, O: Order> Extend<T> for PairingHeap<T, O> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T: Ord + fmt::Debug, O: Order> fmt::Debug for PairingHeap<T, O> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Ord, O: Order> serde::Serialize for PairingHeap<T, O>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence in arbitrary order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Ord, O: Order> serde::Deserialize<'de> for PairingHeap<T, O>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a heap from a sequence in any order. The handles of the
+  /// serialized heap do not refer to the new one.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::PairingHeap;
+  use crate::{Max, Min};
+  use proptest::prelude::*;
+  use std::cell::Cell;
+  use std::collections::BTreeSet;
+  use std::panic::{self, AssertUnwindSafe};
+
+  #[test]
+  fn empty_heap() {
+    let mut heap = PairingHeap::<i32>::new();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+    assert_eq!(heap.pop(), None);
+  }
+
+  #[test]
+  fn pops_in_order() {
+    let mut max: PairingHeap<i32, Max> = [3, 1, 4, 1, 5, 9, 2, 6].into_iter().collect();
+    let mut min: PairingHeap<i32, Min> = [3, 1, 4, 1, 5, 9, 2, 6].into_iter().collect();
+
+    assert_eq!(max.len(), 8);
+    assert_eq!(std::iter::from_fn(|| max.pop()).collect::<Vec<_>>(), [9, 6, 5, 4, 3, 2, 1, 1]);
+    assert_eq!(std::iter::from_fn(|| min.pop()).collect::<Vec<_>>(), [1, 1, 2, 3, 4, 5, 6, 9]);
+  }
+
+  #[test]
+  fn decrease_key() {
+    let mut heap = PairingHeap::<i32, Min>::new();
+    let handles: Vec<_> = (10..20).map(|value| heap.push(value)).collect();
+    heap.pop();
+
+    heap.decrease_key(&handles[5], 0);
+    heap.decrease_key(&handles[9], 12);
+    // A value of the same priority is allowed.
+    heap.decrease_key(&handles[3], 13);
+
+    assert_eq!(heap.get(&handles[0]), None);
+    assert_eq!(heap.get(&handles[9]), Some(&12));
+    assert_eq!(std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>(), [0, 11, 12, 12, 13, 14, 16, 17, 18]);
+  }
+
+  #[test]
+  #[should_panic(expected = "new value must not have lower priority")]
+  fn decrease_key_lowers_priority() {
+    let mut heap = PairingHeap::<i32, Min>::new();
+    let handle = heap.push(1);
+    heap.decrease_key(&handle, 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid handle")]
+  fn decrease_key_of_other_heap() {
+    let mut heap = PairingHeap::<i32>::new();
+    let mut other = PairingHeap::<i32>::new();
+    let handle = other.push(1);
+    heap.decrease_key(&handle, 2);
+  }
+
+  #[test]
+  fn handles_follow_merges() {
+    let (mut a, mut b, mut c) = (PairingHeap::<i32>::new(), PairingHeap::new(), PairingHeap::new());
+    let one = a.push(1);
+    let two = b.push(2);
+    let three = c.push(3);
+
+    b.merge(a);
+    c.merge(b);
+
+    assert_eq!(c.len(), 3);
+    assert_eq!(c.get(&one), Some(&1));
+    c.decrease_key(&two, 5);
+    c.decrease_key(&one, 4);
+    assert_eq!(c.iter().copied().max(), Some(5));
+    assert_eq!(std::iter::from_fn(|| c.pop()).collect::<Vec<_>>(), [5, 4, 3]);
+    assert_eq!(c.get(&three), None);
+  }
+
+  #[test]
+  fn handle_outlives_heap() {
+    let handle = {
+      let mut heap = PairingHeap::<String>::new();
+      heap.push("a".to_string())
+    };
+    let heap = PairingHeap::<String>::new();
+
+    assert_eq!(heap.get(&handle), None);
+  }
+
+  /// A value whose comparisons panic while `PANICS` is set.
+  #[derive(Debug, PartialEq, Eq)]
+  struct Touchy(i32);
+
+  thread_local! {
+    static PANICS: Cell<bool> = const { Cell::new(false) };
+  }
+
+  impl Ord for Touchy {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+      assert!(!PANICS.with(Cell::get), "comparison panicked");
+      self.0.cmp(&other.0)
+    }
+  }
+
+  impl PartialOrd for Touchy {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+      Some(self.cmp(other))
+    }
+  }
+
+  /// Runs the operation with panicking comparisons, expecting it to panic.
+  fn panicking(operation: impl FnOnce()) {
+    PANICS.with(|panics| panics.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(operation));
+    PANICS.with(|panics| panics.set(false));
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn panicking_ord_keeps_heap_valid() {
+    let mut heap: PairingHeap<Touchy> = [9, 1, 2, 3].into_iter().map(Touchy).collect();
+    let one = heap.push(Touchy(0));
+    heap.push(Touchy(4));
+
+    panicking(|| {
+      heap.pop();
+    });
+    panicking(|| {
+      heap.push(Touchy(5));
+    });
+    panicking(|| heap.merge([6, 7].into_iter().map(Touchy).collect()));
+    panicking(|| heap.decrease_key(&one, Touchy(8)));
+
+    assert_eq!(heap.len(), 6);
+    assert_eq!(heap.get(&one), Some(&Touchy(0)));
+    heap.decrease_key(&one, Touchy(10));
+    let values: Vec<_> = std::iter::from_fn(|| heap.pop()).map(|value| value.0).collect();
+    assert_eq!(values, [10, 9, 4, 3, 2, 1]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: PairingHeap<i32> = [1, 3, 2].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    let mut heap: PairingHeap<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>(), [3, 2, 1]);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i16),
+    Pop,
+    DecreaseKey(usize, i16),
+    Merge(Vec<i16>)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => any::<i16>().prop_map(Operation::Push),
+      2 => Just(Operation::Pop),
+      2 => (any::<usize>(), 0..1000i16).prop_map(|(index, by)| Operation::DecreaseKey(index, by)),
+      1 => prop::collection::vec(any::<i16>(), 0..5).prop_map(Operation::Merge)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_sorted_set(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut heap = PairingHeap::<(i32, usize), Min>::new();
+      let mut handles = Vec::new();
+      // The live elements, the index of their handle breaking ties.
+      let mut model = BTreeSet::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            let key = (i32::from(value), handles.len());
+            handles.push(heap.push(key));
+            model.insert(key);
+          }
+          Operation::Pop => prop_assert_eq!(heap.pop(), model.pop_first()),
+          Operation::DecreaseKey(index, by) => {
+            if handles.is_empty() {
+              continue;
+            }
+            let handle = &handles[index % handles.len()];
+            if let Some(&(value, id)) = heap.get(handle) {
+              model.remove(&(value, id));
+              model.insert((value - i32::from(by), id));
+              heap.decrease_key(handle, (value - i32::from(by), id));
+            }
+          }
+          Operation::Merge(values) => {
+            let mut other = PairingHeap::new();
+            for value in values {
+              let key = (i32::from(value), handles.len());
+              handles.push(other.push(key));
+              model.insert(key);
+            }
+            heap.merge(other);
+          }
+        }
+
+        prop_assert_eq!(heap.len(), model.len());
+        prop_assert_eq!(heap.peek(), model.first());
+      }
+    }
+  }
+}
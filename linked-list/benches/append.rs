@@ -0,0 +1,102 @@
+//! Compares building a list with the O(1) `append` against `prepend` and
+//! a naive singly linked list that walks to the end on every append, as
+//! `LinkedList::append` used to before it tracked its tail, with throughput
+//! numbers at several sizes up to 100k elements.
+//!
+//! The naive list takes quadratic time, seconds for 100k elements, so it is
+//! measured in its own group with fewer samples.
+//!
+//! Run with `cargo bench -p linked-list --bench append`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use linked_list::LinkedList;
+
+
+const SIZES: [usize; 4] = [100, 1_000, 10_000, 100_000];
+
+/// A singly linked list without a tail pointer.
+struct NaiveList {
+  head: Option<Box<NaiveNode>>
+}
+
+struct NaiveNode {
+  value: usize,
+  next: Option<Box<NaiveNode>>
+}
+
+impl NaiveList {
+  /// Appends a value by traversing the whole list, O(n).
+  fn append(&mut self, value: usize) {
+    let mut current = &mut self.head;
+
+    while let Some(node) = current {
+      current = &mut node.next;
+    }
+
+    *current = Some(Box::new(NaiveNode { value, next: None }));
+  }
+}
+
+impl Drop for NaiveList {
+  fn drop(&mut self) {
+    let mut current = self.head.take();
+
+    while let Some(mut node) = current {
+      current = node.next.take();
+    }
+  }
+}
+
+fn append(c: &mut Criterion) {
+  let mut group = c.benchmark_group("linked_list/append");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("LinkedList::append", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut list = LinkedList::new();
+        for value in 0..size {
+          list.append(black_box(value));
+        }
+        list
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("LinkedList::prepend", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut list = LinkedList::new();
+        for value in 0..size {
+          list.prepend(black_box(value));
+        }
+        list
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn naive_append(c: &mut Criterion) {
+  let mut group = c.benchmark_group("linked_list/naive_append");
+  group.sample_size(10);
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("naive append", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut list = NaiveList { head: None };
+        for value in 0..size {
+          list.append(black_box(value));
+        }
+        black_box(list.head.as_ref().map(|node| node.value));
+        list
+      })
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, append, naive_append);
+criterion_main!(benches);
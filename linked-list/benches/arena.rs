@@ -0,0 +1,97 @@
+//! Compares `LinkedList`, which allocates every node on its own, against
+//! `ArenaLinkedList`, which keeps its nodes in a single vector, with
+//! throughput numbers for push, pop and iterate at several sizes.
+//!
+//! Run with `cargo bench -p linked-list --bench arena`.
+
+use std::hint::black_box;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use linked_list::{ArenaLinkedList, LinkedList};
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("arena/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("LinkedList", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut list = LinkedList::new();
+        for i in 0..size {
+          list.append(black_box(i));
+        }
+        list
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("ArenaLinkedList", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut list = ArenaLinkedList::new();
+        for i in 0..size {
+          list.append(black_box(i));
+        }
+        list
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("arena/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("LinkedList", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<LinkedList<_>>(),
+        |mut list| {
+          for _ in 0..size {
+            black_box(list.pop_front());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("ArenaLinkedList", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<ArenaLinkedList<_>>(),
+        |mut list| {
+          for _ in 0..size {
+            black_box(list.pop_front());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("arena/iterate");
+
+  for size in SIZES {
+    let boxed: LinkedList<_> = (0..size).collect();
+    let arena: ArenaLinkedList<_> = (0..size).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("LinkedList", size), &boxed, |b, list| {
+      b.iter(|| list.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("ArenaLinkedList", size), &arena, |b, list| {
+      b.iter(|| list.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, iterate);
+criterion_main!(benches);
@@ -0,0 +1,710 @@
+use core::fmt::{self, Display};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use slab::Slab;
+
+use crate::IndexError;
+
+
+/// A singly linked list whose nodes live in a single slab.
+///
+/// This list has the same interface as `LinkedList`, but instead of
+/// allocating every node on its own it stores the nodes in a `Slab` (the
+/// arena) and links them by their key in it. Pushing an element only
+/// allocates when the arena has to grow, so a list of `n` elements takes
+/// about `log n` allocations instead of `n`, and neighbouring nodes are
+/// usually next to each other in memory, which makes iterating faster.
+/// The slab remembers the slots of removed nodes and reuses them for the
+/// next insertions.
+///
+/// The arena never shrinks on its own, so a list that once held many
+/// elements keeps their memory until it is dropped or `shrink_to_fit` is
+/// called.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::ArenaLinkedList;
+///
+/// let mut list = ArenaLinkedList::with_capacity(4);
+/// list.append(1);
+/// list.append(2);
+/// list.prepend(0);
+///
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.pop_front(), Some(0));
+///
+/// // The slot of the removed node is reused.
+/// list.append(3);
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+pub struct ArenaLinkedList<T> {
+  nodes: Slab<Node<T>>,
+  head: Link,
+  /// Key of the last node, used to append in constant time.
+  tail: Link
+}
+
+/// Nodes are linked by their key in the arena.
+type Link = Option<usize>;
+
+/// Represents a node of an `ArenaLinkedList`.
+#[derive(Clone)]
+struct Node<T> {
+  value: T,
+  next: Link
+}
+
+impl<T> Default for ArenaLinkedList<T> {
+  /// Creates a new instance of `ArenaLinkedList` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> ArenaLinkedList<T> {
+  /// Creates a new empty linked list.
+  pub fn new() -> Self {
+    Self { nodes: Slab::new(), head: None, tail: None }
+  }
+
+  /// Creates a new empty linked list with room for at least `capacity`
+  /// elements before the arena has to grow.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self { nodes: Slab::with_capacity(capacity), ..Self::new() }
+  }
+
+  /// Checks if the linked list is empty.
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Returns the number of elements in the linked list.
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Returns the number of elements the list can hold without growing
+  /// the arena.
+  pub fn capacity(&self) -> usize {
+    self.nodes.capacity()
+  }
+
+  /// Inserts a new element at the beginning of the linked list.
+  pub fn prepend(&mut self, value: T) {
+    let node = self.allocate(value, self.head);
+
+    if self.tail.is_none() {
+      self.tail = Some(node);
+    }
+
+    self.head = Some(node);
+  }
+
+  /// Appends a new element at the end of the linked list.
+  pub fn append(&mut self, value: T) {
+    let node = self.allocate(value, None);
+
+    match self.tail {
+      Some(tail) => self.node_mut(tail).next = Some(node),
+      None => self.head = Some(node)
+    }
+
+    self.tail = Some(node);
+  }
+
+  /// Inserts a new element at the given position, shifting all elements
+  /// after it one position towards the end.
+  ///
+  /// Inserting at `len()` appends the element.
+  ///
+  /// # Errors
+  ///
+  /// Returns `IndexError` if `index > len()`; the list is left unchanged.
+  pub fn insert_at(&mut self, index: usize, value: T) -> Result<(), IndexError> {
+    let len = self.len();
+    if index > len {
+      return Err(IndexError { index, len });
+    }
+
+    if index == 0 {
+      self.prepend(value);
+    } else if index == len {
+      self.append(value);
+    } else {
+      let previous = self.index_at(index - 1);
+      let node = self.allocate(value, self.node(previous).next);
+      self.node_mut(previous).next = Some(node);
+    }
+
+    Ok(())
+  }
+
+  /// Returns a reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index >= self.len() {
+      return None;
+    }
+
+    Some(&self.node(self.index_at(index)).value)
+  }
+
+  /// Returns a mutable reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    if index >= self.len() {
+      return None;
+    }
+
+    let node = self.index_at(index);
+    Some(&mut self.node_mut(node).value)
+  }
+
+  /// Returns a reference to the first element, or `None` if the list is
+  /// empty.
+  pub fn front(&self) -> Option<&T> {
+    Some(&self.node(self.head?).value)
+  }
+
+  /// Returns a mutable reference to the first element, or `None` if the
+  /// list is empty.
+  pub fn front_mut(&mut self) -> Option<&mut T> {
+    let head = self.head?;
+    Some(&mut self.node_mut(head).value)
+  }
+
+  /// Returns a reference to the last element in O(1) time, or `None` if
+  /// the list is empty.
+  pub fn back(&self) -> Option<&T> {
+    Some(&self.node(self.tail?).value)
+  }
+
+  /// Returns a mutable reference to the last element in O(1) time, or
+  /// `None` if the list is empty.
+  pub fn back_mut(&mut self) -> Option<&mut T> {
+    let tail = self.tail?;
+    Some(&mut self.node_mut(tail).value)
+  }
+
+  /// Removes the first element of the linked list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_front(&mut self) -> Option<T> {
+    let head = self.head?;
+    let node = self.release(head);
+    self.head = node.next;
+
+    if self.head.is_none() {
+      self.tail = None;
+    }
+
+    Some(node.value)
+  }
+
+  /// Removes the last element of the linked list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_back(&mut self) -> Option<T> {
+    self.remove_at(self.len().checked_sub(1)?)
+  }
+
+  /// Removes the element at the given position and returns it,
+  /// or `None` if the index is out of bounds (the list is then
+  /// left unchanged).
+  pub fn remove_at(&mut self, index: usize) -> Option<T> {
+    if index >= self.len() {
+      return None;
+    }
+
+    if index == 0 {
+      return self.pop_front();
+    }
+
+    let previous = self.index_at(index - 1);
+    let removed = self.node(previous).next.expect("linked node is missing");
+    let node = self.release(removed);
+    self.node_mut(previous).next = node.next;
+
+    if self.tail == Some(removed) {
+      self.tail = Some(previous);
+    }
+
+    Some(node.value)
+  }
+
+  /// Reverses the order of the elements in place by relinking the
+  /// nodes, without allocating.
+  pub fn reverse(&mut self) {
+    let mut previous: Link = None;
+    let mut current = self.head;
+
+    self.tail = self.head;
+
+    while let Some(index) = current {
+      let node = self.node_mut(index);
+      current = node.next;
+      node.next = previous;
+      previous = Some(index);
+    }
+
+    self.head = previous;
+  }
+
+  /// Consumes the list and returns it with the order of the
+  /// elements reversed.
+  pub fn reversed(mut self) -> Self {
+    self.reverse();
+    self
+  }
+
+  /// Removes all elements from the list, keeping the memory of the arena.
+  pub fn clear(&mut self) {
+    self.nodes.clear();
+    self.head = None;
+    self.tail = None;
+  }
+
+  /// Moves the nodes to the front of the arena in list order and releases
+  /// the memory that is not needed to hold them.
+  pub fn shrink_to_fit(&mut self) {
+    *self = core::mem::take(self).into_iter().collect();
+  }
+
+  /// Returns an iterator over references to the elements of the list.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { nodes: &self.nodes, next: self.head, len: self.len() }
+  }
+
+  /// Returns an iterator over mutable references to the elements of the list.
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    IterMut { nodes: &mut self.nodes, next: self.head, len: self.len(), marker: PhantomData }
+  }
+
+  /// Stores a new node in the arena and returns its key.
+  fn allocate(&mut self, value: T, next: Link) -> usize {
+    self.nodes.insert(Node { value, next })
+  }
+
+  /// Takes the node out of the arena. The caller is responsible for
+  /// unlinking the node.
+  fn release(&mut self, index: usize) -> Node<T> {
+    self.nodes.remove(index).expect("linked node is missing")
+  }
+
+  fn node(&self, index: usize) -> &Node<T> {
+    self.nodes.get(index).expect("linked node is missing")
+  }
+
+  fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+    self.nodes.get_mut(index).expect("linked node is missing")
+  }
+
+  /// Returns the arena key of the node at the given position.
+  ///
+  /// The caller must make sure that `index < self.len()`.
+  fn index_at(&self, index: usize) -> usize {
+    let mut current = self.head.expect("linked node is missing");
+
+    for _ in 0..index {
+      current = self.node(current).next.expect("linked node is missing");
+    }
+
+    current
+  }
+}
+
+/// An iterator over the elements of an `ArenaLinkedList`.
+pub struct Iter<'a, T> {
+  nodes: &'a Slab<Node<T>>,
+  next: Link,
+  len: usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  /// Advances the iterator and returns a reference to the next element,
+  /// or `None` if the end of the list is reached.
+  fn next(&mut self) -> Option<Self::Item> {
+    let nodes = self.nodes;
+    self.next.map(|index| {
+      let node = nodes.get(index).expect("linked node is missing");
+      self.next = node.next;
+      self.len -= 1;
+      &node.value
+    })
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.len, Some(self.len))
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+/// A mutable iterator over the elements of an `ArenaLinkedList`.
+pub struct IterMut<'a, T> {
+  /// Pointer to the arena, which stays mutably borrowed for `'a`.
+  nodes: *mut Slab<Node<T>>,
+  next: Link,
+  len: usize,
+  marker: PhantomData<&'a mut Node<T>>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = &'a mut T;
+
+  /// Advances the iterator and returns a mutable reference to the next
+  /// element, or `None` if the end of the list is reached.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next.map(|index| {
+      // SAFETY: the arena is mutably borrowed, and the links never form a
+      // cycle, so every node is yielded at most once.
+      let node = unsafe { Slab::get_ptr(self.nodes, index).expect("linked node is missing").as_mut() };
+      self.next = node.next;
+      self.len -= 1;
+      &mut node.value
+    })
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.len, Some(self.len))
+  }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the elements of an `ArenaLinkedList`.
+pub struct IntoIter<T> {
+  list: ArenaLinkedList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  /// Removes the first element of the underlying list and returns it,
+  /// or `None` if the list is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.list.pop_front()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.list.len(), Some(self.list.len()))
+  }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ArenaLinkedList<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  /// Consumes the list and returns an iterator over its elements.
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter { list: self }
+  }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaLinkedList<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArenaLinkedList<T> {
+  type Item = &'a mut T;
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<T> FromIterator<T> for ArenaLinkedList<T> {
+  /// Creates a linked list from an iterator, preserving the order
+  /// of the elements.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let iter = iter.into_iter();
+    let mut list = Self::with_capacity(iter.size_hint().0);
+
+    for value in iter {
+      list.append(value);
+    }
+
+    list
+  }
+}
+
+impl<T: Clone> Clone for ArenaLinkedList<T> {
+  /// Returns a deep copy of the list, including its vacant slots.
+  fn clone(&self) -> Self {
+    Self {
+      nodes: self.nodes.clone(),
+      head: self.head,
+      tail: self.tail
+    }
+  }
+}
+
+impl<T: PartialEq> PartialEq for ArenaLinkedList<T> {
+  /// Two lists are equal if they hold equal elements in the same order,
+  /// wherever the nodes are in their arenas.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for ArenaLinkedList<T> {}
+
+impl<T: Hash> Hash for ArenaLinkedList<T> {
+  /// Hashes the length followed by every element, like `LinkedList`.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_usize(self.len());
+
+    for value in self.iter() {
+      value.hash(state);
+    }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArenaLinkedList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T: Display> Display for ArenaLinkedList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "[")?;
+
+    for value in self.iter() {
+      write!(f, "{}", value)?;
+    }
+
+    write!(f, "]")
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for ArenaLinkedList<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the head to the tail,
+  /// the same way as a `LinkedList`.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for ArenaLinkedList<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a list from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    alloc::vec::Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::ArenaLinkedList;
+  use crate::{IndexError, LinkedList};
+  use alloc::collections::VecDeque;
+  use alloc::format;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_list() {
+    let list = ArenaLinkedList::<i32>::new();
+
+    assert!(list.is_empty());
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.get(0), None);
+  }
+
+  #[test]
+  fn append_and_prepend() {
+    let mut list = ArenaLinkedList::new();
+    list.append(2);
+    list.prepend(1);
+    list.append(3);
+
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+  }
+
+  #[test]
+  fn front_and_back() {
+    let mut list: ArenaLinkedList<i32> = (1..=3).collect();
+
+    *list.front_mut().unwrap() *= 10;
+    *list.back_mut().unwrap() *= 10;
+    assert_eq!((list.front(), list.back()), (Some(&10), Some(&30)));
+
+    list.clear();
+    assert_eq!((list.front(), list.back()), (None, None));
+  }
+
+  #[test]
+  fn insert_and_remove_at() {
+    let mut list: ArenaLinkedList<i32> = (1..=3).collect();
+
+    assert_eq!(list.insert_at(1, 10), Ok(()));
+    assert_eq!(list.insert_at(5, 0), Err(IndexError { index: 5, len: 4 }));
+    assert_eq!(list.remove_at(3), Some(3));
+    assert_eq!(list.remove_at(3), None);
+    list.append(4);
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 10, 2, 4]);
+  }
+
+  #[test]
+  fn vacant_slots_are_reused() {
+    let mut list: ArenaLinkedList<i32> = (0..4).collect();
+    let capacity = list.capacity();
+
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_back(), Some(3));
+    list.append(4);
+    list.prepend(5);
+
+    assert_eq!(list.nodes.vacant_key(), 4);
+    assert_eq!(list.capacity(), capacity);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [5, 1, 2, 4]);
+  }
+
+  #[test]
+  fn reverse() {
+    let mut list: ArenaLinkedList<i32> = (1..=4).collect();
+    list.reverse();
+    list.append(0);
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+    assert_eq!(list.reversed().pop_front(), Some(0));
+  }
+
+  #[test]
+  fn iter_mut() {
+    let mut list: ArenaLinkedList<i32> = (1..=3).collect();
+    list.prepend(0);
+
+    for value in list.iter_mut() {
+      *value *= 2;
+    }
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 2, 4, 6]);
+  }
+
+  #[test]
+  fn shrink_to_fit() {
+    let mut list: ArenaLinkedList<i32> = (0..100).collect();
+    for _ in 0..98 {
+      list.pop_front();
+    }
+    list.shrink_to_fit();
+
+    assert_eq!(list.nodes.len(), 2);
+    assert_eq!(list.capacity(), 2);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [98, 99]);
+  }
+
+  #[test]
+  fn equality_ignores_the_layout() {
+    let mut first: ArenaLinkedList<i32> = (0..3).collect();
+    first.pop_front();
+    first.append(3);
+    let second: ArenaLinkedList<i32> = (1..4).collect();
+
+    assert_eq!(first, second);
+    assert_eq!(first.clone(), second);
+  }
+
+  #[test]
+  fn matches_linked_list() {
+    let arena: ArenaLinkedList<i32> = (1..=3).collect();
+    let list: LinkedList<i32> = (1..=3).collect();
+
+    assert_eq!(format!("{}", arena), format!("{}", list));
+    assert_eq!(format!("{:?}", arena), format!("{:?}", list));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value: ArenaLinkedList<i32> = (0..4).collect();
+    value.pop_front();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+    assert_eq!(serde_json::from_str::<ArenaLinkedList<i32>>(&json).unwrap(), value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Prepend(i32),
+    Append(i32),
+    InsertAt(usize, i32),
+    PopFront,
+    PopBack,
+    RemoveAt(usize),
+    Reverse
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Prepend),
+      any::<i32>().prop_map(Operation::Append),
+      (0..20usize, any::<i32>()).prop_map(|(index, value)| Operation::InsertAt(index, value)),
+      Just(Operation::PopFront),
+      Just(Operation::PopBack),
+      (0..20usize).prop_map(Operation::RemoveAt),
+      Just(Operation::Reverse)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut list = ArenaLinkedList::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Prepend(value) => {
+            list.prepend(value);
+            model.push_front(value);
+          }
+          Operation::Append(value) => {
+            list.append(value);
+            model.push_back(value);
+          }
+          Operation::InsertAt(index, value) => {
+            let expected = index <= model.len();
+            prop_assert_eq!(list.insert_at(index, value).is_ok(), expected);
+            if expected {
+              model.insert(index, value);
+            }
+          }
+          Operation::PopFront => prop_assert_eq!(list.pop_front(), model.pop_front()),
+          Operation::PopBack => prop_assert_eq!(list.pop_back(), model.pop_back()),
+          Operation::RemoveAt(index) => prop_assert_eq!(list.remove_at(index), model.remove(index)),
+          Operation::Reverse => {
+            list.reverse();
+            model = model.into_iter().rev().collect();
+          }
+        }
+
+        prop_assert_eq!(list.len(), model.len());
+        prop_assert!(list.iter().eq(model.iter()));
+      }
+    }
+  }
+}
@@ -0,0 +1,704 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::{Link, LinkedList, Node};
+
+
+/// A cursor over a `LinkedList`, which points at an element and can move
+/// forwards and backwards through the list.
+///
+/// Besides the elements, a cursor can point at a "ghost" non-element that
+/// sits between the tail and the head of the list: moving forward from
+/// the last element reaches the ghost, and moving forward from the ghost
+/// reaches the first element again.
+///
+/// The list is singly linked, so the cursor remembers the element before
+/// the current one. Moving forward is O(1), while moving backward has to
+/// walk from the head and is O(n).
+///
+/// # Example
+///
+/// ```
+/// use linked_list::LinkedList;
+///
+/// let list: LinkedList<i32> = (1..=3).collect();
+/// let mut cursor = list.cursor_front();
+///
+/// assert_eq!(cursor.current(), Some(&1));
+/// cursor.move_next();
+/// assert_eq!(cursor.current(), Some(&2));
+/// assert_eq!(cursor.peek_prev(), Some(&1));
+/// ```
+pub struct Cursor<'a, T> {
+  list: &'a LinkedList<T>,
+  position: Position<T>
+}
+
+/// A cursor over a `LinkedList` that can also edit the list: insert
+/// elements next to the current one, remove the current element and
+/// split the list in two at the cursor.
+///
+/// Inserting and removing next to the cursor are O(1).
+///
+/// # Example
+///
+/// ```
+/// use linked_list::LinkedList;
+///
+/// let mut list: LinkedList<i32> = (1..=4).collect();
+/// let mut cursor = list.cursor_front_mut();
+///
+/// cursor.move_next();
+/// assert_eq!(cursor.remove_current(), Some(2));
+/// cursor.insert_before(10);
+/// let rest = cursor.split_after();
+///
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 10, 3]);
+/// assert_eq!(rest.iter().copied().collect::<Vec<_>>(), vec![4]);
+/// ```
+pub struct CursorMut<'a, T> {
+  list: &'a mut LinkedList<T>,
+  position: Position<T>
+}
+
+/// The position of a cursor, shared by `Cursor` and `CursorMut`.
+///
+/// `current` is `None` at the ghost, whose index is the length of the
+/// list and whose previous element is the tail.
+struct Position<T> {
+  current: Link<T>,
+  previous: Link<T>,
+  index: usize
+}
+
+impl<T> Clone for Position<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for Position<T> {}
+
+impl<T> Position<T> {
+  /// Returns the position of the element at the given index, or of the
+  /// ghost if `index == list.len()`.
+  fn at(list: &LinkedList<T>, index: usize) -> Self {
+    if index == list.len {
+      return Self { current: None, previous: list.tail, index };
+    }
+
+    let mut position = Self { current: list.head, previous: None, index: 0 };
+    while position.index < index {
+      position.move_next(list);
+    }
+
+    position
+  }
+
+  fn move_next(&mut self, list: &LinkedList<T>) {
+    match self.current {
+      Some(current) => {
+        self.previous = Some(current);
+        // SAFETY: `current` is a live node of the list.
+        self.current = unsafe { (*current.as_ptr()).next };
+        self.index += 1;
+      }
+      None => *self = Self { current: list.head, previous: None, index: 0 }
+    }
+  }
+
+  fn move_prev(&mut self, list: &LinkedList<T>) {
+    *self = match (self.current, self.index) {
+      (Some(_), 0) => Self::at(list, list.len),
+      (None, 0) => return,
+      (_, index) => Self::at(list, index - 1)
+    };
+  }
+}
+
+impl<'a, T> Cursor<'a, T> {
+  /// Returns the index of the current element, or `None` if the cursor
+  /// points at the ghost.
+  pub fn index(&self) -> Option<usize> {
+    self.position.current.map(|_| self.position.index)
+  }
+
+  /// Returns a reference to the current element, or `None` if the cursor
+  /// points at the ghost.
+  pub fn current(&self) -> Option<&'a T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.position.current.map(|node| unsafe { &(*node.as_ptr()).value })
+  }
+
+  /// Returns a reference to the element after the current one. At the
+  /// ghost, this is the first element of the list.
+  pub fn peek_next(&self) -> Option<&'a T> {
+    let next = match self.position.current {
+      // SAFETY: `current` is a live node of the list.
+      Some(current) => unsafe { (*current.as_ptr()).next },
+      None => self.list.head
+    };
+
+    // SAFETY: the node is live for as long as the list is borrowed.
+    next.map(|node| unsafe { &(*node.as_ptr()).value })
+  }
+
+  /// Returns a reference to the element before the current one. At the
+  /// ghost, this is the last element of the list.
+  pub fn peek_prev(&self) -> Option<&'a T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.position.previous.map(|node| unsafe { &(*node.as_ptr()).value })
+  }
+
+  /// Moves the cursor to the next element, or from the last element to
+  /// the ghost, or from the ghost to the first element.
+  pub fn move_next(&mut self) {
+    self.position.move_next(self.list);
+  }
+
+  /// Moves the cursor to the previous element, or from the first element
+  /// to the ghost, or from the ghost to the last element.
+  ///
+  /// This walks the list from the head, so it takes O(n) time.
+  pub fn move_prev(&mut self) {
+    self.position.move_prev(self.list);
+  }
+}
+
+impl<T> Clone for Cursor<'_, T> {
+  fn clone(&self) -> Self {
+    Self { list: self.list, position: self.position }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Cursor<'_, T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Cursor").field(self.list).field(&self.index()).finish()
+  }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+  /// Returns the index of the current element, or `None` if the cursor
+  /// points at the ghost.
+  pub fn index(&self) -> Option<usize> {
+    self.as_cursor().index()
+  }
+
+  /// Returns a reference to the current element, or `None` if the cursor
+  /// points at the ghost.
+  pub fn current(&self) -> Option<&T> {
+    self.as_cursor().current()
+  }
+
+  /// Returns a mutable reference to the current element, or `None` if
+  /// the cursor points at the ghost.
+  pub fn current_mut(&mut self) -> Option<&mut T> {
+    // SAFETY: the node is live and uniquely borrowed for as long as the
+    // cursor is mutably borrowed.
+    self.position.current.map(|node| unsafe { &mut (*node.as_ptr()).value })
+  }
+
+  /// Returns a reference to the element after the current one. At the
+  /// ghost, this is the first element of the list.
+  pub fn peek_next(&self) -> Option<&T> {
+    self.as_cursor().peek_next()
+  }
+
+  /// Returns a reference to the element before the current one. At the
+  /// ghost, this is the last element of the list.
+  pub fn peek_prev(&self) -> Option<&T> {
+    self.as_cursor().peek_prev()
+  }
+
+  /// Moves the cursor to the next element, or from the last element to
+  /// the ghost, or from the ghost to the first element.
+  pub fn move_next(&mut self) {
+    self.position.move_next(self.list);
+  }
+
+  /// Moves the cursor to the previous element, or from the first element
+  /// to the ghost, or from the ghost to the last element.
+  ///
+  /// This walks the list from the head, so it takes O(n) time.
+  pub fn move_prev(&mut self) {
+    self.position.move_prev(self.list);
+  }
+
+  /// Returns a read-only cursor at the same position, which borrows this
+  /// cursor.
+  pub fn as_cursor(&self) -> Cursor<'_, T> {
+    Cursor { list: self.list, position: self.position }
+  }
+
+  /// Inserts a new element before the current one, the cursor keeps
+  /// pointing at the same element. At the ghost, the element is appended
+  /// to the list.
+  pub fn insert_before(&mut self, value: T) {
+    let node = Node::alloc(value, self.position.current);
+
+    match self.position.previous {
+      // SAFETY: `previous` is a live node of the list.
+      Some(previous) => unsafe { (*previous.as_ptr()).next = Some(node) },
+      None => self.list.head = Some(node)
+    }
+
+    if self.position.current.is_none() {
+      self.list.tail = Some(node);
+    }
+
+    self.position.previous = Some(node);
+    self.position.index += 1;
+    self.list.len += 1;
+  }
+
+  /// Inserts a new element after the current one, the cursor keeps
+  /// pointing at the same element. At the ghost, the element is prepended
+  /// to the list.
+  pub fn insert_after(&mut self, value: T) {
+    let Some(current) = self.position.current else {
+      self.list.prepend(value);
+      self.position = Position::at(self.list, self.list.len);
+      return;
+    };
+
+    // SAFETY: `current` is a live node of the list.
+    unsafe {
+      let node = Node::alloc(value, (*current.as_ptr()).next);
+      (*current.as_ptr()).next = Some(node);
+
+      if self.list.tail == Some(current) {
+        self.list.tail = Some(node);
+      }
+    }
+
+    self.list.len += 1;
+  }
+
+  /// Removes the current element and returns it, the cursor then points
+  /// at the element that followed it. Returns `None` and leaves the list
+  /// unchanged if the cursor points at the ghost.
+  pub fn remove_current(&mut self) -> Option<T> {
+    let current = self.position.current?;
+
+    // SAFETY: `current` is a live node of the list, it is unlinked before
+    // ownership is taken back.
+    let node = unsafe { Box::from_raw(current.as_ptr()) };
+
+    match self.position.previous {
+      // SAFETY: `previous` is a live node of the list.
+      Some(previous) => unsafe { (*previous.as_ptr()).next = node.next },
+      None => self.list.head = node.next
+    }
+
+    if self.list.tail == Some(current) {
+      self.list.tail = self.position.previous;
+    }
+
+    self.position.current = node.next;
+    self.list.len -= 1;
+    Some(node.value)
+  }
+
+  /// Moves all elements of `list` in between the current element and
+  /// the one before it in O(1), the cursor keeps pointing at the same
+  /// element. At the ghost, the elements are appended to the list.
+  pub fn splice_before(&mut self, mut list: LinkedList<T>) {
+    let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) else {
+      return;
+    };
+
+    match self.position.previous {
+      // SAFETY: `previous` is a live node of the list.
+      Some(previous) => unsafe { (*previous.as_ptr()).next = Some(head) },
+      None => self.list.head = Some(head)
+    }
+
+    // SAFETY: `tail` is a live node, now owned by this list.
+    unsafe { (*tail.as_ptr()).next = self.position.current };
+
+    if self.position.current.is_none() {
+      self.list.tail = Some(tail);
+    }
+
+    self.position.previous = Some(tail);
+    self.position.index += list.len;
+    self.list.len += core::mem::take(&mut list.len);
+  }
+
+  /// Moves all elements of `list` in between the current element and
+  /// the one after it in O(1), the cursor keeps pointing at the same
+  /// element. At the ghost, the elements are prepended to the list.
+  pub fn splice_after(&mut self, mut list: LinkedList<T>) {
+    let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) else {
+      return;
+    };
+    let len = core::mem::take(&mut list.len);
+
+    match self.position.current {
+      // SAFETY: `current` and `tail` are live nodes, now owned by this list.
+      Some(current) => unsafe {
+        (*tail.as_ptr()).next = (*current.as_ptr()).next;
+        (*current.as_ptr()).next = Some(head);
+
+        if self.list.tail == Some(current) {
+          self.list.tail = Some(tail);
+        }
+      },
+      None => {
+        // SAFETY: `tail` is a live node, now owned by this list.
+        unsafe { (*tail.as_ptr()).next = self.list.head };
+        self.list.head = Some(head);
+
+        if self.list.tail.is_none() {
+          self.list.tail = Some(tail);
+        }
+
+        self.position.previous = self.list.tail;
+        self.position.index += len;
+      }
+    }
+
+    self.list.len += len;
+  }
+
+  /// Splits the list after the current element and returns the elements
+  /// that followed it as a new list. At the ghost, the whole list is
+  /// moved to the new list.
+  pub fn split_after(&mut self) -> LinkedList<T> {
+    let Some(current) = self.position.current else {
+      let rest = core::mem::take(self.list);
+      self.position = Position::at(self.list, 0);
+      return rest;
+    };
+
+    // SAFETY: `current` is a live node of the list.
+    let head = unsafe { (*current.as_ptr()).next.take() };
+    let len = self.list.len - self.position.index - 1;
+    let tail = if head.is_some() { self.list.tail } else { None };
+
+    self.list.tail = Some(current);
+    self.list.len -= len;
+
+    LinkedList { head, tail, len, marker: core::marker::PhantomData }
+  }
+
+  /// Splits the list before the current element and returns the elements
+  /// that preceded it as a new list, the current element becomes the
+  /// first one. At the ghost, the whole list is moved to the new list.
+  pub fn split_before(&mut self) -> LinkedList<T> {
+    let Some(previous) = self.position.previous else {
+      return LinkedList::new();
+    };
+
+    // SAFETY: `previous` is a live node of the list.
+    unsafe { (*previous.as_ptr()).next = None };
+
+    let front = LinkedList {
+      head: self.list.head,
+      tail: Some(previous),
+      len: self.position.index,
+      marker: core::marker::PhantomData
+    };
+
+    self.list.head = self.position.current;
+    if self.position.current.is_none() {
+      self.list.tail = None;
+    }
+    self.list.len -= front.len;
+    self.position.previous = None;
+    self.position.index = 0;
+
+    front
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CursorMut<'_, T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("CursorMut").field(self.list).field(&self.index()).finish()
+  }
+}
+
+impl<T> LinkedList<T> {
+  /// Returns a cursor pointing at the first element, or at the ghost if
+  /// the list is empty.
+  pub fn cursor_front(&self) -> Cursor<'_, T> {
+    Cursor { list: self, position: Position::at(self, 0) }
+  }
+
+  /// Returns a cursor pointing at the last element, or at the ghost if
+  /// the list is empty.
+  ///
+  /// The cursor needs the element before the last one, so this walks the
+  /// list and takes O(n) time.
+  pub fn cursor_back(&self) -> Cursor<'_, T> {
+    Cursor { list: self, position: Position::at(self, self.len.saturating_sub(1)) }
+  }
+
+  /// Returns a cursor that can edit the list, pointing at the first
+  /// element, or at the ghost if the list is empty.
+  pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+    let position = Position::at(self, 0);
+    CursorMut { list: self, position }
+  }
+
+  /// Returns a cursor that can edit the list, pointing at the last
+  /// element, or at the ghost if the list is empty.
+  ///
+  /// The cursor needs the element before the last one, so this walks the
+  /// list and takes O(n) time.
+  pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+    let position = Position::at(self, self.len.saturating_sub(1));
+    CursorMut { list: self, position }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::LinkedList;
+  use alloc::collections::VecDeque;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  fn values(list: &LinkedList<i32>) -> Vec<i32> {
+    list.iter().copied().collect()
+  }
+
+  #[test]
+  fn empty_list() {
+    let list = LinkedList::<i32>::new();
+    let mut cursor = list.cursor_front();
+
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), None);
+  }
+
+  #[test]
+  fn move_around_the_ghost() {
+    let list: LinkedList<i32> = (1..=3).collect();
+    let mut cursor = list.cursor_back();
+
+    assert_eq!(cursor.current(), Some(&3));
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_prev(), Some(&3));
+    assert_eq!(cursor.peek_next(), Some(&1));
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&3));
+    assert_eq!(cursor.index(), Some(2));
+  }
+
+  #[test]
+  fn insert() {
+    let mut list: LinkedList<i32> = (1..=2).collect();
+    let mut cursor = list.cursor_front_mut();
+
+    cursor.insert_before(0);
+    cursor.insert_after(10);
+    assert_eq!(cursor.index(), Some(1));
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(3);
+    cursor.insert_after(-1);
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_prev(), Some(&3));
+
+    assert_eq!(values(&list), [-1, 0, 1, 10, 2, 3]);
+    assert_eq!(list.len(), 6);
+    list.append(4);
+    assert_eq!(list.pop_back(), Some(4));
+  }
+
+  #[test]
+  fn remove_current() {
+    let mut list: LinkedList<i32> = (1..=3).collect();
+    let mut cursor = list.cursor_back_mut();
+
+    assert_eq!(cursor.remove_current(), Some(3));
+    assert_eq!(cursor.remove_current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(1));
+    *cursor.current_mut().unwrap() *= 10;
+
+    assert_eq!(values(&list), [20]);
+    list.append(4);
+    assert_eq!(values(&list), [20, 4]);
+  }
+
+  #[test]
+  fn split_after() {
+    let mut list: LinkedList<i32> = (1..=4).collect();
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+
+    let rest = cursor.split_after();
+    assert_eq!(values(&rest), [3, 4]);
+    cursor.move_next();
+    let all = cursor.split_after();
+    assert_eq!(values(&all), [1, 2]);
+    assert!(list.is_empty());
+  }
+
+  #[test]
+  fn split_before() {
+    let mut list: LinkedList<i32> = (1..=4).collect();
+    let mut cursor = list.cursor_back_mut();
+
+    let front = cursor.split_before();
+    assert_eq!(cursor.index(), Some(0));
+    assert!(cursor.split_before().is_empty());
+    assert_eq!(values(&front), [1, 2, 3]);
+    assert_eq!(values(&list), [4]);
+    assert_eq!(list.len(), 1);
+  }
+
+  #[test]
+  fn splice() {
+    let mut list: LinkedList<i32> = (1..=2).collect();
+    let mut cursor = list.cursor_back_mut();
+
+    cursor.splice_before((10..=11).collect());
+    cursor.splice_after((20..=21).collect());
+    assert_eq!(cursor.index(), Some(3));
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    cursor.splice_after(LinkedList::from_iter([0]));
+    cursor.splice_before(LinkedList::from_iter([30]));
+    assert_eq!(cursor.index(), None);
+
+    assert_eq!(values(&list), [0, 1, 10, 11, 2, 20, 21, 30]);
+    list.append(40);
+    assert_eq!(list.len(), 9);
+    assert_eq!(list.pop_back(), Some(40));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    MoveNext,
+    MovePrev,
+    InsertBefore(i32),
+    InsertAfter(i32),
+    RemoveCurrent,
+    SplitAfter,
+    SplitBefore,
+    SpliceBefore(Vec<i32>),
+    SpliceAfter(Vec<i32>)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      Just(Operation::MoveNext),
+      Just(Operation::MovePrev),
+      any::<i32>().prop_map(Operation::InsertBefore),
+      any::<i32>().prop_map(Operation::InsertAfter),
+      Just(Operation::RemoveCurrent),
+      Just(Operation::SplitAfter),
+      Just(Operation::SplitBefore),
+      prop::collection::vec(any::<i32>(), 0..5).prop_map(Operation::SpliceBefore),
+      prop::collection::vec(any::<i32>(), 0..5).prop_map(Operation::SpliceAfter)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec_deque(
+      values in prop::collection::vec(any::<i32>(), 0..20),
+      operations in prop::collection::vec(operation(), 0..100)
+    ) {
+      let mut list: LinkedList<i32> = values.iter().copied().collect();
+      let mut model: VecDeque<i32> = values.into_iter().collect();
+      // The index of the model cursor, `model.len()` at the ghost.
+      let mut index = 0;
+      let mut cursor = list.cursor_front_mut();
+
+      for operation in operations {
+        match operation {
+          Operation::MoveNext => {
+            cursor.move_next();
+            index = if index == model.len() { 0 } else { index + 1 };
+          }
+          Operation::MovePrev => {
+            cursor.move_prev();
+            index = if index == 0 { model.len() } else { index - 1 };
+          }
+          Operation::InsertBefore(value) => {
+            cursor.insert_before(value);
+            model.insert(index, value);
+            index += 1;
+          }
+          Operation::InsertAfter(value) => {
+            cursor.insert_after(value);
+            if index == model.len() {
+              model.push_front(value);
+              index += 1;
+            } else {
+              model.insert(index + 1, value);
+            }
+          }
+          Operation::RemoveCurrent => {
+            let expected = if index < model.len() { model.remove(index) } else { None };
+            prop_assert_eq!(cursor.remove_current(), expected);
+          }
+          Operation::SplitAfter => {
+            let split = cursor.split_after();
+            let expected = if index == model.len() {
+              index = 0;
+              core::mem::take(&mut model)
+            } else {
+              model.split_off(index + 1)
+            };
+            prop_assert!(split.iter().eq(expected.iter()));
+            prop_assert_eq!(split.len(), expected.len());
+          }
+          Operation::SplitBefore => {
+            let split = cursor.split_before();
+            let rest = model.split_off(index);
+            prop_assert!(split.iter().eq(model.iter()));
+            prop_assert_eq!(split.len(), model.len());
+            model = rest;
+            index = 0;
+          }
+          Operation::SpliceBefore(values) => {
+            cursor.splice_before(values.iter().copied().collect());
+            for value in values {
+              model.insert(index, value);
+              index += 1;
+            }
+          }
+          Operation::SpliceAfter(values) => {
+            cursor.splice_after(values.iter().copied().collect());
+            if index == model.len() {
+              index += values.len();
+              for value in values.into_iter().rev() {
+                model.push_front(value);
+              }
+            } else {
+              for value in values.into_iter().rev() {
+                model.insert(index + 1, value);
+              }
+            }
+          }
+        }
+
+        prop_assert_eq!(cursor.index(), (index < model.len()).then_some(index));
+        prop_assert_eq!(cursor.current(), model.get(index));
+      }
+
+      prop_assert_eq!(list.len(), model.len());
+      prop_assert!(list.iter().eq(model.iter()));
+      list.append(0);
+      prop_assert_eq!(list.pop_back(), Some(0));
+    }
+  }
+}
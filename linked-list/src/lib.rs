@@ -1,4 +1,22 @@
-use std::fmt::Display;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::fmt::{self, Display};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+pub mod arena;
+mod cursor;
+
+pub use arena::ArenaLinkedList;
+pub use cursor::{Cursor, CursorMut};
 
 /// A singly linked list implementation.
 /// 
@@ -6,10 +24,13 @@ use std::fmt::Display;
 /// (node) contains a value and a reference to the next element in the list.
 /// The list starts with a head node, and each node points to the next node
 /// until the end of the list is reached (where the next node is None).
+/// The list also keeps a pointer to its last node, so appending is O(1).
 /// 
 /// # Example
 ///
 /// ```
+/// use linked_list::LinkedList;
+///
 /// let mut list: LinkedList<i32> = LinkedList::new();
 /// assert!(list.is_empty());
 ///
@@ -23,96 +44,1051 @@ use std::fmt::Display;
 /// list.prepend(0);
 /// assert_eq!(list.len(), 4);
 /// ```
-pub struct LinkedList<T: Display> {
-  head: Option<Box<Node<T>>>
+pub struct LinkedList<T> {
+  head: Link<T>,
+  /// Pointer to the last node, used to append in constant time.
+  tail: Link<T>,
+  /// Number of elements, maintained by every mutating operation.
+  len: usize,
+  /// Tells the compiler that the list owns its nodes.
+  marker: PhantomData<Box<Node<T>>>
 }
 
+/// Nodes are linked with raw pointers because the tail pointer aliases
+/// the `next` pointer of the second to last node, which `Box` forbids.
+type Link<T> = Option<NonNull<Node<T>>>;
+
 /// Represents a node in a singly linked list.
 struct Node<T> {
   /// The value stored in the node.
   value: T,
   /// Pointer to the next node in the list.
-  next: Option<Box<Node<T>>>
+  next: Link<T>
+}
+
+impl<T> Node<T> {
+  /// Allocates a node on the heap and leaks it, the list becomes
+  /// responsible for freeing it with `Box::from_raw`.
+  fn alloc(value: T, next: Link<T>) -> NonNull<Node<T>> {
+    NonNull::from(Box::leak(Box::new(Node { value, next })))
+  }
+}
+
+// The list owns its values just like `Box` does, so it is safe to send
+// or share it between threads whenever `T` is.
+unsafe impl<T: Send> Send for LinkedList<T> {}
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
+/// The error returned by `LinkedList::insert_at` when the index is
+/// greater than the length of the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexError {
+  /// The requested index.
+  pub index: usize,
+  /// The length of the list at the time of the call.
+  pub len: usize
+}
+
+impl Display for IndexError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "index {} is out of bounds for a list of length {}", self.index, self.len)
+  }
+}
+
+impl core::error::Error for IndexError {}
+
+impl<T> Default for LinkedList<T> {
+  /// Creates a new instance of `LinkedList` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
-impl<T: Display> LinkedList<T> {
+impl<T> LinkedList<T> {
   /// Creates a new empty linked list.
-  fn new() -> Self {
-    Self { head: None }
+  pub fn new() -> Self {
+    Self { head: None, tail: None, len: 0, marker: PhantomData }
   }
 
   /// Checks if the linked list is empty.
-  fn is_empty(&self) -> bool {
+  pub fn is_empty(&self) -> bool {
     self.head.is_none()
   }
 
   /// Returns the number of elements in the linked list.
-  fn len(&self) -> usize {
-    let mut size: usize = 0;
-    let mut current = &self.head;
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Inserts a new element at the beginning of the linked list.
+  pub fn prepend(&mut self, value: T) {
+    let node = Node::alloc(value, self.head);
+
+    if self.tail.is_none() {
+      self.tail = Some(node);
+    }
+
+    self.head = Some(node);
+    self.len += 1;
+  }
+
+  /// Appends a new element at the end of the linked list.
+  pub fn append(&mut self, value: T) {
+    self.link_back(Node::alloc(value, None));
+  }
+
+  /// Inserts a new element at the given position, shifting all elements
+  /// after it one position towards the end.
+  ///
+  /// Inserting at `len()` appends the element.
+  ///
+  /// # Errors
+  ///
+  /// Returns `IndexError` if `index > len()`; the list is left unchanged.
+  pub fn insert_at(&mut self, index: usize, value: T) -> Result<(), IndexError> {
+    if index > self.len {
+      return Err(IndexError { index, len: self.len });
+    }
+
+    if index == 0 {
+      self.prepend(value);
+    } else if index == self.len {
+      self.append(value);
+    } else {
+      let previous = self.node_at(index - 1);
+
+      // SAFETY: `previous` is a live node owned by this list.
+      unsafe {
+        let next = (*previous.as_ptr()).next;
+        (*previous.as_ptr()).next = Some(Node::alloc(value, next));
+      }
+
+      self.len += 1;
+    }
+
+    Ok(())
+  }
+
+  /// Returns a reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index >= self.len {
+      return None;
+    }
+
+    // SAFETY: the node is live for as long as the list is borrowed.
+    Some(unsafe { &(*self.node_at(index).as_ptr()).value })
+  }
+
+  /// Returns a mutable reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+    if index >= self.len {
+      return None;
+    }
+
+    // SAFETY: the node is live and uniquely borrowed for as long as
+    // the list is mutably borrowed.
+    Some(unsafe { &mut (*self.node_at(index).as_ptr()).value })
+  }
+
+  /// Returns a reference to the first element, or `None` if the list is
+  /// empty.
+  pub fn front(&self) -> Option<&T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.head.map(|head| unsafe { &(*head.as_ptr()).value })
+  }
+
+  /// Returns a mutable reference to the first element, or `None` if the
+  /// list is empty.
+  pub fn front_mut(&mut self) -> Option<&mut T> {
+    // SAFETY: the node is live and uniquely borrowed for as long as
+    // the list is mutably borrowed.
+    self.head.map(|head| unsafe { &mut (*head.as_ptr()).value })
+  }
+
+  /// Returns a reference to the last element in O(1) time, or `None` if
+  /// the list is empty.
+  pub fn back(&self) -> Option<&T> {
+    // SAFETY: the node is live for as long as the list is borrowed.
+    self.tail.map(|tail| unsafe { &(*tail.as_ptr()).value })
+  }
+
+  /// Returns a mutable reference to the last element in O(1) time, or
+  /// `None` if the list is empty.
+  pub fn back_mut(&mut self) -> Option<&mut T> {
+    // SAFETY: the node is live and uniquely borrowed for as long as
+    // the list is mutably borrowed.
+    self.tail.map(|tail| unsafe { &mut (*tail.as_ptr()).value })
+  }
+
+  /// Checks if the list contains an element equal to `value`.
+  pub fn contains(&self, value: &T) -> bool
+  where
+    T: PartialEq,
+  {
+    self.iter().any(|element| element == value)
+  }
+
+  /// Returns a reference to the first element for which `predicate`
+  /// returns `true`, or `None` if there is no such element.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let list: LinkedList<&str> = ["apple", "banana", "cherry"].into_iter().collect();
+  ///
+  /// assert_eq!(list.find(|fruit| fruit.starts_with('b')), Some(&"banana"));
+  /// assert_eq!(list.position(|fruit| fruit.ends_with('y')), Some(2));
+  /// assert!(!list.contains(&"durian"));
+  /// ```
+  pub fn find<P>(&self, mut predicate: P) -> Option<&T>
+  where
+    P: FnMut(&T) -> bool,
+  {
+    self.iter().find(|element| predicate(element))
+  }
+
+  /// Returns the index of the first element for which `predicate`
+  /// returns `true`, or `None` if there is no such element.
+  pub fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: FnMut(&T) -> bool,
+  {
+    self.iter().position(predicate)
+  }
+
+  /// Returns a reference to the middle element, the second of the two
+  /// middle elements if the length is even, or `None` if the list is empty.
+  ///
+  /// It walks the list once with two pointers, the fast one moving two
+  /// nodes for every node of the slow one, so the slow one is halfway when
+  /// the fast one reaches the end.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let list: LinkedList<i32> = (1..=5).collect();
+  /// assert_eq!(list.middle(), Some(&3));
+  ///
+  /// let list: LinkedList<i32> = (1..=4).collect();
+  /// assert_eq!(list.middle(), Some(&3));
+  /// ```
+  pub fn middle(&self) -> Option<&T> {
+    let mut slow = self.iter();
+    let mut fast = self.iter();
+
+    while fast.next().is_some() && fast.next().is_some() {
+      slow.next();
+    }
+
+    slow.next()
+  }
+
+  /// Returns a reference to the `n`-th element counting from the back,
+  /// where `0` is the last element, or `None` if the list is too short.
+  ///
+  /// It walks the list once with a leading pointer `n` nodes ahead of the
+  /// trailing one, so the trailing one is on the element when the leading
+  /// one reaches the last node.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let list: LinkedList<i32> = (1..=5).collect();
+  ///
+  /// assert_eq!(list.nth_from_end(0), Some(&5));
+  /// assert_eq!(list.nth_from_end(3), Some(&2));
+  /// assert_eq!(list.nth_from_end(5), None);
+  /// ```
+  pub fn nth_from_end(&self, n: usize) -> Option<&T> {
+    let mut trailing = self.iter();
+    let mut leading = self.iter();
+    leading.nth(n)?;
+
+    while leading.next().is_some() {
+      trailing.next();
+    }
+
+    trailing.next()
+  }
+
+  /// Removes the first element of the linked list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_front(&mut self) -> Option<T> {
+    self.head.map(|head| {
+      // SAFETY: `head` was allocated by `Node::alloc` and is unlinked
+      // below, so ownership is taken back exactly once.
+      let node = unsafe { Box::from_raw(head.as_ptr()) };
+      self.head = node.next;
+
+      if self.head.is_none() {
+        self.tail = None;
+      }
+
+      self.len -= 1;
+      node.value
+    })
+  }
+
+  /// Removes the last element of the linked list and returns it,
+  /// or `None` if the list is empty.
+  pub fn pop_back(&mut self) -> Option<T> {
+    self.remove_at(self.len.checked_sub(1)?)
+  }
+
+  /// Removes the element at the given position and returns it,
+  /// or `None` if the index is out of bounds (the list is then
+  /// left unchanged).
+  pub fn remove_at(&mut self, index: usize) -> Option<T> {
+    if index >= self.len {
+      return None;
+    }
+
+    if index == 0 {
+      return self.pop_front();
+    }
+
+    let previous = self.node_at(index - 1);
+
+    // SAFETY: both nodes are live nodes owned by this list, and the
+    // removed node is unlinked before ownership is taken back.
+    unsafe {
+      let removed = (*previous.as_ptr()).next.unwrap();
+      (*previous.as_ptr()).next = (*removed.as_ptr()).next;
+
+      if self.tail == Some(removed) {
+        self.tail = Some(previous);
+      }
+
+      self.len -= 1;
+      Some(Box::from_raw(removed.as_ptr()).value)
+    }
+  }
+
+  /// Reverses the order of the elements in place by relinking the
+  /// nodes, without allocating.
+  pub fn reverse(&mut self) {
+    let mut previous: Link<T> = None;
+    let mut current = self.head;
+
+    self.tail = self.head;
 
     while let Some(node) = current {
-      size += 1;
-      current = &node.next;
+      // SAFETY: `node` is a live node owned by this list.
+      unsafe {
+        current = (*node.as_ptr()).next;
+        (*node.as_ptr()).next = previous;
+      }
+
+      previous = Some(node);
     }
 
-    size
+    self.head = previous;
   }
 
-  /// Inserts a new element at the beginning of the linked list.
-  fn prepend(&mut self, value: T) {
-    self.head = Some(
-      Box::new(Node {
-        value,
-        next: self.head.take()
+  /// Consumes the list and returns it with the order of the
+  /// elements reversed.
+  pub fn reversed(mut self) -> Self {
+    self.reverse();
+    self
+  }
+
+  /// Moves all elements of `other` to the end of this list in O(1),
+  /// leaving `other` empty.
+  ///
+  /// This is `std::collections::LinkedList::append`, which is named
+  /// differently here because `append` already adds a single element.
+  pub fn append_list(&mut self, other: &mut Self) {
+    let Some(other_head) = other.head.take() else {
+      return;
+    };
+
+    match self.tail {
+      // SAFETY: `tail` points to a live node owned by this list.
+      Some(tail) => unsafe { (*tail.as_ptr()).next = Some(other_head) },
+      None => self.head = Some(other_head)
+    }
+
+    self.tail = other.tail.take();
+    self.len += core::mem::take(&mut other.len);
+  }
+
+  /// Splits the list in two at the given index and returns the elements
+  /// from `at` onwards as a new list, this list keeps the elements before
+  /// `at`.
+  ///
+  /// Finding the split point takes O(at) time, the split itself is O(1).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len()`.
+  pub fn split_off(&mut self, at: usize) -> Self {
+    assert!(at <= self.len, "cannot split off at a nonexistent index");
+
+    if at == 0 {
+      return core::mem::take(self);
+    }
+
+    let last = self.node_at(at - 1);
+    // SAFETY: `last` is a live node owned by this list.
+    let head = unsafe { (*last.as_ptr()).next.take() };
+    let tail = if head.is_some() { self.tail } else { None };
+    let len = self.len - at;
+
+    self.tail = Some(last);
+    self.len = at;
+
+    Self { head, tail, len, marker: PhantomData }
+  }
+
+  /// Sorts the list in ascending order.
+  ///
+  /// See `sort_by` for details.
+  pub fn sort(&mut self)
+  where
+    T: Ord,
+  {
+    self.sort_by(T::cmp);
+  }
+
+  /// Sorts the list with a comparator function in O(n log n) time.
+  ///
+  /// This is a merge sort that relinks the nodes instead of moving the
+  /// elements, so it neither allocates nor requires `T: Clone`. The sort
+  /// is stable: elements that compare as equal keep their order.
+  ///
+  /// If the comparator panics, the elements of the list are leaked and
+  /// the list is left empty.
+  pub fn sort_by<F>(&mut self, mut compare: F)
+  where
+    F: FnMut(&T, &T) -> Ordering,
+  {
+    let Some(head) = self.head.take() else {
+      return;
+    };
+    let len = core::mem::take(&mut self.len);
+    self.tail = None;
+
+    // SAFETY: the nodes are owned by this list and `len` of them are
+    // linked from `head`.
+    let (head, tail) = unsafe { merge_sort(head, len, &mut compare) };
+
+    self.head = Some(head);
+    self.tail = Some(tail);
+    self.len = len;
+  }
+
+  /// Keeps only the elements for which `keep` returns `true`, in their
+  /// order, and unlinks and drops the other nodes in a single pass.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let mut list: LinkedList<i32> = (1..=6).collect();
+  /// list.retain(|value| value % 2 == 0);
+  ///
+  /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), [2, 4, 6]);
+  /// assert_eq!(list.len(), 3);
+  /// ```
+  pub fn retain<F>(&mut self, mut keep: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    let mut previous: Link<T> = None;
+    let mut current = self.head;
+
+    while let Some(node) = current {
+      // SAFETY: `node` is a live node owned by this list.
+      let (value, next) = unsafe { (&(*node.as_ptr()).value, (*node.as_ptr()).next) };
+      current = next;
+
+      if keep(value) {
+        previous = Some(node);
+      } else {
+        // SAFETY: `node` is a live node that follows `previous`.
+        drop(unsafe { self.unlink(previous, node) });
       }
-    ));
+    }
   }
 
-  /// Appends a new element at the end of the linked list.
-  fn append(&mut self, value: T) {
-    if self.head.is_none() {
-      self.prepend(value);
+  /// Removes consecutive repeated elements, keeping the first of each run.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let mut list: LinkedList<i32> = [1, 1, 2, 3, 3, 3, 1].into_iter().collect();
+  /// list.dedup();
+  ///
+  /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 1]);
+  /// ```
+  pub fn dedup(&mut self)
+  where
+    T: PartialEq,
+  {
+    self.dedup_by(|a, b| a == b);
+  }
+
+  /// Removes consecutive elements that map to the same key, keeping the
+  /// first of each run.
+  pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+  where
+    K: PartialEq,
+    F: FnMut(&mut T) -> K,
+  {
+    self.dedup_by(|a, b| key(a) == key(b));
+  }
+
+  /// Removes consecutive elements for which `same_bucket` returns `true`.
+  ///
+  /// `same_bucket` receives an element and the last element kept before
+  /// it, in that order, like `Vec::dedup_by`, and the element is removed
+  /// if it returns `true`.
+  pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+  where
+    F: FnMut(&mut T, &mut T) -> bool,
+  {
+    let Some(mut kept) = self.head else {
       return;
+    };
+
+    // SAFETY: `kept` and its successor are distinct live nodes owned by
+    // this list, so the two mutable references do not alias.
+    while let Some(node) = unsafe { (*kept.as_ptr()).next } {
+      if same_bucket(unsafe { &mut (*node.as_ptr()).value }, unsafe { &mut (*kept.as_ptr()).value }) {
+        // SAFETY: `node` is a live node that follows `kept`.
+        drop(unsafe { self.unlink(Some(kept), node) });
+      } else {
+        kept = node;
+      }
+    }
+  }
+
+  /// Removes every element equal to an earlier one, keeping the first
+  /// occurrence of each value, in O(n) expected time with a hash set.
+  ///
+  /// Unlike `dedup`, the duplicates do not need to be consecutive.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let mut list: LinkedList<i32> = [3, 1, 3, 2, 1].into_iter().collect();
+  /// list.remove_all_duplicates();
+  ///
+  /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), [3, 1, 2]);
+  /// ```
+  #[cfg(feature = "std")]
+  pub fn remove_all_duplicates(&mut self)
+  where
+    T: Hash + Eq,
+  {
+    let mut seen = std::collections::HashSet::with_capacity(self.len);
+    let mut previous: Link<T> = None;
+    let mut current = self.head;
+
+    while let Some(node) = current {
+      // SAFETY: `node` is a live node owned by this list. The values
+      // borrowed by `seen` belong to nodes that are kept, which are neither
+      // freed nor mutated before `seen` is dropped.
+      let (value, next) = unsafe { (&(*node.as_ptr()).value, (*node.as_ptr()).next) };
+      current = next;
+
+      if seen.insert(value) {
+        previous = Some(node);
+      } else {
+        // SAFETY: `node` is a live node that follows `previous`.
+        drop(unsafe { self.unlink(previous, node) });
+      }
     }
+  }
 
-    let mut current = self.head.as_mut();
+  /// Replaces every element with the result of `map`, or removes it if
+  /// `map` returns `None`, reusing the nodes of the kept elements.
+  ///
+  /// If `map` panics, the elements that have not been mapped yet are
+  /// leaked and the list only keeps the ones mapped before.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let mut list: LinkedList<i32> = (1..=6).collect();
+  /// list.filter_map_in_place(|value| (value % 3 != 0).then_some(value * 10));
+  ///
+  /// assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 20, 40, 50]);
+  /// ```
+  pub fn filter_map_in_place<F>(&mut self, mut map: F)
+  where
+    F: FnMut(T) -> Option<T>,
+  {
+    // The nodes are detached first, so a panic in `map` leaves the list
+    // in a valid state.
+    let mut current = self.head.take();
+    self.tail = None;
+    self.len = 0;
 
     while let Some(node) = current {
-      if node.next.is_none() {
-        node.next = Some(
-          Box::new(Node {
-            value,
-            next: None
-          })
-        );
-        break;
+      // SAFETY: `node` is a detached node that is read exactly once and
+      // then either filled again and relinked or freed without dropping
+      // the moved out value.
+      unsafe {
+        current = (*node.as_ptr()).next.take();
+        let value = ptr::read(&(*node.as_ptr()).value);
+
+        match map(value) {
+          Some(value) => {
+            ptr::write(&mut (*node.as_ptr()).value, value);
+            self.link_back(node);
+          }
+          None => drop(Box::from_raw(node.as_ptr().cast::<MaybeUninit<Node<T>>>()))
+        }
+      }
+    }
+  }
+
+  /// Checks if following the links from the head ever comes back to a
+  /// node already visited, with Floyd's tortoise and hare algorithm.
+  ///
+  /// A slow pointer moves one node at a time and a fast one two nodes at
+  /// a time, they can only meet again if the links form a cycle. It runs
+  /// in O(n) time and O(1) space. The methods of the list never create a
+  /// cycle, so this only returns `true` inside `make_cycle_for_test`.
+  pub fn has_cycle(&self) -> bool {
+    let mut slow = self.head;
+    let mut fast = self.head;
+
+    // SAFETY: every link followed points to a live node owned by the list.
+    while let Some(next) = fast.and_then(|node| unsafe { (*node.as_ptr()).next }) {
+      fast = unsafe { (*next.as_ptr()).next };
+      slow = slow.and_then(|node| unsafe { (*node.as_ptr()).next });
+
+      if fast.is_some() && fast == slow {
+        return true;
       }
+    }
+
+    false
+  }
 
-      current = node.next.as_mut();
+  /// Links the last node back to the node at `index`, calls `f` with the
+  /// list while it contains that cycle, then unlinks it again.
+  ///
+  /// The cycle only exists for the duration of the call and `f` only gets
+  /// a shared reference, so the list cannot be changed while its links
+  /// are broken. Iterating over the whole list inside `f` never ends.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use linked_list::LinkedList;
+  ///
+  /// let mut list: LinkedList<i32> = (1..=4).collect();
+  ///
+  /// assert!(list.make_cycle_for_test(1, |list| list.has_cycle()));
+  /// assert!(!list.has_cycle());
+  /// ```
+  #[cfg(any(test, feature = "test-cycles"))]
+  pub fn make_cycle_for_test<R, F>(&mut self, index: usize, f: F) -> R
+  where
+    F: FnOnce(&Self) -> R,
+  {
+    /// Unlinks the cycle when dropped, even if `f` panics.
+    struct Unlink<T>(NonNull<Node<T>>);
+
+    impl<T> Drop for Unlink<T> {
+      fn drop(&mut self) {
+        // SAFETY: the tail is live as long as the list is borrowed.
+        unsafe { (*self.0.as_ptr()).next = None };
+      }
     }
+
+    assert!(index < self.len, "index out of bounds");
+    let target = self.node_at(index);
+    let tail = self.tail.unwrap();
+
+    // SAFETY: `tail` is the live last node of the list.
+    unsafe { (*tail.as_ptr()).next = Some(target) };
+    let _unlink = Unlink(tail);
+
+    f(self)
+  }
+
+  /// Returns an iterator over references to the elements of the list.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { next: self.head, marker: PhantomData }
+  }
+
+  /// Returns an iterator over mutable references to the elements of the list.
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    IterMut { next: self.head, marker: PhantomData }
+  }
+
+  /// Unlinks `node` from the list, frees it and returns its value.
+  ///
+  /// # Safety
+  ///
+  /// `node` must be a live node of this list that directly follows
+  /// `previous`, or the head if `previous` is `None`.
+  unsafe fn unlink(&mut self, previous: Link<T>, node: NonNull<Node<T>>) -> T {
+    let next = (*node.as_ptr()).next;
+
+    match previous {
+      Some(previous) => (*previous.as_ptr()).next = next,
+      None => self.head = next
+    }
+
+    if next.is_none() {
+      self.tail = previous;
+    }
+
+    self.len -= 1;
+    Box::from_raw(node.as_ptr()).value
+  }
+
+  /// Links a node without successor after the last node of the list.
+  fn link_back(&mut self, node: NonNull<Node<T>>) {
+    match self.tail {
+      // SAFETY: `tail` points to a live node owned by this list.
+      Some(tail) => unsafe { (*tail.as_ptr()).next = Some(node) },
+      None => self.head = Some(node),
+    }
+
+    self.tail = Some(node);
+    self.len += 1;
+  }
+
+  /// Returns the node at the given position.
+  ///
+  /// The caller must make sure that `index < self.len`.
+  fn node_at(&self, index: usize) -> NonNull<Node<T>> {
+    let mut current = self.head.unwrap();
+
+    for _ in 0..index {
+      // SAFETY: the index is in bounds, so every visited node is live
+      // and has a successor.
+      current = unsafe { (*current.as_ptr()).next.unwrap() };
+    }
+
+    current
   }
 }
 
-impl<T: Display> Display for LinkedList<T> {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let mut current = &self.head;
+/// Sorts the first `len` nodes linked from `head` and returns the first
+/// and the last node of the sorted list, which ends with the last node.
+///
+/// # Safety
+///
+/// `len` must be positive and at least `len` live nodes must be linked
+/// from `head`.
+unsafe fn merge_sort<T, F>(head: NonNull<Node<T>>, len: usize, compare: &mut F) -> (NonNull<Node<T>>, NonNull<Node<T>>)
+where
+  F: FnMut(&T, &T) -> Ordering,
+{
+  if len == 1 {
+    (*head.as_ptr()).next = None;
+    return (head, head);
+  }
 
-    write!(f, "[")?;
+  // The second half has to be found before sorting the first one, which
+  // relinks its nodes.
+  let mut middle = head;
+  for _ in 0..len / 2 {
+    middle = (*middle.as_ptr()).next.unwrap();
+  }
+
+  let (left, left_tail) = merge_sort(head, len / 2, compare);
+  let (right, right_tail) = merge_sort(middle, len - len / 2, compare);
+
+  let (mut left, mut right) = (Some(left), Some(right));
+  let mut first: Link<T> = None;
+  let mut last: Link<T> = None;
+
+  while let (Some(l), Some(r)) = (left, right) {
+    // Taking from the left on ties keeps the sort stable.
+    let node = if compare(&(*r.as_ptr()).value, &(*l.as_ptr()).value) == Ordering::Less {
+      right = (*r.as_ptr()).next;
+      r
+    } else {
+      left = (*l.as_ptr()).next;
+      l
+    };
+
+    match last {
+      Some(last) => (*last.as_ptr()).next = Some(node),
+      None => first = Some(node)
+    }
+    last = Some(node);
+  }
+
+  // The rest of one of the halves follows, already linked in order.
+  let (rest, tail) = if left.is_some() { (left, left_tail) } else { (right, right_tail) };
+  (*last.unwrap().as_ptr()).next = rest;
+
+  (first.unwrap(), tail)
+}
+
+impl<T> Drop for LinkedList<T> {
+  /// Frees the nodes one by one, starting from the head.
+  ///
+  /// Every node is unlinked before it is dropped, so dropping never
+  /// recurses into the rest of the list and long lists cannot overflow
+  /// the call stack.
+  fn drop(&mut self) {
+    let mut current = self.head.take();
+    self.tail = None;
+    self.len = 0;
 
     while let Some(node) = current {
-      write!(f, "{}", node.value)?;
-      current = &node.next;
+      // SAFETY: every node was allocated by `Node::alloc` and is visited
+      // exactly once, after its predecessor has been freed.
+      let node = unsafe { Box::from_raw(node.as_ptr()) };
+      current = node.next;
+    }
+  }
+}
+
+/// An iterator over the elements of a `LinkedList`.
+pub struct Iter<'a, T> {
+  next: Link<T>,
+  marker: PhantomData<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  /// Advances the iterator and returns a reference to the next element,
+  /// or `None` if the end of the list is reached.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next.map(|node| {
+      // SAFETY: the node is live for as long as the list is borrowed.
+      let node = unsafe { &*node.as_ptr() };
+      self.next = node.next;
+      &node.value
+    })
+  }
+}
+
+/// A mutable iterator over the elements of a `LinkedList`.
+pub struct IterMut<'a, T> {
+  next: Link<T>,
+  marker: PhantomData<&'a mut Node<T>>
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = &'a mut T;
+
+  /// Advances the iterator and returns a mutable reference to the next
+  /// element, or `None` if the end of the list is reached.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next.map(|node| {
+      // SAFETY: the list is mutably borrowed and every node is yielded
+      // at most once.
+      let node = unsafe { &mut *node.as_ptr() };
+      self.next = node.next;
+      &mut node.value
+    })
+  }
+}
+
+/// An owning iterator over the elements of a `LinkedList`.
+pub struct IntoIter<T> {
+  list: LinkedList<T>
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  /// Removes the first element of the underlying list and returns it,
+  /// or `None` if the list is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.list.pop_front()
+  }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  /// Consumes the list and returns an iterator over its elements.
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter { list: self }
+  }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+  type Item = &'a mut T;
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+  /// Creates a linked list from an iterator, preserving the order
+  /// of the elements.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut list = Self::new();
+
+    for value in iter {
+      list.append(value);
+    }
+
+    list
+  }
+}
+
+impl<T: Clone> Clone for LinkedList<T> {
+  /// Returns a deep copy of the list.
+  fn clone(&self) -> Self {
+    self.iter().cloned().collect()
+  }
+}
+
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+  /// Two lists are equal if they hold equal elements in the same order.
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: Hash> Hash for LinkedList<T> {
+  /// Hashes the length followed by every element, so that lists with
+  /// a common prefix do not collide trivially.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_usize(self.len);
+
+    for value in self.iter() {
+      value.hash(state);
+    }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T: Display> Display for LinkedList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "[")?;
+
+    for value in self.iter() {
+      write!(f, "{}", value)?;
     }
 
     write!(f, "]")
   }
 }
 
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for LinkedList<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the head to the tail.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for LinkedList<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a list from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    alloc::vec::Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+// The nodes of a list can only be reached one after the other, so the
+// parallel iterators first collect the elements into a `Vec`, which rayon
+// can split. This is worth it when the work done per element dominates.
+
+#[cfg(feature = "rayon")]
+impl<T: Send> IntoParallelIterator for LinkedList<T> {
+  type Item = T;
+  type Iter = rayon::vec::IntoIter<T>;
+
+  /// Consumes the list and returns a parallel iterator over its elements,
+  /// from the head to the tail.
+  fn into_par_iter(self) -> Self::Iter {
+    self.into_iter().collect::<Vec<_>>().into_par_iter()
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> IntoParallelIterator for &'a LinkedList<T> {
+  type Item = &'a T;
+  type Iter = rayon::vec::IntoIter<&'a T>;
+
+  /// Returns a parallel iterator over references to the elements of the
+  /// list, from the head to the tail.
+  fn into_par_iter(self) -> Self::Iter {
+    self.iter().collect::<Vec<_>>().into_par_iter()
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Send> IntoParallelIterator for &'a mut LinkedList<T> {
+  type Item = &'a mut T;
+  type Iter = rayon::vec::IntoIter<&'a mut T>;
+
+  /// Returns a parallel iterator over mutable references to the elements
+  /// of the list, from the head to the tail.
+  fn into_par_iter(self) -> Self::Iter {
+    self.iter_mut().collect::<Vec<_>>().into_par_iter()
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> FromParallelIterator<T> for LinkedList<T> {
+  /// Creates a list from the values of the iterator, preserving their order.
+  fn from_par_iter<I: IntoParallelIterator<Item = T>>(iter: I) -> Self {
+    Vec::from_par_iter(iter).into_iter().collect()
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
-  use super::LinkedList;
+  use super::{IndexError, LinkedList};
+  use alloc::collections::VecDeque;
+  use alloc::rc::Rc;
+  use alloc::vec::Vec;
+  use alloc::{format, vec};
+  use proptest::prelude::*;
 
   #[test]
   fn empty_list() {
@@ -147,4 +1123,664 @@ mod tests {
     assert!(!list.is_empty());
     assert_eq!(list.len(), 2);
   }
+
+  #[test]
+  fn pop_front() {
+    let mut list = LinkedList::<i32>::new();
+    assert_eq!(list.pop_front(), None);
+
+    list.append(32);
+    assert_eq!(list.pop_front(), Some(32));
+    assert!(list.is_empty());
+
+    list.append(32);
+    list.append(64);
+    assert_eq!(list.pop_front(), Some(32));
+    assert_eq!(list.pop_front(), Some(64));
+    assert_eq!(list.pop_front(), None);
+  }
+
+  #[test]
+  fn pop_back() {
+    let mut list = LinkedList::<i32>::new();
+    assert_eq!(list.pop_back(), None);
+
+    list.append(32);
+    assert_eq!(list.pop_back(), Some(32));
+    assert!(list.is_empty());
+
+    list.append(32);
+    list.append(64);
+    assert_eq!(list.pop_back(), Some(64));
+    assert_eq!(list.pop_back(), Some(32));
+    assert_eq!(list.pop_back(), None);
+  }
+
+  #[test]
+  fn remove_at() {
+    let mut list = LinkedList::<i32>::new();
+    assert_eq!(list.remove_at(0), None);
+
+    list.append(32);
+    assert_eq!(list.remove_at(1), None);
+    assert_eq!(list.remove_at(0), Some(32));
+    assert!(list.is_empty());
+
+    list.append(32);
+    list.append(64);
+    list.append(128);
+    assert_eq!(list.remove_at(1), Some(64));
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.remove_at(1), Some(128));
+    assert_eq!(list.remove_at(0), Some(32));
+    assert!(list.is_empty());
+  }
+
+  #[test]
+  fn iter() {
+    let mut list = LinkedList::<i32>::new();
+    assert_eq!(list.iter().next(), None);
+
+    list.append(32);
+    list.append(64);
+
+    let mut iter = list.iter();
+    assert_eq!(iter.next(), Some(&32));
+    assert_eq!(iter.next(), Some(&64));
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  fn iter_mut() {
+    let mut list = LinkedList::<i32>::new();
+    list.append(32);
+    list.append(64);
+
+    for value in list.iter_mut() {
+      *value *= 2;
+    }
+
+    assert_eq!(list.pop_front(), Some(64));
+    assert_eq!(list.pop_front(), Some(128));
+  }
+
+  #[test]
+  fn into_iter() {
+    let mut list = LinkedList::<i32>::new();
+    list.append(32);
+    list.append(64);
+
+    let mut iter = list.into_iter();
+    assert_eq!(iter.next(), Some(32));
+    assert_eq!(iter.next(), Some(64));
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  fn from_iter() {
+    let list: LinkedList<i32> = (1..=3).collect();
+
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn non_display_values() {
+    let mut list = LinkedList::<Vec<i32>>::new();
+    list.append(vec![1, 2]);
+    list.prepend(vec![]);
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.pop_back(), Some(vec![1, 2]));
+  }
+
+  #[test]
+  fn len_after_interleaved_operations() {
+    let mut list = LinkedList::<i32>::new();
+
+    list.append(1);
+    list.prepend(0);
+    list.append(2);
+    assert_eq!(list.len(), 3);
+
+    list.remove_at(1);
+    assert_eq!(list.len(), 2);
+    list.remove_at(5);
+    assert_eq!(list.len(), 2);
+
+    list.pop_back();
+    list.prepend(-1);
+    list.pop_front();
+    assert_eq!(list.len(), 1);
+
+    list.pop_front();
+    list.pop_front();
+    list.pop_back();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+
+    let list: LinkedList<i32> = (0..10).collect();
+    assert_eq!(list.len(), 10);
+  }
+
+  #[test]
+  fn append_after_removals() {
+    let mut list = LinkedList::<i32>::new();
+    list.append(1);
+    list.append(2);
+    list.append(3);
+
+    // Removing the last node must move the tail pointer back.
+    assert_eq!(list.remove_at(2), Some(3));
+    list.append(4);
+    assert_eq!(list.pop_back(), Some(4));
+    list.append(5);
+
+    // Emptying the list must reset the tail pointer.
+    list.pop_front();
+    list.pop_front();
+    list.pop_front();
+    list.append(6);
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![6]);
+  }
+
+  #[test]
+  fn insert_at() {
+    let mut list = LinkedList::<i32>::new();
+
+    assert_eq!(list.insert_at(0, 2), Ok(()));
+    assert_eq!(list.insert_at(0, 0), Ok(()));
+    assert_eq!(list.insert_at(1, 1), Ok(()));
+    assert_eq!(list.insert_at(3, 3), Ok(()));
+    assert_eq!(list.insert_at(5, 5), Err(IndexError { index: 5, len: 4 }));
+
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+    // Inserting at the end must keep the tail pointer in sync.
+    list.append(4);
+    assert_eq!(list.get(4), Some(&4));
+  }
+
+  #[test]
+  fn get() {
+    let mut list: LinkedList<i32> = (0..3).collect();
+
+    assert_eq!(list.get(0), Some(&0));
+    assert_eq!(list.get(2), Some(&2));
+    assert_eq!(list.get(3), None);
+
+    *list.get_mut(1).unwrap() = 10;
+    assert_eq!(list.get(1), Some(&10));
+    assert_eq!(list.get_mut(3), None);
+  }
+
+  #[test]
+  fn search() {
+    let list: LinkedList<i32> = [3, 1, 4, 1, 5].into_iter().collect();
+
+    assert!(list.contains(&4));
+    assert!(!list.contains(&2));
+    assert_eq!(list.find(|&value| value > 3), Some(&4));
+    assert_eq!(list.find(|&value| value > 5), None);
+    assert_eq!(list.position(|&value| value == 1), Some(1));
+    assert_eq!(list.position(|&value| value == 5), Some(4));
+    assert_eq!(list.position(|&value| value == 9), None);
+
+    let empty = LinkedList::<i32>::new();
+    assert!(!empty.contains(&0));
+    assert_eq!(empty.position(|_| true), None);
+  }
+
+  #[test]
+  fn middle() {
+    assert_eq!(LinkedList::<i32>::new().middle(), None);
+
+    for len in 1..=6 {
+      let list: LinkedList<i32> = (0..len).collect();
+      assert_eq!(list.middle(), Some(&(len / 2)));
+    }
+  }
+
+  #[test]
+  fn nth_from_end() {
+    let list: LinkedList<i32> = (1..=4).collect();
+
+    assert_eq!(list.nth_from_end(0), Some(&4));
+    assert_eq!(list.nth_from_end(1), Some(&3));
+    assert_eq!(list.nth_from_end(3), Some(&1));
+    assert_eq!(list.nth_from_end(4), None);
+    assert_eq!(list.nth_from_end(usize::MAX), None);
+    assert_eq!(LinkedList::<i32>::new().nth_from_end(0), None);
+  }
+
+  #[test]
+  fn front_and_back() {
+    let mut list = LinkedList::<i32>::new();
+    assert_eq!((list.front(), list.back()), (None, None));
+    assert_eq!(list.front_mut(), None);
+    assert_eq!(list.back_mut(), None);
+
+    list.append(1);
+    assert_eq!((list.front(), list.back()), (Some(&1), Some(&1)));
+
+    list.append(2);
+    list.append(3);
+    *list.front_mut().unwrap() *= 10;
+    *list.back_mut().unwrap() *= 10;
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 2, 30]);
+
+    // The tail must follow the removal of the last node.
+    list.pop_back();
+    assert_eq!(list.back(), Some(&2));
+  }
+
+  #[test]
+  fn reverse() {
+    let mut list = LinkedList::<i32>::new();
+    list.reverse();
+    assert!(list.is_empty());
+
+    list.append(1);
+    list.reverse();
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1]);
+
+    let mut list: LinkedList<i32> = (0..1000).collect();
+    list.reverse();
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), (0..1000).rev().collect::<Vec<_>>());
+    assert_eq!(list.len(), 1000);
+
+    // The tail pointer must now point at the old head.
+    list.append(-1);
+    assert_eq!(list.pop_back(), Some(-1));
+    assert_eq!(list.pop_back(), Some(0));
+  }
+
+  #[test]
+  fn reversed() {
+    let list: LinkedList<i32> = (1..=3).collect();
+
+    assert_eq!(list.reversed().into_iter().collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert!(LinkedList::<i32>::new().reversed().is_empty());
+  }
+
+  #[test]
+  #[cfg_attr(miri, ignore)]
+  fn drop_long_list() {
+    let list: LinkedList<i32> = (0..1_000_000).collect();
+    assert_eq!(list.len(), 1_000_000);
+
+    drop(list);
+  }
+
+  #[test]
+  fn clone_and_eq() {
+    let list: LinkedList<i32> = (0..3).collect();
+    let mut clone = list.clone();
+
+    assert_eq!(clone, list);
+
+    clone.append(3);
+    assert_ne!(clone, list);
+    assert_eq!(list.len(), 3);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn hash() {
+    use std::collections::HashSet;
+
+    let list: LinkedList<i32> = (0..3).collect();
+
+    let mut set = HashSet::new();
+    set.insert(list.clone());
+    assert!(set.contains(&list));
+    assert!(!set.contains(&list.clone().reversed()));
+  }
+
+  #[test]
+  fn debug() {
+    let list: LinkedList<i32> = (1..=3).collect();
+
+    assert_eq!(format!("{list:?}"), "[1, 2, 3]");
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: LinkedList<i32> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let list: LinkedList<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list, value);
+  }
+
+  #[test]
+  #[cfg(feature = "rayon")]
+  fn parallel_iterators() {
+    use rayon::prelude::*;
+
+    let mut list: LinkedList<i32> = (1..=100).into_par_iter().collect();
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), (1..=100).collect::<Vec<_>>());
+
+    list.par_iter_mut().for_each(|value| *value *= 2);
+    assert_eq!(list.par_iter().sum::<i32>(), 10100);
+    assert_eq!(list.into_par_iter().filter(|value| value % 3 == 0).count(), 33);
+  }
+
+  #[test]
+  fn append_list() {
+    let mut list: LinkedList<i32> = (1..=2).collect();
+    let mut other: LinkedList<i32> = (3..=4).collect();
+
+    list.append_list(&mut other);
+    list.append_list(&mut LinkedList::new());
+    assert!(other.is_empty());
+    assert_eq!(list.len(), 4);
+
+    list.append(5);
+    other.append_list(&mut list);
+    assert!(list.is_empty());
+    assert_eq!(other.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+  }
+
+  #[test]
+  fn split_off() {
+    let mut list: LinkedList<i32> = (1..=4).collect();
+
+    let back = list.split_off(1);
+    assert_eq!(back.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(list.split_off(1).len(), 0);
+    list.append(5);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 5]);
+
+    let all = list.split_off(0);
+    assert!(list.is_empty());
+    assert_eq!(all.len(), 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "cannot split off at a nonexistent index")]
+  fn split_off_out_of_bounds() {
+    LinkedList::<i32>::new().split_off(1);
+  }
+
+  #[test]
+  fn sort() {
+    let mut list: LinkedList<i32> = [3, 1, 4, 1, 5, 9, 2, 6].into_iter().collect();
+    list.sort();
+    list.append(10);
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 1, 2, 3, 4, 5, 6, 9, 10]);
+
+    let mut empty = LinkedList::<i32>::new();
+    empty.sort();
+    assert!(empty.is_empty());
+  }
+
+  #[test]
+  fn sort_by_is_stable() {
+    let mut list: LinkedList<(u8, usize)> = (0..100).map(|tag| ((tag * 7 % 5) as u8, tag)).collect();
+    list.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let values: Vec<_> = list.iter().copied().collect();
+    assert!(values.windows(2).all(|pair| pair[0].0 > pair[1].0 || pair[0].1 < pair[1].1));
+  }
+
+  #[test]
+  fn sort_non_clone_values() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Value(i32);
+
+    let mut list: LinkedList<Value> = [2, 3, 1].into_iter().map(Value).collect();
+    list.sort();
+
+    assert_eq!(list.pop_front(), Some(Value(1)));
+    assert_eq!(list.pop_back(), Some(Value(3)));
+  }
+
+  #[test]
+  fn retain() {
+    let mut list: LinkedList<i32> = [1, 2, 3, 4, 5].into_iter().collect();
+
+    // Removes the head and the tail.
+    list.retain(|&value| value != 1 && value != 5);
+    assert_eq!(list.len(), 3);
+    assert_eq!((list.front(), list.back()), (Some(&2), Some(&4)));
+
+    list.append(6);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [2, 3, 4, 6]);
+
+    list.retain(|_| true);
+    assert_eq!(list.len(), 4);
+
+    list.retain(|_| false);
+    assert!(list.is_empty());
+    assert_eq!(list.back(), None);
+
+    list.append(7);
+    assert_eq!((list.front(), list.back(), list.len()), (Some(&7), Some(&7), 1));
+  }
+
+  #[test]
+  fn has_cycle() {
+    let mut list: LinkedList<i32> = (1..=5).collect();
+    assert!(!list.has_cycle());
+    assert!(!LinkedList::<i32>::new().has_cycle());
+
+    for index in 0..5 {
+      assert!(list.make_cycle_for_test(index, |list| {
+        assert_eq!(list.iter().take(7).count(), 7);
+        list.has_cycle()
+      }));
+    }
+
+    // A single node linked to itself.
+    let mut single: LinkedList<i32> = [1].into_iter().collect();
+    assert!(single.make_cycle_for_test(0, LinkedList::has_cycle));
+
+    assert!(!list.has_cycle());
+    list.append(6);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn cycle_unlinked_on_panic() {
+    let mut list: LinkedList<i32> = (1..=3).collect();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      list.make_cycle_for_test(0, |_| panic!("inside the cycle"));
+    }));
+
+    assert!(result.is_err());
+    assert!(!list.has_cycle());
+    assert_eq!(list.len(), 3);
+  }
+
+  #[test]
+  fn dedup() {
+    let mut list: LinkedList<i32> = [1, 1, 2, 2, 2, 3, 1, 1].into_iter().collect();
+    list.dedup();
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 1]);
+    assert_eq!(list.len(), 4);
+
+    // The tail must follow the removal of the last run.
+    list.append(4);
+    assert_eq!(list.back(), Some(&4));
+
+    let mut empty = LinkedList::<i32>::new();
+    empty.dedup();
+    assert!(empty.is_empty());
+  }
+
+  #[test]
+  fn dedup_by() {
+    let mut list: LinkedList<i32> = [10, 11, 20, 25, 31, 39].into_iter().collect();
+    list.dedup_by_key(|value| *value / 10);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 20, 31]);
+
+    // Merges every run into its first element.
+    let mut list: LinkedList<i32> = [1, 2, 5, 6, 7, 10].into_iter().collect();
+    list.dedup_by(|value, kept| {
+      let merged = *value - *kept <= 2;
+      if merged {
+        *kept = *value;
+      }
+      merged
+    });
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [2, 7, 10]);
+    assert_eq!(list.len(), 3);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn remove_all_duplicates() {
+    let mut list: LinkedList<&str> = ["a", "b", "a", "c", "b", "b"].into_iter().collect();
+    list.remove_all_duplicates();
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), ["a", "b", "c"]);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.back(), Some(&"c"));
+  }
+
+  #[test]
+  fn filter_map_in_place() {
+    let mut list: LinkedList<i32> = (1..=6).collect();
+
+    list.filter_map_in_place(|value| (value % 3 != 0).then_some(value * 10));
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.back(), Some(&50));
+
+    list.append(7);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 20, 40, 50, 7]);
+
+    list.filter_map_in_place(|_| None);
+    assert!(list.is_empty());
+    assert_eq!((list.front(), list.back()), (None, None));
+  }
+
+  #[test]
+  fn filter_drops_removed_values() {
+    let value = Rc::new(());
+    let mut list: LinkedList<Rc<()>> = (0..6).map(|_| Rc::clone(&value)).collect();
+
+    let mut index = 0;
+    list.retain(|_| {
+      index += 1;
+      index % 2 == 0
+    });
+    assert_eq!(Rc::strong_count(&value), 4);
+
+    list.filter_map_in_place(|value| Some(value).filter(|_| false));
+    assert_eq!(Rc::strong_count(&value), 1);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Prepend(i32),
+    Append(i32),
+    InsertAt(usize, i32),
+    Get(usize),
+    PopFront,
+    PopBack,
+    RemoveAt(usize),
+    Reverse,
+    SplitOff(usize),
+    Sort,
+    Retain(i32),
+    FilterMap,
+    Dedup
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Prepend),
+      any::<i32>().prop_map(Operation::Append),
+      (0..20usize, any::<i32>()).prop_map(|(index, value)| Operation::InsertAt(index, value)),
+      (0..20usize).prop_map(Operation::Get),
+      Just(Operation::PopFront),
+      Just(Operation::PopBack),
+      (0..20usize).prop_map(Operation::RemoveAt),
+      Just(Operation::Reverse),
+      (0..20usize).prop_map(Operation::SplitOff),
+      Just(Operation::Sort),
+      (2..5i32).prop_map(Operation::Retain),
+      Just(Operation::FilterMap),
+      Just(Operation::Dedup)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut list = LinkedList::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Prepend(value) => {
+            list.prepend(value);
+            model.push_front(value);
+          }
+          Operation::Append(value) => {
+            list.append(value);
+            model.push_back(value);
+          }
+          Operation::InsertAt(index, value) => {
+            let result = list.insert_at(index, value);
+            prop_assert_eq!(result.is_ok(), index <= model.len());
+            if result.is_ok() {
+              model.insert(index, value);
+            }
+          }
+          Operation::Get(index) => prop_assert_eq!(list.get(index), model.get(index)),
+          Operation::PopFront => prop_assert_eq!(list.pop_front(), model.pop_front()),
+          Operation::PopBack => prop_assert_eq!(list.pop_back(), model.pop_back()),
+          Operation::RemoveAt(index) => prop_assert_eq!(list.remove_at(index), model.remove(index)),
+          Operation::Reverse => {
+            list.reverse();
+            model.make_contiguous().reverse();
+          }
+          Operation::SplitOff(at) if at <= model.len() => {
+            let mut back = list.split_off(at);
+            let model_back = model.split_off(at);
+            prop_assert!(list.iter().eq(model.iter()));
+            prop_assert!(back.iter().eq(model_back.iter()));
+
+            list.append_list(&mut back);
+            model.extend(model_back);
+            prop_assert!(back.is_empty());
+          }
+          Operation::SplitOff(_) => {}
+          Operation::Sort => {
+            list.sort();
+            model.make_contiguous().sort();
+          }
+          Operation::Retain(divisor) => {
+            list.retain(|value| value % divisor != 0);
+            model.retain(|value| value % divisor != 0);
+          }
+          Operation::FilterMap => {
+            let halve = |value: i32| (value % 2 == 0).then_some(value / 2);
+            list.filter_map_in_place(halve);
+            model = model.into_iter().filter_map(halve).collect();
+          }
+          Operation::Dedup => {
+            list.dedup();
+            let mut values = Vec::from(model);
+            values.dedup();
+            model = values.into();
+          }
+        }
+
+        prop_assert_eq!(list.len(), model.len());
+        prop_assert!(list.iter().eq(model.iter()));
+      }
+    }
+  }
 }
@@ -0,0 +1,454 @@
+use std::fmt;
+use std::iter::StepBy;
+use std::ops::{Index, IndexMut, Range};
+use std::slice;
+
+
+/// Offsets of the neighbors sharing a side with a cell, in row-major order.
+const SIDES: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+/// Offsets of the neighbors sharing a side or a corner with a cell, in
+/// row-major order.
+const SIDES_AND_CORNERS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A dense two-dimensional grid of values, addressed by row and column.
+///
+/// The values are stored row by row in one `Vec`, so the cell at
+/// `(row, col)` is found in O(1) and a row is a plain slice. Columns are
+/// iterated with a stride of one row. Every cell holds a value, so the grid
+/// takes O(r c) space for `r` rows and `c` columns, which suits maps, images
+/// and matrices with few zeros, where a `SparseMatrix` suits the others.
+///
+/// # Example
+///
+/// ```
+/// use matrix::Grid;
+///
+/// let mut grid = Grid::from_fn(2, 3, |row, col| row * 3 + col);
+/// grid[(1, 2)] = 50;
+///
+/// assert_eq!(grid.row(1), [3, 4, 50]);
+/// assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![1, 4]);
+/// assert_eq!(grid.transpose().row(2), [2, 50]);
+/// assert_eq!(grid.rotate_clockwise().row(0), [3, 0]);
+///
+/// // The cells around the top-left corner.
+/// assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+/// assert_eq!(grid.neighbors8(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 0), (1, 1)]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+  rows: usize,
+  cols: usize,
+  /// Values of the cells, row by row.
+  values: Vec<T>
+}
+
+/// A dense matrix, the same structure as a `Grid`.
+pub type Matrix<T> = Grid<T>;
+
+impl<T> Grid<T> {
+  /// Creates a new grid of `rows` rows and `cols` columns with every cell
+  /// set to the value.
+  pub fn new(rows: usize, cols: usize, value: T) -> Self
+  where
+    T: Clone,
+  {
+    Self { rows, cols, values: vec![value; rows * cols] }
+  }
+
+  /// Creates a new grid of `rows` rows and `cols` columns with every cell
+  /// set to the value of the function at its row and column.
+  pub fn from_fn<F: FnMut(usize, usize) -> T>(rows: usize, cols: usize, mut f: F) -> Self {
+    let values = (0..rows).flat_map(|row| (0..cols).map(move |col| (row, col))).map(|(row, col)| f(row, col)).collect();
+    Self { rows, cols, values }
+  }
+
+  /// Creates a new grid of `rows` rows and `cols` columns from its values,
+  /// row by row.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the number of values is not `rows * cols`.
+  pub fn from_vec(rows: usize, cols: usize, values: Vec<T>) -> Self {
+    assert!(values.len() == rows * cols, "length must be the number of cells");
+    Self { rows, cols, values }
+  }
+
+  /// Returns the number of rows of the grid.
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Returns the number of columns of the grid.
+  pub fn cols(&self) -> usize {
+    self.cols
+  }
+
+  /// Returns the values of the grid, row by row.
+  pub fn as_slice(&self) -> &[T] {
+    &self.values
+  }
+
+  /// Returns the values of the grid, row by row.
+  pub fn into_vec(self) -> Vec<T> {
+    self.values
+  }
+
+  /// Returns a reference to the value of the cell, or `None` if it is out
+  /// of bounds.
+  pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+    self.index_of(row, col).map(|index| &self.values[index])
+  }
+
+  /// Returns a mutable reference to the value of the cell, or `None` if it
+  /// is out of bounds.
+  pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+    self.index_of(row, col).map(|index| &mut self.values[index])
+  }
+
+  /// Returns the values of the row.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` is out of bounds.
+  pub fn row(&self, row: usize) -> &[T] {
+    assert!(row < self.rows, "index out of bounds");
+    &self.values[row * self.cols..(row + 1) * self.cols]
+  }
+
+  /// Returns the values of the row as a mutable slice.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` is out of bounds.
+  pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+    assert!(row < self.rows, "index out of bounds");
+    &mut self.values[row * self.cols..(row + 1) * self.cols]
+  }
+
+  /// Returns an iterator over the values of the column, from the top.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `col` is out of bounds.
+  pub fn column(&self, col: usize) -> Column<'_, T> {
+    assert!(col < self.cols, "index out of bounds");
+    Column { values: self.values[col..].iter().step_by(self.cols) }
+  }
+
+  /// Returns an iterator over the rows of the grid, from the top.
+  pub fn iter_rows(&self) -> Rows<'_, T> {
+    Rows { grid: self, rows: 0..self.rows }
+  }
+
+  /// Returns an iterator over the columns of the grid, from the left.
+  pub fn iter_columns(&self) -> Columns<'_, T> {
+    Columns { grid: self, cols: 0..self.cols }
+  }
+
+  /// Returns an iterator over the values of the grid, row by row.
+  pub fn iter(&self) -> slice::Iter<'_, T> {
+    self.values.iter()
+  }
+
+  /// Returns an iterator over the cells that share a side with the cell,
+  /// in row-major order.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the cell is out of bounds.
+  pub fn neighbors4(&self, row: usize, col: usize) -> Neighbors {
+    self.neighbors(row, col, &SIDES)
+  }
+
+  /// Returns an iterator over the cells that share a side or a corner with
+  /// the cell, in row-major order.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the cell is out of bounds.
+  pub fn neighbors8(&self, row: usize, col: usize) -> Neighbors {
+    self.neighbors(row, col, &SIDES_AND_CORNERS)
+  }
+
+  /// Returns the transposed grid, whose cell at `(col, row)` holds the
+  /// value of this grid at `(row, col)`.
+  pub fn transpose(&self) -> Grid<T>
+  where
+    T: Clone,
+  {
+    Grid::from_fn(self.cols, self.rows, |row, col| self[(col, row)].clone())
+  }
+
+  /// Returns the grid turned a quarter turn clockwise, whose first row is
+  /// the first column of this grid from the bottom.
+  pub fn rotate_clockwise(&self) -> Grid<T>
+  where
+    T: Clone,
+  {
+    Grid::from_fn(self.cols, self.rows, |row, col| self[(self.rows - 1 - col, row)].clone())
+  }
+
+  /// Returns the grid turned a quarter turn counterclockwise, whose first
+  /// row is the last column of this grid from the top.
+  pub fn rotate_counterclockwise(&self) -> Grid<T>
+  where
+    T: Clone,
+  {
+    Grid::from_fn(self.cols, self.rows, |row, col| self[(col, self.cols - 1 - row)].clone())
+  }
+
+  fn index_of(&self, row: usize, col: usize) -> Option<usize> {
+    (row < self.rows && col < self.cols).then_some(row * self.cols + col)
+  }
+
+  fn neighbors(&self, row: usize, col: usize, offsets: &'static [(isize, isize)]) -> Neighbors {
+    assert!(row < self.rows && col < self.cols, "index out of bounds");
+    Neighbors { cell: (row, col), size: (self.rows, self.cols), offsets: offsets.iter() }
+  }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+  type Output = T;
+
+  /// Returns a reference to the value at `(row, col)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the cell is out of bounds.
+  fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+    self.get(row, col).expect("index out of bounds")
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+  /// Returns a mutable reference to the value at `(row, col)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the cell is out of bounds.
+  fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+    self.get_mut(row, col).expect("index out of bounds")
+  }
+}
+
+/// An iterator over the values of a column of a `Grid`.
+pub struct Column<'a, T> {
+  values: StepBy<slice::Iter<'a, T>>
+}
+
+impl<'a, T> Iterator for Column<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.values.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.values.size_hint()
+  }
+}
+
+impl<T> ExactSizeIterator for Column<'_, T> {}
+
+/// An iterator over the rows of a `Grid`, as slices.
+pub struct Rows<'a, T> {
+  grid: &'a Grid<T>,
+  rows: Range<usize>
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+  type Item = &'a [T];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.rows.next().map(|row| self.grid.row(row))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.rows.size_hint()
+  }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+/// An iterator over the columns of a `Grid`.
+pub struct Columns<'a, T> {
+  grid: &'a Grid<T>,
+  cols: Range<usize>
+}
+
+impl<'a, T> Iterator for Columns<'a, T> {
+  type Item = Column<'a, T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.cols.next().map(|col| self.grid.column(col))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.cols.size_hint()
+  }
+}
+
+impl<T> ExactSizeIterator for Columns<'_, T> {}
+
+/// An iterator over the rows and columns of the neighbors of a cell of a
+/// `Grid` that are in bounds.
+pub struct Neighbors {
+  cell: (usize, usize),
+  /// Numbers of rows and columns of the grid.
+  size: (usize, usize),
+  offsets: slice::Iter<'static, (isize, isize)>
+}
+
+impl Iterator for Neighbors {
+  type Item = (usize, usize);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.offsets.find_map(|&(row, col)| {
+      let row = self.cell.0.checked_add_signed(row).filter(|&row| row < self.size.0)?;
+      let col = self.cell.1.checked_add_signed(col).filter(|&col| col < self.size.1)?;
+      Some((row, col))
+    })
+  }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+  type Item = &'a T;
+  type IntoIter = slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+  /// Formats the grid as a list of rows.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter_rows()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Grid<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the dimensions and the values of the grid, row by row.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    (self.rows, self.cols, &self.values).serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Grid<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes the dimensions and the values of a grid.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (rows, cols, values): (usize, usize, Vec<T>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if rows.checked_mul(cols) != Some(values.len()) {
+      return Err(D::Error::invalid_length(values.len(), &"a value for every cell"));
+    }
+
+    Ok(Self { rows, cols, values })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Grid;
+
+  fn grid() -> Grid<u32> {
+    // 0 1 2
+    // 3 4 5
+    Grid::from_fn(2, 3, |row, col| (row * 3 + col) as u32)
+  }
+
+  #[test]
+  fn empty_grid() {
+    let grid = Grid::<u32>::from_vec(3, 0, Vec::new());
+
+    assert_eq!(grid.get(0, 0), None);
+    assert_eq!(grid.iter_rows().map(|row| row.len()).collect::<Vec<_>>(), vec![0, 0, 0]);
+    assert_eq!(grid.iter_columns().count(), 0);
+    assert_eq!(grid.transpose(), Grid::from_vec(0, 3, Vec::new()));
+  }
+
+  #[test]
+  fn get_and_index() {
+    let mut grid = grid();
+    *grid.get_mut(0, 1).unwrap() = 10;
+    grid.row_mut(1)[0] = 30;
+
+    assert_eq!(grid[(0, 1)], 10);
+    assert_eq!(grid.get(1, 0), Some(&30));
+    assert_eq!(grid.get(2, 0), None);
+    assert_eq!(grid.get(0, 3), None);
+    assert_eq!(format!("{:?}", grid), "[[0, 10, 2], [30, 4, 5]]");
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn index_out_of_bounds() {
+    let _ = grid()[(0, 3)];
+  }
+
+  #[test]
+  #[should_panic(expected = "length must be the number of cells")]
+  fn from_vec_wrong_length() {
+    Grid::from_vec(2, 2, vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn rows_and_columns() {
+    let grid = grid();
+    let columns: Vec<Vec<u32>> = grid.iter_columns().map(|column| column.copied().collect()).collect();
+
+    assert_eq!(grid.iter_rows().collect::<Vec<_>>(), vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+    assert_eq!(columns, vec![vec![0, 3], vec![1, 4], vec![2, 5]]);
+    assert_eq!(grid.column(2).len(), 2);
+    assert_eq!(grid.iter().sum::<u32>(), 15);
+  }
+
+  #[test]
+  fn transpose_and_rotate() {
+    let grid = grid();
+
+    assert_eq!(grid.transpose(), Grid::from_vec(3, 2, vec![0, 3, 1, 4, 2, 5]));
+    assert_eq!(grid.rotate_clockwise(), Grid::from_vec(3, 2, vec![3, 0, 4, 1, 5, 2]));
+    assert_eq!(grid.rotate_counterclockwise(), Grid::from_vec(3, 2, vec![2, 5, 1, 4, 0, 3]));
+
+    let turned = (0..4).fold(grid.clone(), |grid, _| grid.rotate_clockwise());
+    assert_eq!(turned, grid);
+    assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+  }
+
+  #[test]
+  fn neighbors() {
+    let grid = Grid::new(3, 3, ());
+
+    assert_eq!(grid.neighbors4(1, 1).collect::<Vec<_>>(), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+    assert_eq!(grid.neighbors8(1, 1).count(), 8);
+    assert_eq!(grid.neighbors4(2, 2).collect::<Vec<_>>(), vec![(1, 2), (2, 1)]);
+    assert_eq!(grid.neighbors8(0, 2).collect::<Vec<_>>(), vec![(0, 1), (1, 1), (1, 2)]);
+    assert_eq!(Grid::new(1, 1, ()).neighbors8(0, 0).next(), None);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = grid();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[2,3,[0,1,2,3,4,5]]");
+
+    let grid: Grid<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(grid, value);
+    assert!(serde_json::from_str::<Grid<u32>>("[2,2,[1]]").is_err());
+  }
+}
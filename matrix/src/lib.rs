@@ -0,0 +1,7 @@
+//! Matrices of values addressed by row and column.
+
+pub mod grid;
+pub mod sparse;
+
+pub use grid::{Grid, Matrix};
+pub use sparse::{CooMatrix, SparseMatrix};
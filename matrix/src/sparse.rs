@@ -0,0 +1,449 @@
+use std::iter::Zip;
+use std::ops::{Add, Mul};
+use std::slice;
+
+
+/// A sparse matrix being built from its entries in any order, in
+/// coordinate (COO) format.
+///
+/// The entries are kept as a list of rows, columns and values, so adding one
+/// is O(1). Converting the list into a `SparseMatrix` sorts it once, which
+/// is the cheap way to build a matrix from edges or measurements that come
+/// in no particular order.
+///
+/// # Example
+///
+/// ```
+/// use matrix::{CooMatrix, SparseMatrix};
+///
+/// let mut entries = CooMatrix::new(2, 3);
+/// entries.push(1, 2, 5);
+/// entries.push(0, 0, 1);
+/// entries.push(1, 2, 7);
+///
+/// // The last value of an entry wins.
+/// let matrix = SparseMatrix::from(entries);
+/// assert_eq!(matrix.get(1, 2), Some(&7));
+/// assert_eq!(matrix.entry_count(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CooMatrix<T> {
+  rows: usize,
+  cols: usize,
+  /// Rows, columns and values of the entries, in the order they were added.
+  entries: Vec<(usize, usize, T)>
+}
+
+impl<T> CooMatrix<T> {
+  /// Creates a new matrix of `rows` rows and `cols` columns without
+  /// entries.
+  pub fn new(rows: usize, cols: usize) -> Self {
+    Self { rows, cols, entries: Vec::new() }
+  }
+
+  /// Returns the number of rows of the matrix.
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Returns the number of columns of the matrix.
+  pub fn cols(&self) -> usize {
+    self.cols
+  }
+
+  /// Returns the number of entries added to the matrix, counting the
+  /// entries added several times once for every time.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Checks if no entry was added to the matrix.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  /// Adds an entry to the matrix. An entry added again replaces the value
+  /// of the previous one in the converted matrix.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` or `col` is out of bounds.
+  pub fn push(&mut self, row: usize, col: usize, value: T) {
+    assert!(row < self.rows && col < self.cols, "index out of bounds");
+    self.entries.push((row, col, value));
+  }
+}
+
+impl<T> Extend<(usize, usize, T)> for CooMatrix<T> {
+  /// Adds every row, column and value of the iterator.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a row or a column is out of bounds.
+  fn extend<I: IntoIterator<Item = (usize, usize, T)>>(&mut self, iter: I) {
+    for (row, col, value) in iter {
+      self.push(row, col, value);
+    }
+  }
+}
+
+/// A sparse matrix in compressed sparse row (CSR) format.
+///
+/// Only the entries that were set are stored, row by row, and every row
+/// keeps its entries sorted by column. The entries of a row are thus next
+/// to each other, which makes iterating a row and multiplying the matrix by
+/// a vector fast, while looking up an entry is a binary search in its row.
+/// The matrix takes O(r + e) space for `r` rows and `e` entries, however
+/// many columns it has, as an adjacency matrix of a sparse graph would.
+///
+/// The entries can not be changed once the matrix is built, it is built
+/// from a `CooMatrix` instead.
+///
+/// # Example
+///
+/// ```
+/// use matrix::{CooMatrix, SparseMatrix};
+///
+/// let mut entries = CooMatrix::new(3, 3);
+/// entries.extend([(0, 1, 2.0), (1, 2, 3.0), (2, 0, 4.0), (0, 0, 1.0)]);
+/// let matrix = SparseMatrix::from(entries);
+///
+/// assert_eq!(matrix.row(0).collect::<Vec<_>>(), vec![(0, &1.0), (1, &2.0)]);
+/// assert_eq!(matrix.get(1, 1), None);
+/// assert_eq!(matrix.mul_vector(&[1.0, 1.0, 2.0]), vec![3.0, 6.0, 4.0]);
+/// assert_eq!(matrix.transpose().get(2, 1), Some(&3.0));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMatrix<T> {
+  rows: usize,
+  cols: usize,
+  /// Position of the first entry of every row in `columns` and `values`,
+  /// and the number of entries as the last position.
+  row_starts: Vec<usize>,
+  /// Columns of the entries, row by row.
+  columns: Vec<usize>,
+  /// Values of the entries, row by row.
+  values: Vec<T>
+}
+
+impl<T> SparseMatrix<T> {
+  /// Creates a new matrix of `rows` rows and `cols` columns without
+  /// entries.
+  pub fn new(rows: usize, cols: usize) -> Self {
+    Self { rows, cols, row_starts: vec![0; rows + 1], columns: Vec::new(), values: Vec::new() }
+  }
+
+  /// Returns the number of rows of the matrix.
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Returns the number of columns of the matrix.
+  pub fn cols(&self) -> usize {
+    self.cols
+  }
+
+  /// Returns the number of stored entries.
+  pub fn entry_count(&self) -> usize {
+    self.values.len()
+  }
+
+  /// Returns a reference to the value of the entry, or `None` if it is not
+  /// stored, in O(log e) for `e` entries in the row.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` or `col` is out of bounds.
+  pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+    assert!(row < self.rows && col < self.cols, "index out of bounds");
+
+    let range = self.row_starts[row]..self.row_starts[row + 1];
+    let index = self.columns[range.clone()].binary_search(&col).ok()?;
+    Some(&self.values[range.start + index])
+  }
+
+  /// Returns an iterator over the columns and values of the entries of the
+  /// row, in the order of the columns.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` is out of bounds.
+  pub fn row(&self, row: usize) -> Row<'_, T> {
+    assert!(row < self.rows, "index out of bounds");
+
+    let range = self.row_starts[row]..self.row_starts[row + 1];
+    Row { entries: self.columns[range.clone()].iter().zip(&self.values[range]) }
+  }
+
+  /// Returns an iterator over the rows, columns and values of the entries,
+  /// row by row.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { matrix: self, row: 0, index: 0 }
+  }
+
+  /// Returns the transposed matrix, whose entry at `(col, row)` is the
+  /// entry of this matrix at `(row, col)`, in O(r + c + e).
+  pub fn transpose(&self) -> SparseMatrix<T>
+  where
+    T: Clone,
+  {
+    // Counts the entries of every column to find where its row starts in
+    // the transposed matrix.
+    let mut row_starts = vec![0; self.cols + 1];
+    for &col in &self.columns {
+      row_starts[col + 1] += 1;
+    }
+    for col in 0..self.cols {
+      row_starts[col + 1] += row_starts[col];
+    }
+
+    // Places the entries row by row, so every column receives its entries
+    // in the order of their rows.
+    let mut next = row_starts.clone();
+    let mut order = vec![0; self.values.len()];
+    let mut columns = vec![0; self.values.len()];
+    for row in 0..self.rows {
+      for index in self.row_starts[row]..self.row_starts[row + 1] {
+        let position = &mut next[self.columns[index]];
+        (order[*position], columns[*position]) = (index, row);
+        *position += 1;
+      }
+    }
+
+    let values = order.into_iter().map(|index| self.values[index].clone()).collect();
+    SparseMatrix { rows: self.cols, cols: self.rows, row_starts, columns, values }
+  }
+
+  /// Multiplies the matrix by a column vector, in O(r + e). The entries
+  /// that are not stored count as zero, the default value of `T`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the length of the vector is not the number of columns.
+  pub fn mul_vector(&self, vector: &[T]) -> Vec<T>
+  where
+    T: Clone + Default + Add<Output = T> + Mul<Output = T>,
+  {
+    assert!(vector.len() == self.cols, "vector length must match the number of columns");
+
+    (0..self.rows)
+      .map(|row| self.row(row).fold(T::default(), |sum, (col, value)| sum + value.clone() * vector[col].clone()))
+      .collect()
+  }
+}
+
+impl<T> From<CooMatrix<T>> for SparseMatrix<T> {
+  /// Sorts the entries row by row in O(e log e), keeping the last value of
+  /// the entries added several times.
+  fn from(coo: CooMatrix<T>) -> Self {
+    let mut entries = coo.entries;
+    // The sort is stable, so the last value of an entry comes last.
+    entries.sort_by_key(|&(row, col, _)| (row, col));
+
+    let mut matrix = Self::new(coo.rows, coo.cols);
+    let mut previous = None;
+    for (row, col, value) in entries {
+      if previous == Some((row, col)) {
+        *matrix.values.last_mut().unwrap() = value;
+        continue;
+      }
+
+      previous = Some((row, col));
+      matrix.row_starts[row + 1] += 1;
+      matrix.columns.push(col);
+      matrix.values.push(value);
+    }
+
+    // Turns the counts of entries of every row into positions.
+    for row in 0..coo.rows {
+      matrix.row_starts[row + 1] += matrix.row_starts[row];
+    }
+
+    matrix
+  }
+}
+
+/// An iterator over the columns and values of the entries of a row of a
+/// `SparseMatrix`.
+pub struct Row<'a, T> {
+  entries: Zip<slice::Iter<'a, usize>, slice::Iter<'a, T>>
+}
+
+impl<'a, T> Iterator for Row<'a, T> {
+  type Item = (usize, &'a T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.entries.next().map(|(&col, value)| (col, value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.entries.size_hint()
+  }
+}
+
+impl<T> ExactSizeIterator for Row<'_, T> {}
+
+/// An iterator over the rows, columns and values of the entries of a
+/// `SparseMatrix`, row by row.
+pub struct Iter<'a, T> {
+  matrix: &'a SparseMatrix<T>,
+  row: usize,
+  /// Position of the next entry.
+  index: usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = (usize, usize, &'a T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let matrix = self.matrix;
+    if self.index == matrix.values.len() {
+      return None;
+    }
+
+    while matrix.row_starts[self.row + 1] <= self.index {
+      self.row += 1;
+    }
+
+    self.index += 1;
+    Some((self.row, matrix.columns[self.index - 1], &matrix.values[self.index - 1]))
+  }
+}
+
+impl<'a, T> IntoIterator for &'a SparseMatrix<T> {
+  type Item = (usize, usize, &'a T);
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for SparseMatrix<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the dimensions and the rows, columns and values of the
+  /// entries, row by row.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    (self.rows, self.cols, self.iter().collect::<Vec<_>>()).serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for SparseMatrix<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes the dimensions and the entries of a matrix.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (rows, cols, entries): (usize, usize, Vec<(usize, usize, T)>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if entries.iter().any(|&(row, col, _)| row >= rows || col >= cols) {
+      return Err(D::Error::custom("entry out of bounds"));
+    }
+
+    Ok(Self::from(CooMatrix { rows, cols, entries }))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{CooMatrix, SparseMatrix};
+  use proptest::prelude::*;
+
+  fn matrix(rows: usize, cols: usize, entries: &[(usize, usize, i64)]) -> SparseMatrix<i64> {
+    let mut coo = CooMatrix::new(rows, cols);
+    coo.extend(entries.iter().copied());
+    SparseMatrix::from(coo)
+  }
+
+  #[test]
+  fn empty_matrix() {
+    let matrix = SparseMatrix::<i64>::new(2, 0);
+
+    assert_eq!(matrix.entry_count(), 0);
+    assert_eq!(matrix.row(1).len(), 0);
+    assert_eq!(matrix.iter().next(), None);
+    assert_eq!(matrix.mul_vector(&[]), vec![0, 0]);
+    assert_eq!(matrix.transpose(), SparseMatrix::new(0, 2));
+  }
+
+  #[test]
+  fn duplicate_entries() {
+    let matrix = matrix(2, 2, &[(1, 1, 1), (0, 1, 2), (1, 1, 3), (1, 0, 4), (1, 1, 5)]);
+
+    assert_eq!(matrix.entry_count(), 3);
+    assert_eq!(matrix.iter().collect::<Vec<_>>(), vec![(0, 1, &2), (1, 0, &4), (1, 1, &5)]);
+  }
+
+  #[test]
+  fn empty_rows() {
+    let matrix = matrix(5, 3, &[(4, 0, 1), (1, 2, 2)]);
+
+    assert_eq!(matrix.iter().collect::<Vec<_>>(), vec![(1, 2, &2), (4, 0, &1)]);
+    assert_eq!(matrix.row(2).next(), None);
+    assert_eq!(matrix.transpose().row(0).collect::<Vec<_>>(), vec![(4, &1)]);
+    assert_eq!(matrix.mul_vector(&[1, 10, 100]), vec![0, 200, 0, 0, 1]);
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn push_out_of_bounds() {
+    CooMatrix::new(2, 2).push(0, 2, 1);
+  }
+
+  #[test]
+  #[should_panic(expected = "vector length must match the number of columns")]
+  fn mul_vector_wrong_length() {
+    matrix(2, 2, &[]).mul_vector(&[1]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = matrix(2, 3, &[(1, 0, 4), (0, 2, 5)]);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[2,3,[[0,2,5],[1,0,4]]]");
+
+    let matrix: SparseMatrix<i64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(matrix, value);
+    assert!(serde_json::from_str::<SparseMatrix<i64>>("[2,3,[[2,0,1]]]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_dense_matrix(
+      rows in 1..8usize,
+      cols in 1..8usize,
+      entries in prop::collection::vec((0..8usize, 0..8usize, -100..100i64), 0..40),
+      vector in prop::collection::vec(-100..100i64, 8)
+    ) {
+      let entries: Vec<_> = entries.into_iter().filter(|&(row, col, _)| row < rows && col < cols).collect();
+      let matrix = matrix(rows, cols, &entries);
+
+      let mut dense = vec![vec![None; cols]; rows];
+      for &(row, col, value) in &entries {
+        dense[row][col] = Some(value);
+      }
+
+      let transposed = matrix.transpose();
+      for (row, values) in dense.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+          prop_assert_eq!(matrix.get(row, col), value.as_ref());
+          prop_assert_eq!(transposed.get(col, row), value.as_ref());
+        }
+      }
+
+      let expected: Vec<i64> = dense.iter().map(|row| row.iter().zip(&vector).map(|(value, x)| value.unwrap_or(0) * x).sum()).collect();
+      prop_assert_eq!(matrix.mul_vector(&vector[..cols]), expected);
+      prop_assert_eq!(transposed.transpose(), matrix);
+    }
+  }
+}
@@ -0,0 +1,11 @@
+//! Collections that hold a key more than once.
+//!
+//! `MultiMap` maps every key to the list of its values and `MultiSet`
+//! counts how many times every value was inserted. Both are built on the
+//! workspace's `HashMap`, so their operations run in expected O(1).
+
+pub mod map;
+pub mod set;
+
+pub use map::MultiMap;
+pub use set::MultiSet;
@@ -0,0 +1,434 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use hashmap::HashMap;
+
+
+/// A map that keeps every value inserted under a key.
+///
+/// Every key maps to the list of its values in insertion order, stored in
+/// the workspace's `HashMap`. A key is only present while it has at least
+/// one value, so `len` counts the key-value pairs and `key_len` the distinct
+/// keys. Values can be visited one pair at a time with `iter` or grouped by
+/// key with `groups`.
+///
+/// # Example
+///
+/// ```
+/// use multi::MultiMap;
+///
+/// let mut authors = MultiMap::new();
+/// authors.insert("Pratchett", "Mort");
+/// authors.insert("Le Guin", "The Dispossessed");
+/// authors.insert("Pratchett", "Small Gods");
+///
+/// assert_eq!(authors.get("Pratchett"), ["Mort", "Small Gods"]);
+/// assert_eq!(authors.len(), 3);
+/// assert_eq!(authors.key_len(), 2);
+///
+/// assert!(authors.remove_value("Pratchett", &"Mort"));
+/// assert_eq!(authors.get("Pratchett"), ["Small Gods"]);
+/// ```
+pub struct MultiMap<K, V> {
+  map: HashMap<K, Vec<V>>,
+  len: usize
+}
+
+impl<K: Hash + Eq, V> Default for MultiMap<K, V> {
+  /// Creates a new instance of `MultiMap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Hash + Eq, V> MultiMap<K, V> {
+  /// Creates a new empty multimap.
+  pub fn new() -> Self {
+    Self { map: HashMap::new(), len: 0 }
+  }
+
+  /// Returns the number of key-value pairs in the multimap.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns the number of distinct keys in the multimap.
+  pub fn key_len(&self) -> usize {
+    self.map.len()
+  }
+
+  /// Checks if the multimap is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Adds the value after the other values of the key.
+  pub fn insert(&mut self, key: K, value: V) {
+    self.map.entry(key).or_default().push(value);
+    self.len += 1;
+  }
+
+  /// Returns the values of the key in insertion order, which is empty if
+  /// the multimap does not contain the key.
+  pub fn get<Q>(&self, key: &Q) -> &[V]
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.get(key).map_or(&[], Vec::as_slice)
+  }
+
+  /// Returns the values of the key as a mutable slice, which is empty if
+  /// the multimap does not contain the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> &mut [V]
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    match self.map.get_mut(key) {
+      Some(values) => values,
+      None => &mut []
+    }
+  }
+
+  /// Checks if the multimap contains at least one value for the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(key)
+  }
+
+  /// Removes the key and returns all its values, or `None` if the
+  /// multimap did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<Vec<V>>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let values = self.map.remove(key)?;
+    self.len -= values.len();
+    Some(values)
+  }
+
+  /// Removes the first occurrence of the value from the values of the key,
+  /// and the key itself if it was its last value.
+  ///
+  /// Returns `true` if the value was present.
+  pub fn remove_value<Q>(&mut self, key: &Q, value: &V) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    V: PartialEq,
+  {
+    let Some(values) = self.map.get_mut(key) else {
+      return false;
+    };
+    let Some(index) = values.iter().position(|other| other == value) else {
+      return false;
+    };
+
+    values.remove(index);
+    if values.is_empty() {
+      self.map.remove(key);
+    }
+
+    self.len -= 1;
+    true
+  }
+
+  /// Returns an iterator over the key-value pairs, where the pairs of a
+  /// key come one after another in insertion order and the keys come in
+  /// arbitrary order.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { groups: self.map.iter(), group: None, remaining: self.len }
+  }
+
+  /// Returns an iterator over the keys with all their values, in arbitrary
+  /// order.
+  pub fn groups(&self) -> Groups<'_, K, V> {
+    Groups { inner: self.map.iter() }
+  }
+
+  /// Returns an iterator over the distinct keys in arbitrary order.
+  pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+    self.map.keys()
+  }
+
+  /// Removes all pairs from the multimap.
+  pub fn clear(&mut self) {
+    self.map.clear();
+    self.len = 0;
+  }
+}
+
+/// An iterator over the key-value pairs of a `MultiMap`.
+pub struct Iter<'a, K, V> {
+  groups: hashmap::Iter<'a, K, Vec<V>>,
+  group: Option<(&'a K, std::slice::Iter<'a, V>)>,
+  remaining: usize
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  /// Advances the iterator and returns the next pair, moving on to the
+  /// following key when the values of the current one are exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((key, values)) = &mut self.group {
+        if let Some(value) = values.next() {
+          self.remaining -= 1;
+          return Some((*key, value));
+        }
+      }
+
+      let (key, values) = self.groups.next()?;
+      self.group = Some((key, values.iter()));
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+/// An iterator over the keys of a `MultiMap` with all their values.
+pub struct Groups<'a, K, V> {
+  inner: hashmap::Iter<'a, K, Vec<V>>
+}
+
+impl<'a, K, V> Iterator for Groups<'a, K, V> {
+  type Item = (&'a K, &'a [V]);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(key, values)| (key, values.as_slice()))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<'a, K: Hash + Eq, V> IntoIterator for &'a MultiMap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for MultiMap<K, V> {
+  /// Creates a multimap from key-value pairs, keeping every pair.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut map = Self::new();
+    map.extend(iter);
+    map
+  }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for MultiMap<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for MultiMap<K, V>
+where
+  K: serde::Serialize + Hash + Eq,
+  V: serde::Serialize,
+{
+  /// Serializes the multimap as a map from every key to the sequence of
+  /// its values, with the keys in arbitrary order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.groups())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for MultiMap<K, V>
+where
+  K: serde::Deserialize<'de> + Hash + Eq,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map from keys to sequences of values. The values of a
+  /// key that appears twice are all kept.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Hash + Eq,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = MultiMap<K, V>;
+
+      fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a map of sequences")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = MultiMap::new();
+
+        while let Some((key, values)) = access.next_entry::<K, Vec<V>>()? {
+          if !values.is_empty() {
+            map.len += values.len();
+            map.map.entry(key).or_insert_with(Vec::new).extend(values);
+          }
+        }
+
+        Ok(map)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::MultiMap;
+  use proptest::prelude::*;
+  use std::collections::HashMap;
+
+  fn sorted_pairs(map: &MultiMap<u8, i32>) -> Vec<(u8, i32)> {
+    let mut pairs: Vec<_> = map.iter().map(|(key, value)| (*key, *value)).collect();
+    pairs.sort();
+    pairs
+  }
+
+  #[test]
+  fn empty_multimap() {
+    let mut map = MultiMap::<i32, i32>::new();
+
+    assert!(map.is_empty());
+    assert!(map.get(&1).is_empty());
+    assert!(map.get_mut(&1).is_empty());
+    assert_eq!(map.remove(&1), None);
+    assert!(!map.remove_value(&1, &1));
+    assert_eq!(map.iter().next(), None);
+  }
+
+  #[test]
+  fn insert_keeps_every_value() {
+    let mut map = MultiMap::new();
+    map.insert(1, 'a');
+    map.insert(2, 'b');
+    map.insert(1, 'c');
+    map.insert(1, 'a');
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.key_len(), 2);
+    assert_eq!(map.get(&1), ['a', 'c', 'a']);
+    assert_eq!(map.get(&2), ['b']);
+
+    map.get_mut(&2)[0] = 'B';
+    assert_eq!(map.get(&2), ['B']);
+  }
+
+  #[test]
+  fn remove() {
+    let mut map: MultiMap<u8, i32> = [(1, 10), (2, 20), (1, 11), (1, 10)].into_iter().collect();
+    assert_eq!(sorted_pairs(&map), vec![(1, 10), (1, 10), (1, 11), (2, 20)]);
+
+    assert!(map.remove_value(&1, &10));
+    assert_eq!(map.get(&1), [11, 10]);
+    assert!(!map.remove_value(&1, &12));
+
+    assert!(map.remove_value(&2, &20));
+    assert!(!map.contains_key(&2));
+    assert_eq!(map.key_len(), 1);
+
+    assert_eq!(map.remove(&1), Some(vec![11, 10]));
+    assert!(map.is_empty());
+  }
+
+  #[test]
+  fn grouped_iteration() {
+    let map: MultiMap<&str, i32> = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+
+    let mut groups: Vec<_> = map.groups().collect();
+    groups.sort();
+    assert_eq!(groups, vec![(&"a", &[1, 3][..]), (&"b", &[2][..])]);
+
+    // The pairs of a key are adjacent and in insertion order.
+    let pairs: Vec<_> = map.iter().collect();
+    let a = pairs.iter().position(|(key, _)| **key == "a").unwrap();
+    assert_eq!(pairs[a..a + 2], [(&"a", &1), (&"a", &3)]);
+    assert_eq!(map.iter().size_hint(), (3, Some(3)));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: MultiMap<u8, i32> = [(1, 10), (2, 20), (1, 11)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+
+    let map: MultiMap<u8, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sorted_pairs(&map), sorted_pairs(&value));
+
+    let map: MultiMap<String, i32> = serde_json::from_str(r#"{"a": [1, 2], "b": []}"#).unwrap();
+    assert_eq!(map.len(), 2);
+    assert!(!map.contains_key("b"));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    RemoveValue(u8, i32)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (0..16u8, 0..4i32).prop_map(|(key, value)| Operation::Insert(key, value)),
+      1 => (0..16u8).prop_map(Operation::Remove),
+      2 => (0..16u8, 0..4i32).prop_map(|(key, value)| Operation::RemoveValue(key, value))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_map_of_vectors(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut map = MultiMap::new();
+      let mut model = HashMap::<u8, Vec<i32>>::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => {
+            map.insert(key, value);
+            model.entry(key).or_default().push(value);
+          }
+          Operation::Remove(key) => prop_assert_eq!(map.remove(&key), model.remove(&key)),
+          Operation::RemoveValue(key, value) => {
+            let values = model.entry(key).or_default();
+            let expected = match values.iter().position(|other| *other == value) {
+              Some(index) => {
+                values.remove(index);
+                true
+              }
+              None => false
+            };
+            model.retain(|_, values| !values.is_empty());
+
+            prop_assert_eq!(map.remove_value(&key, &value), expected);
+          }
+        }
+
+        prop_assert_eq!(map.len(), model.values().map(Vec::len).sum::<usize>());
+        prop_assert_eq!(map.key_len(), model.len());
+        for (key, values) in &model {
+          prop_assert_eq!(map.get(key), values.as_slice());
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,361 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use hashmap::HashMap;
+
+
+/// A set that counts how many times every value was inserted, also known
+/// as a bag.
+///
+/// Every value maps to its number of occurrences in the workspace's
+/// `HashMap`, so a value inserted a million times takes the room of one.
+/// A value is only stored while its count is positive. `len` counts the
+/// occurrences and `distinct_len` the distinct values.
+///
+/// # Example
+///
+/// ```
+/// use multi::MultiSet;
+///
+/// let mut letters: MultiSet<char> = "mississippi".chars().collect();
+///
+/// assert_eq!(letters.count(&'s'), 4);
+/// assert_eq!(letters.len(), 11);
+/// assert_eq!(letters.distinct_len(), 4);
+///
+/// assert!(letters.remove(&'m'));
+/// assert!(!letters.contains(&'m'));
+/// assert_eq!(letters.remove_many(&'p', 5), 2);
+/// assert_eq!(letters.len(), 8);
+/// ```
+pub struct MultiSet<T> {
+  map: HashMap<T, usize>,
+  len: usize
+}
+
+impl<T: Hash + Eq> Default for MultiSet<T> {
+  /// Creates a new instance of `MultiSet` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Hash + Eq> MultiSet<T> {
+  /// Creates a new empty multiset.
+  pub fn new() -> Self {
+    Self { map: HashMap::new(), len: 0 }
+  }
+
+  /// Returns the number of values in the multiset, counting every
+  /// occurrence.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns the number of distinct values in the multiset.
+  pub fn distinct_len(&self) -> usize {
+    self.map.len()
+  }
+
+  /// Checks if the multiset is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Adds one occurrence of the value to the multiset.
+  pub fn insert(&mut self, value: T) {
+    self.insert_many(value, 1);
+  }
+
+  /// Adds `count` occurrences of the value to the multiset.
+  pub fn insert_many(&mut self, value: T, count: usize) {
+    if count > 0 {
+      *self.map.entry(value).or_insert(0) += count;
+      self.len += count;
+    }
+  }
+
+  /// Returns the number of occurrences of the value.
+  pub fn count<Q>(&self, value: &Q) -> usize
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.get(value).copied().unwrap_or(0)
+  }
+
+  /// Checks if the multiset contains at least one occurrence of the value.
+  pub fn contains<Q>(&self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(value)
+  }
+
+  /// Removes one occurrence of the value.
+  ///
+  /// Returns `true` if the value was present.
+  pub fn remove<Q>(&mut self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.remove_many(value, 1) == 1
+  }
+
+  /// Removes up to `count` occurrences of the value and returns how many
+  /// were removed.
+  pub fn remove_many<Q>(&mut self, value: &Q, count: usize) -> usize
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let Some(current) = self.map.get_mut(value) else {
+      return 0;
+    };
+
+    let removed = count.min(*current);
+    *current -= removed;
+    if *current == 0 {
+      self.map.remove(value);
+    }
+
+    self.len -= removed;
+    removed
+  }
+
+  /// Removes every occurrence of the value and returns how many were
+  /// removed.
+  pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let removed = self.map.remove(value).unwrap_or(0);
+    self.len -= removed;
+    removed
+  }
+
+  /// Returns an iterator over the distinct values with their counts, in
+  /// arbitrary order.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { inner: self.map.iter() }
+  }
+
+  /// Removes all values from the multiset.
+  pub fn clear(&mut self) {
+    self.map.clear();
+    self.len = 0;
+  }
+}
+
+/// An iterator over the distinct values of a `MultiSet` with their counts.
+pub struct Iter<'a, T> {
+  inner: hashmap::Iter<'a, T, usize>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = (&'a T, usize);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(value, count)| (value, *count))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<'a, T: Hash + Eq> IntoIterator for &'a MultiSet<T> {
+  type Item = (&'a T, usize);
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for MultiSet<T> {
+  /// Creates a multiset from an iterator, counting duplicate values.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut set = Self::new();
+    set.extend(iter);
+    set
+  }
+}
+
+impl<T: Hash + Eq> Extend<T> for MultiSet<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.insert(value);
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for MultiSet<T>
+where
+  T: serde::Serialize + Hash + Eq,
+{
+  /// Serializes the multiset as a map from every distinct value to its
+  /// count, in arbitrary order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for MultiSet<T>
+where
+  T: serde::Deserialize<'de> + Hash + Eq,
+{
+  /// Deserializes a map from values to counts. The counts of a value that
+  /// appears twice are added up.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T> serde::de::Visitor<'de> for MapVisitor<T>
+    where
+      T: serde::Deserialize<'de> + Hash + Eq,
+    {
+      type Value = MultiSet<T>;
+
+      fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a map of counts")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut set = MultiSet::new();
+
+        while let Some((value, count)) = access.next_entry()? {
+          set.insert_many(value, count);
+        }
+
+        Ok(set)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::MultiSet;
+  use proptest::prelude::*;
+  use std::collections::HashMap;
+
+  fn sorted_counts(set: &MultiSet<u8>) -> Vec<(u8, usize)> {
+    let mut counts: Vec<_> = set.iter().map(|(value, count)| (*value, count)).collect();
+    counts.sort();
+    counts
+  }
+
+  #[test]
+  fn empty_multiset() {
+    let mut set = MultiSet::<i32>::new();
+
+    assert!(set.is_empty());
+    assert_eq!(set.count(&1), 0);
+    assert!(!set.remove(&1));
+    assert_eq!(set.remove_all(&1), 0);
+  }
+
+  #[test]
+  fn insert_counts_occurrences() {
+    let mut set = MultiSet::new();
+    set.insert(1);
+    set.insert(2);
+    set.insert(1);
+    set.insert_many(3, 5);
+    set.insert_many(4, 0);
+
+    assert_eq!(set.len(), 8);
+    assert_eq!(set.distinct_len(), 3);
+    assert_eq!(set.count(&1), 2);
+    assert_eq!(set.count(&3), 5);
+    assert!(!set.contains(&4));
+    assert_eq!(sorted_counts(&set), vec![(1, 2), (2, 1), (3, 5)]);
+  }
+
+  #[test]
+  fn remove_is_multiplicity_aware() {
+    let mut set: MultiSet<u8> = [1, 1, 1, 2, 2].into_iter().collect();
+
+    assert!(set.remove(&1));
+    assert_eq!(set.count(&1), 2);
+    assert_eq!(set.remove_many(&1, 10), 2);
+    assert!(!set.contains(&1));
+
+    assert_eq!(set.remove_all(&2), 2);
+    assert!(set.is_empty());
+    assert_eq!(set.distinct_len(), 0);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: MultiSet<u8> = [1, 1, 2].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+
+    let set: MultiSet<u8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sorted_counts(&set), vec![(1, 2), (2, 1)]);
+
+    let set: MultiSet<String> = serde_json::from_str(r#"{"a": 2, "b": 0}"#).unwrap();
+    assert_eq!(set.len(), 2);
+    assert!(!set.contains("b"));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, usize),
+    Remove(u8, usize),
+    RemoveAll(u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (0..16u8, 0..4usize).prop_map(|(value, count)| Operation::Insert(value, count)),
+      2 => (0..16u8, 0..4usize).prop_map(|(value, count)| Operation::Remove(value, count)),
+      1 => (0..16u8).prop_map(Operation::RemoveAll)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_map_of_counts(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut set = MultiSet::new();
+      let mut model = HashMap::<u8, usize>::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(value, count) => {
+            set.insert_many(value, count);
+            *model.entry(value).or_default() += count;
+          }
+          Operation::Remove(value, count) => {
+            let current = model.entry(value).or_default();
+            let removed = count.min(*current);
+            *current -= removed;
+
+            prop_assert_eq!(set.remove_many(&value, count), removed);
+          }
+          Operation::RemoveAll(value) => {
+            prop_assert_eq!(set.remove_all(&value), model.remove(&value).unwrap_or(0));
+          }
+        }
+        model.retain(|_, count| *count > 0);
+
+        prop_assert_eq!(set.len(), model.values().sum::<usize>());
+        prop_assert_eq!(set.distinct_len(), model.len());
+        for (value, count) in &model {
+          prop_assert_eq!(set.count(value), *count);
+        }
+      }
+    }
+  }
+}
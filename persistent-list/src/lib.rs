@@ -0,0 +1,295 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+pub mod map;
+pub mod vector;
+
+pub use map::PersistentMap;
+pub use vector::PersistentVector;
+
+
+/// An immutable, persistent singly linked list.
+///
+/// Operations never modify a list in place. Instead, `push_front` and
+/// `tail` return new lists that share their nodes with the original one
+/// through reference counting, so both versions stay valid and cloning
+/// a list is O(1).
+///
+/// # Example
+///
+/// ```
+/// use persistent_list::PersistentList;
+///
+/// let empty = PersistentList::new();
+/// let list = empty.push_front(3).push_front(2);
+/// let one = list.push_front(1);
+/// let zero = list.push_front(0);
+///
+/// // `one` and `zero` share the nodes of `list`.
+/// assert_eq!(one.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// assert_eq!(zero.iter().copied().collect::<Vec<_>>(), vec![0, 2, 3]);
+///
+/// assert_eq!(one.head(), Some(&1));
+/// assert_eq!(one.tail(), list);
+/// assert!(empty.is_empty());
+/// ```
+pub struct PersistentList<T> {
+  head: Link<T>,
+  len: usize
+}
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+/// Represents a node of a persistent list, possibly shared by several lists.
+struct Node<T> {
+  /// The value stored in the node.
+  value: T,
+  /// The next node in the list.
+  next: Link<T>
+}
+
+impl<T> Default for PersistentList<T> {
+  /// Creates a new instance of `PersistentList` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> PersistentList<T> {
+  /// Creates a new empty persistent list.
+  pub fn new() -> Self {
+    Self { head: None, len: 0 }
+  }
+
+  /// Checks if the list is empty.
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Returns the number of elements in the list.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns a new list with the value in front of the elements of
+  /// this list, sharing them with it.
+  pub fn push_front(&self, value: T) -> Self {
+    let node = Node { value, next: self.head.clone() };
+    Self { head: Some(Rc::new(node)), len: self.len + 1 }
+  }
+
+  /// Returns a reference to the first element of the list,
+  /// or `None` if the list is empty.
+  pub fn head(&self) -> Option<&T> {
+    self.head.as_ref().map(|node| &node.value)
+  }
+
+  /// Returns the list without its first element, sharing the remaining
+  /// elements with this list.
+  ///
+  /// The tail of an empty list is an empty list.
+  pub fn tail(&self) -> Self {
+    match &self.head {
+      Some(node) => Self { head: node.next.clone(), len: self.len - 1 },
+      None => Self::new()
+    }
+  }
+
+  /// Returns an iterator over the elements of the list.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { next: self.head.as_deref() }
+  }
+}
+
+impl<T> Clone for PersistentList<T> {
+  /// Returns a list sharing all the nodes of this one, O(1).
+  fn clone(&self) -> Self {
+    Self { head: self.head.clone(), len: self.len }
+  }
+}
+
+impl<T> Drop for PersistentList<T> {
+  /// Drops the nodes owned only by this list one by one, stopping at
+  /// the first node that is shared with another list.
+  fn drop(&mut self) {
+    let mut current = self.head.take();
+
+    while let Some(node) = current {
+      match Rc::try_unwrap(node) {
+        Ok(mut node) => current = node.next.take(),
+        Err(_) => break
+      }
+    }
+  }
+}
+
+/// An iterator over the elements of a `PersistentList`.
+pub struct Iter<'a, T> {
+  next: Option<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next.map(|node| {
+      self.next = node.next.as_deref();
+      &node.value
+    })
+  }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T> FromIterator<T> for PersistentList<T> {
+  /// Creates a persistent list from an iterator, preserving the order
+  /// of the elements.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let values: Vec<T> = iter.into_iter().collect();
+    let mut list = Self::new();
+
+    for value in values.into_iter().rev() {
+      list = list.push_front(value);
+    }
+
+    list
+  }
+}
+
+impl<T: PartialEq> PartialEq for PersistentList<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for PersistentList<T> {}
+
+impl<T: Hash> Hash for PersistentList<T> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.len.hash(state);
+    for value in self.iter() {
+      value.hash(state);
+    }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for PersistentList<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the head to the end.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for PersistentList<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a list from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::PersistentList;
+
+  #[test]
+  fn empty_list() {
+    let list = PersistentList::<i32>::new();
+
+    assert!(list.is_empty());
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.head(), None);
+    assert!(list.tail().is_empty());
+  }
+
+  #[test]
+  fn push_front() {
+    let list = PersistentList::new().push_front(2).push_front(1);
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.head(), Some(&1));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+  }
+
+  #[test]
+  fn tail() {
+    let list: PersistentList<i32> = (1..=3).collect();
+    let tail = list.tail();
+
+    assert_eq!(tail.len(), 2);
+    assert_eq!(tail.head(), Some(&2));
+    assert_eq!(tail.tail().tail(), PersistentList::new());
+  }
+
+  #[test]
+  fn versions_are_preserved() {
+    let base: PersistentList<i32> = (2..=3).collect();
+    let a = base.push_front(1);
+    let b = base.push_front(0);
+    drop(base);
+
+    assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![0, 2, 3]);
+    assert_eq!(a.tail(), b.tail());
+  }
+
+  #[test]
+  fn tails_share_nodes() {
+    let list: PersistentList<i32> = (1..=3).collect();
+    let clone = list.clone();
+
+    let head = list.head.as_ref().unwrap();
+    assert!(std::rc::Rc::ptr_eq(head, clone.head.as_ref().unwrap()));
+    assert!(std::rc::Rc::ptr_eq(head.next.as_ref().unwrap(), list.tail().head.as_ref().unwrap()));
+  }
+
+  #[test]
+  fn drop_long_list() {
+    let list: PersistentList<u32> = (0..1_000_000).collect();
+    let tail = list.tail();
+
+    drop(list);
+    assert_eq!(tail.len(), 999_999);
+  }
+
+  #[test]
+  fn debug() {
+    let list: PersistentList<i32> = (1..=3).collect();
+
+    assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: PersistentList<i32> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let list: PersistentList<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list, value);
+  }
+}
@@ -0,0 +1,652 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::slice;
+use std::sync::Arc;
+
+
+/// Number of hash bits consumed by every level of the trie.
+const BITS: u32 = 5;
+
+const MASK: u64 = (1 << BITS) - 1;
+
+/// An immutable, persistent hash map.
+///
+/// The entries are kept in a hash array mapped trie: every level of the
+/// trie is indexed by the next 5 bits of the hash of a key, and every
+/// branch only stores the children that exist, next to a bitmap telling
+/// which ones they are. `insert` and `remove` return a new map that copies
+/// only the branches on the path to the key, O(log n) with a base of 32,
+/// and shares all the others with the original one. Cloning a map is O(1).
+///
+/// The nodes are shared through atomic reference counting, so versions of
+/// a map can be sent to other threads and read there while the original
+/// thread keeps creating new ones, for example to keep the snapshots of
+/// an undo history.
+///
+/// # Example
+///
+/// ```
+/// use persistent_list::PersistentMap;
+///
+/// let empty = PersistentMap::new();
+/// let one = empty.insert("a", 1);
+/// let two = one.insert("b", 2);
+/// let changed = two.insert("a", 10).remove("b");
+///
+/// assert_eq!(two.get("a"), Some(&1));
+/// assert_eq!(two.len(), 2);
+/// assert_eq!(changed.get("a"), Some(&10));
+/// assert!(!changed.contains_key("b"));
+/// assert!(empty.is_empty());
+/// ```
+pub struct PersistentMap<K, V> {
+  root: Arc<Branch<K, V>>,
+  len: usize,
+  hasher: RandomState
+}
+
+/// A node of the trie, possibly shared by several maps.
+#[derive(Clone)]
+struct Branch<K, V> {
+  /// Bit `i` is set if the branch has a child for the hash chunk `i`.
+  bitmap: u32,
+  /// The children, in the order of their hash chunks.
+  children: Vec<Child<K, V>>
+}
+
+#[derive(Clone)]
+enum Child<K, V> {
+  Entry { hash: u64, key: K, value: V },
+  /// The entries whose keys have the same hash.
+  Collision { hash: u64, entries: Vec<(K, V)> },
+  Branch(Arc<Branch<K, V>>)
+}
+
+impl<K, V> Branch<K, V> {
+  fn new() -> Self {
+    Self { bitmap: 0, children: Vec::new() }
+  }
+
+  /// Returns the bit of the hash chunk of `hash` at `shift`, and the
+  /// position its child has or would have in `children`.
+  fn slot(&self, hash: u64, shift: u32) -> (u32, usize) {
+    let bit = 1 << ((hash >> shift) & MASK);
+    (bit, (self.bitmap & (bit - 1)).count_ones() as usize)
+  }
+}
+
+impl<K, V> Default for PersistentMap<K, V> {
+  /// Creates a new instance of `PersistentMap` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K, V> PersistentMap<K, V> {
+  /// Creates a new empty persistent map.
+  pub fn new() -> Self {
+    Self { root: Arc::new(Branch::new()), len: 0, hasher: RandomState::new() }
+  }
+
+  /// Returns the number of entries in the map.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the map is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns an iterator over the entries of the map, in no particular order.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    Iter { stack: vec![self.root.children.iter()], collision: [].iter(), remaining: self.len }
+  }
+
+  /// Returns an iterator over the keys of the map, in no particular order.
+  pub fn keys(&self) -> Keys<'_, K, V> {
+    Keys { inner: self.iter() }
+  }
+
+  /// Returns an iterator over the values of the map, in no particular order.
+  pub fn values(&self) -> Values<'_, K, V> {
+    Values { inner: self.iter() }
+  }
+}
+
+impl<K: Hash + Eq, V> PersistentMap<K, V> {
+  /// Returns a reference to the value of the key, or `None` if the map
+  /// does not contain it.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let hash = self.hasher.hash_one(key);
+    let mut branch = &*self.root;
+    let mut shift = 0;
+
+    loop {
+      let (bit, position) = branch.slot(hash, shift);
+      if branch.bitmap & bit == 0 {
+        return None;
+      }
+
+      match &branch.children[position] {
+        Child::Entry { hash: other, key: candidate, value } => {
+          return (*other == hash && candidate.borrow() == key).then_some(value);
+        }
+        Child::Collision { hash: other, entries } => {
+          return entries.iter().find(|(candidate, _)| *other == hash && candidate.borrow() == key).map(|(_, value)| value);
+        }
+        Child::Branch(child) => {
+          branch = child;
+          shift += BITS;
+        }
+      }
+    }
+  }
+
+  /// Checks if the map contains the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.get(key).is_some()
+  }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PersistentMap<K, V> {
+  /// Returns a new map in which the key has the value, replacing its
+  /// previous value if any, sharing the other entries with this map.
+  pub fn insert(&self, key: K, value: V) -> Self {
+    let mut map = self.clone();
+    map.insert_mut(key, value);
+    map
+  }
+
+  /// Returns a new map without the key, sharing the other entries with
+  /// this map.
+  pub fn remove<Q>(&self, key: &Q) -> Self
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    let mut map = self.clone();
+    let hash = map.hasher.hash_one(key);
+
+    if remove(Arc::make_mut(&mut map.root), 0, hash, key).is_some() {
+      map.len -= 1;
+    }
+
+    map
+  }
+
+  /// Inserts the entry in place, copying only the branches shared with
+  /// other maps.
+  fn insert_mut(&mut self, key: K, value: V) {
+    let hash = self.hasher.hash_one(&key);
+
+    if insert(Arc::make_mut(&mut self.root), 0, hash, key, value).is_none() {
+      self.len += 1;
+    }
+  }
+}
+
+/// Inserts the entry below the branch whose children are indexed by the
+/// hash chunk at `shift`, and returns the previous value of the key.
+fn insert<K: Eq + Clone, V: Clone>(branch: &mut Branch<K, V>, shift: u32, hash: u64, key: K, value: V) -> Option<V> {
+  let (bit, position) = branch.slot(hash, shift);
+
+  if branch.bitmap & bit == 0 {
+    branch.bitmap |= bit;
+    branch.children.insert(position, Child::Entry { hash, key, value });
+    return None;
+  }
+
+  let child = &mut branch.children[position];
+
+  match child {
+    Child::Entry { hash: other, key: existing, value: old } if *other == hash => {
+      if *existing == key {
+        return Some(std::mem::replace(old, value));
+      }
+
+      let (existing, old) = (existing.clone(), old.clone());
+      *child = Child::Collision { hash, entries: vec![(existing, old), (key, value)] };
+      None
+    }
+    Child::Collision { hash: other, entries } if *other == hash => {
+      match entries.iter_mut().find(|(existing, _)| *existing == key) {
+        Some((_, old)) => Some(std::mem::replace(old, value)),
+        None => {
+          entries.push((key, value));
+          None
+        }
+      }
+    }
+    Child::Branch(child) => insert(Arc::make_mut(child), shift + BITS, hash, key, value),
+    // The hash of the child differs from the one of the key, so they are
+    // moved down to a new branch, where their hash chunks may differ.
+    Child::Entry { hash: other, .. } | Child::Collision { hash: other, .. } => {
+      let other = *other;
+      let existing = std::mem::replace(child, Child::Branch(Arc::new(Branch::new())));
+      *child = Child::Branch(Arc::new(split(shift + BITS, other, existing, hash, Child::Entry { hash, key, value })));
+      None
+    }
+  }
+}
+
+/// Returns a branch holding two children of different hashes, below as
+/// many single-child branches as the hash chunks from `shift` on have in
+/// common.
+fn split<K, V>(shift: u32, hash: u64, child: Child<K, V>, other_hash: u64, other: Child<K, V>) -> Branch<K, V> {
+  let (bit, other_bit) = (1 << ((hash >> shift) & MASK), 1 << ((other_hash >> shift) & MASK));
+
+  if bit == other_bit {
+    let branch = split(shift + BITS, hash, child, other_hash, other);
+    return Branch { bitmap: bit, children: vec![Child::Branch(Arc::new(branch))] };
+  }
+
+  let children = if bit < other_bit { vec![child, other] } else { vec![other, child] };
+  Branch { bitmap: bit | other_bit, children }
+}
+
+/// Removes the key from below the branch whose children are indexed by
+/// the hash chunk at `shift`, and returns its value.
+///
+/// A branch left with a single entry or collision is replaced by it, so
+/// the trie stays as shallow as after inserting the remaining keys only.
+fn remove<K, V, Q>(branch: &mut Branch<K, V>, shift: u32, hash: u64, key: &Q) -> Option<V>
+where
+  K: Borrow<Q> + Clone,
+  V: Clone,
+  Q: Eq + ?Sized,
+{
+  let (bit, position) = branch.slot(hash, shift);
+  if branch.bitmap & bit == 0 {
+    return None;
+  }
+
+  match &mut branch.children[position] {
+    Child::Entry { hash: other, key: existing, .. } if *other == hash && (*existing).borrow() == key => {
+      branch.bitmap &= !bit;
+      match branch.children.remove(position) {
+        Child::Entry { value, .. } => Some(value),
+        _ => unreachable!("the child is an entry")
+      }
+    }
+    Child::Collision { hash: other, entries } if *other == hash => {
+      let index = entries.iter().position(|(existing, _)| existing.borrow() == key)?;
+      let (_, value) = entries.remove(index);
+
+      if entries.len() == 1 {
+        let (key, value) = entries.pop().expect("one entry is left");
+        branch.children[position] = Child::Entry { hash, key, value };
+      }
+
+      Some(value)
+    }
+    Child::Branch(child) => {
+      let child = Arc::make_mut(child);
+      let value = remove(child, shift + BITS, hash, key)?;
+
+      if child.children.len() == 1 && !matches!(child.children[0], Child::Branch(_)) {
+        branch.children[position] = child.children.pop().expect("one child is left");
+      }
+
+      Some(value)
+    }
+    _ => None
+  }
+}
+
+impl<K, V> Clone for PersistentMap<K, V> {
+  /// Returns a map sharing all the branches of this one, O(1).
+  fn clone(&self) -> Self {
+    Self { root: Arc::clone(&self.root), len: self.len, hasher: self.hasher.clone() }
+  }
+}
+
+/// An iterator over the entries of a `PersistentMap`.
+pub struct Iter<'a, K, V> {
+  /// The children left to visit in every branch on the path to the
+  /// current child.
+  stack: Vec<slice::Iter<'a, Child<K, V>>>,
+  /// The entries left to visit in the current collision.
+  collision: slice::Iter<'a, (K, V)>,
+  remaining: usize
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((key, value)) = self.collision.next() {
+        self.remaining -= 1;
+        return Some((key, value));
+      }
+
+      match self.stack.last_mut()?.next() {
+        Some(Child::Entry { key, value, .. }) => {
+          self.remaining -= 1;
+          return Some((key, value));
+        }
+        Some(Child::Collision { entries, .. }) => self.collision = entries.iter(),
+        Some(Child::Branch(branch)) => self.stack.push(branch.children.iter()),
+        None => {
+          self.stack.pop();
+        }
+      }
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// An iterator over the keys of a `PersistentMap`.
+pub struct Keys<'a, K, V> {
+  inner: Iter<'a, K, V>
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+  type Item = &'a K;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(key, _)| key)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+/// An iterator over the values of a `PersistentMap`.
+pub struct Values<'a, K, V> {
+  inner: Iter<'a, K, V>
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+  type Item = &'a V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(_, value)| value)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a PersistentMap<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> FromIterator<(K, V)> for PersistentMap<K, V> {
+  /// Creates a persistent map from an iterator of entries, the last value
+  /// of a key wins.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut map = Self::new();
+
+    // The branches are not shared yet, so inserting in place never copies them.
+    for (key, value) in iter {
+      map.insert_mut(key, value);
+    }
+
+    map
+  }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for PersistentMap<K, V> {
+  /// Two maps are equal if they hold the same keys with equal values.
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().all(|(key, value)| other.get(key) == Some(value))
+  }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for PersistentMap<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PersistentMap<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for PersistentMap<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map, in no particular order.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for PersistentMap<K, V>
+where
+  K: serde::Deserialize<'de> + Hash + Eq + Clone,
+  V: serde::Deserialize<'de> + Clone,
+{
+  /// Deserializes a map. Later pairs overwrite earlier pairs with the
+  /// same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Hash + Eq + Clone,
+      V: serde::Deserialize<'de> + Clone,
+    {
+      type Value = PersistentMap<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = PersistentMap::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          map.insert_mut(key, value);
+        }
+
+        Ok(map)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+  use std::hash::{Hash, Hasher};
+  use std::sync::Arc;
+
+  use super::{Child, PersistentMap};
+  use proptest::prelude::*;
+
+  /// A key whose hash only depends on its value divided by 4, so every
+  /// four consecutive keys collide.
+  #[derive(Debug, Clone, PartialEq, Eq)]
+  struct Colliding(u32);
+
+  impl Hash for Colliding {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+      (self.0 / 4).hash(state);
+    }
+  }
+
+  #[test]
+  fn empty_map() {
+    let map = PersistentMap::<i32, i32>::new();
+
+    assert!(map.is_empty());
+    assert_eq!(map.get(&1), None);
+    assert_eq!(map.iter().count(), 0);
+    assert!(map.remove(&1).is_empty());
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let map: PersistentMap<u32, u32> = (0..10_000).map(|key| (key, key * 2)).collect();
+
+    assert_eq!(map.len(), 10_000);
+    assert!((0..10_000).all(|key| map.get(&key) == Some(&(key * 2))));
+    assert_eq!(map.get(&10_000), None);
+
+    let replaced = map.insert(7, 0);
+    assert_eq!(replaced.len(), 10_000);
+    assert_eq!(replaced.get(&7), Some(&0));
+    assert_eq!(map.get(&7), Some(&14));
+  }
+
+  #[test]
+  fn remove() {
+    let full: PersistentMap<u32, u32> = (0..1000).map(|key| (key, key)).collect();
+    let mut map = full.clone();
+
+    for key in (0..1000).filter(|key| key % 3 != 0) {
+      map = map.remove(&key);
+    }
+
+    assert_eq!(map.len(), 334);
+    assert!((0..1000).all(|key| map.contains_key(&key) == (key % 3 == 0)));
+    assert_eq!(full.len(), 1000);
+
+    let emptied = (0..1000).fold(map, |map, key| map.remove(&key));
+    assert!(emptied.is_empty());
+    assert!(emptied.root.children.is_empty());
+  }
+
+  #[test]
+  fn collisions() {
+    let map: PersistentMap<Colliding, u32> = (0..12).map(|key| (Colliding(key), key)).collect();
+
+    assert_eq!(map.len(), 12);
+    assert!((0..12).all(|key| map.get(&Colliding(key)) == Some(&key)));
+    assert_eq!(map.get(&Colliding(12)), None);
+
+    let map = map.insert(Colliding(5), 50).remove(&Colliding(4)).remove(&Colliding(6)).remove(&Colliding(7));
+    assert_eq!(map.len(), 9);
+    assert_eq!(map.get(&Colliding(5)), Some(&50));
+    assert_eq!(map.get(&Colliding(4)), None);
+    assert_eq!(map.iter().count(), 9);
+  }
+
+  #[test]
+  fn shares_branches() {
+    let map: PersistentMap<u32, u32> = (0..1000).map(|key| (key, key)).collect();
+    let changed = map.insert(0, 1);
+
+    let shared = map.root.children.iter().zip(&changed.root.children).filter(|(a, b)| match (a, b) {
+      (Child::Branch(a), Child::Branch(b)) => Arc::ptr_eq(a, b),
+      _ => false
+    });
+
+    // Only the branch on the path to the key is copied.
+    assert_eq!(shared.count(), map.root.children.len() - 1);
+  }
+
+  #[test]
+  fn snapshots_across_threads() {
+    let map: PersistentMap<u32, u32> = (0..100).map(|key| (key, key)).collect();
+    let snapshot = map.clone();
+    let reader = std::thread::spawn(move || (0..100).map(|key| snapshot.get(&key).unwrap()).sum::<u32>());
+
+    let map = map.insert(0, 1000);
+    assert_eq!(reader.join().unwrap(), 4950);
+    assert_eq!(map.get(&0), Some(&1000));
+  }
+
+  #[test]
+  fn equality_and_debug() {
+    let a: PersistentMap<&str, i32> = [("a", 1), ("b", 2)].into_iter().collect();
+    let b: PersistentMap<&str, i32> = [("b", 2), ("a", 1)].into_iter().collect();
+
+    assert_eq!(a, b);
+    assert_ne!(a, b.insert("a", 3));
+    assert_eq!(format!("{:?}", a.remove("b")), r#"{"a": 1}"#);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: PersistentMap<String, i32> = [("a".to_string(), 1)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"a":1}"#);
+
+    let map: PersistentMap<String, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(map, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    /// Goes back to the version before the given number of operations.
+    Restore(usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      4 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      1 => any::<usize>().prop_map(Operation::Restore)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_hash_map(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut versions = vec![(PersistentMap::new(), HashMap::new())];
+
+      for operation in operations {
+        let (map, mut model) = versions.last().unwrap().clone();
+
+        let next = match operation {
+          Operation::Insert(key, value) => {
+            model.insert(Colliding(key.into()), value);
+            (map.insert(Colliding(key.into()), value), model)
+          }
+          Operation::Remove(key) => {
+            model.remove(&Colliding(key.into()));
+            (map.remove(&Colliding(key.into())), model)
+          }
+          Operation::Restore(back) => versions[versions.len() - 1 - back % versions.len()].clone()
+        };
+
+        versions.push(next);
+      }
+
+      // Every version still holds its own entries.
+      for (map, model) in &versions {
+        prop_assert_eq!(map.len(), model.len());
+        prop_assert_eq!(map.iter().count(), model.len());
+        for (key, value) in model {
+          prop_assert_eq!(map.get(key), Some(value));
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,596 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
+use std::rc::Rc;
+use std::slice;
+
+
+/// Number of index bits consumed by every level of the trie.
+const BITS: usize = 5;
+
+/// Number of children of a branch and of values of a leaf.
+const WIDTH: usize = 1 << BITS;
+
+const MASK: usize = WIDTH - 1;
+
+/// An immutable, persistent vector.
+///
+/// The values are kept in the leaves of a trie whose branches have 32
+/// children, and the last up to 32 values in a separate tail leaf, like
+/// the vectors of Clojure. Operations never modify a vector in place:
+/// `push_back`, `set` and `pop_back` return a new vector that copies only
+/// the nodes on the path to the changed value, O(log n) with a base of
+/// 32, and shares all the others with the original one through reference
+/// counting. Cloning a vector is O(1), which makes it cheap to keep
+/// snapshots of a large sequence.
+///
+/// # Example
+///
+/// ```
+/// use persistent_list::PersistentVector;
+///
+/// let base: PersistentVector<i32> = (0..100).collect();
+/// let changed = base.set(42, -1).push_back(100);
+///
+/// // `base` is left untouched and shares most of its nodes with `changed`.
+/// assert_eq!(base[42], 42);
+/// assert_eq!(changed[42], -1);
+/// assert_eq!(base.len(), 100);
+/// assert_eq!(changed.last(), Some(&100));
+/// assert_eq!(changed.pop_back(), base.set(42, -1));
+/// ```
+pub struct PersistentVector<T> {
+  /// A branch whose leaves are `shift / BITS` levels below it.
+  root: Rc<Node<T>>,
+  /// The leaf holding the last values, outside of the trie so that
+  /// pushing does not walk it until the leaf is full.
+  tail: Rc<Node<T>>,
+  shift: usize,
+  len: usize
+}
+
+/// Represents a node of the trie of a persistent vector, possibly shared
+/// by several vectors.
+#[derive(Clone)]
+enum Node<T> {
+  Branch(Vec<Rc<Node<T>>>),
+  Leaf(Vec<T>)
+}
+
+impl<T> Node<T> {
+  fn children(&self) -> &[Rc<Node<T>>] {
+    match self {
+      Node::Branch(children) => children,
+      Node::Leaf(_) => unreachable!("a leaf has no children")
+    }
+  }
+
+  fn children_mut(&mut self) -> &mut Vec<Rc<Node<T>>> {
+    match self {
+      Node::Branch(children) => children,
+      Node::Leaf(_) => unreachable!("a leaf has no children")
+    }
+  }
+
+  fn values(&self) -> &[T] {
+    match self {
+      Node::Leaf(values) => values,
+      Node::Branch(_) => unreachable!("a branch has no values")
+    }
+  }
+
+  fn values_mut(&mut self) -> &mut Vec<T> {
+    match self {
+      Node::Leaf(values) => values,
+      Node::Branch(_) => unreachable!("a branch has no values")
+    }
+  }
+}
+
+impl<T> Default for PersistentVector<T> {
+  /// Creates a new instance of `PersistentVector` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> PersistentVector<T> {
+  /// Creates a new empty persistent vector.
+  pub fn new() -> Self {
+    Self {
+      root: Rc::new(Node::Branch(Vec::new())),
+      tail: Rc::new(Node::Leaf(Vec::new())),
+      shift: BITS,
+      len: 0
+    }
+  }
+
+  /// Checks if the vector is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the number of elements in the vector.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns a reference to the element at the given position,
+  /// or `None` if the index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    (index < self.len).then(|| &self.leaf(index)[index & MASK])
+  }
+
+  /// Returns a reference to the first element of the vector,
+  /// or `None` if the vector is empty.
+  pub fn first(&self) -> Option<&T> {
+    self.get(0)
+  }
+
+  /// Returns a reference to the last element of the vector,
+  /// or `None` if the vector is empty.
+  pub fn last(&self) -> Option<&T> {
+    self.tail.values().last()
+  }
+
+  /// Returns an iterator over the elements of the vector.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter { vector: self, index: 0, leaf: [].iter() }
+  }
+
+  /// Returns the number of elements kept in the trie, the first index of
+  /// the tail.
+  fn tail_offset(&self) -> usize {
+    self.len - self.tail.values().len()
+  }
+
+  /// Returns the values of the leaf holding the element at `index`, which
+  /// must be in bounds.
+  fn leaf(&self, index: usize) -> &[T] {
+    if index >= self.tail_offset() {
+      return self.tail.values();
+    }
+
+    let mut node = &*self.root;
+    let mut level = self.shift;
+
+    while level > 0 {
+      node = &node.children()[(index >> level) & MASK];
+      level -= BITS;
+    }
+
+    node.values()
+  }
+}
+
+impl<T: Clone> PersistentVector<T> {
+  /// Returns a new vector with the value after the elements of this
+  /// vector, sharing them with it.
+  pub fn push_back(&self, value: T) -> Self {
+    let mut vector = self.clone();
+    vector.push_mut(value);
+    vector
+  }
+
+  /// Returns a new vector with the element at `index` replaced by the
+  /// value, sharing all the other elements with this vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  pub fn set(&self, index: usize, value: T) -> Self {
+    assert!(index < self.len, "index out of bounds");
+
+    let mut vector = self.clone();
+    vector.set_mut(index, value);
+    vector
+  }
+
+  /// Returns the vector without its last element, sharing the remaining
+  /// elements with this vector.
+  ///
+  /// Popping from an empty vector returns an empty vector.
+  pub fn pop_back(&self) -> Self {
+    let mut vector = self.clone();
+    vector.pop_mut();
+    vector
+  }
+
+  /// Pushes a value in place, copying only the nodes shared with other
+  /// vectors.
+  fn push_mut(&mut self, value: T) {
+    if self.tail.values().len() < WIDTH {
+      Rc::make_mut(&mut self.tail).values_mut().push(value);
+      self.len += 1;
+      return;
+    }
+
+    // The tail is full: it moves into the trie and the value starts a new one.
+    let mut values = Vec::with_capacity(WIDTH);
+    values.push(value);
+    let leaf = std::mem::replace(&mut self.tail, Rc::new(Node::Leaf(values)));
+
+    if self.len >> BITS > 1 << self.shift {
+      // The trie is full as well, so it grows by one level.
+      let path = new_path(self.shift, leaf);
+      self.root = Rc::new(Node::Branch(vec![Rc::clone(&self.root), path]));
+      self.shift += BITS;
+    } else {
+      push_leaf(self.len - 1, self.shift, Rc::make_mut(&mut self.root), leaf);
+    }
+
+    self.len += 1;
+  }
+
+  /// Replaces the element at `index`, which must be in bounds, in place,
+  /// copying only the nodes shared with other vectors.
+  fn set_mut(&mut self, index: usize, value: T) {
+    if index >= self.tail_offset() {
+      Rc::make_mut(&mut self.tail).values_mut()[index & MASK] = value;
+      return;
+    }
+
+    let mut node = Rc::make_mut(&mut self.root);
+    let mut level = self.shift;
+
+    while level > 0 {
+      node = Rc::make_mut(&mut node.children_mut()[(index >> level) & MASK]);
+      level -= BITS;
+    }
+
+    node.values_mut()[index & MASK] = value;
+  }
+
+  /// Removes the last element in place, copying only the nodes shared
+  /// with other vectors.
+  fn pop_mut(&mut self) {
+    if self.tail.values().len() > 1 || self.len == 1 {
+      Rc::make_mut(&mut self.tail).values_mut().pop();
+      self.len -= 1;
+      return;
+    }
+
+    if self.len == 0 {
+      return;
+    }
+
+    // The tail holds the last element only: the last leaf of the trie
+    // becomes the new tail.
+    self.tail = pop_leaf(self.shift, Rc::make_mut(&mut self.root));
+    self.len -= 1;
+
+    if self.shift > BITS && self.root.children().len() == 1 {
+      self.root = Rc::clone(&self.root.children()[0]);
+      self.shift -= BITS;
+    }
+  }
+}
+
+/// Returns the leaf wrapped in a chain of single-child branches, so that
+/// it is `level / BITS` levels below the returned node.
+fn new_path<T>(level: usize, leaf: Rc<Node<T>>) -> Rc<Node<T>> {
+  if level == 0 {
+    leaf
+  } else {
+    Rc::new(Node::Branch(vec![new_path(level - BITS, leaf)]))
+  }
+}
+
+/// Adds the leaf after the last leaf below the branch, the leaves being
+/// `level / BITS` levels below it. `index` is the index of the last value
+/// of the leaf, which tells which path leads to it.
+fn push_leaf<T: Clone>(index: usize, level: usize, branch: &mut Node<T>, leaf: Rc<Node<T>>) {
+  let children = branch.children_mut();
+  let position = (index >> level) & MASK;
+
+  if level == BITS {
+    children.push(leaf);
+    return;
+  }
+
+  match children.get_mut(position) {
+    Some(child) => push_leaf(index, level - BITS, Rc::make_mut(child), leaf),
+    None => children.push(new_path(level - BITS, leaf))
+  }
+}
+
+/// Removes the last leaf below the branch and returns it, removing the
+/// branches left empty on the way.
+fn pop_leaf<T: Clone>(level: usize, branch: &mut Node<T>) -> Rc<Node<T>> {
+  let children = branch.children_mut();
+
+  if level == BITS {
+    return children.pop().expect("branches are never empty");
+  }
+
+  let child = children.last_mut().expect("branches are never empty");
+  let leaf = pop_leaf(level - BITS, Rc::make_mut(child));
+
+  if child.children().is_empty() {
+    children.pop();
+  }
+
+  leaf
+}
+
+impl<T> Clone for PersistentVector<T> {
+  /// Returns a vector sharing all the nodes of this one, O(1).
+  fn clone(&self) -> Self {
+    Self { root: Rc::clone(&self.root), tail: Rc::clone(&self.tail), shift: self.shift, len: self.len }
+  }
+}
+
+impl<T> Index<usize> for PersistentVector<T> {
+  type Output = T;
+
+  /// Returns a reference to the element at the given position.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  fn index(&self, index: usize) -> &Self::Output {
+    self.get(index).expect("index out of bounds")
+  }
+}
+
+/// An iterator over the elements of a `PersistentVector`.
+pub struct Iter<'a, T> {
+  vector: &'a PersistentVector<T>,
+  /// Index of the first element after the current leaf.
+  index: usize,
+  leaf: slice::Iter<'a, T>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  /// Returns the next element of the current leaf, moving to the next
+  /// leaf once it is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Some(value) = self.leaf.next() {
+      return Some(value);
+    }
+
+    if self.index >= self.vector.len {
+      return None;
+    }
+
+    let values = self.vector.leaf(self.index);
+    self.index += values.len();
+    self.leaf = values.iter();
+    self.leaf.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.vector.len - self.index + self.leaf.len();
+    (len, Some(len))
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a PersistentVector<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T: Clone> FromIterator<T> for PersistentVector<T> {
+  /// Creates a persistent vector from an iterator, preserving the order
+  /// of the elements.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut vector = Self::new();
+
+    // The nodes are not shared yet, so pushing in place never copies them.
+    for value in iter {
+      vector.push_mut(value);
+    }
+
+    vector
+  }
+}
+
+impl<T: PartialEq> PartialEq for PersistentVector<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for PersistentVector<T> {}
+
+impl<T: Hash> Hash for PersistentVector<T> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.len.hash(state);
+    for value in self.iter() {
+      value.hash(state);
+    }
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentVector<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for PersistentVector<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the first to the last.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for PersistentVector<T>
+where
+  T: serde::Deserialize<'de> + Clone,
+{
+  /// Deserializes a vector from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|values| values.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use super::{PersistentVector, WIDTH};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_vector() {
+    let vector = PersistentVector::<i32>::new();
+
+    assert!(vector.is_empty());
+    assert_eq!(vector.get(0), None);
+    assert_eq!(vector.last(), None);
+    assert!(vector.pop_back().is_empty());
+  }
+
+  #[test]
+  fn push_and_get() {
+    let sizes = [1, WIDTH, WIDTH + 1, WIDTH * WIDTH, WIDTH * WIDTH + WIDTH + 1, 40_000];
+
+    for len in sizes {
+      let vector: PersistentVector<usize> = (0..len).collect();
+
+      assert_eq!(vector.len(), len);
+      assert_eq!(vector.first(), Some(&0));
+      assert_eq!(vector.last(), Some(&(len - 1)));
+      assert_eq!(vector.get(len), None);
+      assert!((0..len).all(|index| vector[index] == index));
+      assert!(vector.iter().copied().eq(0..len));
+      assert_eq!(vector.iter().len(), len);
+    }
+  }
+
+  #[test]
+  fn set() {
+    let base: PersistentVector<usize> = (0..2000).collect();
+    let mut vector = base.clone();
+
+    for index in (0..2000).step_by(7) {
+      vector = vector.set(index, index * 10);
+    }
+
+    assert!((0..2000).all(|index| base[index] == index));
+    assert!((0..2000).all(|index| vector[index] == if index % 7 == 0 { index * 10 } else { index }));
+  }
+
+  #[test]
+  #[should_panic(expected = "index out of bounds")]
+  fn set_out_of_bounds() {
+    PersistentVector::<i32>::new().push_back(1).set(1, 2);
+  }
+
+  #[test]
+  fn pop_back() {
+    let len = WIDTH * WIDTH + 2 * WIDTH + 3;
+    let mut vector: PersistentVector<usize> = (0..len).collect();
+    let base = vector.clone();
+
+    for remaining in (0..len).rev() {
+      vector = vector.pop_back();
+      assert_eq!(vector.len(), remaining);
+      assert_eq!(vector.last(), remaining.checked_sub(1).as_ref());
+    }
+
+    assert_eq!(vector, PersistentVector::new());
+    assert_eq!(vector.push_back(7)[0], 7);
+    assert!(base.iter().copied().eq(0..len));
+  }
+
+  #[test]
+  fn shares_nodes() {
+    let base: PersistentVector<i32> = (0..100).collect();
+    let tail_changed = base.set(99, -1);
+    let root_changed = base.set(0, -1);
+
+    assert!(Rc::ptr_eq(&base.root, &tail_changed.root));
+    assert!(Rc::ptr_eq(&base.tail, &root_changed.tail));
+
+    // Only the first leaf is copied.
+    let (base_leaves, changed_leaves) = (base.root.children(), root_changed.root.children());
+    assert!(!Rc::ptr_eq(&base_leaves[0], &changed_leaves[0]));
+    assert!(Rc::ptr_eq(&base_leaves[1], &changed_leaves[1]));
+  }
+
+  #[test]
+  fn debug() {
+    let vector: PersistentVector<i32> = (1..=3).collect();
+
+    assert_eq!(format!("{:?}", vector), "[1, 2, 3]");
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: PersistentVector<i32> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let vector: PersistentVector<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(vector, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop,
+    Set(usize, i32),
+    /// Goes back to the version before the given number of operations.
+    Restore(usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      4 => any::<i32>().prop_map(Operation::Push),
+      1 => Just(Operation::Pop),
+      2 => (any::<usize>(), any::<i32>()).prop_map(|(index, value)| Operation::Set(index, value)),
+      1 => any::<usize>().prop_map(Operation::Restore)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_vec(operations in prop::collection::vec(operation(), 0..2000)) {
+      let mut versions = vec![(PersistentVector::new(), Vec::new())];
+
+      for operation in operations {
+        let (vector, model) = versions.last().unwrap().clone();
+
+        let next = match operation {
+          Operation::Push(value) => (vector.push_back(value), [model, vec![value]].concat()),
+          Operation::Pop => (vector.pop_back(), model[..model.len().saturating_sub(1)].to_vec()),
+          Operation::Set(_, _) if model.is_empty() => continue,
+          Operation::Set(index, value) => {
+            let index = index % model.len();
+            let mut model = model;
+            model[index] = value;
+            (vector.set(index, value), model)
+          }
+          Operation::Restore(back) => versions[versions.len() - 1 - back % versions.len()].clone()
+        };
+
+        versions.push(next);
+      }
+
+      // Every version still holds its own elements.
+      for (vector, model) in &versions {
+        prop_assert_eq!(vector.len(), model.len());
+        prop_assert!(vector.iter().eq(model.iter()));
+      }
+    }
+  }
+}
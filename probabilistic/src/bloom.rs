@@ -0,0 +1,333 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::hash::{hash_pair, indices};
+use crate::membership::{ApproximateMembership, FilterFullError};
+
+
+/// A Bloom filter, a set that can tell that an item was certainly not
+/// inserted or was probably inserted.
+///
+/// The filter is an array of bits. Inserting an item sets the bits at `k`
+/// positions derived from its hash, and an item is reported as present
+/// only if all of its bits are set. An inserted item is therefore always
+/// found, but the bits of an item that was never inserted may have been set
+/// by other items, which happens with a probability that grows with the
+/// number of items. Items can not be removed.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::BloomFilter;
+///
+/// let mut filter = BloomFilter::new(1000, 0.01);
+/// filter.insert("apple");
+/// filter.insert("banana");
+///
+/// assert!(filter.contains("apple"));
+/// assert!(!filter.contains("cherry"));
+/// ```
+pub struct BloomFilter<T: ?Sized> {
+  words: Vec<u64>,
+  /// Number of bits of the filter, `words` may hold a few more.
+  bits: usize,
+  hashes: u32,
+  /// The filter does not own items, only their hashes.
+  items: PhantomData<fn(&T)>
+}
+
+/// The error returned by `BloomFilter::union` when the filters have a
+/// different number of bits or hash functions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IncompatibleFiltersError;
+
+impl fmt::Display for IncompatibleFiltersError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "filters have different parameters")
+  }
+}
+
+impl std::error::Error for IncompatibleFiltersError {}
+
+impl<T: Hash + ?Sized> BloomFilter<T> {
+  /// Creates a new empty filter sized to keep the probability of false
+  /// positives at `false_positive_rate` once `expected_items` items are
+  /// inserted.
+  ///
+  /// The filter uses `m = -n ln(p) / ln(2)^2` bits and `k = m / n ln(2)`
+  /// hash functions, which is optimal for `n` items.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `expected_items` is zero or `false_positive_rate` is not
+  /// between 0 and 1 exclusive.
+  pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+    let (bits, hashes) = optimal_size(expected_items, false_positive_rate);
+    Self::with_size(bits, hashes)
+  }
+
+  /// Creates a new empty filter with `bits` bits and `hashes` hash
+  /// functions.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `bits` or `hashes` is zero.
+  pub fn with_size(bits: usize, hashes: u32) -> Self {
+    assert!(bits > 0, "number of bits must be positive");
+    assert!(hashes > 0, "number of hashes must be positive");
+
+    Self {
+      words: vec![0; bits.div_ceil(64)],
+      bits,
+      hashes,
+      items: PhantomData
+    }
+  }
+
+  /// Returns the number of bits of the filter.
+  pub fn bits(&self) -> usize {
+    self.bits
+  }
+
+  /// Returns the number of hash functions of the filter.
+  pub fn hashes(&self) -> u32 {
+    self.hashes
+  }
+
+  /// Checks if no item was inserted into the filter.
+  pub fn is_empty(&self) -> bool {
+    self.words.iter().all(|&word| word == 0)
+  }
+
+  /// Adds an item to the filter.
+  pub fn insert(&mut self, item: &T) {
+    for index in indices(hash_pair(item), self.hashes, self.bits) {
+      self.words[index / 64] |= 1 << (index % 64);
+    }
+  }
+
+  /// Checks if the item may have been inserted into the filter.
+  ///
+  /// Returns `false` only if the item was certainly not inserted.
+  pub fn contains(&self, item: &T) -> bool {
+    indices(hash_pair(item), self.hashes, self.bits).all(|index| self.words[index / 64] & (1 << (index % 64)) != 0)
+  }
+
+  /// Returns the probability of a false positive given the fraction of bits
+  /// set in the filter.
+  pub fn false_positive_rate(&self) -> f64 {
+    let set: u32 = self.words.iter().map(|word| word.count_ones()).sum();
+    (f64::from(set) / self.bits as f64).powi(self.hashes as i32)
+  }
+
+  /// Adds all items inserted into another filter to this filter.
+  ///
+  /// Both filters must have the same number of bits and hash functions,
+  /// otherwise an error is returned and this filter is left unchanged.
+  pub fn union(&mut self, other: &Self) -> Result<(), IncompatibleFiltersError> {
+    if self.bits != other.bits || self.hashes != other.hashes {
+      return Err(IncompatibleFiltersError);
+    }
+
+    for (word, other) in self.words.iter_mut().zip(&other.words) {
+      *word |= other;
+    }
+
+    Ok(())
+  }
+
+  /// Removes all items from the filter.
+  pub fn clear(&mut self) {
+    self.words.fill(0);
+  }
+}
+
+impl<T: Hash + ?Sized> ApproximateMembership<T> for BloomFilter<T> {
+  /// Adds an item to the filter, which never runs out of room.
+  fn insert(&mut self, item: &T) -> Result<(), FilterFullError> {
+    BloomFilter::insert(self, item);
+    Ok(())
+  }
+
+  fn contains(&self, item: &T) -> bool {
+    BloomFilter::contains(self, item)
+  }
+
+  fn clear(&mut self) {
+    BloomFilter::clear(self);
+  }
+}
+
+/// Returns the number of bits and hash functions of a Bloom filter that
+/// keeps the probability of false positives at `false_positive_rate` once
+/// `expected_items` items are inserted.
+///
+/// # Panics
+///
+/// Panics if `expected_items` is zero or `false_positive_rate` is not
+/// between 0 and 1 exclusive.
+pub(crate) fn optimal_size(expected_items: usize, false_positive_rate: f64) -> (usize, u32) {
+  assert!(expected_items > 0, "expected items must be positive");
+  assert!(
+    false_positive_rate > 0.0 && false_positive_rate < 1.0,
+    "false positive rate must be between 0 and 1"
+  );
+
+  let items = expected_items as f64;
+  let bits = (-items * false_positive_rate.ln() / std::f64::consts::LN_2.powi(2)).ceil();
+  let hashes = (bits / items * std::f64::consts::LN_2).round().max(1.0);
+
+  (bits as usize, hashes as u32)
+}
+
+impl<T: ?Sized> Clone for BloomFilter<T> {
+  fn clone(&self) -> Self {
+    Self {
+      words: self.words.clone(),
+      bits: self.bits,
+      hashes: self.hashes,
+      items: PhantomData
+    }
+  }
+}
+
+impl<T: ?Sized> PartialEq for BloomFilter<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.bits == other.bits && self.hashes == other.hashes && self.words == other.words
+  }
+}
+
+impl<T: ?Sized> Eq for BloomFilter<T> {}
+
+impl<T: ?Sized> fmt::Debug for BloomFilter<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("BloomFilter")
+      .field("bits", &self.bits)
+      .field("hashes", &self.hashes)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> serde::Serialize for BloomFilter<T> {
+  /// Serializes the number of bits and hash functions together with the
+  /// bit array as 64-bit words.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(self.bits, self.hashes, &self.words), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized> serde::Deserialize<'de> for BloomFilter<T> {
+  /// Deserializes the number of bits and hash functions and the bit array
+  /// of a filter.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (bits, hashes, words): (usize, u32, Vec<u64>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if bits == 0 || hashes == 0 {
+      return Err(D::Error::custom("number of bits and hashes must be positive"));
+    }
+    if words.len() != bits.div_ceil(64) {
+      return Err(D::Error::custom("number of words does not match the number of bits"));
+    }
+
+    Ok(Self { words, bits, hashes, items: PhantomData })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{BloomFilter, IncompatibleFiltersError};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_filter() {
+    let filter = BloomFilter::<str>::new(100, 0.01);
+
+    assert!(filter.is_empty());
+    assert!(!filter.contains("apple"));
+    assert_eq!(filter.false_positive_rate(), 0.0);
+  }
+
+  #[test]
+  fn derived_parameters() {
+    let filter = BloomFilter::<u32>::new(1000, 0.01);
+
+    assert_eq!(filter.bits(), 9586);
+    assert_eq!(filter.hashes(), 7);
+  }
+
+  #[test]
+  fn false_positive_rate() {
+    let mut filter = BloomFilter::new(1000, 0.01);
+    for i in 0..1000 {
+      filter.insert(&i);
+    }
+
+    let false_positives = (1000..101_000).filter(|i| filter.contains(i)).count();
+    assert!(false_positives < 2000, "{false_positives} false positives");
+    assert!(filter.false_positive_rate() < 0.02);
+  }
+
+  #[test]
+  fn union() {
+    let mut first = BloomFilter::new(100, 0.01);
+    let mut second = BloomFilter::new(100, 0.01);
+    first.insert("apple");
+    second.insert("banana");
+
+    assert_eq!(first.union(&second), Ok(()));
+    assert!(first.contains("apple"));
+    assert!(first.contains("banana"));
+
+    assert_eq!(first.union(&BloomFilter::new(100, 0.1)), Err(IncompatibleFiltersError));
+  }
+
+  #[test]
+  fn clear() {
+    let mut filter = BloomFilter::new(10, 0.1);
+    filter.insert(&42);
+    filter.clear();
+
+    assert!(filter.is_empty());
+    assert!(!filter.contains(&42));
+  }
+
+  #[test]
+  #[should_panic(expected = "false positive rate must be between 0 and 1")]
+  fn invalid_false_positive_rate() {
+    BloomFilter::<u32>::new(10, 1.0);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = BloomFilter::with_size(70, 3);
+    value.insert("apple");
+
+    let json = serde_json::to_string(&value).unwrap();
+    let filter: BloomFilter<str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(filter, value);
+    assert!(filter.contains("apple"));
+
+    assert!(serde_json::from_str::<BloomFilter<str>>("[70,3,[0]]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn no_false_negatives(items in prop::collection::vec(any::<u64>(), 0..200), bits in 1..2000usize, hashes in 1..8u32) {
+      let mut filter = BloomFilter::with_size(bits, hashes);
+      for item in &items {
+        filter.insert(item);
+      }
+
+      for item in &items {
+        prop_assert!(filter.contains(item));
+      }
+    }
+  }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::hash::{hash_pair, indices};
+use crate::hyperloglog::IncompatibleSketchesError;
+
+
+/// A Count-Min sketch, which estimates how many times every item was
+/// counted in a fixed amount of memory.
+///
+/// The sketch is a table of `depth` rows of `width` counters, and every
+/// row maps an item to one of its counters with a different hash function.
+/// Counting an item increments its counter in every row. Other items may
+/// share these counters, so each of them can only overestimate the count
+/// of the item, and the smallest one is the estimate. With a width of
+/// `e / ε` and a depth of `ln(1 / δ)`, an estimate exceeds the actual count
+/// by more than `ε` times the total of all counts with a probability of at
+/// most `δ`.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::CountMinSketch;
+///
+/// let mut sketch = CountMinSketch::with_error(0.001, 0.01);
+/// for word in "the cat saw the dog and the bird".split(' ') {
+///   sketch.increment(word);
+/// }
+///
+/// assert_eq!(sketch.estimate("the"), 3);
+/// assert_eq!(sketch.estimate("cat"), 1);
+/// assert_eq!(sketch.total(), 8);
+/// ```
+pub struct CountMinSketch<T: ?Sized> {
+  /// Counters of every row, one after the other.
+  counters: Vec<u64>,
+  width: usize,
+  depth: u32,
+  total: u64,
+  /// The sketch does not own items, only their hashes.
+  items: PhantomData<fn(&T)>
+}
+
+impl<T: Hash + ?Sized> CountMinSketch<T> {
+  /// Creates a new empty sketch with `depth` rows of `width` counters.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the width or the depth is zero.
+  pub fn new(width: usize, depth: u32) -> Self {
+    assert!(width > 0, "width must be positive");
+    assert!(depth > 0, "depth must be positive");
+
+    Self {
+      counters: vec![0; width * depth as usize],
+      width,
+      depth,
+      total: 0,
+      items: PhantomData
+    }
+  }
+
+  /// Creates a new empty sketch whose estimates exceed the actual counts
+  /// by more than `epsilon` times the total of all counts with a
+  /// probability of at most `delta`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `epsilon` or `delta` is not between 0 and 1 exclusive.
+  pub fn with_error(epsilon: f64, delta: f64) -> Self {
+    assert!(epsilon > 0.0 && epsilon < 1.0, "epsilon must be between 0 and 1");
+    assert!(delta > 0.0 && delta < 1.0, "delta must be between 0 and 1");
+
+    let width = (std::f64::consts::E / epsilon).ceil();
+    let depth = (1.0 / delta).ln().ceil().max(1.0);
+    Self::new(width as usize, depth as u32)
+  }
+
+  /// Returns the number of counters in every row.
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  /// Returns the number of rows.
+  pub fn depth(&self) -> u32 {
+    self.depth
+  }
+
+  /// Returns the total of all counts.
+  pub fn total(&self) -> u64 {
+    self.total
+  }
+
+  /// Checks if nothing was counted.
+  pub fn is_empty(&self) -> bool {
+    self.total == 0
+  }
+
+  /// Counts an item once.
+  pub fn increment(&mut self, item: &T) {
+    self.add(item, 1);
+  }
+
+  /// Counts an item `count` times.
+  pub fn add(&mut self, item: &T, count: u64) {
+    for (row, index) in indices(hash_pair(item), self.depth, self.width).enumerate() {
+      let counter = &mut self.counters[row * self.width + index];
+      *counter = counter.saturating_add(count);
+    }
+
+    self.total = self.total.saturating_add(count);
+  }
+
+  /// Returns the estimated number of times the item was counted, which is
+  /// never less than the actual count.
+  pub fn estimate(&self, item: &T) -> u64 {
+    indices(hash_pair(item), self.depth, self.width)
+      .enumerate()
+      .map(|(row, index)| self.counters[row * self.width + index])
+      .min()
+      .expect("sketch has no rows")
+  }
+
+  /// Adds all counts of another sketch to this sketch.
+  ///
+  /// Both sketches must have the same width and depth, otherwise an error
+  /// is returned and this sketch is left unchanged.
+  pub fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketchesError> {
+    if self.width != other.width || self.depth != other.depth {
+      return Err(IncompatibleSketchesError);
+    }
+
+    for (counter, &other) in self.counters.iter_mut().zip(&other.counters) {
+      *counter = counter.saturating_add(other);
+    }
+    self.total = self.total.saturating_add(other.total);
+
+    Ok(())
+  }
+
+  /// Resets all counts to zero.
+  pub fn clear(&mut self) {
+    self.counters.fill(0);
+    self.total = 0;
+  }
+}
+
+impl<T: ?Sized> Clone for CountMinSketch<T> {
+  fn clone(&self) -> Self {
+    Self {
+      counters: self.counters.clone(),
+      width: self.width,
+      depth: self.depth,
+      total: self.total,
+      items: PhantomData
+    }
+  }
+}
+
+impl<T: ?Sized> PartialEq for CountMinSketch<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.width == other.width && self.total == other.total && self.counters == other.counters
+  }
+}
+
+impl<T: ?Sized> Eq for CountMinSketch<T> {}
+
+impl<T: ?Sized> fmt::Debug for CountMinSketch<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CountMinSketch")
+      .field("width", &self.width)
+      .field("depth", &self.depth)
+      .field("total", &self.total)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> serde::Serialize for CountMinSketch<T> {
+  /// Serializes the width and the total together with the counters of
+  /// every row.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(self.width, self.total, &self.counters), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized> serde::Deserialize<'de> for CountMinSketch<T> {
+  /// Deserializes the width, the total and the counters of a sketch.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (width, total, counters): (usize, u64, Vec<u64>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if width == 0 || counters.is_empty() || counters.len() % width != 0 {
+      return Err(D::Error::custom("number of counters must be a positive multiple of the width"));
+    }
+    let depth = u32::try_from(counters.len() / width).map_err(D::Error::custom)?;
+
+    Ok(Self { counters, width, depth, total, items: PhantomData })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{CountMinSketch, IncompatibleSketchesError};
+  use std::collections::HashMap;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_sketch() {
+    let sketch = CountMinSketch::<str>::new(10, 3);
+
+    assert!(sketch.is_empty());
+    assert_eq!(sketch.estimate("apple"), 0);
+  }
+
+  #[test]
+  fn derived_dimensions() {
+    let sketch = CountMinSketch::<u32>::with_error(0.01, 0.001);
+
+    assert_eq!(sketch.width(), 272);
+    assert_eq!(sketch.depth(), 7);
+  }
+
+  #[test]
+  fn error_bound() {
+    let (epsilon, delta) = (0.01, 0.01);
+    let mut sketch = CountMinSketch::with_error(epsilon, delta);
+    let mut counts = HashMap::new();
+
+    // A skewed distribution: item `i` is counted about `1000 / i` times.
+    for i in 1..=1000u64 {
+      let count = 1000 / i;
+      sketch.add(&i, count);
+      counts.insert(i, count);
+    }
+
+    let bound = (epsilon * sketch.total() as f64) as u64;
+    let mut exceeding = 0;
+    for (item, &count) in &counts {
+      let estimate = sketch.estimate(item);
+      assert!(estimate >= count);
+      if estimate - count > bound {
+        exceeding += 1;
+      }
+    }
+
+    // At most a fraction `delta` of the items is expected to exceed the
+    // bound, allow three times as many before failing.
+    assert!(exceeding as f64 <= 3.0 * delta * counts.len() as f64, "{exceeding} estimates exceed the bound");
+  }
+
+  #[test]
+  fn merge() {
+    let mut first = CountMinSketch::new(100, 4);
+    let mut second = CountMinSketch::new(100, 4);
+    first.add("apple", 3);
+    second.add("apple", 2);
+    second.increment("banana");
+
+    assert_eq!(first.merge(&second), Ok(()));
+    assert_eq!(first.estimate("apple"), 5);
+    assert_eq!(first.estimate("banana"), 1);
+    assert_eq!(first.total(), 6);
+
+    assert_eq!(first.merge(&CountMinSketch::new(100, 3)), Err(IncompatibleSketchesError));
+  }
+
+  #[test]
+  fn clear() {
+    let mut sketch = CountMinSketch::new(10, 2);
+    sketch.add(&1, 5);
+    sketch.clear();
+
+    assert!(sketch.is_empty());
+    assert_eq!(sketch.estimate(&1), 0);
+  }
+
+  #[test]
+  #[should_panic(expected = "width must be positive")]
+  fn zero_width() {
+    CountMinSketch::<u32>::new(0, 1);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = CountMinSketch::new(4, 2);
+    value.add("apple", 3);
+
+    let json = serde_json::to_string(&value).unwrap();
+    let sketch: CountMinSketch<str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sketch, value);
+    assert_eq!(sketch.depth(), 2);
+    assert_eq!(sketch.estimate("apple"), 3);
+
+    assert!(serde_json::from_str::<CountMinSketch<str>>("[4,0,[0,0,0]]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn never_underestimates(items in prop::collection::vec(0..50u32, 0..300), width in 1..40usize, depth in 1..5u32) {
+      let mut sketch = CountMinSketch::new(width, depth);
+      let mut counts = HashMap::new();
+      for item in &items {
+        sketch.increment(item);
+        *counts.entry(*item).or_insert(0) += 1;
+      }
+
+      prop_assert_eq!(sketch.total(), items.len() as u64);
+      for (item, count) in counts {
+        prop_assert!(sketch.estimate(&item) >= count);
+      }
+    }
+  }
+}
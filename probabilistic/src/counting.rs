@@ -0,0 +1,279 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::bloom::optimal_size;
+use crate::hash::{hash_pair, indices};
+use crate::membership::{ApproximateMembership, FilterFullError};
+
+
+/// A counting Bloom filter, a Bloom filter that supports removing items.
+///
+/// Every bit of a `BloomFilter` is replaced by an 8-bit counter: inserting
+/// an item increments its `k` counters, removing it decrements them, and
+/// an item is reported as present only if none of its counters is zero.
+/// A counter that reaches its maximum sticks to it, since its exact count
+/// is lost, which only happens with hundreds of items sharing a counter.
+///
+/// The filter takes 8 times the memory of a `BloomFilter` with the same
+/// false positive rate.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::CountingBloomFilter;
+///
+/// let mut filter = CountingBloomFilter::new(1000, 0.01);
+/// filter.insert("apple");
+/// filter.insert("banana");
+///
+/// assert!(filter.remove("apple"));
+/// assert!(!filter.contains("apple"));
+/// assert!(filter.contains("banana"));
+/// ```
+pub struct CountingBloomFilter<T: ?Sized> {
+  counters: Vec<u8>,
+  hashes: u32,
+  /// The filter does not own items, only their hashes.
+  items: PhantomData<fn(&T)>
+}
+
+impl<T: Hash + ?Sized> CountingBloomFilter<T> {
+  /// Creates a new empty filter sized to keep the probability of false
+  /// positives at `false_positive_rate` once `expected_items` items are
+  /// inserted, with as many counters as a `BloomFilter` has bits.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `expected_items` is zero or `false_positive_rate` is not
+  /// between 0 and 1 exclusive.
+  pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+    let (counters, hashes) = optimal_size(expected_items, false_positive_rate);
+    Self::with_size(counters, hashes)
+  }
+
+  /// Creates a new empty filter with `counters` counters and `hashes` hash
+  /// functions.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `counters` or `hashes` is zero.
+  pub fn with_size(counters: usize, hashes: u32) -> Self {
+    assert!(counters > 0, "number of counters must be positive");
+    assert!(hashes > 0, "number of hashes must be positive");
+
+    Self {
+      counters: vec![0; counters],
+      hashes,
+      items: PhantomData
+    }
+  }
+
+  /// Returns the number of counters of the filter.
+  pub fn counters(&self) -> usize {
+    self.counters.len()
+  }
+
+  /// Returns the number of hash functions of the filter.
+  pub fn hashes(&self) -> u32 {
+    self.hashes
+  }
+
+  /// Checks if no item is in the filter.
+  pub fn is_empty(&self) -> bool {
+    self.counters.iter().all(|&counter| counter == 0)
+  }
+
+  /// Adds an item to the filter.
+  pub fn insert(&mut self, item: &T) {
+    for index in indices(hash_pair(item), self.hashes, self.counters.len()) {
+      self.counters[index] = self.counters[index].saturating_add(1);
+    }
+  }
+
+  /// Checks if the item may be in the filter.
+  ///
+  /// Returns `false` only if the item is certainly not in the filter.
+  pub fn contains(&self, item: &T) -> bool {
+    indices(hash_pair(item), self.hashes, self.counters.len()).all(|index| self.counters[index] > 0)
+  }
+
+  /// Removes an item from the filter. Returns `false` and leaves the
+  /// filter unchanged if the item is certainly not in the filter.
+  ///
+  /// Removing an item that was never inserted but is reported as present
+  /// removes another item instead, which may then be reported as absent.
+  pub fn remove(&mut self, item: &T) -> bool {
+    if !self.contains(item) {
+      return false;
+    }
+
+    for index in indices(hash_pair(item), self.hashes, self.counters.len()) {
+      if self.counters[index] < u8::MAX {
+        self.counters[index] -= 1;
+      }
+    }
+
+    true
+  }
+
+  /// Removes all items from the filter.
+  pub fn clear(&mut self) {
+    self.counters.fill(0);
+  }
+}
+
+impl<T: Hash + ?Sized> ApproximateMembership<T> for CountingBloomFilter<T> {
+  /// Adds an item to the filter, which never runs out of room.
+  fn insert(&mut self, item: &T) -> Result<(), FilterFullError> {
+    CountingBloomFilter::insert(self, item);
+    Ok(())
+  }
+
+  fn contains(&self, item: &T) -> bool {
+    CountingBloomFilter::contains(self, item)
+  }
+
+  fn clear(&mut self) {
+    CountingBloomFilter::clear(self);
+  }
+}
+
+impl<T: ?Sized> Clone for CountingBloomFilter<T> {
+  fn clone(&self) -> Self {
+    Self {
+      counters: self.counters.clone(),
+      hashes: self.hashes,
+      items: PhantomData
+    }
+  }
+}
+
+impl<T: ?Sized> PartialEq for CountingBloomFilter<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.hashes == other.hashes && self.counters == other.counters
+  }
+}
+
+impl<T: ?Sized> Eq for CountingBloomFilter<T> {}
+
+impl<T: ?Sized> fmt::Debug for CountingBloomFilter<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CountingBloomFilter")
+      .field("counters", &self.counters.len())
+      .field("hashes", &self.hashes)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> serde::Serialize for CountingBloomFilter<T> {
+  /// Serializes the number of hash functions together with the counters.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(self.hashes, &self.counters), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized> serde::Deserialize<'de> for CountingBloomFilter<T> {
+  /// Deserializes the number of hash functions and the counters of a
+  /// filter.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (hashes, counters): (u32, Vec<u8>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if hashes == 0 || counters.is_empty() {
+      return Err(D::Error::custom("number of counters and hashes must be positive"));
+    }
+
+    Ok(Self { counters, hashes, items: PhantomData })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::CountingBloomFilter;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_filter() {
+    let mut filter = CountingBloomFilter::<str>::new(100, 0.01);
+
+    assert!(filter.is_empty());
+    assert!(!filter.contains("apple"));
+    assert!(!filter.remove("apple"));
+  }
+
+  #[test]
+  fn insert_and_remove() {
+    let mut filter = CountingBloomFilter::new(100, 0.01);
+    filter.insert(&1);
+    filter.insert(&2);
+    filter.insert(&2);
+
+    assert!(filter.remove(&2));
+    assert!(filter.contains(&2));
+    assert!(filter.remove(&2));
+    assert!(!filter.contains(&2));
+    assert!(filter.contains(&1));
+  }
+
+  #[test]
+  fn saturated_counters_stick() {
+    let mut filter = CountingBloomFilter::with_size(1, 1);
+    for _ in 0..300 {
+      filter.insert(&7);
+    }
+    for _ in 0..300 {
+      filter.remove(&7);
+    }
+
+    assert!(filter.contains(&7));
+  }
+
+  #[test]
+  fn clear() {
+    let mut filter = CountingBloomFilter::new(10, 0.1);
+    filter.insert(&42);
+    filter.clear();
+
+    assert!(filter.is_empty());
+    assert_eq!(filter.counters(), 48);
+    assert_eq!(filter.hashes(), 3);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = CountingBloomFilter::with_size(8, 2);
+    value.insert("apple");
+
+    let json = serde_json::to_string(&value).unwrap();
+    let filter: CountingBloomFilter<str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(filter, value);
+    assert!(filter.contains("apple"));
+
+    assert!(serde_json::from_str::<CountingBloomFilter<str>>("[0,[0]]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn removed_items_leave_the_others(items in prop::collection::vec(any::<u64>(), 0..100), removed in 0..100usize) {
+      let mut filter = CountingBloomFilter::with_size(2000, 4);
+      for item in &items {
+        filter.insert(item);
+      }
+
+      let removed = removed.min(items.len());
+      for item in &items[..removed] {
+        prop_assert!(filter.remove(item));
+      }
+
+      for item in &items[removed..] {
+        prop_assert!(filter.contains(item));
+      }
+    }
+  }
+}
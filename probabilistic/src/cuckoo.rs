@@ -0,0 +1,387 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::hash::{hash_pair, mix};
+use crate::membership::{ApproximateMembership, FilterFullError};
+
+
+/// Number of fingerprints stored in a bucket.
+const BUCKET_SIZE: usize = 4;
+
+/// Number of fingerprints moved around before an insertion gives up.
+const MAX_KICKS: usize = 500;
+
+/// A fingerprint, where 0 marks an empty slot.
+type Fingerprint = u16;
+
+/// A cuckoo filter, a set that can tell that an item is certainly not
+/// present or is probably present, and supports removing items.
+///
+/// The filter stores a 16-bit fingerprint of every item in one of two
+/// candidate buckets. The second bucket can be computed from the first one
+/// and the fingerprint alone, so when both buckets of a new item are full a
+/// stored fingerprint is kicked out to its other bucket, like in cuckoo
+/// hashing. An item is reported as present if one of its buckets holds its
+/// fingerprint, which happens for an absent item with a probability of
+/// about 0.012%.
+///
+/// The filter takes about 16 bits per item, less than a `BloomFilter` for
+/// false positive rates below 0.5%, and unlike a `CountingBloomFilter` it
+/// does so while supporting removals. It can be filled to about 95% of its
+/// capacity before insertions start failing.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::CuckooFilter;
+///
+/// let mut filter = CuckooFilter::new(1000);
+/// filter.insert("apple").unwrap();
+/// filter.insert("banana").unwrap();
+///
+/// assert!(filter.remove("apple"));
+/// assert!(!filter.contains("apple"));
+/// assert!(filter.contains("banana"));
+/// ```
+pub struct CuckooFilter<T: ?Sized> {
+  /// Fingerprints of every bucket, one after the other.
+  slots: Vec<Fingerprint>,
+  /// Number of buckets minus one, the number of buckets is a power of two.
+  mask: usize,
+  /// A fingerprint and its bucket that did not fit after the last failed
+  /// series of kicks, which would otherwise be lost.
+  victim: Option<(usize, Fingerprint)>,
+  len: usize,
+  /// Counts the kicks to choose which fingerprint to kick out next.
+  kicks: u64,
+  /// The filter does not own items, only their fingerprints.
+  items: PhantomData<fn(&T)>
+}
+
+impl<T: Hash + ?Sized> CuckooFilter<T> {
+  /// Creates a new empty filter with room for at least `capacity` items.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the capacity is zero.
+  pub fn new(capacity: usize) -> Self {
+    assert!(capacity > 0, "capacity must be positive");
+
+    let buckets = capacity.div_ceil(BUCKET_SIZE).next_power_of_two();
+    Self {
+      slots: vec![0; buckets * BUCKET_SIZE],
+      mask: buckets - 1,
+      victim: None,
+      len: 0,
+      kicks: 0,
+      items: PhantomData
+    }
+  }
+
+  /// Returns the number of items in the filter.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the filter is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the number of fingerprints the filter has room for.
+  pub fn capacity(&self) -> usize {
+    self.slots.len()
+  }
+
+  /// Adds an item to the filter.
+  ///
+  /// Inserting an item several times stores several fingerprints, which
+  /// takes as many removals to remove it. Returns an error if the filter
+  /// is too full to take the item, in which case it is left unchanged.
+  pub fn insert(&mut self, item: &T) -> Result<(), FilterFullError> {
+    if self.victim.is_some() {
+      return Err(FilterFullError);
+    }
+
+    let (bucket, fingerprint) = self.locate(item);
+    let alternate = self.alternate(bucket, fingerprint);
+    self.len += 1;
+
+    if self.store(bucket, fingerprint) || self.store(alternate, fingerprint) {
+      return Ok(());
+    }
+
+    let mut bucket = if self.kicks.is_multiple_of(2) { bucket } else { alternate };
+    let mut fingerprint = fingerprint;
+    for _ in 0..MAX_KICKS {
+      let slot = bucket * BUCKET_SIZE + (mix(self.kicks) % BUCKET_SIZE as u64) as usize;
+      self.kicks += 1;
+
+      fingerprint = std::mem::replace(&mut self.slots[slot], fingerprint);
+      bucket = self.alternate(bucket, fingerprint);
+      if self.store(bucket, fingerprint) {
+        return Ok(());
+      }
+    }
+
+    // The new item is stored, but another one got kicked out of the table.
+    self.victim = Some((bucket, fingerprint));
+    Ok(())
+  }
+
+  /// Checks if the item may be in the filter.
+  ///
+  /// Returns `false` only if the item is certainly not in the filter.
+  pub fn contains(&self, item: &T) -> bool {
+    let (bucket, fingerprint) = self.locate(item);
+    let alternate = self.alternate(bucket, fingerprint);
+
+    self.victim.is_some_and(|victim| victim == (bucket, fingerprint) || victim == (alternate, fingerprint))
+      || self.position(bucket, fingerprint).is_some()
+      || self.position(alternate, fingerprint).is_some()
+  }
+
+  /// Removes an item from the filter. Returns `false` and leaves the
+  /// filter unchanged if the item is certainly not in the filter.
+  ///
+  /// Removing an item that was never inserted but is reported as present
+  /// removes another item instead, which may then be reported as absent.
+  pub fn remove(&mut self, item: &T) -> bool {
+    let (bucket, fingerprint) = self.locate(item);
+    let alternate = self.alternate(bucket, fingerprint);
+
+    if self.victim.is_some_and(|victim| victim == (bucket, fingerprint) || victim == (alternate, fingerprint)) {
+      self.victim = None;
+      self.len -= 1;
+      return true;
+    }
+
+    let slot = self.position(bucket, fingerprint).or_else(|| self.position(alternate, fingerprint));
+    let Some(slot) = slot else {
+      return false;
+    };
+
+    self.slots[slot] = 0;
+    self.len -= 1;
+
+    // The removal may have made room for the victim in one of its buckets.
+    if let Some((bucket, fingerprint)) = self.victim {
+      let alternate = self.alternate(bucket, fingerprint);
+      if self.store(bucket, fingerprint) || self.store(alternate, fingerprint) {
+        self.victim = None;
+      }
+    }
+
+    true
+  }
+
+  /// Removes all items from the filter.
+  pub fn clear(&mut self) {
+    self.slots.fill(0);
+    self.victim = None;
+    self.len = 0;
+  }
+
+  /// Returns the first bucket and the fingerprint of an item.
+  fn locate(&self, item: &T) -> (usize, Fingerprint) {
+    let (first, second) = hash_pair(item);
+    let fingerprint = (second as Fingerprint).max(1);
+    (first as usize & self.mask, fingerprint)
+  }
+
+  /// Returns the other bucket of a fingerprint stored in a bucket.
+  fn alternate(&self, bucket: usize, fingerprint: Fingerprint) -> usize {
+    (bucket ^ mix(u64::from(fingerprint)) as usize) & self.mask
+  }
+
+  /// Stores a fingerprint in a free slot of a bucket, if there is one.
+  fn store(&mut self, bucket: usize, fingerprint: Fingerprint) -> bool {
+    let slots = &mut self.slots[bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE];
+
+    match slots.iter_mut().find(|slot| **slot == 0) {
+      Some(slot) => {
+        *slot = fingerprint;
+        true
+      }
+      None => false
+    }
+  }
+
+  /// Returns the slot holding a fingerprint in a bucket.
+  fn position(&self, bucket: usize, fingerprint: Fingerprint) -> Option<usize> {
+    (bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE).find(|&slot| self.slots[slot] == fingerprint)
+  }
+}
+
+impl<T: Hash + ?Sized> ApproximateMembership<T> for CuckooFilter<T> {
+  fn insert(&mut self, item: &T) -> Result<(), FilterFullError> {
+    CuckooFilter::insert(self, item)
+  }
+
+  fn contains(&self, item: &T) -> bool {
+    CuckooFilter::contains(self, item)
+  }
+
+  fn clear(&mut self) {
+    CuckooFilter::clear(self);
+  }
+}
+
+impl<T: ?Sized> Clone for CuckooFilter<T> {
+  fn clone(&self) -> Self {
+    Self {
+      slots: self.slots.clone(),
+      mask: self.mask,
+      victim: self.victim,
+      len: self.len,
+      kicks: self.kicks,
+      items: PhantomData
+    }
+  }
+}
+
+impl<T: ?Sized> fmt::Debug for CuckooFilter<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CuckooFilter")
+      .field("len", &self.len)
+      .field("capacity", &self.slots.len())
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> serde::Serialize for CuckooFilter<T> {
+  /// Serializes the fingerprints of every bucket together with the victim
+  /// of the last failed insertion.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(&self.slots, self.victim), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized> serde::Deserialize<'de> for CuckooFilter<T> {
+  /// Deserializes the fingerprints and the victim of a filter.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (slots, victim): (Vec<Fingerprint>, Option<(usize, Fingerprint)>) =
+      serde::Deserialize::deserialize(deserializer)?;
+
+    let buckets = slots.len() / BUCKET_SIZE;
+    if slots.len() % BUCKET_SIZE != 0 || !buckets.is_power_of_two() {
+      return Err(D::Error::custom("number of buckets must be a power of two"));
+    }
+    if victim.is_some_and(|(bucket, fingerprint)| bucket >= buckets || fingerprint == 0) {
+      return Err(D::Error::custom("victim is out of bounds"));
+    }
+
+    let len = slots.iter().filter(|&&slot| slot != 0).count() + usize::from(victim.is_some());
+    Ok(Self { slots, mask: buckets - 1, victim, len, kicks: 0, items: PhantomData })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{CuckooFilter, FilterFullError};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_filter() {
+    let mut filter = CuckooFilter::<str>::new(100);
+
+    assert!(filter.is_empty());
+    assert_eq!(filter.capacity(), 128);
+    assert!(!filter.contains("apple"));
+    assert!(!filter.remove("apple"));
+  }
+
+  #[test]
+  fn insert_and_remove() {
+    let mut filter = CuckooFilter::new(100);
+    filter.insert(&1).unwrap();
+    filter.insert(&2).unwrap();
+    filter.insert(&2).unwrap();
+
+    assert_eq!(filter.len(), 3);
+    assert!(filter.remove(&2));
+    assert!(filter.contains(&2));
+    assert!(filter.remove(&2));
+    assert!(!filter.contains(&2));
+    assert!(filter.contains(&1));
+  }
+
+  #[test]
+  fn fills_up() {
+    let mut filter = CuckooFilter::new(1024);
+    let inserted = (0..2000).take_while(|i| filter.insert(i).is_ok()).count();
+
+    assert!(inserted > 950, "only {inserted} items inserted");
+    assert_eq!(filter.insert(&usize::MAX), Err(FilterFullError));
+    for i in 0..inserted {
+      assert!(filter.contains(&i));
+    }
+
+    assert!(filter.remove(&0));
+    assert_eq!(filter.len(), inserted - 1);
+    for i in 1..inserted {
+      assert!(filter.contains(&i));
+    }
+  }
+
+  #[test]
+  fn false_positive_rate() {
+    let mut filter = CuckooFilter::new(10_000);
+    for i in 0..9000 {
+      filter.insert(&i).unwrap();
+    }
+
+    let false_positives = (10_000..110_000).filter(|i| filter.contains(i)).count();
+    assert!(false_positives < 100, "{false_positives} false positives");
+  }
+
+  #[test]
+  fn clear() {
+    let mut filter = CuckooFilter::new(4);
+    filter.insert(&42).unwrap();
+    filter.clear();
+
+    assert!(filter.is_empty());
+    assert!(!filter.contains(&42));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = CuckooFilter::new(8);
+    value.insert("apple").unwrap();
+
+    let json = serde_json::to_string(&value).unwrap();
+    let filter: CuckooFilter<str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(filter.len(), 1);
+    assert!(filter.contains("apple"));
+
+    assert!(serde_json::from_str::<CuckooFilter<str>>("[[0,0,0,0,0,0,0,0,0,0,0,0],null]").is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn no_false_negatives(items in prop::collection::vec(any::<u64>(), 0..200), removed in 0..200usize) {
+      let mut filter = CuckooFilter::new(256);
+      for item in &items {
+        filter.insert(item).unwrap();
+      }
+
+      let removed = removed.min(items.len());
+      for item in &items[..removed] {
+        prop_assert!(filter.remove(item));
+      }
+
+      prop_assert_eq!(filter.len(), items.len() - removed);
+      for item in &items[removed..] {
+        prop_assert!(filter.contains(item));
+      }
+    }
+  }
+}
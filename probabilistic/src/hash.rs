@@ -0,0 +1,128 @@
+use std::hash::{Hash, Hasher};
+
+
+/// Hashes an item into two independent 64-bit hashes.
+///
+/// The bytes the item feeds to the hasher go through FNV-1a, whose result
+/// is then scrambled by `mix`. Unlike `DefaultHasher`, whose algorithm may
+/// change between Rust releases, both are fixed here, so the hashes of an
+/// item are the same in every process and with every toolchain, and a
+/// serialized filter or sketch stays valid when loaded by another program.
+pub(crate) fn hash_pair<T: Hash + ?Sized>(item: &T) -> (u64, u64) {
+  let mut hasher = Fnv1a::new();
+  item.hash(&mut hasher);
+  let first = mix(hasher.finish());
+
+  hasher.write_u8(0xff);
+  (first, mix(hasher.finish()))
+}
+
+/// The 64-bit FNV-1a hash function.
+///
+/// Integers are fed in little-endian order and `usize` as a `u64`, so the
+/// hashes do not depend on the platform either.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+  const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+  const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+  fn new() -> Self {
+    Self(Self::OFFSET_BASIS)
+  }
+}
+
+impl Hasher for Fnv1a {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for &byte in bytes {
+      self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+    }
+  }
+
+  fn write_u16(&mut self, value: u16) {
+    self.write(&value.to_le_bytes());
+  }
+
+  fn write_u32(&mut self, value: u32) {
+    self.write(&value.to_le_bytes());
+  }
+
+  fn write_u64(&mut self, value: u64) {
+    self.write(&value.to_le_bytes());
+  }
+
+  fn write_u128(&mut self, value: u128) {
+    self.write(&value.to_le_bytes());
+  }
+
+  fn write_usize(&mut self, value: usize) {
+    self.write_u64(value as u64);
+  }
+
+  fn write_i16(&mut self, value: i16) {
+    self.write_u16(value as u16);
+  }
+
+  fn write_i32(&mut self, value: i32) {
+    self.write_u32(value as u32);
+  }
+
+  fn write_i64(&mut self, value: i64) {
+    self.write_u64(value as u64);
+  }
+
+  fn write_i128(&mut self, value: i128) {
+    self.write_u128(value as u128);
+  }
+
+  fn write_isize(&mut self, value: isize) {
+    self.write_u64(value as i64 as u64);
+  }
+}
+
+/// Returns `count` indices below `len` derived from the two hashes of an
+/// item, simulating `count` independent hash functions.
+pub(crate) fn indices((first, second): (u64, u64), count: u32, len: usize) -> impl Iterator<Item = usize> {
+  (0..u64::from(count)).map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % len as u64) as usize)
+}
+
+/// Scrambles the bits of a value with the finalizer of SplitMix64.
+pub(crate) fn mix(value: u64) -> u64 {
+  let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  z ^ (z >> 31)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{hash_pair, Fnv1a};
+  use std::hash::Hasher;
+
+  #[test]
+  fn fnv1a_test_vectors() {
+    let hash = |bytes: &[u8]| {
+      let mut hasher = Fnv1a::new();
+      hasher.write(bytes);
+      hasher.finish()
+    };
+
+    assert_eq!(hash(b""), 0xCBF2_9CE4_8422_2325);
+    assert_eq!(hash(b"a"), 0xAF63_DC4C_8601_EC8C);
+    assert_eq!(hash(b"foobar"), 0x8594_4171_F739_67E8);
+  }
+
+  #[test]
+  fn hashes_are_fixed() {
+    assert_eq!(hash_pair(&7usize), hash_pair(&7u64));
+    assert_eq!(hash_pair(&-1isize), hash_pair(&-1i64));
+    assert_ne!(hash_pair("ab").0, hash_pair("ab").1);
+    // Changing these breaks the filters and sketches serialized before.
+    assert_eq!(hash_pair("bloom"), (8645889493372168777, 12035198004378456314));
+  }
+}
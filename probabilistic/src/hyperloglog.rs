@@ -0,0 +1,288 @@
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::hash::hash_pair;
+
+
+/// Smallest supported precision.
+const MIN_PRECISION: u8 = 4;
+
+/// Largest supported precision.
+const MAX_PRECISION: u8 = 16;
+
+/// A HyperLogLog sketch, which estimates the number of distinct items
+/// added to it in a fixed amount of memory.
+///
+/// The first `p` bits of the hash of an item select one of `m = 2^p`
+/// registers, and the register keeps the largest number of leading zeros
+/// seen in the rest of the hashes. Seeing `k` leading zeros takes about
+/// `2^k` distinct items, so the harmonic mean of the registers gives an
+/// estimate with a relative standard error of `1.04 / sqrt(m)`, while
+/// adding the same item again changes nothing.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::HyperLogLog;
+///
+/// let mut sketch = HyperLogLog::new(12);
+/// for i in 0..10_000 {
+///   sketch.add(&(i % 1000));
+/// }
+///
+/// let count = sketch.count();
+/// assert!((950..1050).contains(&count));
+/// ```
+pub struct HyperLogLog<T: ?Sized> {
+  registers: Vec<u8>,
+  precision: u8,
+  /// The sketch does not own items, only their hashes.
+  items: PhantomData<fn(&T)>
+}
+
+/// The error returned when merging sketches with different parameters.
+#[derive(Debug, PartialEq, Eq)]
+pub struct IncompatibleSketchesError;
+
+impl fmt::Display for IncompatibleSketchesError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "sketches have different parameters")
+  }
+}
+
+impl std::error::Error for IncompatibleSketchesError {}
+
+impl<T: Hash + ?Sized> HyperLogLog<T> {
+  /// Creates a new empty sketch with `2^precision` registers.
+  ///
+  /// A higher precision gives a more accurate estimate, a precision of 12
+  /// takes 4 KiB with a relative error of 1.6%.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the precision is not between 4 and 16.
+  pub fn new(precision: u8) -> Self {
+    assert!(
+      (MIN_PRECISION..=MAX_PRECISION).contains(&precision),
+      "precision must be between 4 and 16"
+    );
+
+    Self {
+      registers: vec![0; 1 << precision],
+      precision,
+      items: PhantomData
+    }
+  }
+
+  /// Returns the precision of the sketch.
+  pub fn precision(&self) -> u8 {
+    self.precision
+  }
+
+  /// Returns the relative standard error of the estimates of the sketch.
+  pub fn relative_error(&self) -> f64 {
+    1.04 / (self.registers.len() as f64).sqrt()
+  }
+
+  /// Checks if no item was added to the sketch.
+  pub fn is_empty(&self) -> bool {
+    self.registers.iter().all(|&register| register == 0)
+  }
+
+  /// Adds an item to the sketch.
+  pub fn add(&mut self, item: &T) {
+    let (hash, _) = hash_pair(item);
+    let index = (hash >> (64 - self.precision)) as usize;
+    let rest = hash << self.precision;
+    let rank = rest.leading_zeros().min(64 - u32::from(self.precision)) + 1;
+
+    self.registers[index] = self.registers[index].max(rank as u8);
+  }
+
+  /// Returns the estimated number of distinct items added to the sketch.
+  pub fn count(&self) -> u64 {
+    let registers = self.registers.len() as f64;
+    let alpha = match self.registers.len() {
+      16 => 0.673,
+      32 => 0.697,
+      64 => 0.709,
+      _ => 0.7213 / (1.0 + 1.079 / registers)
+    };
+
+    let sum: f64 = self.registers.iter().map(|&register| 2f64.powi(-i32::from(register))).sum();
+    let estimate = alpha * registers * registers / sum;
+
+    // Few items leave empty registers, which are counted more accurately.
+    let empty = self.registers.iter().filter(|&&register| register == 0).count();
+    if estimate <= 2.5 * registers && empty > 0 {
+      return (registers * (registers / empty as f64).ln()).round() as u64;
+    }
+
+    estimate.round() as u64
+  }
+
+  /// Adds all items added to another sketch to this sketch.
+  ///
+  /// Both sketches must have the same precision, otherwise an error is
+  /// returned and this sketch is left unchanged.
+  pub fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketchesError> {
+    if self.precision != other.precision {
+      return Err(IncompatibleSketchesError);
+    }
+
+    for (register, &other) in self.registers.iter_mut().zip(&other.registers) {
+      *register = (*register).max(other);
+    }
+
+    Ok(())
+  }
+
+  /// Removes all items from the sketch.
+  pub fn clear(&mut self) {
+    self.registers.fill(0);
+  }
+}
+
+impl<T: ?Sized> Clone for HyperLogLog<T> {
+  fn clone(&self) -> Self {
+    Self {
+      registers: self.registers.clone(),
+      precision: self.precision,
+      items: PhantomData
+    }
+  }
+}
+
+impl<T: ?Sized> PartialEq for HyperLogLog<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.registers == other.registers
+  }
+}
+
+impl<T: ?Sized> Eq for HyperLogLog<T> {}
+
+impl<T: ?Sized> fmt::Debug for HyperLogLog<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("HyperLogLog")
+      .field("precision", &self.precision)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized> serde::Serialize for HyperLogLog<T> {
+  /// Serializes the precision together with the registers.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&(self.precision, &self.registers), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized> serde::Deserialize<'de> for HyperLogLog<T> {
+  /// Deserializes the precision and the registers of a sketch.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (precision, registers): (u8, Vec<u8>) = serde::Deserialize::deserialize(deserializer)?;
+
+    if !(MIN_PRECISION..=MAX_PRECISION).contains(&precision) {
+      return Err(D::Error::custom("precision must be between 4 and 16"));
+    }
+    if registers.len() != 1 << precision {
+      return Err(D::Error::custom("number of registers does not match the precision"));
+    }
+
+    Ok(Self { registers, precision, items: PhantomData })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{HyperLogLog, IncompatibleSketchesError};
+
+  /// Checks that an estimate is within `errors` relative standard errors
+  /// of the actual count.
+  fn assert_close<T: ?Sized + std::hash::Hash>(sketch: &HyperLogLog<T>, actual: u64, errors: f64) {
+    let estimate = sketch.count() as f64;
+    let tolerance = errors * sketch.relative_error() * actual as f64;
+    assert!((estimate - actual as f64).abs() <= tolerance, "estimated {estimate} instead of {actual}");
+  }
+
+  #[test]
+  fn empty_sketch() {
+    let sketch = HyperLogLog::<str>::new(10);
+
+    assert!(sketch.is_empty());
+    assert_eq!(sketch.count(), 0);
+  }
+
+  #[test]
+  fn small_counts_are_exact() {
+    let mut sketch = HyperLogLog::new(14);
+    for word in ["apple", "banana", "cherry", "apple"] {
+      sketch.add(word);
+    }
+
+    assert_eq!(sketch.count(), 3);
+  }
+
+  #[test]
+  fn large_counts() {
+    for precision in [4, 10, 14] {
+      let mut sketch = HyperLogLog::new(precision);
+      for i in 0..100_000u64 {
+        sketch.add(&i);
+        sketch.add(&(i / 2));
+      }
+
+      assert_close(&sketch, 100_000, 4.0);
+    }
+  }
+
+  #[test]
+  fn merge() {
+    let mut first = HyperLogLog::new(12);
+    let mut second = HyperLogLog::new(12);
+    for i in 0..30_000 {
+      first.add(&i);
+      second.add(&(i + 20_000));
+    }
+
+    assert_eq!(first.merge(&second), Ok(()));
+    assert_close(&first, 50_000, 4.0);
+
+    assert_eq!(first.merge(&HyperLogLog::new(10)), Err(IncompatibleSketchesError));
+  }
+
+  #[test]
+  fn clear() {
+    let mut sketch = HyperLogLog::new(4);
+    sketch.add(&1);
+    sketch.clear();
+
+    assert!(sketch.is_empty());
+    assert_eq!(sketch.precision(), 4);
+  }
+
+  #[test]
+  #[should_panic(expected = "precision must be between 4 and 16")]
+  fn invalid_precision() {
+    HyperLogLog::<u32>::new(17);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = HyperLogLog::new(4);
+    value.add("apple");
+
+    let json = serde_json::to_string(&value).unwrap();
+    let sketch: HyperLogLog<str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sketch, value);
+    assert_eq!(sketch.count(), 1);
+
+    assert!(serde_json::from_str::<HyperLogLog<str>>("[4,[0,0]]").is_err());
+  }
+}
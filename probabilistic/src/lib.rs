@@ -0,0 +1,25 @@
+//! Probabilistic data structures.
+//!
+//! The structures of this crate answer questions about a set of items
+//! approximately, trading a small and configurable probability of error
+//! for much less memory than storing the items themselves.
+//!
+//! The items are hashed with FNV-1a, fixed in this crate rather than taken
+//! from the standard library, so a structure serialized by one program can
+//! be deserialized and queried by another one, whatever Rust version or
+//! platform it is built for, as long as the items hash the same bytes.
+
+mod bloom;
+mod count_min;
+mod counting;
+mod cuckoo;
+mod hash;
+mod hyperloglog;
+mod membership;
+
+pub use bloom::{BloomFilter, IncompatibleFiltersError};
+pub use count_min::CountMinSketch;
+pub use counting::CountingBloomFilter;
+pub use cuckoo::CuckooFilter;
+pub use hyperloglog::{HyperLogLog, IncompatibleSketchesError};
+pub use membership::{ApproximateMembership, FilterFullError};
@@ -0,0 +1,51 @@
+use std::fmt;
+
+
+/// A set that answers membership queries approximately.
+///
+/// An inserted item is always reported as present, but an item that was
+/// never inserted may be reported as present too, with a small
+/// probability that depends on the implementation and its parameters.
+/// The implementations, `BloomFilter`, `CountingBloomFilter` and
+/// `CuckooFilter`, can be swapped behind this trait.
+///
+/// # Example
+///
+/// ```
+/// use probabilistic::{ApproximateMembership, BloomFilter, CuckooFilter};
+///
+/// fn seen_twice(filter: &mut impl ApproximateMembership<str>, words: &[&str]) -> bool {
+///   words.iter().any(|word| filter.contains(word) || filter.insert(word).is_err())
+/// }
+///
+/// let words = ["apple", "banana", "apple"];
+/// assert!(seen_twice(&mut BloomFilter::new(100, 0.01), &words));
+/// assert!(seen_twice(&mut CuckooFilter::new(100), &words));
+/// ```
+pub trait ApproximateMembership<T: ?Sized> {
+  /// Adds an item to the set.
+  ///
+  /// Returns an error if the set has no room left for the item.
+  fn insert(&mut self, item: &T) -> Result<(), FilterFullError>;
+
+  /// Checks if the item may have been inserted into the set.
+  ///
+  /// Returns `false` only if the item was certainly not inserted.
+  fn contains(&self, item: &T) -> bool;
+
+  /// Removes all items from the set.
+  fn clear(&mut self);
+}
+
+/// The error returned when inserting an item into a filter that has no
+/// room left for it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FilterFullError;
+
+impl fmt::Display for FilterFullError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "filter is full")
+  }
+}
+
+impl std::error::Error for FilterFullError {}
@@ -0,0 +1,105 @@
+//! Compares `Queue` against `std::collections::VecDeque` from the standard library
+//! with throughput numbers for push, pop and iterate at several sizes.
+//!
+//! Run with `cargo bench -p queue --bench std`.
+
+use std::hint::black_box;
+use std::collections::VecDeque;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use queue::Queue;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn filled(size: usize) -> Queue<usize> {
+  let mut queue = Queue::new();
+  for i in 0..size {
+    queue.enqueue(i);
+  }
+  queue
+}
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("queue/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Queue", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = Queue::new();
+        for i in 0..size {
+          ours.enqueue(black_box(i));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = VecDeque::new();
+        for i in 0..size {
+          std.push_back(black_box(i));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("queue/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Queue", size), &size, |b, &size| {
+      b.iter_batched(
+        || filled(size),
+        |mut ours| {
+          for _ in 0..size {
+            black_box(ours.dequeue());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<VecDeque<_>>(),
+        |mut std| {
+          for _ in 0..size {
+            black_box(std.pop_front());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("queue/iterate");
+
+  for size in SIZES {
+    let ours = filled(size);
+    let std: VecDeque<_> = (0..size).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Queue", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, iterate);
+criterion_main!(benches);
@@ -0,0 +1,318 @@
+/// A generic queue data structure.
+///
+/// This struct represents a queue, which is a First-In-First-Out
+/// (FIFO) data structure. Elements are added to the back of the
+/// queue and removed from the front. This implementation uses a
+/// ring buffer internally, so both `enqueue` and `dequeue` run in
+/// (amortized) constant time.
+///
+/// # Example
+///
+/// ```
+/// use queue::Queue;
+///
+/// let mut queue = Queue::<i32>::new();
+/// queue.enqueue(7);
+/// queue.enqueue(32);
+///
+/// // item: 7, item: 32
+/// for item in queue.iter() {
+///   println!("item: {item}");
+/// }
+///
+/// assert!(!queue.is_empty());
+/// assert_eq!(queue.peek(), Some(&7));
+/// assert_eq!(queue.dequeue(), Some(7));
+/// assert_eq!(queue.len(), 1);
+/// ```
+pub struct Queue<T> {
+  /// Ring buffer holding the elements, `None` marks a free slot.
+  buffer: Vec<Option<T>>,
+  /// Index of the front element in the buffer.
+  head: usize,
+  /// Number of elements in the queue.
+  len: usize
+}
+
+impl<T> Default for Queue<T> {
+  /// Creates a new instance of `Queue` with default values.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use queue::Queue;
+  ///
+  /// let queue = Queue::<i32>::default();
+  /// ```
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Queue<T> {
+  /// Creates a new empty instance of `Queue`.
+  pub fn new() -> Self {
+    Self { buffer: Vec::new(), head: 0, len: 0 }
+  }
+
+  /// Creates a new empty instance of `Queue` that can hold at least
+  /// `capacity` elements without reallocating.
+  pub fn with_capacity(capacity: usize) -> Self {
+    let mut buffer = Vec::with_capacity(capacity);
+    buffer.resize_with(capacity, || None);
+
+    Self { buffer, head: 0, len: 0 }
+  }
+
+  /// Adds a value to the back of the queue.
+  pub fn enqueue(&mut self, value: T) {
+    if self.len == self.buffer.len() {
+      self.grow();
+    }
+
+    let tail = (self.head + self.len) % self.buffer.len();
+    self.buffer[tail] = Some(value);
+    self.len += 1;
+  }
+
+  /// Removes the front value from the queue and returns it,
+  /// or `None` if the queue is empty.
+  pub fn dequeue(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let value = self.buffer[self.head].take();
+    self.head = (self.head + 1) % self.buffer.len();
+    self.len -= 1;
+
+    value
+  }
+
+  /// Returns a reference to the front value of the queue,
+  /// or `None` if the queue is empty.
+  pub fn peek(&self) -> Option<&T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    self.buffer[self.head].as_ref()
+  }
+
+  /// Returns the number of elements in the queue.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the queue is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns an iterator over the elements of the queue,
+  /// from front to back.
+  pub fn iter(&self) -> QueueIterator<'_, T> {
+    QueueIterator {
+      queue: self,
+      index: 0,
+    }
+  }
+
+  /// Doubles the size of the ring buffer, moving the elements
+  /// so that the front of the queue starts at index zero.
+  fn grow(&mut self) {
+    let capacity = (self.buffer.len() * 2).max(1);
+    let mut buffer = Vec::with_capacity(capacity);
+
+    for index in 0..self.len {
+      let position = (self.head + index) % self.buffer.len();
+      buffer.push(self.buffer[position].take());
+    }
+
+    buffer.resize_with(capacity, || None);
+
+    self.buffer = buffer;
+    self.head = 0;
+  }
+}
+
+/// An iterator over the elements of a `Queue`.
+pub struct QueueIterator<'a, T> {
+  queue: &'a Queue<T>,
+  index: usize,
+}
+
+impl<'a, T> Iterator for QueueIterator<'a, T> {
+  type Item = &'a T;
+
+  /// Advances the iterator and returns the next element,
+  /// or `None` if the iterator is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.index < self.queue.len {
+      let position = (self.queue.head + self.index) % self.queue.buffer.len();
+      self.index += 1;
+      self.queue.buffer[position].as_ref()
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Queue<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the front to the back.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Queue<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a queue from a sequence, the first element ends up in front.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let values = Vec::deserialize(deserializer)?;
+    let mut queue = Self::with_capacity(values.len());
+
+    for value in values {
+      queue.enqueue(value);
+    }
+
+    Ok(queue)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Queue;
+  use std::collections::VecDeque;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_queue() {
+    assert!(Queue::<i32>::new().is_empty());
+  }
+
+  #[test]
+  fn enqueue() {
+    let mut queue = Queue::<i32>::new();
+    queue.enqueue(42);
+    queue.enqueue(314);
+
+    assert_eq!(queue.len(), 2);
+  }
+
+  #[test]
+  fn dequeue() {
+    let mut queue = Queue::<i32>::new();
+    queue.enqueue(42);
+    queue.enqueue(314);
+
+    assert_eq!(queue.dequeue(), Some(42));
+    assert_eq!(queue.dequeue(), Some(314));
+    assert_eq!(queue.dequeue(), None);
+  }
+
+  #[test]
+  fn peek() {
+    let mut queue = Queue::<i32>::new();
+    assert_eq!(queue.peek(), None);
+
+    queue.enqueue(42);
+    queue.enqueue(314);
+
+    assert_eq!(queue.peek(), Some(&42));
+    assert_eq!(queue.len(), 2);
+  }
+
+  #[test]
+  fn wrap_around() {
+    let mut queue = Queue::<i32>::with_capacity(4);
+
+    for value in 0..3 {
+      queue.enqueue(value);
+    }
+
+    assert_eq!(queue.dequeue(), Some(0));
+    assert_eq!(queue.dequeue(), Some(1));
+
+    // The tail wraps to the start of the buffer before it has to grow.
+    for value in 3..8 {
+      queue.enqueue(value);
+    }
+
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4, 5, 6, 7]);
+  }
+
+  #[test]
+  fn iter() {
+    let mut queue = Queue::<i32>::new();
+    queue.enqueue(42);
+    queue.enqueue(314);
+
+    let mut iter = queue.iter();
+    assert_eq!(iter.next(), Some(&42));
+    assert_eq!(iter.next(), Some(&314));
+    assert_eq!(iter.next(), None);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = Queue::<i32>::new();
+    value.enqueue(1);
+    value.enqueue(2);
+    value.dequeue();
+    value.enqueue(3);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[2,3]");
+
+    let mut queue: Queue<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(queue.dequeue(), Some(2));
+    assert_eq!(queue.dequeue(), Some(3));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Enqueue(i32),
+    Dequeue,
+    Peek
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Enqueue),
+      Just(Operation::Dequeue),
+      Just(Operation::Peek)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut queue = Queue::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Enqueue(value) => {
+            queue.enqueue(value);
+            model.push_back(value);
+          }
+          Operation::Dequeue => prop_assert_eq!(queue.dequeue(), model.pop_front()),
+          Operation::Peek => prop_assert_eq!(queue.peek(), model.front())
+        }
+
+        prop_assert_eq!(queue.len(), model.len());
+        prop_assert!(queue.iter().eq(model.iter()));
+      }
+    }
+  }
+}
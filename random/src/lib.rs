@@ -0,0 +1,69 @@
+//! Random numbers for the randomized structures of the workspace.
+//!
+//! Treaps draw the priorities of their nodes and skip lists the levels of
+//! theirs from the generator of this crate. Neither needs statistical
+//! quality beyond a uniform spread, so a small generator shared by both
+//! is enough.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+
+/// The SplitMix64 pseudo-random number generator.
+///
+/// # Example
+///
+/// ```
+/// use random::SplitMix64;
+///
+/// let mut first = SplitMix64::new(Some(42));
+/// let mut second = SplitMix64::new(Some(42));
+///
+/// assert_eq!(first.next_u64(), second.next_u64());
+/// assert!((0.0..1.0).contains(&first.next_f64()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+  /// Creates a generator from the seed, or from a random seed if `None`.
+  pub fn new(seed: Option<u64>) -> Self {
+    Self(seed.unwrap_or_else(|| RandomState::new().build_hasher().finish()))
+  }
+
+  /// Returns the next number of the sequence.
+  pub fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+
+  /// Returns a number uniformly distributed in `[0, 1)`.
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::SplitMix64;
+
+  #[test]
+  fn reference_sequence() {
+    let mut rng = SplitMix64::new(Some(0));
+
+    assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+    assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+  }
+
+  #[test]
+  fn unit_interval() {
+    let mut rng = SplitMix64::new(None);
+
+    assert!((0..1000).map(|_| rng.next_f64()).all(|value| (0.0..1.0).contains(&value)));
+  }
+}
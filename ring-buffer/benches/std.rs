@@ -0,0 +1,141 @@
+//! Compares `RingBuffer` against `std::collections::VecDeque` from the
+//! standard library on pushing, popping and iterating over several sizes of
+//! buffers, and on keeping a window of the latest values of a stream, where
+//! the `VecDeque` pops its front by hand once it holds the whole window.
+//!
+//! Run with `cargo bench -p ring-buffer --bench std`.
+
+use std::hint::black_box;
+use std::collections::VecDeque;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use ring_buffer::{OverflowPolicy, RingBuffer};
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Number of values kept by the window benchmark.
+const WINDOW: usize = 64;
+
+fn filled(size: usize) -> RingBuffer<usize> {
+  let mut buffer = RingBuffer::new(size);
+  for i in 0..size {
+    buffer.push(i).unwrap();
+  }
+  buffer
+}
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("ring_buffer/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("RingBuffer", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = RingBuffer::new(size);
+        for i in 0..size {
+          ours.push(black_box(i)).unwrap();
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = VecDeque::with_capacity(size);
+        for i in 0..size {
+          std.push_back(black_box(i));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("ring_buffer/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("RingBuffer", size), &size, |b, &size| {
+      b.iter_batched(
+        || filled(size),
+        |mut ours| {
+          while let Some(value) = ours.pop() {
+            black_box(value);
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<VecDeque<_>>(),
+        |mut std| {
+          while let Some(value) = std.pop_front() {
+            black_box(value);
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn window(c: &mut Criterion) {
+  let mut group = c.benchmark_group("ring_buffer/window");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("RingBuffer", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = RingBuffer::with_policy(WINDOW, OverflowPolicy::Overwrite);
+        for i in 0..size {
+          black_box(ours.push(black_box(i)).unwrap());
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = VecDeque::with_capacity(WINDOW);
+        for i in 0..size {
+          if std.len() == WINDOW {
+            black_box(std.pop_front());
+          }
+          std.push_back(black_box(i));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("ring_buffer/iterate");
+
+  for size in SIZES {
+    let ours = filled(size);
+    let std: VecDeque<_> = (0..size).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("RingBuffer", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("std::collections::VecDeque", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, window, iterate);
+criterion_main!(benches);
@@ -0,0 +1,489 @@
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::slice;
+
+
+/// A fixed-capacity circular buffer.
+///
+/// Elements are pushed to the back and popped from the front of a buffer
+/// that is allocated once and never grows. When the buffer is full, `push`
+/// either rejects the new element or overwrites the oldest one, depending
+/// on the `OverflowPolicy` of the buffer, which makes it suitable for
+/// streaming data where only the latest values matter.
+///
+/// # Example
+///
+/// ```
+/// use ring_buffer::{OverflowPolicy, RingBuffer};
+///
+/// let mut buffer = RingBuffer::with_policy(3, OverflowPolicy::Overwrite);
+/// for sample in 1..=5 {
+///   buffer.push(sample).unwrap();
+/// }
+///
+/// assert!(buffer.is_full());
+/// assert_eq!(buffer.front(), Some(&3));
+/// assert_eq!(buffer.back(), Some(&5));
+/// assert_eq!(buffer.as_slices(), (&[3][..], &[4, 5][..]));
+/// assert_eq!(buffer.pop(), Some(3));
+/// ```
+pub struct RingBuffer<T> {
+  /// Slots of the buffer. The `len` slots starting at `head`, wrapping
+  /// around the end, are initialized.
+  buffer: Box<[MaybeUninit<T>]>,
+  /// Index of the front element in the buffer.
+  head: usize,
+  /// Number of elements in the buffer.
+  len: usize,
+  policy: OverflowPolicy
+}
+
+/// Decides what `RingBuffer::push` does when the buffer is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OverflowPolicy {
+  /// The new element is rejected and returned in a `BufferFullError`.
+  #[default]
+  Reject,
+  /// The oldest element is removed to make room for the new one.
+  Overwrite
+}
+
+/// The error returned by `RingBuffer::push` when the buffer is full and
+/// its policy is `OverflowPolicy::Reject`.
+///
+/// It carries the rejected value so it can be recovered.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BufferFullError<T>(pub T);
+
+impl<T> BufferFullError<T> {
+  /// Returns the value that could not be pushed.
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl<T> fmt::Display for BufferFullError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "ring buffer is full")
+  }
+}
+
+impl<T: fmt::Debug> std::error::Error for BufferFullError<T> {}
+
+impl<T> RingBuffer<T> {
+  /// Creates a new empty instance of `RingBuffer` that holds at most
+  /// `capacity` elements and rejects new elements when full.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    Self::with_policy(capacity, OverflowPolicy::default())
+  }
+
+  /// Creates a new empty instance of `RingBuffer` that holds at most
+  /// `capacity` elements and handles overflows with the given policy.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `capacity` is zero.
+  pub fn with_policy(capacity: usize, policy: OverflowPolicy) -> Self {
+    assert!(capacity > 0, "capacity must be positive");
+
+    let buffer = (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+    Self { buffer, head: 0, len: 0, policy }
+  }
+
+  /// Returns the maximum number of elements of the buffer.
+  pub fn capacity(&self) -> usize {
+    self.buffer.len()
+  }
+
+  /// Returns the overflow policy of the buffer.
+  pub fn policy(&self) -> OverflowPolicy {
+    self.policy
+  }
+
+  /// Returns the number of elements in the buffer.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the buffer is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Checks if the buffer holds `capacity` elements.
+  pub fn is_full(&self) -> bool {
+    self.len == self.capacity()
+  }
+
+  /// Adds a value to the back of the buffer.
+  ///
+  /// If the buffer is full, the policy of the buffer decides what
+  /// happens: with `OverflowPolicy::Reject` the value is returned back in
+  /// an error, with `OverflowPolicy::Overwrite` the front element is
+  /// removed and returned in `Ok(Some(_))`.
+  pub fn push(&mut self, value: T) -> Result<Option<T>, BufferFullError<T>> {
+    if !self.is_full() {
+      let index = self.physical_index(self.len);
+      self.buffer[index].write(value);
+      self.len += 1;
+      return Ok(None);
+    }
+
+    match self.policy {
+      OverflowPolicy::Reject => Err(BufferFullError(value)),
+      OverflowPolicy::Overwrite => {
+        // SAFETY: the buffer is full, so the front slot is initialized.
+        let oldest = unsafe { self.buffer[self.head].assume_init_read() };
+        self.buffer[self.head].write(value);
+        self.head = self.physical_index(1);
+        Ok(Some(oldest))
+      }
+    }
+  }
+
+  /// Removes the front (oldest) element of the buffer and returns it,
+  /// or `None` if the buffer is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    // SAFETY: the buffer is not empty, so the front slot is initialized,
+    // and it is no longer considered part of the buffer afterwards.
+    let value = unsafe { self.buffer[self.head].assume_init_read() };
+    self.head = self.physical_index(1);
+    self.len -= 1;
+
+    Some(value)
+  }
+
+  /// Returns a reference to the front (oldest) element of the buffer,
+  /// or `None` if the buffer is empty.
+  pub fn front(&self) -> Option<&T> {
+    self.get(0)
+  }
+
+  /// Returns a reference to the back (newest) element of the buffer,
+  /// or `None` if the buffer is empty.
+  pub fn back(&self) -> Option<&T> {
+    self.len.checked_sub(1).and_then(|index| self.get(index))
+  }
+
+  /// Returns a reference to the element at the given position counted
+  /// from the front, or `None` if the index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index >= self.len {
+      return None;
+    }
+
+    // SAFETY: the first `len` slots from the front are initialized.
+    Some(unsafe { self.buffer[self.physical_index(index)].assume_init_ref() })
+  }
+
+  /// Returns the elements of the buffer from front to back as two slices.
+  ///
+  /// The second slice is empty unless the elements wrap around the end
+  /// of the underlying storage.
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let (front, back) = self.slot_ranges();
+
+    // SAFETY: both ranges only cover initialized slots, and
+    // `MaybeUninit<T>` has the same layout as `T`.
+    unsafe {
+      (
+        slice::from_raw_parts(self.buffer[front.clone()].as_ptr().cast(), front.len()),
+        slice::from_raw_parts(self.buffer[back.clone()].as_ptr().cast(), back.len())
+      )
+    }
+  }
+
+  /// Removes all elements from the buffer.
+  pub fn clear(&mut self) {
+    while self.pop().is_some() {}
+  }
+
+  /// Returns an iterator over the elements of the buffer from front to back.
+  pub fn iter(&self) -> Iter<'_, T> {
+    let (front, back) = self.as_slices();
+    Iter { front: front.iter(), back: back.iter() }
+  }
+
+  /// Converts a position counted from the front into an index of the
+  /// underlying storage.
+  fn physical_index(&self, index: usize) -> usize {
+    (self.head + index) % self.capacity()
+  }
+
+  /// Returns the ranges of initialized slots, from front to back.
+  fn slot_ranges(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    let end = self.head + self.len;
+
+    if end <= self.capacity() {
+      (self.head..end, 0..0)
+    } else {
+      (self.head..self.capacity(), 0..end - self.capacity())
+    }
+  }
+}
+
+impl<T> Drop for RingBuffer<T> {
+  /// Drops the elements of the buffer.
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<T: Clone> Clone for RingBuffer<T> {
+  fn clone(&self) -> Self {
+    let mut buffer = Self::with_policy(self.capacity(), self.policy);
+
+    for value in self.iter() {
+      let _ = buffer.push(value.clone());
+    }
+
+    buffer
+  }
+}
+
+impl<T: PartialEq> PartialEq for RingBuffer<T> {
+  /// Compares the elements from front to back, regardless of the
+  /// capacity or the policy of the buffers.
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for RingBuffer<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for RingBuffer<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+/// A double-ended iterator over the elements of a `RingBuffer`.
+pub struct Iter<'a, T> {
+  front: slice::Iter<'a, T>,
+  back: slice::Iter<'a, T>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.front.next().or_else(|| self.back.next())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.front.len() + self.back.len();
+    (len, Some(len))
+  }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.back.next_back().or_else(|| self.front.next_back())
+  }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for RingBuffer<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the capacity and the policy together with the elements
+  /// from front to back.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let values: Vec<&T> = self.iter().collect();
+    serde::Serialize::serialize(&(self.capacity(), self.policy, values), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for RingBuffer<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes the capacity, the policy and the elements of a buffer.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let (capacity, policy, values): (usize, OverflowPolicy, Vec<T>) =
+      serde::Deserialize::deserialize(deserializer)?;
+
+    if capacity == 0 {
+      return Err(D::Error::custom("capacity must be positive"));
+    }
+    if values.len() > capacity {
+      return Err(D::Error::custom("number of values exceeds the capacity"));
+    }
+
+    let mut buffer = Self::with_policy(capacity, policy);
+    for value in values {
+      let _ = buffer.push(value);
+    }
+
+    Ok(buffer)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{BufferFullError, OverflowPolicy, RingBuffer};
+  use std::collections::VecDeque;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_buffer() {
+    let buffer = RingBuffer::<i32>::new(2);
+
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.capacity(), 2);
+    assert_eq!(buffer.policy(), OverflowPolicy::Reject);
+    assert_eq!(buffer.front(), None);
+    assert_eq!(buffer.back(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "capacity must be positive")]
+  fn zero_capacity() {
+    RingBuffer::<i32>::new(0);
+  }
+
+  #[test]
+  fn push_and_pop() {
+    let mut buffer = RingBuffer::new(3);
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(buffer.pop(), Some(1));
+    assert_eq!(buffer.pop(), Some(2));
+    assert_eq!(buffer.pop(), None);
+  }
+
+  #[test]
+  fn reject_when_full() {
+    let mut buffer = RingBuffer::new(2);
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+
+    assert!(buffer.is_full());
+    assert_eq!(buffer.push(3), Err(BufferFullError(3)));
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+  }
+
+  #[test]
+  fn overwrite_when_full() {
+    let mut buffer = RingBuffer::with_policy(2, OverflowPolicy::Overwrite);
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+
+    assert_eq!(buffer.push(3), Ok(Some(1)));
+    assert_eq!(buffer.push(4), Ok(Some(2)));
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4]);
+  }
+
+  #[test]
+  fn as_slices_wrap_around() {
+    let mut buffer = RingBuffer::new(4);
+    for value in 1..=4 {
+      buffer.push(value).unwrap();
+    }
+    buffer.pop();
+    buffer.pop();
+    buffer.push(5).unwrap();
+
+    assert_eq!(buffer.as_slices(), (&[3, 4][..], &[5][..]));
+    assert_eq!(buffer.get(2), Some(&5));
+    assert_eq!(buffer.get(3), None);
+    assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), vec![5, 4, 3]);
+  }
+
+  #[test]
+  fn drop_values() {
+    let value = std::rc::Rc::new(());
+    let mut buffer = RingBuffer::with_policy(2, OverflowPolicy::Overwrite);
+
+    for _ in 0..3 {
+      let _ = buffer.push(std::rc::Rc::clone(&value));
+    }
+    assert_eq!(std::rc::Rc::strong_count(&value), 3);
+
+    drop(buffer);
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = RingBuffer::with_policy(3, OverflowPolicy::Overwrite);
+    for sample in 1..=4 {
+      value.push(sample).unwrap();
+    }
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"[3,"Overwrite",[2,3,4]]"#);
+
+    let buffer: RingBuffer<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(buffer, value);
+    assert_eq!(buffer.policy(), OverflowPolicy::Overwrite);
+
+    assert!(serde_json::from_str::<RingBuffer<i32>>(r#"[1,"Reject",[1,2]]"#).is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      2 => any::<i32>().prop_map(Operation::Push),
+      1 => Just(Operation::Pop)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn overwrite_behaves_like_vec_deque(capacity in 1..8usize, operations in prop::collection::vec(operation(), 0..100)) {
+      let mut buffer = RingBuffer::with_policy(capacity, OverflowPolicy::Overwrite);
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            let evicted = if model.len() == capacity { model.pop_front() } else { None };
+            model.push_back(value);
+            prop_assert_eq!(buffer.push(value), Ok(evicted));
+          }
+          Operation::Pop => prop_assert_eq!(buffer.pop(), model.pop_front())
+        }
+
+        prop_assert_eq!(buffer.len(), model.len());
+        prop_assert_eq!(buffer.front(), model.front());
+        prop_assert_eq!(buffer.back(), model.back());
+        prop_assert!(buffer.iter().eq(model.iter()));
+      }
+    }
+  }
+}
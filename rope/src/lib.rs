@@ -0,0 +1,756 @@
+//! A rope, a string stored as a balanced tree of chunks for editing large
+//! texts.
+
+use std::fmt::{self, Write};
+use std::hash::{Hash, Hasher};
+use std::ops::{Bound, Range, RangeBounds};
+use std::str;
+use std::sync::Arc;
+
+mod node;
+
+use node::{join, split, Node};
+
+
+/// A string stored as a balanced tree of chunks, for editing large texts.
+///
+/// The leaves of the tree hold chunks of up to 1 KiB of the text, and
+/// every branch knows the number of bytes, characters and line breaks
+/// below it. Inserting, removing and slicing therefore take O(log n)
+/// time instead of moving the rest of the text like a `String` does, and
+/// every position can be given in characters, converted to and from
+/// bytes, or looked up as a line and a column.
+///
+/// Positions are character indices unless stated otherwise. The nodes are
+/// shared between ropes, so cloning and slicing a rope are O(log n) and
+/// leave the original untouched.
+///
+/// # Example
+///
+/// ```
+/// use rope::Rope;
+///
+/// let mut rope = Rope::from("Hello, world!\nSecond line");
+/// rope.insert(7, "big ");
+/// rope.remove(0..5);
+/// rope.insert(0, "Goodbye");
+///
+/// assert_eq!(rope.to_string(), "Goodbye, big world!\nSecond line");
+/// assert_eq!(rope.len_lines(), 2);
+/// assert_eq!(rope.line_col(21), (1, 1));
+/// assert_eq!(rope.line(1).to_string(), "Second line");
+/// assert_eq!(rope.slice(9..12), "big");
+/// ```
+#[derive(Clone)]
+pub struct Rope {
+  root: Arc<Node>
+}
+
+impl Default for Rope {
+  /// Creates a new instance of `Rope` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Rope {
+  /// Creates a new empty rope.
+  pub fn new() -> Self {
+    Self { root: Node::leaf(String::new()) }
+  }
+
+  /// Checks if the rope is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len_bytes() == 0
+  }
+
+  /// Returns the length of the text in bytes.
+  pub fn len_bytes(&self) -> usize {
+    self.root.metrics().bytes
+  }
+
+  /// Returns the length of the text in characters.
+  pub fn len_chars(&self) -> usize {
+    self.root.metrics().chars
+  }
+
+  /// Returns the number of lines, which is one more than the number of
+  /// `'\n'` characters.
+  pub fn len_lines(&self) -> usize {
+    self.root.metrics().newlines + 1
+  }
+
+  /// Inserts the text before the character at `char_index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is greater than the length in characters.
+  pub fn insert(&mut self, char_index: usize, text: &str) {
+    let (before, after) = split(&self.root, self.char_to_byte(char_index));
+    self.root = join(join(before, Node::build(text)), after);
+  }
+
+  /// Removes the characters in the range.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is out of bounds.
+  pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+    let Range { start, end } = self.byte_range(range);
+    let (before, rest) = split(&self.root, start);
+    let (_, after) = split(&rest, end - start);
+    self.root = join(before, after);
+  }
+
+  /// Returns the characters in the range as a new rope, sharing the
+  /// nodes of this one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is out of bounds.
+  pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Rope {
+    let Range { start, end } = self.byte_range(range);
+    let (_, rest) = split(&self.root, start);
+    Rope { root: split(&rest, end - start).0 }
+  }
+
+  /// Appends the text of the other rope, in O(log n).
+  pub fn append(&mut self, other: Rope) {
+    self.root = join(Arc::clone(&self.root), other.root);
+  }
+
+  /// Splits the rope at `char_index`, keeping the characters before it
+  /// and returning the rest, in O(log n).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is greater than the length in characters.
+  pub fn split_off(&mut self, char_index: usize) -> Rope {
+    let (before, after) = split(&self.root, self.char_to_byte(char_index));
+    self.root = before;
+    Rope { root: after }
+  }
+
+  /// Returns the character at `char_index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is out of bounds.
+  pub fn char(&self, char_index: usize) -> char {
+    assert!(char_index < self.len_chars(), "char index out of bounds");
+    let (text, offset) = self.chunk_at_byte(self.char_to_byte(char_index));
+    text[offset..].chars().next().expect("the offset is a character boundary")
+  }
+
+  /// Returns the byte at `byte_index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `byte_index` is out of bounds.
+  pub fn byte(&self, byte_index: usize) -> u8 {
+    assert!(byte_index < self.len_bytes(), "byte index out of bounds");
+    let (text, offset) = self.chunk_at_byte(byte_index);
+    text.as_bytes()[offset]
+  }
+
+  /// Returns the byte offset of the character at `char_index`, or the
+  /// length in bytes if `char_index` is the length in characters.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is greater than the length in characters.
+  pub fn char_to_byte(&self, char_index: usize) -> usize {
+    assert!(char_index <= self.len_chars(), "char index out of bounds");
+    let (mut node, mut chars, mut bytes) = (&*self.root, char_index, 0);
+
+    loop {
+      match node {
+        Node::Leaf { text, .. } => return bytes + text.char_indices().nth(chars).map_or(text.len(), |(offset, _)| offset),
+        Node::Branch { left, right, .. } => {
+          let metrics = left.metrics();
+          if chars < metrics.chars {
+            node = left;
+          } else {
+            (node, chars, bytes) = (right, chars - metrics.chars, bytes + metrics.bytes);
+          }
+        }
+      }
+    }
+  }
+
+  /// Returns the index of the character that contains the byte at
+  /// `byte_index`, or the length in characters if `byte_index` is the
+  /// length in bytes.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `byte_index` is greater than the length in bytes.
+  pub fn byte_to_char(&self, byte_index: usize) -> usize {
+    assert!(byte_index <= self.len_bytes(), "byte index out of bounds");
+    let (mut node, mut bytes, mut chars) = (&*self.root, byte_index, 0);
+
+    loop {
+      match node {
+        Node::Leaf { text, .. } => {
+          // Counts the characters starting before the byte, which includes
+          // the one containing it if it is not the first byte of a character.
+          let starts = text.as_bytes()[..bytes].iter().filter(|&&byte| !is_continuation(byte)).count();
+          return chars + starts - usize::from(!text.is_char_boundary(bytes));
+        }
+        Node::Branch { left, right, .. } => {
+          let metrics = left.metrics();
+          if bytes < metrics.bytes {
+            node = left;
+          } else {
+            (node, bytes, chars) = (right, bytes - metrics.bytes, chars + metrics.chars);
+          }
+        }
+      }
+    }
+  }
+
+  /// Returns the index of the line of the character at `char_index`, or of
+  /// the last line if `char_index` is the length in characters.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is greater than the length in characters.
+  pub fn char_to_line(&self, char_index: usize) -> usize {
+    assert!(char_index <= self.len_chars(), "char index out of bounds");
+    let (mut node, mut chars, mut lines) = (&*self.root, char_index, 0);
+
+    loop {
+      match node {
+        Node::Leaf { text, .. } => return lines + text.chars().take(chars).filter(|&c| c == '\n').count(),
+        Node::Branch { left, right, .. } => {
+          let metrics = left.metrics();
+          if chars < metrics.chars {
+            node = left;
+          } else {
+            (node, chars, lines) = (right, chars - metrics.chars, lines + metrics.newlines);
+          }
+        }
+      }
+    }
+  }
+
+  /// Returns the index of the first character of the line.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `line_index` is out of bounds.
+  pub fn line_to_char(&self, line_index: usize) -> usize {
+    assert!(line_index < self.len_lines(), "line index out of bounds");
+    if line_index == 0 {
+      return 0;
+    }
+
+    // The line starts after the `line_index`-th line break.
+    let (mut node, mut newlines, mut chars) = (&*self.root, line_index, 0);
+
+    loop {
+      match node {
+        Node::Leaf { text, .. } => {
+          let (offset, _) = text.chars().enumerate().filter(|&(_, c)| c == '\n').nth(newlines - 1).expect("the leaf has the line break");
+          return chars + offset + 1;
+        }
+        Node::Branch { left, right, .. } => {
+          let metrics = left.metrics();
+          if newlines <= metrics.newlines {
+            node = left;
+          } else {
+            (node, newlines, chars) = (right, newlines - metrics.newlines, chars + metrics.chars);
+          }
+        }
+      }
+    }
+  }
+
+  /// Returns the line and the column, both starting at zero and counted
+  /// in characters, of the character at `char_index`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `char_index` is greater than the length in characters.
+  pub fn line_col(&self, char_index: usize) -> (usize, usize) {
+    let line = self.char_to_line(char_index);
+    (line, char_index - self.line_to_char(line))
+  }
+
+  /// Returns the line as a new rope, with its line break if it has one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `line_index` is out of bounds.
+  pub fn line(&self, line_index: usize) -> Rope {
+    let start = self.line_to_char(line_index);
+    let end = if line_index + 1 < self.len_lines() { self.line_to_char(line_index + 1) } else { self.len_chars() };
+    self.slice(start..end)
+  }
+
+  /// Returns an iterator over the chunks of the text, the strings stored
+  /// in the leaves of the tree, in order.
+  pub fn chunks(&self) -> Chunks<'_> {
+    Chunks { stack: vec![&*self.root] }
+  }
+
+  /// Returns an iterator over the characters of the text.
+  pub fn chars(&self) -> Chars<'_> {
+    Chars { chunks: self.chunks(), chars: "".chars() }
+  }
+
+  /// Returns the leaf containing the byte at `byte_index`, which must be
+  /// in bounds, and the offset of the byte in it.
+  fn chunk_at_byte(&self, byte_index: usize) -> (&str, usize) {
+    let (mut node, mut bytes) = (&*self.root, byte_index);
+
+    loop {
+      match node {
+        Node::Leaf { text, .. } => return (text, bytes),
+        Node::Branch { left, right, .. } => {
+          let metrics = left.metrics();
+          if bytes < metrics.bytes {
+            node = left;
+          } else {
+            (node, bytes) = (right, bytes - metrics.bytes);
+          }
+        }
+      }
+    }
+  }
+
+  /// Converts a range of characters to a range of bytes.
+  fn byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+    let start = match range.start_bound() {
+      Bound::Included(&start) => start,
+      Bound::Excluded(&start) => start + 1,
+      Bound::Unbounded => 0
+    };
+    let end = match range.end_bound() {
+      Bound::Included(&end) => end + 1,
+      Bound::Excluded(&end) => end,
+      Bound::Unbounded => self.len_chars()
+    };
+
+    assert!(start <= end && end <= self.len_chars(), "range out of bounds");
+    self.char_to_byte(start)..self.char_to_byte(end)
+  }
+}
+
+/// Checks if the byte continues a character started by an earlier byte.
+fn is_continuation(byte: u8) -> bool {
+  byte & 0xc0 == 0x80
+}
+
+/// An iterator over the chunks of a `Rope`.
+pub struct Chunks<'a> {
+  /// Nodes still to visit, the next one on top.
+  stack: Vec<&'a Node>
+}
+
+impl<'a> Iterator for Chunks<'a> {
+  type Item = &'a str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.stack.pop()? {
+        Node::Leaf { text, .. } if text.is_empty() => {}
+        Node::Leaf { text, .. } => return Some(text),
+        Node::Branch { left, right, .. } => {
+          self.stack.push(right);
+          self.stack.push(left);
+        }
+      }
+    }
+  }
+}
+
+/// An iterator over the characters of a `Rope`.
+pub struct Chars<'a> {
+  chunks: Chunks<'a>,
+  /// The characters left in the current chunk.
+  chars: str::Chars<'a>
+}
+
+impl Iterator for Chars<'_> {
+  type Item = char;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(c) = self.chars.next() {
+        return Some(c);
+      }
+
+      self.chars = self.chunks.next()?.chars();
+    }
+  }
+}
+
+impl From<&str> for Rope {
+  /// Creates a rope holding the text, in O(n).
+  fn from(text: &str) -> Self {
+    Self { root: Node::build(text) }
+  }
+}
+
+impl From<String> for Rope {
+  /// Creates a rope holding the text, in O(n).
+  fn from(text: String) -> Self {
+    Self::from(text.as_str())
+  }
+}
+
+impl From<&Rope> for String {
+  /// Collects the text of the rope into a string, in O(n).
+  fn from(rope: &Rope) -> Self {
+    let mut text = String::with_capacity(rope.len_bytes());
+    text.extend(rope.chunks());
+    text
+  }
+}
+
+impl From<Rope> for String {
+  /// Collects the text of the rope into a string, in O(n).
+  fn from(rope: Rope) -> Self {
+    String::from(&rope)
+  }
+}
+
+impl fmt::Display for Rope {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.chunks().try_for_each(|chunk| f.write_str(chunk))
+  }
+}
+
+impl fmt::Debug for Rope {
+  /// Formats the text as a quoted string, like `String` does.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('"')?;
+    for c in self.chars() {
+      write!(f, "{}", c.escape_debug())?;
+    }
+    f.write_char('"')
+  }
+}
+
+impl PartialEq for Rope {
+  /// Two ropes are equal if they hold the same text, however it is split
+  /// into chunks.
+  fn eq(&self, other: &Self) -> bool {
+    self.len_bytes() == other.len_bytes() && self.chunks().flat_map(str::bytes).eq(other.chunks().flat_map(str::bytes))
+  }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+  fn eq(&self, other: &str) -> bool {
+    self.len_bytes() == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+  }
+}
+
+impl PartialEq<&str> for Rope {
+  fn eq(&self, other: &&str) -> bool {
+    *self == **other
+  }
+}
+
+impl Hash for Rope {
+  /// Hashes the text in blocks of a fixed size, so equal ropes make the same
+  /// writes to the hasher however they are split into chunks. A text shorter
+  /// than a block hashes like `str`.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    let mut block = [0; 256];
+    let mut filled = 0;
+
+    for mut bytes in self.chunks().map(str::as_bytes) {
+      while !bytes.is_empty() {
+        let count = bytes.len().min(block.len() - filled);
+        block[filled..filled + count].copy_from_slice(&bytes[..count]);
+        (filled, bytes) = (filled + count, &bytes[count..]);
+
+        if filled == block.len() {
+          state.write(&block);
+          filled = 0;
+        }
+      }
+    }
+    if filled > 0 || self.is_empty() {
+      state.write(&block[..filled]);
+    }
+    state.write_u8(0xff);
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rope {
+  /// Serializes the text as a string.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rope {
+  /// Deserializes a rope from a string.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    String::deserialize(deserializer).map(Rope::from)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::collections::hash_map::DefaultHasher;
+  use std::hash::{Hash, Hasher};
+
+  use super::node::{Metrics, Node, MAX_LEAF};
+  use super::Rope;
+  use proptest::prelude::*;
+
+  /// Checks that the tree is balanced, that every branch knows the
+  /// lengths of its text, and that no leaf is too long.
+  fn check(node: &Node) -> Metrics {
+    match node {
+      Node::Leaf { text, metrics } => {
+        assert!(text.len() <= MAX_LEAF);
+        assert_eq!(*metrics, Metrics::of(text));
+        *metrics
+      }
+      Node::Branch { left, right, metrics, height } => {
+        assert!(left.height().abs_diff(right.height()) <= 1);
+        assert_eq!(*height, left.height().max(right.height()) + 1);
+        assert_eq!(*metrics, check(left) + check(right));
+        *metrics
+      }
+    }
+  }
+
+  fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  #[test]
+  fn empty_rope() {
+    let rope = Rope::new();
+
+    assert!(rope.is_empty());
+    assert_eq!(rope.len_chars(), 0);
+    assert_eq!(rope.len_lines(), 1);
+    assert_eq!(rope.chunks().count(), 0);
+    assert_eq!(rope.line_col(0), (0, 0));
+    assert_eq!(rope, "");
+  }
+
+  #[test]
+  fn from_and_to_string() {
+    let text = "The quick brown fox jumps over the lazy dog.\n".repeat(50);
+    let rope = Rope::from(text.clone());
+
+    check(&rope.root);
+    assert!(rope.root.height() > 5);
+    assert_eq!(rope.len_bytes(), text.len());
+    assert_eq!(String::from(&rope), text);
+    assert_eq!(rope.to_string(), text);
+    assert_eq!(rope.chars().collect::<String>(), text);
+  }
+
+  #[test]
+  fn insert_and_remove() {
+    let mut rope = Rope::from("0123456789");
+    rope.insert(5, "abcdefghijklmnop");
+    rope.insert(0, "<");
+    rope.insert(rope.len_chars(), ">");
+    assert_eq!(rope, "<01234abcdefghijklmnop56789>");
+
+    rope.remove(3..20);
+    assert_eq!(rope, "<01op56789>");
+    rope.remove(..=1);
+    rope.remove(8..);
+    assert_eq!(rope, "1op56789");
+    check(&rope.root);
+  }
+
+  #[test]
+  #[should_panic(expected = "range out of bounds")]
+  fn remove_out_of_bounds() {
+    Rope::from("abc").remove(2..4);
+  }
+
+  #[test]
+  fn slice_append_and_split_off() {
+    let mut rope = Rope::from("Hello, wonderful world");
+    let slice = rope.slice(7..16);
+    assert_eq!(slice, "wonderful");
+
+    let mut tail = rope.split_off(5);
+    assert_eq!(rope, "Hello");
+    assert_eq!(tail, ", wonderful world");
+
+    tail.append(Rope::from("!"));
+    rope.append(tail);
+    assert_eq!(rope, "Hello, wonderful world!");
+    assert_eq!(slice, "wonderful");
+    check(&rope.root);
+  }
+
+  #[test]
+  fn char_and_byte_indexing() {
+    // 'é' takes two bytes, '€' three and '🦀' four.
+    let rope = Rope::from("aé€🦀b".repeat(4));
+
+    assert_eq!(rope.len_chars(), 20);
+    assert_eq!(rope.len_bytes(), 44);
+    assert_eq!(rope.char(3), '🦀');
+    assert_eq!(rope.char(19), 'b');
+    assert_eq!(rope.byte(1), 0xc3);
+
+    assert_eq!(rope.char_to_byte(2), 3);
+    assert_eq!(rope.char_to_byte(5), 11);
+    assert_eq!(rope.char_to_byte(20), 44);
+    assert_eq!(rope.byte_to_char(11), 5);
+    // A byte in the middle of '🦀' belongs to it.
+    assert_eq!(rope.byte_to_char(8), 3);
+    assert_eq!(rope.byte_to_char(44), 20);
+
+    for index in 0..=20 {
+      assert_eq!(rope.byte_to_char(rope.char_to_byte(index)), index);
+    }
+  }
+
+  #[test]
+  fn lines() {
+    let rope = Rope::from("first\nsécond line\n\nlast");
+
+    assert_eq!(rope.len_lines(), 4);
+    assert_eq!(rope.line_to_char(1), 6);
+    assert_eq!(rope.line_to_char(3), 19);
+    assert_eq!(rope.char_to_line(5), 0);
+    assert_eq!(rope.char_to_line(6), 1);
+    assert_eq!(rope.line_col(9), (1, 3));
+    assert_eq!(rope.line_col(rope.len_chars()), (3, 4));
+    assert_eq!(rope.line(1), "sécond line\n");
+    assert_eq!(rope.line(2), "\n");
+    assert_eq!(rope.line(3), "last");
+  }
+
+  #[test]
+  fn equality_and_hash() {
+    let built = Rope::from("abcdefghijklmnopqrstuvwxyz");
+    let mut edited = Rope::from("abcz");
+    edited.insert(3, "defghijklmnopqrstuvwxy");
+
+    assert_ne!(built.chunks().collect::<Vec<_>>(), edited.chunks().collect::<Vec<_>>());
+    assert_eq!(built, edited);
+    assert_eq!(hash(&built), hash(&edited));
+    assert_eq!(hash(&built), hash("abcdefghijklmnopqrstuvwxyz"));
+    assert_eq!(format!("{:?}", Rope::from("a\"b\n")), r#""a\"b\n""#);
+  }
+
+  /// A hasher that remembers every write, as hashers that do not stream
+  /// their input tell writes apart.
+  #[derive(Default)]
+  struct Writes(Vec<Vec<u8>>);
+
+  impl Hasher for Writes {
+    fn write(&mut self, bytes: &[u8]) {
+      self.0.push(bytes.to_vec());
+    }
+
+    fn finish(&self) -> u64 {
+      hash(&self.0)
+    }
+  }
+
+  #[test]
+  fn hash_ignores_chunks() {
+    let text: String = (0..1000).map(|i| char::from(b'a' + (i % 26) as u8)).collect();
+    let whole = Rope::from(text.as_str());
+    let mut joined = Rope::new();
+    for piece in text.as_bytes().chunks(37) {
+      joined.append(Rope::from(str::from_utf8(piece).unwrap()));
+    }
+
+    assert_ne!(whole.chunks().collect::<Vec<_>>(), joined.chunks().collect::<Vec<_>>());
+    let (mut a, mut b) = (Writes::default(), Writes::default());
+    whole.hash(&mut a);
+    joined.hash(&mut b);
+    assert_eq!(a.0, b.0);
+    assert_eq!(a.finish(), b.finish());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = Rope::from("one\ntwo");
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#""one\ntwo""#);
+
+    let rope: Rope = serde_json::from_str(&json).unwrap();
+    assert_eq!(rope, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(usize, String),
+    Remove(usize, usize),
+    Slice(usize, usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      (any::<usize>(), "[a-cé€\n]{0,20}").prop_map(|(index, text)| Operation::Insert(index, text)),
+      (any::<usize>(), any::<usize>()).prop_map(|(start, end)| Operation::Remove(start, end)),
+      (any::<usize>(), any::<usize>()).prop_map(|(start, end)| Operation::Slice(start, end))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_string(operations in prop::collection::vec(operation(), 0..60)) {
+      let mut rope = Rope::new();
+      let mut model: Vec<char> = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(index, text) => {
+            let index = index % (model.len() + 1);
+            rope.insert(index, &text);
+            model.splice(index..index, text.chars());
+          }
+          Operation::Remove(start, end) | Operation::Slice(start, end) if model.is_empty() => {
+            let _ = (start, end);
+          }
+          Operation::Remove(start, end) => {
+            let (start, end) = (start % model.len(), end % model.len());
+            let range = start.min(end)..start.max(end);
+            rope.remove(range.clone());
+            model.drain(range);
+          }
+          Operation::Slice(start, end) => {
+            let (start, end) = (start % model.len(), end % model.len());
+            let range = start.min(end)..start.max(end);
+            prop_assert_eq!(rope.slice(range.clone()).to_string(), model[range].iter().collect::<String>());
+          }
+        }
+
+        check(&rope.root);
+        let text: String = model.iter().collect();
+        prop_assert_eq!(rope.to_string(), text.clone());
+        prop_assert_eq!(rope.len_chars(), model.len());
+        prop_assert_eq!(rope.len_lines(), text.split('\n').count());
+
+        for index in 0..=model.len() {
+          let line = model[..index].iter().filter(|&&c| c == '\n').count();
+          let column = model[..index].iter().rev().take_while(|&&c| c != '\n').count();
+          prop_assert_eq!(rope.line_col(index), (line, column));
+          prop_assert_eq!(rope.char_to_byte(index), model[..index].iter().map(|c| c.len_utf8()).sum::<usize>());
+        }
+      }
+    }
+  }
+}
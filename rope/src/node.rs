@@ -0,0 +1,185 @@
+use std::ops::Add;
+use std::sync::Arc;
+
+
+/// Maximum number of bytes of a leaf.
+///
+/// Tests use tiny leaves so that short texts already build deep trees.
+pub(crate) const MAX_LEAF: usize = if cfg!(test) { 8 } else { 1024 };
+
+/// The lengths of a text in every unit a rope can be indexed by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Metrics {
+  pub(crate) bytes: usize,
+  pub(crate) chars: usize,
+  /// Number of `'\n'` characters.
+  pub(crate) newlines: usize
+}
+
+impl Metrics {
+  pub(crate) fn of(text: &str) -> Self {
+    Self { bytes: text.len(), chars: text.chars().count(), newlines: text.bytes().filter(|&byte| byte == b'\n').count() }
+  }
+}
+
+impl Add for Metrics {
+  type Output = Self;
+
+  fn add(self, other: Self) -> Self {
+    Self { bytes: self.bytes + other.bytes, chars: self.chars + other.chars, newlines: self.newlines + other.newlines }
+  }
+}
+
+/// A node of the tree of a rope, possibly shared by several ropes.
+///
+/// The tree is an AVL tree: the heights of the two children of a branch
+/// differ by at most one, so its height stays O(log n).
+pub(crate) enum Node {
+  Leaf { text: String, metrics: Metrics },
+  Branch { left: Arc<Node>, right: Arc<Node>, metrics: Metrics, height: usize }
+}
+
+impl Node {
+  pub(crate) fn leaf(text: String) -> Arc<Self> {
+    Arc::new(Node::Leaf { metrics: Metrics::of(&text), text })
+  }
+
+  /// Returns a branch over the two nodes, which must be balanced.
+  fn branch(left: Arc<Self>, right: Arc<Self>) -> Arc<Self> {
+    let (metrics, height) = (left.metrics() + right.metrics(), left.height().max(right.height()) + 1);
+    Arc::new(Node::Branch { left, right, metrics, height })
+  }
+
+  pub(crate) fn metrics(&self) -> Metrics {
+    match self {
+      Node::Leaf { metrics, .. } | Node::Branch { metrics, .. } => *metrics
+    }
+  }
+
+  pub(crate) fn height(&self) -> usize {
+    match self {
+      Node::Leaf { .. } => 0,
+      Node::Branch { height, .. } => *height
+    }
+  }
+
+  /// Builds a balanced tree over the text, cut into leaves at character
+  /// boundaries.
+  pub(crate) fn build(text: &str) -> Arc<Self> {
+    let mut nodes = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+      let mut end = rest.len().min(MAX_LEAF);
+      while !rest.is_char_boundary(end) {
+        end -= 1;
+      }
+
+      nodes.push(Node::leaf(rest[..end].to_owned()));
+      rest = &rest[end..];
+    }
+
+    if nodes.is_empty() {
+      return Node::leaf(String::new());
+    }
+
+    build_from(nodes)
+  }
+}
+
+/// Joins the nodes pairwise, level by level, until a single root is left.
+fn build_from(mut nodes: Vec<Arc<Node>>) -> Arc<Node> {
+  while nodes.len() > 1 {
+    let mut paired = Vec::with_capacity(nodes.len().div_ceil(2));
+    let mut iter = nodes.into_iter();
+
+    while let Some(left) = iter.next() {
+      paired.push(match iter.next() {
+        Some(right) => join(left, right),
+        None => left
+      });
+    }
+
+    nodes = paired;
+  }
+
+  nodes.pop().expect("there is at least one node")
+}
+
+/// Returns a balanced tree holding the text of `left` followed by the text
+/// of `right`, in O(|height(left) - height(right)|).
+pub(crate) fn join(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+  if left.metrics().bytes == 0 {
+    return right;
+  }
+  if right.metrics().bytes == 0 {
+    return left;
+  }
+
+  if let (Node::Leaf { text: a, .. }, Node::Leaf { text: b, .. }) = (&*left, &*right) {
+    if a.len() + b.len() <= MAX_LEAF {
+      return Node::leaf([a.as_str(), b.as_str()].concat());
+    }
+  }
+
+  let (left_height, right_height) = (left.height(), right.height());
+
+  if left_height > right_height + 1 {
+    let Node::Branch { left: first, right: second, .. } = &*left else { unreachable!("a higher node is a branch") };
+    balance(Arc::clone(first), join(Arc::clone(second), right))
+  } else if right_height > left_height + 1 {
+    let Node::Branch { left: first, right: second, .. } = &*right else { unreachable!("a higher node is a branch") };
+    balance(join(left, Arc::clone(first)), Arc::clone(second))
+  } else {
+    Node::branch(left, right)
+  }
+}
+
+/// Returns a branch over two nodes whose heights differ by at most two,
+/// rotating them if they differ by two.
+fn balance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+  let (left_height, right_height) = (left.height(), right.height());
+
+  if left_height > right_height + 1 {
+    let Node::Branch { left: a, right: b, .. } = &*left else { unreachable!("a higher node is a branch") };
+
+    if a.height() >= b.height() {
+      return Node::branch(Arc::clone(a), Node::branch(Arc::clone(b), right));
+    }
+
+    let Node::Branch { left: b1, right: b2, .. } = &**b else { unreachable!("a higher node is a branch") };
+    Node::branch(Node::branch(Arc::clone(a), Arc::clone(b1)), Node::branch(Arc::clone(b2), right))
+  } else if right_height > left_height + 1 {
+    let Node::Branch { left: a, right: b, .. } = &*right else { unreachable!("a higher node is a branch") };
+
+    if b.height() >= a.height() {
+      return Node::branch(Node::branch(left, Arc::clone(a)), Arc::clone(b));
+    }
+
+    let Node::Branch { left: a1, right: a2, .. } = &**a else { unreachable!("a higher node is a branch") };
+    Node::branch(Node::branch(left, Arc::clone(a1)), Node::branch(Arc::clone(a2), Arc::clone(b)))
+  } else {
+    Node::branch(left, right)
+  }
+}
+
+/// Splits the tree at the byte offset, which must be a character boundary,
+/// into the text before it and the text after it.
+pub(crate) fn split(node: &Arc<Node>, at: usize) -> (Arc<Node>, Arc<Node>) {
+  match &**node {
+    _ if at == 0 => (Node::leaf(String::new()), Arc::clone(node)),
+    _ if at == node.metrics().bytes => (Arc::clone(node), Node::leaf(String::new())),
+    Node::Leaf { text, .. } => (Node::leaf(text[..at].to_owned()), Node::leaf(text[at..].to_owned())),
+    Node::Branch { left, right, .. } => {
+      let middle = left.metrics().bytes;
+
+      if at <= middle {
+        let (first, second) = split(left, at);
+        (first, join(second, Arc::clone(right)))
+      } else {
+        let (first, second) = split(right, at - middle);
+        (join(Arc::clone(left), first), second)
+      }
+    }
+  }
+}
@@ -0,0 +1,354 @@
+use std::marker::PhantomData;
+use std::ops::{Add, Range, RangeBounds};
+
+use crate::bounds;
+
+
+/// Describes the values and the range updates of a `LazySegmentTree`.
+pub trait LazyOperation {
+  /// The type of the elements and of the query results.
+  type Value: Clone;
+  /// The type of the updates applied to ranges of elements.
+  type Update: Clone;
+
+  /// Combines two adjacent values, must be associative.
+  fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+
+  /// Applies an update to the combination of `len` elements.
+  fn apply(value: &Self::Value, update: &Self::Update, len: usize) -> Self::Value;
+
+  /// Composes two updates into one that has the effect of applying
+  /// `first` and then `second`.
+  fn compose(first: &Self::Update, second: &Self::Update) -> Self::Update;
+}
+
+/// Marker type for a tree answering range sums, where an update adds a
+/// value to every element of a range.
+pub struct AddSum<T>(PhantomData<T>);
+
+/// Marker type for a tree answering range minimums, where an update adds
+/// a value to every element of a range.
+pub struct AddMin<T>(PhantomData<T>);
+
+/// Marker type for a tree answering range maximums, where an update adds
+/// a value to every element of a range.
+pub struct AddMax<T>(PhantomData<T>);
+
+impl<T: Clone + Add<Output = T>> LazyOperation for AddSum<T> {
+  type Value = T;
+  type Update = T;
+
+  fn combine(left: &T, right: &T) -> T {
+    left.clone() + right.clone()
+  }
+
+  fn apply(value: &T, update: &T, len: usize) -> T {
+    value.clone() + times(update, len)
+  }
+
+  fn compose(first: &T, second: &T) -> T {
+    first.clone() + second.clone()
+  }
+}
+
+impl<T: Clone + Ord + Add<Output = T>> LazyOperation for AddMin<T> {
+  type Value = T;
+  type Update = T;
+
+  fn combine(left: &T, right: &T) -> T {
+    left.min(right).clone()
+  }
+
+  fn apply(value: &T, update: &T, _len: usize) -> T {
+    value.clone() + update.clone()
+  }
+
+  fn compose(first: &T, second: &T) -> T {
+    first.clone() + second.clone()
+  }
+}
+
+impl<T: Clone + Ord + Add<Output = T>> LazyOperation for AddMax<T> {
+  type Value = T;
+  type Update = T;
+
+  fn combine(left: &T, right: &T) -> T {
+    left.max(right).clone()
+  }
+
+  fn apply(value: &T, update: &T, _len: usize) -> T {
+    value.clone() + update.clone()
+  }
+
+  fn compose(first: &T, second: &T) -> T {
+    first.clone() + second.clone()
+  }
+}
+
+/// Adds a value to itself `count` times by doubling, in O(log count).
+///
+/// `count` is never zero since an update always covers some elements.
+fn times<T: Clone + Add<Output = T>>(value: &T, count: usize) -> T {
+  let mut result: Option<T> = None;
+  let mut power = value.clone();
+  let mut count = count;
+
+  loop {
+    if count % 2 == 1 {
+      result = Some(match result {
+        Some(result) => result + power.clone(),
+        None => power.clone()
+      });
+    }
+
+    count /= 2;
+    if count == 0 {
+      return result.expect("count must be positive");
+    }
+    power = power.clone() + power;
+  }
+}
+
+
+/// A segment tree with lazy propagation, supporting both range queries
+/// and range updates in O(log n).
+///
+/// An update covering a whole block of elements is applied to the block
+/// and recorded as pending for its children, and only pushed down when a
+/// later update splits the block. The operation is described by a type
+/// implementing `LazyOperation`, such as `AddSum`, `AddMin` or `AddMax`.
+///
+/// # Example
+///
+/// ```
+/// use segment_tree::{AddSum, LazySegmentTree};
+///
+/// let mut tree = LazySegmentTree::<AddSum<i64>>::build(&[1, 2, 3, 4, 5]);
+/// tree.update(1..4, 10);
+///
+/// assert_eq!(tree.query(..), Some(45));
+/// assert_eq!(tree.query(3..), Some(19));
+/// ```
+pub struct LazySegmentTree<O: LazyOperation> {
+  /// Combination of every block, the children of the node `i` are the
+  /// nodes `2 * i + 1` and `2 * i + 2`.
+  values: Vec<Option<O::Value>>,
+  /// Updates already applied to a block but not yet to its children.
+  pending: Vec<Option<O::Update>>,
+  len: usize
+}
+
+impl<O: LazyOperation> LazySegmentTree<O> {
+  /// Builds a lazy segment tree over the values, in O(n).
+  pub fn build(values: &[O::Value]) -> Self {
+    let len = values.len();
+    let mut tree = Self {
+      values: vec![None; 4 * len],
+      pending: vec![None; 4 * len],
+      len
+    };
+
+    if len > 0 {
+      tree.build_node(0, 0..len, values);
+    }
+
+    tree
+  }
+
+  /// Returns the number of elements.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the tree has no elements.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Applies an update to every element in the range, in O(log n).
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is out of bounds.
+  pub fn update<R: RangeBounds<usize>>(&mut self, range: R, update: O::Update) {
+    let range = bounds(range, self.len);
+
+    if !range.is_empty() {
+      self.update_node(0, 0..self.len, &range, &update);
+    }
+  }
+
+  /// Combines the elements in the range from left to right, in O(log n).
+  /// Returns `None` for an empty range.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is out of bounds.
+  pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Option<O::Value> {
+    let range = bounds(range, self.len);
+
+    if range.is_empty() {
+      None
+    } else {
+      Some(self.query_node(0, 0..self.len, &range, None))
+    }
+  }
+
+  fn build_node(&mut self, node: usize, span: Range<usize>, values: &[O::Value]) {
+    let value = if span.len() == 1 {
+      values[span.start].clone()
+    } else {
+      let middle = span.start + span.len() / 2;
+      self.build_node(2 * node + 1, span.start..middle, values);
+      self.build_node(2 * node + 2, middle..span.end, values);
+      self.combined(node)
+    };
+
+    self.values[node] = Some(value);
+  }
+
+  fn update_node(&mut self, node: usize, span: Range<usize>, range: &Range<usize>, update: &O::Update) {
+    if range.end <= span.start || span.end <= range.start {
+      return;
+    }
+
+    if range.start <= span.start && span.end <= range.end {
+      self.apply(node, span.len(), update);
+      return;
+    }
+
+    let middle = span.start + span.len() / 2;
+    self.push(node, span.clone(), middle);
+    self.update_node(2 * node + 1, span.start..middle, range, update);
+    self.update_node(2 * node + 2, middle..span.end, range, update);
+    self.values[node] = Some(self.combined(node));
+  }
+
+  /// Combines the blocks covered by the range, applying `carry`, the
+  /// updates of the ancestors that were not pushed down to this node.
+  fn query_node(&self, node: usize, span: Range<usize>, range: &Range<usize>, carry: Option<&O::Update>) -> O::Value {
+    let value = self.values[node].as_ref().expect("segment node is missing");
+
+    if range.start <= span.start && span.end <= range.end {
+      return match carry {
+        Some(update) => O::apply(value, update, span.len()),
+        None => value.clone()
+      };
+    }
+
+    let carry = match (&self.pending[node], carry) {
+      (Some(pending), Some(carry)) => Some(O::compose(pending, carry)),
+      (pending, carry) => pending.clone().or_else(|| carry.cloned())
+    };
+
+    let middle = span.start + span.len() / 2;
+    let left = (range.start < middle).then(|| self.query_node(2 * node + 1, span.start..middle, range, carry.as_ref()));
+    let right = (middle < range.end).then(|| self.query_node(2 * node + 2, middle..span.end, range, carry.as_ref()));
+
+    match (left, right) {
+      (Some(left), Some(right)) => O::combine(&left, &right),
+      (left, right) => left.or(right).expect("range must intersect the node")
+    }
+  }
+
+  /// Applies an update to the block of a node and records it as pending
+  /// for its children.
+  fn apply(&mut self, node: usize, len: usize, update: &O::Update) {
+    let value = self.values[node].as_ref().expect("segment node is missing");
+    self.values[node] = Some(O::apply(value, update, len));
+
+    if len > 1 {
+      self.pending[node] = Some(match &self.pending[node] {
+        Some(pending) => O::compose(pending, update),
+        None => update.clone()
+      });
+    }
+  }
+
+  /// Pushes the pending update of a node down to its children.
+  fn push(&mut self, node: usize, span: Range<usize>, middle: usize) {
+    if let Some(update) = self.pending[node].take() {
+      self.apply(2 * node + 1, middle - span.start, &update);
+      self.apply(2 * node + 2, span.end - middle, &update);
+    }
+  }
+
+  fn combined(&self, node: usize) -> O::Value {
+    let left = self.values[2 * node + 1].as_ref().expect("segment node is missing");
+    let right = self.values[2 * node + 2].as_ref().expect("segment node is missing");
+    O::combine(left, right)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{AddMax, AddMin, AddSum, LazySegmentTree};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_tree() {
+    let mut tree = LazySegmentTree::<AddSum<i32>>::build(&[]);
+    tree.update(.., 1);
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.query(..), None);
+  }
+
+  #[test]
+  fn range_add_sum() {
+    let mut tree = LazySegmentTree::<AddSum<i32>>::build(&[1, 2, 3, 4, 5]);
+    tree.update(1..4, 10);
+    tree.update(..2, -1);
+
+    assert_eq!(tree.len(), 5);
+    assert_eq!(tree.query(..), Some(43));
+    assert_eq!(tree.query(0..1), Some(0));
+    assert_eq!(tree.query(1..3), Some(24));
+    assert_eq!(tree.query(4..), Some(5));
+  }
+
+  #[test]
+  fn range_add_min_max() {
+    let values = [4, 1, 6, 3];
+    let mut mins = LazySegmentTree::<AddMin<i32>>::build(&values);
+    let mut maxes = LazySegmentTree::<AddMax<i32>>::build(&values);
+    mins.update(..2, 5);
+    maxes.update(2.., -4);
+
+    assert_eq!(mins.query(..), Some(3));
+    assert_eq!(mins.query(..2), Some(6));
+    assert_eq!(maxes.query(..), Some(4));
+    assert_eq!(maxes.query(2..), Some(2));
+  }
+
+  proptest! {
+    #[test]
+    fn matches_naive_range_updates(
+      values in prop::collection::vec(-100..100i64, 1..50),
+      operations in prop::collection::vec(
+        (any::<bool>(), any::<prop::sample::Index>(), any::<prop::sample::Index>(), -100..100i64),
+        0..50
+      )
+    ) {
+      let mut values = values;
+      let mut sums = LazySegmentTree::<AddSum<i64>>::build(&values);
+      let mut maxes = LazySegmentTree::<AddMax<i64>>::build(&values);
+
+      for (is_update, a, b, delta) in operations {
+        let (a, b) = (a.index(values.len() + 1), b.index(values.len() + 1));
+        let (start, end) = (a.min(b), a.max(b));
+
+        if is_update {
+          values[start..end].iter_mut().for_each(|value| *value += delta);
+          sums.update(start..end, delta);
+          maxes.update(start..end, delta);
+        } else {
+          let slice = &values[start..end];
+          prop_assert_eq!(sums.query(start..end), slice.iter().copied().reduce(|a, b| a + b));
+          prop_assert_eq!(maxes.query(start..end), slice.iter().copied().max());
+        }
+      }
+    }
+  }
+}
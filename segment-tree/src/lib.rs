@@ -0,0 +1,288 @@
+use std::ops::{Add, Bound, Range, RangeBounds};
+
+mod lazy;
+mod monoid;
+
+pub use lazy::{AddMax, AddMin, AddSum, LazyOperation, LazySegmentTree};
+pub use monoid::{Group, Monoid, Sum};
+
+
+/// A segment tree answering range queries over a sequence.
+///
+/// The tree stores the combination of every aligned block of elements,
+/// where `combine` is any associative function: a sum, a minimum, a
+/// greatest common divisor, a string concatenation... A query over a range
+/// combines O(log n) blocks, and updating an element recomputes the O(log n)
+/// blocks that contain it. `combine` does not need to be commutative, the
+/// elements are always combined from left to right.
+///
+/// # Example
+///
+/// ```
+/// use segment_tree::SegmentTree;
+///
+/// let mut tree = SegmentTree::sum(&[5, 3, 8, 1]);
+/// assert_eq!(tree.query(1..3), Some(11));
+///
+/// tree.update(2, 2);
+/// assert_eq!(tree.query(..), Some(11));
+///
+/// let tree = SegmentTree::build(&[12, 18, 8], |a: &u32, b: &u32| gcd(*a, *b));
+/// assert_eq!(tree.query(..2), Some(6));
+///
+/// fn gcd(a: u32, b: u32) -> u32 {
+///   if b == 0 { a } else { gcd(b, a % b) }
+/// }
+/// ```
+pub struct SegmentTree<T, F = fn(&T, &T) -> T> {
+  /// Blocks of the tree: the elements are stored in the second half and
+  /// `tree[i]` combines `tree[2 * i]` and `tree[2 * i + 1]`.
+  tree: Vec<T>,
+  len: usize,
+  combine: F
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> SegmentTree<T, F> {
+  /// Builds a segment tree over the values with the given associative
+  /// function, in O(n).
+  pub fn build(values: &[T], combine: F) -> Self {
+    let len = values.len();
+    // The first half is only filled to be overwritten below, and index 0
+    // is never used.
+    let mut tree = values.to_vec();
+    tree.extend_from_slice(values);
+
+    for index in (1..len).rev() {
+      tree[index] = combine(&tree[2 * index], &tree[2 * index + 1]);
+    }
+
+    Self { tree, len, combine }
+  }
+
+  /// Returns the number of elements.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the tree has no elements.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns a reference to the element at the index, or `None` if the
+  /// index is out of bounds.
+  pub fn get(&self, index: usize) -> Option<&T> {
+    if index < self.len {
+      Some(&self.tree[self.len + index])
+    } else {
+      None
+    }
+  }
+
+  /// Replaces the element at the index, in O(log n).
+  ///
+  /// # Panics
+  ///
+  /// Panics if the index is out of bounds.
+  pub fn update(&mut self, index: usize, value: T) {
+    assert!(index < self.len, "index out of bounds");
+
+    let mut index = self.len + index;
+    self.tree[index] = value;
+
+    while index > 1 {
+      index /= 2;
+      self.tree[index] = (self.combine)(&self.tree[2 * index], &self.tree[2 * index + 1]);
+    }
+  }
+
+  /// Combines the elements in the range from left to right, in O(log n).
+  /// Returns `None` for an empty range.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is out of bounds.
+  pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Option<T> {
+    let Range { start, end } = bounds(range, self.len);
+    let (mut start, mut end) = (start + self.len, end + self.len);
+    let (mut left, mut right): (Option<T>, Option<T>) = (None, None);
+
+    while start < end {
+      if start % 2 == 1 {
+        left = Some(match left {
+          Some(left) => (self.combine)(&left, &self.tree[start]),
+          None => self.tree[start].clone()
+        });
+        start += 1;
+      }
+      if end % 2 == 1 {
+        end -= 1;
+        right = Some(match right {
+          Some(right) => (self.combine)(&self.tree[end], &right),
+          None => self.tree[end].clone()
+        });
+      }
+
+      start /= 2;
+      end /= 2;
+    }
+
+    match (left, right) {
+      (Some(left), Some(right)) => Some((self.combine)(&left, &right)),
+      (left, right) => left.or(right)
+    }
+  }
+}
+
+impl<T: Clone> SegmentTree<T> {
+  /// Builds a segment tree combining the values with a monoid.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use segment_tree::{SegmentTree, Sum};
+  ///
+  /// let tree = SegmentTree::with_monoid::<Sum<i32>>(&[1, 2, 3]);
+  /// assert_eq!(tree.query(1..), Some(5));
+  /// ```
+  pub fn with_monoid<M: Monoid<Value = T>>(values: &[T]) -> Self {
+    Self::build(values, M::combine)
+  }
+}
+
+impl<T: Clone + Add<Output = T>> SegmentTree<T> {
+  /// Builds a segment tree answering range sums.
+  pub fn sum(values: &[T]) -> Self {
+    Self::build(values, |a, b| a.clone() + b.clone())
+  }
+}
+
+impl<T: Clone + Ord> SegmentTree<T> {
+  /// Builds a segment tree answering range minimums.
+  pub fn min(values: &[T]) -> Self {
+    Self::build(values, |a, b| a.min(b).clone())
+  }
+
+  /// Builds a segment tree answering range maximums.
+  pub fn max(values: &[T]) -> Self {
+    Self::build(values, |a, b| a.max(b).clone())
+  }
+}
+
+/// Converts range bounds into a range of indices of a sequence of `len`
+/// elements.
+///
+/// # Panics
+///
+/// Panics if the range is out of bounds or starts after its end.
+fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+  let start = match range.start_bound() {
+    Bound::Included(&start) => start,
+    Bound::Excluded(&start) => start + 1,
+    Bound::Unbounded => 0
+  };
+  let end = match range.end_bound() {
+    Bound::Included(&end) => end + 1,
+    Bound::Excluded(&end) => end,
+    Bound::Unbounded => len
+  };
+
+  assert!(start <= end && end <= len, "range out of bounds");
+  start..end
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{SegmentTree, Sum};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_tree() {
+    let tree = SegmentTree::<i32>::sum(&[]);
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.query(..), None);
+    assert_eq!(tree.get(0), None);
+  }
+
+  #[test]
+  fn sum_min_max() {
+    let values = [5, 3, 8, 1, 9, 2];
+
+    assert_eq!(SegmentTree::sum(&values).query(1..4), Some(12));
+    assert_eq!(SegmentTree::min(&values).query(..3), Some(3));
+    assert_eq!(SegmentTree::max(&values).query(3..=5), Some(9));
+  }
+
+  #[test]
+  fn empty_range() {
+    let tree = SegmentTree::sum(&[1, 2, 3]);
+
+    assert_eq!(tree.query(2..2), None);
+  }
+
+  #[test]
+  fn update() {
+    let mut tree = SegmentTree::min(&[4, 2, 7]);
+    tree.update(1, 9);
+
+    assert_eq!(tree.get(1), Some(&9));
+    assert_eq!(tree.query(..), Some(4));
+  }
+
+  #[test]
+  fn combines_from_left_to_right() {
+    let words: Vec<String> = ["a", "b", "c", "d", "e"].iter().map(|word| word.to_string()).collect();
+    let mut tree = SegmentTree::build(&words, |a: &String, b: &String| format!("{a}{b}"));
+
+    assert_eq!(tree.query(..).as_deref(), Some("abcde"));
+    assert_eq!(tree.query(1..4).as_deref(), Some("bcd"));
+
+    tree.update(2, "x".to_string());
+    assert_eq!(tree.query(..).as_deref(), Some("abxde"));
+  }
+
+  #[test]
+  fn with_monoid() {
+    let mut tree = SegmentTree::with_monoid::<Sum<u64>>(&[2, 4, 6]);
+    tree.update(0, 10);
+
+    assert_eq!(tree.query(..2), Some(14));
+  }
+
+  #[test]
+  #[should_panic(expected = "range out of bounds")]
+  fn range_out_of_bounds() {
+    SegmentTree::sum(&[1, 2, 3]).query(1..4);
+  }
+
+  proptest! {
+    #[test]
+    fn matches_naive_queries(
+      values in prop::collection::vec(-100..100i64, 1..50),
+      updates in prop::collection::vec((any::<prop::sample::Index>(), -100..100i64), 0..20),
+      queries in prop::collection::vec((any::<prop::sample::Index>(), any::<prop::sample::Index>()), 1..20)
+    ) {
+      let mut values = values;
+      let mut sums = SegmentTree::sum(&values);
+      let mut mins = SegmentTree::min(&values);
+
+      for (index, value) in updates {
+        let index = index.index(values.len());
+        values[index] = value;
+        sums.update(index, value);
+        mins.update(index, value);
+      }
+
+      for (a, b) in queries {
+        let (a, b) = (a.index(values.len() + 1), b.index(values.len() + 1));
+        let (start, end) = (a.min(b), a.max(b));
+        let slice = &values[start..end];
+
+        prop_assert_eq!(sums.query(start..end), slice.iter().copied().reduce(|a, b| a + b));
+        prop_assert_eq!(mins.query(start..end), slice.iter().copied().min());
+      }
+    }
+  }
+}
@@ -0,0 +1,46 @@
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
+
+
+/// An associative operation with an identity element.
+///
+/// Describes how the elements of range query structures such as
+/// `SegmentTree` and `FenwickTree` are combined.
+pub trait Monoid {
+  /// The type of the combined values.
+  type Value: Clone;
+
+  /// Returns the identity element, which leaves any value unchanged when
+  /// combined with it.
+  fn identity() -> Self::Value;
+
+  /// Combines two values, must be associative.
+  fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+}
+
+/// A monoid where every combination can be undone.
+pub trait Group: Monoid {
+  /// Returns the value that combined with `right` gives `left`.
+  fn difference(left: &Self::Value, right: &Self::Value) -> Self::Value;
+}
+
+/// Marker type for the monoid of values under addition.
+pub struct Sum<T>(PhantomData<T>);
+
+impl<T: Clone + Default + Add<Output = T>> Monoid for Sum<T> {
+  type Value = T;
+
+  fn identity() -> T {
+    T::default()
+  }
+
+  fn combine(left: &T, right: &T) -> T {
+    left.clone() + right.clone()
+  }
+}
+
+impl<T: Clone + Default + Add<Output = T> + Sub<Output = T>> Group for Sum<T> {
+  fn difference(left: &T, right: &T) -> T {
+    left.clone() - right.clone()
+  }
+}
@@ -0,0 +1,646 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+use random::SplitMix64;
+use slab::Slab;
+
+
+/// An ordered map based on a skip list.
+///
+/// The entries are kept sorted in a linked list, and every node is also
+/// linked into a random number of express lanes above it: a node reaches
+/// the next level with a fixed probability, so every level holds a fraction
+/// of the nodes of the level below. Searches start on the highest level and
+/// drop down a level whenever the next node would overshoot, which gives an
+/// expected O(log n) cost for searching, inserting and removing.
+///
+/// Nodes are stored in a `Slab` and linked by their keys, so the
+/// implementation is entirely safe.
+///
+/// # Example
+///
+/// ```
+/// use skiplist::SkipList;
+///
+/// let mut list = SkipList::new();
+/// list.insert(3, "three");
+/// list.insert(1, "one");
+/// list.insert(2, "two");
+///
+/// assert_eq!(list.get(&2), Some(&"two"));
+/// assert_eq!(list.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// assert_eq!(list.range(2..).map(|(key, _)| *key).collect::<Vec<_>>(), vec![2, 3]);
+///
+/// assert_eq!(list.remove(&1), Some("one"));
+/// assert_eq!(list.len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct SkipList<K, V> {
+  nodes: Slab<Node<K, V>>,
+  /// First node of every level.
+  head: Vec<Link>,
+  /// Number of levels that currently contain nodes.
+  level: usize,
+  probability: f64,
+  rng: SplitMix64
+}
+
+/// Key of the next node in a level, `None` marks the end of the level.
+type Link = Option<usize>;
+
+#[derive(Clone)]
+struct Node<K, V> {
+  key: K,
+  value: V,
+  /// Next node in every level the node belongs to, from the bottom up.
+  forward: Vec<Link>
+}
+
+/// Parameters of a `SkipList`.
+///
+/// # Example
+///
+/// ```
+/// use skiplist::{Config, SkipList};
+///
+/// let list = SkipList::<i32, i32>::with_config(Config { seed: Some(42), ..Config::default() });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+  /// Probability that a node also belongs to the next level, 0.5 by default.
+  pub probability: f64,
+  /// Maximum number of levels, 16 by default.
+  pub max_level: usize,
+  /// Seed of the random number generator choosing the levels of the
+  /// nodes. `None`, the default, picks a random seed, while a fixed seed
+  /// makes the shape of the list reproducible.
+  pub seed: Option<u64>
+}
+
+impl Default for Config {
+  /// Creates a new instance of `Config` with default values.
+  fn default() -> Self {
+    Self { probability: 0.5, max_level: 16, seed: None }
+  }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+  /// Creates a new instance of `SkipList` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+  /// Creates a new empty skip list with the default configuration.
+  pub fn new() -> Self {
+    Self::with_config(Config::default())
+  }
+
+  /// Creates a new empty skip list with the given configuration.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the probability is not strictly between 0 and 1,
+  /// or if the maximum level is zero.
+  pub fn with_config(config: Config) -> Self {
+    assert!(config.probability > 0.0 && config.probability < 1.0, "probability must be between 0 and 1");
+    assert!(config.max_level > 0, "max level must be positive");
+
+    Self {
+      nodes: Slab::new(),
+      head: vec![None; config.max_level],
+      level: 0,
+      probability: config.probability,
+      rng: SplitMix64::new(config.seed)
+    }
+  }
+
+  /// Returns the number of entries in the list.
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Checks if the list is empty.
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// Inserts a key-value pair into the list.
+  ///
+  /// If the list already contained the key, its value is replaced and
+  /// the old value is returned.
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    let update = self.predecessors(|other| *other < key);
+
+    if let Some(next) = self.next(update[0], 0) {
+      let node = self.node_mut(next);
+      if node.key == key {
+        return Some(mem::replace(&mut node.value, value));
+      }
+    }
+
+    let level = self.random_level();
+    self.level = self.level.max(level);
+
+    let forward = (0..level).map(|level| self.next(update[level], level)).collect();
+    let index = self.nodes.insert(Node { key, value, forward });
+
+    for (level, &previous) in update.iter().enumerate().take(level) {
+      self.set_next(previous, level, Some(index));
+    }
+
+    None
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the list
+  /// does not contain the key.
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.find(key).map(|index| &self.node(index).value)
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if
+  /// the list does not contain the key.
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.find(key).map(|index| &mut self.node_mut(index).value)
+  }
+
+  /// Checks if the list contains the key.
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self.find(key).is_some()
+  }
+
+  /// Removes the key from the list and returns its value,
+  /// or `None` if the list did not contain the key.
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    let update = self.predecessors(|other| other.borrow() < key);
+    let index = self.next(update[0], 0).filter(|&index| self.node(index).key.borrow() == key)?;
+
+    let node = self.nodes.remove(index).expect("linked node is missing");
+    for (level, &next) in node.forward.iter().enumerate() {
+      self.set_next(update[level], level, next);
+    }
+
+    while self.level > 0 && self.head[self.level - 1].is_none() {
+      self.level -= 1;
+    }
+
+    Some(node.value)
+  }
+
+  /// Returns the entry with the smallest key, or `None` if the list is empty.
+  pub fn first(&self) -> Option<(&K, &V)> {
+    self.head[0].map(|index| self.entry(index))
+  }
+
+  /// Returns the entry with the largest key, or `None` if the list is empty.
+  pub fn last(&self) -> Option<(&K, &V)> {
+    let mut current = None;
+
+    for level in (0..self.level).rev() {
+      while let Some(next) = self.next(current, level) {
+        current = Some(next);
+      }
+    }
+
+    current.map(|index| self.entry(index))
+  }
+
+  /// Returns an iterator over the entries whose keys fall in the range,
+  /// in ascending order of keys.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use skiplist::SkipList;
+  ///
+  /// let list: SkipList<i32, ()> = (1..=10).map(|key| (key, ())).collect();
+  ///
+  /// assert_eq!(list.range(3..6).map(|(key, _)| *key).collect::<Vec<_>>(), vec![3, 4, 5]);
+  /// assert_eq!(list.range(..=2).map(|(key, _)| *key).collect::<Vec<_>>(), vec![1, 2]);
+  /// ```
+  pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+  {
+    let start = match range.start_bound() {
+      Bound::Included(start) => self.first_after(|key| key.borrow() < start),
+      Bound::Excluded(start) => self.first_after(|key| key.borrow() <= start),
+      Bound::Unbounded => self.head[0]
+    };
+    let end = match range.end_bound() {
+      Bound::Included(end) => self.first_after(|key| key.borrow() <= end),
+      Bound::Excluded(end) => self.first_after(|key| key.borrow() < end),
+      Bound::Unbounded => None
+    };
+
+    // An empty range may start after its end, in which case the
+    // iterator would never reach `end`.
+    let next = start.filter(|&index| range.contains(self.node(index).key.borrow()));
+
+    Range { list: self, next, end }
+  }
+
+  /// Returns the index of the node with the key.
+  fn find<Q>(&self, key: &Q) -> Option<usize>
+  where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+  {
+    self
+      .first_after(|other| other.borrow() < key)
+      .filter(|&index| self.node(index).key.borrow() == key)
+  }
+
+  /// Returns the first node whose key does not satisfy `before`, which must
+  /// hold for a prefix of the keys.
+  fn first_after(&self, before: impl Fn(&K) -> bool) -> Link {
+    let update = self.predecessors(before);
+    self.next(update[0], 0)
+  }
+
+  /// Returns, for every level, the last node whose key satisfies `before`,
+  /// where `None` stands for the head of the level.
+  fn predecessors(&self, before: impl Fn(&K) -> bool) -> Vec<Link> {
+    let mut update = vec![None; self.head.len()];
+    let mut current = None;
+
+    for level in (0..self.level).rev() {
+      while let Some(next) = self.next(current, level) {
+        if !before(&self.node(next).key) {
+          break;
+        }
+        current = Some(next);
+      }
+      update[level] = current;
+    }
+
+    update
+  }
+
+  /// Picks the number of levels of a new node.
+  fn random_level(&mut self) -> usize {
+    let mut level = 1;
+
+    while level < self.head.len() && self.rng.next_f64() < self.probability {
+      level += 1;
+    }
+
+    level
+  }
+}
+
+impl<K, V> SkipList<K, V> {
+  /// Returns an iterator over the entries of the list in ascending order of keys.
+  pub fn iter(&self) -> Range<'_, K, V> {
+    Range { list: self, next: self.head[0], end: None }
+  }
+
+  /// Returns an iterator over the keys of the list in ascending order.
+  pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+    self.iter().map(|(key, _)| key)
+  }
+
+  /// Returns an iterator over the values of the list in ascending order of keys.
+  pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+    self.iter().map(|(_, value)| value)
+  }
+
+  /// Removes all entries from the list.
+  pub fn clear(&mut self) {
+    self.nodes.clear();
+    self.head.fill(None);
+    self.level = 0;
+  }
+
+  fn node(&self, index: usize) -> &Node<K, V> {
+    self.nodes.get(index).expect("linked node is missing")
+  }
+
+  fn node_mut(&mut self, index: usize) -> &mut Node<K, V> {
+    self.nodes.get_mut(index).expect("linked node is missing")
+  }
+
+  fn entry(&self, index: usize) -> (&K, &V) {
+    let node = self.node(index);
+    (&node.key, &node.value)
+  }
+
+  /// Returns the node following `link` in the level.
+  fn next(&self, link: Link, level: usize) -> Link {
+    match link {
+      Some(index) => self.node(index).forward[level],
+      None => self.head[level]
+    }
+  }
+
+  /// Makes `next` follow `link` in the level.
+  fn set_next(&mut self, link: Link, level: usize, next: Link) {
+    match link {
+      Some(index) => self.node_mut(index).forward[level] = next,
+      None => self.head[level] = next
+    }
+  }
+}
+
+/// An iterator over a range of entries of a `SkipList`, in ascending
+/// order of keys.
+pub struct Range<'a, K, V> {
+  list: &'a SkipList<K, V>,
+  next: Link,
+  /// First node after the range, or `None` if the range is not bounded.
+  end: Link
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let index = self.next.filter(|&index| Some(index) != self.end)?;
+    self.next = self.list.node(index).forward[0];
+    Some(self.list.entry(index))
+  }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipList<K, V> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Range<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipList<K, V> {
+  /// Creates a skip list from key-value pairs. Later pairs overwrite
+  /// earlier pairs with the same key.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut list = Self::new();
+    list.extend(iter);
+    list
+  }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipList<K, V> {
+  fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+    for (key, value) in iter {
+      self.insert(key, value);
+    }
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for SkipList<K, V> {
+  fn eq(&self, other: &Self) -> bool {
+    self.nodes.len() == other.nodes.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for SkipList<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for SkipList<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for SkipList<K, V>
+where
+  K: serde::Serialize,
+  V: serde::Serialize,
+{
+  /// Serializes the entries as a map in ascending order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for SkipList<K, V>
+where
+  K: serde::Deserialize<'de> + Ord,
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a map into a skip list with the default configuration.
+  /// Later pairs overwrite earlier pairs with the same key.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+    impl<'de, K, V> serde::de::Visitor<'de> for MapVisitor<K, V>
+    where
+      K: serde::Deserialize<'de> + Ord,
+      V: serde::Deserialize<'de>,
+    {
+      type Value = SkipList<K, V>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut list = SkipList::new();
+
+        while let Some((key, value)) = access.next_entry()? {
+          list.insert(key, value);
+        }
+
+        Ok(list)
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Config, SkipList};
+  use std::collections::BTreeMap;
+  use std::ops::Bound::{self, Excluded, Included, Unbounded};
+  use proptest::prelude::*;
+
+  /// Returns the number of levels of every node in key order.
+  fn heights<K: Ord, V>(list: &SkipList<K, V>) -> Vec<usize> {
+    let mut heights = Vec::new();
+    let mut current = list.head[0];
+
+    while let Some(index) = current {
+      heights.push(list.node(index).forward.len());
+      current = list.node(index).forward[0];
+    }
+
+    heights
+  }
+
+  #[test]
+  fn empty_list() {
+    let list = SkipList::<i32, i32>::new();
+
+    assert!(list.is_empty());
+    assert_eq!(list.get(&1), None);
+    assert_eq!(list.first(), None);
+    assert_eq!(list.last(), None);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut list = SkipList::new();
+
+    assert_eq!(list.insert(2, "two"), None);
+    assert_eq!(list.insert(1, "one"), None);
+    assert_eq!(list.insert(2, "deux"), Some("two"));
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.get(&2), Some(&"deux"));
+    assert!(list.contains_key(&1));
+    assert!(!list.contains_key(&3));
+
+    *list.get_mut(&1).unwrap() = "un";
+    assert_eq!(list.first(), Some((&1, &"un")));
+    assert_eq!(list.last(), Some((&2, &"deux")));
+  }
+
+  #[test]
+  fn remove() {
+    let mut list: SkipList<i32, i32> = (0..100).map(|key| (key, key * 10)).collect();
+
+    for key in (0..100).step_by(2) {
+      assert_eq!(list.remove(&key), Some(key * 10));
+    }
+
+    assert_eq!(list.remove(&0), None);
+    assert_eq!(list.len(), 50);
+    assert!(list.keys().copied().eq((1..100).step_by(2)));
+
+    // Freed slots are reused.
+    assert_eq!(list.nodes.vacant_key(), 98);
+    list.insert(0, 0);
+    assert_eq!(list.nodes.vacant_key(), 96);
+  }
+
+  #[test]
+  fn ordered_iteration() {
+    let list: SkipList<i32, ()> = [5, 3, 8, 1, 9, 2].into_iter().map(|key| (key, ())).collect();
+
+    assert_eq!(list.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3, 5, 8, 9]);
+  }
+
+  #[test]
+  fn range() {
+    let list: SkipList<i32, ()> = (0..20).step_by(2).map(|key| (key, ())).collect();
+    let keys = |range: (Bound<i32>, Bound<i32>)| list.range(range).map(|(key, _)| *key).collect::<Vec<_>>();
+
+    assert_eq!(keys((Included(4), Excluded(10))), vec![4, 6, 8]);
+    assert_eq!(keys((Excluded(4), Included(10))), vec![6, 8, 10]);
+    assert_eq!(keys((Included(5), Unbounded)), vec![6, 8, 10, 12, 14, 16, 18]);
+    assert_eq!(keys((Unbounded, Excluded(3))), vec![0, 2]);
+    assert_eq!(keys((Included(10), Excluded(5))), Vec::<i32>::new());
+    assert_eq!(keys((Included(30), Unbounded)), Vec::<i32>::new());
+  }
+
+  #[test]
+  fn seeded_lists_have_the_same_shape() {
+    let config = Config { seed: Some(7), ..Config::default() };
+    let mut a = SkipList::with_config(config);
+    let mut b = SkipList::with_config(config);
+
+    for key in 0..200 {
+      a.insert(key, ());
+      b.insert(key, ());
+    }
+
+    assert_eq!(heights(&a), heights(&b));
+  }
+
+  #[test]
+  fn level_cap() {
+    let config = Config { probability: 0.9, max_level: 3, seed: Some(1) };
+    let mut list = SkipList::with_config(config);
+    list.extend((0..100).map(|key| (key, ())));
+
+    assert!(heights(&list).iter().all(|&height| height <= 3));
+    assert_eq!(list.level, 3);
+  }
+
+  #[test]
+  #[should_panic(expected = "probability must be between 0 and 1")]
+  fn invalid_probability() {
+    SkipList::<i32, i32>::with_config(Config { probability: 1.0, ..Config::default() });
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: SkipList<String, i32> = [("b".to_string(), 2), ("a".to_string(), 1)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"a":1,"b":2}"#);
+
+    let list: SkipList<String, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(u8, i32),
+    Remove(u8),
+    Get(u8),
+    Range(u8, u8)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => (any::<u8>(), any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      2 => any::<u8>().prop_map(Operation::Remove),
+      1 => any::<u8>().prop_map(Operation::Get),
+      1 => (any::<u8>(), any::<u8>()).prop_map(|(start, end)| Operation::Range(start, end))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_btree_map(seed in any::<u64>(), operations in prop::collection::vec(operation(), 0..300)) {
+      let mut list = SkipList::with_config(Config { seed: Some(seed), ..Config::default() });
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(list.insert(key, value), model.insert(key, value)),
+          Operation::Remove(key) => prop_assert_eq!(list.remove(&key), model.remove(&key)),
+          Operation::Get(key) => prop_assert_eq!(list.get(&key), model.get(&key)),
+          Operation::Range(start, end) => {
+            let start = start.min(end);
+            prop_assert!(list.range(start..=end).eq(model.range(start..=end)));
+          }
+        }
+
+        prop_assert_eq!(list.len(), model.len());
+      }
+
+      prop_assert!(list.iter().eq(model.iter()));
+      prop_assert_eq!(list.first(), model.first_key_value());
+      prop_assert_eq!(list.last(), model.last_key_value());
+    }
+  }
+}
@@ -0,0 +1,583 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Enumerate;
+use core::ops::{Index, IndexMut};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+
+/// A slab, a storage that hands out a stable key for every value.
+///
+/// The values are stored in a vector of slots and a key is the index of
+/// a slot, so a value can be reached from its key in O(1) and the key stays
+/// valid until the value is removed, whatever happens to the other values.
+/// A removed value leaves a vacant slot that is reused by the next
+/// insertion, so inserting and removing are O(1) as well.
+///
+/// This makes a slab a convenient backing store for linked structures,
+/// whose nodes can refer to each other by key instead of by pointer.
+///
+/// # Example
+///
+/// ```
+/// use slab::Slab;
+///
+/// let mut slab = Slab::new();
+/// let apple = slab.insert("apple");
+/// let banana = slab.insert("banana");
+///
+/// assert_eq!(slab[apple], "apple");
+/// assert_eq!(slab.remove(banana), Some("banana"));
+///
+/// // The vacant slot is reused.
+/// assert_eq!(slab.insert("cherry"), banana);
+/// assert_eq!(slab.len(), 2);
+/// ```
+pub struct Slab<T> {
+  /// Value slots, `None` marks a vacant slot listed in `free`.
+  slots: Vec<Option<T>>,
+  free: Vec<usize>,
+  len: usize
+}
+
+impl<T> Default for Slab<T> {
+  /// Creates a new instance of `Slab` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Slab<T> {
+  /// Creates a new empty slab.
+  pub fn new() -> Self {
+    Self::with_capacity(0)
+  }
+
+  /// Creates a new empty slab with room for `capacity` values before
+  /// reallocating.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      slots: Vec::with_capacity(capacity),
+      free: Vec::new(),
+      len: 0
+    }
+  }
+
+  /// Returns the number of values in the slab.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the slab is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the number of values the slab can hold without reallocating.
+  pub fn capacity(&self) -> usize {
+    self.slots.capacity()
+  }
+
+  /// Returns the key the next inserted value will get.
+  pub fn vacant_key(&self) -> usize {
+    self.free.last().copied().unwrap_or(self.slots.len())
+  }
+
+  /// Stores a value in a vacant slot and returns its key.
+  pub fn insert(&mut self, value: T) -> usize {
+    self.len += 1;
+
+    match self.free.pop() {
+      Some(key) => {
+        self.slots[key] = Some(value);
+        key
+      }
+      None => {
+        self.slots.push(Some(value));
+        self.slots.len() - 1
+      }
+    }
+  }
+
+  /// Returns a reference to the value with the key.
+  pub fn get(&self, key: usize) -> Option<&T> {
+    self.slots.get(key)?.as_ref()
+  }
+
+  /// Returns a mutable reference to the value with the key.
+  pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+    self.slots.get_mut(key)?.as_mut()
+  }
+
+  /// Returns mutable references to the values with two different keys.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the keys are equal.
+  pub fn get2_mut(&mut self, first: usize, second: usize) -> Option<(&mut T, &mut T)> {
+    assert!(first != second, "keys must be different");
+
+    let (low, high) = (first.min(second), first.max(second));
+    if high >= self.slots.len() {
+      return None;
+    }
+
+    let (left, right) = self.slots.split_at_mut(high);
+    let (low, high) = (left[low].as_mut()?, right[0].as_mut()?);
+
+    if first < second {
+      Some((low, high))
+    } else {
+      Some((high, low))
+    }
+  }
+
+  /// Checks if the slab holds a value with the key.
+  pub fn contains(&self, key: usize) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// Removes the value with the key and returns it,
+  /// or `None` if the slot is vacant.
+  pub fn remove(&mut self, key: usize) -> Option<T> {
+    let value = self.slots.get_mut(key)?.take()?;
+    self.free.push(key);
+    self.len -= 1;
+    Some(value)
+  }
+
+  /// Keeps only the values for which the predicate returns `true`.
+  pub fn retain<F: FnMut(usize, &mut T) -> bool>(&mut self, mut predicate: F) {
+    for key in 0..self.slots.len() {
+      if let Some(value) = self.slots[key].as_mut() {
+        if !predicate(key, value) {
+          self.remove(key);
+        }
+      }
+    }
+  }
+
+  /// Returns a pointer to the value with the key, or `None` if the slot
+  /// is vacant, without creating a reference to the whole slab.
+  ///
+  /// Linked structures built on a slab use it to hand out mutable
+  /// references to several of their values at once, which `get_mut`
+  /// cannot do because it borrows the whole slab.
+  ///
+  /// # Safety
+  ///
+  /// `slab` must point to a live slab that may be mutated, and no
+  /// reference to the value with the key may be alive.
+  pub unsafe fn get_ptr(slab: *mut Self, key: usize) -> Option<NonNull<T>> {
+    // SAFETY: `Vec::as_mut_ptr` does not create a reference to the values,
+    // so references the caller holds to other values stay valid, and the
+    // slot is in bounds.
+    unsafe {
+      let slots = ptr::addr_of_mut!((*slab).slots);
+      if key >= (*slots).len() {
+        return None;
+      }
+
+      (*(*slots).as_mut_ptr().add(key)).as_mut().map(NonNull::from)
+    }
+  }
+
+  /// Removes all values from the slab.
+  pub fn clear(&mut self) {
+    self.slots.clear();
+    self.free.clear();
+    self.len = 0;
+  }
+
+  /// Returns an iterator over the keys and the values, in ascending order
+  /// of keys.
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter {
+      slots: self.slots.iter().enumerate(),
+      len: self.len
+    }
+  }
+
+  /// Returns an iterator over the keys and mutable references to the
+  /// values, in ascending order of keys.
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    IterMut {
+      slots: self.slots.iter_mut().enumerate(),
+      len: self.len
+    }
+  }
+}
+
+impl<T> Index<usize> for Slab<T> {
+  type Output = T;
+
+  /// Returns a reference to the value with the key.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the slot is vacant.
+  fn index(&self, key: usize) -> &T {
+    self.get(key).expect("invalid slab key")
+  }
+}
+
+impl<T> IndexMut<usize> for Slab<T> {
+  /// Returns a mutable reference to the value with the key.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the slot is vacant.
+  fn index_mut(&mut self, key: usize) -> &mut T {
+    self.get_mut(key).expect("invalid slab key")
+  }
+}
+
+/// An iterator over the keys and the values of a `Slab`.
+pub struct Iter<'a, T> {
+  slots: Enumerate<slice::Iter<'a, Option<T>>>,
+  /// Number of values not yet returned.
+  len: usize
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = (usize, &'a T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (key, value) = self.slots.find_map(|(key, slot)| Some((key, slot.as_ref()?)))?;
+    self.len -= 1;
+    Some((key, value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.len, Some(self.len))
+  }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let (key, value) = self.slots.rfind(|(_, slot)| slot.is_some())?;
+    self.len -= 1;
+    Some((key, value.as_ref()?))
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+/// An iterator over the keys and mutable references to the values of a
+/// `Slab`.
+pub struct IterMut<'a, T> {
+  slots: Enumerate<slice::IterMut<'a, Option<T>>>,
+  /// Number of values not yet returned.
+  len: usize
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = (usize, &'a mut T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (key, value) = self.slots.find_map(|(key, slot)| Some((key, slot.as_mut()?)))?;
+    self.len -= 1;
+    Some((key, value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.len, Some(self.len))
+  }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let (key, value) = self.slots.rfind(|(_, slot)| slot.is_some())?;
+    self.len -= 1;
+    Some((key, value.as_mut()?))
+  }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a Slab<T> {
+  type Item = (usize, &'a T);
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a mut Slab<T> {
+  type Item = (usize, &'a mut T);
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<T> FromIterator<T> for Slab<T> {
+  /// Creates a slab holding the values under the keys `0..n`.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let slots: Vec<Option<T>> = iter.into_iter().map(Some).collect();
+    Self { len: slots.len(), slots, free: Vec::new() }
+  }
+}
+
+impl<T: Clone> Clone for Slab<T> {
+  fn clone(&self) -> Self {
+    Self {
+      slots: self.slots.clone(),
+      free: self.free.clone(),
+      len: self.len
+    }
+  }
+}
+
+impl<T: PartialEq> PartialEq for Slab<T> {
+  /// Two slabs are equal if they hold equal values under the same keys.
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for Slab<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Slab<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Slab<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the values as a map from keys to values, in ascending
+  /// order of keys.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Slab<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a map from keys to values into a slab holding the values
+  /// under the same keys, with the missing keys vacant. Later pairs
+  /// overwrite earlier pairs with the same key.
+  ///
+  /// Keys of at least twice the number of pairs are rejected, so that the
+  /// input can not make the slab allocate more than it holds.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<T>(core::marker::PhantomData<T>);
+
+    impl<'de, T> serde::de::Visitor<'de> for MapVisitor<T>
+    where
+      T: serde::Deserialize<'de>,
+    {
+      type Value = Slab<T>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map from keys to values")
+      }
+
+      fn visit_map<A: serde::de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        use serde::de::Error;
+
+        let mut entries: Vec<(usize, T)> = Vec::new();
+        while let Some(entry) = access.next_entry()? {
+          entries.push(entry);
+        }
+
+        // The slab takes a slot for every key up to the greatest one, so
+        // the keys are not trusted to size it beyond the values read.
+        let limit = entries.len().saturating_mul(2);
+        let mut slots: Vec<Option<T>> = Vec::new();
+
+        for (key, value) in entries {
+          let len = key.checked_add(1).ok_or_else(|| A::Error::custom("key is too large"))?;
+          if key >= limit {
+            return Err(A::Error::custom("key is more than twice the number of values"));
+          }
+
+          if key >= slots.len() {
+            slots.resize_with(len, || None);
+          }
+          slots[key] = Some(value);
+        }
+
+        let free: Vec<usize> = (0..slots.len()).rev().filter(|&key| slots[key].is_none()).collect();
+        Ok(Slab { len: slots.len() - free.len(), slots, free })
+      }
+    }
+
+    deserializer.deserialize_map(MapVisitor(core::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Slab;
+  use alloc::collections::BTreeMap;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_slab() {
+    let mut slab = Slab::<i32>::new();
+
+    assert!(slab.is_empty());
+    assert_eq!(slab.vacant_key(), 0);
+    assert_eq!(slab.get(0), None);
+    assert_eq!(slab.remove(0), None);
+    assert_eq!(slab.iter().next(), None);
+  }
+
+  #[test]
+  fn keys_are_stable() {
+    let mut slab = Slab::new();
+    let keys: Vec<_> = (0..5).map(|i| slab.insert(i * 10)).collect();
+    slab.remove(keys[1]);
+    slab.remove(keys[3]);
+
+    assert_eq!(slab[keys[4]], 40);
+    assert!(!slab.contains(keys[1]));
+    assert_eq!(slab.vacant_key(), keys[3]);
+    assert_eq!(slab.insert(50), keys[3]);
+    assert_eq!(slab.insert(60), keys[1]);
+    assert_eq!(slab.insert(70), 5);
+  }
+
+  #[test]
+  fn iteration() {
+    let mut slab: Slab<_> = ["a", "b", "c", "d"].into_iter().collect();
+    slab.remove(1);
+
+    assert_eq!(slab.iter().collect::<Vec<_>>(), [(0, &"a"), (2, &"c"), (3, &"d")]);
+    assert_eq!(slab.iter().rev().map(|(key, _)| key).collect::<Vec<_>>(), [3, 2, 0]);
+    assert_eq!(slab.iter().len(), 3);
+
+    for (_, value) in &mut slab {
+      *value = "x";
+    }
+    assert!(slab.iter().all(|(_, value)| *value == "x"));
+  }
+
+  #[test]
+  fn get2_mut() {
+    let mut slab: Slab<_> = (0..3).collect();
+    let (first, second) = slab.get2_mut(2, 0).unwrap();
+    core::mem::swap(first, second);
+
+    assert_eq!(slab[0], 2);
+    assert_eq!(slab[2], 0);
+    assert_eq!(slab.get2_mut(0, 3), None);
+  }
+
+  #[test]
+  fn get_ptr() {
+    let mut slab: Slab<_> = (0..3).collect();
+    slab.remove(1);
+
+    let slab_ptr: *mut Slab<i32> = &mut slab;
+    // SAFETY: the slab is live and the keys are different.
+    unsafe {
+      let first = &mut *Slab::get_ptr(slab_ptr, 0).unwrap().as_ptr();
+      let last = &mut *Slab::get_ptr(slab_ptr, 2).unwrap().as_ptr();
+      core::mem::swap(first, last);
+
+      assert_eq!(Slab::get_ptr(slab_ptr, 1), None);
+      assert_eq!(Slab::get_ptr(slab_ptr, 3), None);
+    }
+
+    assert_eq!(slab.iter().collect::<Vec<_>>(), [(0, &2), (2, &0)]);
+  }
+
+  #[test]
+  fn retain() {
+    let mut slab: Slab<_> = (0..6).collect();
+    slab.retain(|_, value| *value % 2 == 0);
+
+    assert_eq!(slab.len(), 3);
+    assert_eq!(slab.iter().map(|(key, _)| key).collect::<Vec<_>>(), [0, 2, 4]);
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid slab key")]
+  fn index_vacant_slot() {
+    let mut slab = Slab::new();
+    let key = slab.insert(1);
+    slab.remove(key);
+
+    let _ = slab[key];
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value: Slab<_> = (10..14).collect();
+    value.remove(1);
+    value.remove(2);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"0":10,"3":13}"#);
+
+    let mut slab: Slab<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(slab, value);
+    assert_eq!(slab.insert(11), 1);
+    assert_eq!(slab.insert(12), 2);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_rejects_keys_beyond_the_values() {
+    assert!(serde_json::from_str::<Slab<i32>>(r#"{"0":10,"3":13}"#).is_ok());
+    assert!(serde_json::from_str::<Slab<i32>>(r#"{"0":10,"4":13}"#).is_err());
+    assert!(serde_json::from_str::<Slab<i32>>(r#"{"1000000000":1}"#).is_err());
+    assert!(serde_json::from_str::<Slab<i32>>(&format!(r#"{{"{}":1}}"#, usize::MAX)).is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(i32),
+    Remove(usize),
+    Get(usize)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Insert),
+      (0..64usize).prop_map(Operation::Remove),
+      (0..64usize).prop_map(Operation::Get),
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_btree_map(operations in prop::collection::vec(operation(), 0..300)) {
+      let mut slab = Slab::new();
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(value) => {
+            let key = slab.insert(value);
+            prop_assert_eq!(model.insert(key, value), None);
+          }
+          Operation::Remove(key) => prop_assert_eq!(slab.remove(key), model.remove(&key)),
+          Operation::Get(key) => prop_assert_eq!(slab.get(key), model.get(&key)),
+        }
+
+        prop_assert_eq!(slab.len(), model.len());
+      }
+
+      prop_assert!(slab.iter().eq(model.iter().map(|(key, value)| (*key, value))));
+    }
+  }
+}
@@ -0,0 +1,71 @@
+use heap::MaxHeap;
+
+use crate::Sorter;
+
+
+/// Heapsort, built on the `MaxHeap` of the `heap` crate.
+///
+/// The vector is turned into a heap in O(n), then the greatest element is
+/// repeatedly swapped to the end of the heap, which shrinks by one. This
+/// takes O(n log n) time in every case and happens within the memory of
+/// the vector. The sort is not stable.
+///
+/// # Example
+///
+/// ```
+/// use sorting::{HeapSort, Sorter};
+///
+/// let mut values = vec![5, 3, 4, 1];
+/// HeapSort::sort(&mut values);
+///
+/// assert_eq!(values, [1, 3, 4, 5]);
+/// ```
+pub struct HeapSort;
+
+impl Sorter for HeapSort {
+  type Sequence<T: Ord> = Vec<T>;
+
+  const STABLE: bool = false;
+
+  fn sort<T: Ord>(items: &mut Vec<T>) {
+    let heap = MaxHeap::from_vec(std::mem::take(items));
+    *items = heap.into_sorted_vec();
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::HeapSort;
+  use crate::Sorter;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty() {
+    let mut values: Vec<i32> = Vec::new();
+    HeapSort::sort(&mut values);
+
+    assert!(values.is_empty());
+  }
+
+  #[test]
+  fn keeps_the_allocation() {
+    let mut values: Vec<i32> = (0..100).rev().collect();
+    let pointer = values.as_ptr();
+    HeapSort::sort(&mut values);
+
+    assert_eq!(values.as_ptr(), pointer);
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+  }
+
+  proptest! {
+    #[test]
+    fn sorts_like_std(mut values in prop::collection::vec(any::<i16>(), 0..300)) {
+      let mut expected = values.clone();
+      expected.sort();
+      HeapSort::sort(&mut values);
+
+      prop_assert_eq!(values, expected);
+    }
+  }
+}
@@ -0,0 +1,82 @@
+use crate::Sorter;
+
+
+/// Insertion sort, which inserts every element into the sorted prefix
+/// before it.
+///
+/// The position of an element is found with a binary search, so sorting
+/// takes O(n log n) comparisons, but shifting the prefix still takes
+/// O(n^2) moves in the worst case. It is fast for short or nearly sorted
+/// slices, taking O(n) time on a sorted one. The sort is stable: an
+/// element is inserted after all equal elements of the prefix.
+///
+/// # Example
+///
+/// ```
+/// use sorting::{InsertionSort, Sorter};
+///
+/// let mut values = [5, 3, 4, 1];
+/// InsertionSort::sort(&mut values[..]);
+///
+/// assert_eq!(values, [1, 3, 4, 5]);
+/// ```
+pub struct InsertionSort;
+
+impl Sorter for InsertionSort {
+  type Sequence<T: Ord> = [T];
+
+  const STABLE: bool = true;
+
+  fn sort<T: Ord>(items: &mut [T]) {
+    for end in 1..items.len() {
+      let position = items[..end].partition_point(|value| *value <= items[end]);
+      items[position..=end].rotate_right(1);
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::InsertionSort;
+  use crate::Sorter;
+  use crate::tests::{is_stable, tagged};
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_and_single() {
+    let mut values: [i32; 0] = [];
+    InsertionSort::sort(&mut values[..]);
+
+    let mut values = [1];
+    InsertionSort::sort(&mut values[..]);
+    assert_eq!(values, [1]);
+  }
+
+  #[test]
+  fn reversed() {
+    let mut values: Vec<i32> = (0..100).rev().collect();
+    InsertionSort::sort(&mut values[..]);
+
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn stable() {
+    let mut values = tagged(100);
+    InsertionSort::sort(&mut values[..]);
+
+    assert!(is_stable(&values));
+  }
+
+  proptest! {
+    #[test]
+    fn sorts_like_std(mut values in prop::collection::vec(any::<i16>(), 0..100)) {
+      let mut expected = values.clone();
+      expected.sort();
+      InsertionSort::sort(&mut values[..]);
+
+      prop_assert_eq!(values, expected);
+    }
+  }
+}
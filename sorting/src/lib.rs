@@ -0,0 +1,147 @@
+//! Sorting algorithms for slices and for the structures of the workspace.
+//!
+//! Every algorithm is a marker type that implements `Sorter` for the
+//! sequence it sorts, so they can be swapped for each other through the
+//! common `sort` function:
+//!
+//! | Algorithm       | Sequence        | Time          | Stable |
+//! | --------------- | --------------- | ------------- | ------ |
+//! | `InsertionSort` | `[T]`           | O(n^2)        | yes    |
+//! | `QuickSort`     | `[T]`           | O(n log n)    | no     |
+//! | `HeapSort`      | `Vec<T>`        | O(n log n)    | no     |
+//! | `MergeSort`     | `LinkedList<T>` | O(n log n)    | yes    |
+//!
+//! A stable algorithm keeps elements that compare as equal in the order
+//! they had before sorting. Quicksort has an expected running time of
+//! O(n log n), its worst case is O(n^2).
+//!
+//! # Example
+//!
+//! ```
+//! use linked_list::LinkedList;
+//! use sorting::{MergeSort, QuickSort};
+//!
+//! let mut values = [3, 1, 2];
+//! sorting::sort::<QuickSort, _>(&mut values[..]);
+//! assert_eq!(values, [1, 2, 3]);
+//!
+//! let mut list: LinkedList<i32> = [3, 1, 2].into_iter().collect();
+//! sorting::sort::<MergeSort, _>(&mut list);
+//! assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+//! ```
+
+mod heap_sort;
+mod insertion;
+mod merge;
+mod quick;
+
+pub use heap_sort::HeapSort;
+pub use insertion::InsertionSort;
+pub use merge::MergeSort;
+pub use quick::QuickSort;
+
+
+/// A sorting algorithm.
+pub trait Sorter {
+  /// The kind of sequence the algorithm sorts.
+  type Sequence<T: Ord>: ?Sized;
+
+  /// Whether the algorithm keeps elements that compare as equal in their
+  /// original order.
+  const STABLE: bool;
+
+  /// Sorts the sequence in ascending order.
+  fn sort<T: Ord>(items: &mut Self::Sequence<T>);
+}
+
+/// Sorts a sequence in ascending order with the algorithm `S`.
+pub fn sort<S: Sorter, T: Ord>(items: &mut S::Sequence<T>) {
+  S::sort(items);
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{HeapSort, InsertionSort, MergeSort, QuickSort, Sorter};
+  use linked_list::LinkedList;
+  use std::cmp::Ordering;
+
+  /// A value that is compared by its key only, the tag tells equal
+  /// values apart to check stability.
+  #[derive(Debug, Clone, Copy)]
+  pub(crate) struct Tagged {
+    pub key: u8,
+    pub tag: usize
+  }
+
+  impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+      self.key == other.key
+    }
+  }
+
+  impl Eq for Tagged {}
+
+  impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+      Some(self.cmp(other))
+    }
+  }
+
+  impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+      self.key.cmp(&other.key)
+    }
+  }
+
+  /// Returns values with many equal keys, tagged in their original order.
+  pub(crate) fn tagged(len: usize) -> Vec<Tagged> {
+    (0..len).map(|tag| Tagged { key: (tag * 7 % 5) as u8, tag }).collect()
+  }
+
+  /// Checks that equal keys are in their original order.
+  pub(crate) fn is_stable<'a>(values: impl IntoIterator<Item = &'a Tagged>) -> bool {
+    let values: Vec<_> = values.into_iter().collect();
+    values.windows(2).all(|pair| pair[0].key < pair[1].key || pair[0].tag < pair[1].tag)
+  }
+
+  #[test]
+  fn common_api() {
+    let expected = [1, 2, 3, 4];
+
+    let mut values = [4, 2, 3, 1];
+    super::sort::<InsertionSort, _>(&mut values[..]);
+    assert_eq!(values, expected);
+
+    let mut values = [4, 2, 3, 1];
+    super::sort::<QuickSort, _>(&mut values[..]);
+    assert_eq!(values, expected);
+
+    let mut values = vec![4, 2, 3, 1];
+    super::sort::<HeapSort, _>(&mut values);
+    assert_eq!(values, expected);
+
+    let mut list: LinkedList<_> = [4, 2, 3, 1].into_iter().collect();
+    super::sort::<MergeSort, _>(&mut list);
+    assert!(list.iter().eq(expected.iter()));
+  }
+
+  #[test]
+  fn documented_stability() {
+    let mut values = tagged(50);
+    InsertionSort::sort(&mut values[..]);
+    assert_eq!(is_stable(&values), InsertionSort::STABLE);
+
+    let mut list: LinkedList<_> = tagged(50).into_iter().collect();
+    MergeSort::sort(&mut list);
+    assert_eq!(is_stable(&list), MergeSort::STABLE);
+
+    let mut values = tagged(50);
+    QuickSort::sort(&mut values[..]);
+    assert!(!QuickSort::STABLE && !is_stable(&values));
+
+    let mut values = tagged(50);
+    HeapSort::sort(&mut values);
+    assert!(!HeapSort::STABLE && !is_stable(&values));
+  }
+}
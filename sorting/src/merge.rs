@@ -0,0 +1,68 @@
+use linked_list::LinkedList;
+
+use crate::Sorter;
+
+
+/// Merge sort for a `LinkedList`, which relinks the nodes of the list
+/// instead of moving the elements.
+///
+/// This is `LinkedList::sort` behind the `Sorter` interface: no node is
+/// allocated or freed and no element is moved, so elements that are not
+/// `Clone` are sorted as cheaply as any other. Sorting takes O(n log n)
+/// time and is stable.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::LinkedList;
+/// use sorting::{MergeSort, Sorter};
+///
+/// let mut list: LinkedList<i32> = [5, 3, 4, 1].into_iter().collect();
+/// MergeSort::sort(&mut list);
+///
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 3, 4, 5]);
+/// ```
+pub struct MergeSort;
+
+impl Sorter for MergeSort {
+  type Sequence<T: Ord> = LinkedList<T>;
+
+  const STABLE: bool = true;
+
+  fn sort<T: Ord>(list: &mut LinkedList<T>) {
+    list.sort();
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::MergeSort;
+  use crate::Sorter;
+  use linked_list::LinkedList;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_and_single() {
+    let mut list = LinkedList::<i32>::new();
+    MergeSort::sort(&mut list);
+    assert!(list.is_empty());
+
+    list.append(1);
+    MergeSort::sort(&mut list);
+    assert_eq!(list.get(0), Some(&1));
+  }
+
+  proptest! {
+    #[test]
+    fn sorts_like_std(values in prop::collection::vec(any::<i16>(), 0..300)) {
+      let mut list: LinkedList<_> = values.iter().copied().collect();
+      let mut expected = values;
+      expected.sort();
+      MergeSort::sort(&mut list);
+
+      prop_assert_eq!(list.len(), expected.len());
+      prop_assert!(list.iter().eq(expected.iter()));
+    }
+  }
+}
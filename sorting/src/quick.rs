@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+
+use crate::Sorter;
+
+
+/// Quicksort, which splits the slice around a pivot and sorts both sides.
+///
+/// The pivot is the median of the first, middle and last elements, and
+/// the slice is split three ways into the elements less than, equal to
+/// and greater than the pivot, so slices with many equal elements are
+/// sorted quickly as well. Only the smaller side is sorted recursively,
+/// which keeps the depth of the recursion at O(log n).
+///
+/// Sorting takes O(n log n) time on average and O(n^2) in the worst case,
+/// without allocating. The sort is not stable.
+///
+/// # Example
+///
+/// ```
+/// use sorting::{QuickSort, Sorter};
+///
+/// let mut values = [5, 3, 4, 1, 3];
+/// QuickSort::sort(&mut values[..]);
+///
+/// assert_eq!(values, [1, 3, 3, 4, 5]);
+/// ```
+pub struct QuickSort;
+
+impl Sorter for QuickSort {
+  type Sequence<T: Ord> = [T];
+
+  const STABLE: bool = false;
+
+  fn sort<T: Ord>(mut items: &mut [T]) {
+    while items.len() > 1 {
+      let (less, greater) = partition(items);
+
+      // Recurse into the smaller side and keep looping on the larger one.
+      let len = items.len();
+      let (left, rest) = items.split_at_mut(less);
+      let right = &mut rest[greater - less..];
+
+      if left.len() < len - greater {
+        Self::sort(left);
+        items = right;
+      } else {
+        Self::sort(right);
+        items = left;
+      }
+    }
+  }
+}
+
+/// Moves the median of the first, middle and last elements to the front.
+fn choose_pivot<T: Ord>(items: &mut [T]) {
+  let (first, middle, last) = (0, items.len() / 2, items.len() - 1);
+
+  if items[middle] < items[first] {
+    items.swap(middle, first);
+  }
+  if items[last] < items[middle] {
+    items.swap(last, middle);
+    if items[middle] < items[first] {
+      items.swap(middle, first);
+    }
+  }
+
+  items.swap(0, middle);
+}
+
+/// Partitions the slice around a pivot into the elements less than, equal
+/// to and greater than it, and returns the bounds of the equal elements.
+fn partition<T: Ord>(items: &mut [T]) -> (usize, usize) {
+  choose_pivot(items);
+
+  // Invariant: `[0, less)` < pivot, `[less, current)` == pivot and
+  // `[greater, len)` > pivot, with the pivot itself at `less`.
+  let (mut less, mut current, mut greater) = (0, 1, items.len());
+
+  while current < greater {
+    match items[current].cmp(&items[less]) {
+      Ordering::Less => {
+        items.swap(current, less);
+        less += 1;
+        current += 1;
+      }
+      Ordering::Equal => current += 1,
+      Ordering::Greater => {
+        greater -= 1;
+        items.swap(current, greater);
+      }
+    }
+  }
+
+  (less, greater)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::QuickSort;
+  use crate::Sorter;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_and_single() {
+    let mut values: [i32; 0] = [];
+    QuickSort::sort(&mut values[..]);
+
+    let mut values = [1];
+    QuickSort::sort(&mut values[..]);
+    assert_eq!(values, [1]);
+  }
+
+  #[test]
+  fn sorted_and_reversed() {
+    let expected: Vec<i32> = (0..1000).collect();
+
+    let mut values = expected.clone();
+    QuickSort::sort(&mut values[..]);
+    assert_eq!(values, expected);
+
+    values.reverse();
+    QuickSort::sort(&mut values[..]);
+    assert_eq!(values, expected);
+  }
+
+  #[test]
+  fn all_equal() {
+    let mut values = vec![7; 10_000];
+    QuickSort::sort(&mut values[..]);
+
+    assert!(values.iter().all(|&value| value == 7));
+  }
+
+  proptest! {
+    #[test]
+    fn sorts_like_std(mut values in prop::collection::vec(any::<i16>(), 0..300)) {
+      let mut expected = values.clone();
+      expected.sort();
+      QuickSort::sort(&mut values[..]);
+
+      prop_assert_eq!(values, expected);
+    }
+  }
+}
@@ -0,0 +1,479 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::slice;
+
+use heap::MaxHeap;
+
+
+/// A k-d tree of points in `K` dimensions.
+///
+/// Every node splits the space in two along one axis, cycling through the
+/// axes level by level: the points of its left subtree are not greater
+/// than its own point on that axis, and those of its right subtree are not
+/// smaller. Searches skip the subtrees on the far side of a split when the
+/// split is farther than what they are looking for, so finding the nearest
+/// points takes O(log n) on typical data.
+///
+/// `build_from_points` splits at the median on every level and returns a
+/// balanced tree, while `insert` adds a point at the bottom of the tree,
+/// which can leave it unbalanced after many insertions.
+///
+/// Distances are Euclidean, and coordinates must not be NaN.
+///
+/// # Example
+///
+/// ```
+/// use spatial::KdTree;
+///
+/// let mut tree = KdTree::build_from_points([[2.0, 3.0], [5.0, 4.0], [9.0, 6.0], [4.0, 7.0], [8.0, 1.0]]);
+/// tree.insert([7.0, 2.0]);
+///
+/// assert_eq!(tree.nearest_neighbor(&[9.0, 2.0]), Some(&[8.0, 1.0]));
+/// assert_eq!(tree.k_nearest(&[6.0, 3.5], 2), vec![&[5.0, 4.0], &[7.0, 2.0]]);
+///
+/// let mut inside = tree.range_search(&[3.0, 0.0], &[8.0, 5.0]);
+/// inside.sort_by(|a, b| a.partial_cmp(b).unwrap());
+/// assert_eq!(inside, vec![&[5.0, 4.0], &[7.0, 2.0], &[8.0, 1.0]]);
+/// ```
+#[derive(Clone)]
+pub struct KdTree<const K: usize> {
+  /// Nodes of the tree, referring to their children by index.
+  nodes: Vec<Node<K>>,
+  root: Option<usize>
+}
+
+/// Represents a node in a k-d tree.
+#[derive(Clone)]
+struct Node<const K: usize> {
+  point: [f64; K],
+  /// Subtree with the points not greater than `point` on the axis of the
+  /// node.
+  left: Option<usize>,
+  /// Subtree with the points not smaller than `point` on the axis of the
+  /// node.
+  right: Option<usize>
+}
+
+/// A point found by a nearest neighbor search, ordered by its distance.
+#[derive(Clone, Copy)]
+struct Candidate {
+  /// The squared distance to the query.
+  distance: f64,
+  index: usize
+}
+
+impl PartialEq for Candidate {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Candidate {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.distance.total_cmp(&other.distance).then(self.index.cmp(&other.index))
+  }
+}
+
+impl<const K: usize> Default for KdTree<K> {
+  /// Creates a new instance of `KdTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const K: usize> KdTree<K> {
+  /// Creates a new empty k-d tree.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `K` is zero.
+  pub fn new() -> Self {
+    assert!(K > 0, "dimension must be positive");
+    Self { nodes: Vec::new(), root: None }
+  }
+
+  /// Creates a balanced k-d tree of the points in O(n log n), splitting
+  /// them at the median on every level.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `K` is zero or a coordinate is NaN.
+  pub fn build_from_points<I: IntoIterator<Item = [f64; K]>>(points: I) -> Self {
+    let mut points: Vec<[f64; K]> = points.into_iter().collect();
+    points.iter().for_each(check);
+
+    let mut tree = Self::new();
+    tree.nodes.reserve(points.len());
+    tree.root = tree.build(&mut points, 0);
+    tree
+  }
+
+  /// Returns the number of points in the tree.
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// Adds a point at the bottom of the tree, in O(h) where `h` is the
+  /// height of the tree.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a coordinate is NaN.
+  pub fn insert(&mut self, point: [f64; K]) {
+    check(&point);
+    let index = self.nodes.len();
+    self.nodes.push(Node { point, left: None, right: None });
+
+    let Some(mut current) = self.root else {
+      self.root = Some(index);
+      return;
+    };
+
+    for axis in (0..K).cycle() {
+      let node = &mut self.nodes[current];
+      let child = if point[axis] < node.point[axis] { &mut node.left } else { &mut node.right };
+
+      match *child {
+        Some(next) => current = next,
+        None => {
+          *child = Some(index);
+          return;
+        }
+      }
+    }
+  }
+
+  /// Returns the point nearest to the query, or `None` if the tree is
+  /// empty.
+  pub fn nearest_neighbor(&self, query: &[f64; K]) -> Option<&[f64; K]> {
+    self.k_nearest(query, 1).pop()
+  }
+
+  /// Returns the `k` points nearest to the query, or all of them if the
+  /// tree has fewer, from the nearest to the farthest.
+  ///
+  /// The candidates are kept in a `MaxHeap`, so the farthest of them is
+  /// replaced whenever a nearer point is found.
+  pub fn k_nearest(&self, query: &[f64; K], k: usize) -> Vec<&[f64; K]> {
+    if k == 0 {
+      return Vec::new();
+    }
+
+    let mut candidates = MaxHeap::with_capacity(k + 1);
+    self.search(self.root, 0, query, k, &mut candidates);
+
+    candidates.into_sorted_vec().into_iter().map(|candidate| &self.nodes[candidate.index].point).collect()
+  }
+
+  /// Returns the points inside the box between the two corners, bounds
+  /// included, in no particular order.
+  pub fn range_search(&self, min: &[f64; K], max: &[f64; K]) -> Vec<&[f64; K]> {
+    let mut found = Vec::new();
+    let mut stack: Vec<(usize, usize)> = self.root.map(|root| (root, 0)).into_iter().collect();
+
+    while let Some((index, axis)) = stack.pop() {
+      let node = &self.nodes[index];
+
+      if (0..K).all(|i| min[i] <= node.point[i] && node.point[i] <= max[i]) {
+        found.push(&node.point);
+      }
+      if let Some(left) = node.left.filter(|_| min[axis] <= node.point[axis]) {
+        stack.push((left, (axis + 1) % K));
+      }
+      if let Some(right) = node.right.filter(|_| node.point[axis] <= max[axis]) {
+        stack.push((right, (axis + 1) % K));
+      }
+    }
+
+    found
+  }
+
+  /// Returns an iterator over the points of the tree in no particular
+  /// order.
+  pub fn iter(&self) -> Iter<'_, K> {
+    Iter { nodes: self.nodes.iter() }
+  }
+
+  /// Builds a balanced subtree of the points, whose nodes split them
+  /// along `axis` first, and returns the index of its root.
+  fn build(&mut self, points: &mut [[f64; K]], axis: usize) -> Option<usize> {
+    if points.is_empty() {
+      return None;
+    }
+
+    let median = points.len() / 2;
+    points.select_nth_unstable_by(median, |a, b| a[axis].total_cmp(&b[axis]));
+
+    let (left, rest) = points.split_at_mut(median);
+    let left = self.build(left, (axis + 1) % K);
+    let right = self.build(&mut rest[1..], (axis + 1) % K);
+
+    self.nodes.push(Node { point: rest[0], left, right });
+    Some(self.nodes.len() - 1)
+  }
+
+  /// Adds the points of the subtree nearer to the query than the farthest
+  /// candidate to the candidates, keeping at most `k` of them.
+  ///
+  /// The subtrees wait on a stack rather than the call stack, since trees
+  /// grown by `insert` can be as deep as they are large.
+  fn search(&self, index: Option<usize>, axis: usize, query: &[f64; K], k: usize, candidates: &mut MaxHeap<Candidate>) {
+    // The subtrees to search, with the squared distance from the query to
+    // the split separating them from it.
+    let mut stack: Vec<(usize, usize, f64)> = index.map(|index| (index, axis, 0.0)).into_iter().collect();
+
+    while let Some((index, axis, split)) = stack.pop() {
+      // The far side of a split is only worth searching if the split is
+      // nearer than the farthest candidate.
+      if candidates.len() == k && split >= candidates.peek().unwrap().distance {
+        continue;
+      }
+      let node = &self.nodes[index];
+
+      let distance = (0..K).map(|i| (node.point[i] - query[i]).powi(2)).sum();
+      candidates.push(Candidate { distance, index });
+      if candidates.len() > k {
+        candidates.pop();
+      }
+
+      let offset = query[axis] - node.point[axis];
+      let (near, far) = if offset < 0.0 { (node.left, node.right) } else { (node.right, node.left) };
+
+      // The near side is popped first, before the far side is checked.
+      if let Some(far) = far {
+        stack.push((far, (axis + 1) % K, offset * offset));
+      }
+      if let Some(near) = near {
+        stack.push((near, (axis + 1) % K, 0.0));
+      }
+    }
+  }
+}
+
+/// Checks that none of the coordinates of the point is NaN.
+fn check<const K: usize>(point: &[f64; K]) {
+  assert!(point.iter().all(|coordinate| !coordinate.is_nan()), "coordinates must not be NaN");
+}
+
+/// An iterator over the points of a `KdTree`.
+pub struct Iter<'a, const K: usize> {
+  nodes: slice::Iter<'a, Node<K>>
+}
+
+impl<'a, const K: usize> Iterator for Iter<'a, K> {
+  type Item = &'a [f64; K];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.nodes.next().map(|node| &node.point)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.nodes.size_hint()
+  }
+}
+
+impl<const K: usize> ExactSizeIterator for Iter<'_, K> {}
+
+impl<'a, const K: usize> IntoIterator for &'a KdTree<K> {
+  type Item = &'a [f64; K];
+  type IntoIter = Iter<'a, K>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<const K: usize> FromIterator<[f64; K]> for KdTree<K> {
+  /// Creates a balanced k-d tree of the points, like `build_from_points`.
+  fn from_iter<I: IntoIterator<Item = [f64; K]>>(iter: I) -> Self {
+    Self::build_from_points(iter)
+  }
+}
+
+impl<const K: usize> fmt::Debug for KdTree<K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<const K: usize> serde::Serialize for KdTree<K> {
+  /// Serializes the points as a sequence of sequences of coordinates.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter().map(|point| &point[..]))
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const K: usize> serde::Deserialize<'de> for KdTree<K> {
+  /// Deserializes a balanced tree from a sequence of points.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let points: Vec<Vec<f64>> = serde::Deserialize::deserialize(deserializer)?;
+    let points = points
+      .into_iter()
+      .map(|point| <[f64; K]>::try_from(point).map_err(|point| D::Error::invalid_length(point.len(), &"a point of K coordinates")))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    if points.iter().flatten().any(|coordinate| coordinate.is_nan()) {
+      return Err(D::Error::custom("coordinates must not be NaN"));
+    }
+
+    Ok(Self::build_from_points(points))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{KdTree, Node};
+  use proptest::prelude::*;
+
+  fn distance<const K: usize>(a: &[f64; K], b: &[f64; K]) -> f64 {
+    (0..K).map(|i| (a[i] - b[i]).powi(2)).sum()
+  }
+
+  fn height<const K: usize>(tree: &KdTree<K>, index: Option<usize>) -> usize {
+    index.map_or(0, |index| 1 + height(tree, tree.nodes[index].left).max(height(tree, tree.nodes[index].right)))
+  }
+
+  #[test]
+  fn empty_tree() {
+    let tree = KdTree::<2>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.nearest_neighbor(&[0.0, 0.0]), None);
+    assert!(tree.k_nearest(&[0.0, 0.0], 3).is_empty());
+    assert!(tree.range_search(&[-1.0, -1.0], &[1.0, 1.0]).is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "dimension must be positive")]
+  fn zero_dimensions() {
+    KdTree::<0>::new();
+  }
+
+  #[test]
+  #[should_panic(expected = "coordinates must not be NaN")]
+  fn insert_nan() {
+    KdTree::new().insert([0.0, f64::NAN]);
+  }
+
+  #[test]
+  fn build_is_balanced() {
+    let points: Vec<[f64; 3]> = (0..1023).map(|i| [i as f64, (i * 7 % 31) as f64, (i % 5) as f64]).collect();
+    let tree = KdTree::build_from_points(points);
+
+    assert_eq!(tree.len(), 1023);
+    assert_eq!(height(&tree, tree.root), 10);
+  }
+
+  #[test]
+  fn nearest_neighbor() {
+    let mut tree = KdTree::new();
+    for point in [[0.0, 0.0], [10.0, 10.0], [3.0, 4.0], [-5.0, 2.0]] {
+      tree.insert(point);
+    }
+
+    assert_eq!(tree.nearest_neighbor(&[0.5, 0.5]), Some(&[0.0, 0.0]));
+    assert_eq!(tree.nearest_neighbor(&[3.0, 5.0]), Some(&[3.0, 4.0]));
+    assert_eq!(tree.nearest_neighbor(&[-4.0, 3.0]), Some(&[-5.0, 2.0]));
+    assert_eq!(tree.nearest_neighbor(&[100.0, 100.0]), Some(&[10.0, 10.0]));
+  }
+
+  #[test]
+  fn k_nearest() {
+    let tree: KdTree<1> = (0..10).map(|i| [i as f64]).collect();
+
+    assert_eq!(tree.k_nearest(&[4.2], 3), vec![&[4.0], &[5.0], &[3.0]]);
+    assert_eq!(tree.k_nearest(&[-1.0], 0), Vec::<&[f64; 1]>::new());
+    assert_eq!(tree.k_nearest(&[-1.0], 20).len(), 10);
+  }
+
+  #[test]
+  fn search_deep_tree() {
+    // The shape `insert` gives to sorted points, one right child per level.
+    let mut tree = KdTree::<1>::new();
+    tree.nodes = (0..100_000).map(|i| Node { point: [i as f64], left: None, right: Some(i + 1) }).collect();
+    tree.nodes.last_mut().unwrap().right = None;
+    tree.root = Some(0);
+
+    assert_eq!(tree.nearest_neighbor(&[200_000.0]), Some(&[99_999.0]));
+    assert_eq!(tree.k_nearest(&[-1.0], 2), vec![&[0.0], &[1.0]]);
+  }
+
+  #[test]
+  fn range_search() {
+    let tree: KdTree<2> = (0..5).flat_map(|x| (0..5).map(move |y| [x as f64, y as f64])).collect();
+    let mut found = tree.range_search(&[1.0, 2.0], &[2.0, 4.0]);
+    found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    assert_eq!(found, vec![&[1.0, 2.0], &[1.0, 3.0], &[1.0, 4.0], &[2.0, 2.0], &[2.0, 3.0], &[2.0, 4.0]]);
+    assert!(tree.range_search(&[5.5, 0.0], &[9.0, 9.0]).is_empty());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = KdTree::build_from_points([[1.0, 2.0], [3.0, 4.0]]);
+
+    let json = serde_json::to_string(&value).unwrap();
+    let tree: KdTree<2> = serde_json::from_str(&json).unwrap();
+    let mut points: Vec<_> = tree.iter().collect();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(points, vec![&[1.0, 2.0], &[3.0, 4.0]]);
+
+    assert!(serde_json::from_str::<KdTree<2>>("[[1.0,2.0,3.0]]").is_err());
+  }
+
+  fn point() -> impl Strategy<Value = [f64; 3]> {
+    // Small integer coordinates, so points often share coordinates and
+    // lie at the same distance from a query.
+    prop::array::uniform3((-8..8).prop_map(f64::from))
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_linear_scan(
+      built in prop::collection::vec(point(), 0..40),
+      inserted in prop::collection::vec(point(), 0..40),
+      query in point(),
+      corner in point(),
+      k in 0..10usize
+    ) {
+      let mut tree = KdTree::build_from_points(built.clone());
+      for &point in &inserted {
+        tree.insert(point);
+      }
+      let points: Vec<_> = built.iter().chain(&inserted).collect();
+
+      let mut expected: Vec<f64> = points.iter().map(|point| distance(point, &query)).collect();
+      expected.sort_by(f64::total_cmp);
+      expected.truncate(k);
+      let found: Vec<f64> = tree.k_nearest(&query, k).iter().map(|point| distance(point, &query)).collect();
+      prop_assert_eq!(found, expected);
+
+      let (min, max) = ([0, 1, 2].map(|i| query[i].min(corner[i])), [0, 1, 2].map(|i| query[i].max(corner[i])));
+      let mut expected: Vec<_> = points.into_iter().filter(|point| (0..3).all(|i| min[i] <= point[i] && point[i] <= max[i])).collect();
+      let mut found = tree.range_search(&min, &max);
+      expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+      found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+      prop_assert_eq!(found, expected);
+    }
+  }
+}
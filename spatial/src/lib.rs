@@ -0,0 +1,8 @@
+//! Spatial indexes, which find the points near a location or inside a
+//! region without looking at all of them.
+
+pub mod kd;
+pub mod region;
+
+pub use kd::KdTree;
+pub use region::{Octree, OutOfBoundsError, QuadTree, Region, RegionTree};
@@ -0,0 +1,585 @@
+use std::fmt;
+use std::mem;
+use std::slice;
+
+
+/// Depth below which leaves are no longer split, so that many values at
+/// the same point do not split the space forever.
+const MAX_DEPTH: usize = 32;
+
+/// A quadtree, which splits a rectangle into four quadrants.
+pub type QuadTree<T> = RegionTree<T, 2>;
+
+/// An octree, which splits a box into eight octants.
+pub type Octree<T> = RegionTree<T, 3>;
+
+/// A tree of values at points of a region in `D` dimensions, which splits
+/// the region into `2^D` equal parts whenever one holds too many values.
+///
+/// Every leaf of the tree holds the values in its part of the region, up
+/// to the capacity of the tree. When a leaf gets more, it is split on all
+/// axes at its center, and its values are moved to the new leaves. Dense
+/// areas are thus split finely and empty ones not at all, and a range
+/// search only visits the leaves that overlap the searched region.
+///
+/// `QuadTree` and `Octree` are the trees of two and three dimensions. The
+/// leaves can be listed with their regions, for example to draw the tree.
+///
+/// # Example
+///
+/// ```
+/// use spatial::{QuadTree, Region};
+///
+/// let mut tree = QuadTree::new(Region::new([0.0, 0.0], [100.0, 100.0]), 2);
+/// tree.insert([10.0, 10.0], "player").unwrap();
+/// tree.insert([12.0, 11.0], "arrow").unwrap();
+/// tree.insert([80.0, 60.0], "tree").unwrap();
+/// assert!(tree.insert([120.0, 0.0], "outside").is_err());
+///
+/// // The first quadrant was split when it got a third value.
+/// assert_eq!(tree.leaves().count(), 4);
+///
+/// let mut near = tree.range_search(&Region::new([5.0, 5.0], [15.0, 15.0]));
+/// near.sort_by_key(|(_, name)| **name);
+/// assert_eq!(near, vec![(&[12.0, 11.0], &"arrow"), (&[10.0, 10.0], &"player")]);
+///
+/// assert_eq!(tree.remove(&[12.0, 11.0]), Some("arrow"));
+/// assert_eq!(tree.leaves().count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct RegionTree<T, const D: usize> {
+  root: Node<T, D>,
+  region: Region<D>,
+  /// Number of values a leaf holds before it is split.
+  capacity: usize,
+  len: usize
+}
+
+/// Represents a node in a region tree.
+#[derive(Clone)]
+enum Node<T, const D: usize> {
+  Leaf(Vec<([f64; D], T)>),
+  /// A node split at its center, with `2^D` children. Bit `i` of the index
+  /// of a child tells if its part is above the center on axis `i`.
+  Branch(Vec<Node<T, D>>)
+}
+
+/// An axis-aligned box in `D` dimensions, bounds included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Region<const D: usize> {
+  min: [f64; D],
+  max: [f64; D]
+}
+
+/// The error returned by `RegionTree::insert` when the point is outside
+/// the region of the tree.
+///
+/// It carries the rejected value so it can be recovered.
+#[derive(Debug, PartialEq, Eq)]
+pub struct OutOfBoundsError<T>(pub T);
+
+impl<T> OutOfBoundsError<T> {
+  /// Returns the value that could not be inserted.
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl<T> fmt::Display for OutOfBoundsError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "point is outside the region of the tree")
+  }
+}
+
+impl<T: fmt::Debug> std::error::Error for OutOfBoundsError<T> {}
+
+impl<const D: usize> Region<D> {
+  /// Creates the box between the two corners.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a coordinate of `min` is greater than the one of `max`, or
+  /// if a coordinate is NaN.
+  pub fn new(min: [f64; D], max: [f64; D]) -> Self {
+    assert!((0..D).all(|i| min[i] <= max[i]), "min must not exceed max");
+    Self { min, max }
+  }
+
+  /// Returns the corner with the smallest coordinates.
+  pub fn min(&self) -> &[f64; D] {
+    &self.min
+  }
+
+  /// Returns the corner with the greatest coordinates.
+  pub fn max(&self) -> &[f64; D] {
+    &self.max
+  }
+
+  /// Checks if the point is inside the box.
+  pub fn contains(&self, point: &[f64; D]) -> bool {
+    (0..D).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+  }
+
+  /// Checks if the two boxes have at least one point in common.
+  pub fn intersects(&self, other: &Region<D>) -> bool {
+    (0..D).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+  }
+
+  /// Returns the index of the part of the box that holds the point, when
+  /// it is split at its center, and the region of that part.
+  fn part(&self, point: &[f64; D]) -> (usize, Region<D>) {
+    let index = (0..D).filter(|&i| point[i] >= self.center(i)).fold(0, |index, i| index | 1 << i);
+    (index, self.part_at(index))
+  }
+
+  /// Returns the region of the part with the index, when the box is split
+  /// at its center.
+  fn part_at(&self, index: usize) -> Region<D> {
+    let mut part = *self;
+
+    for i in 0..D {
+      if index & 1 << i == 0 {
+        part.max[i] = self.center(i);
+      } else {
+        part.min[i] = self.center(i);
+      }
+    }
+
+    part
+  }
+
+  fn center(&self, axis: usize) -> f64 {
+    self.min[axis] + (self.max[axis] - self.min[axis]) / 2.0
+  }
+}
+
+impl<T, const D: usize> RegionTree<T, D> {
+  /// Creates a new empty tree over the region, whose leaves are split
+  /// when they hold more than `capacity` values.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `D` or `capacity` is zero.
+  pub fn new(region: Region<D>, capacity: usize) -> Self {
+    assert!(D > 0, "dimension must be positive");
+    assert!(capacity > 0, "capacity must be positive");
+    Self { root: Node::Leaf(Vec::new()), region, capacity, len: 0 }
+  }
+
+  /// Returns the region of the tree.
+  pub fn region(&self) -> &Region<D> {
+    &self.region
+  }
+
+  /// Returns the number of values a leaf holds before it is split.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Returns the number of values in the tree.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the tree is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Inserts a value at the point, even if there already are values at
+  /// the same point.
+  ///
+  /// Returns the value back in an error if the point is outside the region
+  /// of the tree.
+  pub fn insert(&mut self, point: [f64; D], value: T) -> Result<(), OutOfBoundsError<T>> {
+    if !self.region.contains(&point) {
+      return Err(OutOfBoundsError(value));
+    }
+
+    insert(&mut self.root, self.region, 0, self.capacity, point, value);
+    self.len += 1;
+    Ok(())
+  }
+
+  /// Removes a value at the point and returns it, or `None` if there is
+  /// no value at the point.
+  ///
+  /// The leaves of a node are merged back into one when they hold no more
+  /// than `capacity` values together.
+  pub fn remove(&mut self, point: &[f64; D]) -> Option<T> {
+    if !self.region.contains(point) {
+      return None;
+    }
+
+    let value = remove(&mut self.root, self.region, self.capacity, point)?;
+    self.len -= 1;
+    Some(value)
+  }
+
+  /// Returns the points and values inside the region, bounds included, in
+  /// no particular order.
+  pub fn range_search(&self, region: &Region<D>) -> Vec<(&[f64; D], &T)> {
+    let mut found = Vec::new();
+    let mut stack = vec![(&self.root, self.region)];
+
+    while let Some((node, bounds)) = stack.pop() {
+      match node {
+        Node::Leaf(items) => found.extend(items.iter().filter(|(point, _)| region.contains(point)).map(|(point, value)| (point, value))),
+        Node::Branch(children) => {
+          let parts = children.iter().enumerate().map(|(index, child)| (child, bounds.part_at(index)));
+          stack.extend(parts.filter(|(_, part)| part.intersects(region)));
+        }
+      }
+    }
+
+    found
+  }
+
+  /// Returns an iterator over the leaves of the tree, with their regions
+  /// and the points and values they hold.
+  pub fn leaves(&self) -> Leaves<'_, T, D> {
+    Leaves { stack: vec![(&self.root, self.region)] }
+  }
+
+  /// Returns an iterator over the points and values of the tree, leaf by
+  /// leaf.
+  pub fn iter(&self) -> Iter<'_, T, D> {
+    Iter { leaves: self.leaves(), items: [].iter() }
+  }
+}
+
+/// Inserts the value into the subtree of the region, splitting the leaf
+/// that gets it if it gets too many values.
+fn insert<T, const D: usize>(node: &mut Node<T, D>, region: Region<D>, depth: usize, capacity: usize, point: [f64; D], value: T) {
+  match node {
+    Node::Branch(children) => {
+      let (index, part) = region.part(&point);
+      insert(&mut children[index], part, depth + 1, capacity, point, value);
+    }
+    Node::Leaf(items) => {
+      items.push((point, value));
+
+      if items.len() > capacity && depth < MAX_DEPTH {
+        let items = mem::take(items);
+        *node = Node::Branch((0..1 << D).map(|_| Node::Leaf(Vec::new())).collect());
+
+        for (point, value) in items {
+          insert(node, region, depth, capacity, point, value);
+        }
+      }
+    }
+  }
+}
+
+/// Removes a value at the point from the subtree of the region, merging
+/// the leaves of the nodes on the way back if they got few enough values.
+fn remove<T, const D: usize>(node: &mut Node<T, D>, region: Region<D>, capacity: usize, point: &[f64; D]) -> Option<T> {
+  match node {
+    Node::Leaf(items) => {
+      let index = items.iter().position(|(other, _)| other == point)?;
+      Some(items.swap_remove(index).1)
+    }
+    Node::Branch(children) => {
+      let (index, part) = region.part(point);
+      let value = remove(&mut children[index], part, capacity, point)?;
+
+      let leaves: Option<Vec<_>> = children.iter().map(|child| match child {
+        Node::Leaf(items) => Some(items.len()),
+        Node::Branch(_) => None
+      }).collect();
+
+      if leaves.is_some_and(|lengths| lengths.iter().sum::<usize>() <= capacity) {
+        let items = mem::take(children).into_iter().flat_map(|child| match child {
+          Node::Leaf(items) => items,
+          Node::Branch(_) => unreachable!("all children are leaves")
+        });
+        *node = Node::Leaf(items.collect());
+      }
+
+      Some(value)
+    }
+  }
+}
+
+/// An iterator over the leaves of a `RegionTree`.
+pub struct Leaves<'a, T, const D: usize> {
+  /// Nodes still to visit with their regions, the next one on top.
+  stack: Vec<(&'a Node<T, D>, Region<D>)>
+}
+
+impl<'a, T, const D: usize> Iterator for Leaves<'a, T, D> {
+  type Item = (Region<D>, &'a [([f64; D], T)]);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.stack.pop()? {
+        (Node::Leaf(items), region) => return Some((region, items)),
+        (Node::Branch(children), region) => {
+          self.stack.extend(children.iter().enumerate().rev().map(|(index, child)| (child, region.part_at(index))));
+        }
+      }
+    }
+  }
+}
+
+/// An iterator over the points and values of a `RegionTree`.
+pub struct Iter<'a, T, const D: usize> {
+  leaves: Leaves<'a, T, D>,
+  /// The items left in the current leaf.
+  items: slice::Iter<'a, ([f64; D], T)>
+}
+
+impl<'a, T, const D: usize> Iterator for Iter<'a, T, D> {
+  type Item = (&'a [f64; D], &'a T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((point, value)) = self.items.next() {
+        return Some((point, value));
+      }
+
+      self.items = self.leaves.next()?.1.iter();
+    }
+  }
+}
+
+impl<'a, T, const D: usize> IntoIterator for &'a RegionTree<T, D> {
+  type Item = (&'a [f64; D], &'a T);
+  type IntoIter = Iter<'a, T, D>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T: fmt::Debug, const D: usize> fmt::Debug for RegionTree<T, D> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const D: usize> serde::Serialize for RegionTree<T, D>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the corners of the region and the capacity together with
+  /// the points and values.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let items: Vec<(&[f64], &T)> = self.iter().map(|(point, value)| (&point[..], value)).collect();
+    serde::Serialize::serialize(&(&self.region.min[..], &self.region.max[..], self.capacity, items), serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const D: usize> serde::Deserialize<'de> for RegionTree<T, D>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes the region, the capacity and the values of a tree.
+  fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    use serde::de::Error;
+
+    fn point<E: Error, const D: usize>(coordinates: Vec<f64>) -> Result<[f64; D], E> {
+      <[f64; D]>::try_from(coordinates).map_err(|coordinates| E::invalid_length(coordinates.len(), &"a point of D coordinates"))
+    }
+
+    /// The corners, the capacity and the points and values of a tree.
+    type Fields<T> = (Vec<f64>, Vec<f64>, usize, Vec<(Vec<f64>, T)>);
+
+    let (min, max, capacity, items): Fields<T> = serde::Deserialize::deserialize(deserializer)?;
+    let (min, max) = (point(min)?, point(max)?);
+
+    if !(0..D).all(|i| min[i] <= max[i]) {
+      return Err(De::Error::custom("min must not exceed max"));
+    }
+    if capacity == 0 {
+      return Err(De::Error::custom("capacity must be positive"));
+    }
+
+    let mut tree = Self::new(Region::new(min, max), capacity);
+    for (coordinates, value) in items {
+      tree.insert(point(coordinates)?, value).map_err(|_| De::Error::custom("point is outside the region of the tree"))?;
+    }
+
+    Ok(tree)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Node, OutOfBoundsError, Octree, QuadTree, Region, MAX_DEPTH};
+  use proptest::prelude::*;
+
+  /// Checks that every value is inside the region of its leaf, and that
+  /// leaves are only over capacity at the maximum depth.
+  fn check<T>(node: &Node<T, 2>, region: Region<2>, depth: usize, capacity: usize) {
+    match node {
+      Node::Leaf(items) => {
+        assert!(items.len() <= capacity || depth == MAX_DEPTH);
+        assert!(items.iter().all(|(point, _)| region.contains(point)));
+      }
+      Node::Branch(children) => {
+        assert_eq!(children.len(), 4);
+        for (index, child) in children.iter().enumerate() {
+          check(child, region.part_at(index), depth + 1, capacity);
+        }
+      }
+    }
+  }
+
+  fn square(size: f64) -> Region<2> {
+    Region::new([0.0, 0.0], [size, size])
+  }
+
+  #[test]
+  fn empty_tree() {
+    let mut tree = QuadTree::<()>::new(square(1.0), 4);
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.capacity(), 4);
+    assert_eq!(tree.leaves().count(), 1);
+    assert_eq!(tree.remove(&[0.5, 0.5]), None);
+    assert!(tree.range_search(&square(1.0)).is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "min must not exceed max")]
+  fn inverted_region() {
+    Region::new([1.0, 0.0], [0.0, 1.0]);
+  }
+
+  #[test]
+  #[should_panic(expected = "capacity must be positive")]
+  fn zero_capacity() {
+    QuadTree::<()>::new(square(1.0), 0);
+  }
+
+  #[test]
+  fn insert_outside() {
+    let mut tree = QuadTree::new(square(1.0), 1);
+
+    assert_eq!(tree.insert([1.0, 1.0], 'a'), Ok(()));
+    assert_eq!(tree.insert([1.5, 0.0], 'b'), Err(OutOfBoundsError('b')));
+    assert_eq!(tree.len(), 1);
+  }
+
+  #[test]
+  fn split_and_merge() {
+    let mut tree = QuadTree::new(square(8.0), 1);
+    tree.insert([1.0, 1.0], 1).unwrap();
+    tree.insert([7.0, 1.0], 2).unwrap();
+    tree.insert([1.0, 7.0], 3).unwrap();
+
+    let leaves: Vec<_> = tree.leaves().map(|(region, items)| (*region.min(), items.len())).collect();
+    assert_eq!(leaves, vec![([0.0, 0.0], 1), ([4.0, 0.0], 1), ([0.0, 4.0], 1), ([4.0, 4.0], 0)]);
+
+    // A fourth value close to the first splits its quadrant again.
+    tree.insert([3.0, 3.0], 4).unwrap();
+    assert_eq!(tree.leaves().count(), 7);
+
+    assert_eq!(tree.remove(&[3.0, 3.0]), Some(4));
+    assert_eq!(tree.leaves().count(), 4);
+    assert_eq!(tree.remove(&[7.0, 1.0]), Some(2));
+    assert_eq!(tree.remove(&[1.0, 7.0]), Some(3));
+    assert_eq!(tree.leaves().count(), 1);
+    assert_eq!(tree.iter().collect::<Vec<_>>(), vec![(&[1.0, 1.0], &1)]);
+  }
+
+  #[test]
+  fn same_point() {
+    let mut tree = QuadTree::new(square(1.0), 1);
+    for value in 0..5 {
+      tree.insert([0.25, 0.25], value).unwrap();
+    }
+
+    check(&tree.root, square(1.0), 0, 1);
+    assert_eq!(tree.range_search(&Region::new([0.25, 0.25], [0.25, 0.25])).len(), 5);
+    assert!(tree.remove(&[0.25, 0.25]).is_some());
+    assert_eq!(tree.len(), 4);
+  }
+
+  #[test]
+  fn octree() {
+    let mut tree = Octree::new(Region::new([0.0; 3], [1.0; 3]), 1);
+    tree.insert([0.1, 0.1, 0.1], 'a').unwrap();
+    tree.insert([0.9, 0.9, 0.9], 'b').unwrap();
+
+    assert_eq!(tree.leaves().count(), 8);
+    assert_eq!(tree.range_search(&Region::new([0.5; 3], [1.0; 3])), vec![(&[0.9, 0.9, 0.9], &'b')]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value = QuadTree::new(square(2.0), 1);
+    value.insert([0.5, 0.5], 'a').unwrap();
+    value.insert([1.5, 0.5], 'b').unwrap();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"[[0.0,0.0],[2.0,2.0],1,[[[0.5,0.5],"a"],[[1.5,0.5],"b"]]]"#);
+
+    let tree: QuadTree<char> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tree.iter().collect::<Vec<_>>(), value.iter().collect::<Vec<_>>());
+    assert_eq!(tree.leaves().count(), 4);
+
+    assert!(serde_json::from_str::<QuadTree<char>>(r#"[[0.0,0.0],[2.0,2.0],1,[[[3.0,0.5],"a"]]]"#).is_err());
+    assert!(serde_json::from_str::<QuadTree<char>>(r#"[[0.0,0.0],[2.0,2.0],0,[]]"#).is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert([f64; 2]),
+    Remove(usize),
+    RangeSearch([f64; 2], [f64; 2])
+  }
+
+  fn point() -> impl Strategy<Value = [f64; 2]> {
+    prop::array::uniform2((0..16).prop_map(f64::from))
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      3 => point().prop_map(Operation::Insert),
+      1 => any::<usize>().prop_map(Operation::Remove),
+      1 => (point(), point()).prop_map(|(a, b)| Operation::RangeSearch(a, b))
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_linear_scan(capacity in 1..4usize, operations in prop::collection::vec(operation(), 0..100)) {
+      let mut tree = QuadTree::new(square(15.0), capacity);
+      let mut model: Vec<([f64; 2], usize)> = Vec::new();
+
+      for (value, operation) in operations.into_iter().enumerate() {
+        match operation {
+          Operation::Insert(point) => {
+            tree.insert(point, value).unwrap();
+            model.push((point, value));
+          }
+          Operation::Remove(index) if !model.is_empty() => {
+            let point = model[index % model.len()].0;
+            let removed = tree.remove(&point).unwrap();
+            model.retain(|&entry| entry != (point, removed));
+          }
+          Operation::Remove(_) => {}
+          Operation::RangeSearch(a, b) => {
+            let region = Region::new([a[0].min(b[0]), a[1].min(b[1])], [a[0].max(b[0]), a[1].max(b[1])]);
+            let mut expected: Vec<_> = model.iter().filter(|(point, _)| region.contains(point)).map(|&(_, value)| value).collect();
+            let mut found: Vec<_> = tree.range_search(&region).into_iter().map(|(_, &value)| value).collect();
+            expected.sort_unstable();
+            found.sort_unstable();
+            prop_assert_eq!(found, expected);
+          }
+        }
+
+        check(&tree.root, square(15.0), 0, capacity);
+        prop_assert_eq!(tree.len(), model.len());
+        prop_assert_eq!(tree.iter().count(), model.len());
+      }
+    }
+  }
+}
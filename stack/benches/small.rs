@@ -0,0 +1,60 @@
+//! Compares `SmallStack`, which keeps up to 16 elements inline, against
+//! `Stack` and `Vec`, which allocate on their first push, with throughput
+//! numbers for building and emptying a fresh stack at several depths.
+//!
+//! Run with `cargo bench -p stack --bench small`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use stack::{SmallStack, Stack};
+
+
+/// Depths below, at and above the inline capacity of the `SmallStack`.
+const DEPTHS: [usize; 4] = [4, 16, 64, 256];
+
+fn push_pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("small/push_pop");
+
+  for depth in DEPTHS {
+    group.throughput(Throughput::Elements(depth as u64));
+    group.bench_with_input(BenchmarkId::new("SmallStack", depth), &depth, |b, &depth| {
+      b.iter(|| {
+        let mut ours = SmallStack::<usize, 16>::new();
+        for i in 0..depth {
+          ours.push(black_box(i));
+        }
+        while let Some(value) = ours.pop() {
+          black_box(value);
+        }
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("Stack", depth), &depth, |b, &depth| {
+      b.iter(|| {
+        let mut ours = Stack::new();
+        for i in 0..depth {
+          ours.push(black_box(i));
+        }
+        while let Some(value) = ours.pop() {
+          black_box(value);
+        }
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("Vec", depth), &depth, |b, &depth| {
+      b.iter(|| {
+        let mut std = Vec::new();
+        for i in 0..depth {
+          std.push(black_box(i));
+        }
+        while let Some(value) = std.pop() {
+          black_box(value);
+        }
+      })
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push_pop);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+//! Compares `Stack` against `Vec` from the standard library
+//! with throughput numbers for push, pop and iterate at several sizes.
+//!
+//! Run with `cargo bench -p stack --bench std`.
+
+use std::hint::black_box;
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput
+};
+use stack::Stack;
+
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn push(c: &mut Criterion) {
+  let mut group = c.benchmark_group("stack/push");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Stack", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut ours = Stack::new();
+        for i in 0..size {
+          ours.push(black_box(i));
+        }
+        ours
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("Vec", size), &size, |b, &size| {
+      b.iter(|| {
+        let mut std = Vec::new();
+        for i in 0..size {
+          std.push(black_box(i));
+        }
+        std
+      })
+    });
+  }
+
+  group.finish();
+}
+
+fn pop(c: &mut Criterion) {
+  let mut group = c.benchmark_group("stack/pop");
+
+  for size in SIZES {
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Stack", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<Stack<_>>(),
+        |mut ours| {
+          for _ in 0..size {
+            black_box(ours.pop());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+    group.bench_with_input(BenchmarkId::new("Vec", size), &size, |b, &size| {
+      b.iter_batched(
+        || (0..size).collect::<Vec<_>>(),
+        |mut std| {
+          for _ in 0..size {
+            black_box(std.pop());
+          }
+        },
+        BatchSize::SmallInput
+      )
+    });
+  }
+
+  group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+  let mut group = c.benchmark_group("stack/iterate");
+
+  for size in SIZES {
+    let ours: Stack<_> = (0..size).collect();
+    let std: Vec<_> = (0..size).collect();
+
+    group.throughput(Throughput::Elements(size as u64));
+    group.bench_with_input(BenchmarkId::new("Stack", size), &ours, |b, ours| {
+      b.iter(|| ours.iter().sum::<usize>())
+    });
+    group.bench_with_input(BenchmarkId::new("Vec", size), &std, |b, std| {
+      b.iter(|| std.iter().sum::<usize>())
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, push, pop, iterate);
+criterion_main!(benches);
@@ -0,0 +1,357 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::Rev;
+use core::slice;
+
+use crate::storage::{ArrayStorage, Storage};
+use crate::StackFullError;
+
+
+/// A stack of at most `N` elements stored inline, without allocating.
+///
+/// The elements live inside the stack itself, so it can be kept on the
+/// call stack or in a `static`, and it is available without the `alloc`
+/// feature for targets that have no allocator.
+///
+/// # Example
+///
+/// ```
+/// use stack::{ArrayStack, StackFullError};
+///
+/// let mut stack = ArrayStack::<i32, 2>::new();
+/// stack.push(7);
+/// stack.push(32);
+///
+/// assert!(stack.is_full());
+/// assert_eq!(stack.try_push(1), Err(StackFullError(1)));
+/// assert_eq!(stack.pop(), Some(32));
+/// assert_eq!(stack.as_slice(), [7]);
+/// ```
+pub struct ArrayStack<T, const N: usize> {
+  items: ArrayStorage<T, N>
+}
+
+impl<T, const N: usize> Default for ArrayStack<T, N> {
+  /// Creates a new instance of `ArrayStack` with default values.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::ArrayStack;
+  ///
+  /// let stack = ArrayStack::<i32, 8>::default();
+  /// ```
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, const N: usize> ArrayStack<T, N> {
+  /// Creates a new empty instance of `ArrayStack`.
+  pub const fn new() -> Self {
+    Self { items: ArrayStorage::new() }
+  }
+
+  /// Returns the maximum number of elements, `N`.
+  pub const fn capacity(&self) -> usize {
+    N
+  }
+
+  /// Checks if the stack holds `N` elements.
+  pub fn is_full(&self) -> bool {
+    self.items.len() == N
+  }
+
+  /// Pushes a value onto the top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack is full, use `try_push` to handle that case.
+  pub fn push(&mut self, value: T) {
+    assert!(!self.is_full(), "stack is full");
+    let _ = self.items.push(value);
+  }
+
+  /// Pushes a value onto the top of the stack, or returns it back
+  /// inside `StackFullError` if the stack is full.
+  pub fn try_push(&mut self, value: T) -> Result<(), StackFullError<T>> {
+    self.items.push(value).map_err(StackFullError)
+  }
+
+  /// Removes the top value from the stack and returns it,
+  /// or `None` if the stack is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    self.items.pop()
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    while self.items.pop().is_some() {}
+  }
+
+  /// Returns a reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.items.top()
+  }
+
+  /// Returns a mutable reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek_mut(&mut self) -> Option<&mut T> {
+    self.items.top_mut()
+  }
+
+  /// Checks if the stack is empty.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Returns a slice of all elements, from the bottom of the stack
+  /// to the top.
+  pub fn as_slice(&self) -> &[T] {
+    self.items.as_slice()
+  }
+
+  /// Returns an iterator over the elements of the stack, from the top
+  /// to the bottom.
+  pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+    self.as_slice().iter().rev()
+  }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayStack<T, N> {
+  type Item = &'a T;
+  type IntoIter = Rev<slice::Iter<'a, T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayStack<T, N> {
+  /// Creates a stack by pushing every value of the iterator in order,
+  /// so the last value ends up on top.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the iterator yields more than `N` values.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut stack = Self::new();
+    stack.extend(iter);
+    stack
+  }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayStack<T, N> {
+  /// Pushes every value of the iterator onto the stack in order.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack becomes full.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayStack<T, N> {
+  fn clone(&self) -> Self {
+    Self { items: self.items.clone() }
+  }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayStack<T, N> {
+  /// Two stacks are equal if they hold equal elements in the same order.
+  fn eq(&self, other: &Self) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayStack<T, N> {}
+
+impl<T: Hash, const N: usize> Hash for ArrayStack<T, N> {
+  /// Hashes the elements from the bottom of the stack to the top.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayStack<T, N> {
+  /// Formats the elements from the bottom of the stack to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.as_slice()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for ArrayStack<T, N>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the bottom of the stack to the top.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.as_slice())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for ArrayStack<T, N>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a stack from a sequence of at most `N` elements, the last
+  /// element ends up on top.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct Visitor<T, const N: usize>(core::marker::PhantomData<T>);
+
+    impl<'de, T: serde::Deserialize<'de>, const N: usize> serde::de::Visitor<'de> for Visitor<T, N> {
+      type Value = ArrayStack<T, N>;
+
+      fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of at most {N} elements")
+      }
+
+      fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut stack = ArrayStack::new();
+
+        while let Some(value) = seq.next_element()? {
+          if stack.try_push(value).is_err() {
+            return Err(serde::de::Error::invalid_length(N + 1, &self));
+          }
+        }
+
+        Ok(stack)
+      }
+    }
+
+    deserializer.deserialize_seq(Visitor(core::marker::PhantomData))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::ArrayStack;
+  use crate::StackFullError;
+  use alloc::format;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  #[test]
+  fn push_and_pop() {
+    let mut stack = ArrayStack::<i32, 4>::new();
+    stack.push(42);
+    stack.push(314);
+
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.peek(), Some(&314));
+    *stack.peek_mut().unwrap() += 1;
+    assert_eq!(stack.pop(), Some(315));
+    assert_eq!(stack.pop(), Some(42));
+    assert_eq!(stack.pop(), None);
+  }
+
+  #[test]
+  fn try_push() {
+    let mut stack = ArrayStack::<i32, 2>::new();
+
+    assert_eq!(stack.try_push(42), Ok(()));
+    assert_eq!(stack.try_push(314), Ok(()));
+    assert!(stack.is_full());
+    assert_eq!(stack.try_push(7), Err(StackFullError(7)));
+
+    stack.pop();
+    assert_eq!(stack.try_push(7), Ok(()));
+    assert_eq!(stack.as_slice(), [42, 7]);
+  }
+
+  #[test]
+  #[should_panic(expected = "stack is full")]
+  fn push_full() {
+    let mut stack = ArrayStack::<i32, 1>::new();
+    stack.push(42);
+    stack.push(314);
+  }
+
+  #[test]
+  fn zero_capacity() {
+    let mut stack = ArrayStack::<i32, 0>::new();
+
+    assert!(stack.is_full());
+    assert_eq!(stack.try_push(1), Err(StackFullError(1)));
+    assert_eq!(stack.capacity(), 0);
+  }
+
+  #[test]
+  fn const_construction() {
+    static EMPTY: ArrayStack<u8, 16> = ArrayStack::new();
+
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.capacity(), 16);
+  }
+
+  #[test]
+  fn iter_and_traits() {
+    let mut stack: ArrayStack<i32, 4> = (1..=3).collect();
+
+    assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [3, 2, 1]);
+    assert_eq!(format!("{stack:?}"), "[1, 2, 3]");
+    assert_eq!(stack.clone(), stack);
+
+    stack.clear();
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: ArrayStack<i32, 4> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let stack: ArrayStack<i32, 4> = serde_json::from_str(&json).unwrap();
+    assert_eq!(stack, value);
+    assert!(serde_json::from_str::<ArrayStack<i32, 2>>(&json).is_err());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![any::<i32>().prop_map(Operation::Push), Just(Operation::Pop)]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_vec(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut stack = ArrayStack::<i32, 8>::new();
+      let mut model = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            let pushed = stack.try_push(value).is_ok();
+            prop_assert_eq!(pushed, model.len() < 8);
+            if pushed {
+              model.push(value);
+            }
+          }
+          Operation::Pop => prop_assert_eq!(stack.pop(), model.pop())
+        }
+
+        prop_assert_eq!(stack.as_slice(), model.as_slice());
+      }
+    }
+  }
+}
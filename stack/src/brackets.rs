@@ -0,0 +1,243 @@
+//! Checking that the brackets of a text are balanced with a stack.
+//!
+//! Every opening bracket is pushed on a stack, and every closing bracket
+//! has to match the opening bracket on top of it, which is then popped.
+//! The text is balanced if no closing bracket is out of place and the
+//! stack is empty at the end.
+//!
+//! # Example
+//!
+//! ```
+//! use stack::brackets::{self, BracketError, Brackets};
+//!
+//! assert_eq!(brackets::validate("fn main() { let v = [1, 2]; }"), Ok(()));
+//! assert_eq!(
+//!   brackets::validate("(]"),
+//!   Err(BracketError::Mismatched { position: 1, expected: ')', found: ']' })
+//! );
+//!
+//! let angle = Brackets::new(&[('<', '>')]);
+//! assert_eq!(angle.validate("Vec<Option<u8>>"), Ok(()));
+//! assert_eq!(angle.validate("a > b"), Err(BracketError::Unexpected { position: 2, found: '>' }));
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Stack;
+
+
+/// The first problem found by `validate`. Positions are byte offsets into
+/// the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketError {
+  /// A closing bracket while no bracket is open.
+  Unexpected { position: usize, found: char },
+  /// A closing bracket that does not match the innermost open bracket.
+  Mismatched { position: usize, expected: char, found: char },
+  /// An opening bracket that is never closed, the first one if there are
+  /// several.
+  Unclosed { position: usize, bracket: char }
+}
+
+impl BracketError {
+  /// Returns the position of the offending bracket.
+  pub fn position(&self) -> usize {
+    match *self {
+      BracketError::Unexpected { position, .. }
+      | BracketError::Mismatched { position, .. }
+      | BracketError::Unclosed { position, .. } => position
+    }
+  }
+}
+
+impl fmt::Display for BracketError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BracketError::Unexpected { position, found } => {
+        write!(f, "unexpected {:?} at position {}", found, position)
+      }
+      BracketError::Mismatched { position, expected, found } => {
+        write!(f, "expected {:?} but found {:?} at position {}", expected, found, position)
+      }
+      BracketError::Unclosed { position, bracket } => {
+        write!(f, "{:?} at position {} is never closed", bracket, position)
+      }
+    }
+  }
+}
+
+impl core::error::Error for BracketError {}
+
+/// A set of bracket pairs to validate texts with.
+///
+/// The opening and the closing bracket of a pair may be the same
+/// character, like `|` in `|x|`: it then closes the innermost open
+/// bracket if that is the same character, and opens a new one otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Brackets {
+  /// The opening and the closing bracket of every pair.
+  pairs: Vec<(char, char)>
+}
+
+impl Default for Brackets {
+  /// Creates a new instance of `Brackets` with default values: round,
+  /// square and curly brackets.
+  fn default() -> Self {
+    Self::new(&[('(', ')'), ('[', ']'), ('{', '}')])
+  }
+}
+
+impl Brackets {
+  /// Creates a set of bracket pairs, given as `(opening, closing)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a character is part of more than one pair.
+  pub fn new(pairs: &[(char, char)]) -> Self {
+    for (index, &(open, close)) in pairs.iter().enumerate() {
+      assert!(
+        pairs[index + 1..].iter().all(|&(other_open, other_close)| {
+          ![other_open, other_close].contains(&open) && ![other_open, other_close].contains(&close)
+        }),
+        "bracket pairs must not share characters"
+      );
+    }
+
+    Self { pairs: pairs.to_vec() }
+  }
+
+  /// Returns the bracket pairs as `(opening, closing)`.
+  pub fn pairs(&self) -> &[(char, char)] {
+    &self.pairs
+  }
+
+  /// Checks that the brackets of the text are balanced, all other
+  /// characters are ignored.
+  ///
+  /// # Errors
+  ///
+  /// Returns a `BracketError` describing the first bracket that is out of
+  /// place, or the first one that is never closed.
+  pub fn validate(&self, text: &str) -> Result<(), BracketError> {
+    // The open brackets with their positions, the innermost on top.
+    let mut open: Stack<(usize, char)> = Stack::new();
+
+    for (position, character) in text.char_indices() {
+      let innermost = open.peek().map(|&(_, bracket)| self.closing(bracket));
+
+      if innermost == Some(character) {
+        open.pop();
+      } else if self.pairs.iter().any(|&(opening, _)| opening == character) {
+        open.push((position, character));
+      } else if self.pairs.iter().any(|&(_, closing)| closing == character) {
+        return Err(match innermost {
+          Some(expected) => BracketError::Mismatched { position, expected, found: character },
+          None => BracketError::Unexpected { position, found: character }
+        });
+      }
+    }
+
+    match open.as_slice().first() {
+      Some(&(position, bracket)) => Err(BracketError::Unclosed { position, bracket }),
+      None => Ok(())
+    }
+  }
+
+  /// Returns the closing bracket of an opening bracket.
+  fn closing(&self, opening: char) -> char {
+    self
+      .pairs
+      .iter()
+      .find(|&&(open, _)| open == opening)
+      .map(|&(_, close)| close)
+      .expect("opening bracket has a pair")
+  }
+}
+
+/// Checks that the round, square and curly brackets of the text are
+/// balanced.
+///
+/// # Errors
+///
+/// Returns a `BracketError` describing the first bracket that is out of
+/// place, or the first one that is never closed.
+pub fn validate(text: &str) -> Result<(), BracketError> {
+  Brackets::default().validate(text)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{validate, BracketError, Brackets};
+  use alloc::format;
+  use alloc::string::String;
+  use proptest::prelude::*;
+
+  #[test]
+  fn balanced() {
+    assert_eq!(validate(""), Ok(()));
+    assert_eq!(validate("no brackets"), Ok(()));
+    assert_eq!(validate("{[()()]}[]"), Ok(()));
+  }
+
+  #[test]
+  fn unbalanced() {
+    assert_eq!(validate("())"), Err(BracketError::Unexpected { position: 2, found: ')' }));
+    assert_eq!(validate("{(})"), Err(BracketError::Mismatched { position: 2, expected: ')', found: '}' }));
+    assert_eq!(validate("([)"), Err(BracketError::Mismatched { position: 2, expected: ']', found: ')' }));
+    assert_eq!(validate("( [ {}"), Err(BracketError::Unclosed { position: 0, bracket: '(' }));
+  }
+
+  #[test]
+  fn positions_are_byte_offsets() {
+    let error = validate("é(").unwrap_err();
+
+    assert_eq!(error, BracketError::Unclosed { position: 2, bracket: '(' });
+    assert_eq!(error.position(), 2);
+  }
+
+  #[test]
+  fn custom_pairs() {
+    let brackets = Brackets::new(&[('<', '>'), ('|', '|')]);
+
+    assert_eq!(brackets.validate("<|x|> (ignored"), Ok(()));
+    assert_eq!(brackets.validate("|<x|>"), Err(BracketError::Mismatched { position: 4, expected: '|', found: '>' }));
+    assert_eq!(brackets.validate("<|"), Err(BracketError::Unclosed { position: 0, bracket: '<' }));
+  }
+
+  #[test]
+  #[should_panic(expected = "bracket pairs must not share characters")]
+  fn shared_characters() {
+    Brackets::new(&[('(', ')'), (')', '(')]);
+  }
+
+  /// Generates a balanced text of round and square brackets.
+  fn balanced_text() -> impl Strategy<Value = String> {
+    let leaf = Just(String::new());
+    leaf.prop_recursive(4, 64, 4, |inner| {
+      prop::collection::vec((inner, any::<bool>()), 1..4).prop_map(|groups| {
+        groups
+          .into_iter()
+          .map(|(text, square)| if square { format!("[{}]", text) } else { format!("({})", text) })
+          .collect()
+      })
+    })
+  }
+
+  proptest! {
+    #[test]
+    fn balanced_texts_are_valid(text in balanced_text()) {
+      prop_assert_eq!(validate(&text), Ok(()));
+    }
+
+    #[test]
+    fn removing_a_bracket_unbalances(text in balanced_text(), index in any::<prop::sample::Index>()) {
+      prop_assume!(!text.is_empty());
+      let mut text = text;
+      text.remove(index.index(text.len()));
+
+      prop_assert!(validate(&text).is_err());
+    }
+  }
+}
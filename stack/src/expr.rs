@@ -0,0 +1,435 @@
+//! Arithmetic expressions evaluated with stacks.
+//!
+//! An infix expression such as `2 * (3 + 4)` is first converted to
+//! postfix (reverse Polish) notation with the shunting-yard algorithm,
+//! which holds the pending operators on a stack until their operands are
+//! complete, giving `2 3 4 + *`. A postfix expression is then evaluated
+//! with a stack of values: a number is pushed, and an operator pops its
+//! operands and pushes the result.
+//!
+//! Expressions are made of integers, the binary operators `+`, `-`, `*`,
+//! `/`, `%` and `^` (power, right-associative), the unary minus and
+//! parentheses. The arithmetic is done on `i64`, and overflows are
+//! reported as errors instead of wrapping.
+//!
+//! # Example
+//!
+//! ```
+//! use stack::expr::{self, ExprError};
+//!
+//! assert_eq!(expr::evaluate("2 * (3 + 4) - 2 ^ 3"), Ok(6));
+//!
+//! let postfix = expr::to_postfix("-(1 + 2) * 3").unwrap();
+//! assert_eq!(expr::format_postfix(&postfix), "1 2 + neg 3 *");
+//!
+//! assert_eq!(expr::evaluate("(1 + 2"), Err(ExprError::UnmatchedOpenParen { position: 0 }));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::Stack;
+
+
+/// An operator of an arithmetic expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Remainder,
+  Power,
+  /// The unary minus.
+  Negate
+}
+
+impl Operator {
+  /// Returns the precedence of the operator, operators with a higher
+  /// precedence are applied first.
+  pub fn precedence(self) -> u8 {
+    match self {
+      Operator::Add | Operator::Subtract => 1,
+      Operator::Multiply | Operator::Divide | Operator::Remainder => 2,
+      Operator::Negate => 3,
+      Operator::Power => 4
+    }
+  }
+
+  /// Checks if a chain of the operator is grouped from the right, like
+  /// `2 ^ 3 ^ 2 = 2 ^ (3 ^ 2)`.
+  pub fn is_right_associative(self) -> bool {
+    matches!(self, Operator::Power | Operator::Negate)
+  }
+
+  /// Returns the number of operands of the operator.
+  pub fn arity(self) -> usize {
+    match self {
+      Operator::Negate => 1,
+      _ => 2
+    }
+  }
+
+  /// Applies a binary operator, the second operand is ignored by the
+  /// unary minus.
+  fn apply(self, left: i64, right: i64) -> Result<i64, ExprError> {
+    let result = match self {
+      Operator::Add => left.checked_add(right),
+      Operator::Subtract => left.checked_sub(right),
+      Operator::Multiply => left.checked_mul(right),
+      Operator::Divide | Operator::Remainder if right == 0 => return Err(ExprError::DivisionByZero),
+      Operator::Divide => left.checked_div(right),
+      Operator::Remainder => left.checked_rem(right),
+      Operator::Power => {
+        let exponent = u32::try_from(right).map_err(|_| {
+          if right < 0 { ExprError::NegativeExponent } else { ExprError::Overflow }
+        })?;
+        left.checked_pow(exponent)
+      }
+      Operator::Negate => left.checked_neg()
+    };
+
+    result.ok_or(ExprError::Overflow)
+  }
+
+  /// Returns the binary operator written as `symbol`.
+  fn binary(symbol: char) -> Option<Self> {
+    match symbol {
+      '+' => Some(Operator::Add),
+      '-' => Some(Operator::Subtract),
+      '*' => Some(Operator::Multiply),
+      '/' => Some(Operator::Divide),
+      '%' => Some(Operator::Remainder),
+      '^' => Some(Operator::Power),
+      _ => None
+    }
+  }
+}
+
+impl fmt::Display for Operator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let symbol = match self {
+      Operator::Add => "+",
+      Operator::Subtract => "-",
+      Operator::Multiply => "*",
+      Operator::Divide => "/",
+      Operator::Remainder => "%",
+      Operator::Power => "^",
+      Operator::Negate => "neg"
+    };
+
+    write!(f, "{}", symbol)
+  }
+}
+
+/// A token of an expression in postfix notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+  Number(i64),
+  Operator(Operator)
+}
+
+impl fmt::Display for Token {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Token::Number(number) => write!(f, "{}", number),
+      Token::Operator(operator) => write!(f, "{}", operator)
+    }
+  }
+}
+
+/// The error returned when an expression cannot be parsed or evaluated.
+///
+/// Positions are byte offsets into the expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprError {
+  /// A character that is not part of any token.
+  InvalidCharacter { position: usize, character: char },
+  /// A number that does not fit in an `i64`.
+  NumberTooLarge { position: usize },
+  /// An operator or a parenthesis where an operand is expected, or the
+  /// other way around.
+  UnexpectedToken { position: usize },
+  /// The expression ends where an operand is expected, including an
+  /// empty expression.
+  UnexpectedEnd,
+  /// An opening parenthesis that is never closed.
+  UnmatchedOpenParen { position: usize },
+  /// A closing parenthesis without an opening one.
+  UnmatchedCloseParen { position: usize },
+  /// A postfix operator without enough operands on the stack.
+  MissingOperand,
+  /// A postfix expression that leaves more than one value on the stack.
+  ExtraOperand,
+  DivisionByZero,
+  NegativeExponent,
+  /// The result does not fit in an `i64`.
+  Overflow
+}
+
+impl fmt::Display for ExprError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ExprError::InvalidCharacter { position, character } => {
+        write!(f, "invalid character {:?} at position {}", character, position)
+      }
+      ExprError::NumberTooLarge { position } => write!(f, "number at position {} is too large", position),
+      ExprError::UnexpectedToken { position } => write!(f, "unexpected token at position {}", position),
+      ExprError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+      ExprError::UnmatchedOpenParen { position } => write!(f, "unmatched '(' at position {}", position),
+      ExprError::UnmatchedCloseParen { position } => write!(f, "unmatched ')' at position {}", position),
+      ExprError::MissingOperand => write!(f, "operator is missing an operand"),
+      ExprError::ExtraOperand => write!(f, "expression has operands without an operator"),
+      ExprError::DivisionByZero => write!(f, "division by zero"),
+      ExprError::NegativeExponent => write!(f, "negative exponent"),
+      ExprError::Overflow => write!(f, "arithmetic overflow")
+    }
+  }
+}
+
+impl core::error::Error for ExprError {}
+
+/// An entry of the operator stack of the shunting-yard algorithm.
+enum Pending {
+  Operator(Operator),
+  /// An opening parenthesis and its position.
+  Paren(usize)
+}
+
+/// Converts an infix expression to postfix notation with the
+/// shunting-yard algorithm.
+///
+/// # Errors
+///
+/// Returns an `ExprError` describing the first problem found, such as an
+/// unmatched parenthesis or a missing operand.
+pub fn to_postfix(expression: &str) -> Result<Vec<Token>, ExprError> {
+  let mut output = Vec::new();
+  let mut operators: Stack<Pending> = Stack::new();
+  // Whether the next token has to be an operand: a number, a unary
+  // minus or an opening parenthesis.
+  let mut expect_operand = true;
+  let mut chars = expression.char_indices().peekable();
+
+  while let Some((position, character)) = chars.next() {
+    match character {
+      _ if character.is_whitespace() => {}
+      '0'..='9' => {
+        if !expect_operand {
+          return Err(ExprError::UnexpectedToken { position });
+        }
+
+        let mut end = position + 1;
+        while let Some(&(index, '0'..='9')) = chars.peek() {
+          end = index + 1;
+          chars.next();
+        }
+
+        let number = expression[position..end].parse().map_err(|_| ExprError::NumberTooLarge { position })?;
+        output.push(Token::Number(number));
+        expect_operand = false;
+      }
+      '(' if expect_operand => operators.push(Pending::Paren(position)),
+      ')' if !expect_operand => loop {
+        match operators.pop() {
+          Some(Pending::Operator(operator)) => output.push(Token::Operator(operator)),
+          Some(Pending::Paren(_)) => break,
+          None => return Err(ExprError::UnmatchedCloseParen { position })
+        }
+      },
+      '-' if expect_operand => operators.push(Pending::Operator(Operator::Negate)),
+      _ if !expect_operand && Operator::binary(character).is_some() => {
+        let operator = Operator::binary(character).unwrap();
+
+        while let Some(Pending::Operator(top)) = operators.peek() {
+          let top = *top;
+          if top.precedence() < operator.precedence()
+            || (top.precedence() == operator.precedence() && operator.is_right_associative())
+          {
+            break;
+          }
+
+          operators.pop();
+          output.push(Token::Operator(top));
+        }
+
+        operators.push(Pending::Operator(operator));
+        expect_operand = true;
+      }
+      '(' | ')' => return Err(ExprError::UnexpectedToken { position }),
+      _ if Operator::binary(character).is_some() => return Err(ExprError::UnexpectedToken { position }),
+      _ => return Err(ExprError::InvalidCharacter { position, character })
+    }
+  }
+
+  if expect_operand {
+    return Err(ExprError::UnexpectedEnd);
+  }
+
+  while let Some(pending) = operators.pop() {
+    match pending {
+      Pending::Operator(operator) => output.push(Token::Operator(operator)),
+      Pending::Paren(position) => {
+        // Report the outermost unclosed parenthesis.
+        let position = operators
+          .as_slice()
+          .iter()
+          .find_map(|pending| match pending {
+            Pending::Paren(position) => Some(*position),
+            Pending::Operator(_) => None
+          })
+          .unwrap_or(position);
+        return Err(ExprError::UnmatchedOpenParen { position });
+      }
+    }
+  }
+
+  Ok(output)
+}
+
+/// Evaluates an expression in postfix notation.
+///
+/// # Errors
+///
+/// Returns `MissingOperand` or `ExtraOperand` if the tokens do not form
+/// a postfix expression, or an arithmetic error.
+pub fn evaluate_postfix(tokens: &[Token]) -> Result<i64, ExprError> {
+  let mut values: Stack<i64> = Stack::new();
+
+  for token in tokens {
+    match *token {
+      Token::Number(number) => values.push(number),
+      Token::Operator(operator) => {
+        let right = values.pop().ok_or(ExprError::MissingOperand)?;
+        let result = if operator.arity() == 1 {
+          operator.apply(right, 0)?
+        } else {
+          let left = values.pop().ok_or(ExprError::MissingOperand)?;
+          operator.apply(left, right)?
+        };
+
+        values.push(result);
+      }
+    }
+  }
+
+  let result = values.pop().ok_or(ExprError::MissingOperand)?;
+  if !values.is_empty() {
+    return Err(ExprError::ExtraOperand);
+  }
+
+  Ok(result)
+}
+
+/// Evaluates an infix expression.
+///
+/// # Errors
+///
+/// Returns an `ExprError` if the expression is malformed or its
+/// evaluation fails.
+pub fn evaluate(expression: &str) -> Result<i64, ExprError> {
+  evaluate_postfix(&to_postfix(expression)?)
+}
+
+/// Formats postfix tokens separated by spaces, writing the unary minus
+/// as `neg`.
+pub fn format_postfix(tokens: &[Token]) -> String {
+  let mut output = String::new();
+
+  for (index, token) in tokens.iter().enumerate() {
+    if index > 0 {
+      output.push(' ');
+    }
+    // Writing to a `String` never fails.
+    let _ = write!(output, "{}", token);
+  }
+
+  output
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{evaluate, evaluate_postfix, format_postfix, to_postfix, ExprError, Operator, Token};
+  use alloc::string::String;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  fn postfix(expression: &str) -> String {
+    format_postfix(&to_postfix(expression).unwrap())
+  }
+
+  #[test]
+  fn precedence_and_associativity() {
+    assert_eq!(postfix("1 + 2 * 3"), "1 2 3 * +");
+    assert_eq!(postfix("1 - 2 - 3"), "1 2 - 3 -");
+    assert_eq!(postfix("2 ^ 3 ^ 2"), "2 3 2 ^ ^");
+    assert_eq!(postfix("(1 + 2) * 3"), "1 2 + 3 *");
+    assert_eq!(postfix("-2 ^ 2"), "2 2 ^ neg");
+    assert_eq!(postfix("2 ^ -1 * 3"), "2 1 neg ^ 3 *");
+  }
+
+  #[test]
+  fn evaluate_expressions() {
+    assert_eq!(evaluate("42"), Ok(42));
+    assert_eq!(evaluate("2 * (3 + 4) - 5"), Ok(9));
+    assert_eq!(evaluate("2 ^ 3 ^ 2"), Ok(512));
+    assert_eq!(evaluate("-2 ^ 2"), Ok(-4));
+    assert_eq!(evaluate("(-2) ^ 2"), Ok(4));
+    assert_eq!(evaluate("7 / 2 + 7 % 2 - --1"), Ok(3));
+    assert_eq!(evaluate("((((1))))"), Ok(1));
+  }
+
+  #[test]
+  fn mismatched_parentheses() {
+    assert_eq!(evaluate("((1 + 2) * 3"), Err(ExprError::UnmatchedOpenParen { position: 0 }));
+    assert_eq!(evaluate("(1) + (2"), Err(ExprError::UnmatchedOpenParen { position: 6 }));
+    assert_eq!(evaluate("(1 + (2"), Err(ExprError::UnmatchedOpenParen { position: 0 }));
+    assert_eq!(evaluate("1 + 2)"), Err(ExprError::UnmatchedCloseParen { position: 5 }));
+    assert_eq!(evaluate("()"), Err(ExprError::UnexpectedToken { position: 1 }));
+  }
+
+  #[test]
+  fn syntax_errors() {
+    assert_eq!(evaluate(""), Err(ExprError::UnexpectedEnd));
+    assert_eq!(evaluate("1 +"), Err(ExprError::UnexpectedEnd));
+    assert_eq!(evaluate("1 * / 2"), Err(ExprError::UnexpectedToken { position: 4 }));
+    assert_eq!(evaluate("1 2"), Err(ExprError::UnexpectedToken { position: 2 }));
+    assert_eq!(evaluate("1 + x"), Err(ExprError::InvalidCharacter { position: 4, character: 'x' }));
+    assert_eq!(evaluate("99999999999999999999"), Err(ExprError::NumberTooLarge { position: 0 }));
+  }
+
+  #[test]
+  fn arithmetic_errors() {
+    assert_eq!(evaluate("1 / (2 - 2)"), Err(ExprError::DivisionByZero));
+    assert_eq!(evaluate("1 % 0"), Err(ExprError::DivisionByZero));
+    assert_eq!(evaluate("2 ^ -1"), Err(ExprError::NegativeExponent));
+    assert_eq!(evaluate("2 ^ 63"), Err(ExprError::Overflow));
+    assert_eq!(evaluate("-9223372036854775807 - 2"), Err(ExprError::Overflow));
+  }
+
+  #[test]
+  fn malformed_postfix() {
+    let add = Token::Operator(Operator::Add);
+
+    assert_eq!(evaluate_postfix(&[Token::Number(1), add]), Err(ExprError::MissingOperand));
+    assert_eq!(evaluate_postfix(&[Token::Number(1), Token::Number(2)]), Err(ExprError::ExtraOperand));
+    assert_eq!(evaluate_postfix(&[]), Err(ExprError::MissingOperand));
+  }
+
+  proptest! {
+    #[test]
+    fn sums_and_products(terms in prop::collection::vec(prop::collection::vec(-100..100i64, 1..4), 1..5)) {
+      // A sum of products, each factor in parentheses.
+      let expression = terms
+        .iter()
+        .map(|factors| factors.iter().map(|factor| alloc::format!("({})", factor)).collect::<Vec<_>>().join(" * "))
+        .collect::<Vec<_>>()
+        .join(" + ");
+      let expected: i64 = terms.iter().map(|factors| factors.iter().product::<i64>()).sum();
+
+      prop_assert_eq!(evaluate(&expression), Ok(expected));
+    }
+  }
+}
@@ -0,0 +1,275 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::storage::Storage;
+use crate::StackFullError;
+
+
+/// A stack whose values are kept in a pluggable `Storage`.
+///
+/// It has the same methods as `Stack`, so switching the storage only
+/// changes the type of the stack and not the code that uses it. The
+/// default storage is a `Vec`, see the `storage` module for the others.
+///
+/// # Example
+///
+/// ```
+/// use linked_list::LinkedList;
+/// use stack::storage::{ArrayStorage, Hybrid};
+/// use stack::GenericStack;
+///
+/// fn fill<S: stack::storage::Storage<i32>>(stack: &mut GenericStack<i32, S>) {
+///   for value in 1..=3 {
+///     if stack.try_push(value).is_err() {
+///       break;
+///     }
+///   }
+/// }
+///
+/// let mut vec = GenericStack::<i32>::new();
+/// let mut list = GenericStack::<i32, LinkedList<i32>>::new();
+/// let mut array = GenericStack::<i32, ArrayStorage<i32, 2>>::new();
+/// let mut hybrid = GenericStack::<i32, Hybrid<i32, 2>>::new();
+/// fill(&mut vec);
+/// fill(&mut list);
+/// fill(&mut array);
+/// fill(&mut hybrid);
+///
+/// assert_eq!(vec.peek(), Some(&3));
+/// assert_eq!(list.peek(), Some(&3));
+/// assert_eq!(array.peek(), Some(&2));
+/// assert_eq!(hybrid.storage().spilled(), 1);
+/// ```
+pub struct GenericStack<T, S = Vec<T>> {
+  storage: S,
+  marker: PhantomData<T>
+}
+
+impl<T, S: Storage<T> + Default> Default for GenericStack<T, S> {
+  /// Creates a new instance of `GenericStack` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, S: Storage<T> + Default> GenericStack<T, S> {
+  /// Creates a new empty instance of `GenericStack`.
+  pub fn new() -> Self {
+    Self::with_storage(S::default())
+  }
+}
+
+impl<T, S: Storage<T>> GenericStack<T, S> {
+  /// Creates a stack on top of the given storage, the values it already
+  /// holds stay in the stack.
+  pub fn with_storage(storage: S) -> Self {
+    Self { storage, marker: PhantomData }
+  }
+
+  /// Returns a reference to the storage of the stack.
+  pub fn storage(&self) -> &S {
+    &self.storage
+  }
+
+  /// Consumes the stack and returns its storage.
+  pub fn into_storage(self) -> S {
+    self.storage
+  }
+
+  /// Pushes a value onto the top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the storage is full, use `try_push` to handle that case.
+  pub fn push(&mut self, value: T) {
+    assert!(self.storage.push(value).is_ok(), "stack is full");
+  }
+
+  /// Pushes a value onto the top of the stack, or returns it back
+  /// inside `StackFullError` if the storage is full.
+  pub fn try_push(&mut self, value: T) -> Result<(), StackFullError<T>> {
+    self.storage.push(value).map_err(StackFullError)
+  }
+
+  /// Removes the top value from the stack and returns it,
+  /// or `None` if the stack is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    self.storage.pop()
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    while self.storage.pop().is_some() {}
+  }
+
+  /// Returns a reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.storage.top()
+  }
+
+  /// Returns a mutable reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek_mut(&mut self) -> Option<&mut T> {
+    self.storage.top_mut()
+  }
+
+  /// Checks if the stack is empty.
+  pub fn is_empty(&self) -> bool {
+    self.storage.is_empty()
+  }
+
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.storage.len()
+  }
+
+  /// Returns an iterator over the elements of the stack, from the top
+  /// to the bottom.
+  pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+    self.storage.iter()
+  }
+}
+
+impl<T, S: Storage<T> + Default> FromIterator<T> for GenericStack<T, S> {
+  /// Creates a stack by pushing every value of the iterator in order,
+  /// so the last value ends up on top.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the storage becomes full.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut stack = Self::new();
+    stack.extend(iter);
+    stack
+  }
+}
+
+impl<T, S: Storage<T>> Extend<T> for GenericStack<T, S> {
+  /// Pushes every value of the iterator onto the stack in order.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the storage becomes full.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T, S: Clone> Clone for GenericStack<T, S> {
+  fn clone(&self) -> Self {
+    Self { storage: self.storage.clone(), marker: PhantomData }
+  }
+}
+
+impl<T: fmt::Debug, S: Storage<T>> fmt::Debug for GenericStack<T, S> {
+  /// Formats the elements from the bottom of the stack to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let values: Vec<&T> = self.iter().collect();
+    f.debug_list().entries(values.into_iter().rev()).finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use alloc::format;
+  use alloc::vec::Vec;
+
+  use super::GenericStack;
+  use crate::storage::{ArrayStorage, Hybrid, Storage};
+  use crate::StackFullError;
+  use linked_list::LinkedList;
+  use proptest::prelude::*;
+
+  #[test]
+  fn default_storage() {
+    let mut stack = GenericStack::<i32>::new();
+    stack.push(42);
+    stack.push(314);
+
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.peek(), Some(&314));
+    assert_eq!(stack.pop(), Some(314));
+    *stack.peek_mut().unwrap() += 1;
+    assert_eq!(stack.into_storage(), [43]);
+  }
+
+  #[test]
+  fn try_push_full() {
+    let mut stack = GenericStack::<i32, ArrayStorage<i32, 1>>::new();
+
+    assert_eq!(stack.try_push(42), Ok(()));
+    assert_eq!(stack.try_push(314), Err(StackFullError(314)));
+    assert_eq!(stack.len(), 1);
+  }
+
+  #[test]
+  #[should_panic(expected = "stack is full")]
+  fn push_full() {
+    let mut stack = GenericStack::<i32, ArrayStorage<i32, 1>>::new();
+    stack.push(42);
+    stack.push(314);
+  }
+
+  #[test]
+  fn with_storage() {
+    let stack = GenericStack::with_storage(alloc::vec![1, 2, 3]);
+
+    assert_eq!(stack.peek(), Some(&3));
+    assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [3, 2, 1]);
+  }
+
+  #[test]
+  fn debug_and_clear() {
+    let mut stack: GenericStack<i32, LinkedList<i32>> = (1..=3).collect();
+
+    assert_eq!(format!("{stack:?}"), "[1, 2, 3]");
+    assert_eq!(format!("{:?}", stack.clone()), "[1, 2, 3]");
+
+    stack.clear();
+    assert!(stack.is_empty());
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![any::<i32>().prop_map(Operation::Push), Just(Operation::Pop)]
+  }
+
+  /// Applies the operations to a stack and returns the popped values
+  /// followed by the values left in the stack.
+  fn run<S: Storage<i32> + Default>(operations: &[Operation]) -> Vec<Option<i32>> {
+    let mut stack = GenericStack::<i32, S>::new();
+    let mut output = Vec::new();
+
+    for operation in operations {
+      match *operation {
+        Operation::Push(value) => stack.push(value),
+        Operation::Pop => output.push(stack.pop())
+      }
+    }
+
+    output.extend(stack.iter().copied().map(Some));
+    output
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn storages_agree(operations in prop::collection::vec(operation(), 0..100)) {
+      let expected = run::<Vec<_>>(&operations);
+
+      prop_assert_eq!(&run::<LinkedList<_>>(&operations), &expected);
+      prop_assert_eq!(&run::<Hybrid<_, 4>>(&operations), &expected);
+      prop_assert_eq!(&run::<ArrayStorage<_, 100>>(&operations), &expected);
+    }
+  }
+}
@@ -0,0 +1,346 @@
+use crate::Stack;
+
+
+/// An undo/redo history built on two stacks.
+///
+/// Every entry, for example an edit made to a document, is pushed on the
+/// undo stack. Undoing moves the latest entry to the redo stack, and
+/// redoing moves it back, so both take O(1) time. Pushing a new entry
+/// clears the redo stack, since the undone entries no longer follow from
+/// the latest one.
+///
+/// A history with a capacity limit forgets its oldest entries once the
+/// undo stack is full. A `Checkpoint` marks a position in the history,
+/// for example the last saved one, that can later be compared with the
+/// current position or restored.
+///
+/// # Example
+///
+/// ```
+/// use stack::History;
+///
+/// let mut history = History::new();
+/// history.push("type a");
+/// history.push("type b");
+///
+/// assert_eq!(history.undo(), Some(&"type b"));
+/// assert_eq!(history.undo(), Some(&"type a"));
+/// assert_eq!(history.undo(), None);
+/// assert_eq!(history.redo(), Some(&"type a"));
+///
+/// // A new entry discards the undone ones.
+/// history.push("type c");
+/// assert_eq!(history.redo(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct History<T> {
+  /// Entries that can be undone, the latest on top, each with its id.
+  undo: Stack<(u64, T)>,
+  /// Entries that can be redone, the latest undone on top.
+  redo: Stack<(u64, T)>,
+  /// Maximum number of entries that can be undone.
+  limit: Option<usize>,
+  /// Id of the next pushed entry.
+  next_id: u64,
+  /// Whether the oldest entries were forgotten.
+  evicted: bool
+}
+
+/// A position in a `History`, returned by `History::checkpoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checkpoint(
+  /// Id of the latest entry at the checkpoint, `None` before any entry.
+  Option<u64>
+);
+
+impl<T> Default for History<T> {
+  /// Creates a new instance of `History` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> History<T> {
+  /// Creates a new empty history without a capacity limit.
+  pub fn new() -> Self {
+    Self { undo: Stack::new(), redo: Stack::new(), limit: None, next_id: 0, evicted: false }
+  }
+
+  /// Creates a new empty history that keeps at most `limit` entries that
+  /// can be undone, forgetting the oldest ones.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the limit is zero.
+  pub fn with_capacity_limit(limit: usize) -> Self {
+    assert!(limit > 0, "capacity limit must be positive");
+    Self { limit: Some(limit), ..Self::new() }
+  }
+
+  /// Returns the maximum number of entries that can be undone, or `None`
+  /// if the history is unbounded.
+  pub fn capacity_limit(&self) -> Option<usize> {
+    self.limit
+  }
+
+  /// Returns the number of entries that can be undone.
+  pub fn undo_len(&self) -> usize {
+    self.undo.len()
+  }
+
+  /// Returns the number of entries that can be redone.
+  pub fn redo_len(&self) -> usize {
+    self.redo.len()
+  }
+
+  /// Checks if the history has neither entries to undo nor to redo.
+  pub fn is_empty(&self) -> bool {
+    self.undo.is_empty() && self.redo.is_empty()
+  }
+
+  /// Returns the latest entry, the one `undo` would undo next.
+  pub fn latest(&self) -> Option<&T> {
+    self.undo.peek().map(|(_, entry)| entry)
+  }
+
+  /// Adds a new entry and discards all entries that can be redone.
+  ///
+  /// If the history is full, its oldest entry is forgotten, which takes
+  /// O(n) time.
+  pub fn push(&mut self, entry: T) {
+    self.redo.clear();
+
+    if self.limit.is_some_and(|limit| self.undo.len() >= limit) {
+      self.undo.items.remove(0);
+      self.evicted = true;
+    }
+
+    self.undo.push((self.next_id, entry));
+    self.next_id += 1;
+  }
+
+  /// Undoes the latest entry and returns it, or `None` if there is
+  /// nothing to undo.
+  pub fn undo(&mut self) -> Option<&T> {
+    let entry = self.undo.pop()?;
+    self.redo.push(entry);
+    self.redo.peek().map(|(_, entry)| entry)
+  }
+
+  /// Redoes the latest undone entry and returns it, or `None` if there
+  /// is nothing to redo.
+  pub fn redo(&mut self) -> Option<&T> {
+    let entry = self.redo.pop()?;
+    self.undo.push(entry);
+    self.latest()
+  }
+
+  /// Discards all entries that can be redone and returns how many there
+  /// were.
+  pub fn truncate_redo(&mut self) -> usize {
+    let len = self.redo.len();
+    self.redo.clear();
+    len
+  }
+
+  /// Returns a checkpoint of the current position.
+  pub fn checkpoint(&self) -> Checkpoint {
+    Checkpoint(self.undo.peek().map(|&(id, _)| id))
+  }
+
+  /// Checks if the history is at the position of the checkpoint, for
+  /// example to tell if a document has changed since it was saved.
+  pub fn is_at(&self, checkpoint: Checkpoint) -> bool {
+    self.checkpoint() == checkpoint
+  }
+
+  /// Undoes or redoes entries until the history is at the position of the
+  /// checkpoint. To revert or reapply the entries one by one instead, call
+  /// `undo` or `redo` until `is_at` returns `true`.
+  ///
+  /// Returns `false` and leaves the history unchanged if the position was
+  /// discarded by a push or `truncate_redo`, or forgotten because of the
+  /// capacity limit.
+  pub fn restore(&mut self, checkpoint: Checkpoint) -> bool {
+    let contains = |stack: &Stack<(u64, T)>| stack.iter().any(|&(id, _)| Some(id) == checkpoint.0);
+
+    if checkpoint.0.is_none() && !self.evicted || contains(&self.undo) {
+      while !self.is_at(checkpoint) {
+        self.undo();
+      }
+    } else if contains(&self.redo) {
+      while !self.is_at(checkpoint) {
+        self.redo();
+      }
+    } else {
+      return false;
+    }
+
+    true
+  }
+
+  /// Removes all entries.
+  pub fn clear(&mut self) {
+    self.undo.clear();
+    self.redo.clear();
+    self.evicted = false;
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::History;
+  use alloc::vec::Vec;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_history() {
+    let mut history = History::<i32>::new();
+
+    assert!(history.is_empty());
+    assert_eq!(history.latest(), None);
+    assert_eq!(history.undo(), None);
+    assert_eq!(history.redo(), None);
+  }
+
+  #[test]
+  fn undo_and_redo() {
+    let mut history = History::new();
+    history.push(1);
+    history.push(2);
+
+    assert_eq!(history.undo(), Some(&2));
+    assert_eq!(history.latest(), Some(&1));
+    assert_eq!((history.undo_len(), history.redo_len()), (1, 1));
+    assert_eq!(history.redo(), Some(&2));
+    assert_eq!(history.redo(), None);
+  }
+
+  #[test]
+  fn push_discards_redo() {
+    let mut history = History::new();
+    history.push(1);
+    history.push(2);
+    history.undo();
+    history.push(3);
+
+    assert_eq!(history.redo(), None);
+    assert_eq!(history.undo(), Some(&3));
+    assert_eq!(history.undo(), Some(&1));
+  }
+
+  #[test]
+  fn capacity_limit() {
+    let mut history = History::with_capacity_limit(2);
+    for entry in 1..=4 {
+      history.push(entry);
+    }
+
+    assert_eq!(history.capacity_limit(), Some(2));
+    assert_eq!(history.undo(), Some(&4));
+    assert_eq!(history.undo(), Some(&3));
+    assert_eq!(history.undo(), None);
+  }
+
+  #[test]
+  fn truncate_redo() {
+    let mut history = History::new();
+    history.push(1);
+    history.push(2);
+    history.undo();
+    history.undo();
+
+    assert_eq!(history.truncate_redo(), 2);
+    assert!(history.is_empty());
+  }
+
+  #[test]
+  fn checkpoints() {
+    let mut history = History::new();
+    let start = history.checkpoint();
+    history.push(1);
+    let saved = history.checkpoint();
+    history.push(2);
+    history.push(3);
+    let end = history.checkpoint();
+
+    assert!(!history.is_at(saved));
+    assert!(history.restore(saved));
+    assert_eq!(history.latest(), Some(&1));
+    assert!(history.restore(start));
+    assert_eq!(history.undo_len(), 0);
+    assert!(history.restore(end));
+    assert_eq!(history.latest(), Some(&3));
+    assert!(history.restore(end));
+    assert_eq!(history.redo_len(), 0);
+  }
+
+  #[test]
+  fn discarded_checkpoints() {
+    let mut history = History::with_capacity_limit(2);
+    let start = history.checkpoint();
+    history.push(1);
+    history.push(2);
+    let discarded = history.checkpoint();
+    history.undo();
+    history.push(3);
+    history.push(4);
+
+    assert!(!history.restore(discarded));
+    assert!(!history.restore(start));
+    assert_eq!(history.latest(), Some(&4));
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Undo,
+    Redo,
+    TruncateRedo
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Push),
+      Just(Operation::Undo),
+      Just(Operation::Redo),
+      Just(Operation::TruncateRedo)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_two_vecs(limit in 1..10usize, operations in prop::collection::vec(operation(), 0..100)) {
+      let mut history = History::with_capacity_limit(limit);
+      let (mut undo, mut redo) = (Vec::new(), Vec::new());
+
+      for operation in operations {
+        match operation {
+          Operation::Push(entry) => {
+            history.push(entry);
+            redo.clear();
+            if undo.len() == limit {
+              undo.remove(0);
+            }
+            undo.push(entry);
+          }
+          Operation::Undo => {
+            let entry = undo.pop();
+            prop_assert_eq!(history.undo(), entry.as_ref());
+            redo.extend(entry);
+          }
+          Operation::Redo => {
+            let entry = redo.pop();
+            prop_assert_eq!(history.redo(), entry.as_ref());
+            undo.extend(entry);
+          }
+          Operation::TruncateRedo => prop_assert_eq!(history.truncate_redo(), core::mem::take(&mut redo).len())
+        }
+
+        prop_assert_eq!(history.latest(), undo.last());
+        prop_assert_eq!((history.undo_len(), history.redo_len()), (undo.len(), redo.len()));
+      }
+    }
+  }
+}
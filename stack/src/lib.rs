@@ -1,4 +1,53 @@
-use std::ops::Deref;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Tests always have an allocator, whatever the features.
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::{Drain, Vec};
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "alloc")]
+use core::iter::Rev;
+#[cfg(feature = "deref")]
+use core::ops::Deref;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+mod array;
+#[cfg(feature = "alloc")]
+pub mod brackets;
+#[cfg(feature = "alloc")]
+pub mod expr;
+#[cfg(feature = "alloc")]
+mod generic;
+#[cfg(feature = "alloc")]
+mod history;
+#[cfg(feature = "alloc")]
+mod min_stack;
+#[cfg(feature = "alloc")]
+mod monotonic;
+#[cfg(feature = "alloc")]
+mod small;
+pub mod storage;
+#[cfg(feature = "alloc")]
+mod two_stack_queue;
+
+pub use array::ArrayStack;
+#[cfg(feature = "alloc")]
+pub use generic::GenericStack;
+#[cfg(feature = "alloc")]
+pub use history::{Checkpoint, History};
+#[cfg(feature = "alloc")]
+pub use min_stack::MinStack;
+#[cfg(feature = "alloc")]
+pub use monotonic::MonotonicStack;
+#[cfg(feature = "alloc")]
+pub use small::SmallStack;
+#[cfg(feature = "alloc")]
+pub use two_stack_queue::TwoStackQueue;
 
 
 /// A generic stack data structure.
@@ -11,6 +60,8 @@ use std::ops::Deref;
 /// # Example
 /// 
 /// ```
+/// use stack::Stack;
+///
 /// let mut stack = Stack::<i32>::new();
 /// stack.push(7);
 /// stack.push(32);
@@ -21,20 +72,47 @@ use std::ops::Deref;
 /// }
 /// 
 /// assert!(!stack.is_empty());
-/// assert_eq!(stack.peek(), &32);
+/// assert_eq!(stack.peek(), Some(&32));
 /// assert_eq!(stack.pop(), Some(32));
 /// assert_eq!(stack.len(), 1);
 /// ```
+#[cfg(feature = "alloc")]
 pub struct Stack<T> {
-  items: Vec<T>
+  items: Vec<T>,
+  /// Maximum number of elements, or `None` for an unbounded stack.
+  limit: Option<usize>
+}
+
+/// The error returned by `Stack::try_push` when the stack is full.
+///
+/// It carries the rejected value so it can be recovered.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StackFullError<T>(pub T);
+
+impl<T> StackFullError<T> {
+  /// Returns the value that could not be pushed.
+  pub fn into_inner(self) -> T {
+    self.0
+  }
 }
 
+impl<T> fmt::Display for StackFullError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "stack is full")
+  }
+}
+
+impl<T: fmt::Debug> core::error::Error for StackFullError<T> {}
+
+#[cfg(feature = "alloc")]
 impl<T> Default for Stack<T> {
   /// Creates a new instance of `Stack` with default values.
   /// 
   /// # Example
   /// 
   /// ```
+  /// use stack::Stack;
+  ///
   /// let stack = Stack::<i32>::default();
   /// ```
   fn default() -> Self {
@@ -42,15 +120,72 @@ impl<T> Default for Stack<T> {
   }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> Stack<T> {
   /// Creates a new empty instance of `Stack`.
   pub fn new() -> Self {
-    Self { items: Vec::new() }
+    Self { items: Vec::new(), limit: None }
+  }
+
+  /// Creates a new empty instance of `Stack` that can hold at least
+  /// `capacity` elements without reallocating.
+  ///
+  /// Unlike `with_capacity_limit`, the stack is unbounded and grows
+  /// beyond `capacity` if needed.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self { items: Vec::with_capacity(capacity), limit: None }
+  }
+
+  /// Creates a new empty instance of `Stack` that holds at most
+  /// `limit` elements.
+  ///
+  /// The whole capacity is allocated upfront, so the stack never
+  /// reallocates.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::{Stack, StackFullError};
+  ///
+  /// let mut stack = Stack::<i32>::with_capacity_limit(1);
+  /// assert_eq!(stack.try_push(7), Ok(()));
+  /// assert_eq!(stack.try_push(32), Err(StackFullError(32)));
+  /// ```
+  pub fn with_capacity_limit(limit: usize) -> Self {
+    Self { items: Vec::with_capacity(limit), limit: Some(limit) }
+  }
+
+  /// Returns the maximum number of elements of a bounded stack,
+  /// or `None` if the stack is unbounded.
+  pub fn capacity_limit(&self) -> Option<usize> {
+    self.limit
+  }
+
+  /// Checks if a bounded stack has reached its capacity limit.
+  pub fn is_full(&self) -> bool {
+    self.limit.is_some_and(|limit| self.items.len() >= limit)
   }
 
   /// Pushes a value onto the top of the stack.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack is bounded and full, use `try_push` to
+  /// handle that case.
   pub fn push(&mut self, value: T) {
+    assert!(!self.is_full(), "stack is full");
+    self.items.push(value);
+  }
+
+  /// Pushes a value onto the top of the stack, or returns it back
+  /// inside `StackFullError` if the stack is bounded and full.
+  pub fn try_push(&mut self, value: T) -> Result<(), StackFullError<T>> {
+    if self.is_full() {
+      return Err(StackFullError(value));
+    }
+
     self.items.push(value);
+    Ok(())
   }
 
   /// Removes the top value from the stack and returns it, 
@@ -59,19 +194,162 @@ impl<T> Stack<T> {
     self.items.pop()
   }
 
+  /// Pushes clones of all values of the slice onto the stack, so the
+  /// last value of the slice ends up on top.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack is bounded and the values do not fit; in that
+  /// case the stack is left unchanged.
+  pub fn push_slice(&mut self, values: &[T])
+  where
+    T: Clone,
+  {
+    if let Some(limit) = self.limit {
+      assert!(limit - self.items.len() >= values.len(), "stack is full");
+    }
+
+    self.items.extend_from_slice(values);
+  }
+
+  /// Removes up to `n` values from the top of the stack and returns
+  /// them in the order they were popped (top first).
+  pub fn pop_n(&mut self, n: usize) -> Vec<T> {
+    self.drain_top(n).collect()
+  }
+
+  /// Removes up to `n` values from the top of the stack and returns an
+  /// iterator yielding them from top to bottom.
+  ///
+  /// The values are removed even if the iterator is not fully consumed.
+  pub fn drain_top(&mut self, n: usize) -> Rev<Drain<'_, T>> {
+    let start = self.items.len().saturating_sub(n);
+    self.items.drain(start..).rev()
+  }
+
+  /// Removes all values from the stack and returns an iterator yielding
+  /// them from top to bottom.
+  ///
+  /// The stack is empty afterwards even if the iterator is not fully consumed.
+  pub fn drain(&mut self) -> Rev<Drain<'_, T>> {
+    self.items.drain(..).rev()
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    self.items.clear();
+  }
+
+  /// Keeps only the values for which `keep` returns `true`, without
+  /// changing their order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::Stack;
+  ///
+  /// let mut stack: Stack<i32> = (1..=6).collect();
+  /// stack.retain(|value| value % 2 == 0);
+  ///
+  /// assert_eq!(stack.as_slice(), [2, 4, 6]);
+  /// ```
+  pub fn retain<F>(&mut self, keep: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    self.items.retain(keep);
+  }
+
   /// Returns a reference to the top value of the stack, 
   /// or `None` if the stack is empty.
   pub fn peek(&self) -> Option<&T> {
     self.items.last()
   }
 
+  /// Returns a mutable reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::Stack;
+  ///
+  /// let mut stack: Stack<i32> = (1..=3).collect();
+  /// if let Some(top) = stack.peek_mut() {
+  ///   *top *= 10;
+  /// }
+  ///
+  /// assert_eq!(stack.pop(), Some(30));
+  /// ```
+  pub fn peek_mut(&mut self) -> Option<&mut T> {
+    self.items.last_mut()
+  }
+
   /// Checks if the stack is empty.
   pub fn is_empty(&self) -> bool {
     self.items.is_empty()
   }
 
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Returns the number of elements the stack can hold without
+  /// reallocating.
+  pub fn capacity(&self) -> usize {
+    self.items.capacity()
+  }
+
+  /// Reserves capacity for at least `additional` more elements, so they
+  /// can be pushed without reallocating.
+  ///
+  /// A bounded stack never reserves room beyond its capacity limit.
+  pub fn reserve(&mut self, additional: usize) {
+    let additional = match self.limit {
+      Some(limit) => additional.min(limit.saturating_sub(self.items.len())),
+      None => additional
+    };
+
+    self.items.reserve(additional);
+  }
+
+  /// Shrinks the capacity of the stack as much as possible, releasing
+  /// the memory that its elements do not use.
+  ///
+  /// A bounded stack allocates its whole capacity upfront, after
+  /// shrinking it reallocates again when it grows.
+  pub fn shrink_to_fit(&mut self) {
+    self.items.shrink_to_fit();
+  }
+
+  /// Shortens the stack to its bottom `len` elements and drops the others,
+  /// without changing its capacity. Does nothing if the stack holds `len`
+  /// elements or fewer.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::Stack;
+  ///
+  /// let mut stack: Stack<i32> = (1..=5).collect();
+  /// stack.truncate(2);
+  ///
+  /// assert_eq!(stack.peek(), Some(&2));
+  /// assert_eq!(stack.len(), 2);
+  /// ```
+  pub fn truncate(&mut self, len: usize) {
+    self.items.truncate(len);
+  }
+
+  /// Returns a slice of all elements, from the bottom of the stack
+  /// to the top.
+  pub fn as_slice(&self) -> &[T] {
+    &self.items
+  }
+
   /// Returns an iterator over the elements of the stack.
-  pub fn iter(&self) -> StackIterator<T> {
+  pub fn iter(&self) -> StackIterator<'_, T> {
     StackIterator {
         items: &self.items,
         index: self.items.len(),
@@ -80,11 +358,13 @@ impl<T> Stack<T> {
 }
 
 /// An iterator over the elements of a `Stack`.
+#[cfg(feature = "alloc")]
 pub struct StackIterator<'a, T> {
   items: &'a Vec<T>,
   index: usize,
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T> Iterator for StackIterator<'a, T> {
   type Item = &'a T;
 
@@ -104,6 +384,122 @@ impl<'a, T> Iterator for StackIterator<'a, T> {
   }
 }
 
+/// An owning iterator over the elements of a `Stack`.
+#[cfg(feature = "alloc")]
+pub struct StackIntoIterator<T> {
+  items: Vec<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Iterator for StackIntoIterator<T> {
+  type Item = T;
+
+  /// Removes the top element of the remaining elements and returns it,
+  /// or `None` if the iterator is exhausted.
+  fn next(&mut self) -> Option<Self::Item> {
+    self.items.pop()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.items.len(), Some(self.items.len()))
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> IntoIterator for Stack<T> {
+  type Item = T;
+  type IntoIter = StackIntoIterator<T>;
+
+  /// Consumes the stack and returns an iterator over its elements,
+  /// from the top of the stack to the bottom.
+  fn into_iter(self) -> Self::IntoIter {
+    StackIntoIterator { items: self.items }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoIterator for &'a Stack<T> {
+  type Item = &'a T;
+  type IntoIter = StackIterator<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> FromIterator<T> for Stack<T> {
+  /// Creates a stack by pushing every value of the iterator in order,
+  /// so the last value ends up on top.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::Stack;
+  ///
+  /// let stack: Stack<i32> = (0..10).collect();
+  /// assert_eq!(stack.peek(), Some(&9));
+  /// ```
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    Self { items: iter.into_iter().collect(), limit: None }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Extend<T> for Stack<T> {
+  /// Pushes every value of the iterator onto the stack in order.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the stack is bounded and becomes full.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> Clone for Stack<T> {
+  /// Returns a copy of the stack with the same elements and
+  /// capacity limit.
+  fn clone(&self) -> Self {
+    Self { items: self.items.clone(), limit: self.limit }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialEq> PartialEq for Stack<T> {
+  /// Two stacks are equal if they hold equal elements in the same
+  /// order, regardless of their capacity limits.
+  fn eq(&self, other: &Self) -> bool {
+    self.items == other.items
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Eq for Stack<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> Hash for Stack<T> {
+  /// Hashes the elements from the bottom of the stack to the top.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.items.hash(state);
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug> fmt::Debug for Stack<T> {
+  /// Formats the elements from the bottom of the stack to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.items.iter()).finish()
+  }
+}
+
+/// Deprecated: exposes the whole `Vec` API and breaks the LIFO
+/// abstraction. Only available with the `deref` feature to ease
+/// migration; use `as_slice`, `len`, `capacity` and `iter` instead.
+#[cfg(feature = "deref")]
 impl<T> Deref for Stack<T> {
   type Target = Vec<T>;
 
@@ -117,10 +513,68 @@ impl<T> Deref for Stack<T> {
   }
 }
 
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Stack<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the bottom of the stack to the top.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.items.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Stack<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes an unbounded stack from a sequence, the last element ends up on top.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|items| Self { items, limit: None })
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> IntoParallelIterator for Stack<T> {
+  type Item = T;
+  type Iter = rayon::iter::Rev<rayon::vec::IntoIter<T>>;
 
-#[cfg(test)]
+  /// Consumes the stack and returns a parallel iterator over its elements,
+  /// from the top of the stack to the bottom.
+  fn into_par_iter(self) -> Self::Iter {
+    self.items.into_par_iter().rev()
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> IntoParallelIterator for &'a Stack<T> {
+  type Item = &'a T;
+  type Iter = rayon::iter::Rev<rayon::slice::Iter<'a, T>>;
+
+  /// Returns a parallel iterator over the elements of the stack, from the
+  /// top of the stack to the bottom.
+  fn into_par_iter(self) -> Self::Iter {
+    self.items.par_iter().rev()
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> FromParallelIterator<T> for Stack<T> {
+  /// Creates an unbounded stack from the values of the iterator in order,
+  /// so the last value ends up on top.
+  fn from_par_iter<I: IntoParallelIterator<Item = T>>(iter: I) -> Self {
+    Self { items: Vec::from_par_iter(iter), limit: None }
+  }
+}
+
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-  use super::Stack;
+  use super::{Stack, StackFullError};
+  use alloc::vec::Vec;
+  use alloc::{format, vec};
+  use proptest::prelude::*;
 
   #[test]
   fn empty_stack() {
@@ -157,6 +611,31 @@ mod tests {
     assert_eq!(stack.len(), 2);
   }
 
+  #[test]
+  fn retain() {
+    let mut stack: Stack<i32> = (1..=5).collect();
+    stack.retain(|&value| value != 5);
+
+    assert_eq!(stack.len(), 4);
+    assert_eq!(stack.peek(), Some(&4));
+
+    stack.retain(|_| false);
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  fn peek_mut() {
+    let mut stack = Stack::<i32>::new();
+    assert_eq!(stack.peek_mut(), None);
+
+    stack.push(42);
+    stack.push(314);
+    *stack.peek_mut().unwrap() += 1;
+
+    assert_eq!(stack.pop(), Some(315));
+    assert_eq!(stack.peek(), Some(&42));
+  }
+
   #[test]
   fn is_empty() {
     let mut stack = Stack::<i32>::new();
@@ -181,4 +660,321 @@ mod tests {
       }
     }
   }
+
+  #[test]
+  fn try_push() {
+    let mut stack = Stack::<i32>::with_capacity_limit(2);
+
+    assert_eq!(stack.try_push(42), Ok(()));
+    assert!(!stack.is_full());
+    assert_eq!(stack.try_push(314), Ok(()));
+    assert!(stack.is_full());
+    assert_eq!(stack.try_push(7), Err(StackFullError(7)));
+    assert_eq!(stack.len(), 2);
+
+    stack.pop();
+    assert_eq!(stack.try_push(7), Ok(()));
+    assert_eq!(stack.peek(), Some(&7));
+  }
+
+  #[test]
+  fn try_push_unbounded() {
+    let mut stack = Stack::<i32>::new();
+
+    for value in 0..100 {
+      assert_eq!(stack.try_push(value), Ok(()));
+    }
+
+    assert!(!stack.is_full());
+    assert_eq!(stack.capacity_limit(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "stack is full")]
+  fn push_full() {
+    let mut stack = Stack::<i32>::with_capacity_limit(1);
+    stack.push(42);
+    stack.push(314);
+  }
+
+  #[test]
+  fn push_slice() {
+    let mut stack = Stack::<i32>::new();
+    stack.push(1);
+    stack.push_slice(&[2, 3, 4]);
+
+    assert_eq!(stack.len(), 4);
+    assert_eq!(stack.pop(), Some(4));
+  }
+
+  #[test]
+  #[should_panic(expected = "stack is full")]
+  fn push_slice_full() {
+    let mut stack = Stack::<i32>::with_capacity_limit(2);
+    stack.push_slice(&[1, 2, 3]);
+  }
+
+  #[test]
+  fn extend() {
+    let mut stack = Stack::<i32>::new();
+    stack.extend(0..5);
+
+    assert_eq!(stack.len(), 5);
+    assert_eq!(stack.peek(), Some(&4));
+  }
+
+  #[test]
+  fn pop_n() {
+    let mut stack = Stack::<i32>::new();
+    stack.push_slice(&[1, 2, 3, 4]);
+
+    assert_eq!(stack.pop_n(3), vec![4, 3, 2]);
+    assert_eq!(stack.pop_n(3), vec![1]);
+    assert_eq!(stack.pop_n(3), Vec::<i32>::new());
+  }
+
+  #[test]
+  fn drain_top() {
+    let mut stack = Stack::<i32>::new();
+    stack.push_slice(&[1, 2, 3, 4]);
+
+    let mut drained = stack.drain_top(2);
+    assert_eq!(drained.next(), Some(4));
+    drop(drained);
+
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.peek(), Some(&2));
+  }
+
+  #[test]
+  fn drain() {
+    let mut stack = Stack::<i32>::new();
+    stack.push_slice(&[1, 2, 3]);
+
+    assert_eq!(stack.drain().collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert!(stack.is_empty());
+
+    stack.push_slice(&[1, 2, 3]);
+    let mut drained = stack.drain();
+    assert_eq!(drained.next(), Some(3));
+    drop(drained);
+
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  fn clear() {
+    let mut stack = Stack::with_capacity_limit(2);
+    stack.push(1);
+    stack.push(2);
+    stack.clear();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.peek(), None);
+    assert_eq!(stack.try_push(3), Ok(()));
+  }
+
+  #[test]
+  fn as_slice() {
+    let mut stack = Stack::<i32>::new();
+    stack.push(42);
+    stack.push(314);
+
+    assert_eq!(stack.as_slice(), &[42, 314]);
+  }
+
+  #[test]
+  fn capacity() {
+    let stack = Stack::<i32>::with_capacity_limit(16);
+
+    assert!(stack.capacity() >= 16);
+    assert_eq!(stack.len(), 0);
+  }
+
+  #[test]
+  fn with_capacity() {
+    let mut stack = Stack::<i32>::with_capacity(4);
+    let capacity = stack.capacity();
+
+    assert!(capacity >= 4);
+    assert_eq!(stack.capacity_limit(), None);
+
+    stack.extend(0..4);
+    assert_eq!(stack.capacity(), capacity);
+
+    // The capacity is not a limit.
+    stack.push(4);
+    assert_eq!(stack.len(), 5);
+  }
+
+  #[test]
+  fn reserve() {
+    let mut stack: Stack<i32> = (0..3).collect();
+    stack.reserve(10);
+    assert!(stack.capacity() >= 13);
+
+    let mut bounded = Stack::<i32>::with_capacity_limit(4);
+    bounded.shrink_to_fit();
+    bounded.push(1);
+    bounded.reserve(100);
+    assert!(bounded.capacity() >= 4);
+    assert!(bounded.capacity() < 100);
+  }
+
+  #[test]
+  fn shrink_to_fit() {
+    let mut stack = Stack::<i32>::with_capacity(100);
+    stack.extend(0..10);
+    stack.shrink_to_fit();
+
+    assert!(stack.capacity() >= 10);
+    assert!(stack.capacity() < 100);
+    assert_eq!(stack.peek(), Some(&9));
+  }
+
+  #[test]
+  fn truncate() {
+    let mut stack: Stack<i32> = (0..10).collect();
+    let capacity = stack.capacity();
+
+    stack.truncate(20);
+    assert_eq!(stack.len(), 10);
+
+    stack.truncate(3);
+    assert_eq!(stack.as_slice(), [0, 1, 2]);
+    assert_eq!(stack.capacity(), capacity);
+
+    stack.truncate(0);
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  fn clone_and_eq() {
+    let mut stack = Stack::<i32>::with_capacity_limit(4);
+    stack.push_slice(&[1, 2, 3]);
+
+    let clone = stack.clone();
+    assert_eq!(clone, stack);
+    assert_eq!(clone.capacity_limit(), Some(4));
+
+    let mut other = Stack::<i32>::new();
+    other.push_slice(&[1, 2, 3]);
+    assert_eq!(other, stack);
+
+    other.pop();
+    assert_ne!(other, stack);
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn hash() {
+    use std::collections::HashSet;
+
+    let mut stack = Stack::<i32>::new();
+    stack.push_slice(&[1, 2]);
+
+    let mut set = HashSet::new();
+    set.insert(stack.clone());
+    assert!(set.contains(&stack));
+
+    stack.push(3);
+    assert!(!set.contains(&stack));
+  }
+
+  #[test]
+  fn debug() {
+    let mut stack = Stack::<i32>::new();
+    stack.push_slice(&[1, 2, 3]);
+
+    assert_eq!(format!("{stack:?}"), "[1, 2, 3]");
+  }
+
+  #[test]
+  fn from_iter() {
+    let stack: Stack<i32> = (0..10).collect();
+
+    assert_eq!(stack.len(), 10);
+    assert_eq!(stack.peek(), Some(&9));
+    assert_eq!(stack.capacity_limit(), None);
+  }
+
+  #[test]
+  fn into_iter() {
+    let stack: Stack<i32> = (1..=3).collect();
+
+    let borrowed: Vec<_> = (&stack).into_iter().copied().collect();
+    assert_eq!(borrowed, vec![3, 2, 1]);
+
+    let mut owned = Vec::new();
+    for item in stack {
+      owned.push(item);
+    }
+
+    assert_eq!(owned, vec![3, 2, 1]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: Stack<i32> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let stack: Stack<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(stack, value);
+  }
+
+  #[test]
+  #[cfg(feature = "rayon")]
+  fn parallel_iterators() {
+    use rayon::prelude::*;
+
+    let stack: Stack<i32> = (1..=100).into_par_iter().collect();
+    assert_eq!(stack.peek(), Some(&100));
+    assert_eq!(stack.par_iter().map(|value| value * 2).sum::<i32>(), 10100);
+
+    let expected: Vec<i32> = stack.iter().copied().collect();
+    assert_eq!(stack.par_iter().copied().collect::<Vec<_>>(), expected);
+    assert_eq!(stack.into_par_iter().collect::<Vec<_>>(), expected);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop,
+    Peek
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Push),
+      Just(Operation::Pop),
+      Just(Operation::Peek)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    fn behaves_like_vec(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut stack = Stack::new();
+      let mut model = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            stack.push(value);
+            model.push(value);
+          }
+          Operation::Pop => prop_assert_eq!(stack.pop(), model.pop()),
+          Operation::Peek => prop_assert_eq!(stack.peek(), model.last())
+        }
+
+        prop_assert_eq!(stack.len(), model.len());
+        prop_assert_eq!(stack.is_empty(), model.is_empty());
+      }
+
+      prop_assert!(stack.iter().eq(model.iter().rev()));
+    }
+  }
 }
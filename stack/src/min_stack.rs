@@ -0,0 +1,258 @@
+use crate::{Stack, StackIterator};
+
+
+/// A stack that tracks its minimum and maximum values.
+///
+/// Next to the values it keeps two auxiliary stacks with the positions
+/// of the successive minimums and maximums, so `min` and `max` are
+/// answered in O(1) time without searching the stack.
+///
+/// # Example
+///
+/// ```
+/// use stack::MinStack;
+///
+/// let mut stack = MinStack::new();
+/// stack.push(5);
+/// stack.push(2);
+/// stack.push(8);
+///
+/// assert_eq!(stack.min(), Some(&2));
+/// assert_eq!(stack.max(), Some(&8));
+///
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!(stack.min(), Some(&5));
+/// assert_eq!(stack.max(), Some(&5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MinStack<T> {
+  items: Stack<T>,
+  /// Positions of the successive minimums, the current one on top.
+  mins: Stack<usize>,
+  /// Positions of the successive maximums, the current one on top.
+  maxes: Stack<usize>
+}
+
+impl<T: Ord> Default for MinStack<T> {
+  /// Creates a new instance of `MinStack` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord> MinStack<T> {
+  /// Creates a new empty instance of `MinStack`.
+  pub fn new() -> Self {
+    Self { items: Stack::new(), mins: Stack::new(), maxes: Stack::new() }
+  }
+
+  /// Pushes a value onto the top of the stack.
+  pub fn push(&mut self, value: T) {
+    let index = self.items.len();
+
+    if self.min().is_none_or(|min| value < *min) {
+      self.mins.push(index);
+    }
+    if self.max().is_none_or(|max| value > *max) {
+      self.maxes.push(index);
+    }
+
+    self.items.push(value);
+  }
+
+  /// Removes the top value of the stack and returns it,
+  /// or `None` if the stack is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    let value = self.items.pop()?;
+    let index = self.items.len();
+
+    if self.mins.peek() == Some(&index) {
+      self.mins.pop();
+    }
+    if self.maxes.peek() == Some(&index) {
+      self.maxes.pop();
+    }
+
+    Some(value)
+  }
+
+  /// Returns a reference to the smallest value of the stack,
+  /// or `None` if the stack is empty.
+  ///
+  /// If several values are equal, the one closest to the bottom is returned.
+  pub fn min(&self) -> Option<&T> {
+    self.mins.peek().map(|&index| &self.items.as_slice()[index])
+  }
+
+  /// Returns a reference to the largest value of the stack,
+  /// or `None` if the stack is empty.
+  ///
+  /// If several values are equal, the one closest to the bottom is returned.
+  pub fn max(&self) -> Option<&T> {
+    self.maxes.peek().map(|&index| &self.items.as_slice()[index])
+  }
+}
+
+impl<T> MinStack<T> {
+  /// Returns a reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.items.peek()
+  }
+
+  /// Checks if the stack is empty.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    self.items.clear();
+    self.mins.clear();
+    self.maxes.clear();
+  }
+
+  /// Returns an iterator over the values of the stack from top to bottom.
+  pub fn iter(&self) -> StackIterator<'_, T> {
+    self.items.iter()
+  }
+}
+
+impl<T: Ord> FromIterator<T> for MinStack<T> {
+  /// Creates a stack by pushing the values in order,
+  /// so the last value ends up on top.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut stack = Self::new();
+    stack.extend(iter);
+    stack
+  }
+}
+
+impl<T: Ord> Extend<T> for MinStack<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for MinStack<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the bottom of the stack to the top.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&self.items, serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for MinStack<T>
+where
+  T: serde::Deserialize<'de> + Ord,
+{
+  /// Deserializes a stack from a sequence, the last element ends up on top.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    alloc::vec::Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::MinStack;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_stack() {
+    let stack = MinStack::<i32>::new();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.min(), None);
+    assert_eq!(stack.max(), None);
+  }
+
+  #[test]
+  fn push_and_pop() {
+    let mut stack = MinStack::new();
+    stack.push(3);
+    stack.push(1);
+    stack.push(4);
+
+    assert_eq!(stack.len(), 3);
+    assert_eq!(stack.peek(), Some(&4));
+    assert_eq!(stack.pop(), Some(4));
+    assert_eq!(stack.pop(), Some(1));
+    assert_eq!(stack.pop(), Some(3));
+    assert_eq!(stack.pop(), None);
+  }
+
+  #[test]
+  fn min_and_max() {
+    let mut stack: MinStack<i32> = [3, 1, 4, 1, 5].into_iter().collect();
+
+    assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&5)));
+    stack.pop();
+    assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&4)));
+    stack.pop();
+    assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&4)));
+    stack.pop();
+    assert_eq!((stack.min(), stack.max()), (Some(&1), Some(&3)));
+    stack.pop();
+    assert_eq!((stack.min(), stack.max()), (Some(&3), Some(&3)));
+  }
+
+  #[test]
+  fn duplicate_minimum() {
+    let mut stack: MinStack<i32> = [2, 2].into_iter().collect();
+    stack.pop();
+
+    assert_eq!(stack.min(), Some(&2));
+  }
+
+  #[test]
+  fn clear() {
+    let mut stack: MinStack<i32> = (1..=3).collect();
+    stack.clear();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.min(), None);
+
+    stack.push(7);
+    assert_eq!((stack.min(), stack.max()), (Some(&7), Some(&7)));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: MinStack<i32> = [2, 1, 3].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[2,1,3]");
+
+    let stack: MinStack<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(stack.min(), Some(&1));
+    assert_eq!(stack.max(), Some(&3));
+  }
+
+  proptest! {
+    #[test]
+    fn tracks_min_and_max(values in prop::collection::vec(any::<i8>(), 0..50), pops in 0..50usize) {
+      let mut stack: MinStack<i8> = values.iter().copied().collect();
+      let mut model = values;
+
+      for _ in 0..pops {
+        prop_assert_eq!(stack.pop(), model.pop());
+        prop_assert_eq!(stack.min(), model.iter().min());
+        prop_assert_eq!(stack.max(), model.iter().max());
+      }
+    }
+  }
+}
@@ -0,0 +1,238 @@
+use alloc::vec::{Splice, Vec};
+use core::iter::{self, Once, Rev};
+
+use crate::StackIterator;
+
+
+/// A stack whose values never increase from the bottom to the top.
+///
+/// Pushing a value first pops every value below it off the top, so the
+/// stack stays sorted and its largest value is at the bottom. The values
+/// popped by a push are returned, and the pushed value is the first larger
+/// one that came after each of them, which answers the next greater element
+/// problem in one pass. Every value is pushed and popped at most once, so a
+/// push takes amortized O(1) time.
+///
+/// The values are compared with `Ord`. Wrapping them in
+/// `core::cmp::Reverse` keeps the stack non-decreasing instead, for the
+/// next smaller element.
+///
+/// # Example
+///
+/// ```
+/// use core::cmp::Reverse;
+/// use stack::MonotonicStack;
+///
+/// // The next greater element of every value, if any.
+/// let values = [2, 1, 5, 3, 3, 4];
+/// let mut next = vec![None; values.len()];
+/// let mut stack = MonotonicStack::new();
+///
+/// for (index, &value) in values.iter().enumerate() {
+///   // The reversed index keeps an equal value from popping another.
+///   for (_, Reverse(popped)) in stack.push((value, Reverse(index))) {
+///     next[popped] = Some(value);
+///   }
+/// }
+///
+/// assert_eq!(next, vec![Some(5), Some(5), None, Some(4), Some(4), None]);
+/// assert_eq!(stack.max(), Some(&(5, Reverse(2))));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MonotonicStack<T> {
+  /// Values from the bottom to the top, in non-increasing order.
+  items: Vec<T>
+}
+
+impl<T: Ord> Default for MonotonicStack<T> {
+  /// Creates a new instance of `MonotonicStack` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Ord> MonotonicStack<T> {
+  /// Creates a new empty instance of `MonotonicStack`.
+  pub fn new() -> Self {
+    Self { items: Vec::new() }
+  }
+
+  /// Pops the values smaller than the value off the top of the stack, then
+  /// pushes the value onto the top.
+  ///
+  /// Returns an iterator over the popped values from the top down. The
+  /// values are popped and the value pushed even if the iterator is not
+  /// consumed.
+  pub fn push(&mut self, value: T) -> Rev<Splice<'_, Once<T>>> {
+    let mut start = self.items.len();
+    while start > 0 && self.items[start - 1] < value {
+      start -= 1;
+    }
+
+    self.items.splice(start.., iter::once(value)).rev()
+  }
+}
+
+impl<T> MonotonicStack<T> {
+  /// Removes the top value of the stack, the smallest one, and returns
+  /// it, or `None` if the stack is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    self.items.pop()
+  }
+
+  /// Returns a reference to the top value of the stack, the smallest one,
+  /// or `None` if the stack is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.items.last()
+  }
+
+  /// Returns a reference to the bottom value of the stack, the largest
+  /// one, or `None` if the stack is empty.
+  pub fn max(&self) -> Option<&T> {
+    self.items.first()
+  }
+
+  /// Checks if the stack is empty.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    self.items.clear();
+  }
+
+  /// Returns a slice of all elements, from the bottom of the stack
+  /// to the top.
+  pub fn as_slice(&self) -> &[T] {
+    &self.items
+  }
+
+  /// Returns an iterator over the values of the stack from top to bottom.
+  pub fn iter(&self) -> StackIterator<'_, T> {
+    StackIterator { items: &self.items, index: self.items.len() }
+  }
+}
+
+impl<T: Ord> Extend<T> for MonotonicStack<T> {
+  /// Pushes the values in order, dropping the values they pop.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      let _ = self.push(value);
+    }
+  }
+}
+
+impl<T: Ord> FromIterator<T> for MonotonicStack<T> {
+  /// Creates a stack by pushing the values in order, so it holds the
+  /// values that are not smaller than any value after them.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut stack = Self::new();
+    stack.extend(iter);
+    stack
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for MonotonicStack<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the bottom of the stack to the top.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&self.items, serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for MonotonicStack<T>
+where
+  T: serde::Deserialize<'de> + Ord,
+{
+  /// Deserializes a stack by pushing the values of a sequence in order.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::MonotonicStack;
+  use alloc::vec;
+  use alloc::vec::Vec;
+  use core::cmp::Reverse;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_stack() {
+    let mut stack = MonotonicStack::<i32>::new();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.max(), None);
+    assert_eq!(stack.pop(), None);
+  }
+
+  #[test]
+  fn push_pops_smaller_values() {
+    let mut stack: MonotonicStack<i32> = [5, 3, 3, 1].into_iter().collect();
+
+    assert_eq!(stack.push(3).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(stack.as_slice(), [5, 3, 3, 3]);
+    assert_eq!(stack.push(4).collect::<Vec<_>>(), vec![3, 3, 3]);
+    assert_eq!(stack.iter().collect::<Vec<_>>(), vec![&4, &5]);
+    assert_eq!((stack.peek(), stack.max()), (Some(&4), Some(&5)));
+  }
+
+  #[test]
+  fn unconsumed_push() {
+    let mut stack: MonotonicStack<i32> = [3, 2, 1].into_iter().collect();
+    drop(stack.push(2));
+
+    assert_eq!(stack.as_slice(), [3, 2, 2]);
+    assert_eq!(stack.len(), 3);
+  }
+
+  #[test]
+  fn next_smaller_with_reverse() {
+    let stack: MonotonicStack<Reverse<i32>> = [4, 1, 3, 2, 5].into_iter().map(Reverse).collect();
+
+    assert_eq!(stack.as_slice(), [Reverse(1), Reverse(2), Reverse(5)]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: MonotonicStack<i32> = [3, 1, 2].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[3,2]");
+
+    let stack: MonotonicStack<i32> = serde_json::from_str("[1,3,2]").unwrap();
+    assert_eq!(stack.as_slice(), value.as_slice());
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn finds_next_greater_elements(values in prop::collection::vec(any::<i8>(), 0..50)) {
+      let mut next = vec![None; values.len()];
+      let mut stack = MonotonicStack::new();
+
+      for (index, &value) in values.iter().enumerate() {
+        for (_, Reverse(popped)) in stack.push((value, Reverse(index))) {
+          next[popped] = Some(index);
+        }
+        prop_assert!(stack.as_slice().windows(2).all(|pair| pair[0] >= pair[1]));
+      }
+
+      let expected: Vec<_> = (0..values.len()).map(|i| (i + 1..values.len()).find(|&j| values[j] > values[i])).collect();
+      prop_assert_eq!(next, expected);
+    }
+  }
+}
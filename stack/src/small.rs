@@ -0,0 +1,348 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::Rev;
+use core::slice;
+
+use crate::storage::{ArrayStorage, Storage};
+
+
+/// A stack that keeps up to `N` elements inline and moves them to the heap
+/// once it grows beyond that.
+///
+/// Stacks that stay shallow, like the ones of most recursive algorithms on
+/// small inputs, never allocate, while deep stacks still grow without
+/// limit. Once spilled, the elements stay on the heap until the stack is
+/// dropped.
+///
+/// # Example
+///
+/// ```
+/// use stack::SmallStack;
+///
+/// let mut stack = SmallStack::<i32, 2>::new();
+/// stack.push(7);
+/// stack.push(32);
+/// assert!(!stack.spilled());
+///
+/// stack.push(1);
+/// assert!(stack.spilled());
+/// assert_eq!(stack.pop(), Some(1));
+/// assert_eq!(stack.as_slice(), [7, 32]);
+/// ```
+pub struct SmallStack<T, const N: usize> {
+  items: Items<T, N>
+}
+
+/// Where the elements of a `SmallStack` are kept.
+enum Items<T, const N: usize> {
+  Inline(ArrayStorage<T, N>),
+  Heap(Vec<T>)
+}
+
+impl<T, const N: usize> Default for SmallStack<T, N> {
+  /// Creates a new instance of `SmallStack` with default values.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use stack::SmallStack;
+  ///
+  /// let stack = SmallStack::<i32, 8>::default();
+  /// ```
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, const N: usize> SmallStack<T, N> {
+  /// Creates a new empty instance of `SmallStack`.
+  pub const fn new() -> Self {
+    Self { items: Items::Inline(ArrayStorage::new()) }
+  }
+
+  /// Checks if the elements have been moved to the heap.
+  pub fn spilled(&self) -> bool {
+    matches!(self.items, Items::Heap(_))
+  }
+
+  /// Pushes a value onto the top of the stack, moving all elements to the
+  /// heap if the inline storage is full.
+  pub fn push(&mut self, value: T) {
+    match &mut self.items {
+      Items::Inline(inline) => {
+        if let Err(value) = inline.push(value) {
+          let mut heap = Vec::with_capacity(2 * N + 1);
+          heap.extend(core::iter::from_fn(|| inline.pop()));
+          heap.reverse();
+          heap.push(value);
+          self.items = Items::Heap(heap);
+        }
+      }
+      Items::Heap(heap) => heap.push(value)
+    }
+  }
+
+  /// Removes the top value from the stack and returns it,
+  /// or `None` if the stack is empty.
+  pub fn pop(&mut self) -> Option<T> {
+    match &mut self.items {
+      Items::Inline(inline) => inline.pop(),
+      Items::Heap(heap) => heap.pop()
+    }
+  }
+
+  /// Removes all values from the stack.
+  pub fn clear(&mut self) {
+    while self.pop().is_some() {}
+  }
+
+  /// Returns a reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.as_slice().last()
+  }
+
+  /// Returns a mutable reference to the top value of the stack,
+  /// or `None` if the stack is empty.
+  pub fn peek_mut(&mut self) -> Option<&mut T> {
+    match &mut self.items {
+      Items::Inline(inline) => inline.top_mut(),
+      Items::Heap(heap) => heap.last_mut()
+    }
+  }
+
+  /// Checks if the stack is empty.
+  pub fn is_empty(&self) -> bool {
+    self.as_slice().is_empty()
+  }
+
+  /// Returns the number of elements in the stack.
+  pub fn len(&self) -> usize {
+    self.as_slice().len()
+  }
+
+  /// Returns the number of elements the stack can hold without
+  /// allocating, or reallocating once spilled.
+  pub fn capacity(&self) -> usize {
+    match &self.items {
+      Items::Inline(_) => N,
+      Items::Heap(heap) => heap.capacity()
+    }
+  }
+
+  /// Returns a slice of all elements, from the bottom of the stack
+  /// to the top.
+  pub fn as_slice(&self) -> &[T] {
+    match &self.items {
+      Items::Inline(inline) => inline.as_slice(),
+      Items::Heap(heap) => heap
+    }
+  }
+
+  /// Returns an iterator over the elements of the stack, from the top
+  /// to the bottom.
+  pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+    self.as_slice().iter().rev()
+  }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallStack<T, N> {
+  type Item = &'a T;
+  type IntoIter = Rev<slice::Iter<'a, T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallStack<T, N> {
+  /// Creates a stack by pushing every value of the iterator in order,
+  /// so the last value ends up on top.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut stack = Self::new();
+    stack.extend(iter);
+    stack
+  }
+}
+
+impl<T, const N: usize> Extend<T> for SmallStack<T, N> {
+  /// Pushes every value of the iterator onto the stack in order.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallStack<T, N> {
+  /// Returns a copy of the stack, kept inline if the elements fit.
+  fn clone(&self) -> Self {
+    self.as_slice().iter().cloned().collect()
+  }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SmallStack<T, N> {
+  /// Two stacks are equal if they hold equal elements in the same order,
+  /// whether they have spilled or not.
+  fn eq(&self, other: &Self) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallStack<T, N> {}
+
+impl<T: Hash, const N: usize> Hash for SmallStack<T, N> {
+  /// Hashes the elements from the bottom of the stack to the top.
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallStack<T, N> {
+  /// Formats the elements from the bottom of the stack to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.as_slice()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for SmallStack<T, N>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the bottom of the stack to the top.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.as_slice())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for SmallStack<T, N>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a stack from a sequence, the last element ends up on top.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use alloc::format;
+  use alloc::rc::Rc;
+  use alloc::vec::Vec;
+
+  use super::SmallStack;
+  use proptest::prelude::*;
+
+  #[test]
+  fn push_and_pop() {
+    let mut stack = SmallStack::<i32, 4>::new();
+    stack.push(42);
+    stack.push(314);
+
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.peek(), Some(&314));
+    assert_eq!(stack.pop(), Some(314));
+    assert_eq!(stack.pop(), Some(42));
+    assert_eq!(stack.pop(), None);
+    assert!(!stack.spilled());
+  }
+
+  #[test]
+  fn spill() {
+    let mut stack: SmallStack<i32, 3> = (1..=3).collect();
+    assert!(!stack.spilled());
+    assert_eq!(stack.capacity(), 3);
+
+    stack.push(4);
+    assert!(stack.spilled());
+    assert!(stack.capacity() >= 4);
+    *stack.peek_mut().unwrap() *= 10;
+    assert_eq!(stack.as_slice(), [1, 2, 3, 40]);
+
+    stack.clear();
+    assert!(stack.spilled());
+    assert!(stack.is_empty());
+  }
+
+  #[test]
+  fn zero_inline_capacity() {
+    let mut stack = SmallStack::<i32, 0>::new();
+    stack.push(1);
+
+    assert!(stack.spilled());
+    assert_eq!(stack.peek(), Some(&1));
+  }
+
+  #[test]
+  fn clone_moves_back_inline() {
+    let mut stack: SmallStack<i32, 2> = (1..=3).collect();
+    stack.pop();
+
+    let clone = stack.clone();
+    assert!(!clone.spilled());
+    assert_eq!(clone, stack);
+    assert_eq!(format!("{clone:?}"), "[1, 2]");
+    assert_eq!(clone.iter().copied().collect::<Vec<_>>(), [2, 1]);
+  }
+
+  #[test]
+  fn drops_values() {
+    let value = Rc::new(());
+    let mut stack = SmallStack::<_, 2>::new();
+    (0..3).for_each(|_| stack.push(Rc::clone(&value)));
+    let inline: SmallStack<_, 2> = [Rc::clone(&value)].into_iter().collect();
+
+    assert_eq!(Rc::strong_count(&value), 5);
+    drop(stack);
+    drop(inline);
+    assert_eq!(Rc::strong_count(&value), 1);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: SmallStack<i32, 2> = (1..=3).collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3]");
+
+    let stack: SmallStack<i32, 2> = serde_json::from_str(&json).unwrap();
+    assert_eq!(stack, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Push(i32),
+    Pop
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![any::<i32>().prop_map(Operation::Push), Just(Operation::Pop)]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_vec(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut stack = SmallStack::<i32, 4>::new();
+      let mut model = Vec::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Push(value) => {
+            stack.push(value);
+            model.push(value);
+          }
+          Operation::Pop => prop_assert_eq!(stack.pop(), model.pop())
+        }
+
+        prop_assert_eq!(stack.as_slice(), model.as_slice());
+      }
+    }
+  }
+}
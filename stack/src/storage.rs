@@ -0,0 +1,408 @@
+//! Backing storages for `GenericStack`.
+//!
+//! A storage holds the values of a stack and decides how they are
+//! allocated: `Vec` grows on the heap, `ArrayStorage` keeps a fixed number
+//! of values inline, the crate's `LinkedList` allocates every value on its
+//! own so pushing never moves the other values, and `Hybrid` keeps the
+//! bottom values inline and spills the rest into a linked list.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+use core::{fmt, ptr, slice};
+
+#[cfg(feature = "alloc")]
+use linked_list::LinkedList;
+
+
+/// The storage of the values of a `GenericStack`, accessed at the top only.
+///
+/// `ArrayStorage` is available without the `alloc` feature, as it backs
+/// `ArrayStack`.
+pub trait Storage<T> {
+  /// Pushes a value on top of the storage, or returns it back if the
+  /// storage is full.
+  fn push(&mut self, value: T) -> Result<(), T>;
+
+  /// Removes the top value and returns it, or `None` if the storage is
+  /// empty.
+  fn pop(&mut self) -> Option<T>;
+
+  /// Returns a reference to the top value.
+  fn top(&self) -> Option<&T>;
+
+  /// Returns a mutable reference to the top value.
+  fn top_mut(&mut self) -> Option<&mut T>;
+
+  /// Returns the number of values in the storage.
+  fn len(&self) -> usize;
+
+  /// Checks if the storage is empty.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns an iterator over the values, from the top to the bottom.
+  fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a
+  where
+    T: 'a;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Storage<T> for Vec<T> {
+  fn push(&mut self, value: T) -> Result<(), T> {
+    self.push(value);
+    Ok(())
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop()
+  }
+
+  fn top(&self) -> Option<&T> {
+    self.last()
+  }
+
+  fn top_mut(&mut self) -> Option<&mut T> {
+    self.last_mut()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a
+  where
+    T: 'a,
+  {
+    self.as_slice().iter().rev()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Storage<T> for LinkedList<T> {
+  /// Prepends the value, the front of the list is the top of the stack.
+  fn push(&mut self, value: T) -> Result<(), T> {
+    self.prepend(value);
+    Ok(())
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.pop_front()
+  }
+
+  fn top(&self) -> Option<&T> {
+    self.front()
+  }
+
+  fn top_mut(&mut self) -> Option<&mut T> {
+    self.front_mut()
+  }
+
+  fn len(&self) -> usize {
+    self.len()
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a
+  where
+    T: 'a,
+  {
+    self.iter()
+  }
+}
+
+/// A storage for at most `N` values, kept inline without allocating.
+///
+/// # Example
+///
+/// ```
+/// use stack::storage::{ArrayStorage, Storage};
+///
+/// let mut storage = ArrayStorage::<i32, 1>::new();
+/// assert_eq!(storage.push(7), Ok(()));
+/// assert_eq!(storage.push(32), Err(32));
+/// assert_eq!(storage.as_slice(), [7]);
+/// ```
+pub struct ArrayStorage<T, const N: usize> {
+  /// The values, only the first `len` are initialized.
+  items: [MaybeUninit<T>; N],
+  len: usize
+}
+
+impl<T, const N: usize> Default for ArrayStorage<T, N> {
+  /// Creates a new instance of `ArrayStorage` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T, const N: usize> ArrayStorage<T, N> {
+  /// Creates a new empty instance of `ArrayStorage`.
+  pub const fn new() -> Self {
+    Self { items: [const { MaybeUninit::uninit() }; N], len: 0 }
+  }
+
+  /// Returns a slice of all values, from the bottom to the top.
+  pub fn as_slice(&self) -> &[T] {
+    // SAFETY: the first `len` values are initialized.
+    unsafe { slice::from_raw_parts(self.items.as_ptr().cast(), self.len) }
+  }
+
+  /// Returns a mutable slice of all values, from the bottom to the top.
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    // SAFETY: the first `len` values are initialized.
+    unsafe { slice::from_raw_parts_mut(self.items.as_mut_ptr().cast(), self.len) }
+  }
+}
+
+impl<T, const N: usize> Storage<T> for ArrayStorage<T, N> {
+  fn push(&mut self, value: T) -> Result<(), T> {
+    if self.len == N {
+      return Err(value);
+    }
+
+    self.items[self.len].write(value);
+    self.len += 1;
+    Ok(())
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.len -= 1;
+    // SAFETY: the value was initialized and is no longer counted in `len`,
+    // so it is read only once.
+    Some(unsafe { self.items[self.len].assume_init_read() })
+  }
+
+  fn top(&self) -> Option<&T> {
+    self.as_slice().last()
+  }
+
+  fn top_mut(&mut self) -> Option<&mut T> {
+    self.as_mut_slice().last_mut()
+  }
+
+  fn len(&self) -> usize {
+    self.len
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a
+  where
+    T: 'a,
+  {
+    self.as_slice().iter().rev()
+  }
+}
+
+impl<T, const N: usize> Drop for ArrayStorage<T, N> {
+  fn drop(&mut self) {
+    let values = ptr::slice_from_raw_parts_mut(self.items.as_mut_ptr().cast::<T>(), self.len);
+    // Forget the values first, so a panicking destructor cannot lead to a
+    // double drop.
+    self.len = 0;
+    // SAFETY: the values were initialized and are dropped only once.
+    unsafe { values.drop_in_place() }
+  }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayStorage<T, N> {
+  fn clone(&self) -> Self {
+    let mut storage = Self::new();
+
+    for value in self.as_slice() {
+      // Cannot fail, as the clone has the same capacity.
+      let _ = storage.push(value.clone());
+    }
+
+    storage
+  }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayStorage<T, N> {
+  /// Formats the values from the bottom to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.as_slice()).finish()
+  }
+}
+
+/// A storage that keeps the bottom `N` values inline and spills the values
+/// above them into a linked list.
+///
+/// Shallow stacks never allocate, and deep stacks allocate one node per
+/// spilled value instead of reallocating and moving all values.
+///
+/// # Example
+///
+/// ```
+/// use stack::storage::{Hybrid, Storage};
+///
+/// let mut storage = Hybrid::<i32, 2>::new();
+/// for value in 1..=3 {
+///   storage.push(value).unwrap();
+/// }
+///
+/// assert_eq!(storage.spilled(), 1);
+/// assert_eq!(storage.iter().copied().collect::<Vec<_>>(), [3, 2, 1]);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct Hybrid<T, const N: usize> {
+  inline: ArrayStorage<T, N>,
+  /// The values above the inline ones, the top of the stack at the front.
+  spill: LinkedList<T>
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> Default for Hybrid<T, N> {
+  /// Creates a new instance of `Hybrid` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> Hybrid<T, N> {
+  /// Creates a new empty instance of `Hybrid`.
+  pub fn new() -> Self {
+    Self { inline: ArrayStorage::new(), spill: LinkedList::new() }
+  }
+
+  /// Returns the number of values spilled into the linked list.
+  pub fn spilled(&self) -> usize {
+    self.spill.len()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> Storage<T> for Hybrid<T, N> {
+  fn push(&mut self, value: T) -> Result<(), T> {
+    match self.inline.push(value) {
+      Ok(()) => Ok(()),
+      Err(value) => Storage::push(&mut self.spill, value)
+    }
+  }
+
+  fn pop(&mut self) -> Option<T> {
+    self.spill.pop_front().or_else(|| self.inline.pop())
+  }
+
+  fn top(&self) -> Option<&T> {
+    self.spill.front().or_else(|| self.inline.top())
+  }
+
+  fn top_mut(&mut self) -> Option<&mut T> {
+    match self.spill.front_mut() {
+      Some(top) => Some(top),
+      None => self.inline.top_mut()
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.inline.len() + self.spill.len()
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a
+  where
+    T: 'a,
+  {
+    self.spill.iter().chain(Storage::iter(&self.inline))
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone, const N: usize> Clone for Hybrid<T, N> {
+  fn clone(&self) -> Self {
+    Self { inline: self.inline.clone(), spill: self.spill.iter().cloned().collect() }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug, const N: usize> fmt::Debug for Hybrid<T, N> {
+  /// Formats the values from the bottom to the top.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let spilled: Vec<&T> = self.spill.iter().collect();
+    f.debug_list().entries(self.inline.as_slice()).entries(spilled.into_iter().rev()).finish()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use alloc::rc::Rc;
+  #[cfg(feature = "alloc")]
+  use alloc::{format, vec::Vec};
+
+  use super::{ArrayStorage, Storage};
+  #[cfg(feature = "alloc")]
+  use super::Hybrid;
+  #[cfg(feature = "alloc")]
+  use linked_list::LinkedList;
+
+  /// Pushes values until the storage is full or holds `count` values, then
+  /// pops them all, checking the order.
+  fn push_and_pop<S: Storage<usize> + Default>(count: usize) -> usize {
+    let mut storage = S::default();
+    let mut pushed = 0;
+
+    while pushed < count && storage.push(pushed + 1).is_ok() {
+      *storage.top_mut().unwrap() -= 1;
+      pushed += 1;
+      assert_eq!(storage.top(), Some(&(pushed - 1)));
+    }
+
+    assert_eq!(storage.len(), pushed);
+    assert!(storage.iter().copied().eq((0..pushed).rev()));
+    assert!(core::iter::from_fn(|| storage.pop()).eq((0..pushed).rev()));
+    assert!(storage.is_empty());
+    pushed
+  }
+
+  #[test]
+  fn array_storages() {
+    assert_eq!(push_and_pop::<ArrayStorage<_, 4>>(10), 4);
+    assert_eq!(push_and_pop::<ArrayStorage<_, 0>>(10), 0);
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn heap_storages() {
+    assert_eq!(push_and_pop::<Vec<_>>(10), 10);
+    assert_eq!(push_and_pop::<LinkedList<_>>(10), 10);
+    assert_eq!(push_and_pop::<Hybrid<_, 4>>(10), 10);
+    assert_eq!(push_and_pop::<Hybrid<_, 0>>(10), 10);
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn hybrid_spills() {
+    let mut storage = Hybrid::<i32, 2>::new();
+    (1..=4).for_each(|value| storage.push(value).unwrap());
+
+    assert_eq!(storage.spilled(), 2);
+    assert_eq!(format!("{storage:?}"), "[1, 2, 3, 4]");
+    assert_eq!(format!("{:?}", storage.clone()), "[1, 2, 3, 4]");
+
+    storage.pop();
+    storage.pop();
+    assert_eq!(storage.spilled(), 0);
+    assert_eq!(storage.top(), Some(&2));
+  }
+
+  #[test]
+  fn array_storage_drops_values() {
+    let value = Rc::new(());
+    let mut storage = ArrayStorage::<_, 3>::new();
+    storage.push(Rc::clone(&value)).unwrap();
+    storage.push(Rc::clone(&value)).unwrap();
+
+    let clone = storage.clone();
+    assert_eq!(Rc::strong_count(&value), 5);
+
+    drop(storage.pop());
+    drop(storage);
+    drop(clone);
+    assert_eq!(Rc::strong_count(&value), 1);
+  }
+}
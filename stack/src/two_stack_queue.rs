@@ -0,0 +1,248 @@
+use core::fmt;
+use core::iter::Chain;
+use core::slice;
+
+use crate::{Stack, StackIterator};
+
+
+/// A first-in first-out queue made of two stacks.
+///
+/// New values are pushed onto an inbox stack. Values are popped off an
+/// outbox stack, and when it runs empty the inbox is popped onto it, which
+/// reverses the values so the oldest ends up on top. Every value is moved
+/// once, so `enqueue` and `dequeue` take amortized O(1) time, though a
+/// single `dequeue` may move all the values.
+///
+/// # Example
+///
+/// ```
+/// use stack::TwoStackQueue;
+///
+/// let mut queue = TwoStackQueue::new();
+/// queue.enqueue(1);
+/// queue.enqueue(2);
+/// assert_eq!(queue.dequeue(), Some(1));
+///
+/// queue.enqueue(3);
+/// assert_eq!(queue.iter().collect::<Vec<_>>(), vec![&2, &3]);
+/// assert_eq!(queue.dequeue(), Some(2));
+/// assert_eq!(queue.dequeue(), Some(3));
+/// assert_eq!(queue.dequeue(), None);
+/// ```
+#[derive(Clone)]
+pub struct TwoStackQueue<T> {
+  /// The newest values, the last enqueued on top.
+  inbox: Stack<T>,
+  /// The oldest values, the next to dequeue on top.
+  outbox: Stack<T>
+}
+
+impl<T> Default for TwoStackQueue<T> {
+  /// Creates a new instance of `TwoStackQueue` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> TwoStackQueue<T> {
+  /// Creates a new empty instance of `TwoStackQueue`.
+  pub fn new() -> Self {
+    Self { inbox: Stack::new(), outbox: Stack::new() }
+  }
+
+  /// Adds a value to the back of the queue.
+  pub fn enqueue(&mut self, value: T) {
+    self.inbox.push(value);
+  }
+
+  /// Removes the front value from the queue and returns it,
+  /// or `None` if the queue is empty.
+  pub fn dequeue(&mut self) -> Option<T> {
+    if self.outbox.is_empty() {
+      while let Some(value) = self.inbox.pop() {
+        self.outbox.push(value);
+      }
+    }
+
+    self.outbox.pop()
+  }
+
+  /// Returns a reference to the front value of the queue,
+  /// or `None` if the queue is empty.
+  pub fn peek(&self) -> Option<&T> {
+    self.outbox.peek().or_else(|| self.inbox.as_slice().first())
+  }
+
+  /// Returns the number of elements in the queue.
+  pub fn len(&self) -> usize {
+    self.inbox.len() + self.outbox.len()
+  }
+
+  /// Checks if the queue is empty.
+  pub fn is_empty(&self) -> bool {
+    self.inbox.is_empty() && self.outbox.is_empty()
+  }
+
+  /// Removes all values from the queue.
+  pub fn clear(&mut self) {
+    self.inbox.clear();
+    self.outbox.clear();
+  }
+
+  /// Returns an iterator over the values of the queue from front to back.
+  pub fn iter(&self) -> Chain<StackIterator<'_, T>, slice::Iter<'_, T>> {
+    self.outbox.iter().chain(self.inbox.as_slice())
+  }
+}
+
+impl<T> FromIterator<T> for TwoStackQueue<T> {
+  /// Creates a queue by enqueuing the values in order.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    Self { inbox: iter.into_iter().collect(), outbox: Stack::new() }
+  }
+}
+
+impl<T> Extend<T> for TwoStackQueue<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    self.inbox.extend(iter);
+  }
+}
+
+impl<T: PartialEq> PartialEq for TwoStackQueue<T> {
+  /// Compares the values in order, however they are split between the
+  /// stacks.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for TwoStackQueue<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for TwoStackQueue<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for TwoStackQueue<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the elements as a sequence from the front to the back.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for TwoStackQueue<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a queue from a sequence, preserving the order of the elements.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    alloc::vec::Vec::deserialize(deserializer).map(|items| items.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::TwoStackQueue;
+  use alloc::collections::VecDeque;
+  use alloc::format;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_queue() {
+    let mut queue = TwoStackQueue::<i32>::new();
+
+    assert!(queue.is_empty());
+    assert_eq!(queue.peek(), None);
+    assert_eq!(queue.dequeue(), None);
+  }
+
+  #[test]
+  fn peek_across_stacks() {
+    let mut queue: TwoStackQueue<i32> = (1..=3).collect();
+
+    assert_eq!(queue.peek(), Some(&1));
+    assert_eq!(queue.dequeue(), Some(1));
+    queue.extend([4, 5]);
+
+    assert_eq!(queue.peek(), Some(&2));
+    assert_eq!(queue.len(), 4);
+    assert_eq!(format!("{:?}", queue), "[2, 3, 4, 5]");
+  }
+
+  #[test]
+  fn equal_however_split() {
+    let mut queue: TwoStackQueue<i32> = (0..=3).collect();
+    queue.dequeue();
+
+    assert_eq!(queue, (1..=3).collect());
+    assert_ne!(queue, (1..=4).collect());
+  }
+
+  #[test]
+  fn clear() {
+    let mut queue: TwoStackQueue<i32> = (1..=3).collect();
+    queue.dequeue();
+    queue.clear();
+
+    assert!(queue.is_empty());
+    queue.enqueue(7);
+    assert_eq!(queue.dequeue(), Some(7));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let mut value: TwoStackQueue<i32> = (0..=3).collect();
+    value.dequeue();
+    value.enqueue(4);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,2,3,4]");
+
+    let queue: TwoStackQueue<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(queue, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Enqueue(i32),
+    Dequeue
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      any::<i32>().prop_map(Operation::Enqueue),
+      Just(Operation::Dequeue)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_vec_deque(operations in prop::collection::vec(operation(), 0..200)) {
+      let mut queue = TwoStackQueue::new();
+      let mut model = VecDeque::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Enqueue(value) => {
+            queue.enqueue(value);
+            model.push_back(value);
+          }
+          Operation::Dequeue => prop_assert_eq!(queue.dequeue(), model.pop_front())
+        }
+
+        prop_assert_eq!(queue.peek(), model.front());
+        prop_assert_eq!(queue.len(), model.len());
+        prop_assert!(queue.iter().eq(model.iter()));
+      }
+    }
+  }
+}
@@ -0,0 +1,341 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+
+/// The root of the trie, matching the empty prefix.
+const ROOT: usize = 0;
+
+/// An automaton that finds all the occurrences of several patterns in one
+/// pass over a text.
+///
+/// The patterns are stored in a trie of bytes, whose every state stands for
+/// a prefix of a pattern. Every state also has a failure link to the state
+/// of its longest proper suffix that is in the trie, where the search goes
+/// on when the next byte of the text does not continue the current prefix.
+/// A search thus reads every byte of the text once and finds all the
+/// occurrences in O(n + m + z), for a text of length `n`, patterns of total
+/// length `m` and `z` occurrences.
+///
+/// # Example
+///
+/// ```
+/// use strings::AhoCorasick;
+///
+/// let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+/// let matches: Vec<_> = automaton.find_iter("ushers").map(|m| (m.pattern(), m.range())).collect();
+///
+/// // Overlapping occurrences are all found, by their end.
+/// assert_eq!(matches, vec![(1, 1..4), (0, 2..4), (3, 2..6)]);
+/// assert!(automaton.is_match("this"));
+/// assert!(!automaton.is_match("hi"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+  /// States of the trie. The children of a state come from the patterns,
+  /// the links are added once all the patterns are in.
+  states: Vec<State>,
+  /// Lengths of the patterns, by index.
+  lengths: Vec<usize>
+}
+
+/// Represents a state of an Aho-Corasick automaton.
+#[derive(Clone, Debug, Default)]
+struct State {
+  /// Children of the state by byte, sorted by byte.
+  children: Vec<(u8, usize)>,
+  /// The state of the longest proper suffix of this prefix in the trie.
+  failure: usize,
+  /// The nearest state on the failure path that ends patterns.
+  output: Option<usize>,
+  /// Indices of the patterns equal to the prefix of the state.
+  patterns: Vec<usize>
+}
+
+/// An occurrence of a pattern in a text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Match {
+  pattern: usize,
+  start: usize,
+  end: usize
+}
+
+impl Match {
+  /// Returns the index of the pattern, in the order the automaton got the
+  /// patterns.
+  pub fn pattern(&self) -> usize {
+    self.pattern
+  }
+
+  /// Returns the position of the first byte of the occurrence.
+  pub fn start(&self) -> usize {
+    self.start
+  }
+
+  /// Returns the position after the last byte of the occurrence.
+  pub fn end(&self) -> usize {
+    self.end
+  }
+
+  /// Returns the positions of the occurrence.
+  pub fn range(&self) -> Range<usize> {
+    self.start..self.end
+  }
+}
+
+impl State {
+  fn child(&self, byte: u8) -> Option<usize> {
+    let index = self.children.binary_search_by_key(&byte, |&(child, _)| child).ok()?;
+    Some(self.children[index].1)
+  }
+}
+
+impl AhoCorasick {
+  /// Builds the automaton of the patterns in O(m), where `m` is their
+  /// total length.
+  ///
+  /// The patterns are numbered in order. The same pattern can be given
+  /// several times, and its occurrences are then reported once for every
+  /// index. An empty pattern occurs at every position of a text.
+  pub fn new<I, P>(patterns: I) -> Self
+  where
+    I: IntoIterator<Item = P>,
+    P: AsRef<[u8]>,
+  {
+    let mut automaton = Self { states: vec![State::default()], lengths: Vec::new() };
+
+    for pattern in patterns {
+      automaton.insert(pattern.as_ref());
+    }
+    automaton.link();
+
+    automaton
+  }
+
+  /// Returns the number of patterns.
+  pub fn pattern_count(&self) -> usize {
+    self.lengths.len()
+  }
+
+  /// Checks if any pattern occurs in the text.
+  pub fn is_match<H: AsRef<[u8]> + ?Sized>(&self, haystack: &H) -> bool {
+    self.find_iter(haystack).next().is_some()
+  }
+
+  /// Returns an iterator over the occurrences of all the patterns in the
+  /// text, including overlapping ones, by their end. Occurrences that end
+  /// at the same position come from the longest to the shortest.
+  pub fn find_iter<'a, 'h, H: AsRef<[u8]> + ?Sized>(&'a self, haystack: &'h H) -> FindIter<'a, 'h> {
+    let pending = self.first_output(ROOT);
+    FindIter { automaton: self, haystack: haystack.as_ref(), position: 0, state: ROOT, pending }
+  }
+
+  /// Adds the pattern to the trie.
+  fn insert(&mut self, pattern: &[u8]) {
+    let mut state = ROOT;
+
+    for &byte in pattern {
+      state = match self.states[state].children.binary_search_by_key(&byte, |&(child, _)| child) {
+        Ok(index) => self.states[state].children[index].1,
+        Err(index) => {
+          self.states.push(State::default());
+          let child = self.states.len() - 1;
+          self.states[state].children.insert(index, (byte, child));
+          child
+        }
+      };
+    }
+
+    self.states[state].patterns.push(self.lengths.len());
+    self.lengths.push(pattern.len());
+  }
+
+  /// Adds the failure and output links, level by level from the root, so
+  /// the links of the shorter prefixes are known first.
+  fn link(&mut self) {
+    let mut queue = VecDeque::from([ROOT]);
+
+    while let Some(state) = queue.pop_front() {
+      for index in 0..self.states[state].children.len() {
+        let (byte, child) = self.states[state].children[index];
+        let failure = if state == ROOT { ROOT } else { self.next_state(self.states[state].failure, byte) };
+
+        let output = if self.states[failure].patterns.is_empty() { self.states[failure].output } else { Some(failure) };
+        (self.states[child].failure, self.states[child].output) = (failure, output);
+        queue.push_back(child);
+      }
+    }
+  }
+
+  /// Returns the state after reading the byte in the state, following the
+  /// failure links until a state has a child for the byte.
+  fn next_state(&self, mut state: usize, byte: u8) -> usize {
+    loop {
+      if let Some(child) = self.states[state].child(byte) {
+        return child;
+      }
+      if state == ROOT {
+        return ROOT;
+      }
+      state = self.states[state].failure;
+    }
+  }
+
+  /// Returns the first state on the failure path of the state, itself
+  /// included, that ends patterns.
+  fn first_output(&self, state: usize) -> Option<(usize, usize)> {
+    let state = if self.states[state].patterns.is_empty() { self.states[state].output? } else { state };
+    Some((state, 0))
+  }
+}
+
+/// An iterator over the occurrences of the patterns of an `AhoCorasick`
+/// automaton in a text.
+pub struct FindIter<'a, 'h> {
+  automaton: &'a AhoCorasick,
+  haystack: &'h [u8],
+  /// Number of bytes of the text read so far.
+  position: usize,
+  state: usize,
+  /// The state whose patterns end at `position` and the index of the next
+  /// one of them to report.
+  pending: Option<(usize, usize)>
+}
+
+impl Iterator for FindIter<'_, '_> {
+  type Item = Match;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((state, index)) = self.pending {
+        let current = &self.automaton.states[state];
+
+        match current.patterns.get(index) {
+          Some(&pattern) => {
+            self.pending = Some((state, index + 1));
+            let start = self.position - self.automaton.lengths[pattern];
+            return Some(Match { pattern, start, end: self.position });
+          }
+          None => self.pending = current.output.map(|output| (output, 0))
+        }
+        continue;
+      }
+
+      let &byte = self.haystack.get(self.position)?;
+      self.state = self.automaton.next_state(self.state, byte);
+      self.position += 1;
+      self.pending = self.automaton.first_output(self.state);
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AhoCorasick {
+  /// Serializes the patterns as a sequence of byte strings.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut patterns = vec![Vec::new(); self.lengths.len()];
+    let mut stack = vec![(ROOT, Vec::new())];
+
+    while let Some((state, prefix)) = stack.pop() {
+      for &pattern in &self.states[state].patterns {
+        patterns[pattern] = prefix.clone();
+      }
+      for &(byte, child) in &self.states[state].children {
+        let mut prefix = prefix.clone();
+        prefix.push(byte);
+        stack.push((child, prefix));
+      }
+    }
+
+    serializer.collect_seq(patterns)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AhoCorasick {
+  /// Deserializes the patterns and builds their automaton.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::<Vec<u8>>::deserialize(deserializer).map(AhoCorasick::new)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::AhoCorasick;
+  use proptest::prelude::*;
+
+  fn matches(automaton: &AhoCorasick, haystack: &str) -> Vec<(usize, usize, usize)> {
+    automaton.find_iter(haystack).map(|m| (m.pattern(), m.start(), m.end())).collect()
+  }
+
+  #[test]
+  fn no_patterns() {
+    let automaton = AhoCorasick::new(Vec::<&str>::new());
+
+    assert_eq!(automaton.pattern_count(), 0);
+    assert!(!automaton.is_match("anything"));
+  }
+
+  #[test]
+  fn overlapping_matches() {
+    let automaton = AhoCorasick::new(["a", "ab", "bab", "bc", "bca", "c", "caa"]);
+
+    assert_eq!(matches(&automaton, "abccab"), vec![(0, 0, 1), (1, 0, 2), (3, 1, 3), (5, 2, 3), (5, 3, 4), (0, 4, 5), (1, 4, 6)]);
+  }
+
+  #[test]
+  fn nested_patterns() {
+    let automaton = AhoCorasick::new(["aaa", "a", "aa"]);
+
+    assert_eq!(matches(&automaton, "aaa"), vec![(1, 0, 1), (2, 0, 2), (1, 1, 2), (0, 0, 3), (2, 1, 3), (1, 2, 3)]);
+  }
+
+  #[test]
+  fn duplicate_and_empty_patterns() {
+    let automaton = AhoCorasick::new(["x", "", "x"]);
+
+    assert_eq!(matches(&automaton, "x"), vec![(1, 0, 0), (0, 0, 1), (2, 0, 1), (1, 1, 1)]);
+  }
+
+  #[test]
+  fn bytes() {
+    let automaton = AhoCorasick::new([&[0xffu8, 0][..], &[0]]);
+
+    assert_eq!(automaton.find_iter(&[1u8, 0xff, 0, 0]).map(|m| m.range()).collect::<Vec<_>>(), vec![1..3, 2..3, 3..4]);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = AhoCorasick::new(["ab", "b", ""]);
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[[97,98],[98],[]]");
+
+    let automaton: AhoCorasick = serde_json::from_str(&json).unwrap();
+    assert_eq!(matches(&automaton, "abb"), matches(&value, "abb"));
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_naive_search(patterns in prop::collection::vec("[abc]{0,4}", 0..8), haystack in "[abc]{0,40}") {
+      let automaton = AhoCorasick::new(&patterns);
+
+      let mut expected = Vec::new();
+      for end in 0..=haystack.len() {
+        for (pattern, text) in patterns.iter().enumerate() {
+          if haystack[..end].ends_with(text.as_str()) {
+            expected.push((pattern, end - text.len(), end));
+          }
+        }
+      }
+
+      let mut found = matches(&automaton, &haystack);
+      // Occurrences come by their end, from the longest to the shortest.
+      prop_assert!(found.windows(2).all(|pair| (pair[0].2, pair[0].1) <= (pair[1].2, pair[1].1)));
+      found.sort_by_key(|&(pattern, start, end)| (end, pattern, start));
+      prop_assert_eq!(found, expected);
+    }
+  }
+}
@@ -0,0 +1,12 @@
+//! Structures for searching in strings.
+//!
+//! The structures work on bytes, so they accept `str`, `[u8]` and anything
+//! else that can be viewed as bytes, and report positions as byte offsets.
+
+pub mod aho_corasick;
+pub mod radix;
+pub mod suffix;
+
+pub use aho_corasick::{AhoCorasick, Match};
+pub use radix::RadixTrie;
+pub use suffix::SuffixArray;
@@ -0,0 +1,478 @@
+use std::fmt;
+use std::mem;
+
+
+/// A map from byte strings to values, stored in a trie whose edges are
+/// labeled with whole byte strings instead of single bytes.
+///
+/// A trie with one node per byte spends a node on every byte of every key,
+/// even where keys share nothing. This trie keeps a node only where keys
+/// branch or end, and labels the edge to it with all the bytes in between,
+/// so it has at most two nodes per key however long the keys are. This path
+/// compression is the idea of the PATRICIA trie.
+///
+/// Lookups compare the key with the labels on the way down in O(k) for a
+/// key of length `k`, and the keys come out of the trie in byte order.
+///
+/// # Example
+///
+/// ```
+/// use strings::RadixTrie;
+///
+/// let mut routes = RadixTrie::new();
+/// routes.insert("/", "index");
+/// routes.insert("/users", "list users");
+/// routes.insert("/users/new", "new user");
+///
+/// assert_eq!(routes.get("/users"), Some(&"list users"));
+/// assert_eq!(routes.get("/user"), None);
+///
+/// // The longest key that is a prefix of the path.
+/// assert_eq!(routes.longest_prefix("/users/42"), Some((&b"/users"[..], &"list users")));
+/// assert_eq!(routes.longest_prefix("/about"), Some((&b"/"[..], &"index")));
+///
+/// let keys: Vec<_> = routes.iter().map(|(key, _)| String::from_utf8(key).unwrap()).collect();
+/// assert_eq!(keys, vec!["/", "/users", "/users/new"]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RadixTrie<V> {
+  /// The node of the empty key, with an empty label.
+  root: Node<V>,
+  len: usize
+}
+
+/// Represents a node in a radix trie.
+#[derive(Clone, PartialEq, Eq)]
+struct Node<V> {
+  /// Bytes on the edge from the parent, never empty but for the root.
+  label: Box<[u8]>,
+  /// The value of the key that ends at the node, if one does.
+  value: Option<V>,
+  /// Children of the node, sorted by the first byte of their labels, which
+  /// are all different.
+  children: Vec<Node<V>>
+}
+
+impl<V> Default for RadixTrie<V> {
+  /// Creates a new instance of `RadixTrie` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<V> RadixTrie<V> {
+  /// Creates a new empty trie.
+  pub fn new() -> Self {
+    Self { root: Node { label: Box::default(), value: None, children: Vec::new() }, len: 0 }
+  }
+
+  /// Returns the number of keys in the trie.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Checks if the trie is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Inserts a value for the key.
+  ///
+  /// Returns the previous value of the key, or `None` if the key was not
+  /// in the trie.
+  pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+    let previous = insert(&mut self.root, key.as_ref(), value);
+    if previous.is_none() {
+      self.len += 1;
+    }
+    previous
+  }
+
+  /// Returns a reference to the value of the key, or `None` if the key is
+  /// not in the trie.
+  pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+    let (node, rest) = self.descend(key.as_ref());
+    node.value.as_ref().filter(|_| rest == 0)
+  }
+
+  /// Returns a mutable reference to the value of the key, or `None` if the
+  /// key is not in the trie.
+  pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+    let mut node = &mut self.root;
+    let mut key = key.as_ref();
+
+    while let Some(&first) = key.first() {
+      node = node.child_mut(first).filter(|child| key.starts_with(&child.label))?;
+      key = &key[node.label.len()..];
+    }
+
+    node.value.as_mut()
+  }
+
+  /// Checks if the key is in the trie.
+  pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// Removes the key from the trie and returns its value, or `None` if the
+  /// key was not in the trie.
+  ///
+  /// The nodes left without a value and with a single child are merged
+  /// with it, so the trie stays compressed.
+  pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+    let value = remove(&mut self.root, key.as_ref())?;
+    self.len -= 1;
+    Some(value)
+  }
+
+  /// Returns the longest key in the trie that is a prefix of the given
+  /// key, as a slice of the given key, with its value, or `None` if no key
+  /// in the trie is a prefix of it.
+  pub fn longest_prefix<'k, K: AsRef<[u8]> + ?Sized>(&self, key: &'k K) -> Option<(&'k [u8], &V)> {
+    let key = key.as_ref();
+    let (mut node, mut depth) = (&self.root, 0);
+    let mut longest = node.value.as_ref().map(|value| (0, value));
+
+    while let Some(child) = key.get(depth).and_then(|&first| node.child(first)) {
+      if !key[depth..].starts_with(&child.label) {
+        break;
+      }
+
+      (node, depth) = (child, depth + child.label.len());
+      if let Some(value) = &node.value {
+        longest = Some((depth, value));
+      }
+    }
+
+    longest.map(|(length, value)| (&key[..length], value))
+  }
+
+  /// Returns an iterator over the keys and values of the trie, in the
+  /// order of the keys.
+  pub fn iter(&self) -> Iter<'_, V> {
+    Iter { stack: vec![(&self.root, 0)], key: Vec::new() }
+  }
+
+  /// Follows the key down the trie as far as it matches whole labels, and
+  /// returns the last node reached with the number of bytes of the key
+  /// left.
+  fn descend(&self, key: &[u8]) -> (&Node<V>, usize) {
+    let (mut node, mut key) = (&self.root, key);
+
+    while let Some(child) = key.first().and_then(|&first| node.child(first)).filter(|child| key.starts_with(&child.label)) {
+      (node, key) = (child, &key[child.label.len()..]);
+    }
+
+    (node, key.len())
+  }
+}
+
+impl<V> Node<V> {
+  fn leaf(label: &[u8], value: V) -> Self {
+    Self { label: label.into(), value: Some(value), children: Vec::new() }
+  }
+
+  /// Returns the position of the child whose label starts with the byte,
+  /// or where it would be inserted.
+  fn find(&self, first: u8) -> Result<usize, usize> {
+    self.children.binary_search_by_key(&first, |child| child.label[0])
+  }
+
+  fn child(&self, first: u8) -> Option<&Node<V>> {
+    self.find(first).ok().map(|index| &self.children[index])
+  }
+
+  fn child_mut(&mut self, first: u8) -> Option<&mut Node<V>> {
+    self.find(first).ok().map(|index| &mut self.children[index])
+  }
+}
+
+/// Inserts the value for the key, relative to the node, into the subtree
+/// of the node.
+fn insert<V>(node: &mut Node<V>, key: &[u8], value: V) -> Option<V> {
+  let Some(&first) = key.first() else {
+    return node.value.replace(value);
+  };
+
+  let index = match node.find(first) {
+    Ok(index) => index,
+    Err(index) => {
+      node.children.insert(index, Node::leaf(key, value));
+      return None;
+    }
+  };
+
+  let child = &mut node.children[index];
+  let common = child.label.iter().zip(key).take_while(|(a, b)| a == b).count();
+
+  if common < child.label.len() {
+    // Splits the edge where the key leaves it, under a new node that gets
+    // the shared part of the label.
+    let split = Node { label: key[..common].into(), value: None, children: Vec::new() };
+    let mut rest = mem::replace(child, split);
+    rest.label = rest.label[common..].into();
+    child.children.push(rest);
+  }
+
+  insert(child, &key[common..], value)
+}
+
+/// Removes the key, relative to the node, from the subtree of the node,
+/// and compresses the child it went through.
+fn remove<V>(node: &mut Node<V>, key: &[u8]) -> Option<V> {
+  let Some(&first) = key.first() else {
+    return node.value.take();
+  };
+
+  let index = node.find(first).ok()?;
+  let child = &mut node.children[index];
+  if !key.starts_with(&child.label) {
+    return None;
+  }
+
+  let value = remove(child, &key[child.label.len()..])?;
+
+  if child.value.is_none() {
+    match child.children.len() {
+      0 => {
+        node.children.remove(index);
+      }
+      1 => {
+        let mut grandchild = child.children.pop().unwrap();
+        grandchild.label = [&child.label[..], &grandchild.label[..]].concat().into();
+        *child = grandchild;
+      }
+      _ => {}
+    }
+  }
+
+  Some(value)
+}
+
+/// An iterator over the keys and values of a `RadixTrie`, in the order of
+/// the keys.
+pub struct Iter<'a, V> {
+  /// Nodes still to visit with the length of the key of their parent, the
+  /// next one on top.
+  stack: Vec<(&'a Node<V>, usize)>,
+  /// The key of the last visited node.
+  key: Vec<u8>
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+  type Item = (Vec<u8>, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some((node, depth)) = self.stack.pop() {
+      self.key.truncate(depth);
+      self.key.extend_from_slice(&node.label);
+      self.stack.extend(node.children.iter().rev().map(|child| (child, self.key.len())));
+
+      if let Some(value) = &node.value {
+        return Some((self.key.clone(), value));
+      }
+    }
+
+    None
+  }
+}
+
+impl<'a, V> IntoIterator for &'a RadixTrie<V> {
+  type Item = (Vec<u8>, &'a V);
+  type IntoIter = Iter<'a, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for RadixTrie<V> {
+  /// Creates a trie by inserting every key and value of the iterator.
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut trie = Self::new();
+
+    for (key, value) in iter {
+      trie.insert(key, value);
+    }
+
+    trie
+  }
+}
+
+impl<V: fmt::Debug> fmt::Debug for RadixTrie<V> {
+  /// Formats the trie as a map, with the keys as lossy UTF-8 strings.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter().map(|(key, value)| (String::from_utf8_lossy(&key).into_owned(), value))).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<V> serde::Serialize for RadixTrie<V>
+where
+  V: serde::Serialize,
+{
+  /// Serializes the keys and values as a sequence of pairs in the order of
+  /// the keys, as byte strings are not keys in every format.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for RadixTrie<V>
+where
+  V: serde::Deserialize<'de>,
+{
+  /// Deserializes a trie by inserting every pair of a sequence.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::<(Vec<u8>, V)>::deserialize(deserializer).map(|entries| entries.into_iter().collect())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Node, RadixTrie};
+  use proptest::prelude::*;
+  use std::collections::BTreeMap;
+
+  /// Checks that every node but the root has a label, that children are
+  /// sorted, and that no node could be merged, and returns the number of
+  /// nodes of the subtree.
+  fn check<V>(node: &Node<V>, root: bool) -> usize {
+    assert!(root || !node.label.is_empty());
+    assert!(root || node.value.is_some() || node.children.len() > 1);
+    assert!(node.children.windows(2).all(|pair| pair[0].label[0] < pair[1].label[0]));
+    1 + node.children.iter().map(|child| check(child, false)).sum::<usize>()
+  }
+
+  #[test]
+  fn empty_trie() {
+    let mut trie = RadixTrie::<i32>::new();
+
+    assert!(trie.is_empty());
+    assert_eq!(trie.get(""), None);
+    assert_eq!(trie.longest_prefix("abc"), None);
+    assert_eq!(trie.remove("abc"), None);
+    assert_eq!(trie.iter().count(), 0);
+  }
+
+  #[test]
+  fn insert_and_get() {
+    let mut trie = RadixTrie::new();
+
+    assert_eq!(trie.insert("romane", 1), None);
+    assert_eq!(trie.insert("romanus", 2), None);
+    assert_eq!(trie.insert("romulus", 3), None);
+    assert_eq!(trie.insert("rom", 4), None);
+    assert_eq!(trie.insert("romane", 10), Some(1));
+
+    assert_eq!(trie.len(), 4);
+    assert_eq!(trie.get("romane"), Some(&10));
+    assert_eq!(trie.get("rom"), Some(&4));
+    assert_eq!(trie.get("roman"), None);
+    assert_eq!(trie.get("romanes"), None);
+    *trie.get_mut("romulus").unwrap() += 1;
+    assert_eq!(trie.get("romulus"), Some(&4));
+
+    // The root, "rom", "an", "e", "us" and "ulus".
+    assert_eq!(check(&trie.root, true), 6);
+  }
+
+  #[test]
+  fn empty_key() {
+    let mut trie = RadixTrie::new();
+    trie.insert("", 0);
+    trie.insert("a", 1);
+
+    assert_eq!(trie.get(""), Some(&0));
+    assert_eq!(trie.longest_prefix("b"), Some((&b""[..], &0)));
+    assert_eq!(trie.remove(""), Some(0));
+    assert_eq!(trie.longest_prefix("b"), None);
+  }
+
+  #[test]
+  fn remove_merges_nodes() {
+    let mut trie: RadixTrie<i32> = [("test", 1), ("team", 2), ("toast", 3)].into_iter().collect();
+    assert_eq!(check(&trie.root, true), 6);
+
+    assert_eq!(trie.remove("te"), None);
+    assert_eq!(trie.remove("team"), Some(2));
+    assert_eq!(check(&trie.root, true), 4);
+    assert_eq!(trie.remove("toast"), Some(3));
+
+    assert_eq!(check(&trie.root, true), 2);
+    assert_eq!(&*trie.root.children[0].label, b"test");
+  }
+
+  #[test]
+  fn longest_prefix() {
+    let trie: RadixTrie<&str> = [("10.0", "a"), ("10.0.0", "b"), ("10.1", "c")].into_iter().collect();
+
+    assert_eq!(trie.longest_prefix("10.0.0.1"), Some((&b"10.0.0"[..], &"b")));
+    assert_eq!(trie.longest_prefix("10.0.1.1"), Some((&b"10.0"[..], &"a")));
+    assert_eq!(trie.longest_prefix("10.2"), None);
+    assert_eq!(trie.longest_prefix("10."), None);
+  }
+
+  #[test]
+  fn iter_in_key_order() {
+    let trie: RadixTrie<usize> = ["b", "abc", "ab", "", "abd", "a"].into_iter().map(|key| (key, key.len())).collect();
+    let keys: Vec<_> = trie.iter().map(|(key, _)| String::from_utf8(key).unwrap()).collect();
+
+    assert_eq!(keys, vec!["", "a", "ab", "abc", "abd", "b"]);
+    assert_eq!(format!("{:?}", trie), r#"{"": 0, "a": 1, "ab": 2, "abc": 3, "abd": 3, "b": 1}"#);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value: RadixTrie<i32> = [("b", 2), ("a", 1)].into_iter().collect();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[[[97],1],[[98],2]]");
+
+    let trie: RadixTrie<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(trie, value);
+  }
+
+  #[derive(Debug, Clone)]
+  enum Operation {
+    Insert(String, i32),
+    Remove(String),
+    LongestPrefix(String)
+  }
+
+  fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+      2 => ("[ab]{0,5}", any::<i32>()).prop_map(|(key, value)| Operation::Insert(key, value)),
+      1 => "[ab]{0,5}".prop_map(Operation::Remove),
+      1 => "[ab]{0,6}".prop_map(Operation::LongestPrefix)
+    ]
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn behaves_like_btree_map(operations in prop::collection::vec(operation(), 0..100)) {
+      let mut trie = RadixTrie::new();
+      let mut model = BTreeMap::new();
+
+      for operation in operations {
+        match operation {
+          Operation::Insert(key, value) => prop_assert_eq!(trie.insert(&key, value), model.insert(key.into_bytes(), value)),
+          Operation::Remove(key) => prop_assert_eq!(trie.remove(&key), model.remove(key.as_bytes())),
+          Operation::LongestPrefix(key) => {
+            let expected = (0..=key.len()).rev().find_map(|length| model.get_key_value(&key.as_bytes()[..length]));
+            let found = trie.longest_prefix(&key).map(|(prefix, value)| (prefix.to_vec(), value));
+            prop_assert_eq!(found.as_ref().map(|(prefix, value)| (prefix, *value)), expected);
+          }
+        }
+
+        check(&trie.root, true);
+        prop_assert_eq!(trie.len(), model.len());
+        prop_assert!(trie.iter().eq(model.iter().map(|(key, value)| (key.clone(), value))));
+      }
+    }
+  }
+}
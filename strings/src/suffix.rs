@@ -0,0 +1,299 @@
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+
+
+/// The suffixes of a text in sorted order, for finding patterns in it.
+///
+/// The array holds the starting positions of all the suffixes of the text,
+/// sorted by the suffixes. The suffixes starting with a pattern are next to
+/// each other in that order, so a binary search finds all the occurrences
+/// of a pattern of length `m` in O(m log n), however many there are.
+///
+/// The array is built by prefix doubling in O(n log n): the suffixes are
+/// sorted by their first character, then by their first two, four, and so
+/// on, every round sorting pairs of ranks of the previous one with a
+/// counting sort.
+///
+/// # Example
+///
+/// ```
+/// use strings::SuffixArray;
+///
+/// let array = SuffixArray::new("banana");
+///
+/// // The suffixes "a", "ana", "anana", "banana", "na" and "nana".
+/// assert_eq!(array.suffixes(), [5, 3, 1, 0, 4, 2]);
+/// assert!(array.contains("nan"));
+/// assert!(!array.contains("nab"));
+/// assert_eq!(array.find_all("ana"), vec![1, 3]);
+///
+/// // "ana" is the longest prefix shared by neighboring suffixes.
+/// assert_eq!(array.lcp_array(), vec![0, 1, 3, 0, 0, 2]);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct SuffixArray {
+  text: Vec<u8>,
+  /// Starting positions of the suffixes of `text`, in sorted order.
+  suffixes: Vec<usize>
+}
+
+impl SuffixArray {
+  /// Builds the suffix array of the text in O(n log n).
+  pub fn new<T: AsRef<[u8]>>(text: T) -> Self {
+    let text = text.as_ref().to_vec();
+    let suffixes = build(&text);
+    Self { text, suffixes }
+  }
+
+  /// Returns the text of the array.
+  pub fn text(&self) -> &[u8] {
+    &self.text
+  }
+
+  /// Returns the length of the text in bytes.
+  pub fn len(&self) -> usize {
+    self.text.len()
+  }
+
+  /// Checks if the text is empty.
+  pub fn is_empty(&self) -> bool {
+    self.text.is_empty()
+  }
+
+  /// Returns the starting positions of the suffixes of the text, in the
+  /// order of the suffixes.
+  pub fn suffixes(&self) -> &[usize] {
+    &self.suffixes
+  }
+
+  /// Checks if the pattern occurs in the text, in O(m log n).
+  pub fn contains<P: AsRef<[u8]>>(&self, pattern: P) -> bool {
+    !self.matching(pattern.as_ref()).is_empty()
+  }
+
+  /// Returns the number of occurrences of the pattern in the text, in
+  /// O(m log n). Overlapping occurrences are all counted.
+  pub fn count<P: AsRef<[u8]>>(&self, pattern: P) -> usize {
+    self.matching(pattern.as_ref()).len()
+  }
+
+  /// Returns the positions of all the occurrences of the pattern in the
+  /// text, in ascending order. Overlapping occurrences are all returned.
+  pub fn find_all<P: AsRef<[u8]>>(&self, pattern: P) -> Vec<usize> {
+    let mut positions = self.suffixes[self.matching(pattern.as_ref())].to_vec();
+    positions.sort_unstable();
+    positions
+  }
+
+  /// Computes the longest common prefix array with Kasai's algorithm in
+  /// O(n).
+  ///
+  /// The element at `i` is the length of the longest prefix shared by the
+  /// suffixes at `i - 1` and `i` of `suffixes`, and the first element is
+  /// zero.
+  pub fn lcp_array(&self) -> Vec<usize> {
+    let n = self.text.len();
+    let mut rank = vec![0; n];
+    for (index, &suffix) in self.suffixes.iter().enumerate() {
+      rank[suffix] = index;
+    }
+
+    // The prefix shared with the previous suffix shrinks by at most one
+    // from a suffix to the next one in the text, so it is never counted
+    // from scratch.
+    let (mut lcp, mut shared) = (vec![0; n], 0);
+    for suffix in 0..n {
+      if rank[suffix] == 0 {
+        shared = 0;
+        continue;
+      }
+
+      let previous = self.suffixes[rank[suffix] - 1];
+      while suffix + shared < n && previous + shared < n && self.text[suffix + shared] == self.text[previous + shared] {
+        shared += 1;
+      }
+
+      lcp[rank[suffix]] = shared;
+      shared = shared.saturating_sub(1);
+    }
+
+    lcp
+  }
+
+  /// Returns the range of `suffixes` whose suffixes start with the pattern.
+  fn matching(&self, pattern: &[u8]) -> Range<usize> {
+    // Compares only the beginning of every suffix, as long as the pattern.
+    let prefix = |suffix: usize| &self.text[suffix..self.text.len().min(suffix + pattern.len())];
+
+    let start = self.suffixes.partition_point(|&suffix| prefix(suffix) < pattern);
+    let end = start + self.suffixes[start..].partition_point(|&suffix| prefix(suffix) == pattern);
+    start..end
+  }
+}
+
+/// Sorts the suffixes of the text by prefix doubling.
+fn build(text: &[u8]) -> Vec<usize> {
+  let n = text.len();
+  let mut suffixes: Vec<usize> = (0..n).collect();
+  suffixes.sort_unstable_by_key(|&suffix| text[suffix]);
+
+  // The rank of a suffix is the number of distinct prefixes of the
+  // current length that are smaller than its own.
+  let mut rank: Vec<usize> = text.iter().map(|&byte| usize::from(byte)).collect();
+  let mut next = vec![0; n];
+  let mut classes = 256;
+  let mut length = 1;
+
+  while length < n {
+    // Orders the suffixes by the rank of their second half first: the
+    // suffixes without one come first, then the others in the order of
+    // their second half, which starts `length` later.
+    let mut order: Vec<usize> = (n - length..n).collect();
+    order.extend(suffixes.iter().filter(|&&suffix| suffix >= length).map(|&suffix| suffix - length));
+
+    // Then sorts them by the rank of their first half with a stable
+    // counting sort.
+    let mut starts = vec![0; classes + 1];
+    for &suffix in &order {
+      starts[rank[suffix] + 1] += 1;
+    }
+    for class in 1..=classes {
+      starts[class] += starts[class - 1];
+    }
+    for &suffix in &order {
+      suffixes[starts[rank[suffix]]] = suffix;
+      starts[rank[suffix]] += 1;
+    }
+
+    let key = |suffix: usize| (rank[suffix], rank.get(suffix + length));
+    next[suffixes[0]] = 0;
+    for pair in suffixes.windows(2) {
+      next[pair[1]] = next[pair[0]] + usize::from(key(pair[0]) != key(pair[1]));
+    }
+
+    mem::swap(&mut rank, &mut next);
+    classes = rank[suffixes[n - 1]] + 1;
+    if classes == n {
+      break;
+    }
+    length *= 2;
+  }
+
+  suffixes
+}
+
+impl fmt::Debug for SuffixArray {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("SuffixArray").field("text", &String::from_utf8_lossy(&self.text)).field("suffixes", &self.suffixes).finish()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SuffixArray {
+  /// Serializes the text only, as the array is rebuilt from it.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.text.serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SuffixArray {
+  /// Deserializes a text and builds its suffix array.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::<u8>::deserialize(deserializer).map(SuffixArray::new)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::SuffixArray;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_text() {
+    let array = SuffixArray::new("");
+
+    assert!(array.is_empty());
+    assert!(array.suffixes().is_empty());
+    assert!(array.lcp_array().is_empty());
+    assert!(!array.contains("a"));
+    assert_eq!(array.count(""), 0);
+  }
+
+  #[test]
+  fn sorted_suffixes() {
+    let array = SuffixArray::new("mississippi");
+
+    assert_eq!(array.suffixes(), [10, 7, 4, 1, 0, 9, 8, 6, 3, 5, 2]);
+    assert_eq!(array.lcp_array(), vec![0, 1, 1, 4, 0, 0, 1, 0, 2, 1, 3]);
+  }
+
+  #[test]
+  fn repeated_characters() {
+    let array = SuffixArray::new("aaaa");
+
+    assert_eq!(array.suffixes(), [3, 2, 1, 0]);
+    assert_eq!(array.lcp_array(), vec![0, 1, 2, 3]);
+    assert_eq!(array.find_all("aa"), vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn find_all() {
+    let array = SuffixArray::new("the cat sat on the mat");
+
+    assert_eq!(array.find_all("at"), vec![5, 9, 20]);
+    assert_eq!(array.find_all("the "), vec![0, 15]);
+    assert_eq!(array.count("t"), 5);
+    assert!(array.find_all("dog").is_empty());
+    // The empty pattern occurs at every position.
+    assert_eq!(array.count(""), 22);
+  }
+
+  #[test]
+  fn bytes() {
+    let array = SuffixArray::new([0u8, 255, 0, 255, 0]);
+
+    assert_eq!(array.find_all([0, 255]), vec![0, 2]);
+    assert!(array.contains(b"\xff\x00\xff"));
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = SuffixArray::new("abc");
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[97,98,99]");
+
+    let array: SuffixArray = serde_json::from_str(&json).unwrap();
+    assert_eq!(array, value);
+  }
+
+  proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn matches_naive_search(text in "[ab]{0,40}", pattern in "[ab]{1,4}") {
+      let array = SuffixArray::new(&text);
+      let text = text.as_bytes();
+
+      let mut expected: Vec<usize> = (0..text.len()).collect();
+      expected.sort_by_key(|&suffix| &text[suffix..]);
+      prop_assert_eq!(array.suffixes(), &expected[..]);
+
+      let lcp: Vec<usize> = (0..expected.len()).map(|i| {
+        if i == 0 {
+          return 0;
+        }
+        let (a, b) = (&text[expected[i - 1]..], &text[expected[i]..]);
+        a.iter().zip(b).take_while(|(x, y)| x == y).count()
+      }).collect();
+      prop_assert_eq!(array.lcp_array(), lcp);
+
+      let positions: Vec<usize> = (0..text.len()).filter(|&i| text[i..].starts_with(pattern.as_bytes())).collect();
+      prop_assert_eq!(array.contains(&pattern), !positions.is_empty());
+      prop_assert_eq!(array.find_all(&pattern), positions);
+    }
+  }
+}
@@ -0,0 +1,546 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+
+/// A binary tree, whose nodes have at most a left and a right child.
+///
+/// Unlike a binary search tree, the values are not ordered: the shape of
+/// the tree is built by hand, for example to represent an expression or a
+/// decision tree. Every subtree is a `BinaryTree` itself, which may be
+/// empty.
+///
+/// # Example
+///
+/// ```
+/// use tree::BinaryTree;
+///
+/// //     *
+/// //    / \
+/// //   +   3
+/// //  / \
+/// // 1   2
+/// let mut tree = BinaryTree::node('*', BinaryTree::leaf('+'), BinaryTree::leaf('3'));
+/// tree.left_mut().unwrap().set_left(BinaryTree::leaf('1'));
+/// tree.left_mut().unwrap().set_right(BinaryTree::leaf('2'));
+///
+/// assert_eq!(tree.in_order().collect::<String>(), "1+2*3");
+/// assert_eq!(tree.post_order().collect::<String>(), "12+3*");
+/// assert_eq!(tree.height(), 3);
+///
+/// tree.mirror();
+/// assert_eq!(tree.in_order().collect::<String>(), "3*2+1");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryTree<T> {
+  root: Option<Box<Node<T>>>
+}
+
+/// Represents a node in a binary tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Node<T> {
+  value: T,
+  left: BinaryTree<T>,
+  right: BinaryTree<T>
+}
+
+/// Callbacks for `BinaryTree::walk`, each of which does nothing unless
+/// it is implemented.
+///
+/// Every callback receives a value and the depth of its node, which is
+/// zero for the root.
+pub trait Visitor<T> {
+  /// Called before the subtrees of the node are walked, in pre-order.
+  fn enter(&mut self, _value: &T, _depth: usize) {}
+
+  /// Called between the walks of the left and the right subtree, in
+  /// in-order.
+  fn visit(&mut self, _value: &T, _depth: usize) {}
+
+  /// Called after the subtrees of the node are walked, in post-order.
+  fn leave(&mut self, _value: &T, _depth: usize) {}
+}
+
+impl<T> Default for BinaryTree<T> {
+  /// Creates a new instance of `BinaryTree` with default values.
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> BinaryTree<T> {
+  /// Creates a new empty binary tree.
+  pub fn new() -> Self {
+    Self { root: None }
+  }
+
+  /// Creates a tree with a single node.
+  pub fn leaf(value: T) -> Self {
+    Self::node(value, Self::new(), Self::new())
+  }
+
+  /// Creates a tree with a root node and its two subtrees.
+  pub fn node(value: T, left: Self, right: Self) -> Self {
+    Self { root: Some(Box::new(Node { value, left, right })) }
+  }
+
+  /// Checks if the tree has no nodes.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns the value of the root node.
+  pub fn value(&self) -> Option<&T> {
+    self.root.as_ref().map(|node| &node.value)
+  }
+
+  /// Returns a mutable reference to the value of the root node.
+  pub fn value_mut(&mut self) -> Option<&mut T> {
+    self.root.as_mut().map(|node| &mut node.value)
+  }
+
+  /// Returns the left subtree, or `None` if the tree is empty.
+  pub fn left(&self) -> Option<&Self> {
+    self.root.as_ref().map(|node| &node.left)
+  }
+
+  /// Returns the right subtree, or `None` if the tree is empty.
+  pub fn right(&self) -> Option<&Self> {
+    self.root.as_ref().map(|node| &node.right)
+  }
+
+  /// Returns the left subtree mutably, or `None` if the tree is empty.
+  pub fn left_mut(&mut self) -> Option<&mut Self> {
+    self.root.as_mut().map(|node| &mut node.left)
+  }
+
+  /// Returns the right subtree mutably, or `None` if the tree is empty.
+  pub fn right_mut(&mut self) -> Option<&mut Self> {
+    self.root.as_mut().map(|node| &mut node.right)
+  }
+
+  /// Replaces the left subtree and returns the previous one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the tree is empty.
+  pub fn set_left(&mut self, subtree: Self) -> Self {
+    std::mem::replace(self.left_mut().expect("cannot set a child of an empty tree"), subtree)
+  }
+
+  /// Replaces the right subtree and returns the previous one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the tree is empty.
+  pub fn set_right(&mut self, subtree: Self) -> Self {
+    std::mem::replace(self.right_mut().expect("cannot set a child of an empty tree"), subtree)
+  }
+
+  /// Returns the number of nodes, which takes O(n) time.
+  pub fn count(&self) -> usize {
+    self.pre_order().count()
+  }
+
+  /// Returns the number of levels, which is zero for an empty tree and
+  /// one for a single node.
+  pub fn height(&self) -> usize {
+    let mut level: Vec<&Node<T>> = self.root.as_deref().into_iter().collect();
+    let mut height = 0;
+
+    while !level.is_empty() {
+      height += 1;
+      level = level
+        .into_iter()
+        .flat_map(|node| node.left.root.as_deref().into_iter().chain(node.right.root.as_deref()))
+        .collect();
+    }
+
+    height
+  }
+
+  /// Swaps the left and right subtrees of every node, which reverses the
+  /// in-order sequence.
+  pub fn mirror(&mut self) {
+    let mut stack = vec![self];
+
+    while let Some(tree) = stack.pop() {
+      if let Some(node) = tree.root.as_deref_mut() {
+        std::mem::swap(&mut node.left, &mut node.right);
+        stack.push(&mut node.left);
+        stack.push(&mut node.right);
+      }
+    }
+  }
+
+  /// Walks the tree depth-first and calls the visitor on every node
+  /// before, between and after its subtrees.
+  pub fn walk<V: Visitor<T>>(&self, visitor: &mut V) {
+    // Nodes with their depth and how many of their subtrees were walked.
+    let mut stack: Vec<(&Node<T>, usize, u8)> = Vec::new();
+
+    if let Some(root) = self.root.as_deref() {
+      visitor.enter(&root.value, 0);
+      stack.push((root, 0, 0));
+    }
+
+    while let Some((node, depth, walked)) = stack.last_mut() {
+      let (node, depth) = (*node, *depth);
+      *walked += 1;
+
+      let subtree = match *walked {
+        1 => &node.left,
+        2 => {
+          visitor.visit(&node.value, depth);
+          &node.right
+        }
+        _ => {
+          visitor.leave(&node.value, depth);
+          stack.pop();
+          continue;
+        }
+      };
+
+      if let Some(child) = subtree.root.as_deref() {
+        visitor.enter(&child.value, depth + 1);
+        stack.push((child, depth + 1, 0));
+      }
+    }
+  }
+
+  /// Returns an iterator visiting the node itself, then its left
+  /// subtree, and then its right subtree.
+  pub fn pre_order(&self) -> PreOrder<'_, T> {
+    PreOrder { stack: self.root.as_deref().into_iter().collect() }
+  }
+
+  /// Returns an iterator visiting the left subtree, the node itself,
+  /// and then the right subtree.
+  pub fn in_order(&self) -> InOrder<'_, T> {
+    let mut iter = InOrder { stack: Vec::new() };
+    iter.push_left_spine(self.root.as_deref());
+    iter
+  }
+
+  /// Returns an iterator visiting the left subtree, then the right
+  /// subtree, and then the node itself.
+  pub fn post_order(&self) -> PostOrder<'_, T> {
+    PostOrder { stack: self.root.as_deref().map(|node| (node, false)).into_iter().collect() }
+  }
+
+  /// Returns an iterator visiting the nodes level by level, each level
+  /// from left to right.
+  pub fn level_order(&self) -> LevelOrder<'_, T> {
+    LevelOrder { queue: self.root.as_deref().into_iter().collect() }
+  }
+}
+
+/// A pre-order iterator over the values of a `BinaryTree`.
+pub struct PreOrder<'a, T> {
+  stack: Vec<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.stack.extend(node.right.root.as_deref());
+    self.stack.extend(node.left.root.as_deref());
+    Some(&node.value)
+  }
+}
+
+/// An in-order iterator over the values of a `BinaryTree`.
+pub struct InOrder<'a, T> {
+  stack: Vec<&'a Node<T>>
+}
+
+impl<'a, T> InOrder<'a, T> {
+  /// Pushes the node and all of its left descendants onto the stack.
+  fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+    while let Some(current) = node {
+      self.stack.push(current);
+      node = current.left.root.as_deref();
+    }
+  }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.push_left_spine(node.right.root.as_deref());
+    Some(&node.value)
+  }
+}
+
+/// A post-order iterator over the values of a `BinaryTree`.
+pub struct PostOrder<'a, T> {
+  /// Pending nodes, flagged once their children have been scheduled.
+  stack: Vec<(&'a Node<T>, bool)>
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some((node, expanded)) = self.stack.pop() {
+      if expanded {
+        return Some(&node.value);
+      }
+
+      self.stack.push((node, true));
+      self.stack.extend(node.right.root.as_deref().map(|right| (right, false)));
+      self.stack.extend(node.left.root.as_deref().map(|left| (left, false)));
+    }
+
+    None
+  }
+}
+
+/// A level-order iterator over the values of a `BinaryTree`.
+pub struct LevelOrder<'a, T> {
+  queue: VecDeque<&'a Node<T>>
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.queue.pop_front()?;
+    self.queue.extend(node.left.root.as_deref());
+    self.queue.extend(node.right.root.as_deref());
+    Some(&node.value)
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> IntoParallelIterator for &'a BinaryTree<T> {
+  type Item = &'a T;
+  type Iter = rayon::vec::IntoIter<&'a T>;
+
+  /// Returns a parallel iterator over the values of the tree, collected
+  /// in pre-order.
+  fn into_par_iter(self) -> Self::Iter {
+    self.pre_order().collect::<Vec<_>>().into_par_iter()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for BinaryTree<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes an empty tree as none, and any other tree as a triple of
+  /// its root value and its left and right subtrees.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self.root.as_deref() {
+      Some(node) => serializer.serialize_some(&(&node.value, &node.left, &node.right)),
+      None => serializer.serialize_none()
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for BinaryTree<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a tree from none or a triple of a value and two subtrees.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let root: Option<(T, Self, Self)> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(root.map_or_else(Self::new, |(value, left, right)| Self::node(value, left, right)))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{BinaryTree, Visitor};
+  use proptest::prelude::*;
+
+  /// Builds the tree:
+  ///
+  /// ```text
+  ///     1
+  ///    / \
+  ///   2   3
+  ///  / \   \
+  /// 4   5   6
+  /// ```
+  fn sample() -> BinaryTree<i32> {
+    let two = BinaryTree::node(2, BinaryTree::leaf(4), BinaryTree::leaf(5));
+    let three = BinaryTree::node(3, BinaryTree::new(), BinaryTree::leaf(6));
+    BinaryTree::node(1, two, three)
+  }
+
+  fn collect<'a>(values: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+    values.copied().collect()
+  }
+
+  #[test]
+  fn empty_tree() {
+    let tree = BinaryTree::<i32>::new();
+
+    assert!(tree.is_empty());
+    assert_eq!(tree.value(), None);
+    assert_eq!(tree.left(), None);
+    assert_eq!((tree.count(), tree.height()), (0, 0));
+    assert_eq!(tree.level_order().next(), None);
+  }
+
+  #[test]
+  fn construction() {
+    let mut tree = BinaryTree::leaf(1);
+    assert_eq!(tree.set_left(BinaryTree::leaf(2)), BinaryTree::new());
+    tree.set_right(BinaryTree::leaf(3));
+    *tree.right_mut().unwrap().value_mut().unwrap() = 30;
+
+    assert_eq!(tree.left().and_then(BinaryTree::value), Some(&2));
+    assert_eq!(tree.right().and_then(BinaryTree::value), Some(&30));
+    assert_eq!(tree.set_left(BinaryTree::new()), BinaryTree::leaf(2));
+    assert_eq!(tree.count(), 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "cannot set a child of an empty tree")]
+  fn set_child_of_empty_tree() {
+    BinaryTree::new().set_right(BinaryTree::leaf(1));
+  }
+
+  #[test]
+  fn traversals() {
+    let tree = sample();
+
+    assert_eq!(collect(tree.pre_order()), [1, 2, 4, 5, 3, 6]);
+    assert_eq!(collect(tree.in_order()), [4, 2, 5, 1, 3, 6]);
+    assert_eq!(collect(tree.post_order()), [4, 5, 2, 6, 3, 1]);
+    assert_eq!(collect(tree.level_order()), [1, 2, 3, 4, 5, 6]);
+  }
+
+  #[test]
+  fn height_and_count() {
+    let mut tree = sample();
+    assert_eq!((tree.count(), tree.height()), (6, 3));
+
+    tree.right_mut().unwrap().right_mut().unwrap().set_left(BinaryTree::leaf(7));
+    assert_eq!((tree.count(), tree.height()), (7, 4));
+  }
+
+  #[test]
+  fn mirror() {
+    let mut tree = sample();
+    tree.mirror();
+
+    assert_eq!(collect(tree.in_order()), [6, 3, 1, 5, 2, 4]);
+    assert_eq!(collect(tree.level_order()), [1, 3, 2, 6, 5, 4]);
+
+    tree.mirror();
+    assert_eq!(tree, sample());
+  }
+
+  #[test]
+  fn walk() {
+    /// Renders the tree with parentheses around every subtree.
+    struct Parenthesize(String);
+
+    impl Visitor<i32> for Parenthesize {
+      fn enter(&mut self, _value: &i32, depth: usize) {
+        if depth > 0 {
+          self.0.push('(');
+        }
+      }
+
+      fn visit(&mut self, value: &i32, _depth: usize) {
+        self.0.push_str(&value.to_string());
+      }
+
+      fn leave(&mut self, _value: &i32, depth: usize) {
+        if depth > 0 {
+          self.0.push(')');
+        }
+      }
+    }
+
+    let mut visitor = Parenthesize(String::new());
+    sample().walk(&mut visitor);
+
+    assert_eq!(visitor.0, "((4)2(5))1(3(6))");
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = BinaryTree::node(1, BinaryTree::leaf(2), BinaryTree::new());
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[1,[2,null,null],null]");
+    assert_eq!(serde_json::from_str::<BinaryTree<i32>>(&json).unwrap(), value);
+  }
+
+  #[test]
+  #[cfg(feature = "rayon")]
+  fn parallel_iterator() {
+    use rayon::prelude::*;
+
+    let tree = sample();
+    assert_eq!(tree.par_iter().sum::<i32>(), 21);
+    assert_eq!(tree.par_iter().copied().collect::<Vec<_>>(), [1, 2, 4, 5, 3, 6]);
+    assert_eq!(BinaryTree::<i32>::new().par_iter().count(), 0);
+  }
+
+  fn binary_tree() -> impl Strategy<Value = BinaryTree<i32>> {
+    let leaf = prop_oneof![Just(BinaryTree::new()), any::<i32>().prop_map(BinaryTree::leaf)];
+    leaf.prop_recursive(8, 128, 2, |inner| {
+      (any::<i32>(), inner.clone(), inner).prop_map(|(value, left, right)| BinaryTree::node(value, left, right))
+    })
+  }
+
+  /// Collects the values in the given order recursively, as a reference
+  /// for the iterators.
+  fn recursive(tree: &BinaryTree<i32>, order: usize, values: &mut Vec<i32>) {
+    if let (Some(&value), Some(left), Some(right)) = (tree.value(), tree.left(), tree.right()) {
+      values.extend((order == 0).then_some(value));
+      recursive(left, order, values);
+      values.extend((order == 1).then_some(value));
+      recursive(right, order, values);
+      values.extend((order == 2).then_some(value));
+    }
+  }
+
+  proptest! {
+    #[test]
+    fn traversals_match_recursion(tree in binary_tree()) {
+      let iterators = [collect(tree.pre_order()), collect(tree.in_order()), collect(tree.post_order())];
+
+      for (order, values) in iterators.into_iter().enumerate() {
+        let mut expected = Vec::new();
+        recursive(&tree, order, &mut expected);
+        prop_assert_eq!(values, expected);
+      }
+
+      let mut sorted = collect(tree.level_order());
+      let mut expected = collect(tree.pre_order());
+      sorted.sort();
+      expected.sort();
+      prop_assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn mirror_reverses_in_order(tree in binary_tree()) {
+      let mut mirrored = tree.clone();
+      mirrored.mirror();
+
+      let mut reversed = collect(tree.in_order());
+      reversed.reverse();
+      prop_assert_eq!(collect(mirrored.in_order()), reversed);
+      prop_assert_eq!((mirrored.count(), mirrored.height()), (tree.count(), tree.height()));
+
+      mirrored.mirror();
+      prop_assert_eq!(mirrored, tree);
+    }
+  }
+}
@@ -0,0 +1,12 @@
+//! Trees that are not ordered by their values, but shaped by hand.
+//!
+//! The nodes of a tree are added one by one under a chosen parent, which
+//! makes these trees fit for data that is hierarchical by nature, like file
+//! systems or documents, rather than for searching.
+
+pub mod binary;
+pub mod nary;
+mod render;
+
+pub use binary::BinaryTree;
+pub use nary::{NodeId, Tree};
@@ -0,0 +1,543 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use slab::Slab;
+
+
+/// A handle to a node of a `Tree`.
+///
+/// A handle stays valid until its node is removed, after which it may be
+/// reused for a node added later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// A general-purpose tree, whose nodes can have any number of ordered
+/// children.
+///
+/// The nodes are stored in a `Slab` and refer to each other by `NodeId`,
+/// so adding a node is O(1) and every node knows its parent. The tree
+/// always has a root, which makes it a good fit for hierarchies like file
+/// systems or documents.
+///
+/// # Example
+///
+/// ```
+/// use tree::Tree;
+///
+/// let mut tree = Tree::new("/");
+/// let home = tree.add_child(tree.root(), "home");
+/// let user = tree.add_child(home, "user");
+/// tree.add_child(tree.root(), "tmp");
+///
+/// assert_eq!(tree.parent(user), Some(home));
+/// assert_eq!(tree.depth(user), 2);
+///
+/// let names: Vec<_> = tree.pre_order(tree.root()).map(|node| tree[node]).collect();
+/// assert_eq!(names, ["/", "home", "user", "tmp"]);
+/// ```
+#[derive(Clone)]
+pub struct Tree<T> {
+  nodes: Slab<Node<T>>,
+  root: usize
+}
+
+#[derive(Clone)]
+struct Node<T> {
+  value: T,
+  parent: Option<usize>,
+  children: Vec<usize>
+}
+
+impl<T> Tree<T> {
+  /// Creates a new tree with a single root node.
+  pub fn new(root: T) -> Self {
+    let mut nodes = Slab::new();
+    let root = nodes.insert(Node { value: root, parent: None, children: Vec::new() });
+    Self { nodes, root }
+  }
+
+  /// Returns the root node.
+  pub fn root(&self) -> NodeId {
+    NodeId(self.root)
+  }
+
+  /// Returns the number of nodes, including the root.
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Always returns `false`, a tree has at least its root.
+  pub fn is_empty(&self) -> bool {
+    false
+  }
+
+  /// Checks if the node belongs to the tree.
+  pub fn contains(&self, node: NodeId) -> bool {
+    self.nodes.contains(node.0)
+  }
+
+  /// Returns a reference to the value of the node.
+  pub fn get(&self, node: NodeId) -> Option<&T> {
+    self.nodes.get(node.0).map(|node| &node.value)
+  }
+
+  /// Returns a mutable reference to the value of the node.
+  pub fn get_mut(&mut self, node: NodeId) -> Option<&mut T> {
+    self.nodes.get_mut(node.0).map(|node| &mut node.value)
+  }
+
+  /// Adds a new node as the last child of `parent` and returns it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the parent is not in the tree.
+  pub fn add_child(&mut self, parent: NodeId, value: T) -> NodeId {
+    assert!(self.contains(parent), "invalid node id");
+
+    let child = self.nodes.insert(Node { value, parent: Some(parent.0), children: Vec::new() });
+    self.nodes[parent.0].children.push(child);
+    NodeId(child)
+  }
+
+  /// Removes a node with all its descendants and returns them as a new
+  /// tree rooted at the node, or `None` if the node is not in the tree or
+  /// is its root.
+  ///
+  /// The removed nodes get new handles in the returned tree.
+  pub fn remove_subtree(&mut self, node: NodeId) -> Option<Tree<T>> {
+    let parent = self.nodes.get(node.0)?.parent?;
+    self.nodes[parent].children.retain(|&child| child != node.0);
+
+    // Move the nodes over in pre-order, so parents are moved before
+    // their children.
+    let mut removed: Option<Tree<T>> = None;
+    let mut pending = vec![(node.0, None)];
+
+    while let Some((old, new_parent)) = pending.pop() {
+      let Node { value, children, .. } = self.nodes.remove(old).expect("tree node is missing");
+
+      let new = match (&mut removed, new_parent) {
+        (Some(tree), Some(new_parent)) => tree.add_child(new_parent, value),
+        _ => {
+          let tree = removed.insert(Tree::new(value));
+          tree.root()
+        }
+      };
+
+      pending.extend(children.into_iter().rev().map(|child| (child, Some(new))));
+    }
+
+    removed
+  }
+
+  /// Returns the parent of the node, or `None` for the root or a node that
+  /// is not in the tree.
+  pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+    self.nodes.get(node.0)?.parent.map(NodeId)
+  }
+
+  /// Returns the children of the node in order, which is empty for a node
+  /// that is not in the tree.
+  pub fn children(&self, node: NodeId) -> Children<'_> {
+    let children = self.nodes.get(node.0).map_or(&[][..], |node| &node.children);
+    Children { inner: children.iter() }
+  }
+
+  /// Checks if the node has no children.
+  pub fn is_leaf(&self, node: NodeId) -> bool {
+    self.children(node).len() == 0
+  }
+
+  /// Returns the number of edges between the node and the root.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the node is not in the tree.
+  pub fn depth(&self, node: NodeId) -> usize {
+    assert!(self.contains(node), "invalid node id");
+    std::iter::successors(self.parent(node), |&node| self.parent(node)).count()
+  }
+
+  /// Returns an iterator over the node and its descendants, every node
+  /// before its children.
+  pub fn pre_order(&self, node: NodeId) -> PreOrder<'_, T> {
+    let stack = if self.contains(node) { vec![node.0] } else { Vec::new() };
+    PreOrder { tree: self, stack }
+  }
+
+  /// Returns an iterator over the node and its descendants, every node
+  /// after its children.
+  pub fn post_order(&self, node: NodeId) -> PostOrder<'_, T> {
+    let stack = if self.contains(node) { vec![(node.0, 0)] } else { Vec::new() };
+    PostOrder { tree: self, stack }
+  }
+
+  /// Returns an iterator over the node and its descendants level by level,
+  /// from the node down.
+  pub fn breadth_first(&self, node: NodeId) -> BreadthFirst<'_, T> {
+    let queue = if self.contains(node) { VecDeque::from([node.0]) } else { VecDeque::new() };
+    BreadthFirst { tree: self, queue }
+  }
+}
+
+impl<T> Index<NodeId> for Tree<T> {
+  type Output = T;
+
+  fn index(&self, node: NodeId) -> &Self::Output {
+    self.get(node).expect("invalid node id")
+  }
+}
+
+impl<T> IndexMut<NodeId> for Tree<T> {
+  fn index_mut(&mut self, node: NodeId) -> &mut Self::Output {
+    self.get_mut(node).expect("invalid node id")
+  }
+}
+
+impl<T: PartialEq> PartialEq for Tree<T> {
+  /// Two trees are equal if they have the same shape and equal values at
+  /// the same places, whatever the handles of their nodes.
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.flatten().eq(other.flatten())
+  }
+}
+
+impl<T: Eq> Eq for Tree<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Tree<T> {
+  /// Formats every node as its value followed by the list of its
+  /// children, if it has any.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    struct Subtree<'a, T>(&'a Tree<T>, NodeId);
+
+    impl<T: fmt::Debug> fmt::Debug for Subtree<'_, T> {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Subtree(tree, node) = *self;
+        tree[node].fmt(f)?;
+
+        if !tree.is_leaf(node) {
+          f.write_str(" ")?;
+          f.debug_list().entries(tree.children(node).map(|child| Subtree(tree, child))).finish()?;
+        }
+
+        Ok(())
+      }
+    }
+
+    Subtree(self, self.root()).fmt(f)
+  }
+}
+
+impl<T> Tree<T> {
+  /// Returns the nodes in pre-order, each with the pre-order position of
+  /// its parent, which describes the tree without its handles.
+  pub(crate) fn flatten(&self) -> impl Iterator<Item = (Option<usize>, &T)> + '_ {
+    let mut positions = std::collections::HashMap::new();
+
+    self.pre_order(self.root()).enumerate().map(move |(position, node)| {
+      positions.insert(node, position);
+      (self.parent(node).map(|parent| positions[&parent]), &self[node])
+    })
+  }
+}
+
+/// An iterator over the children of a node of a `Tree`.
+pub struct Children<'a> {
+  inner: std::slice::Iter<'a, usize>
+}
+
+impl Iterator for Children<'_> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|&child| NodeId(child))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl DoubleEndedIterator for Children<'_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.inner.next_back().map(|&child| NodeId(child))
+  }
+}
+
+impl ExactSizeIterator for Children<'_> {}
+
+/// A pre-order iterator over the nodes of a `Tree`.
+pub struct PreOrder<'a, T> {
+  tree: &'a Tree<T>,
+  /// Nodes still to visit, the next one on top.
+  stack: Vec<usize>
+}
+
+impl<T> Iterator for PreOrder<'_, T> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.stack.pop()?;
+    self.stack.extend(self.tree.nodes[node].children.iter().rev());
+    Some(NodeId(node))
+  }
+}
+
+/// A post-order iterator over the nodes of a `Tree`.
+pub struct PostOrder<'a, T> {
+  tree: &'a Tree<T>,
+  /// Path from the start node, each node with the number of its children
+  /// that were visited.
+  stack: Vec<(usize, usize)>
+}
+
+impl<T> Iterator for PostOrder<'_, T> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let (node, visited) = self.stack.last_mut()?;
+      let node = *node;
+
+      match self.tree.nodes[node].children.get(*visited) {
+        Some(&child) => {
+          *visited += 1;
+          self.stack.push((child, 0));
+        }
+        None => {
+          self.stack.pop();
+          return Some(NodeId(node));
+        }
+      }
+    }
+  }
+}
+
+/// A breadth-first iterator over the nodes of a `Tree`.
+pub struct BreadthFirst<'a, T> {
+  tree: &'a Tree<T>,
+  queue: VecDeque<usize>
+}
+
+impl<T> Iterator for BreadthFirst<'_, T> {
+  type Item = NodeId;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let node = self.queue.pop_front()?;
+    self.queue.extend(&self.tree.nodes[node].children);
+    Some(NodeId(node))
+  }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> IntoParallelIterator for &'a Tree<T> {
+  type Item = &'a T;
+  type Iter = rayon::vec::IntoIter<&'a T>;
+
+  /// Returns a parallel iterator over the values of the tree, collected
+  /// in pre-order from the root.
+  fn into_par_iter(self) -> Self::Iter {
+    self.pre_order(self.root()).map(|node| &self[node]).collect::<Vec<_>>().into_par_iter()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Tree<T>
+where
+  T: serde::Serialize,
+{
+  /// Serializes the nodes in pre-order as a sequence of pairs of the
+  /// position of the parent in the sequence, `None` for the root, and the
+  /// value.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.flatten())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Tree<T>
+where
+  T: serde::Deserialize<'de>,
+{
+  /// Deserializes a tree from pairs of the position of the parent and the
+  /// value, in which every parent comes before its children.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let pairs: Vec<(Option<usize>, T)> = serde::Deserialize::deserialize(deserializer)?;
+    let mut pairs = pairs.into_iter().enumerate();
+
+    let mut tree = match pairs.next() {
+      Some((_, (None, root))) => Tree::new(root),
+      _ => return Err(D::Error::custom("the first node must be the only root"))
+    };
+    let mut ids = vec![tree.root()];
+
+    for (position, (parent, value)) in pairs {
+      match parent {
+        Some(parent) if parent < position => ids.push(tree.add_child(ids[parent], value)),
+        _ => return Err(D::Error::custom("every parent must come before its children"))
+      }
+    }
+
+    Ok(tree)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::Tree;
+  use proptest::prelude::*;
+
+  /// Builds the tree `1 [2 [4, 5], 3 [6]]`.
+  fn sample() -> Tree<i32> {
+    let mut tree = Tree::new(1);
+    let two = tree.add_child(tree.root(), 2);
+    let three = tree.add_child(tree.root(), 3);
+    tree.add_child(two, 4);
+    tree.add_child(two, 5);
+    tree.add_child(three, 6);
+    tree
+  }
+
+  fn values(tree: &Tree<i32>, nodes: impl Iterator<Item = super::NodeId>) -> Vec<i32> {
+    nodes.map(|node| tree[node]).collect()
+  }
+
+  #[test]
+  fn single_node() {
+    let tree = Tree::new("root");
+
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[tree.root()], "root");
+    assert_eq!(tree.parent(tree.root()), None);
+    assert!(tree.is_leaf(tree.root()));
+  }
+
+  #[test]
+  fn accessors() {
+    let mut tree = sample();
+    let root = tree.root();
+    let children: Vec<_> = tree.children(root).collect();
+
+    assert_eq!(values(&tree, children.iter().copied()), [2, 3]);
+    assert_eq!(tree.parent(children[1]), Some(root));
+    assert_eq!(tree.depth(tree.children(children[0]).next().unwrap()), 2);
+
+    tree[children[1]] = 30;
+    assert_eq!(tree.get(children[1]), Some(&30));
+  }
+
+  #[test]
+  fn traversals() {
+    let tree = sample();
+    let root = tree.root();
+
+    assert_eq!(values(&tree, tree.pre_order(root)), [1, 2, 4, 5, 3, 6]);
+    assert_eq!(values(&tree, tree.post_order(root)), [4, 5, 2, 6, 3, 1]);
+    assert_eq!(values(&tree, tree.breadth_first(root)), [1, 2, 3, 4, 5, 6]);
+
+    let two = tree.children(root).next().unwrap();
+    assert_eq!(values(&tree, tree.post_order(two)), [4, 5, 2]);
+  }
+
+  #[test]
+  fn remove_subtree() {
+    let mut tree = sample();
+    let two = tree.children(tree.root()).next().unwrap();
+    let removed = tree.remove_subtree(two).unwrap();
+
+    assert_eq!(tree.len(), 3);
+    assert!(!tree.contains(two));
+    assert_eq!(values(&tree, tree.pre_order(tree.root())), [1, 3, 6]);
+    assert_eq!(values(&removed, removed.pre_order(removed.root())), [2, 4, 5]);
+
+    assert_eq!(tree.remove_subtree(tree.root()), None);
+    assert_eq!(tree.remove_subtree(two), None);
+  }
+
+  #[test]
+  fn equality_and_debug() {
+    let mut tree = sample();
+    let two = tree.children(tree.root()).next().unwrap();
+    let removed = tree.remove_subtree(two).unwrap();
+
+    let mut expected = Tree::new(2);
+    expected.add_child(expected.root(), 4);
+    expected.add_child(expected.root(), 5);
+
+    assert_eq!(removed, expected);
+    assert_ne!(tree, expected);
+    assert_eq!(format!("{:?}", sample()), "1 [2 [4, 5], 3 [6]]");
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid node id")]
+  fn add_child_to_removed_node() {
+    let mut tree = sample();
+    let two = tree.children(tree.root()).next().unwrap();
+    tree.remove_subtree(two);
+    tree.add_child(two, 7);
+  }
+
+  #[test]
+  #[cfg(feature = "serde")]
+  fn serde_round_trip() {
+    let value = sample();
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, "[[null,1],[0,2],[1,4],[1,5],[0,3],[4,6]]");
+    assert_eq!(serde_json::from_str::<Tree<i32>>(&json).unwrap(), value);
+
+    assert!(serde_json::from_str::<Tree<i32>>("[]").is_err());
+    assert!(serde_json::from_str::<Tree<i32>>("[[null,1],[1,2]]").is_err());
+  }
+
+  #[test]
+  #[cfg(feature = "rayon")]
+  fn parallel_iterator() {
+    use rayon::prelude::*;
+
+    let tree = sample();
+    assert_eq!(tree.par_iter().sum::<i32>(), 21);
+    assert_eq!(tree.par_iter().copied().collect::<Vec<_>>(), [1, 2, 4, 5, 3, 6]);
+  }
+
+  proptest! {
+    #[test]
+    fn traversals_visit_every_node_once(parents in prop::collection::vec(any::<prop::sample::Index>(), 0..50)) {
+      // Node `i + 1` is a child of one of the nodes before it.
+      let mut tree = Tree::new(0);
+      let mut nodes = vec![tree.root()];
+      for (value, parent) in parents.iter().enumerate() {
+        let parent = nodes[parent.index(nodes.len())];
+        nodes.push(tree.add_child(parent, value as i32 + 1));
+      }
+
+      let expected: Vec<i32> = (0..nodes.len() as i32).collect();
+      for mut order in [
+        values(&tree, tree.pre_order(tree.root())),
+        values(&tree, tree.post_order(tree.root())),
+        values(&tree, tree.breadth_first(tree.root()))
+      ] {
+        order.sort();
+        prop_assert_eq!(order, expected.clone());
+      }
+
+      // Parents come before their children in pre-order and breadth-first
+      // order, and after them in post-order.
+      let pre: Vec<_> = tree.pre_order(tree.root()).collect();
+      let post: Vec<_> = tree.post_order(tree.root()).collect();
+      for &node in &nodes[1..] {
+        let parent = tree.parent(node).unwrap();
+        let position = |order: &[_], node| order.iter().position(|&other| other == node);
+        prop_assert!(position(&pre, parent) < position(&pre, node));
+        prop_assert!(position(&post, parent) > position(&post, node));
+      }
+    }
+  }
+}
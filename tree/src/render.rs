@@ -0,0 +1,284 @@
+//! Rendering trees as ASCII art, as Graphviz DOT and as Mermaid
+//! flowcharts.
+//!
+//! The ASCII layout draws every node on its own line below its parent,
+//! like the `tree` command does for directories:
+//!
+//! ```text
+//! /
+//! |-- home
+//! |   `-- user
+//! `-- tmp
+//! ```
+//!
+//! The DOT output describes a directed graph with an edge from every
+//! node to each of its children, and can be drawn with
+//! `dot -Tsvg tree.dot -o tree.svg`. The Mermaid output describes the same
+//! graph as a top-down flowchart, which can be embedded in Markdown.
+
+use std::fmt::{Display, Write};
+
+use crate::{BinaryTree, Tree};
+
+
+impl<T: Display> Tree<T> {
+  /// Renders the tree as ASCII art, with one line per node.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use tree::Tree;
+  ///
+  /// let mut tree = Tree::new("/");
+  /// let home = tree.add_child(tree.root(), "home");
+  /// tree.add_child(home, "user");
+  /// tree.add_child(tree.root(), "tmp");
+  ///
+  /// assert_eq!(tree.to_ascii(), "/\n|-- home\n|   `-- user\n`-- tmp\n");
+  /// ```
+  pub fn to_ascii(&self) -> String {
+    let children = |node| self.children(node).map(Some).collect();
+    ascii(self.root(), &|node| self[node].to_string(), &children)
+  }
+
+  /// Renders the tree as a Graphviz DOT digraph, whose nodes are named
+  /// after their pre-order position.
+  pub fn to_dot(&self) -> String {
+    dot(self.flatten().map(|(parent, value)| (parent, value.to_string(), None)))
+  }
+
+  /// Renders the tree as a Mermaid flowchart, whose nodes are named after
+  /// their pre-order position.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use tree::Tree;
+  ///
+  /// let mut tree = Tree::new("/");
+  /// tree.add_child(tree.root(), "home");
+  ///
+  /// assert_eq!(tree.to_mermaid(), "flowchart TD\n  n0[\"/\"]\n  n1[\"home\"]\n  n0 --> n1\n");
+  /// ```
+  pub fn to_mermaid(&self) -> String {
+    mermaid(self.flatten().map(|(parent, value)| (parent, value.to_string(), None)))
+  }
+}
+
+impl<T: Display> BinaryTree<T> {
+  /// Renders the tree as ASCII art, with one line per node. The left
+  /// child is drawn above the right one, and a missing child of a node
+  /// with only one child is drawn as `(empty)`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use tree::BinaryTree;
+  ///
+  /// let tree = BinaryTree::node(1, BinaryTree::new(), BinaryTree::leaf(2));
+  ///
+  /// assert_eq!(tree.to_ascii(), "1\n|-- (empty)\n`-- 2\n");
+  /// ```
+  pub fn to_ascii(&self) -> String {
+    if self.is_empty() {
+      return String::new();
+    }
+
+    ascii(self, &|tree| tree.value().expect("rendered subtree is empty").to_string(), &binary_children)
+  }
+
+  /// Renders the tree as a Graphviz DOT digraph, whose nodes are named
+  /// after their pre-order position. The edges are labeled `L` and `R`
+  /// after the side of the child.
+  pub fn to_dot(&self) -> String {
+    dot(self.labels())
+  }
+
+  /// Renders the tree as a Mermaid flowchart, whose nodes are named after
+  /// their pre-order position. The edges are labeled `L` and `R` after the
+  /// side of the child.
+  pub fn to_mermaid(&self) -> String {
+    mermaid(self.labels())
+  }
+
+  /// Returns the labels of the nodes in pre-order, each with the position
+  /// of its parent and the side it is on.
+  fn labels(&self) -> impl Iterator<Item = (Option<usize>, String, Option<&'static str>)> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![(self, None, None)];
+
+    while let Some((tree, parent, side)) = stack.pop() {
+      if let (Some(value), Some(left), Some(right)) = (tree.value(), tree.left(), tree.right()) {
+        let position = nodes.len();
+        nodes.push((parent, value.to_string(), side));
+        stack.push((right, Some(position), Some("R")));
+        stack.push((left, Some(position), Some("L")));
+      }
+    }
+
+    nodes.into_iter()
+  }
+}
+
+/// Returns the children of a binary tree to draw, the empty ones as
+/// `None`, or nothing for a leaf.
+fn binary_children<T>(tree: &BinaryTree<T>) -> Vec<Option<&BinaryTree<T>>> {
+  match (tree.left(), tree.right()) {
+    (Some(left), Some(right)) if !left.is_empty() || !right.is_empty() => {
+      [left, right].into_iter().map(|child| Some(child).filter(|child| !child.is_empty())).collect()
+    }
+    _ => Vec::new()
+  }
+}
+
+/// Draws the subtree of `node`, where `children` returns the children of
+/// a node in order and `None` for a child to draw as missing.
+fn ascii<N: Copy>(node: N, label: &impl Fn(N) -> String, children: &impl Fn(N) -> Vec<Option<N>>) -> String {
+  fn descendants<N: Copy>(
+    output: &mut String,
+    node: N,
+    prefix: &mut String,
+    label: &impl Fn(N) -> String,
+    children: &impl Fn(N) -> Vec<Option<N>>
+  ) {
+    let nodes = children(node);
+
+    for (index, child) in nodes.iter().enumerate() {
+      let last = index + 1 == nodes.len();
+      let branch = if last { "`-- " } else { "|-- " };
+
+      match *child {
+        Some(child) => {
+          writeln!(output, "{}{}{}", prefix, branch, label(child)).unwrap();
+
+          let len = prefix.len();
+          prefix.push_str(if last { "    " } else { "|   " });
+          descendants(output, child, prefix, label, children);
+          prefix.truncate(len);
+        }
+        None => writeln!(output, "{}{}(empty)", prefix, branch).unwrap()
+      }
+    }
+  }
+
+  let mut output = label(node) + "\n";
+  descendants(&mut output, node, &mut String::new(), label, children);
+  output
+}
+
+/// Writes a DOT digraph of the nodes, given in pre-order as the position
+/// of their parent, their label and the label of the edge from their
+/// parent.
+fn dot<'a>(nodes: impl Iterator<Item = (Option<usize>, String, Option<&'a str>)>) -> String {
+  let mut output = String::from("digraph {\n");
+
+  for (position, (parent, label, edge)) in nodes.enumerate() {
+    writeln!(output, "  n{} [label=\"{}\"];", position, escape(&label)).unwrap();
+
+    match (parent, edge) {
+      (Some(parent), Some(edge)) => writeln!(output, "  n{} -> n{} [label=\"{}\"];", parent, position, edge).unwrap(),
+      (Some(parent), None) => writeln!(output, "  n{} -> n{};", parent, position).unwrap(),
+      (None, _) => {}
+    }
+  }
+
+  output.push_str("}\n");
+  output
+}
+
+/// Writes a top-down Mermaid flowchart of the nodes, given like for `dot`.
+fn mermaid<'a>(nodes: impl Iterator<Item = (Option<usize>, String, Option<&'a str>)>) -> String {
+  let mut output = String::from("flowchart TD\n");
+
+  for (position, (parent, label, edge)) in nodes.enumerate() {
+    writeln!(output, "  n{}[\"{}\"]", position, escape_mermaid(&label)).unwrap();
+
+    match (parent, edge) {
+      (Some(parent), Some(edge)) => writeln!(output, "  n{} -->|{}| n{}", parent, edge, position).unwrap(),
+      (Some(parent), None) => writeln!(output, "  n{} --> n{}", parent, position).unwrap(),
+      (None, _) => {}
+    }
+  }
+
+  output
+}
+
+/// Escapes a label to be quoted in Mermaid, which reads HTML entities.
+fn escape_mermaid(label: &str) -> String {
+  label.replace('&', "#amp;").replace('"', "#quot;").replace('\n', "<br>")
+}
+
+/// Escapes a label to be quoted in DOT.
+fn escape(label: &str) -> String {
+  label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+  use crate::{BinaryTree, Tree};
+
+  #[test]
+  fn tree_to_ascii() {
+    let mut tree = Tree::new(1);
+    let two = tree.add_child(tree.root(), 2);
+    tree.add_child(two, 3);
+    tree.add_child(two, 4);
+    tree.add_child(tree.root(), 5);
+
+    assert_eq!(Tree::new(1).to_ascii(), "1\n");
+    assert_eq!(tree.to_ascii(), "1\n|-- 2\n|   |-- 3\n|   `-- 4\n`-- 5\n");
+  }
+
+  #[test]
+  fn tree_to_dot() {
+    let mut tree = Tree::new("a \"quoted\"\nroot");
+    let child = tree.add_child(tree.root(), "b");
+    tree.add_child(child, "c");
+
+    assert_eq!(
+      tree.to_dot(),
+      "digraph {\n  n0 [label=\"a \\\"quoted\\\"\\nroot\"];\n  n1 [label=\"b\"];\n  n0 -> n1;\n  n2 [label=\"c\"];\n  n1 -> n2;\n}\n"
+    );
+  }
+
+  #[test]
+  fn binary_tree_to_ascii() {
+    let left = BinaryTree::node(2, BinaryTree::leaf(4), BinaryTree::new());
+    let tree = BinaryTree::node(1, left, BinaryTree::leaf(3));
+
+    assert_eq!(BinaryTree::<i32>::new().to_ascii(), "");
+    assert_eq!(tree.to_ascii(), "1\n|-- 2\n|   |-- 4\n|   `-- (empty)\n`-- 3\n");
+  }
+
+  #[test]
+  fn binary_tree_to_dot() {
+    let tree = BinaryTree::node(1, BinaryTree::new(), BinaryTree::leaf(2));
+
+    assert_eq!(BinaryTree::<i32>::new().to_dot(), "digraph {\n}\n");
+    assert_eq!(tree.to_dot(), "digraph {\n  n0 [label=\"1\"];\n  n1 [label=\"2\"];\n  n0 -> n1 [label=\"R\"];\n}\n");
+  }
+
+  #[test]
+  fn tree_to_mermaid() {
+    let mut tree = Tree::new("say \"hi\"");
+    let child = tree.add_child(tree.root(), "a & b");
+    tree.add_child(child, "two\nlines");
+
+    assert_eq!(
+      tree.to_mermaid(),
+      "flowchart TD\n  n0[\"say #quot;hi#quot;\"]\n  n1[\"a #amp; b\"]\n  n0 --> n1\n  n2[\"two<br>lines\"]\n  n1 --> n2\n"
+    );
+  }
+
+  #[test]
+  fn binary_tree_to_mermaid() {
+    let tree = BinaryTree::node(1, BinaryTree::leaf(2), BinaryTree::leaf(3));
+
+    assert_eq!(BinaryTree::<i32>::new().to_mermaid(), "flowchart TD\n");
+    assert_eq!(
+      tree.to_mermaid(),
+      "flowchart TD\n  n0[\"1\"]\n  n1[\"2\"]\n  n0 -->|L| n1\n  n2[\"3\"]\n  n0 -->|R| n2\n"
+    );
+  }
+}